@@ -12,9 +12,6 @@ use crate::{
 use bevy::prelude::*;
 use text::{Text3dGizmo, Text3dGizmos};
 
-#[cfg(feature = "sweep_debug")]
-use crate::mesh::payload::VertexPayload;
-
 /// Show the vertex indices of a mesh in blue.
 pub fn show_vertex_indices(texts: &mut ResMut<Text3dGizmos>, mesh: &BevyMesh3d) {
     mesh.vertices().for_each(|v| {
@@ -26,22 +23,17 @@ pub fn show_vertex_indices(texts: &mut ResMut<Text3dGizmos>, mesh: &BevyMesh3d)
 }
 
 /// Visualized the tesselation meta data of a mesh.
+/// Only shows something if the meta data was recorded, see `TesselationMeta::with_sweep_debug`.
 pub fn show_tesselation_meta<V: IndexType>(
-    _texts: &mut ResMut<Text3dGizmos>,
-    _mesh: &BevyMesh3d,
-    _meta: &TesselationMeta<V>,
+    texts: &mut ResMut<Text3dGizmos>,
+    mesh: &BevyMesh3d,
+    meta: &TesselationMeta<V>,
 ) {
-    #[cfg(feature = "sweep_debug")]
-    for (index, t) in _meta.sweep.vertex_type.iter() {
-        _texts.write(
+    for (index, t) in meta.sweep.vertex_type.iter() {
+        texts.write(
             Text3dGizmo::new(
                 format!("{} {:?}", index, t),
-                *_mesh
-                    .vertices()
-                    .nth(index.index())
-                    .unwrap()
-                    .payload()
-                    .vertex(),
+                mesh.vertices().nth(index.index()).unwrap().pos(),
             )
             .with_color(Color::srgb(1.0, 0.0, 0.0)),
         );
@@ -1,14 +1,15 @@
-use super::{Polygon2dBevy, BevyVertexPayload3d};
+use super::{BevyVertexPayload3d, BevyVertexStreams, Polygon2dBevy};
 use crate::{
     halfedge::{
         HalfEdgeFaceImpl, HalfEdgeImpl, HalfEdgeImplMeshType, HalfEdgeMeshImpl, HalfEdgeVertexImpl,
     },
-    math::{HasNormal, HasPosition, IndexType},
+    math::{HasNormal, HasPosition, HasUV, IndexType},
     mesh::{
-        EmptyEdgePayload, EmptyFacePayload, EmptyMeshPayload, EuclideanMeshType, MeshType,
-        MeshType3D, MeshTypeHalfEdge, Triangulateable,
+        corner_vertices, flat_normal_vertices, stripify, CornerPayload, EmptyEdgePayload,
+        EmptyFacePayload, EmptyMeshPayload, EuclideanMeshType, MeshType, MeshType3D,
+        MeshTypeHalfEdge, Meshlet, Triangulateable, Triangulation,
     },
-    tesselate::{TesselationMeta, TriangulationAlgorithm},
+    tesselate::{TesselationCache, TesselationMeta, TesselationOptions, TriangulationAlgorithm},
 };
 use bevy::{
     math::{Quat, Vec2, Vec3},
@@ -54,8 +55,45 @@ impl MeshType3D for BevyMeshType3d32 {}
 /// A mesh with bevy 3D vertices
 pub type BevyMesh3d = HalfEdgeMeshImpl<BevyMeshType3d32>;
 
-impl<T: HalfEdgeImplMeshType<VP = BevyVertexPayload3d> + MeshType3D<Vec = Vec3, S = f32>>
-    HalfEdgeMeshImpl<T>
+/// A mesh type for bevy like `BevyMeshType3d32`, but with 16 bit indices.
+///
+/// Saves memory for the many small meshes typical for procedural scattering and
+/// exports `Indices::U16`. Meshes are limited to 65535 vertices, half-edges, and faces
+/// each, and inserting more panics.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct BevyMeshType3d16;
+
+impl MeshType for BevyMeshType3d16 {
+    type E = u16;
+    type V = u16;
+    type F = u16;
+    type EP = EmptyEdgePayload<Self>;
+    type VP = BevyVertexPayload3d;
+    type FP = EmptyFacePayload<Self>;
+    type MP = EmptyMeshPayload<Self>;
+    type Mesh = BevyMesh3d16;
+    type Face = HalfEdgeFaceImpl<Self>;
+    type Edge = HalfEdgeImpl<Self>;
+    type Vertex = HalfEdgeVertexImpl<Self>;
+}
+impl EuclideanMeshType<3> for BevyMeshType3d16 {
+    type S = f32;
+    type Vec = Vec3;
+    type Vec2 = Vec2;
+    type Trans = bevy::transform::components::Transform;
+    type Rot = Quat;
+    type Poly = Polygon2dBevy;
+}
+impl HalfEdgeImplMeshType for BevyMeshType3d16 {}
+impl MeshTypeHalfEdge for BevyMeshType3d16 {}
+impl MeshType3D for BevyMeshType3d16 {}
+
+/// A mesh with bevy 3D vertices and 16 bit indices
+pub type BevyMesh3d16 = HalfEdgeMeshImpl<BevyMeshType3d16>;
+
+impl<T: HalfEdgeImplMeshType + MeshType3D<Vec = Vec3, S = f32>> HalfEdgeMeshImpl<T>
+where
+    T::VP: HasNormal<3, Vec3, S = f32> + 'static,
 {
     fn bevy_indices(&self, indices: &Vec<T::V>) -> bevy::render::mesh::Indices {
         if std::mem::size_of::<T::V>() == std::mem::size_of::<u32>() {
@@ -84,58 +122,193 @@ impl<T: HalfEdgeImplMeshType<VP = BevyVertexPayload3d> + MeshType3D<Vec = Vec3,
         }
     }
 
-    /// Replace the mesh's attributes with the current mesh.
-    /// Requires the mesh to be a triangle list and have the MAIN_WORLD usage.
-    pub fn bevy_set(&self, mesh: &mut bevy::render::mesh::Mesh) {
-        self.bevy_set_ex(
+    /// Like `bevy_set_ex`, but also inserts the custom vertex attributes read from the
+    /// vertex payloads by the given `streams`, e.g., additional uv channels or data for
+    /// custom shaders. Works with any vertex payload with positions and normals.
+    pub fn bevy_set_streams(
+        &self,
+        mesh: &mut bevy::render::mesh::Mesh,
+        algo: TriangulationAlgorithm,
+        generate_flat_normals: bool,
+        meta: &mut TesselationMeta<T::V>,
+        streams: &BevyVertexStreams<T::VP>,
+    ) where
+        T::EP: CornerPayload<T::VP>,
+    {
+        self.bevy_set_impl(
             mesh,
-            TriangulationAlgorithm::Auto,
-            false,
-            &mut TesselationMeta::default(),
+            algo,
+            generate_flat_normals,
+            &TesselationOptions::default(),
+            meta,
+            streams,
         );
     }
 
-    /// Like bevy_set, but with additional meta information
-    pub fn bevy_set_ex(
+    /// Triangulates the mesh as requested by `options` and replaces the attributes of the
+    /// bevy mesh.
+    fn bevy_set_impl(
         &self,
         mesh: &mut bevy::render::mesh::Mesh,
         algo: TriangulationAlgorithm,
         generate_flat_normals: bool,
+        options: &TesselationOptions,
         meta: &mut TesselationMeta<T::V>,
-    ) {
-        assert!(mesh.primitive_topology() == PrimitiveTopology::TriangleList);
+        streams: &BevyVertexStreams<T::VP>,
+    ) where
+        T::EP: CornerPayload<T::VP>,
+    {
+        let topology = mesh.primitive_topology();
+        assert!(
+            topology == PrimitiveTopology::TriangleList
+                || topology == PrimitiveTopology::TriangleStrip
+        );
         assert!(mesh.asset_usage.contains(RenderAssetUsages::MAIN_WORLD));
         Self::bevy_remove_attributes(mesh);
 
         // use https://crates.io/crates/stats_alloc to measure memory usage
         //let now = std::time::Instant::now();
-        let (is, vs) = if generate_flat_normals {
-            self.triangulate_and_generate_flat_normals_post(algo, meta)
+        let mut raw = Vec::new();
+        let mut tri = Triangulation::new(&mut raw);
+        self.triangulate_into_with_options(&mut tri, algo, options, meta);
+        let ranges = tri.face_ranges().collect();
+        let (mut is, vs) = if generate_flat_normals {
+            flat_normal_vertices::<T>(self, &raw, ranges)
         } else {
-            self.triangulate(algo, meta)
+            corner_vertices::<T>(self, &raw, ranges)
         };
         //let elapsed = now.elapsed();
         //println!("///////////////////\nTriangulation took {:.2?}", elapsed);
+        options.post_process(&mut is, vs.len());
 
+        self.bevy_insert(mesh, is, vs, streams);
+    }
+
+    /// Inserts the triangles and vertices into the cleared bevy mesh.
+    fn bevy_insert(
+        &self,
+        mesh: &mut bevy::render::mesh::Mesh,
+        is: Vec<T::V>,
+        vs: Vec<T::VP>,
+        streams: &BevyVertexStreams<T::VP>,
+    ) {
+        let topology = mesh.primitive_topology();
+        let is = if topology == PrimitiveTopology::TriangleStrip {
+            stripify(&is)
+        } else {
+            is
+        };
         mesh.insert_indices(self.bevy_indices(&is));
         mesh.insert_attribute(
             bevy::render::mesh::Mesh::ATTRIBUTE_POSITION,
-            VertexAttributeValues::Float32x3(
-                vs.iter()
-                    .map(|vp: &<BevyMeshType3d32 as MeshType>::VP| vp.pos().to_array())
-                    .collect(),
-            ),
+            VertexAttributeValues::Float32x3(vs.iter().map(|vp| vp.pos().to_array()).collect()),
         );
         mesh.insert_attribute(
             bevy::render::mesh::Mesh::ATTRIBUTE_NORMAL,
-            VertexAttributeValues::Float32x3(
-                vs.iter()
-                    .map(|vp| (vp as &BevyVertexPayload3d).normal().to_array())
-                    .collect(),
-            ),
+            VertexAttributeValues::Float32x3(vs.iter().map(|vp| vp.normal().to_array()).collect()),
+        );
+        streams.insert(mesh, &vs);
+    }
+
+    /// Converts the mesh to a bevy triangle list with the custom vertex attributes of
+    /// the given `streams`, see `bevy_set_streams`.
+    pub fn to_bevy_streams(
+        &self,
+        usage: RenderAssetUsages,
+        algo: TriangulationAlgorithm,
+        streams: &BevyVertexStreams<T::VP>,
+    ) -> bevy::render::mesh::Mesh
+    where
+        T::EP: CornerPayload<T::VP>,
+    {
+        let mut mesh = bevy::render::mesh::Mesh::new(PrimitiveTopology::TriangleList, usage);
+        self.bevy_set_streams(
+            &mut mesh,
+            algo,
+            false,
+            &mut TesselationMeta::default(),
+            streams,
+        );
+        mesh
+    }
+}
+
+impl<T: HalfEdgeImplMeshType<VP = BevyVertexPayload3d> + MeshType3D<Vec = Vec3, S = f32>>
+    HalfEdgeMeshImpl<T>
+where
+    T::EP: CornerPayload<BevyVertexPayload3d>,
+{
+    /// Replace the mesh's attributes with the current mesh.
+    /// Requires the mesh to be a triangle list and have the MAIN_WORLD usage.
+    pub fn bevy_set(&self, mesh: &mut bevy::render::mesh::Mesh) {
+        self.bevy_set_ex(
+            mesh,
+            TriangulationAlgorithm::Auto,
+            false,
+            &mut TesselationMeta::default(),
+        );
+    }
+
+    /// Like bevy_set, but with additional meta information.
+    /// Unless flat normals are generated, the attributes of the face corners are applied
+    /// and vertices are split where their corners differ.
+    /// If the mesh is a triangle strip, the triangles are stripified, see `stripify`.
+    pub fn bevy_set_ex(
+        &self,
+        mesh: &mut bevy::render::mesh::Mesh,
+        algo: TriangulationAlgorithm,
+        generate_flat_normals: bool,
+        meta: &mut TesselationMeta<T::V>,
+    ) {
+        self.bevy_set_streams(
+            mesh,
+            algo,
+            generate_flat_normals,
+            meta,
+            &BevyVertexStreams::new(),
+        );
+    }
+
+    /// Like `bevy_set_ex`, but schedules and post-processes the triangulation as requested
+    /// by `options`, see `Triangulateable::triangulate_with_options`.
+    pub fn bevy_set_with_options(
+        &self,
+        mesh: &mut bevy::render::mesh::Mesh,
+        algo: TriangulationAlgorithm,
+        generate_flat_normals: bool,
+        options: &TesselationOptions,
+        meta: &mut TesselationMeta<T::V>,
+    ) {
+        self.bevy_set_impl(
+            mesh,
+            algo,
+            generate_flat_normals,
+            options,
+            meta,
+            &BevyVertexStreams::new(),
         );
     }
 
+    /// Like `bevy_set`, but only re-triangulates the faces that changed since the last
+    /// call with the same `cache`, e.g., when updating the mesh every frame in an editor.
+    /// The attributes of the face corners are not applied.
+    pub fn bevy_set_cached(
+        &self,
+        mesh: &mut bevy::render::mesh::Mesh,
+        cache: &mut TesselationCache<T>,
+        meta: &mut TesselationMeta<T::V>,
+    ) {
+        let topology = mesh.primitive_topology();
+        assert!(
+            topology == PrimitiveTopology::TriangleList
+                || topology == PrimitiveTopology::TriangleStrip
+        );
+        assert!(mesh.asset_usage.contains(RenderAssetUsages::MAIN_WORLD));
+        Self::bevy_remove_attributes(mesh);
+        let (is, vs) = cache.triangulate(self, meta);
+        self.bevy_insert(mesh, is, vs, &BevyVertexStreams::new());
+    }
+
     /// Convert the mesh to a bevy mesh
     pub fn to_bevy(&self, usage: RenderAssetUsages) -> bevy::render::mesh::Mesh {
         let mut mesh = bevy::render::mesh::Mesh::new(PrimitiveTopology::TriangleList, usage);
@@ -143,7 +316,7 @@ impl<T: HalfEdgeImplMeshType<VP = BevyVertexPayload3d> + MeshType3D<Vec = Vec3,
         mesh
     }
 
-    /// Convert the mesh to a bevy mesh with additional meta information
+    /// Convert the mesh to a bevy mesh with additional meta information.
     pub fn to_bevy_ex(
         &self,
         usage: RenderAssetUsages,
@@ -159,6 +332,60 @@ impl<T: HalfEdgeImplMeshType<VP = BevyVertexPayload3d> + MeshType3D<Vec = Vec3,
         );
         mesh
     }
+
+    /// Like `to_bevy_ex`, but the mesh is a triangle strip instead of a triangle list,
+    /// which reduces the size of the index buffer of large meshes, see `stripify`.
+    pub fn to_bevy_strips(
+        &self,
+        usage: RenderAssetUsages,
+        algo: TriangulationAlgorithm,
+        generate_flat_normals: bool,
+    ) -> bevy::render::mesh::Mesh {
+        let mut mesh = bevy::render::mesh::Mesh::new(PrimitiveTopology::TriangleStrip, usage);
+        self.bevy_set_ex(
+            &mut mesh,
+            algo,
+            generate_flat_normals,
+            &mut TesselationMeta::default(),
+        );
+        mesh
+    }
+
+    /// Converts the mesh to a bevy mesh with its triangles grouped by meshlets of at most
+    /// 255 vertices and 128 triangles, see `build_meshlets`. The mesh only has positions,
+    /// normals, uvs, and 32 bit indices, so it can be passed to bevy's
+    /// `MeshletMesh::from_mesh` (requires bevy's `meshlet_processor` feature) for
+    /// GPU-driven rendering. Also returns the meshlets with their culling bounds.
+    pub fn to_bevy_meshlets(
+        &self,
+        usage: RenderAssetUsages,
+        algo: TriangulationAlgorithm,
+    ) -> (bevy::render::mesh::Mesh, Vec<Meshlet<T::V, Vec3>>) {
+        let (meshlets, vs) =
+            self.triangulate_meshlets(algo, &mut TesselationMeta::default(), 255, 128);
+        let mut mesh = bevy::render::mesh::Mesh::new(PrimitiveTopology::TriangleList, usage);
+        mesh.insert_attribute(
+            bevy::render::mesh::Mesh::ATTRIBUTE_POSITION,
+            VertexAttributeValues::Float32x3(vs.iter().map(|vp| vp.pos().to_array()).collect()),
+        );
+        mesh.insert_attribute(
+            bevy::render::mesh::Mesh::ATTRIBUTE_NORMAL,
+            VertexAttributeValues::Float32x3(vs.iter().map(|vp| vp.normal().to_array()).collect()),
+        );
+        mesh.insert_attribute(
+            bevy::render::mesh::Mesh::ATTRIBUTE_UV_0,
+            VertexAttributeValues::Float32x2(vs.iter().map(|vp| vp.uv().to_array()).collect()),
+        );
+        mesh.insert_indices(bevy::render::mesh::Indices::U32(
+            meshlets
+                .iter()
+                .flat_map(|m| m.global_triangles())
+                .flatten()
+                .map(|i| i.index() as u32)
+                .collect(),
+        ));
+        (mesh, meshlets)
+    }
 }
 
 #[cfg(feature = "nalgebra")]
@@ -173,3 +400,64 @@ impl From<&crate::extensions::nalgebra::Mesh3d64> for HalfEdgeMeshImpl<BevyMeshT
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{extensions::bevy::*, prelude::*};
+    use bevy::render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    };
+
+    #[test]
+    fn test_u16_indices() {
+        let mesh = BevyMesh3d16::uv_sphere(1.0, 16, 16);
+        assert!(mesh.check().is_ok());
+        let bevy_mesh = mesh.to_bevy_ex(
+            RenderAssetUsages::all(),
+            TriangulationAlgorithm::Auto,
+            false,
+        );
+        let Some(Indices::U16(indices)) = bevy_mesh.indices() else {
+            panic!("expected 16 bit indices");
+        };
+        assert_eq!(
+            indices.len(),
+            BevyMesh3d::uv_sphere(1.0, 16, 16)
+                .to_bevy_ex(
+                    RenderAssetUsages::all(),
+                    TriangulationAlgorithm::Auto,
+                    false
+                )
+                .indices()
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_strips() {
+        let mesh = BevyMesh3d::uv_sphere(1.0, 16, 16);
+        let list = mesh.to_bevy_ex(
+            RenderAssetUsages::all(),
+            TriangulationAlgorithm::Auto,
+            false,
+        );
+        let strips = mesh.to_bevy_strips(
+            RenderAssetUsages::all(),
+            TriangulationAlgorithm::Auto,
+            false,
+        );
+        assert_eq!(
+            strips.primitive_topology(),
+            PrimitiveTopology::TriangleStrip
+        );
+        let (Some(Indices::U32(list)), Some(Indices::U32(strip))) =
+            (list.indices(), strips.indices())
+        else {
+            panic!("expected 32 bit indices");
+        };
+        assert!(strip.len() < list.len());
+        assert_eq!(unstripify(strip).len(), list.len());
+    }
+}
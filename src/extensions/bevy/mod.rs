@@ -3,12 +3,14 @@
 mod math;
 mod mesh2d;
 mod mesh3d;
+mod streams;
 mod vertex_payload_2d;
 mod vertex_payload_3d;
 
 pub use math::*;
 pub use mesh2d::*;
 pub use mesh3d::*;
+pub use streams::*;
 pub use vertex_payload_2d::*;
 pub use vertex_payload_3d::*;
 
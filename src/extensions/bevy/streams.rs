@@ -0,0 +1,198 @@
+use bevy::{
+    math::{Vec2, Vec3, Vec4},
+    render::mesh::{Mesh, MeshVertexAttribute, VertexAttributeValues},
+};
+
+use crate::util::ColorRamp;
+
+type StreamFn<VP> = Box<dyn Fn(&[VP]) -> VertexAttributeValues>;
+
+/// A list of custom vertex attributes that are read from the vertex payloads when
+/// exporting a mesh to bevy, e.g., additional uv channels or per-vertex wind weights
+/// for a custom shader.
+///
+/// Each stream is inserted as the given `MeshVertexAttribute`, so the id and name of
+/// the attribute must match the ones used by the shader.
+///
+/// ```ignore
+/// const ATTRIBUTE_WIND: MeshVertexAttribute =
+///     MeshVertexAttribute::new("Wind", 988540917, VertexFormat::Float32);
+/// let streams = BevyVertexStreams::<MyPayload>::new()
+///     .with_vec2(Mesh::ATTRIBUTE_UV_1, |vp| vp.lightmap_uv)
+///     .with_f32(ATTRIBUTE_WIND, |vp| vp.wind);
+/// let bevy_mesh = mesh.to_bevy_streams(RenderAssetUsages::all(), TriangulationAlgorithm::Auto, &streams);
+/// ```
+pub struct BevyVertexStreams<VP> {
+    streams: Vec<(MeshVertexAttribute, StreamFn<VP>)>,
+}
+
+impl<VP: 'static> Default for BevyVertexStreams<VP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<VP: 'static> BevyVertexStreams<VP> {
+    /// Creates an empty list of streams.
+    pub fn new() -> Self {
+        Self {
+            streams: Vec::new(),
+        }
+    }
+
+    /// Adds a stream computing all values of the attribute at once.
+    /// The values must match the format of the attribute.
+    pub fn with_values(
+        mut self,
+        attribute: MeshVertexAttribute,
+        values: impl Fn(&[VP]) -> VertexAttributeValues + 'static,
+    ) -> Self {
+        self.streams.push((attribute, Box::new(values)));
+        self
+    }
+
+    /// Adds a stream with one `f32` per vertex.
+    pub fn with_f32(
+        self,
+        attribute: MeshVertexAttribute,
+        f: impl Fn(&VP) -> f32 + 'static,
+    ) -> Self {
+        self.with_values(attribute, move |vs| {
+            VertexAttributeValues::Float32(vs.iter().map(&f).collect())
+        })
+    }
+
+    /// Adds a stream with one `u32` per vertex.
+    pub fn with_u32(
+        self,
+        attribute: MeshVertexAttribute,
+        f: impl Fn(&VP) -> u32 + 'static,
+    ) -> Self {
+        self.with_values(attribute, move |vs| {
+            VertexAttributeValues::Uint32(vs.iter().map(&f).collect())
+        })
+    }
+
+    /// Adds a stream with one `Vec2` per vertex, e.g., an additional uv channel.
+    pub fn with_vec2(
+        self,
+        attribute: MeshVertexAttribute,
+        f: impl Fn(&VP) -> Vec2 + 'static,
+    ) -> Self {
+        self.with_values(attribute, move |vs| {
+            VertexAttributeValues::Float32x2(vs.iter().map(|vp| f(vp).to_array()).collect())
+        })
+    }
+
+    /// Adds a stream with one `Vec3` per vertex.
+    pub fn with_vec3(
+        self,
+        attribute: MeshVertexAttribute,
+        f: impl Fn(&VP) -> Vec3 + 'static,
+    ) -> Self {
+        self.with_values(attribute, move |vs| {
+            VertexAttributeValues::Float32x3(vs.iter().map(|vp| f(vp).to_array()).collect())
+        })
+    }
+
+    /// Adds a stream with one `Vec4` per vertex, e.g., vertex colors or tangents.
+    pub fn with_vec4(
+        self,
+        attribute: MeshVertexAttribute,
+        f: impl Fn(&VP) -> Vec4 + 'static,
+    ) -> Self {
+        self.with_values(attribute, move |vs| {
+            VertexAttributeValues::Float32x4(vs.iter().map(|vp| f(vp).to_array()).collect())
+        })
+    }
+
+    /// Adds a stream of colors by mapping the scalar `f` of each vertex through the `ramp`,
+    /// e.g., `Mesh::ATTRIBUTE_COLOR` to inspect curvature or weights without a custom shader.
+    /// Without a `range`, the smallest and largest value of the exported vertices are used.
+    pub fn with_color_ramp(
+        self,
+        attribute: MeshVertexAttribute,
+        ramp: ColorRamp,
+        range: Option<(f64, f64)>,
+        f: impl Fn(&VP) -> f32 + 'static,
+    ) -> Self {
+        self.with_values(attribute, move |vs| {
+            let values = vs.iter().map(|vp| f(vp) as f64).collect::<Vec<_>>();
+            VertexAttributeValues::Float32x4(ramp.map_all(&values, range))
+        })
+    }
+
+    /// Returns the attributes of the streams.
+    pub fn attributes(&self) -> impl Iterator<Item = &MeshVertexAttribute> {
+        self.streams.iter().map(|(a, _)| a)
+    }
+
+    /// Returns the number of streams.
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Returns whether there are no streams.
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Inserts the streams of the vertices `vs` into the bevy mesh.
+    /// Panics if the values of a stream don't match the format of its attribute.
+    pub fn insert(&self, mesh: &mut Mesh, vs: &[VP]) {
+        for (attribute, values) in self.streams.iter() {
+            mesh.insert_attribute(*attribute, values(vs));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extensions::bevy::*, prelude::*};
+    use bevy::render::{render_asset::RenderAssetUsages, render_resource::VertexFormat};
+
+    #[test]
+    fn test_vertex_streams() {
+        const ATTRIBUTE_WIND: MeshVertexAttribute =
+            MeshVertexAttribute::new("Wind", 988540917, VertexFormat::Float32);
+        let mesh = BevyMesh3d::cube(1.0);
+        let streams = BevyVertexStreams::<BevyVertexPayload3d>::new()
+            .with_vec2(Mesh::ATTRIBUTE_UV_0, |vp| *vp.uv())
+            .with_vec2(Mesh::ATTRIBUTE_UV_1, |vp| Vec2::new(vp.pos().x, vp.pos().z))
+            .with_f32(ATTRIBUTE_WIND, |vp| vp.pos().y.max(0.0))
+            .with_color_ramp(Mesh::ATTRIBUTE_COLOR, ColorRamp::grayscale(), None, |vp| {
+                vp.pos().y
+            });
+        assert_eq!(streams.len(), 4);
+        let bevy_mesh = mesh.to_bevy_streams(
+            RenderAssetUsages::all(),
+            TriangulationAlgorithm::Auto,
+            &streams,
+        );
+        let n = bevy_mesh.count_vertices();
+        for attribute in streams.attributes() {
+            assert_eq!(bevy_mesh.attribute(attribute.id).unwrap().len(), n);
+        }
+        let Some(VertexAttributeValues::Float32(wind)) = bevy_mesh.attribute(ATTRIBUTE_WIND) else {
+            panic!("missing wind stream");
+        };
+        let Some(VertexAttributeValues::Float32x3(pos)) =
+            bevy_mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("missing positions");
+        };
+        for (w, p) in wind.iter().zip(pos.iter()) {
+            assert_eq!(*w, p[1].max(0.0));
+        }
+        let Some(VertexAttributeValues::Float32x4(colors)) =
+            bevy_mesh.attribute(Mesh::ATTRIBUTE_COLOR)
+        else {
+            panic!("missing colors");
+        };
+        for (c, p) in colors.iter().zip(pos.iter()) {
+            let gray = if p[1] > 0.0 { 1.0 } else { 0.0 };
+            assert_eq!(*c, [gray, gray, gray, 1.0]);
+        }
+    }
+}
@@ -98,15 +98,20 @@ impl<S: Scalar, const D: usize> NdRotate<S, D> {
                 rot: None,
             }
         } else if D == 3 {
+            let from3 = from.fixed_rows::<3>(0).into_owned();
+            let to3 = to.fixed_rows::<3>(0).into_owned();
+            // `rotation_between` fails for opposite vectors. Rotate by PI around any orthogonal axis instead.
+            let rot3 = nalgebra::Rotation3::rotation_between(&from3, &to3).unwrap_or_else(|| {
+                let axis = if Scalar::abs(from3.x) < Scalar::abs(from3.y) {
+                    from3.cross(&nalgebra::Vector3::x())
+                } else {
+                    from3.cross(&nalgebra::Vector3::y())
+                };
+                nalgebra::Rotation3::from_axis_angle(&nalgebra::Unit::new_normalize(axis), S::PI)
+            });
             Self {
                 rot2: None,
-                rot3: Some(
-                    nalgebra::Rotation3::rotation_between(
-                        &from.fixed_rows::<3>(0).into_owned(),
-                        &to.fixed_rows::<3>(0).into_owned(),
-                    )
-                    .expect("Failed to create rotation"),
-                ),
+                rot3: Some(rot3),
                 rot: None,
             }
         } else {
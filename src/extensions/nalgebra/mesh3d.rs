@@ -0,0 +1,231 @@
+use crate::{
+    halfedge::{
+        HalfEdgeFaceImpl, HalfEdgeImpl, HalfEdgeImplMeshType, HalfEdgeMeshImpl, HalfEdgeVertexImpl,
+    },
+    mesh::{
+        CornerEdgePayload, EmptyEdgePayload, EmptyFacePayload, EmptyMeshPayload, EuclideanMeshType,
+        MeshType, MeshType3D, MeshTypeHalfEdge, TaggedFacePayload,
+    },
+};
+
+use super::{NdAffine, NdRotate, Polygon2d, VecN, VertexPayloadPNU};
+
+/// A mesh type for nalgebra with
+/// - 3D vertices,
+/// - usize indices,
+/// - face payloads recording the provenance of each face,
+/// - no edge payload,
+/// - f64 vertex positions, normals, and uv coordinates
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MeshType3d64PNUTagged;
+
+impl MeshType for MeshType3d64PNUTagged {
+    type E = usize;
+    type V = usize;
+    type F = usize;
+    type EP = EmptyEdgePayload<Self>;
+    type VP = VertexPayloadPNU<f64, 3>;
+    type FP = TaggedFacePayload<Self>;
+    type MP = EmptyMeshPayload<Self>;
+    type Mesh = Mesh3d64Tagged;
+    type Face = HalfEdgeFaceImpl<Self>;
+    type Edge = HalfEdgeImpl<Self>;
+    type Vertex = HalfEdgeVertexImpl<Self>;
+}
+
+impl EuclideanMeshType<3> for MeshType3d64PNUTagged {
+    type S = f64;
+    type Vec = VecN<f64, 3>;
+    type Vec2 = VecN<f64, 2>;
+    type Trans = NdAffine<f64, 3>;
+    type Rot = NdRotate<f64, 3>;
+    type Poly = Polygon2d<f64>;
+}
+
+impl HalfEdgeImplMeshType for MeshType3d64PNUTagged {}
+impl MeshTypeHalfEdge for MeshType3d64PNUTagged {}
+impl MeshType3D for MeshType3d64PNUTagged {}
+
+/// A mesh with 3D vertices, usize indices, f64 positions, normals, and uv coordinates,
+/// and faces that remember which operation or primitive created them.
+pub type Mesh3d64Tagged = HalfEdgeMeshImpl<MeshType3d64PNUTagged>;
+
+/// A mesh type for nalgebra with
+/// - 3D vertices,
+/// - usize indices,
+/// - no face payload,
+/// - normals and uv coordinates per face corner stored in the edge payloads,
+/// - f64 vertex positions, normals, and uv coordinates
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MeshType3d64PNUCorners;
+
+impl MeshType for MeshType3d64PNUCorners {
+    type E = usize;
+    type V = usize;
+    type F = usize;
+    type EP = CornerEdgePayload<3, Self>;
+    type VP = VertexPayloadPNU<f64, 3>;
+    type FP = EmptyFacePayload<Self>;
+    type MP = EmptyMeshPayload<Self>;
+    type Mesh = Mesh3d64Corners;
+    type Face = HalfEdgeFaceImpl<Self>;
+    type Edge = HalfEdgeImpl<Self>;
+    type Vertex = HalfEdgeVertexImpl<Self>;
+}
+
+impl EuclideanMeshType<3> for MeshType3d64PNUCorners {
+    type S = f64;
+    type Vec = VecN<f64, 3>;
+    type Vec2 = VecN<f64, 2>;
+    type Trans = NdAffine<f64, 3>;
+    type Rot = NdRotate<f64, 3>;
+    type Poly = Polygon2d<f64>;
+}
+
+impl HalfEdgeImplMeshType for MeshType3d64PNUCorners {}
+impl MeshTypeHalfEdge for MeshType3d64PNUCorners {}
+impl MeshType3D for MeshType3d64PNUCorners {}
+
+/// A mesh with 3D vertices, usize indices, f64 positions, normals, and uv coordinates,
+/// and normals and uv coordinates per face corner.
+pub type Mesh3d64Corners = HalfEdgeMeshImpl<MeshType3d64PNUCorners>;
+
+/// A mesh type for nalgebra with
+/// - 3D vertices,
+/// - u16 indices,
+/// - no face or edge payload,
+/// - f64 vertex positions, normals, and uv coordinates
+///
+/// Meshes are limited to 65535 vertices, half-edges, and faces each,
+/// and inserting more panics.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MeshType3d64PNU16;
+
+impl MeshType for MeshType3d64PNU16 {
+    type E = u16;
+    type V = u16;
+    type F = u16;
+    type EP = EmptyEdgePayload<Self>;
+    type VP = VertexPayloadPNU<f64, 3>;
+    type FP = EmptyFacePayload<Self>;
+    type MP = EmptyMeshPayload<Self>;
+    type Mesh = Mesh3d64U16;
+    type Face = HalfEdgeFaceImpl<Self>;
+    type Edge = HalfEdgeImpl<Self>;
+    type Vertex = HalfEdgeVertexImpl<Self>;
+}
+
+impl EuclideanMeshType<3> for MeshType3d64PNU16 {
+    type S = f64;
+    type Vec = VecN<f64, 3>;
+    type Vec2 = VecN<f64, 2>;
+    type Trans = NdAffine<f64, 3>;
+    type Rot = NdRotate<f64, 3>;
+    type Poly = Polygon2d<f64>;
+}
+
+impl HalfEdgeImplMeshType for MeshType3d64PNU16 {}
+impl MeshTypeHalfEdge for MeshType3d64PNU16 {}
+impl MeshType3D for MeshType3d64PNU16 {}
+
+/// A mesh with 3D vertices, u16 indices, f64 positions, normals, and uv coordinates.
+pub type Mesh3d64U16 = HalfEdgeMeshImpl<MeshType3d64PNU16>;
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_select_by_tag() {
+        let prism = Mesh3d64Tagged::regular_prism(1.0, 1.0, 6);
+        assert_eq!(prism.select_by_tag("cap").len(), 2);
+        assert_eq!(prism.select_by_tag("side-wall").len(), 6);
+
+        let gear = Mesh3d64Tagged::spur_gear(1.0, 9, 0.35, 0.5);
+        assert_eq!(gear.select_by_tag("cap").len(), 2);
+        assert_eq!(gear.select_by_tag("side-wall").len(), gear.num_faces() - 2);
+
+        let arrow = Mesh3d64Tagged::arrow(1.0, 0.1, 0.2, 0.3);
+        assert_eq!(arrow.select_by_tag("cap").len(), 1);
+        assert_eq!(arrow.select_by_tag("head").len(), 2 * 16);
+
+        // extruding a face tags the new faces
+        let mut cube = Mesh3d64Tagged::cube(1.0);
+        assert!(cube.select_by_tag("cap").is_empty());
+        let f = cube.face_ids().next().unwrap();
+        let normal = cube.face(f).normal(&cube);
+        cube.extrude_face(f, NdAffine::from_translation(normal * 0.5));
+        assert_eq!(cube.select_by_tag("cap").len(), 1);
+        assert_eq!(cube.select_by_tag("side-wall").len(), 4);
+        assert!(cube.check().is_ok());
+
+        // payloads without provenance are never tagged
+        let plain = Mesh3d64::regular_prism(1.0, 1.0, 6);
+        assert!(plain.select_by_tag("cap").is_empty());
+    }
+
+    #[test]
+    fn test_corner_attributes() {
+        let mut cube = Mesh3d64Corners::cube(1.0);
+        let algo = TriangulationAlgorithm::Auto;
+        let mut meta = TesselationMeta::default();
+
+        // without corner attributes, no vertex is split
+        let (is, vs) = cube.triangulate_with_corners(algo, &mut meta);
+        assert_eq!(vs.len(), 8);
+        assert_eq!(is.len(), 6 * 2 * 3);
+
+        // the corners of the top face get flat normals and their own uvs
+        let top = cube
+            .face_ids()
+            .find(|f| cube.face(*f).normal(&cube).y > 0.9)
+            .unwrap();
+        let edges = cube.face(top).edge_ids(&cube).collect::<Vec<_>>();
+        for (i, e) in edges.iter().enumerate() {
+            let payload = cube.edge_mut(*e).payload_mut();
+            payload.set_normal(Some(Vec3::new(0.0, 1.0, 0.0)));
+            payload.set_uv(Some(Vec2::new((i % 2) as f64, (i / 2) as f64)));
+        }
+        let (is, vs) = cube.triangulate_with_corners(algo, &mut meta);
+        assert_eq!(vs.len(), 8 + 4);
+        assert_eq!(is.len(), 6 * 2 * 3);
+        let up = vs
+            .iter()
+            .filter(|v| v.normal().y == 1.0)
+            .collect::<Vec<_>>();
+        assert_eq!(up.len(), 4);
+        assert!(up.iter().all(|v| v.pos().y > 0.0));
+
+        // identical corners share a vertex
+        for e in edges {
+            cube.edge_mut(e).payload_mut().set_uv(None);
+        }
+        let (_, vs) = cube.triangulate_with_corners(algo, &mut meta);
+        assert_eq!(vs.len(), 8 + 4);
+        let e = cube.face(top).edge_id();
+        cube.edge_mut(e).payload_mut().set_normal(None);
+        let (_, vs) = cube.triangulate_with_corners(algo, &mut meta);
+        assert_eq!(vs.len(), 8 + 3);
+    }
+
+    #[test]
+    fn test_u16_indices() {
+        let sphere = Mesh3d64U16::icosphere(1.0, 4);
+        let reference = Mesh3d64::icosphere(1.0, 4);
+        assert!(sphere.check().is_ok());
+        assert_eq!(sphere.num_vertices(), reference.num_vertices());
+        let (indices, vs) =
+            sphere.triangulate(TriangulationAlgorithm::Fan, &mut Default::default());
+        let (ri, rv) = reference.triangulate(TriangulationAlgorithm::Fan, &mut Default::default());
+        assert_eq!(vs.len(), rv.len());
+        assert!(indices.iter().map(|i| *i as usize).eq(ri.iter().copied()));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range for u16 indices")]
+    fn test_u16_overflow() {
+        // about 15k vertices but more than 65535 half-edges
+        Mesh3d64U16::heightmap(1.0, 1.0, 120, |_, _| 0.0);
+    }
+}
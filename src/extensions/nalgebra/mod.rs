@@ -3,9 +3,11 @@
 mod default_vertex_payload;
 mod math;
 mod mesh2d;
+mod mesh3d;
 mod mesh_nd;
 
 pub use default_vertex_payload::*;
 pub use math::*;
 pub use mesh2d::*;
+pub use mesh3d::*;
 pub use mesh_nd::*;
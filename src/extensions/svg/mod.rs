@@ -1,8 +1,14 @@
 //! This module contains the svg-specific implementations
 
-use crate::mesh::{
-    CurvedEdge, DefaultEdgePayload, DefaultFacePayload, EuclideanMeshType, MeshTypeHalfEdge,
+use crate::{
+    math::{Scalar, TransformTrait, Vector},
+    mesh::{
+        CurvedEdge, DefaultEdgePayload, DefaultFacePayload, EuclideanMeshType, HalfEdge,
+        MeshIslands, MeshTypeHalfEdge, VertexBasics,
+    },
+    operations::NestingLayout,
 };
+use std::collections::HashSet;
 
 mod svg;
 
@@ -33,6 +39,58 @@ where
         mesh.import_svg(svg);
         mesh
     }
+
+    /// Exports the faces of the mesh as an SVG string with one path per face.
+    /// The view box is the bounding box of the vertices.
+    #[cfg(feature = "svg")]
+    fn export_svg(&self) -> String {
+        let inf = T::S::INFINITY;
+        let [x0, y0, x1, y1] = self.vertices().fold([inf, inf, -inf, -inf], |b, v| {
+            let p = v.pos();
+            [
+                b[0].min(p.x()),
+                b[1].min(p.y()),
+                b[2].max(p.x()),
+                b[3].max(p.y()),
+            ]
+        });
+        svg::export_svg::<T>(
+            self,
+            self.face_ids().map(|f| (f, T::Trans::identity())),
+            [x0, y0, x1 - x0, y1 - y0],
+        )
+    }
+
+    /// Exports the sheets of a nesting layout of the mesh, see `MeshNesting::nest`, as one
+    /// SVG string per sheet. The islands are placed according to the layout, so the mesh
+    /// itself must not be moved by `MeshNesting::apply_nesting`.
+    #[cfg(feature = "svg")]
+    fn export_nesting_svg(&self, layout: &NestingLayout<T::V, T::S, T::Trans>) -> Vec<String>
+    where
+        Self: MeshIslands<2, T>,
+        T::Edge: HalfEdge<T>,
+    {
+        (0..layout.sheets)
+            .map(|sheet| {
+                let mut faces = Vec::new();
+                for p in layout.sheet(sheet) {
+                    let mut seen = HashSet::new();
+                    for v in self.island_vertices(p.island) {
+                        for e in self.vertex(v).edges_out(self) {
+                            if !e.is_boundary_self() && seen.insert(e.face_id()) {
+                                faces.push((e.face_id(), p.transform));
+                            }
+                        }
+                    }
+                }
+                svg::export_svg::<T>(
+                    self,
+                    faces,
+                    [T::S::ZERO, T::S::ZERO, layout.width, layout.height],
+                )
+            })
+            .collect()
+    }
 }
 
 impl<T: EuclideanMeshType<2>> BackendSVG<T> for T::Mesh
@@ -1,8 +1,8 @@
 use crate::{
-    math::Vector,
+    math::{Scalar, TransformTrait, Vector},
     mesh::{
-        CurvedEdge, DefaultEdgePayload, DefaultFacePayload, EuclideanMeshType, MeshTypeHalfEdge,
-        PathBuilder,
+        CurvedEdge, CurvedEdgeType, DefaultEdgePayload, DefaultFacePayload, EdgeBasics,
+        EuclideanMeshType, FaceBasics, MeshBasics, MeshTypeHalfEdge, PathBuilder, VertexBasics,
     },
 };
 
@@ -64,13 +64,10 @@ where
                 pb.line_to(end);
             }
             usvg::tiny_skia_path::PathSegment::QuadTo(c1, p) => {
-                let end = pb.add_vertex_autoclose(v(p));
-                pb.quad_to(v(c1), end);
+                pb.quad_bezier_to(v(c1), v(p));
             }
             usvg::tiny_skia_path::PathSegment::CubicTo(c1, c2, p) => {
-                let end = pb.add_vertex_autoclose(v(p));
-                //pb.line_to(end);
-                pb.cubic_to(v(c1), v(c2), end);
+                pb.cubic_bezier_to(v(c1), v(c2), v(p));
             }
             usvg::tiny_skia_path::PathSegment::Close => {
                 pb.close(Default::default());
@@ -114,3 +111,51 @@ where
     let tree = res.expect("Failed to parse SVG");
     import_group::<T>(mesh, tree.root());
 }
+
+/// Returns the path data of the face with the positions transformed by `t`.
+fn face_path<T: EuclideanMeshType<2>>(mesh: &T::Mesh, f: T::F, t: &T::Trans) -> String
+where
+    T::Edge: CurvedEdge<2, T>,
+{
+    let p = |v: T::Vec| {
+        let q = t.apply(v);
+        format!("{} {}", q.x().to_f64(), q.y().to_f64())
+    };
+    let mut d = String::new();
+    for (i, e) in mesh.face(f).edges(mesh).enumerate() {
+        if i == 0 {
+            d += &format!("M {}", p(e.origin(mesh).pos()));
+        }
+        let end = p(e.target(mesh).pos());
+        d += &match e.curve_type() {
+            CurvedEdgeType::Linear => format!(" L {}", end),
+            CurvedEdgeType::QuadraticBezier(c) => format!(" Q {} {}", p(c), end),
+            CurvedEdgeType::CubicBezier(c1, c2) => format!(" C {} {} {}", p(c1), p(c2), end),
+        };
+    }
+    d + " Z"
+}
+
+/// Returns an SVG document with a path for each of the given faces, transformed by
+/// the given transformation, and the given view box `[x, y, width, height]`.
+pub(crate) fn export_svg<T: EuclideanMeshType<2>>(
+    mesh: &T::Mesh,
+    faces: impl IntoIterator<Item = (T::F, T::Trans)>,
+    view_box: [T::S; 4],
+) -> String
+where
+    T::Edge: CurvedEdge<2, T>,
+{
+    let [x, y, w, h] = view_box.map(|c| c.to_f64());
+    let mut svg = format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' viewBox='{} {} {} {}'>\n",
+        x, y, w, h
+    );
+    for (f, t) in faces {
+        svg += &format!(
+            "<path d='{}' fill='none' stroke='black'/>\n",
+            face_path::<T>(mesh, f, &t)
+        );
+    }
+    svg + "</svg>\n"
+}
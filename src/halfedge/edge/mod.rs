@@ -8,7 +8,7 @@ use super::HalfEdgeImplMeshType;
 use crate::{
     math::IndexType,
     mesh::{DefaultEdgePayload, Edge, EdgeBasics, EdgePayload},
-    util::Deletable,
+    util::{remap_index, Deletable},
 };
 
 // TODO: Memory alignment?
@@ -80,6 +80,17 @@ impl<T: HalfEdgeImplMeshType> std::fmt::Debug for HalfEdgeImpl<T> {
     }
 }
 
+impl<T: HalfEdgeImplMeshType> HalfEdgeImpl<T> {
+    /// Replaces all references to other elements using the maps of `DeletableVector::compact_map`.
+    pub(crate) fn remap(&mut self, vs: &[T::V], es: &[T::E], fs: &[T::F]) {
+        self.next = remap_index(es, self.next);
+        self.twin = remap_index(es, self.twin);
+        self.prev = remap_index(es, self.prev);
+        self.origin_id = remap_index(vs, self.origin_id);
+        self.face = remap_index(fs, self.face);
+    }
+}
+
 impl<T: HalfEdgeImplMeshType> Deletable<T::E> for HalfEdgeImpl<T> {
     fn delete(&mut self) {
         assert!(self.id != IndexType::max());
@@ -4,9 +4,10 @@ use super::{HalfEdgeImplMeshType, HalfEdgeMeshImpl};
 use crate::{
     math::IndexType,
     mesh::{
-        EdgeBasics, FaceBasics, HalfEdge, MeshBasics, Triangulation, VertexBasics, VertexPayload,
+        CompactionMap, EdgeBasics, FaceBasics, HalfEdge, MeshBasics, MeshCompact, Triangulation,
+        VertexBasics, VertexPayload,
     },
-    util::Deletable,
+    util::{remap_index, Deletable, Element},
 };
 
 impl<T: HalfEdgeImplMeshType> MeshBasics<T> for HalfEdgeMeshImpl<T> {
@@ -40,6 +41,23 @@ impl<T: HalfEdgeImplMeshType> MeshBasics<T> for HalfEdgeMeshImpl<T> {
         self.faces.get_mut(index)
     }
 
+    fn generation(&self) -> Option<u64> {
+        Some(
+            self.vertices
+                .generation()
+                .max(self.halfedges.generation())
+                .max(self.faces.generation()),
+        )
+    }
+
+    fn face_changed_since(&self, face: T::F, generation: u64) -> bool {
+        self.faces.changed_since(face, generation)
+            || self.face(face).edges(self).any(|e| {
+                self.halfedges.changed_since(e.id(), generation)
+                    || self.vertices.changed_since(e.origin_id(), generation)
+            })
+    }
+
     fn is_open(&self) -> bool {
         self.halfedges.iter().any(|e| e.is_boundary_self())
     }
@@ -64,6 +82,7 @@ impl<T: HalfEdgeImplMeshType> MeshBasics<T> for HalfEdgeMeshImpl<T> {
         self.vertices.clear();
         self.halfedges.clear();
         self.faces.clear();
+        self.attributes.clear_values();
         self
     }
 
@@ -191,3 +210,92 @@ impl<T: HalfEdgeImplMeshType> MeshBasics<T> for HalfEdgeMeshImpl<T> {
         })
     }
 }
+
+impl<T: HalfEdgeImplMeshType> MeshCompact<T> for HalfEdgeMeshImpl<T> {
+    fn compact(&mut self) -> CompactionMap<T> {
+        let map = CompactionMap {
+            vertices: self.vertices.compact_map(),
+            edges: self.halfedges.compact_map(),
+            faces: self.faces.compact_map(),
+        };
+        for v in self.vertices.iter_mut() {
+            v.remap(&map.edges);
+        }
+        for e in self.halfedges.iter_mut() {
+            e.remap(&map.vertices, &map.edges, &map.faces);
+        }
+        for f in self.faces.iter_mut() {
+            f.set_edge(remap_index(&map.edges, f.edge_id()));
+        }
+        self.vertices.compact();
+        self.halfedges.compact();
+        self.faces.compact();
+        self.attributes
+            .remap_elements(Element::Vertex, &map.vertices);
+        self.attributes.remap_elements(Element::Edge, &map.edges);
+        self.attributes.remap_elements(Element::Face, &map.faces);
+        map
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_compact() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        assert!(mesh.clone().compact().is_identity());
+
+        // insert three vertices into an edge and remove the first two again
+        let e = mesh.edge_ids().next().unwrap();
+        let (a, b) = (mesh.edge(e).origin_id(), mesh.edge(e).target_id(&mesh));
+        let p = |t: f64| {
+            let p = mesh.vertex(a).pos().lerp(&mesh.vertex(b).pos(), t);
+            (
+                Default::default(),
+                Default::default(),
+                VertexPayloadPNU::from_pos(p),
+            )
+        };
+        let ps = [p(0.25), p(0.5), p(0.75)];
+        mesh.insert_vertices_into_edge(e, ps.into_iter());
+        mesh.join_edges(8);
+        mesh.join_edges(9);
+        mesh.attributes_mut()
+            .add::<usize>("id", Element::Vertex)
+            .set(10usize, 10);
+        let f = mesh.face_ids().next().unwrap();
+        let vs = mesh.face(f).vertex_ids(&mesh).collect::<Vec<_>>();
+        mesh.split_face_default(f, vs[0], vs[2], []);
+        mesh.remove_edge(mesh.shared_edge_id(vs[0], vs[2]).unwrap());
+        assert_eq!(mesh.max_vertex_index(), 11);
+        let before = mesh.clone();
+
+        let map = mesh.compact();
+        assert!(!map.is_identity());
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.max_vertex_index(), mesh.num_vertices());
+        assert_eq!(mesh.num_vertices(), 9);
+        assert_eq!(map.vertex(8), None);
+        assert_eq!(map.vertex(10), Some(8));
+        assert_eq!(mesh.vertex(8).pos(), before.vertex(10).pos());
+        assert_eq!(
+            *mesh.attributes().get::<usize>("id").unwrap().get(8usize),
+            10
+        );
+        assert_eq!(map.faces.len(), 7);
+        assert_eq!(mesh.num_faces(), 6);
+        for face in before.faces() {
+            let g = map.face(face.id()).unwrap();
+            let old = face
+                .vertex_ids(&before)
+                .map(|v| map.vertex(v).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(mesh.face(g).vertex_ids(&mesh).collect::<Vec<_>>(), old);
+        }
+        assert_eq!(mesh.num_edges(), before.num_edges());
+        assert!(mesh.edges().all(|e| e.id() < mesh.num_edges()));
+    }
+}
@@ -2,10 +2,13 @@ use crate::{
     halfedge::{HalfEdgeFaceImpl, HalfEdgeImplMeshType, HalfEdgeMeshImpl},
     math::IndexType,
     mesh::{
-        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, FaceBasics, HalfEdge, HalfEdgeVertex,
-        MeshBasics, MeshBuilder, MeshHalfEdgeBuilder,
+        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, FaceBasics, HalfEdge,
+        HalfEdgeSemiBuilder, HalfEdgeVertex, MeshBasics, MeshBuilder, MeshHalfEdgeBuilder,
+        VertexBasics,
     },
+    util::Element,
 };
+use std::collections::HashMap;
 
 /*
 impl<T:HalfEdgeImplMeshType> HalfEdgeMeshImpl<T> {
@@ -82,9 +85,75 @@ impl<T: HalfEdgeImplMeshType> MeshBuilder<T> for HalfEdgeMeshImpl<T> {
             self.edge_mut(e).delete_face();
         }
         self.faces.delete_internal(f);
+        self.attributes.remove_element(Element::Face, f);
         fp
     }
 
+    fn remove_edge(&mut self, e: T::E) -> T::F {
+        let edge = self.edge(e).clone();
+        let twin = edge.twin(self);
+        let (f1, f2) = (edge.face_id(), twin.face_id());
+        assert!(
+            f1 != f2 || edge.is_boundary_self(),
+            "Cannot remove edge {} since it has the face {} on both sides",
+            e,
+            f1
+        );
+        let (prev1, next1) = (edge.prev_id(), edge.next_id());
+        let (prev2, next2) = (twin.prev_id(), twin.next_id());
+        assert!(
+            next1 != twin.id() && next2 != e,
+            "Cannot remove edge {} since an endpoint has no other edges",
+            e
+        );
+
+        self.edge_mut(prev1).set_next(next2);
+        self.edge_mut(next2).set_prev(prev1);
+        self.edge_mut(prev2).set_next(next1);
+        self.edge_mut(next1).set_prev(prev2);
+        if self.vertex(edge.origin_id()).edge_id(self) == e {
+            self.vertex_mut(edge.origin_id()).set_edge(next2);
+        }
+        if self.vertex(twin.origin_id()).edge_id(self) == twin.id() {
+            self.vertex_mut(twin.origin_id()).set_edge(next1);
+        }
+
+        let kept = if !edge.is_boundary_self() && !twin.is_boundary_self() {
+            self.faces.delete_internal(f2);
+            self.attributes.remove_element(Element::Face, f2);
+            self.face_mut(f1).set_edge(next1);
+            self.edge(next1)
+                .clone()
+                .edges_face_mut(self)
+                .filter(|e| e.face_id() != f1)
+                .for_each(|e| {
+                    e.delete_face();
+                    e.set_face(f1);
+                });
+            f1
+        } else {
+            // the remaining face is merged with the outside
+            for f in [f1, f2] {
+                if f != IndexType::max() {
+                    self.faces.delete_internal(f);
+                    self.attributes.remove_element(Element::Face, f);
+                }
+            }
+            self.edge(next1)
+                .clone()
+                .edges_face_mut(self)
+                .filter(|e| !e.is_boundary_self())
+                .for_each(|e| e.delete_face());
+            IndexType::max()
+        };
+
+        self.halfedges.delete_internal(e);
+        self.halfedges.delete_internal(twin.id());
+        self.attributes.remove_element(Element::Edge, e);
+        self.attributes.remove_element(Element::Edge, twin.id());
+        kept
+    }
+
     fn close_hole(&mut self, e: T::E, fp: T::FP, curved: bool) -> T::F {
         let f = self.faces.push(HalfEdgeFaceImpl::new(e, curved, fp));
         self.edge(e)
@@ -121,32 +190,36 @@ impl<T: HalfEdgeImplMeshType> MeshBuilder<T> for HalfEdgeMeshImpl<T> {
         e: T::E,
         ps: I,
     ) -> T::E {
+        // Each new vertex is inserted between the current origin of `e` and `e`,
+        // so the vertices are inserted in order from the origin to the target.
         let twin_id = self.edge(e).twin_id();
-        let mut current = self.edge(e).prev_id();
-        let mut current_twin = self.edge(twin_id).next_id();
-        let f1 = self.edge(e).face_id();
-        let f2 = self.edge(twin_id).face_id();
-        let mut last_v = self.edge(e).origin_id();
-        let mut first = true;
-        for (ep1, ep2, vp) in ps {
-            let (v, e1, e2) =
-                self.add_vertex_via_edge(current, self.edge(current).twin_id(), vp, ep1, ep2);
-            current = e1;
-            current_twin = e2;
-            last_v = v;
-            self.edge_mut(current).set_face(f1);
-            self.edge_mut(current_twin).set_face(f2);
-            if first {
-                self.vertex_mut(self.edge(e).origin_id()).set_edge(e1);
-                first = false;
+        let (f1, f2) = (self.edge(e).face_id(), self.edge(twin_id).face_id());
+        let (a, b) = (self.edge(e).origin_id(), self.edge(twin_id).origin_id());
+        let ps = ps.collect::<Vec<_>>();
+        let n = ps.len();
+        for (i, (ep1, ep2, vp)) in ps.into_iter().enumerate() {
+            let origin = self.edge(e).origin_id();
+            let (prev, next) = (self.edge(e).prev_id(), self.edge(twin_id).next_id());
+            let v = self.vertices.push(T::Vertex::new(e, vp));
+            let (e1, e2) = self.insert_edge_no_update_no_check(
+                (e, prev, origin, f1, ep1),
+                (next, twin_id, v, f2, ep2),
+            );
+            self.edge_mut(prev).set_next(e1);
+            self.edge_mut(e).set_prev(e1);
+            self.edge_mut(next).set_prev(e2);
+            self.edge_mut(twin_id).set_next(e2);
+            self.edge_mut(e).set_origin(v);
+            if self.vertex(origin).edge_id(self) == e {
+                self.vertex_mut(origin).set_edge(e1);
             }
-        }
 
-        self.edge_mut(current).set_next(e);
-        self.edge_mut(e).set_prev(current);
-        self.edge_mut(current_twin).set_prev(twin_id);
-        self.edge_mut(twin_id).set_next(current_twin);
-        self.edge_mut(e).set_origin(last_v);
+            let t = (i + 1) as f64 / (n + 1) as f64;
+            self.attributes
+                .interpolate_element(Element::Vertex, a, b, t, v);
+            self.attributes.copy_element(Element::Edge, e, e1);
+            self.attributes.copy_element(Element::Edge, twin_id, e2);
+        }
 
         return e;
     }
@@ -196,4 +269,109 @@ impl<T: HalfEdgeImplMeshType> MeshBuilder<T> for HalfEdgeMeshImpl<T> {
         self.insert_edge(first, Default::default(), last, Default::default());
         return first;
     }
+
+    fn insert_indexed_faces(
+        &mut self,
+        vp: impl IntoIterator<Item = T::VP>,
+        faces: impl IntoIterator<Item = Vec<usize>>,
+    ) -> Vec<T::F>
+    where
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        let vs: Vec<T::V> = vp.into_iter().map(|vp| self.add_vertex(vp)).collect();
+
+        // (origin, target) -> halfedge
+        let mut edge_map: HashMap<(usize, usize), T::E> = HashMap::new();
+        // (halfedge, origin, target, face, prev, next)
+        let mut inner: Vec<(T::E, usize, usize, T::F, T::E, T::E)> = Vec::new();
+        let mut res = Vec::new();
+
+        for cycle in faces {
+            assert!(
+                cycle.len() >= 3,
+                "a face must have at least 3 vertices, but got {:?}",
+                cycle
+            );
+            let f = self.faces.allocate();
+            let es: Vec<T::E> = cycle.iter().map(|_| self.halfedges.allocate()).collect();
+            let n = cycle.len();
+            for i in 0..n {
+                let key = (cycle[i], cycle[(i + 1) % n]);
+                assert!(
+                    edge_map.insert(key, es[i]).is_none(),
+                    "the halfedge {:?} is used twice; the faces are not a consistently oriented manifold",
+                    key
+                );
+                inner.push((es[i], key.0, key.1, f, es[(i + n - 1) % n], es[(i + 1) % n]));
+            }
+            self.faces
+                .set(f, HalfEdgeFaceImpl::new(es[0], false, T::FP::default()));
+            res.push(f);
+        }
+
+        // find or allocate the twins. Twins without a face are boundary halfedges.
+        let mut twins: HashMap<T::E, T::E> = HashMap::new();
+        let mut boundary: Vec<(T::E, usize, T::E)> = Vec::new();
+        for &(e, a, b, _, _, _) in &inner {
+            if let Some(&t) = edge_map.get(&(b, a)) {
+                twins.insert(e, t);
+            } else {
+                let t = self.halfedges.allocate();
+                twins.insert(e, t);
+                twins.insert(t, e);
+                boundary.push((t, b, e));
+            }
+        }
+
+        for &(e, origin, _, f, prev, next) in &inner {
+            self.insert_halfedge_no_update_no_check(
+                e,
+                vs[origin],
+                f,
+                prev,
+                twins[&e],
+                next,
+                T::EP::default(),
+            );
+        }
+
+        // Boundary halfedges are inserted with a preliminary next / prev and linked afterwards.
+        for &(t, origin, e) in &boundary {
+            self.insert_halfedge_no_update_no_check(
+                t,
+                vs[origin],
+                IndexType::max(),
+                e,
+                e,
+                e,
+                T::EP::default(),
+            );
+        }
+        for &(t, _, e) in &boundary {
+            // rotate around the target of t until we find the outgoing boundary halfedge
+            let mut cur = e;
+            loop {
+                let candidate = twins[&self.edge(cur).prev_id()];
+                if self.edge(candidate).is_boundary_self() {
+                    self.edge_mut(t).set_next(candidate);
+                    self.edge_mut(candidate).set_prev(t);
+                    break;
+                }
+                cur = candidate;
+            }
+        }
+
+        // prefer boundary edges as representatives of the vertices
+        for &(t, origin, _) in &boundary {
+            self.vertex_mut(vs[origin]).set_edge(t);
+        }
+        for &(e, origin, _, _, _, _) in &inner {
+            if self.vertex(vs[origin]).edge_id(self) == IndexType::max() {
+                self.vertex_mut(vs[origin]).set_edge(e);
+            }
+        }
+
+        res
+    }
 }
@@ -4,10 +4,11 @@ use crate::{
     },
     math::IndexType,
     mesh::{
-        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, FaceBasics, HalfEdge,
-        HalfEdgeSemiBuilder, HalfEdgeVertex, MeshBasics, MeshHalfEdgeBuilder,
+        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, FaceBasics, HalfEdge, HalfEdgeMesh,
+        HalfEdgeSemiBuilder, HalfEdgeVertex, MeshBasics, MeshBuilder, MeshHalfEdgeBuilder,
         MeshType, VertexBasics,
     },
+    util::Element,
 };
 use itertools::Itertools;
 
@@ -320,6 +321,161 @@ impl<T: HalfEdgeImplMeshType> MeshHalfEdgeBuilder<T> for HalfEdgeMeshImpl<T> {
             curved,
         )
     }
+
+    fn split_face(
+        &mut self,
+        f: T::F,
+        from: T::V,
+        to: T::V,
+        chain: impl IntoIterator<Item = (T::EP, T::EP, T::VP)>,
+        ep1: T::EP,
+        ep2: T::EP,
+    ) -> (T::F, T::F) {
+        assert!(from != to, "Cannot split a face at a single vertex");
+        let find = |v: T::V| {
+            self.face(f)
+                .edges(self)
+                .find(|e| e.target_id(self) == v)
+                .unwrap_or_else(|| panic!("Vertex {} is not on face {}", v, f))
+                .id()
+        };
+        let mut inside = find(from);
+        let outside = find(to);
+        let curved = self.face(f).may_be_curved();
+        let attributes = self.attributes.element_values(Element::Face, f);
+        let fp = self.remove_face(f);
+
+        // walk along the chain, always keeping the rest of the face on the left
+        let mut output = self.edge(inside).next_id();
+        for (ep_in, ep_out, vp) in chain {
+            let (_, e1, e2) = self.add_vertex_via_edge(inside, output, vp, ep_in, ep_out);
+            inside = e1;
+            output = e2;
+        }
+        assert!(
+            self.edge(inside).next_id() != outside && self.edge(outside).next_id() != inside,
+            "The vertices {} and {} are already connected",
+            from,
+            to
+        );
+        let (e1, e2) = self.insert_edge(inside, ep1, outside, ep2);
+
+        let left = self.close_hole(e1, fp, curved);
+        let right = self.close_hole(e2, fp, curved);
+        self.attributes.restore_element(left, &attributes);
+        self.attributes.restore_element(right, &attributes);
+        (left, right)
+    }
+
+    fn split_face_default(
+        &mut self,
+        f: T::F,
+        from: T::V,
+        to: T::V,
+        chain: impl IntoIterator<Item = T::VP>,
+    ) -> (T::F, T::F)
+    where
+        T::EP: DefaultEdgePayload,
+    {
+        self.split_face(
+            f,
+            from,
+            to,
+            chain
+                .into_iter()
+                .map(|vp| (Default::default(), Default::default(), vp)),
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    fn join_edges(&mut self, v: T::V) -> T::E {
+        // u -a-> v -b-> w and the twins w -b'-> v -a'-> u
+        let b = self.vertex(v).edge(self).expect("The vertex has no edges");
+        let a = b.prev(self);
+        let b_twin = b.twin(self);
+        let a_twin = a.twin(self);
+        assert!(
+            b_twin.next_id() == a_twin.id(),
+            "Vertex {} must have exactly two neighbors",
+            v
+        );
+        let w = b_twin.origin_id();
+        assert!(
+            a.origin_id() != w,
+            "Cannot join the edges of vertex {} since both lead to the same vertex",
+            v
+        );
+
+        self.edge_mut(a.id()).set_next(b.next_id());
+        self.edge_mut(b.next_id()).set_prev(a.id());
+        self.edge_mut(a_twin.id()).set_prev(b_twin.prev_id());
+        self.edge_mut(b_twin.prev_id()).set_next(a_twin.id());
+        self.edge_mut(a_twin.id()).set_origin(w);
+        if !b.is_boundary_self() && self.face(b.face_id()).edge_id() == b.id() {
+            self.face_mut(b.face_id()).set_edge(a.id());
+        }
+        if !b_twin.is_boundary_self() && self.face(b_twin.face_id()).edge_id() == b_twin.id() {
+            self.face_mut(b_twin.face_id()).set_edge(a_twin.id());
+        }
+        if self.vertex(w).edge_id(self) == b_twin.id() {
+            self.vertex_mut(w).set_edge(a_twin.id());
+        }
+
+        self.halfedges.delete_internal(b.id());
+        self.halfedges.delete_internal(b_twin.id());
+        self.vertices.delete_internal(v);
+        self.attributes.remove_element(Element::Edge, b.id());
+        self.attributes.remove_element(Element::Edge, b_twin.id());
+        self.attributes.remove_element(Element::Vertex, v);
+        a.id()
+    }
+
+    fn stitch_boundary_loops(&mut self, a: T::E, b: T::E) {
+        let la = self.edges_from(a).map(|e| e.id()).collect::<Vec<_>>();
+        let lb = self.edges_back_from(b).map(|e| e.id()).collect::<Vec<_>>();
+        assert!(
+            la.len() == lb.len(),
+            "The boundary loops have different lengths {} and {}",
+            la.len(),
+            lb.len()
+        );
+        assert!(
+            la.iter().all(|e| self.edge(*e).is_boundary_self())
+                && lb.iter().all(|e| self.edge(*e).is_boundary_self()),
+            "Can only stitch boundary loops"
+        );
+        assert!(!la.contains(&b), "Cannot stitch a loop to itself");
+
+        // collect the outgoing edges first since the rotation around the vertices follows the twins
+        let outs = lb
+            .iter()
+            .map(|e| {
+                self.edge(*e)
+                    .origin(self)
+                    .edges_out(self)
+                    .map(|e| e.id())
+                    .collect_vec()
+            })
+            .collect_vec();
+        for ((ea, eb), outs) in la.iter().zip(lb.iter()).zip(outs) {
+            let (ta, tb) = (self.edge(*ea).twin_id(), self.edge(*eb).twin_id());
+            let w = self.edge(ta).origin_id();
+            let vb = self.edge(*eb).origin_id();
+            self.vertices.delete_internal(vb);
+            self.attributes.remove_element(Element::Vertex, vb);
+            for e in outs {
+                self.edge_mut(e).set_origin(w);
+            }
+            self.edge_mut(ta).set_twin(tb);
+            self.edge_mut(tb).set_twin(ta);
+            self.vertex_mut(w).set_edge(ta);
+        }
+        for e in la.iter().chain(lb.iter()) {
+            self.halfedges.delete_internal(*e);
+            self.attributes.remove_element(Element::Edge, *e);
+        }
+    }
 }
 
 impl<T: HalfEdgeImplMeshType> HalfEdgeMeshImpl<T> {
@@ -328,7 +484,7 @@ impl<T: HalfEdgeImplMeshType> HalfEdgeMeshImpl<T> {
         fv: FV,
         fe: FE,
         ff: FF,
-        fm: FM
+        fm: FM,
     ) -> Self
     where
         FE: Fn(&T2::EP) -> T::EP,
@@ -393,3 +549,69 @@ impl<T: HalfEdgeImplMeshType> HalfEdgeMeshImpl<T> {
         res
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn top(mesh: &Mesh3d64) -> (usize, Vec<usize>) {
+        let f = mesh
+            .faces()
+            .find(|f| f.normal(mesh).y > 0.5)
+            .expect("no top face");
+        (f.id(), f.vertex_ids(mesh).collect())
+    }
+
+    #[test]
+    fn test_split_face() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        let (f, vs) = top(&mesh);
+        let (left, right) = mesh.split_face_default(f, vs[0], vs[2], []);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.num_faces(), 7);
+        for g in [left, right] {
+            assert_eq!(mesh.face(g).vertices(&mesh).count(), 3);
+            assert!(mesh.face(g).normal(&mesh).y.is_about(0.5, 1e-12));
+        }
+        // the left face contains the boundary from `to` back to `from`
+        assert!(mesh.face(left).vertex_ids(&mesh).any(|v| v == vs[3]));
+        assert!(mesh.face(right).vertex_ids(&mesh).any(|v| v == vs[1]));
+    }
+
+    #[test]
+    fn test_split_face_chain() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        let (f, vs) = top(&mesh);
+        let p = |i: usize| mesh.vertex(vs[i]).pos();
+        let (a, b) = (p(0).lerp(&p(1), 0.5), p(3).lerp(&p(2), 0.5));
+        let chain = [
+            VertexPayloadPNU::from_pos(a.lerp(&b, 0.3)),
+            VertexPayloadPNU::from_pos(a.lerp(&b, 0.6)),
+        ];
+        let (left, right) = mesh.split_face_default(f, vs[0], vs[2], chain);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.num_vertices(), 10);
+        assert_eq!(mesh.num_faces(), 7);
+        assert_eq!(mesh.face(left).vertices(&mesh).count(), 5);
+        assert_eq!(mesh.face(right).vertices(&mesh).count(), 5);
+        let area = |g: usize| {
+            mesh.face(g)
+                .vertices(&mesh)
+                .map(|v| v.pos())
+                .normal()
+                .norm()
+        };
+        assert!((area(left) + area(right)).is_about(1.0, 1e-12));
+    }
+
+    #[test]
+    #[should_panic(expected = "already connected")]
+    fn test_split_face_adjacent() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        let (f, vs) = top(&mesh);
+        mesh.split_face_default(f, vs[0], vs[1], []);
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     mesh::{
         EdgeBasics, HalfEdge, HalfEdgeSemiBuilder, MeshBasics, MeshHalfEdgeBuilder, VertexBasics,
     },
+    util::Element,
 };
 
 // TODO: Simplify these
@@ -122,6 +123,7 @@ impl<T: HalfEdgeImplMeshType> HalfEdgeSemiBuilder<T> for HalfEdgeMeshImpl<T> {
 
     fn subdivide_unsafe(&mut self, e: T::E, vp: T::VP, ep: T::EP) -> T::E {
         let old_edge = self.edge(e).clone();
+        let target = self.edge(old_edge.next_id()).origin_id();
 
         let new_v = self.vertices.allocate();
         let new_edge = self.halfedges.allocate();
@@ -143,6 +145,15 @@ impl<T: HalfEdgeImplMeshType> HalfEdgeSemiBuilder<T> for HalfEdgeMeshImpl<T> {
         self.edge_mut(old_edge.next_id()).set_prev(new_edge);
         self.edge_mut(old_edge.id()).set_next(new_edge);
 
+        self.attributes.interpolate_element(
+            Element::Vertex,
+            old_edge.origin_id(),
+            target,
+            0.5,
+            new_v,
+        );
+        self.attributes.copy_element(Element::Edge, e, new_edge);
+
         new_edge
     }
 
@@ -187,6 +198,7 @@ impl<T: HalfEdgeImplMeshType> HalfEdgeSemiBuilder<T> for HalfEdgeMeshImpl<T> {
         self.edge_mut(other_old.id()).set_twin(new_edge);
         self.edge_mut(old_edge.next_id()).set_prev(new_edge);
         self.edge_mut(old_edge.id()).set_next(new_edge);
+        self.attributes.copy_element(Element::Edge, e, new_edge);
 
         Some(new_edge)
     }
@@ -8,9 +8,11 @@ use super::HalfEdgeImplMeshType;
 use crate::{
     math::{HasNormal, Scalar, Transformable, Vector},
     mesh::{
-        EuclideanMeshType, MeshTopology, MeshTrait, TransformableMesh, Triangulateable, WithNormals,
+        EuclideanMeshType, MeshDump, MeshIslands, MeshIsomorphism, MeshSelection, MeshStatistics,
+        MeshTopology, MeshTrait, MeshTransaction, MeshType3D, MeshValidation, TransformableMesh,
+        Triangulateable, WithNormals,
     },
-    util::DeletableVector,
+    util::{Attributes, DeletableVector},
 };
 
 /// A halfedge-inspired mesh data structure for (open) manifold meshes.
@@ -32,6 +34,7 @@ pub struct HalfEdgeMeshImpl<T: HalfEdgeImplMeshType> {
     halfedges: DeletableVector<T::Edge, T::E>,
     faces: DeletableVector<T::Face, T::F>,
     payload: T::MP,
+    attributes: Attributes,
 }
 
 impl<T: HalfEdgeImplMeshType> HalfEdgeMeshImpl<T> {
@@ -42,8 +45,20 @@ impl<T: HalfEdgeImplMeshType> HalfEdgeMeshImpl<T> {
             halfedges: DeletableVector::new(),
             faces: DeletableVector::new(),
             payload: T::MP::default(),
+            attributes: Attributes::new(),
         }
     }
+
+    /// Returns the named attribute channels attached to the elements of the mesh.
+    pub fn attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+
+    /// Returns the named attribute channels attached to the elements of the mesh mutably,
+    /// e.g., to add a channel with `mesh.attributes_mut().add::<f32>("temperature", Element::Vertex)`.
+    pub fn attributes_mut(&mut self) -> &mut Attributes {
+        &mut self.attributes
+    }
 }
 
 impl<T: HalfEdgeImplMeshType> Default for HalfEdgeMeshImpl<T> {
@@ -70,10 +85,33 @@ impl<
 {
 }
 impl<T: HalfEdgeImplMeshType> MeshTopology<T> for HalfEdgeMeshImpl<T> {}
+impl<T: HalfEdgeImplMeshType> MeshSelection<T> for HalfEdgeMeshImpl<T> {}
+impl<T: HalfEdgeImplMeshType> MeshTransaction<T> for HalfEdgeMeshImpl<T> {}
+impl<T: HalfEdgeImplMeshType> MeshDump<T> for HalfEdgeMeshImpl<T> {}
+impl<const D: usize, T: HalfEdgeImplMeshType + EuclideanMeshType<D>> MeshIslands<D, T>
+    for HalfEdgeMeshImpl<T>
+{
+    fn attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+
+    fn attributes_mut(&mut self) -> &mut Attributes {
+        &mut self.attributes
+    }
+}
 impl<T: HalfEdgeImplMeshType> Triangulateable<T> for HalfEdgeMeshImpl<T> {}
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshStatistics<T> for HalfEdgeMeshImpl<T> {}
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshValidation<T> for HalfEdgeMeshImpl<T> {}
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshIsomorphism<T> for HalfEdgeMeshImpl<T> {}
 impl<T: HalfEdgeImplMeshType> MeshTrait for HalfEdgeMeshImpl<T> {
     type T = T;
 }
 
 #[cfg(feature = "netsci")]
 impl<T: HalfEdgeImplMeshType> crate::mesh::NetworkScience<T> for HalfEdgeMeshImpl<T> {}
+
+#[cfg(feature = "compression")]
+impl<T: HalfEdgeImplMeshType + crate::mesh::MeshType3D> crate::mesh::MeshCompression<T>
+    for HalfEdgeMeshImpl<T>
+{
+}
@@ -1,11 +1,24 @@
 use super::{HalfEdgeImplMeshType, HalfEdgeMeshImpl};
 use crate::{
+    math::{HasNormal, HasUV, Transformable},
     mesh::{
-        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, EuclideanMeshType, FaceBasics,
-        HalfEdge, MeshBasics, MeshBuilder, MeshPosition, MeshType3D, MeshTypeHalfEdge,
+        CornerPayload, CurvedEdge, DefaultEdgePayload, DefaultFacePayload, EdgeBasics,
+        EuclideanMeshType, FaceBasics, HalfEdge, MeshBasics, MeshBuilder, MeshPosition, MeshType3D,
+        MeshTypeHalfEdge,
     },
-    operations::{MeshExtrude, MeshLoft, MeshSubdivision},
-    primitives::{Make2dShape, MakePlane, MakePrismatoid, MakeSphere},
+    operations::{
+        MeshArray, MeshBevel, MeshDecimate, MeshDisplace, MeshDissolve, MeshExtrude, MeshFillHoles,
+        MeshFillet, MeshFit, MeshImplicit, MeshInternalFaces, MeshIvy, MeshKnife, MeshLSystem,
+        MeshLoft, MeshMetaballs, MeshMirror, MeshNesting, MeshOffset, MeshPaint, MeshPipeline,
+        MeshPlanarize, MeshProject, MeshReplicate, MeshSdf, MeshSeams, MeshShapeGrammar,
+        MeshSilhouette, MeshSlice, MeshSmoothing, MeshSplit, MeshSplitFaces, MeshStreamlines,
+        MeshSubdivision, MeshSubmesh, MeshThicken,
+    },
+    primitives::{
+        Make2dShape, MakeArrow, MakeCrate, MakeCurved2dShape, MakeGear, MakeGem, MakeParametric,
+        MakePlane, MakePrismatoid, MakeRoof, MakeSphere, MakeSpring, MakeTube, MakeVoxels,
+    },
+    util::Attributes,
 };
 
 impl<T: HalfEdgeImplMeshType<Mesh = Self>> Make2dShape<T> for HalfEdgeMeshImpl<T>
@@ -27,6 +40,20 @@ where
     }
 }
 
+impl<T: HalfEdgeImplMeshType + MeshType3D> MakeGem<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MakeParametric<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
 impl<const D: usize, T: HalfEdgeImplMeshType + EuclideanMeshType<D>> MakePlane<D, T>
     for HalfEdgeMeshImpl<T>
 where
@@ -44,6 +71,13 @@ where
 {
 }
 
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MakeCrate<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
 impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MakeSphere<T> for HalfEdgeMeshImpl<T>
 where
     T::EP: DefaultEdgePayload,
@@ -52,6 +86,48 @@ where
 {
 }
 
+impl<T: HalfEdgeImplMeshType + MeshType3D> MakeGear<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MakeArrow<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MakeTube<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MakeSpring<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MakeRoof<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MakeVoxels<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
 impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge> MeshSubdivision<T> for HalfEdgeMeshImpl<T>
 where
     T::EP: DefaultEdgePayload,
@@ -60,6 +136,13 @@ where
 {
 }
 
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshSubmesh<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
 impl<const D: usize, T: HalfEdgeImplMeshType + EuclideanMeshType<D>> MeshPosition<D, T>
     for HalfEdgeMeshImpl<T>
 {
@@ -72,9 +155,208 @@ where
 {
 }
 
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshThicken<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshSplitFaces<T>
+    for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshFillHoles<T>
+    for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshPlanarize<T>
+    for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshReplicate<T>
+    for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshPipeline<T>
+    for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshDisplace<T> for HalfEdgeMeshImpl<T> {}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshDissolve<T>
+    for HalfEdgeMeshImpl<T>
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshImplicit<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+#[cfg(feature = "fonts")]
+impl<T: HalfEdgeImplMeshType + MeshType3D> crate::operations::MeshEngrave<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshLSystem<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshShapeGrammar<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshMetaballs<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshInternalFaces<T> for HalfEdgeMeshImpl<T> {}
+
+impl<T: HalfEdgeImplMeshType + EuclideanMeshType<2>> MeshOffset<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshFit<T> for HalfEdgeMeshImpl<T> {}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshProject<T> for HalfEdgeMeshImpl<T> {}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshSdf<T> for HalfEdgeMeshImpl<T> {}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshBevel<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshDecimate<T>
+    for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload + CornerPayload<T::VP>,
+    T::FP: DefaultFacePayload,
+    T::VP: HasNormal<3, T::Vec, S = T::S> + HasUV<T::Vec2, S = T::S>,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshSeams<T> for HalfEdgeMeshImpl<T> {}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshSmoothing<T> for HalfEdgeMeshImpl<T> {}
+
+impl<T: HalfEdgeImplMeshType + MeshType3D> MeshSlice<T> for HalfEdgeMeshImpl<T> {}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshSilhouette<T>
+    for HalfEdgeMeshImpl<T>
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshStreamlines<T>
+    for HalfEdgeMeshImpl<T>
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshPaint<T> for HalfEdgeMeshImpl<T> {
+    fn attributes_mut(&mut self) -> &mut Attributes {
+        HalfEdgeMeshImpl::attributes_mut(self)
+    }
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshIvy<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshMirror<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshArray<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + EuclideanMeshType<2>> MeshNesting<T> for HalfEdgeMeshImpl<T> {}
+
 impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge> MeshLoft<T> for HalfEdgeMeshImpl<T>
 where
     T::EP: DefaultEdgePayload,
     T::FP: DefaultFacePayload,
 {
 }
+
+impl<const D: usize, T: HalfEdgeImplMeshType<Mesh = Self> + EuclideanMeshType<D>>
+    MakeCurved2dShape<D, T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::Edge: CurvedEdge<D, T>,
+{
+}
+
+impl<const D: usize, T: HalfEdgeImplMeshType + MeshTypeHalfEdge + EuclideanMeshType<D>>
+    MeshFillet<D, T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshSplit<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+}
+
+impl<T: HalfEdgeImplMeshType + MeshTypeHalfEdge + MeshType3D> MeshKnife<T> for HalfEdgeMeshImpl<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+}
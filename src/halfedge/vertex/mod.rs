@@ -7,7 +7,7 @@ use super::HalfEdgeImplMeshType;
 use crate::{
     math::IndexType,
     mesh::{DefaultVertexPayload, HalfEdgeVertex, MeshType, Vertex, VertexBasics, VertexPayload},
-    util::Deletable,
+    util::{remap_index, Deletable},
 };
 
 /// A vertex in a mesh.
@@ -43,6 +43,13 @@ impl<T: HalfEdgeImplMeshType> HalfEdgeVertexImpl<T> {
     }
 }
 
+impl<T: HalfEdgeImplMeshType> HalfEdgeVertexImpl<T> {
+    /// Replaces all references to other elements using the maps of `DeletableVector::compact_map`.
+    pub(crate) fn remap(&mut self, es: &[T::E]) {
+        self.edge = remap_index(es, self.edge);
+    }
+}
+
 impl<T: HalfEdgeImplMeshType> HalfEdgeVertex<T> for HalfEdgeVertexImpl<T> {
     fn set_edge(&mut self, edge: T::E) {
         self.edge = edge;
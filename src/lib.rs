@@ -8,10 +8,18 @@ pub mod math;
 pub mod mesh;
 pub mod operations;
 pub mod primitives;
+#[deprecated(
+    since = "0.3.0",
+    note = "use `procedural_modelling::stable` or the `mesh` and `halfedge` modules instead"
+)]
+pub mod representation;
+pub mod stable;
 pub mod tesselate;
 pub mod util;
 
 /// A prelude for easy importing of commonly used types and traits.
+/// This includes unstable parts of the api. See `stable` for the subset covered by
+/// semantic versioning.
 pub mod prelude {
     pub use crate::halfedge::*;
     pub use crate::math::*;
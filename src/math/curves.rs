@@ -0,0 +1,271 @@
+//! B-spline and NURBS curves in nd space.
+
+use super::{distance_to_segment, Scalar, Vector};
+
+/// A non-uniform rational B-spline (NURBS) curve in nd space.
+///
+/// With all weights equal to one, this is a plain (polynomial) B-spline curve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NurbsCurve<S: Scalar, const D: usize, VecN: Vector<S, D>> {
+    degree: usize,
+    control_points: Vec<VecN>,
+    weights: Vec<S>,
+    knots: Vec<S>,
+}
+
+/// Returns a clamped knot vector with uniformly spaced inner knots in `[0, 1]` for
+/// `n` control points, such that the curve starts at the first and ends at the last one.
+pub fn clamped_uniform_knots<S: Scalar>(n: usize, degree: usize) -> Vec<S> {
+    assert!(n > degree, "need more control points than the degree");
+    let spans = n - degree;
+    (0..n + degree + 1)
+        .map(|i| S::from_usize(i.clamp(degree, n) - degree) / S::from_usize(spans))
+        .collect()
+}
+
+impl<S: Scalar, const D: usize, VecN: Vector<S, D>> NurbsCurve<S, D, VecN> {
+    /// Creates a new NURBS curve of the given `degree`. There must be one weight per
+    /// control point and `control_points.len() + degree + 1` non-decreasing knots.
+    pub fn new(degree: usize, control_points: Vec<VecN>, weights: Vec<S>, knots: Vec<S>) -> Self {
+        assert!(degree >= 1, "the degree must be at least 1");
+        assert!(
+            control_points.len() > degree,
+            "need more control points than the degree"
+        );
+        assert_eq!(
+            weights.len(),
+            control_points.len(),
+            "need one weight per point"
+        );
+        assert_eq!(
+            knots.len(),
+            control_points.len() + degree + 1,
+            "need as many knots as points plus degree plus one"
+        );
+        assert!(
+            knots.windows(2).all(|w| w[0] <= w[1]),
+            "the knots must be non-decreasing"
+        );
+        assert!(
+            weights.iter().all(|w| *w > S::ZERO),
+            "weights must be positive"
+        );
+        Self {
+            degree,
+            control_points,
+            weights,
+            knots,
+        }
+    }
+
+    /// Creates a non-rational B-spline curve with a clamped uniform knot vector,
+    /// i.e., the curve starts at the first and ends at the last control point.
+    pub fn bspline(degree: usize, control_points: Vec<VecN>) -> Self {
+        let knots = clamped_uniform_knots(control_points.len(), degree);
+        let weights = vec![S::ONE; control_points.len()];
+        Self::new(degree, control_points, weights, knots)
+    }
+
+    /// Returns the degree of the curve.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Returns the control points of the curve.
+    pub fn control_points(&self) -> &[VecN] {
+        &self.control_points
+    }
+
+    /// Returns the weights of the control points.
+    pub fn weights(&self) -> &[S] {
+        &self.weights
+    }
+
+    /// Returns the knot vector of the curve.
+    pub fn knots(&self) -> &[S] {
+        &self.knots
+    }
+
+    /// Returns whether the weights differ, i.e., the curve is not a plain B-spline.
+    pub fn is_rational(&self) -> bool {
+        self.weights.iter().any(|w| *w != self.weights[0])
+    }
+
+    /// Returns the range of valid parameters.
+    pub fn domain(&self) -> (S, S) {
+        (
+            self.knots[self.degree],
+            self.knots[self.control_points.len()],
+        )
+    }
+
+    /// Returns the index of the knot span containing `t`.
+    fn span(&self, t: S) -> usize {
+        let n = self.control_points.len();
+        // the last span is closed on both sides
+        let i = self.knots[..=n].partition_point(|k| *k <= t);
+        i.saturating_sub(1).clamp(self.degree, n - 1)
+    }
+
+    /// Evaluates the curve at parameter `t` using de Boor's algorithm in homogeneous
+    /// coordinates. `t` is clamped to the domain of the curve.
+    pub fn point_at(&self, t: S) -> VecN {
+        let (t0, t1) = self.domain();
+        let t = t.clamp(t0, t1);
+        let p = self.degree;
+        let k = self.span(t);
+        let mut d: Vec<(VecN, S)> = (0..=p)
+            .map(|j| {
+                let i = j + k - p;
+                (self.control_points[i] * self.weights[i], self.weights[i])
+            })
+            .collect();
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let i = j + k - p;
+                let den = self.knots[i + p + 1 - r] - self.knots[i];
+                let alpha = if den > S::ZERO {
+                    (t - self.knots[i]) / den
+                } else {
+                    S::ZERO
+                };
+                let beta = S::ONE - alpha;
+                d[j] = (
+                    d[j - 1].0 * beta + d[j].0 * alpha,
+                    d[j - 1].1 * beta + d[j].1 * alpha,
+                );
+            }
+        }
+        d[p].0 / d[p].1
+    }
+
+    /// Flattens the curve to a polyline that deviates at most `tol` from the curve.
+    ///
+    /// Each knot span is subdivided recursively until the points at a quarter, half,
+    /// and three quarters of the parameter interval of each segment are within `tol`
+    /// of the chord. The polyline starts and ends at the ends of the curve.
+    pub fn flatten(&self, tol: S) -> Vec<VecN> {
+        const MAX_DEPTH: usize = 16;
+
+        fn subdivide<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+            curve: &NurbsCurve<S, D, VecN>,
+            (t0, a): (S, VecN),
+            (t1, b): (S, VecN),
+            tol: S,
+            depth: usize,
+            res: &mut Vec<VecN>,
+        ) {
+            let at = |f: S| {
+                let t = t0 + (t1 - t0) * f;
+                (t, curve.point_at(t))
+            };
+            let mid = at(S::HALF);
+            let flat = depth >= MAX_DEPTH
+                || [at(S::from(0.25)), mid, at(S::from(0.75))]
+                    .iter()
+                    .all(|(_, p)| distance_to_segment(*p, a, b) <= tol);
+            if flat {
+                res.push(b);
+            } else {
+                subdivide(curve, (t0, a), mid, tol, depth + 1, res);
+                subdivide(curve, mid, (t1, b), tol, depth + 1, res);
+            }
+        }
+
+        let (t0, t1) = self.domain();
+        let mut res = vec![self.point_at(t0)];
+        let mut last = t0;
+        for k in &self.knots {
+            if *k > last && *k <= t1 {
+                let a = *res.last().unwrap();
+                subdivide(self, (last, a), (*k, self.point_at(*k)), tol, 0, &mut res);
+                last = *k;
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use super::*;
+    use crate::extensions::nalgebra::*;
+
+    #[test]
+    fn test_bspline() {
+        let knots = clamped_uniform_knots::<f64>(5, 2);
+        assert_eq!(
+            knots,
+            vec![0.0, 0.0, 0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0, 1.0, 1.0]
+        );
+
+        let points = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(2.0, -1.0),
+            Vec2::new(3.0, 1.0),
+        ];
+        let curve = NurbsCurve::bspline(3, points.clone());
+        assert!(!curve.is_rational());
+        assert_eq!(curve.domain(), (0.0, 1.0));
+        // a clamped cubic with 4 points is a bezier curve
+        let bezier = |t: f64| {
+            let s = 1.0 - t;
+            points[0] * s * s * s
+                + points[1] * 3.0 * s * s * t
+                + points[2] * 3.0 * s * t * t
+                + points[3] * t * t * t
+        };
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            assert!(curve.point_at(t).is_about(&bezier(t), 1e-12));
+        }
+
+        // degree 1 is the control polygon
+        let line = NurbsCurve::bspline(1, points.clone());
+        assert!(line.point_at(0.5).is_about(&Vec2::new(1.5, 0.5), 1e-12));
+        assert_eq!(line.flatten(1e-3), points);
+    }
+
+    #[test]
+    fn test_nurbs_circle() {
+        // an exact circle made of four rational quadratic arcs
+        let w = std::f64::consts::FRAC_1_SQRT_2;
+        let curve = NurbsCurve::new(
+            2,
+            vec![
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec3::new(-1.0, 1.0, 0.0),
+                Vec3::new(-1.0, 0.0, 0.0),
+                Vec3::new(-1.0, -1.0, 0.0),
+                Vec3::new(0.0, -1.0, 0.0),
+                Vec3::new(1.0, -1.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+            ],
+            vec![1.0, w, 1.0, w, 1.0, w, 1.0, w, 1.0],
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, 4.0],
+        );
+        assert!(curve.is_rational());
+        for i in 0..=40 {
+            let p = curve.point_at(i as f64 / 10.0);
+            assert!(p.norm().is_about(1.0, 1e-12));
+        }
+        assert!(curve
+            .point_at(1.0)
+            .is_about(&Vec3::new(0.0, 1.0, 0.0), 1e-12));
+
+        let coarse = curve.flatten(1e-2);
+        let fine = curve.flatten(1e-4);
+        assert!(coarse.len() < fine.len());
+        assert!(coarse[0].is_about(coarse.last().unwrap(), 1e-12));
+        for polyline in [coarse, fine] {
+            for (a, b) in polyline.iter().zip(polyline.iter().skip(1)) {
+                // the sagitta of each chord is within the tolerance
+                assert!(1.0 - ((*a + *b) * 0.5).norm() <= 1e-2);
+            }
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Least-squares fitting of planes, spheres, and cylinders to point sets.
+
+use super::{Scalar, Vector3D};
+
+/// A plane fitted to a set of points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FittedPlane<Vec: Vector3D> {
+    /// The centroid of the points, which lies on the plane.
+    pub point: Vec,
+    /// The unit normal of the plane.
+    pub normal: Vec,
+    /// The root mean square distance of the points to the plane.
+    pub rms: Vec::S,
+}
+
+/// A sphere fitted to a set of points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FittedSphere<Vec: Vector3D> {
+    /// The center of the sphere.
+    pub center: Vec,
+    /// The radius of the sphere.
+    pub radius: Vec::S,
+    /// The root mean square distance of the points to the sphere.
+    pub rms: Vec::S,
+}
+
+/// An infinite cylinder fitted to a set of points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FittedCylinder<Vec: Vector3D> {
+    /// The point on the axis closest to the centroid of the points.
+    pub point: Vec,
+    /// The unit direction of the axis.
+    pub axis: Vec,
+    /// The radius of the cylinder.
+    pub radius: Vec::S,
+    /// The root mean square distance of the points to the cylinder.
+    pub rms: Vec::S,
+}
+
+/// Solves the linear system `a x = b` using Gaussian elimination with partial pivoting.
+/// Returns `None` if the system is singular.
+fn solve<S: Scalar, const N: usize>(mut a: [[S; N]; N], mut b: [S; N]) -> Option<[S; N]> {
+    let scale = a
+        .iter()
+        .flatten()
+        .fold(S::ZERO, |m, x| m.max(x.abs()))
+        .max(S::EPS);
+    for col in 0..N {
+        let pivot = (col..N)
+            .max_by(|i, j| a[*i][col].abs().partial_cmp(&a[*j][col].abs()).unwrap())
+            .unwrap();
+        if a[pivot][col].abs() <= S::EPS.sqrt() * scale {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (top, bottom) = a.split_at_mut(col + 1);
+        for (i, row) in bottom.iter_mut().enumerate() {
+            let f = row[col] / top[col][col];
+            for (x, y) in row[col..].iter_mut().zip(&top[col][col..]) {
+                *x -= f * *y;
+            }
+            b[col + 1 + i] -= f * b[col];
+        }
+    }
+    let mut x = [S::ZERO; N];
+    for row in (0..N).rev() {
+        let sum = S::stable_sum((row + 1..N).map(|k| a[row][k] * x[k]));
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// Returns the eigenvalues and the eigenvectors (as columns) of the symmetric matrix `m`
+/// using Jacobi rotations.
+fn symmetric_eigen<S: Scalar>(mut m: [[S; 3]; 3]) -> ([S; 3], [[S; 3]; 3]) {
+    let mut v = [[S::ZERO; 3]; 3];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = S::ONE;
+    }
+    for _ in 0..50 {
+        let off = m[0][1].abs() + m[0][2].abs() + m[1][2].abs();
+        let diag = m[0][0].abs() + m[1][1].abs() + m[2][2].abs();
+        if off <= S::EPS * diag || off == S::ZERO {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if m[p][q] == S::ZERO {
+                continue;
+            }
+            let theta = (m[q][q] - m[p][p]) / (S::TWO * m[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + S::ONE).sqrt());
+            let t = if theta == S::ZERO { S::ONE } else { t };
+            let c = S::ONE / (t * t + S::ONE).sqrt();
+            let s = t * c;
+            // m = J^T m J with the rotation J in the (p, q) plane
+            for row in m.iter_mut() {
+                let (mkp, mkq) = (row[p], row[q]);
+                row[p] = c * mkp - s * mkq;
+                row[q] = s * mkp + c * mkq;
+            }
+            let (mp, mq) = (m[p], m[q]);
+            m[p] = [0, 1, 2].map(|k| c * mp[k] - s * mq[k]);
+            m[q] = [0, 1, 2].map(|k| s * mp[k] + c * mq[k]);
+            for row in v.iter_mut() {
+                let (vp, vq) = (row[p], row[q]);
+                row[p] = c * vp - s * vq;
+                row[q] = s * vp + c * vq;
+            }
+        }
+    }
+    ([m[0][0], m[1][1], m[2][2]], v)
+}
+
+fn centroid<Vec: Vector3D>(points: &[Vec]) -> Vec {
+    Vec::new(
+        Vec::S::stable_mean(points.iter().map(|p| p.x())),
+        Vec::S::stable_mean(points.iter().map(|p| p.y())),
+        Vec::S::stable_mean(points.iter().map(|p| p.z())),
+    )
+}
+
+fn rms<S: Scalar>(errors: impl Iterator<Item = S>) -> S {
+    S::stable_mean(errors.map(|e| e * e)).sqrt()
+}
+
+/// Returns the eigenvalues and unit eigenvectors of the covariance of the points
+/// around their centroid, sorted by increasing eigenvalue.
+fn principal_axes<Vec: Vector3D>(points: &[Vec], c: Vec) -> [(Vec::S, Vec); 3] {
+    let mut cov = [[Vec::S::ZERO; 3]; 3];
+    for p in points {
+        let d = (*p - c).to_array();
+        for i in 0..3 {
+            for j in 0..3 {
+                cov[i][j] += d[i] * d[j];
+            }
+        }
+    }
+    let (values, vectors) = symmetric_eigen(cov);
+    let mut axes = [0, 1, 2].map(|i| {
+        (
+            values[i],
+            Vec::new(vectors[0][i], vectors[1][i], vectors[2][i]).normalize(),
+        )
+    });
+    axes.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    axes
+}
+
+/// Fits a plane to the points by minimizing the squared distances.
+/// Returns `None` if there are less than 3 points.
+pub fn fit_plane<Vec: Vector3D>(points: &[Vec]) -> Option<FittedPlane<Vec>> {
+    if points.len() < 3 {
+        return None;
+    }
+    let point = centroid(points);
+    let normal = principal_axes(points, point)[0].1;
+    Some(FittedPlane {
+        point,
+        normal,
+        rms: rms(points.iter().map(|p| (*p - point).dot(&normal))),
+    })
+}
+
+/// Fits a sphere to the points by minimizing the algebraic distance
+/// `|p - center|^2 - radius^2`, which is exact for points on a sphere.
+/// Returns `None` if there are less than 4 points or they are coplanar.
+pub fn fit_sphere<Vec: Vector3D>(points: &[Vec]) -> Option<FittedSphere<Vec>> {
+    if points.len() < 4 {
+        return None;
+    }
+    // work relative to the centroid to keep the system well-conditioned
+    let c = centroid(points);
+    // |p|^2 = 2 center . p + (radius^2 - |center|^2)
+    let mut a = [[Vec::S::ZERO; 4]; 4];
+    let mut b = [Vec::S::ZERO; 4];
+    for p in points {
+        let d = *p - c;
+        let row = [
+            d.x() * Vec::S::TWO,
+            d.y() * Vec::S::TWO,
+            d.z() * Vec::S::TWO,
+            Vec::S::ONE,
+        ];
+        let rhs = d.length_squared();
+        for i in 0..4 {
+            for j in 0..4 {
+                a[i][j] += row[i] * row[j];
+            }
+            b[i] += row[i] * rhs;
+        }
+    }
+    let x = solve(a, b)?;
+    let offset = Vec::new(x[0], x[1], x[2]);
+    let radius = (x[3] + offset.length_squared()).max(Vec::S::ZERO).sqrt();
+    let center = c + offset;
+    Some(FittedSphere {
+        center,
+        radius,
+        rms: rms(points.iter().map(|p| p.distance(&center) - radius)),
+    })
+}
+
+/// Fits a circle to the 2d coordinates of the points in the basis `u`, `v` (relative to `c`).
+/// Returns the 2d center, the radius, and the rms error.
+fn fit_circle<Vec: Vector3D>(
+    points: &[Vec],
+    c: Vec,
+    u: Vec,
+    v: Vec,
+) -> Option<(Vec::S, Vec::S, Vec::S, Vec::S)> {
+    let mut a = [[Vec::S::ZERO; 3]; 3];
+    let mut b = [Vec::S::ZERO; 3];
+    for p in points {
+        let d = *p - c;
+        let (x, y) = (d.dot(&u), d.dot(&v));
+        let row = [x * Vec::S::TWO, y * Vec::S::TWO, Vec::S::ONE];
+        let rhs = x * x + y * y;
+        for i in 0..3 {
+            for j in 0..3 {
+                a[i][j] += row[i] * row[j];
+            }
+            b[i] += row[i] * rhs;
+        }
+    }
+    let [cx, cy, k] = solve(a, b)?;
+    let radius = (k + cx * cx + cy * cy).max(Vec::S::ZERO).sqrt();
+    let error = rms(points.iter().map(|p| {
+        let d = *p - c;
+        let (x, y) = (d.dot(&u) - cx, d.dot(&v) - cy);
+        (x * x + y * y).sqrt() - radius
+    }));
+    Some((cx, cy, radius, error))
+}
+
+/// Returns two unit vectors orthogonal to the unit vector `n` and to each other.
+fn orthonormal_basis<Vec: Vector3D>(n: Vec) -> (Vec, Vec) {
+    let helper = if n.x().abs() < Vec::S::HALF {
+        Vec::new(Vec::S::ONE, Vec::S::ZERO, Vec::S::ZERO)
+    } else {
+        Vec::new(Vec::S::ZERO, Vec::S::ONE, Vec::S::ZERO)
+    };
+    let u = n.cross(&helper).normalize();
+    (u, n.cross(&u))
+}
+
+/// Fits an infinite cylinder to the points by minimizing the squared distances.
+/// Returns `None` if there are less than 5 points.
+///
+/// For each candidate axis, a circle is fitted to the points projected along it. The axis
+/// is found by testing the principal axes of the points and a grid of directions and then
+/// refining the best one with a local search, so the result is an approximation whose
+/// accuracy is limited by the search but usually close to the optimum.
+pub fn fit_cylinder<Vec: Vector3D>(points: &[Vec]) -> Option<FittedCylinder<Vec>> {
+    if points.len() < 5 {
+        return None;
+    }
+    let c = centroid(points);
+    let error = |axis: Vec| {
+        let (u, v) = orthonormal_basis(axis);
+        fit_circle(points, c, u, v).map_or(Vec::S::INFINITY, |(_, _, _, e)| e)
+    };
+
+    let mut best = principal_axes(points, c)
+        .iter()
+        .map(|(_, a)| *a)
+        .min_by(|a, b| error(*a).partial_cmp(&error(*b)).unwrap())
+        .unwrap();
+    let mut best_error = error(best);
+    // a coarse grid on the hemisphere
+    let steps = 12;
+    for i in 0..steps {
+        for j in 0..2 * steps {
+            let theta =
+                Vec::S::from_usize(i) / Vec::S::from_usize(steps) * Vec::S::PI * Vec::S::HALF;
+            let phi = Vec::S::from_usize(j) / Vec::S::from_usize(steps) * Vec::S::PI;
+            let axis = Vec::new(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            let e = error(axis);
+            if e < best_error {
+                (best, best_error) = (axis, e);
+            }
+        }
+    }
+
+    // refine by tilting the axis in shrinking steps
+    let mut step = Vec::S::PI / Vec::S::from_usize(steps);
+    while step > Vec::S::EPS.sqrt() * Vec::S::from(0.01) {
+        let (u, v) = orthonormal_basis(best);
+        let mut improved = false;
+        for d in [u, -u, v, -v] {
+            let axis = (best + d * step.tan()).normalize();
+            let e = error(axis);
+            if e < best_error {
+                (best, best_error, improved) = (axis, e, true);
+            }
+        }
+        if !improved {
+            step *= Vec::S::HALF;
+        }
+    }
+
+    let (u, v) = orthonormal_basis(best);
+    let (cx, cy, radius, rms) = fit_circle(points, c, u, v)?;
+    Some(FittedCylinder {
+        point: c + u * cx + v * cy,
+        axis: best,
+        radius,
+        rms,
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use super::*;
+    use crate::{extensions::nalgebra::*, math::Vector};
+
+    #[test]
+    fn test_fit_plane_and_sphere() {
+        let n = Vec3::new(1.0, 2.0, 2.0).normalize();
+        let (u, v) = orthonormal_basis(n);
+        let offset = Vec3::new(1.0, -1.0, 3.0);
+        let points = (0..20)
+            .map(|i| {
+                let (x, y) = ((i % 5) as f64, (i / 5) as f64 * 0.7);
+                offset + u * x + v * y
+            })
+            .collect::<Vec<_>>();
+        let plane = fit_plane(&points).unwrap();
+        assert!(plane.normal.dot(&n).abs().is_about(1.0, 1e-12));
+        assert!(plane.rms < 1e-12);
+        assert!(fit_plane(&points[..2]).is_none());
+        // coplanar points don't define a sphere
+        assert!(fit_sphere(&points).is_none());
+
+        // alternate the points between two parallel planes
+        let noisy = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| *p + n * if i % 2 == 0 { 0.1 } else { -0.1 })
+            .collect::<Vec<_>>();
+        // the best plane is at most as far away as the original one
+        let fit = fit_plane(&noisy).unwrap();
+        assert!(fit.rms > 0.05 && fit.rms <= 0.1 + 1e-12);
+        assert!(fit.normal.dot(&n).abs() > 0.99);
+
+        let center = Vec3::new(3.0, 1.0, -2.0);
+        let sphere = (0..30)
+            .map(|i| {
+                let (a, b) = (i as f64 * 0.7, i as f64 * 1.3);
+                center + Vec3::new(a.cos() * b.sin(), a.sin() * b.sin(), b.cos()) * 2.5
+            })
+            .collect::<Vec<_>>();
+        let fit = fit_sphere(&sphere).unwrap();
+        assert!(fit.center.is_about(&center, 1e-9));
+        assert!(fit.radius.is_about(2.5, 1e-9));
+        assert!(fit.rms < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_cylinder() {
+        let axis = Vec3::new(0.3, 1.0, -0.5).normalize();
+        let (u, v) = orthonormal_basis(axis);
+        let base = Vec3::new(1.0, 2.0, 3.0);
+        let points = (0..40)
+            .map(|i| {
+                let a = i as f64 * 0.9;
+                base + axis * ((i % 7) as f64 * 0.5) + (u * a.cos() + v * a.sin()) * 1.5
+            })
+            .collect::<Vec<_>>();
+        let fit = fit_cylinder(&points).unwrap();
+        assert!(fit.axis.dot(&axis).abs().is_about(1.0, 1e-9));
+        assert!(fit.radius.is_about(1.5, 1e-6));
+        assert!(fit.rms < 1e-6);
+        // the point lies on the axis
+        let d = fit.point - base;
+        assert!((d - axis * d.dot(&axis)).norm() < 1e-6);
+    }
+}
@@ -47,6 +47,16 @@ impl Scalar for f32 {
         f32::tan(*self)
     }
 
+    #[inline(always)]
+    fn exp(&self) -> Self {
+        f32::exp(*self)
+    }
+
+    #[inline(always)]
+    fn cbrt(&self) -> Self {
+        f32::cbrt(*self)
+    }
+
     #[inline(always)]
     fn cot(&self) -> Self {
         self.tan().recip()
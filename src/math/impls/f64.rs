@@ -47,6 +47,16 @@ impl Scalar for f64 {
         f64::tan(*self)
     }
 
+    #[inline(always)]
+    fn exp(&self) -> Self {
+        f64::exp(*self)
+    }
+
+    #[inline(always)]
+    fn cbrt(&self) -> Self {
+        f64::cbrt(*self)
+    }
+
     #[inline(always)]
     fn cot(&self) -> Self {
         self.tan().recip()
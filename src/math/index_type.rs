@@ -12,6 +12,7 @@ pub trait IndexType:
     + num_traits::Zero
 {
     /// Create a new index from a usize. Panics if the usize is out of range.
+    /// Since `max()` marks missing elements, it is out of range as well.
     fn new(x: usize) -> Self;
 
     /// Convert the index to a usize.
@@ -41,7 +42,12 @@ impl IndexType for usize {
 impl IndexType for u32 {
     #[inline(always)]
     fn new(x: usize) -> Self {
-        assert!(x <= ::std::u32::MAX as usize, "Index out of range: {}", x);
+        assert!(
+            x < ::std::u32::MAX as usize,
+            "Index {} is out of range for u32 indices (the maximum is reserved). \
+             Use a mesh type with larger indices for meshes of this size.",
+            x
+        );
         x as u32
     }
 
@@ -59,7 +65,12 @@ impl IndexType for u32 {
 impl IndexType for u16 {
     #[inline(always)]
     fn new(x: usize) -> Self {
-        assert!(x <= ::std::u16::MAX as usize, "Index out of range: {}", x);
+        assert!(
+            x < ::std::u16::MAX as usize,
+            "Index {} is out of range for u16 indices (the maximum is reserved). \
+             Use a mesh type with larger indices for meshes of this size.",
+            x
+        );
         x as u16
     }
 
@@ -77,7 +88,12 @@ impl IndexType for u16 {
 impl IndexType for u8 {
     #[inline(always)]
     fn new(x: usize) -> Self {
-        assert!(x <= ::std::u8::MAX as usize, "Index out of range: {}", x);
+        assert!(
+            x < ::std::u8::MAX as usize,
+            "Index {} is out of range for u8 indices (the maximum is reserved). \
+             Use a mesh type with larger indices for meshes of this size.",
+            x
+        );
         x as u8
     }
 
@@ -1,12 +1,17 @@
 //! Traits to define the geometric primitives and operations used in the library.
 
+mod curves;
+mod fit;
 pub mod impls;
 mod index_type;
 mod line_segment;
+mod offset;
 mod polygon;
+mod polyline;
 mod position;
 mod quaternion;
 mod scalar;
+mod skeleton;
 mod transform;
 mod transformable;
 mod vector;
@@ -15,12 +20,17 @@ mod vector3d;
 mod vector4d;
 mod zero;
 
+pub use curves::*;
+pub use fit::*;
 pub use index_type::*;
 pub use line_segment::*;
+pub use offset::*;
 pub use polygon::*;
+pub use polyline::*;
 pub use position::*;
 pub use quaternion::*;
 pub use scalar::*;
+pub use skeleton::*;
 pub use transform::*;
 pub use transformable::*;
 pub use vector::*;
@@ -28,3 +38,9 @@ pub use vector2d::*;
 pub use vector3d::*;
 pub use vector4d::*;
 pub use zero::*;
+
+#[cfg(feature = "noise")]
+mod noise;
+
+#[cfg(feature = "noise")]
+pub use noise::*;
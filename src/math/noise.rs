@@ -0,0 +1,176 @@
+use super::{Scalar, Vector3D};
+
+/// The 12 gradient directions of simplex noise, i.e., the midpoints of the edges of a cube.
+const GRADIENTS: [[f64; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+/// Hashes a lattice point to one of the gradients.
+fn gradient(i: i64, j: i64, k: i64, seed: u32) -> &'static [f64; 3] {
+    let mut h = (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (j as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (k as u64).wrapping_mul(0x1656_67B1_9E37_79F9)
+        ^ (seed as u64).wrapping_mul(0x27D4_EB2F_1656_67C5);
+    h ^= h >> 29;
+    h = h.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h ^= h >> 32;
+    &GRADIENTS[(h % 12) as usize]
+}
+
+/// Evaluates 3D simplex noise (after Ken Perlin and Stefan Gustavson) at the given point.
+/// The result is in `[-1, 1]` and changes smoothly with the position.
+/// Different seeds give uncorrelated noise fields.
+pub fn simplex_noise<V: Vector3D>(p: V, seed: u32) -> V::S {
+    let (x, y, z) = (p.x().to_f64(), p.y().to_f64(), p.z().to_f64());
+    const F3: f64 = 1.0 / 3.0;
+    const G3: f64 = 1.0 / 6.0;
+
+    // skew the input space to find the simplex cell
+    let s = (x + y + z) * F3;
+    let (i, j, k) = ((x + s).floor(), (y + s).floor(), (z + s).floor());
+    let t = (i + j + k) * G3;
+    let d0 = [x - (i - t), y - (j - t), z - (k - t)];
+
+    // find the simplex (one of six tetrahedra) containing the point
+    let (o1, o2) = if d0[0] >= d0[1] {
+        if d0[1] >= d0[2] {
+            ([1, 0, 0], [1, 1, 0])
+        } else if d0[0] >= d0[2] {
+            ([1, 0, 0], [1, 0, 1])
+        } else {
+            ([0, 0, 1], [1, 0, 1])
+        }
+    } else if d0[1] < d0[2] {
+        ([0, 0, 1], [0, 1, 1])
+    } else if d0[0] < d0[2] {
+        ([0, 1, 0], [0, 1, 1])
+    } else {
+        ([0, 1, 0], [1, 1, 0])
+    };
+
+    let (i, j, k) = (i as i64, j as i64, k as i64);
+    let corners = [[0, 0, 0], o1, o2, [1, 1, 1]];
+    let mut value = 0.0;
+    for (n, o) in corners.iter().enumerate() {
+        let g = G3 * n as f64;
+        let d = [
+            d0[0] - o[0] as f64 + g,
+            d0[1] - o[1] as f64 + g,
+            d0[2] - o[2] as f64 + g,
+        ];
+        let falloff = 0.6 - d[0] * d[0] - d[1] * d[1] - d[2] * d[2];
+        if falloff > 0.0 {
+            let grad = gradient(i + o[0], j + o[1], k + o[2], seed);
+            let dot = grad[0] * d[0] + grad[1] * d[1] + grad[2] * d[2];
+            value += falloff.powi(4) * dot;
+        }
+    }
+
+    // scale the result to approximately cover [-1, 1]
+    V::S::from((32.0 * value).clamp(-1.0, 1.0) as f32)
+}
+
+/// Fractal Brownian motion: Sums several octaves of simplex noise with increasing
+/// frequency and decreasing amplitude to create natural looking detail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fbm<S: Scalar> {
+    /// Number of noise layers.
+    pub octaves: usize,
+
+    /// Frequency of the first octave.
+    pub frequency: S,
+
+    /// Factor by which the frequency increases with each octave.
+    pub lacunarity: S,
+
+    /// Factor by which the amplitude decreases with each octave.
+    pub persistence: S,
+
+    /// Seed of the first octave. Each octave uses a different seed.
+    pub seed: u32,
+}
+
+impl<S: Scalar> Default for Fbm<S> {
+    fn default() -> Self {
+        Self {
+            octaves: 4,
+            frequency: S::ONE,
+            lacunarity: S::TWO,
+            persistence: S::HALF,
+            seed: 0,
+        }
+    }
+}
+
+impl<S: Scalar> Fbm<S> {
+    /// Samples the noise at the given point. The result is normalized to `[-1, 1]`.
+    pub fn sample<V: Vector3D<S = S>>(&self, p: V) -> S {
+        let mut frequency = self.frequency;
+        let mut amplitude = S::ONE;
+        let mut sum = S::ZERO;
+        let mut total = S::ZERO;
+        for octave in 0..self.octaves {
+            let seed = self.seed.wrapping_add(octave as u32);
+            sum += simplex_noise(p * frequency, seed) * amplitude;
+            total += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.persistence;
+        }
+        if total > S::ZERO {
+            sum / total
+        } else {
+            S::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_simplex_noise() {
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for i in 0..1000 {
+            let t = i as f64 * 0.137;
+            let p = Vec3::new(t, (t * 1.3).sin() * 5.0, t * 0.71);
+            let v = simplex_noise(p, 7);
+            min = min.min(v);
+            max = max.max(v);
+
+            // continuous
+            let q = p + Vec3::new(1e-4, 0.0, 0.0);
+            assert!((simplex_noise(q, 7) - v).abs() < 1e-2);
+            // deterministic
+            assert_eq!(simplex_noise(p, 7), v);
+        }
+        assert!(min >= -1.0 && max <= 1.0);
+        assert!(min < -0.3 && max > 0.3);
+        assert_ne!(
+            simplex_noise(Vec3::new(0.3, 0.2, 0.1), 1),
+            simplex_noise(Vec3::new(0.3, 0.2, 0.1), 2)
+        );
+    }
+
+    #[test]
+    fn test_fbm() {
+        let fbm = Fbm::<f64>::default();
+        for i in 0..100 {
+            let v = fbm.sample(Vec3::new(i as f64 * 0.31, 0.5, -0.2));
+            assert!((-1.0..=1.0).contains(&v));
+        }
+    }
+}
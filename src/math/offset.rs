@@ -0,0 +1,219 @@
+//! Offsetting of 2d polygons.
+
+use super::{Scalar, Vector2D};
+
+/// How the offset edges are connected where the offset leaves a gap at a corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OffsetJoin<S: Scalar> {
+    /// Extend the edges until they meet. Corners whose miter would be more than `limit`
+    /// times the offset distance away from the original corner are beveled instead.
+    Miter(S),
+
+    /// Connect the edges with a circular arc around the original corner.
+    /// The arcs use the given number of segments per full turn.
+    Round(usize),
+
+    /// Connect the edges with a straight line.
+    Bevel,
+}
+
+impl<S: Scalar> Default for OffsetJoin<S> {
+    fn default() -> Self {
+        // the default miter limit of SVG
+        OffsetJoin::Miter(S::FOUR)
+    }
+}
+
+/// Rotates the vector `v` by `angle` radians counter-clockwise.
+fn rotate<Vec2: Vector2D>(v: Vec2, angle: Vec2::S) -> Vec2 {
+    let (s, c) = (angle.sin(), angle.cos());
+    Vec2::new(v.x() * c - v.y() * s, v.x() * s + v.y() * c)
+}
+
+/// Moves the edges of the closed polygon `points` by `distance` to their right, i.e., a
+/// counter-clockwise polygon grows for positive distances and shrinks for negative ones
+/// while a clockwise polygon, e.g., a hole, does the opposite.
+///
+/// Where the moved edges leave a gap at a corner, they are connected according to `join`.
+/// Elsewhere, they are cut at their intersection. The result isn't cleaned up, i.e., it
+/// can intersect itself where the distance is larger than the features of the polygon.
+/// Returns an empty polygon if all edges of the shrunk polygon vanish or turn around.
+pub fn offset_polygon<Vec2: Vector2D>(
+    points: &[Vec2],
+    distance: Vec2::S,
+    join: OffsetJoin<Vec2::S>,
+) -> Vec<Vec2> {
+    // remove duplicate points
+    let mut ps: Vec<Vec2> = Vec::with_capacity(points.len());
+    for p in points {
+        if ps
+            .last()
+            .map_or(true, |q: &Vec2| q.distance_squared(p) > Vec2::S::EPS)
+        {
+            ps.push(*p);
+        }
+    }
+    while ps.len() > 1 && ps[0].distance_squared(ps.last().unwrap()) <= Vec2::S::EPS {
+        ps.pop();
+    }
+    let n = ps.len();
+    if n < 3 {
+        return Vec::new();
+    }
+
+    let signed_area = |ps: &[Vec2]| {
+        Vec2::S::stable_sum(
+            (0..ps.len()).map(|i| ps[i].perp_dot(&ps[(i + 1) % ps.len()]) * Vec2::S::HALF),
+        )
+    };
+    let area = signed_area(&ps);
+    if distance.abs() <= Vec2::S::EPS {
+        return ps;
+    }
+
+    let normal = |i: usize| {
+        let e = (ps[(i + 1) % n] - ps[i]).normalize();
+        Vec2::new(e.y(), -e.x())
+    };
+    let mut res = Vec::with_capacity(n);
+    // the range of points generated for each corner
+    let mut ranges = Vec::with_capacity(n);
+    for (i, p) in ps.iter().copied().enumerate() {
+        let start = res.len();
+        let (n0, n1) = (normal((i + n - 1) % n), normal(i));
+        let cos = n0.dot(&n1);
+        let a = p + n0 * distance;
+        let b = p + n1 * distance;
+        let gap = n0.perp_dot(&n1) * distance > Vec2::S::ZERO;
+        let miter = |res: &mut Vec<Vec2>| {
+            if cos > Vec2::S::EPS - Vec2::S::ONE {
+                res.push(p + (n0 + n1) * (distance / (Vec2::S::ONE + cos)));
+            } else {
+                // the polygon turns back on itself
+                res.push(a);
+                res.push(b);
+            }
+        };
+        if !gap || cos >= Vec2::S::ONE - Vec2::S::EPS {
+            miter(&mut res);
+            ranges.push((start, res.len() - 1));
+            continue;
+        }
+        match join {
+            OffsetJoin::Miter(limit) => {
+                // the miter length relative to the distance is 1 / cos(angle / 2)
+                if Vec2::S::TWO <= limit * limit * (Vec2::S::ONE + cos) {
+                    miter(&mut res);
+                } else {
+                    res.push(a);
+                    res.push(b);
+                }
+            }
+            OffsetJoin::Bevel => {
+                res.push(a);
+                res.push(b);
+            }
+            OffsetJoin::Round(segments) => {
+                assert!(segments >= 3, "round joins need at least 3 segments");
+                let angle = n0.perp_dot(&n1).atan2(cos);
+                let step = Vec2::S::TWO * Vec2::S::PI / Vec2::S::from_usize(segments);
+                let k = ((angle.abs() / step).to_f64().ceil() as usize).max(1);
+                for j in 0..=k {
+                    let t = angle * Vec2::S::from_usize(j) / Vec2::S::from_usize(k);
+                    res.push(p + rotate(n0, t) * distance);
+                }
+            }
+        }
+        ranges.push((start, res.len() - 1));
+    }
+
+    // if every edge turned around, the polygon collapsed
+    let collapsed = (0..n).all(|i| {
+        let (a, b) = (res[ranges[i].1], res[ranges[(i + 1) % n].0]);
+        (b - a).dot(&(ps[(i + 1) % n] - ps[i])) <= Vec2::S::ZERO
+    });
+    if collapsed {
+        return Vec::new();
+    }
+
+    let new_area = signed_area(&res);
+    if new_area.abs() <= Vec2::S::EPS || new_area.is_positive() != area.is_positive() {
+        return Vec::new();
+    }
+    res
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use super::*;
+    use crate::{extensions::nalgebra::*, math::Vector};
+
+    fn square() -> Vec<Vec2<f64>> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ]
+    }
+
+    fn area(ps: &[Vec2<f64>]) -> f64 {
+        (0..ps.len())
+            .map(|i| ps[i].perp_dot(&ps[(i + 1) % ps.len()]) * 0.5)
+            .sum()
+    }
+
+    #[test]
+    fn test_offset_joins() {
+        let miter = offset_polygon(&square(), 0.5, OffsetJoin::Miter(2.0));
+        assert_eq!(miter.len(), 4);
+        assert!(miter[0].is_about(&Vec2::new(-0.5, -0.5), 1e-12));
+        assert!(area(&miter).is_about(4.0, 1e-12));
+
+        // the miter of a right angle is sqrt(2) times the distance away
+        let limited = offset_polygon(&square(), 0.5, OffsetJoin::Miter(1.4));
+        assert_eq!(limited.len(), 8);
+        let bevel = offset_polygon(&square(), 0.5, OffsetJoin::Bevel);
+        assert_eq!(bevel, limited);
+        assert!(area(&bevel).is_about(4.0 - 4.0 * 0.125, 1e-12));
+
+        // round joins approach the area of the rounded square
+        let round = offset_polygon(&square(), 0.5, OffsetJoin::Round(256));
+        assert_eq!(round.len(), 4 * (64 + 1));
+        let expected = 1.0 + 4.0 * 0.5 + std::f64::consts::PI * 0.25;
+        assert!((area(&round) - expected).abs() < 1e-3);
+        assert!(round.iter().all(|p| {
+            let q = Vec2::new(p.x.clamp(0.0, 1.0), p.y.clamp(0.0, 1.0));
+            p.distance(&q).is_about(0.5, 1e-12)
+        }));
+    }
+
+    #[test]
+    fn test_offset_inset() {
+        // shrinking doesn't need joins at convex corners
+        let inset = offset_polygon(&square(), -0.25, OffsetJoin::Round(16));
+        assert_eq!(inset.len(), 4);
+        assert!(area(&inset).is_about(0.25, 1e-12));
+
+        // a clockwise hole shrinks when growing
+        let hole = square().into_iter().rev().collect::<Vec<_>>();
+        let grown = offset_polygon(&hole, 0.25, OffsetJoin::Bevel);
+        assert!(area(&grown).is_about(-0.25, 1e-12));
+
+        // the concave corner of an L-shape gets a join when shrinking
+        let l = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ];
+        assert_eq!(offset_polygon(&l, -0.25, OffsetJoin::Bevel).len(), 7);
+        assert_eq!(offset_polygon(&l, 0.25, OffsetJoin::Bevel).len(), 11);
+
+        // too much shrinking makes it vanish
+        assert!(offset_polygon(&square(), -0.6, OffsetJoin::Bevel).is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+//! Arc-length based utilities for polylines in nd space.
+
+use super::{Scalar, Vector};
+
+/// Returns the segments of the polyline as pairs of points.
+/// If `closed` is true, the last point is connected with the first one.
+fn segments<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+    points: &[VecN],
+    closed: bool,
+) -> impl Iterator<Item = (VecN, VecN)> + '_ {
+    let n = points.len();
+    let m = if closed && n > 1 {
+        n
+    } else {
+        n.saturating_sub(1)
+    };
+    (0..m).map(move |i| (points[i], points[(i + 1) % n]))
+}
+
+/// Returns the total length of the polyline.
+pub fn polyline_length<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+    points: &[VecN],
+    closed: bool,
+) -> S {
+    S::stable_sum(segments(points, closed).map(|(a, b)| a.distance(&b)))
+}
+
+/// Returns the cumulative arc length at each vertex of the polyline, starting with zero.
+/// When `closed` is true, the last entry is the length of the whole loop, i.e.,
+/// the arc length when returning to the first vertex.
+pub fn arc_lengths<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+    points: &[VecN],
+    closed: bool,
+) -> Vec<S> {
+    let mut res = Vec::with_capacity(points.len() + 1);
+    if points.is_empty() {
+        return res;
+    }
+    let mut acc = S::ZERO;
+    res.push(acc);
+    for (a, b) in segments(points, closed) {
+        acc += a.distance(&b);
+        res.push(acc);
+    }
+    res
+}
+
+/// Returns the point at arc length `s` along the polyline.
+/// `s` is clamped to the length of the polyline.
+pub fn point_at_arc_length<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+    points: &[VecN],
+    closed: bool,
+    s: S,
+) -> VecN {
+    assert!(!points.is_empty(), "the polyline must not be empty");
+    let lengths = arc_lengths(points, closed);
+    sample(points, &lengths, s)
+}
+
+fn sample<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+    points: &[VecN],
+    lengths: &[S],
+    s: S,
+) -> VecN {
+    if lengths.len() < 2 {
+        return points[0];
+    }
+    let n = points.len();
+    let total = *lengths.last().unwrap();
+    let s = s.clamp(S::ZERO, total);
+    // find the segment containing s
+    let i = match lengths.partition_point(|&l| l <= s) {
+        0 => 0,
+        k => (k - 1).min(lengths.len() - 2),
+    };
+    let len = lengths[i + 1] - lengths[i];
+    if len <= S::ZERO {
+        return points[i];
+    }
+    let (a, b) = (points[i], points[(i + 1) % n]);
+    a + (b - a) * ((s - lengths[i]) / len)
+}
+
+/// Resamples the polyline with `n` points that are evenly spaced by arc length.
+///
+/// For open polylines the first and last point are kept.
+/// For closed polylines the first point is kept and the spacing includes the closing segment.
+pub fn resample_even<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+    points: &[VecN],
+    n: usize,
+    closed: bool,
+) -> Vec<VecN> {
+    if points.is_empty() || n == 0 {
+        return Vec::new();
+    }
+    if n == 1 {
+        return vec![points[0]];
+    }
+    let lengths = arc_lengths(points, closed);
+    let total = *lengths.last().unwrap();
+    let steps = if closed { n } else { n - 1 };
+    let step = total / S::from_usize(steps);
+    (0..n)
+        .map(|i| {
+            if !closed && i == n - 1 {
+                points[points.len() - 1]
+            } else {
+                sample(points, &lengths, step * S::from_usize(i))
+            }
+        })
+        .collect()
+}
+
+/// Returns the distance of `p` to the line segment from `a` to `b`.
+pub(crate) fn distance_to_segment<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+    p: VecN,
+    a: VecN,
+    b: VecN,
+) -> S {
+    let ab = b - a;
+    let len2 = ab.length_squared();
+    if len2 <= S::ZERO {
+        return p.distance(&a);
+    }
+    let t = ((p - a).dot(&ab) / len2).clamp(S::ZERO, S::ONE);
+    p.distance(&(a + ab * t))
+}
+
+/// Resamples the polyline adaptively: Vertices are removed where the polyline is
+/// almost straight and kept where it is curved, such that the result deviates
+/// at most `tol` from the original (Ramer-Douglas-Peucker).
+///
+/// For closed polylines the first vertex is always kept.
+pub fn resample_adaptive<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+    points: &[VecN],
+    tol: S,
+    closed: bool,
+) -> Vec<VecN> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    fn simplify<S: Scalar, const D: usize, VecN: Vector<S, D>>(
+        points: &[VecN],
+        tol: S,
+        keep: &mut [bool],
+        offset: usize,
+    ) {
+        let n = points.len();
+        if n < 3 {
+            return;
+        }
+        let (a, b) = (points[0], points[n - 1]);
+        let (i, d) = (1..n - 1)
+            .map(|i| (i, distance_to_segment(points[i], a, b)))
+            .fold(
+                (0, S::NEG_INFINITY),
+                |acc, x| if x.1 > acc.1 { x } else { acc },
+            );
+        if d > tol {
+            keep[offset + i] = true;
+            simplify(&points[..=i], tol, keep, offset);
+            simplify(&points[i..], tol, keep, offset + i);
+        }
+    }
+
+    let mut pts = points.to_vec();
+    if closed {
+        pts.push(points[0]);
+    }
+    let mut keep = vec![false; pts.len()];
+    keep[0] = true;
+    let last = pts.len() - 1;
+    keep[last] = true;
+    if closed {
+        // split the loop at the vertex farthest from the first one so it doesn't collapse
+        let far = (1..last)
+            .max_by(|&i, &j| {
+                pts[i]
+                    .distance_squared(&pts[0])
+                    .partial_cmp(&pts[j].distance_squared(&pts[0]))
+                    .unwrap()
+            })
+            .unwrap();
+        keep[far] = true;
+        simplify(&pts[..=far], tol, &mut keep, 0);
+        simplify(&pts[far..], tol, &mut keep, far);
+    } else {
+        simplify(&pts, tol, &mut keep, 0);
+    }
+
+    let mut res: Vec<VecN> = pts
+        .iter()
+        .zip(keep.iter())
+        .filter_map(|(p, &k)| if k { Some(*p) } else { None })
+        .collect();
+    if closed {
+        res.pop();
+    }
+    res
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use super::*;
+    use crate::extensions::nalgebra::*;
+
+    #[test]
+    fn test_resample_even() {
+        let line = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 3.0),
+        ];
+        assert!(polyline_length(&line, false).is_about(4.0, 1e-12));
+        let res = resample_even(&line, 5, false);
+        assert_eq!(res.len(), 5);
+        assert!(res[0].is_about(&Vec2::new(0.0, 0.0), 1e-12));
+        assert!(res[1].is_about(&Vec2::new(1.0, 0.0), 1e-12));
+        assert!(res[2].is_about(&Vec2::new(1.0, 1.0), 1e-12));
+        assert!(res[4].is_about(&Vec2::new(1.0, 3.0), 1e-12));
+
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ];
+        let res = resample_even(&square, 8, true);
+        assert_eq!(res.len(), 8);
+        for i in 0..8 {
+            let d = res[i].distance(&res[(i + 1) % 8]);
+            assert!(d <= 0.5 + 1e-12);
+        }
+        assert!(res[3].is_about(&Vec2::new(1.0, 0.5), 1e-12));
+    }
+
+    #[test]
+    fn test_resample_adaptive() {
+        // a straight line with many collinear points collapses to its end points
+        let line: Vec<Vec2<f64>> = (0..10).map(|i| Vec2::new(i as f64, 0.0)).collect();
+        let res = resample_adaptive(&line, 1e-6, false);
+        assert_eq!(res, vec![Vec2::new(0.0, 0.0), Vec2::new(9.0, 0.0)]);
+
+        // a dense circle keeps more points for smaller tolerances
+        let circle: Vec<Vec2<f64>> = (0..256)
+            .map(|i| {
+                let a = i as f64 / 256.0 * std::f64::consts::TAU;
+                Vec2::new(a.cos(), a.sin())
+            })
+            .collect();
+        let coarse = resample_adaptive(&circle, 0.1, true);
+        let fine = resample_adaptive(&circle, 0.001, true);
+        assert!(coarse.len() >= 4);
+        assert!(coarse.len() < fine.len());
+        assert!(fine.len() < circle.len());
+    }
+}
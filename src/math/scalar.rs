@@ -75,6 +75,13 @@ pub trait Scalar:
     /// Converts a usize to the scalar.
     fn from_usize(value: usize) -> Self;
 
+    /// Converts a 64-bit floating point number to the scalar. The default implementation
+    /// goes through two `f32`s to keep more precision than a single one.
+    fn from_f64(value: f64) -> Self {
+        let hi = value as f32;
+        Self::from(hi) + Self::from((value - hi as f64) as f32)
+    }
+
     /// Returns the absolute value of the scalar.
     fn abs(self) -> Self {
         if self.is_positive() {
@@ -102,6 +109,12 @@ pub trait Scalar:
     /// Returns the atan2 of the scalar.
     fn atan2(&self, x: Self) -> Self;
 
+    /// Returns the exponential function of the scalar.
+    fn exp(&self) -> Self;
+
+    /// Returns the cube root of the scalar.
+    fn cbrt(&self) -> Self;
+
     /// Returns the maximum of two scalars.
     fn max(&self, b: Self) -> Self;
 
@@ -199,6 +212,11 @@ impl<S: Scalar> OrderedFloats<S> {
     pub fn new(value: S) -> Self {
         OrderedFloats { value }
     }
+
+    /// Returns the wrapped value.
+    pub fn value(&self) -> S {
+        self.value
+    }
 }
 
 impl<S: Scalar> std::cmp::Eq for OrderedFloats<S> {}
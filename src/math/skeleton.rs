@@ -0,0 +1,482 @@
+//! Straight skeletons of 2d polygons with holes.
+
+use super::{Scalar, Vector, Vector2D};
+
+/// The straight skeleton of a set of polygons.
+///
+/// It is traced by the vertices of the polygons while all edges move inwards with unit
+/// speed, i.e., the time a node is reached equals its distance to the edges whose faces meet
+/// there. Lifting the nodes by their time yields a roof with a pitch of 45 degrees.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StraightSkeleton<Vec2: Vector2D> {
+    /// The positions of the nodes and the times they were reached.
+    /// The first nodes are the vertices of the input polygons in order.
+    pub nodes: Vec<(Vec2, Vec2::S)>,
+
+    /// The arcs of the skeleton as pairs of node indices, not including the input edges.
+    pub arcs: Vec<(usize, usize)>,
+
+    /// The face swept by each input edge as a ccw cycle of node indices.
+    /// Starts with the two vertices of the edge. Edges are numbered like their first vertex.
+    pub faces: Vec<Vec<usize>>,
+}
+
+/// A vertex of the moving wavefront.
+#[derive(Clone, Debug)]
+struct Wave<Vec2: Vector2D> {
+    /// The position at `time`.
+    pos: Vec2,
+    time: Vec2::S,
+    vel: Vec2,
+    /// The node where the vertex started.
+    node: usize,
+    /// The edge ending at the vertex.
+    left: usize,
+    /// The edge starting at the vertex.
+    right: usize,
+    prev: usize,
+    next: usize,
+    active: bool,
+}
+
+impl<Vec2: Vector2D> Wave<Vec2> {
+    fn at(&self, t: Vec2::S) -> Vec2 {
+        self.pos + self.vel * (t - self.time)
+    }
+}
+
+/// The next event of the simulation.
+enum Event {
+    /// The edge starting at the vertex collapses.
+    Edge(usize),
+    /// The reflex vertex splits the edge starting at the other vertex.
+    Split(usize, usize),
+}
+
+struct Skeleton<Vec2: Vector2D> {
+    /// The start and the inward normal of each edge.
+    edges: Vec<(Vec2, Vec2)>,
+    waves: Vec<Wave<Vec2>>,
+    nodes: Vec<(Vec2, Vec2::S)>,
+    arcs: Vec<(usize, usize)>,
+    /// The arcs bounding the face of each edge.
+    face_arcs: Vec<Vec<(usize, usize)>>,
+    eps: Vec2::S,
+}
+
+impl<Vec2: Vector2D> Skeleton<Vec2> {
+    fn dir(&self, e: usize) -> Vec2 {
+        let n = self.edges[e].1;
+        Vec2::new(n.y(), -n.x())
+    }
+
+    /// The velocity of a vertex between the two edges.
+    fn velocity(&self, left: usize, right: usize) -> Vec2 {
+        let (a, b) = (self.edges[left].1, self.edges[right].1);
+        let det = a.perp_dot(&b);
+        if det.abs() > Vec2::S::EPS {
+            Vec2::new((b.y() - a.y()) / det, (a.x() - b.x()) / det)
+        } else if a.dot(&b) > Vec2::S::ZERO {
+            a
+        } else {
+            // the wavefronts of the edges overlap and will vanish immediately
+            <Vec2 as Vector<Vec2::S, 2>>::zero()
+        }
+    }
+
+    fn add_wave(
+        &mut self,
+        pos: Vec2,
+        time: Vec2::S,
+        node: usize,
+        left: usize,
+        right: usize,
+    ) -> usize {
+        let vel = self.velocity(left, right);
+        self.waves.push(Wave {
+            pos,
+            time,
+            vel,
+            node,
+            left,
+            right,
+            prev: usize::MAX,
+            next: usize::MAX,
+            active: true,
+        });
+        self.waves.len() - 1
+    }
+
+    fn link(&mut self, a: usize, b: usize) {
+        self.waves[a].next = b;
+        self.waves[b].prev = a;
+    }
+
+    /// Returns the node at the given position and time, reusing coinciding nodes.
+    fn add_node(&mut self, pos: Vec2, time: Vec2::S) -> usize {
+        if let Some(i) = self
+            .nodes
+            .iter()
+            .position(|(p, t)| p.distance(&pos) <= self.eps && (*t - time).abs() <= self.eps)
+        {
+            return i;
+        }
+        self.nodes.push((pos, time));
+        self.nodes.len() - 1
+    }
+
+    /// Adds an arc on the boundary of the faces of the two edges.
+    fn add_arc(&mut self, a: usize, b: usize, e1: usize, e2: usize) {
+        if a == b {
+            return;
+        }
+        self.arcs.push((a, b));
+        self.face_arcs[e1].push((a, b));
+        if e1 != e2 {
+            self.face_arcs[e2].push((a, b));
+        }
+    }
+
+    /// Ends the wave at the node.
+    fn finish(&mut self, w: usize, node: usize) {
+        let (from, left, right) = (self.waves[w].node, self.waves[w].left, self.waves[w].right);
+        self.add_arc(from, node, left, right);
+        self.waves[w].active = false;
+    }
+
+    fn loop_of(&self, w: usize) -> Vec<usize> {
+        let mut res = vec![w];
+        let mut cur = self.waves[w].next;
+        while cur != w {
+            res.push(cur);
+            cur = self.waves[cur].next;
+        }
+        res
+    }
+
+    /// Collapses loops that can't enclose any area anymore.
+    fn cleanup(&mut self, w: usize) {
+        let lp = self.loop_of(w);
+        if lp.len() >= 3 {
+            return;
+        }
+        if lp.len() == 2 {
+            let (a, b) = (lp[0], lp[1]);
+            let (wa, wb) = (&self.waves[a], &self.waves[b]);
+            let (na, nb, ea, eb) = (wa.node, wb.node, wa.right, wb.right);
+            self.add_arc(na, nb, ea, eb);
+        }
+        for v in lp {
+            self.waves[v].active = false;
+        }
+    }
+
+    /// The time when the edge starting at `u` collapses.
+    fn edge_event(&self, u: usize, now: Vec2::S) -> Option<Vec2::S> {
+        let wu = &self.waves[u];
+        let ww = &self.waves[wu.next];
+        let d = self.dir(wu.right);
+        let a = (wu.pos - wu.vel * wu.time - ww.pos + ww.vel * ww.time).dot(&d);
+        let b = (wu.vel - ww.vel).dot(&d);
+        if b <= Vec2::S::EPS {
+            return None;
+        }
+        let t = -a / b;
+        (t >= now - self.eps).then(|| t.max(now))
+    }
+
+    /// The time when the reflex vertex `v` hits the edge starting at `u`.
+    fn split_event(&self, v: usize, u: usize, now: Vec2::S) -> Option<Vec2::S> {
+        let wv = &self.waves[v];
+        let wu = &self.waves[u];
+        let e = wu.right;
+        if u == v || wu.next == v || e == wv.left || e == wv.right {
+            return None;
+        }
+        let (o, n) = self.edges[e];
+        let denom = Vec2::S::ONE - wv.vel.dot(&n);
+        if denom <= Vec2::S::EPS {
+            return None;
+        }
+        let t = ((wv.pos - o).dot(&n) - wv.vel.dot(&n) * wv.time) / denom;
+        if t < now - self.eps || t < wv.time - self.eps {
+            return None;
+        }
+        let t = t.max(now);
+        // the hit point must lie within the current extent of the edge
+        let d = self.dir(e);
+        let x = (wv.at(t) - o).dot(&d);
+        let a = (wu.at(t) - o).dot(&d);
+        let b = (self.waves[wu.next].at(t) - o).dot(&d);
+        (a - self.eps <= x && x <= b + self.eps).then_some(t)
+    }
+
+    fn is_reflex(&self, v: usize) -> bool {
+        let w = &self.waves[v];
+        self.dir(w.left).perp_dot(&self.dir(w.right)) < -Vec2::S::EPS
+    }
+
+    fn next_event(&self, now: Vec2::S) -> Option<(Vec2::S, Event)> {
+        let mut best: Option<(Vec2::S, Event)> = None;
+        let active = (0..self.waves.len())
+            .filter(|w| self.waves[*w].active)
+            .collect::<Vec<_>>();
+        for &u in &active {
+            if let Some(t) = self.edge_event(u, now) {
+                if best.as_ref().map_or(true, |(b, _)| t < *b) {
+                    best = Some((t, Event::Edge(u)));
+                }
+            }
+        }
+        for &v in active.iter().filter(|v| self.is_reflex(**v)) {
+            for &u in &active {
+                if let Some(t) = self.split_event(v, u, now) {
+                    // prefer edge events at the same time
+                    if best.as_ref().map_or(true, |(b, _)| t < *b - self.eps) {
+                        best = Some((t, Event::Split(v, u)));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    fn handle_edge(&mut self, u: usize, t: Vec2::S) {
+        let w = self.waves[u].next;
+        let x = (self.waves[u].at(t) + self.waves[w].at(t)) * Vec2::S::HALF;
+        let node = self.add_node(x, t);
+        let lp = self.loop_of(u);
+        if lp.len() <= 3 {
+            // the last triangle collapses into a single point
+            for v in lp {
+                self.finish(v, node);
+            }
+            return;
+        }
+        let (prev, next) = (self.waves[u].prev, self.waves[w].next);
+        let (left, right) = (self.waves[u].left, self.waves[w].right);
+        self.finish(u, node);
+        self.finish(w, node);
+        let z = self.add_wave(x, t, node, left, right);
+        self.link(prev, z);
+        self.link(z, next);
+    }
+
+    fn handle_split(&mut self, v: usize, u: usize, t: Vec2::S) {
+        let x = self.waves[v].at(t);
+        let node = self.add_node(x, t);
+        let e = self.waves[u].right;
+        let b = self.waves[u].next;
+        let (prev, next) = (self.waves[v].prev, self.waves[v].next);
+        let (left, right) = (self.waves[v].left, self.waves[v].right);
+        self.finish(v, node);
+        let v1 = self.add_wave(x, t, node, left, e);
+        let v2 = self.add_wave(x, t, node, e, right);
+        self.link(prev, v1);
+        self.link(v1, b);
+        self.link(u, v2);
+        self.link(v2, next);
+        self.cleanup(v1);
+        self.cleanup(v2);
+    }
+
+    /// Walks along the arcs of the face of the edge from its end back to its start.
+    fn face(&self, e: usize, start: usize, end: usize) -> Vec<usize> {
+        let arcs = &self.face_arcs[e];
+        let mut used = vec![false; arcs.len()];
+        let mut res = vec![start, end];
+        let mut cur = end;
+        while let Some(i) =
+            (0..arcs.len()).find(|i| !used[*i] && (arcs[*i].0 == cur || arcs[*i].1 == cur))
+        {
+            used[i] = true;
+            cur = if arcs[i].0 == cur {
+                arcs[i].1
+            } else {
+                arcs[i].0
+            };
+            if cur == start {
+                break;
+            }
+            res.push(cur);
+        }
+        res
+    }
+}
+
+/// Computes the straight skeleton of the polygons given by the closed `rings`.
+///
+/// The interior must be on the left of every ring, i.e., outlines are counter-clockwise
+/// and holes are clockwise. Rings must not intersect each other or themselves and
+/// consecutive vertices must be distinct.
+///
+/// The skeleton is found by simulating the shrinking polygon event by event. Each step
+/// checks all pairs of vertices and edges, so this is meant for modestly sized polygons
+/// such as building footprints.
+pub fn straight_skeleton<Vec2: Vector2D>(rings: &[Vec<Vec2>]) -> StraightSkeleton<Vec2> {
+    let points = rings.iter().flatten().copied().collect::<Vec<_>>();
+    let size = points
+        .iter()
+        .map(|p| p.x().abs().max(p.y().abs()))
+        .fold(Vec2::S::ONE, |a, b| a.max(b));
+    let mut s = Skeleton {
+        edges: Vec::with_capacity(points.len()),
+        waves: Vec::with_capacity(points.len() * 2),
+        nodes: points.iter().map(|p| (*p, Vec2::S::ZERO)).collect(),
+        arcs: Vec::new(),
+        face_arcs: vec![Vec::new(); points.len()],
+        eps: Vec2::S::EPS.sqrt() * size,
+    };
+
+    let mut offset = 0;
+    for ring in rings {
+        let n = ring.len();
+        assert!(n >= 3, "each ring needs at least 3 vertices");
+        for (i, p) in ring.iter().enumerate() {
+            let d = (ring[(i + 1) % n] - *p).normalize();
+            s.edges.push((*p, Vec2::new(-d.y(), d.x())));
+        }
+        for (i, p) in ring.iter().enumerate() {
+            let left = offset + (i + n - 1) % n;
+            s.add_wave(*p, Vec2::S::ZERO, offset + i, left, offset + i);
+        }
+        for i in 0..n {
+            s.link(offset + i, offset + (i + 1) % n);
+        }
+        offset += n;
+    }
+
+    let mut now = Vec2::S::ZERO;
+    while let Some((t, event)) = s.next_event(now) {
+        now = t;
+        match event {
+            Event::Edge(u) => s.handle_edge(u, t),
+            Event::Split(v, u) => s.handle_split(v, u, t),
+        }
+    }
+
+    // should only happen due to numerical problems: collapse the remaining loops
+    for w in 0..s.waves.len() {
+        if s.waves[w].active {
+            let lp = s.loop_of(w);
+            let c = Vec2::S::stable_mean(lp.iter().map(|v| s.waves[*v].at(now).x()));
+            let d = Vec2::S::stable_mean(lp.iter().map(|v| s.waves[*v].at(now).y()));
+            let node = s.add_node(Vec2::new(c, d), now);
+            for v in lp {
+                s.finish(v, node);
+            }
+        }
+    }
+
+    let mut offset = 0;
+    let mut faces = Vec::with_capacity(points.len());
+    for ring in rings {
+        let n = ring.len();
+        for i in 0..n {
+            faces.push(s.face(offset + i, offset + i, offset + (i + 1) % n));
+        }
+        offset += n;
+    }
+
+    StraightSkeleton {
+        nodes: s.nodes,
+        arcs: s.arcs,
+        faces,
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use super::*;
+    use crate::extensions::nalgebra::*;
+
+    fn area(ps: &[Vec2<f64>]) -> f64 {
+        (0..ps.len())
+            .map(|i| ps[i].perp_dot(&ps[(i + 1) % ps.len()]) * 0.5)
+            .sum()
+    }
+
+    fn face_area(s: &StraightSkeleton<Vec2<f64>>, f: &[usize]) -> f64 {
+        area(&f.iter().map(|i| s.nodes[*i].0).collect::<Vec<_>>())
+    }
+
+    /// Checks that the faces tile the polygons.
+    fn check(rings: &[Vec<Vec2<f64>>]) -> StraightSkeleton<Vec2<f64>> {
+        let s = straight_skeleton(rings);
+        let total = rings.iter().map(|r| area(r)).sum::<f64>();
+        let faces = s.faces.iter().map(|f| face_area(&s, f)).collect::<Vec<_>>();
+        assert!(faces.iter().all(|a| *a >= -1e-9), "{:?}", faces);
+        assert!(faces.iter().sum::<f64>().is_about(total, 1e-9));
+        s
+    }
+
+    #[test]
+    fn test_skeleton_rectangle() {
+        let rect = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ];
+        let s = check(&[rect]);
+        // four corners and the two ends of the ridge
+        assert_eq!(s.nodes.len(), 6);
+        assert_eq!(s.arcs.len(), 5);
+        assert_eq!(s.faces[0].len(), 4);
+        assert_eq!(s.faces[1].len(), 3);
+        for (p, t) in &s.nodes[4..] {
+            assert!(p.y.is_about(0.5, 1e-12));
+            assert!(t.is_about(0.5, 1e-12));
+        }
+    }
+
+    #[test]
+    fn test_skeleton_reflex() {
+        // the reflex corner of the L-shape splits the wavefront
+        let l = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(3.0, 0.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 3.0),
+            Vec2::new(0.0, 3.0),
+        ];
+        let s = check(&[l]);
+        let max = s.nodes.iter().map(|(_, t)| *t).fold(0.0, f64::max);
+        assert!(max.is_about(0.5, 1e-12));
+
+        // a T-shape needs a split event
+        let t = vec![
+            Vec2::new(1.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(3.0, 2.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(0.0, 3.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 2.0),
+        ];
+        check(&[t]);
+    }
+
+    #[test]
+    fn test_skeleton_hole() {
+        let outer = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ];
+        let hole = vec![
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 3.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(3.0, 1.0),
+        ];
+        let s = check(&[outer, hole]);
+        // the ring between outline and hole has a constant width
+        let max = s.nodes.iter().map(|(_, t)| *t).fold(0.0, f64::max);
+        assert!(max.is_about(0.5, 1e-12));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    math::{Scalar, Transformable, Vector},
+    math::{resample_even, Scalar, Transformable, Vector},
     mesh::{EdgeBasics, EuclideanMeshType, VertexBasics},
 };
 
@@ -82,6 +82,20 @@ pub trait CurvedEdge<const D: usize, T: EuclideanMeshType<D, Edge = Self>>: Edge
             .collect();
     }
 
+    /// Converts the curved edge to a sequence of `n` line segments of equal arc length.
+    /// Like `flatten_uniform`, this only returns the inner points.
+    fn flatten_even(&self, n: usize, mesh: &T::Mesh) -> Vec<T::Vec> {
+        assert!(n > 0);
+        // approximate the arc length using a dense uniform flattening
+        let mut dense = vec![self.origin(mesh).pos()];
+        dense.extend(self.flatten_uniform(16 * n, mesh));
+        dense.push(self.target(mesh).pos());
+        let mut res = resample_even(&dense, n + 1, false);
+        res.pop();
+        res.remove(0);
+        res
+    }
+
     /// Converts the curved edge to a sequence of line segments with a specific error using De Casteljau's algorithm
     fn flatten_casteljau(&self, error: T::S, mesh: &T::Mesh) -> Vec<T::Vec> {
         fn recursive_flatten<const D: usize, T: EuclideanMeshType<D>>(
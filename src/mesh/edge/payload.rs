@@ -1,8 +1,8 @@
 use std::hash::Hash;
 
 use crate::{
-    math::Transformable,
-    mesh::{EuclideanMeshType, MeshType},
+    math::{HasNormal, HasUV, TransformTrait, Transformable},
+    mesh::{EuclideanMeshType, MeshType, VertexPayload},
 };
 
 use super::CurvedEdgeType;
@@ -16,6 +16,13 @@ pub trait EdgePayload: Clone + std::fmt::Debug + PartialEq {
     fn is_empty(&self) -> bool;
 }
 
+/// An edge payload that stores attributes of the face corner at the origin of the halfedge,
+/// i.e., attributes that can differ between the faces around a vertex (a.k.a. wedges).
+pub trait CornerPayload<VP: VertexPayload>: EdgePayload {
+    /// Overrides the attributes of the vertex payload `vp` with the ones of the corner.
+    fn apply_corner(&self, vp: &mut VP);
+}
+
 /// The default edge payload can be safely constructed with a default constructor.
 /// For example, when extruding, it is ok for all new edges to have the same default payload.
 pub trait DefaultEdgePayload: EdgePayload + Default {}
@@ -39,6 +46,10 @@ impl<T: MeshType> EdgePayload for EmptyEdgePayload<T> {
 
 impl<T: MeshType> DefaultEdgePayload for EmptyEdgePayload<T> {}
 
+impl<T: MeshType, VP: VertexPayload> CornerPayload<VP> for EmptyEdgePayload<T> {
+    fn apply_corner(&self, _: &mut VP) {}
+}
+
 impl<const D: usize, T: EuclideanMeshType<D>> Transformable<D> for EmptyEdgePayload<T> {
     type Rot = T::Rot;
     type S = T::S;
@@ -121,3 +132,92 @@ impl<const D: usize, T: EuclideanMeshType<D>> Transformable<D> for CurvedEdgePay
         self
     }
 }
+
+/// An edge payload with an optional normal and uv coordinate of the face corner at the origin
+/// of the halfedge, e.g., for hard edges and uv seams. Corners without an attribute use the
+/// one of their vertex.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CornerEdgePayload<const D: usize, T: EuclideanMeshType<D>> {
+    normal: Option<T::Vec>,
+    uv: Option<T::Vec2>,
+}
+
+impl<const D: usize, T: EuclideanMeshType<D>> Default for CornerEdgePayload<D, T> {
+    fn default() -> Self {
+        Self {
+            normal: None,
+            uv: None,
+        }
+    }
+}
+
+impl<const D: usize, T: EuclideanMeshType<D>> CornerEdgePayload<D, T> {
+    /// Returns the normal of the corner if it has one.
+    pub fn normal(&self) -> Option<T::Vec> {
+        self.normal
+    }
+
+    /// Sets the normal of the corner.
+    pub fn set_normal(&mut self, normal: Option<T::Vec>) {
+        self.normal = normal;
+    }
+
+    /// Returns the uv coordinates of the corner if it has some.
+    pub fn uv(&self) -> Option<T::Vec2> {
+        self.uv
+    }
+
+    /// Sets the uv coordinates of the corner.
+    pub fn set_uv(&mut self, uv: Option<T::Vec2>) {
+        self.uv = uv;
+    }
+}
+
+impl<const D: usize, T: EuclideanMeshType<D>> EdgePayload for CornerEdgePayload<D, T> {
+    fn allocate() -> Self {
+        Default::default()
+    }
+    fn is_empty(&self) -> bool {
+        self.normal.is_none() && self.uv.is_none()
+    }
+}
+
+impl<const D: usize, T: EuclideanMeshType<D>> DefaultEdgePayload for CornerEdgePayload<D, T> {}
+
+impl<const D: usize, T: EuclideanMeshType<D>, VP> CornerPayload<VP> for CornerEdgePayload<D, T>
+where
+    VP: VertexPayload + HasNormal<D, T::Vec, S = T::S> + HasUV<T::Vec2, S = T::S>,
+{
+    fn apply_corner(&self, vp: &mut VP) {
+        if let Some(normal) = self.normal {
+            vp.set_normal(normal);
+        }
+        if let Some(uv) = self.uv {
+            vp.set_uv(uv);
+        }
+    }
+}
+
+impl<const D: usize, T: EuclideanMeshType<D>> Transformable<D> for CornerEdgePayload<D, T> {
+    type Rot = T::Rot;
+    type S = T::S;
+    type Trans = T::Trans;
+    type Vec = T::Vec;
+
+    fn transform(&mut self, t: &Self::Trans) -> &mut Self {
+        if let Some(normal) = &mut self.normal {
+            *normal = t.apply_vec(*normal);
+        }
+        self
+    }
+
+    fn lerp(&mut self, other: &Self, t: Self::S) -> &mut Self {
+        if let (Some(a), Some(b)) = (&mut self.normal, other.normal) {
+            *a = *a + (b - *a) * t;
+        }
+        if let (Some(a), Some(b)) = (&mut self.uv, other.uv) {
+            *a = *a + (b - *a) * t;
+        }
+        self
+    }
+}
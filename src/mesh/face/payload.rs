@@ -7,11 +7,28 @@ use crate::{
 pub trait FacePayload: Clone + Copy + PartialEq + Eq + std::fmt::Debug {
     /// Returns a new default instance without any meaningful data.
     fn allocate() -> Self;
+
+    /// Returns the provenance tag of the face, i.e., the name of the part of the
+    /// operation or primitive that created it, e.g., "cap" or "side-wall".
+    /// Payloads that don't store provenance always return `None`.
+    fn tag(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Records the provenance tag of the face. Ignored by payloads that don't store provenance.
+    fn set_tag(&mut self, _tag: &'static str) {}
 }
 
 /// A FacePayload that is safe to be constructed with defaults.
 /// For example, when extruding, it is ok for all new faces to have the same default payload.
-pub trait DefaultFacePayload: FacePayload + Default {}
+pub trait DefaultFacePayload: FacePayload + Default {
+    /// Returns the default payload with the given provenance tag.
+    fn with_tag(tag: &'static str) -> Self {
+        let mut fp = Self::default();
+        fp.set_tag(tag);
+        fp
+    }
+}
 
 /// An empty face payload if you don't need any additional information.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -43,3 +60,44 @@ impl<const D: usize, T: EuclideanMeshType<D>> Transformable<D> for EmptyFacePayl
         self
     }
 }
+
+/// A face payload that records which operation or primitive created the face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TaggedFacePayload<T: MeshType> {
+    tag: Option<&'static str>,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: MeshType> FacePayload for TaggedFacePayload<T> {
+    fn allocate() -> Self {
+        Self {
+            tag: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn tag(&self) -> Option<&'static str> {
+        self.tag
+    }
+
+    fn set_tag(&mut self, tag: &'static str) {
+        self.tag = Some(tag);
+    }
+}
+
+impl<T: MeshType> DefaultFacePayload for TaggedFacePayload<T> {}
+
+impl<const D: usize, T: EuclideanMeshType<D>> Transformable<D> for TaggedFacePayload<T> {
+    type Rot = T::Rot;
+    type S = T::S;
+    type Trans = T::Trans;
+    type Vec = T::Vec;
+
+    fn transform(&mut self, _: &T::Trans) -> &mut Self {
+        self
+    }
+
+    fn lerp(&mut self, _: &Self, _: Self::S) -> &mut Self {
+        self
+    }
+}
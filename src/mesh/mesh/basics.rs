@@ -1,8 +1,8 @@
 use crate::{
     math::{HasPosition, IndexType, Scalar, Vector},
     mesh::{
-        CurvedEdge, CurvedEdgeType, DefaultEdgePayload, EdgeBasics, FaceBasics, MeshType,
-        VertexBasics,
+        CurvedEdge, CurvedEdgeType, DefaultEdgePayload, EdgeBasics, FaceBasics, FacePayload,
+        MeshType, VertexBasics,
     },
 };
 use itertools::Itertools;
@@ -149,6 +149,19 @@ pub trait MeshBasics<T: MeshType<Mesh = Self>>: Default + std::fmt::Debug + Clon
     where
         T: 'a;
 
+    /// Returns a counter that increases whenever the mesh changes, or `None` if the mesh
+    /// doesn't track its changes. Use it with `face_changed_since` to find the faces that
+    /// changed, e.g., to update caches incrementally.
+    fn generation(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns whether the face, its edges, or its vertices changed since the mesh had the
+    /// given `generation`. Conservatively returns `true` if the mesh doesn't track its changes.
+    fn face_changed_since(&self, _face: T::F, _generation: u64) -> bool {
+        true
+    }
+
     /// Returns the id of the (half)edge from `v` to `w` or `None` if they are not neighbors.
     fn shared_edge(&self, v: T::V, w: T::V) -> Option<T::Edge>;
 
@@ -160,6 +173,23 @@ pub trait MeshBasics<T: MeshType<Mesh = Self>>: Default + std::fmt::Debug + Clon
     /// TODO: Currently cannot distinguish between holes and "the outside"
     fn shared_face(&self, v0: T::V, v1: T::V) -> Option<T::F>;
 
+    /// Returns the ids of all faces tagged with `tag`, i.e., the faces created
+    /// by the part of an operation or primitive with that name (e.g., "cap").
+    /// Always empty if the face payload doesn't store provenance.
+    fn select_by_tag(&self, tag: &str) -> Vec<T::F> {
+        self.faces()
+            .filter(|f| f.payload().tag() == Some(tag))
+            .map(|f| f.id())
+            .collect()
+    }
+
+    /// Sets the provenance tag of the given faces.
+    fn tag_faces(&mut self, faces: impl IntoIterator<Item = T::F>, tag: &'static str) {
+        for f in faces {
+            self.face_mut(f).payload_mut().set_tag(tag);
+        }
+    }
+
     /// Converts the mesh to a mesh without curved edges
     fn flatten_curved_edges<const D: usize>(&mut self, tol: T::S) -> &mut Self
     where
@@ -55,12 +55,14 @@ pub trait MeshBuilder<T: MeshType<Mesh = Self>>: MeshBasics<T> {
         vs: I,
     ) -> T::E;
 
-    /*
     /// Remove the edge resp. the halfedge and its twin.
     /// Adjacent faces are joined if there are any.
     /// Returns the id of the face that is kept.
+    ///
+    /// If only one side has a face, it is removed and its edges become part of the boundary.
+    /// In that case, `IndexType::max()` is returned. Panics if the faces on both sides are
+    /// the same or an endpoint has no other edges.
     fn remove_edge(&mut self, e: T::E) -> T::F;
-    */
 
     /// add a new vertex and return it's id
     fn add_vertex(&mut self, vp: T::VP) -> T::V;
@@ -81,6 +83,20 @@ pub trait MeshBuilder<T: MeshType<Mesh = Self>>: MeshBasics<T> {
     fn insert_loop(&mut self, vp: impl IntoIterator<Item = T::VP>) -> T::E
     where
         T::EP: DefaultEdgePayload;
+
+    /// Inserts the vertices `vp` and connects them with faces given as cycles of indices into `vp`.
+    /// The faces have to be consistently oriented (ccw) and must form a manifold surface.
+    /// Vertices that are not referenced by any face are inserted as isolated vertices.
+    ///
+    /// Returns the ids of the inserted faces in the order they were given.
+    fn insert_indexed_faces(
+        &mut self,
+        vp: impl IntoIterator<Item = T::VP>,
+        faces: impl IntoIterator<Item = Vec<usize>>,
+    ) -> Vec<T::F>
+    where
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload;
 }
 
 // TODO: These need to be simplified
@@ -186,6 +202,50 @@ pub trait MeshHalfEdgeBuilder<T: MeshType<Mesh = Self>>: MeshBasics<T> {
     where
         T::EP: DefaultEdgePayload,
         T::FP: DefaultFacePayload;
+
+    /// Splits the face `f` into two faces by inserting a chain of edges from vertex `from`
+    /// to vertex `to`, which must both be on the boundary of `f`.
+    /// Each item of `chain` inserts a new vertex inside the face and the pair of halfedges
+    /// leading to it. The last pair of halfedges connecting the chain with `to` gets
+    /// the payloads `ep1` and `ep2`.
+    /// The face payload is copied to both faces. The chain must not leave the face or
+    /// intersect its boundary, and without new vertices, `from` and `to` must not be adjacent.
+    ///
+    /// Returns the face to the left of the chain when walking from `from` to `to`
+    /// and the face to the right of it.
+    fn split_face(
+        &mut self,
+        f: T::F,
+        from: T::V,
+        to: T::V,
+        chain: impl IntoIterator<Item = (T::EP, T::EP, T::VP)>,
+        ep1: T::EP,
+        ep2: T::EP,
+    ) -> (T::F, T::F);
+
+    /// Same as `split_face` but with default edge payloads
+    fn split_face_default(
+        &mut self,
+        f: T::F,
+        from: T::V,
+        to: T::V,
+        chain: impl IntoIterator<Item = T::VP>,
+    ) -> (T::F, T::F)
+    where
+        T::EP: DefaultEdgePayload;
+
+    /// Removes the vertex `v`, which must have exactly two neighbors, and joins its two
+    /// edges into one, which keeps the payloads of one of them.
+    /// The adjacent faces lose one vertex each.
+    /// Returns one of the halfedges of the joined edge.
+    fn join_edges(&mut self, v: T::V) -> T::E;
+
+    /// Stitches two boundary loops of the same length together.
+    /// `a` and `b` are boundary halfedges of the two loops such that the target of `a` is
+    /// merged with the origin of `b`, the target of `a.next` with the origin of `b.prev`, etc.
+    /// The vertices of the loop of `b` are removed and the halfedges of both loops
+    /// are replaced by the inner halfedges becoming twins of each other.
+    fn stitch_boundary_loops(&mut self, a: T::E, b: T::E);
 }
 
 /// Some basic operations to build meshes with halfedges.
@@ -2,6 +2,7 @@ use super::{MeshBasics, MeshType};
 
 /// A trait for checking the consistency of a mesh.
 pub trait MeshChecker<T: MeshType<Mesh = Self>>: MeshBasics<T> {
-    /// Checks the mesh for consistency
+    /// Checks the mesh for consistency and returns the first problem found.
+    /// Use `MeshValidation::validate` to list all of them.
     fn check(&self) -> Result<(), String>;
 }
@@ -0,0 +1,58 @@
+use super::{MeshBasics, MeshType};
+use crate::math::IndexType;
+
+/// The mapping from the old to the new indices of the elements returned by `MeshCompact::compact`.
+/// Deleted elements are mapped to `IndexType::max()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionMap<T: MeshType> {
+    /// The new index of each old vertex index
+    pub vertices: Vec<T::V>,
+
+    /// The new index of each old edge index
+    pub edges: Vec<T::E>,
+
+    /// The new index of each old face index
+    pub faces: Vec<T::F>,
+}
+
+impl<T: MeshType> CompactionMap<T> {
+    /// Returns the new index of the vertex or `None` if it was deleted.
+    pub fn vertex(&self, old: T::V) -> Option<T::V> {
+        Self::get(&self.vertices, old)
+    }
+
+    /// Returns the new index of the edge or `None` if it was deleted.
+    pub fn edge(&self, old: T::E) -> Option<T::E> {
+        Self::get(&self.edges, old)
+    }
+
+    /// Returns the new index of the face or `None` if it was deleted.
+    pub fn face(&self, old: T::F) -> Option<T::F> {
+        Self::get(&self.faces, old)
+    }
+
+    /// Whether no element changed its index.
+    pub fn is_identity(&self) -> bool {
+        self.vertices
+            .iter()
+            .enumerate()
+            .all(|(i, v)| v.index() == i)
+            && self.edges.iter().enumerate().all(|(i, e)| e.index() == i)
+            && self.faces.iter().enumerate().all(|(i, f)| f.index() == i)
+    }
+
+    fn get<I: IndexType>(map: &[I], old: I) -> Option<I> {
+        map.get(old.index())
+            .copied()
+            .filter(|i| *i != IndexType::max())
+    }
+}
+
+/// Methods to remove the gaps left by deleted elements of a mesh.
+pub trait MeshCompact<T: MeshType<Mesh = Self>>: MeshBasics<T> {
+    /// Removes the gaps in the index space left by deleted elements, e.g., after many
+    /// removals, by moving the vertices, edges, and faces into dense arrays while
+    /// keeping their order. This improves cache locality and shrinks `max_vertex_index`.
+    /// All indices held outside of the mesh become invalid; use the returned map to update them.
+    fn compact(&mut self) -> CompactionMap<T>;
+}
@@ -0,0 +1,259 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, FaceBasics, MeshBuilder, MeshType3D, VertexBasics,
+    },
+};
+use std::collections::HashMap;
+
+/// Identifies the format and its version.
+const MAGIC: [u8; 4] = *b"PMZ\x01";
+
+fn write_varint(out: &mut Vec<u8>, mut x: u64) {
+    while x >= 0x80 {
+        out.push((x as u8) | 0x80);
+        x >>= 7;
+    }
+    out.push(x as u8);
+}
+
+fn zigzag(x: i64) -> u64 {
+    ((x << 1) ^ (x >> 63)) as u64
+}
+
+fn unzigzag(x: u64) -> i64 {
+    ((x >> 1) as i64) ^ -((x & 1) as i64)
+}
+
+/// Reads the compressed stream.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.data.len() - self.pos < n {
+            return Err("unexpected end of data".to_string());
+        }
+        self.pos += n;
+        Ok(&self.data[self.pos - n..self.pos])
+    }
+
+    fn f64(&mut self) -> Result<f64, String> {
+        Ok(f64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut x = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.bytes(1)?[0];
+            x |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(x);
+            }
+        }
+        Err("varint is too long".to_string())
+    }
+
+    /// Reads a count that can't be larger than the remaining number of bytes.
+    fn count(&mut self) -> Result<usize, String> {
+        let n = self.varint()?;
+        if n > (self.data.len() - self.pos) as u64 {
+            return Err(format!("count {} exceeds the size of the data", n));
+        }
+        Ok(n as usize)
+    }
+}
+
+/// Compact binary encoding of meshes, e.g., to cache many generated meshes.
+///
+/// Positions are quantized to a grid over the bounding box and delta-encoded. Vertices are
+/// renumbered in the order the faces reference them first, so the face indices can be
+/// delta-encoded, too. All numbers are stored as variable-length integers.
+/// Only positions and connectivity are stored; the other vertex, edge, and face payloads
+/// are reset to their defaults when decoding, so normals have to be recomputed.
+pub trait MeshCompression<T: MeshType3D<Mesh = Self>>: MeshBuilder<T> {
+    /// Encodes the mesh with `bits` bits per coordinate (between 1 and 32).
+    /// The positions are off by at most half a grid step, i.e., the size of the
+    /// bounding box divided by `2^(bits+1) - 2`.
+    fn compress(&self, bits: u32) -> Vec<u8> {
+        assert!((1..=32).contains(&bits), "bits must be between 1 and 32");
+        let mut index: HashMap<T::V, usize> = HashMap::new();
+        let mut order = Vec::new();
+        let mut faces = Vec::new();
+        for f in self.faces() {
+            let cycle = f
+                .vertex_ids(self)
+                .map(|v| {
+                    *index.entry(v).or_insert_with(|| {
+                        order.push(v);
+                        order.len() - 1
+                    })
+                })
+                .collect::<Vec<_>>();
+            faces.push(cycle);
+        }
+        for v in self.vertex_ids() {
+            index.entry(v).or_insert_with(|| {
+                order.push(v);
+                order.len() - 1
+            });
+        }
+
+        let coords = |v: &T::V| {
+            let p = self.vertex(*v).pos();
+            [p.x().to_f64(), p.y().to_f64(), p.z().to_f64()]
+        };
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for p in order.iter().map(coords) {
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+        if order.is_empty() {
+            (min, max) = ([0.0; 3], [0.0; 3]);
+        }
+
+        let mut out = MAGIC.to_vec();
+        out.push(bits as u8);
+        for m in min {
+            out.extend(m.to_le_bytes());
+        }
+        for k in 0..3 {
+            out.extend((max[k] - min[k]).to_le_bytes());
+        }
+        write_varint(&mut out, order.len() as u64);
+        write_varint(&mut out, faces.len() as u64);
+        let mut prev = 0i64;
+        for cycle in &faces {
+            write_varint(&mut out, cycle.len() as u64);
+            for i in cycle {
+                write_varint(&mut out, zigzag(*i as i64 - prev));
+                prev = *i as i64;
+            }
+        }
+
+        let steps = ((1u64 << bits) - 1) as f64;
+        let mut last = [0i64; 3];
+        for p in order.iter().map(coords) {
+            for k in 0..3 {
+                let extent = max[k] - min[k];
+                let q = if extent > 0.0 {
+                    ((p[k] - min[k]) / extent * steps).round() as i64
+                } else {
+                    0
+                };
+                write_varint(&mut out, zigzag(q - last[k]));
+                last[k] = q;
+            }
+        }
+        out
+    }
+
+    /// Decodes a mesh encoded with `compress`.
+    fn decompress(data: &[u8]) -> Result<Self, String>
+    where
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        let mut r = Reader { data, pos: 0 };
+        if r.bytes(4)? != MAGIC {
+            return Err("not a compressed mesh".to_string());
+        }
+        let bits = r.bytes(1)?[0] as u32;
+        if !(1..=32).contains(&bits) {
+            return Err(format!("invalid number of bits {}", bits));
+        }
+        let min = [r.f64()?, r.f64()?, r.f64()?];
+        let extent = [r.f64()?, r.f64()?, r.f64()?];
+        let num_vertices = r.count()?;
+        let num_faces = r.count()?;
+
+        let mut faces = Vec::with_capacity(num_faces);
+        let mut prev = 0i64;
+        for _ in 0..num_faces {
+            let n = r.count()?;
+            if n < 3 {
+                return Err(format!("face with {} vertices", n));
+            }
+            let mut cycle = Vec::with_capacity(n);
+            for _ in 0..n {
+                prev += unzigzag(r.varint()?);
+                if prev < 0 || prev >= num_vertices as i64 {
+                    return Err(format!("vertex index {} out of bounds", prev));
+                }
+                cycle.push(prev as usize);
+            }
+            faces.push(cycle);
+        }
+
+        let steps = ((1u64 << bits) - 1) as f64;
+        let mut last = [0i64; 3];
+        let mut vertices = Vec::with_capacity(num_vertices);
+        for _ in 0..num_vertices {
+            let mut p = [0.0; 3];
+            for k in 0..3 {
+                last[k] += unzigzag(r.varint()?);
+                p[k] = min[k] + last[k] as f64 / steps * extent[k];
+            }
+            vertices.push(T::VP::from_pos(T::Vec::from_xyz(
+                T::S::from_f64(p[0]),
+                T::S::from_f64(p[1]),
+                T::S::from_f64(p[2]),
+            )));
+        }
+        if r.pos != data.len() {
+            return Err("trailing data".to_string());
+        }
+
+        let mut mesh = Self::default();
+        mesh.insert_indexed_faces(vertices, faces);
+        Ok(mesh)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_compress_roundtrip() {
+        let sphere = Mesh3d64::uv_sphere(2.0, 32, 32);
+        let data = sphere.compress(16);
+        // three doubles per vertex and one u32 per corner
+        let raw = sphere.num_vertices() * 24 + sphere.num_edges() * 4;
+        assert!(data.len() * 3 < raw);
+
+        let decoded = Mesh3d64::decompress(&data).unwrap();
+        assert!(decoded.check().is_ok());
+        assert_eq!(decoded.num_vertices(), sphere.num_vertices());
+        assert_eq!(decoded.num_faces(), sphere.num_faces());
+        assert_eq!(decoded.num_edges(), sphere.num_edges());
+        let step = 4.0 / 65535.0;
+        // the faces are inserted in the same order
+        for (f, g) in sphere.faces().zip(decoded.faces()) {
+            assert_eq!(f.num_vertices(&sphere), g.num_vertices(&decoded));
+            for v in f.vertices(&sphere) {
+                assert!(g
+                    .vertices(&decoded)
+                    .any(|w| (v.pos() - w.pos()).abs().max() <= step / 2.0 + 1e-12));
+            }
+        }
+    }
+
+    #[test]
+    fn test_decompress_invalid() {
+        let data = Mesh3d64::cube(1.0).compress(8);
+        assert!(Mesh3d64::decompress(&data).is_ok());
+        assert!(Mesh3d64::decompress(&data[..data.len() - 1]).is_err());
+        assert!(Mesh3d64::decompress(&data[1..]).is_err());
+        let mut extra = data.clone();
+        extra.push(0);
+        assert!(Mesh3d64::decompress(&extra).is_err());
+        assert!(Mesh3d64::decompress(&[]).is_err());
+    }
+}
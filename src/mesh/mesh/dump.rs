@@ -0,0 +1,236 @@
+use super::{EuclideanMeshType, HalfEdgeMesh, MeshType};
+use crate::{
+    math::{IndexType, Scalar, Vector},
+    mesh::{EdgeBasics, FaceBasics, HalfEdge, VertexBasics},
+};
+use std::collections::{HashMap, HashSet};
+
+/// Methods to generate a human-readable, structured dump of the mesh.
+/// Useful for bug reports and golden tests.
+pub trait MeshDump<T: MeshType<Mesh = Self>>: HalfEdgeMesh<T>
+where
+    T::Edge: HalfEdge<T>,
+{
+    /// Returns the vertex cycle of each face, ordered by face id.
+    fn face_cycles(&self) -> Vec<(T::F, Vec<T::V>)> {
+        let mut res = self
+            .faces()
+            .map(|f| (f.id(), f.vertex_ids(self).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        res.sort_by_key(|(f, _)| *f);
+        res
+    }
+
+    /// Returns the boundary loops of the mesh, i.e., the chains of half-edges without a face.
+    /// Each loop starts at the half-edge with the smallest id.
+    fn boundary_loops(&self) -> Vec<Vec<T::E>> {
+        let mut visited = HashSet::new();
+        let mut res = Vec::new();
+        let mut ids = self
+            .edges()
+            .filter(|e| e.is_boundary_self())
+            .map(|e| e.id())
+            .collect::<Vec<_>>();
+        ids.sort();
+        for e in ids {
+            if visited.contains(&e) {
+                continue;
+            }
+            let chain = self.edges_from(e).map(|e| e.id()).collect::<Vec<_>>();
+            visited.extend(chain.iter().copied());
+            res.push(chain);
+        }
+        res
+    }
+
+    /// Returns the connected components of the mesh as sorted lists of vertex ids.
+    /// The islands are sorted by their smallest vertex id.
+    fn islands(&self) -> Vec<Vec<T::V>> {
+        // union-find on the vertex ids
+        let mut parent: HashMap<T::V, T::V> = self.vertex_ids().map(|v| (v, v)).collect();
+        fn find<V: IndexType>(parent: &mut HashMap<V, V>, v: V) -> V {
+            let mut root = v;
+            while parent[&root] != root {
+                root = parent[&root];
+            }
+            let mut cur = v;
+            while cur != root {
+                let next = parent[&cur];
+                parent.insert(cur, root);
+                cur = next;
+            }
+            root
+        }
+        for e in self.edges() {
+            let a = find(&mut parent, e.origin_id());
+            let b = find(&mut parent, e.target_id(self));
+            if a != b {
+                parent.insert(a.max(b), a.min(b));
+            }
+        }
+
+        let mut groups: HashMap<T::V, Vec<T::V>> = HashMap::new();
+        for v in self.vertex_ids() {
+            let root = find(&mut parent, v);
+            groups.entry(root).or_default().push(v);
+        }
+        let mut res = groups.into_values().collect::<Vec<_>>();
+        res.iter_mut().for_each(|g| g.sort());
+        res.sort();
+        res
+    }
+
+    /// Returns a structured, human-readable dump of the mesh topology
+    /// listing the face cycles, boundary loops, and islands.
+    fn dump(&self) -> String {
+        let mut s = format!(
+            "Mesh with {} vertices, {} half-edges, {} faces\n",
+            self.num_vertices(),
+            self.num_edges(),
+            self.num_faces()
+        );
+
+        s.push_str("faces:\n");
+        for (f, cycle) in self.face_cycles() {
+            s.push_str(&format!("  {}: {}\n", f, join_ids(&cycle, " -> ")));
+        }
+
+        let loops = self.boundary_loops();
+        s.push_str(&format!("boundary loops: {}\n", loops.len()));
+        for l in loops {
+            let vs = l
+                .iter()
+                .map(|e| self.edge(*e).origin_id())
+                .collect::<Vec<_>>();
+            s.push_str(&format!(
+                "  [{}]: {}\n",
+                join_ids(&l, ","),
+                join_ids(&vs, " -> ")
+            ));
+        }
+
+        let islands = self.islands();
+        s.push_str(&format!("islands: {}\n", islands.len()));
+        for island in islands {
+            s.push_str(&format!("  {{{}}}\n", join_ids(&island, ", ")));
+        }
+
+        s
+    }
+
+    /// Renders a small ASCII projection of the mesh onto the first two coordinates.
+    /// Vertices are drawn as their id (if it is a single digit) or `#`, edges as `.`.
+    ///
+    /// Returns `None` if the mesh is empty or has more than `max_vertices` vertices.
+    fn ascii_projection<const D: usize>(
+        &self,
+        width: usize,
+        height: usize,
+        max_vertices: usize,
+    ) -> Option<String>
+    where
+        T: EuclideanMeshType<D>,
+    {
+        if self.num_vertices() == 0 || self.num_vertices() > max_vertices || width < 2 || height < 2
+        {
+            return None;
+        }
+
+        let (mut min_x, mut min_y) = (T::S::INFINITY, T::S::INFINITY);
+        let (mut max_x, mut max_y) = (T::S::NEG_INFINITY, T::S::NEG_INFINITY);
+        for v in self.vertices() {
+            let p: T::Vec = v.pos();
+            min_x = min_x.min(p.x());
+            min_y = min_y.min(p.y());
+            max_x = max_x.max(p.x());
+            max_y = max_y.max(p.y());
+        }
+        let dx = (max_x - min_x).max(T::S::EPS);
+        let dy = (max_y - min_y).max(T::S::EPS);
+
+        // y grows upwards, so the first row is the largest y
+        let to_cell = |p: T::Vec| -> (i64, i64) {
+            let x = ((p.x() - min_x) / dx * T::S::from_usize(width - 1)).to_f64();
+            let y = ((max_y - p.y()) / dy * T::S::from_usize(height - 1)).to_f64();
+            (x.round() as i64, y.round() as i64)
+        };
+
+        let mut grid = vec![vec![' '; width]; height];
+        for e in self.edges() {
+            let (x0, y0) = to_cell(e.origin(self).pos());
+            let (x1, y1) = to_cell(e.target(self).pos());
+            let steps = (x1 - x0).abs().max((y1 - y0).abs()).max(1);
+            for i in 0..=steps {
+                let x = x0 + (x1 - x0) * i / steps;
+                let y = y0 + (y1 - y0) * i / steps;
+                grid[y as usize][x as usize] = '.';
+            }
+        }
+        for v in self.vertices() {
+            let (x, y) = to_cell(v.pos());
+            grid[y as usize][x as usize] = if v.id().index() < 10 {
+                char::from_digit(v.id().index() as u32, 10).unwrap()
+            } else {
+                '#'
+            };
+        }
+
+        Some(
+            grid.iter()
+                .map(|row| row.iter().collect::<String>().trim_end().to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+}
+
+fn join_ids<I: IndexType>(ids: &[I], sep: &str) -> String {
+    ids.iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_dump_polygon() {
+        let mesh = Mesh2d64::regular_polygon(1.0, 4);
+        assert_eq!(mesh.face_cycles().len(), 1);
+        assert_eq!(mesh.face_cycles()[0].1.len(), 4);
+        assert_eq!(mesh.boundary_loops().len(), 1);
+        assert_eq!(mesh.boundary_loops()[0].len(), 4);
+        assert_eq!(mesh.islands(), vec![vec![0, 1, 2, 3]]);
+        assert!(mesh.to_string().contains("islands: 1"));
+
+        let art = mesh.ascii_projection(9, 5, 16).unwrap();
+        assert_eq!(art.lines().count(), 5);
+        for v in 0..4 {
+            assert!(art.contains(&v.to_string()));
+        }
+        assert!(mesh.ascii_projection(9, 5, 3).is_none());
+    }
+
+    #[test]
+    fn test_dump_cube() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        assert_eq!(mesh.face_cycles().len(), 6);
+        assert!(mesh.boundary_loops().is_empty());
+        assert_eq!(mesh.islands().len(), 1);
+
+        mesh.insert_polygon(
+            [
+                Vec3::new(5.0, 0.0, 0.0),
+                Vec3::new(6.0, 0.0, 0.0),
+                Vec3::new(6.0, 1.0, 0.0),
+            ]
+            .map(VertexPayloadPNU::from_pos),
+        );
+        assert_eq!(mesh.islands().len(), 2);
+        assert_eq!(mesh.boundary_loops().len(), 1);
+        assert!(mesh.dump().contains("islands: 2"));
+    }
+}
@@ -1,4 +1,4 @@
-use ab_glyph::{Font as AbFont, FontRef, GlyphId, ScaleFont};
+use ab_glyph::{point, Font as AbFont, FontRef, GlyphId, OutlineCurve, Point, ScaleFont};
 
 use crate::{
     math::{HasPosition, IndexType, Vector},
@@ -27,6 +27,11 @@ impl<'a> Font<'a> {
         self.scale = scale;
     }
 
+    /// Get the scale of the font.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
     fn font_scale(&self) -> f32 {
         self.scale //* self.font.pt_to_px_scale(1.0).map(|x| x.x).unwrap_or(1.0)
     }
@@ -46,11 +51,19 @@ impl<'a> Font<'a> {
         T::EP: DefaultEdgePayload,
         T::FP: DefaultFacePayload,
     {
+        for (glyph_id, x_pos) in self.glyph_positions(text) {
+            self.draw_glyph_outlines::<D, T>(glyph_id, x_pos, mesh);
+        }
+    }
+
+    /// Returns the glyphs of the text with their horizontal positions.
+    fn glyph_positions(&self, text: &str) -> Vec<(GlyphId, f32)> {
         let mut x_pos = 0.0;
         let mut last_glyph_id = None;
 
         let scaled_font = self.font.as_scaled(self.font_scale());
 
+        let mut res = Vec::new();
         for c in text.chars() {
             let glyph_id = scaled_font.glyph_id(c);
 
@@ -64,11 +77,51 @@ impl<'a> Font<'a> {
                 scale: PxScale::from(self.scale),
             };*/
 
-            self.draw_glyph_outlines::<D, T>(glyph_id, x_pos, mesh);
+            res.push((glyph_id, x_pos));
 
             x_pos += scaled_font.h_advance(glyph_id);
             last_glyph_id = Some(glyph_id);
         }
+        res
+    }
+
+    /// The factor from font units to the units of the layout.
+    fn outline_scale(&self) -> f32 {
+        self.font
+            .pt_to_px_scale(self.scale)
+            .map(|x| x.x)
+            .unwrap_or(self.scale)
+            / self.font.height_unscaled()
+    }
+
+    /// Returns the closed outlines of each glyph of the laid out text as polylines, placed
+    /// like the outlines of `layout_text`. Bezier segments are sampled at four points. Fills
+    /// are oriented counter-clockwise and holes clockwise. The last point isn't repeated.
+    /// Glyphs without outlines, e.g., spaces, are skipped.
+    pub fn text_outlines(&self, text: &str) -> Vec<Vec<Vec<[f32; 2]>>> {
+        let scale = self.outline_scale();
+        let mut res = Vec::new();
+        for (glyph_id, x_pos) in self.glyph_positions(text) {
+            let Some(outline) = self.font.outline(glyph_id) else {
+                continue;
+            };
+            let mut contours = split_contours(&outline.curves);
+            orient_contours(&mut contours);
+            res.push(
+                contours
+                    .iter()
+                    .filter(|c| c.len() > 1 && c.last().unwrap().p1 == c[0].p0)
+                    .map(|contour| {
+                        contour
+                            .iter()
+                            .flat_map(|s| s.samples())
+                            .map(|p| [p.x * scale + x_pos, p.y * scale])
+                            .collect()
+                    })
+                    .collect(),
+            );
+        }
+        res
     }
 
     fn draw_glyph_outlines<const D: usize, T: EuclideanMeshType<D>>(
@@ -82,60 +135,36 @@ impl<'a> Font<'a> {
         T::EP: DefaultEdgePayload,
         T::FP: DefaultFacePayload,
     {
-        // TODO: Improve stability. Detect when to close and when to insert holes
-
         let Some(outline) = self.font.outline(glyph) else {
             println!("No outline found for glyph");
             return;
         };
 
-        let scale = T::S::from(
-            self.font
-                .pt_to_px_scale(self.scale)
-                .map(|x| x.x)
-                .unwrap_or(self.scale)
-                / self.font.height_unscaled(),
-        );
-        let mut first = None;
-        let mut cur = None;
-        let mut cur_v = IndexType::max();
-        let mut start_v = IndexType::max();
-        let mut prev_v = IndexType::max();
-
-        outline.curves.iter().for_each(|curve| {
-            let (p0, p1, c0, c1) = match curve {
-                ab_glyph::OutlineCurve::Line(p0, p1) => (
-                    T::Vec::from_xy(T::S::from(p0.x), T::S::from(p0.y)),
-                    T::Vec::from_xy(T::S::from(p1.x), T::S::from(p1.y)),
-                    None,
-                    None,
-                ),
-                ab_glyph::OutlineCurve::Quad(p0, p1, p2) => (
-                    T::Vec::from_xy(T::S::from(p0.x), T::S::from(p0.y)),
-                    T::Vec::from_xy(T::S::from(p2.x), T::S::from(p2.y)),
-                    Some(T::Vec::from_xy(T::S::from(p1.x), T::S::from(p1.y))),
-                    None,
-                ),
-                ab_glyph::OutlineCurve::Cubic(p0, p1, p2, p3) => (
-                    T::Vec::from_xy(T::S::from(p0.x), T::S::from(p0.y)),
-                    T::Vec::from_xy(T::S::from(p3.x), T::S::from(p3.y)),
-                    Some(T::Vec::from_xy(T::S::from(p1.x), T::S::from(p1.y))),
-                    Some(T::Vec::from_xy(T::S::from(p2.x), T::S::from(p2.y))),
-                ),
-            };
+        let scale = T::S::from(self.outline_scale());
+        let trans = T::Vec::from_x(T::S::from(x_pos));
+        let to_vec = |p: Point| T::Vec::from_xy(T::S::from(p.x), T::S::from(p.y)) * scale + trans;
 
-            if p0 == p1 && c0.is_none() && c1.is_none() {
-                return;
-            }
-
-            //println!("Adding edge from {:?} to {:?} {:?} {:?}", p0, p1, c0, c1);
+        let mut contours = split_contours(&outline.curves);
+        orient_contours(&mut contours);
 
-            let cur_e;
+        for contour in contours {
+            let closed = contour.len() > 1 && contour.last().unwrap().p1 == contour[0].p0;
+            let mut cur_v = IndexType::max();
+            let mut start_v = IndexType::max();
+            let mut prev_v = IndexType::max();
 
-            let trans = T::Vec::from_x(T::S::from(x_pos));
-            if let Some(_p) = cur {
-                // TODO: don't use eq but similarity
-                if first.unwrap() == p1 {
+            for (i, segment) in contour.iter().enumerate() {
+                let cur_e;
+                if i == 0 {
+                    let (v0, v1) = mesh.add_isolated_edge_default(
+                        T::VP::from_pos(to_vec(segment.p0)),
+                        T::VP::from_pos(to_vec(segment.p1)),
+                    );
+                    cur_v = v1;
+                    prev_v = v0;
+                    start_v = v0;
+                    cur_e = mesh.shared_edge_id(v0, v1).unwrap();
+                } else if closed && i == contour.len() - 1 {
                     let (_, _, e) = mesh.close_face_vertices(
                         prev_v,
                         Default::default(),
@@ -145,44 +174,229 @@ impl<'a> Font<'a> {
                         Default::default(),
                         false,
                     );
-                    cur_v = IndexType::max();
-                    prev_v = IndexType::max();
-                    start_v = IndexType::max();
-                    first = None;
-                    cur = None;
                     cur_e = e;
                 } else {
-                    //assert!(p0 == p || p1 == first.unwrap(), "Expected {:?} but got {:?}", p, p0);
                     let (v, e, _) = mesh
-                        .add_vertex_via_vertex_default(cur_v, T::VP::from_pos(p1 * scale + trans));
+                        .add_vertex_via_vertex_default(cur_v, T::VP::from_pos(to_vec(segment.p1)));
                     cur_e = e;
                     prev_v = cur_v;
                     cur_v = v;
-                    cur = Some(p1);
                 }
-            } else {
-                first = Some(p0);
-                cur = Some(p1);
-                let (v0, v1) = mesh.add_isolated_edge_default(
-                    T::VP::from_pos(p0 * scale + trans),
-                    T::VP::from_pos(p1 * scale + trans),
-                );
-                cur_v = v1;
-                prev_v = v0;
-                start_v = v0;
-                cur_e = mesh.shared_edge_id(v0, v1).unwrap();
+
+                match (segment.c0, segment.c1) {
+                    (Some(c0), Some(c1)) => {
+                        mesh.edge_mut(cur_e)
+                            .set_curve_type(CurvedEdgeType::CubicBezier(to_vec(c0), to_vec(c1)));
+                    }
+                    (Some(c0), None) => {
+                        mesh.edge_mut(cur_e)
+                            .set_curve_type(CurvedEdgeType::QuadraticBezier(to_vec(c0)));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// A line or bezier segment of a glyph outline in font units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    p0: Point,
+    p1: Point,
+    c0: Option<Point>,
+    c1: Option<Point>,
+}
+
+impl Segment {
+    fn reversed(&self) -> Self {
+        match (self.c0, self.c1) {
+            (Some(c0), Some(c1)) => Segment {
+                p0: self.p1,
+                p1: self.p0,
+                c0: Some(c1),
+                c1: Some(c0),
+            },
+            _ => Segment {
+                p0: self.p1,
+                p1: self.p0,
+                ..*self
+            },
+        }
+    }
+
+    /// Samples the segment without its end point.
+    fn samples(&self) -> impl Iterator<Item = Point> + '_ {
+        let n = if self.c0.is_some() { 4 } else { 1 };
+        (0..n).map(move |i| {
+            let t = i as f32 / n as f32;
+            let s = 1.0 - t;
+            let weighted = |ws: &[(Point, f32)]| {
+                ws.iter().fold(point(0.0, 0.0), |acc, (p, w)| {
+                    point(acc.x + p.x * w, acc.y + p.y * w)
+                })
+            };
+            match (self.c0, self.c1) {
+                (Some(c0), Some(c1)) => weighted(&[
+                    (self.p0, s * s * s),
+                    (c0, 3.0 * s * s * t),
+                    (c1, 3.0 * s * t * t),
+                    (self.p1, t * t * t),
+                ]),
+                (Some(c0), None) => {
+                    weighted(&[(self.p0, s * s), (c0, 2.0 * s * t), (self.p1, t * t)])
+                }
+                _ => self.p0,
             }
+        })
+    }
+}
+
+/// Splits the curves of a glyph outline into contours. A contour ends when a
+/// segment returns to the start of the contour. Degenerate segments are skipped.
+fn split_contours(curves: &[OutlineCurve]) -> Vec<Vec<Segment>> {
+    let mut contours: Vec<Vec<Segment>> = Vec::new();
+    let mut open = false;
+    for curve in curves {
+        let segment = match *curve {
+            OutlineCurve::Line(p0, p1) => Segment {
+                p0,
+                p1,
+                c0: None,
+                c1: None,
+            },
+            OutlineCurve::Quad(p0, p1, p2) => Segment {
+                p0,
+                p1: p2,
+                c0: Some(p1),
+                c1: None,
+            },
+            OutlineCurve::Cubic(p0, p1, p2, p3) => Segment {
+                p0,
+                p1: p3,
+                c0: Some(p1),
+                c1: Some(p2),
+            },
+        };
+        if segment.p0 == segment.p1 && segment.c0.is_none() {
+            continue;
+        }
+        if !open {
+            contours.push(Vec::new());
+        }
+        let contour = contours.last_mut().unwrap();
+        contour.push(segment);
+        // TODO: don't use eq but similarity
+        open = contour.len() == 1 || segment.p1 != contour[0].p0;
+    }
+    contours
+}
+
+/// Whether `p` lies inside the closed polyline.
+fn contains_point(polygon: &[Point], p: Point) -> bool {
+    let mut inside = false;
+    for i in 0..polygon.len() {
+        let (u, v) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        if (u.y > p.y) != (v.y > p.y) && p.x < u.x + (p.y - u.y) / (v.y - u.y) * (v.x - u.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
 
-            if c1.is_some() {
-                mesh.edge_mut(cur_e)
-                    .set_curve_type(CurvedEdgeType::CubicBezier(
-                        c0.unwrap() * scale + trans,
-                        c1.unwrap() * scale + trans,
-                    ));
-            } else if c0.is_some() {
-                mesh.edge_mut(cur_e)
-                    .set_curve_type(CurvedEdgeType::QuadraticBezier(c0.unwrap() * scale + trans));
+/// Fixes the orientation of the closed contours of a glyph since some fonts mix up
+/// the winding of their contours, causing holes to fill or fills to be holes.
+///
+/// The nesting depth of each contour, i.e., the number of contours enclosing it, decides
+/// whether it is a fill or a hole. Fills are oriented counter-clockwise and holes
+/// clockwise, so `find_holes` can distinguish them regardless of the font's conventions.
+fn orient_contours(contours: &mut [Vec<Segment>]) {
+    let polygons = contours
+        .iter()
+        .map(|c| c.iter().flat_map(|s| s.samples()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let closed = |c: &Vec<Segment>| c.len() > 1 && c.last().unwrap().p1 == c[0].p0;
+
+    for (i, contour) in contours.iter_mut().enumerate() {
+        if !closed(contour) {
+            continue;
+        }
+        let depth = polygons
+            .iter()
+            .enumerate()
+            .filter(|(j, other)| *j != i && other.len() > 2 && contains_point(other, contour[0].p0))
+            .count();
+        let polygon = &polygons[i];
+        let area = (0..polygon.len())
+            .map(|k| {
+                let (u, v) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                u.x * v.y - v.x * u.y
+            })
+            .sum::<f32>();
+        if (area > 0.0) != (depth % 2 == 0) {
+            contour.reverse();
+            for segment in contour.iter_mut() {
+                *segment = segment.reversed();
             }
-        });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32, ccw: bool) -> Vec<OutlineCurve> {
+        let ps = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+            .map(|(u, v)| point(x + u * size, y + v * size));
+        let ps = if ccw {
+            ps
+        } else {
+            [ps[0], ps[3], ps[2], ps[1]]
+        };
+        (0..4)
+            .map(|i| OutlineCurve::Line(ps[i], ps[(i + 1) % 4]))
+            .collect()
+    }
+
+    fn signed_area(contour: &[Segment]) -> f32 {
+        contour
+            .iter()
+            .map(|s| s.p0.x * s.p1.y - s.p1.x * s.p0.y)
+            .sum::<f32>()
+    }
+
+    #[test]
+    fn test_orient_contours() {
+        // an outline with a hole and an island inside the hole, all wound the same way
+        for ccw in [true, false] {
+            let curves = [
+                square(0.0, 0.0, 10.0, ccw),
+                square(2.0, 2.0, 6.0, ccw),
+                square(4.0, 4.0, 2.0, ccw),
+                square(20.0, 0.0, 1.0, !ccw),
+            ]
+            .concat();
+            let mut contours = split_contours(&curves);
+            assert_eq!(contours.len(), 4);
+            orient_contours(&mut contours);
+            let areas = contours.iter().map(|c| signed_area(c)).collect::<Vec<_>>();
+            assert!(areas[0] > 0.0 && areas[1] < 0.0 && areas[2] > 0.0 && areas[3] > 0.0);
+            for contour in &contours {
+                for (a, b) in contour.iter().zip(contour.iter().cycle().skip(1)) {
+                    assert_eq!(a.p1, b.p0);
+                }
+            }
+        }
+
+        // reversing curves swaps their control points
+        let cubic = Segment {
+            p0: point(0.0, 0.0),
+            p1: point(3.0, 0.0),
+            c0: Some(point(1.0, 1.0)),
+            c1: Some(point(2.0, 1.0)),
+        };
+        assert_eq!(cubic.reversed().c0, cubic.c1);
+        assert_eq!(cubic.reversed().reversed(), cubic);
     }
 }
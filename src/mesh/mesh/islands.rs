@@ -0,0 +1,255 @@
+use super::{EuclideanMeshType, MeshDump};
+use crate::{
+    math::{TransformTrait, Transformable},
+    mesh::{EdgeBasics, FaceBasics, HalfEdge, VertexBasics},
+    util::{Attributes, Element},
+};
+use std::collections::HashSet;
+
+/// The name of the vertex attribute channel storing the payloads of the islands.
+pub const ISLAND_ATTRIBUTE: &str = "island";
+
+/// The payload of an island, i.e., a connected component of a mesh such as a glyph of a text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IslandPayload<Trans> {
+    /// The name of the island. Empty if the island wasn't named.
+    pub name: String,
+
+    /// The transformation applied to the island by `MeshIslands::transform_island`
+    /// since the payload was created, i.e., the placement of the part.
+    pub transform: Trans,
+
+    /// Free-form tags to group islands, e.g., "glyph" or "uppercase".
+    pub tags: Vec<String>,
+}
+
+impl<Trans> IslandPayload<Trans> {
+    /// Creates a payload with the given name and transformation and no tags.
+    pub fn new(name: impl Into<String>, transform: Trans) -> Self {
+        Self {
+            name: name.into(),
+            transform,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Adds the tag to the payload.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Returns whether the payload has the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// Methods to attach payloads to the islands of a mesh and manipulate islands separately,
+/// e.g., to move the glyphs of a text without splitting it into separate meshes.
+///
+/// An island is identified by its smallest vertex id, i.e., the first vertex of the
+/// island as returned by `MeshDump::islands`. Each method accepting an island also accepts
+/// any other vertex of it. The payloads are stored in the vertex attribute channel
+/// `ISLAND_ATTRIBUTE` at that vertex, so they survive inserting further geometry. If two
+/// islands are joined, the payload of the island with the smaller id is kept.
+pub trait MeshIslands<const D: usize, T: EuclideanMeshType<D, Mesh = Self>>: MeshDump<T>
+where
+    T::Edge: HalfEdge<T>,
+{
+    /// Returns the attribute channels storing the payloads of the islands.
+    fn attributes(&self) -> &Attributes;
+
+    /// Returns the attribute channels storing the payloads of the islands mutably.
+    fn attributes_mut(&mut self) -> &mut Attributes;
+
+    /// Returns the ids of all islands ordered like `MeshDump::islands`.
+    fn island_ids(&self) -> Vec<T::V> {
+        self.islands().iter().map(|island| island[0]).collect()
+    }
+
+    /// Returns the vertices of the island containing `v` in breadth-first order.
+    fn island_vertices(&self, v: T::V) -> Vec<T::V> {
+        let mut visited = HashSet::from([v]);
+        let mut res = vec![v];
+        let mut i = 0;
+        while i < res.len() {
+            for w in self.vertex(res[i]).neighbor_ids(self) {
+                if visited.insert(w) {
+                    res.push(w);
+                }
+            }
+            i += 1;
+        }
+        res
+    }
+
+    /// Returns the id of the island containing `v`.
+    fn island_of(&self, v: T::V) -> T::V {
+        self.island_vertices(v).into_iter().min().unwrap()
+    }
+
+    /// Returns the payload of the island containing `v`. Islands without a payload
+    /// have an empty name, the identity transformation, and no tags.
+    fn island_payload(&self, v: T::V) -> IslandPayload<T::Trans> {
+        let island = self.island_of(v);
+        self.attributes()
+            .get::<IslandPayload<T::Trans>>(ISLAND_ATTRIBUTE)
+            .map(|channel| channel.get(island).clone())
+            .unwrap_or_else(|| IslandPayload::new("", T::Trans::identity()))
+    }
+
+    /// Sets the payload of the island containing `v`.
+    fn set_island_payload(&mut self, v: T::V, payload: IslandPayload<T::Trans>) -> &mut Self {
+        let island = self.island_of(v);
+        let attributes = self.attributes_mut();
+        if !attributes.contains(ISLAND_ATTRIBUTE) {
+            attributes.add_with_default(
+                ISLAND_ATTRIBUTE,
+                Element::Vertex,
+                IslandPayload::new("", T::Trans::identity()),
+            );
+        }
+        attributes
+            .get_mut::<IslandPayload<T::Trans>>(ISLAND_ATTRIBUTE)
+            .expect("The island attribute has the wrong type")
+            .set(island, payload);
+        self
+    }
+
+    /// Sets the name of the island containing `v`.
+    fn name_island(&mut self, v: T::V, name: impl Into<String>) -> &mut Self {
+        let payload = IslandPayload {
+            name: name.into(),
+            ..self.island_payload(v)
+        };
+        self.set_island_payload(v, payload)
+    }
+
+    /// Adds the tag to the island containing `v`.
+    fn tag_island(&mut self, v: T::V, tag: impl Into<String>) -> &mut Self {
+        let payload = self.island_payload(v).with_tag(tag);
+        self.set_island_payload(v, payload)
+    }
+
+    /// Returns the id of the first island with the given name.
+    fn find_island(&self, name: &str) -> Option<T::V> {
+        self.island_ids()
+            .into_iter()
+            .find(|island| self.island_payload(*island).name == name)
+    }
+
+    /// Returns the ids of the islands with the given tag.
+    fn islands_tagged(&self, tag: &str) -> Vec<T::V> {
+        self.island_ids()
+            .into_iter()
+            .filter(|island| self.island_payload(*island).has_tag(tag))
+            .collect()
+    }
+
+    /// Transforms the island containing `v`, i.e., the payloads of its vertices,
+    /// their outgoing half-edges, and the adjacent faces, and appends `t` to
+    /// the transformation of the island's payload.
+    fn transform_island(&mut self, v: T::V, t: &T::Trans) -> &mut Self
+    where
+        T::VP: Transformable<D, Rot = T::Rot, Vec = T::Vec, Trans = T::Trans, S = T::S>,
+        T::EP: Transformable<D, Rot = T::Rot, Vec = T::Vec, Trans = T::Trans, S = T::S>,
+        T::FP: Transformable<D, Rot = T::Rot, Vec = T::Vec, Trans = T::Trans, S = T::S>,
+    {
+        let vertices = self.island_vertices(v);
+        let mut faces = HashSet::new();
+        for w in &vertices {
+            let edges: Vec<T::E> = self.vertex(*w).edges_out(self).map(|e| e.id()).collect();
+            for e in edges {
+                if !self.edge(e).is_boundary_self() {
+                    faces.insert(self.edge(e).face_id());
+                }
+                self.edge_mut(e).payload_mut().transform(t);
+            }
+            self.vertex_mut(*w).payload_mut().transform(t);
+        }
+        for f in faces {
+            self.face_mut(f).payload_mut().transform(t);
+        }
+
+        let mut payload = self.island_payload(v);
+        payload.transform = payload.transform.chain(t);
+        self.set_island_payload(v, payload)
+    }
+
+    /// Translates the island containing `v`. See `transform_island`.
+    fn translate_island(&mut self, v: T::V, d: &T::Vec) -> &mut Self
+    where
+        T::VP: Transformable<D, Rot = T::Rot, Vec = T::Vec, Trans = T::Trans, S = T::S>,
+        T::EP: Transformable<D, Rot = T::Rot, Vec = T::Vec, Trans = T::Trans, S = T::S>,
+        T::FP: Transformable<D, Rot = T::Rot, Vec = T::Vec, Trans = T::Trans, S = T::S>,
+    {
+        self.transform_island(v, &T::Trans::from_translation(*d))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    /// Three unit cubes in a row, each shifted by `3` along the x-axis.
+    fn cubes() -> Mesh3d64 {
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new().with_weld(1e-9);
+        for i in 0..3 {
+            let cube = Mesh3d64::cube(1.0).translated(&Vec3::new(3.0 * i as f64, 0.0, 0.0));
+            for f in cube.faces() {
+                builder.add_polygon(f.vertices(&cube).map(|v| *v.payload()));
+            }
+        }
+        builder.build()
+    }
+
+    fn centroid(mesh: &Mesh3d64, island: usize) -> Vec3<f64> {
+        let vs = mesh.island_vertices(island);
+        vs.iter().map(|v| mesh.vertex(*v).pos()).sum::<Vec3<f64>>() / vs.len() as f64
+    }
+
+    #[test]
+    fn test_island_payloads() {
+        let mut mesh = cubes();
+        let ids = mesh.island_ids();
+        assert_eq!(ids.len(), 3);
+        assert!(ids.iter().all(|v| mesh.island_vertices(*v).len() == 8));
+        assert_eq!(mesh.island_payload(ids[1]).name, "");
+
+        for (i, name) in ["a", "b", "c"].iter().enumerate() {
+            mesh.name_island(ids[i], *name);
+        }
+        // any vertex of an island refers to the island
+        let other = *mesh.island_vertices(ids[2]).last().unwrap();
+        assert_eq!(mesh.island_of(other), ids[2]);
+        mesh.tag_island(other, "odd").tag_island(ids[0], "odd");
+        assert_eq!(mesh.find_island("b"), Some(ids[1]));
+        assert_eq!(mesh.find_island("d"), None);
+        assert_eq!(mesh.islands_tagged("odd"), vec![ids[0], ids[2]]);
+        assert_eq!(mesh.island_payload(other).name, "c");
+
+        // moving one island leaves the others in place and records the transformation
+        let before = [centroid(&mesh, ids[0]), centroid(&mesh, ids[2])];
+        let b = mesh.find_island("b").unwrap();
+        let up = Vec3::new(0.0, 2.0, 0.0);
+        mesh.translate_island(b, &up);
+        mesh.transform_island(b, &NdAffine::from_scale(Vec3::new(2.0, 2.0, 2.0)));
+        assert!(mesh.check().is_ok());
+        assert!(centroid(&mesh, b).is_about(&Vec3::new(6.0, 4.0, 0.0), 1e-9));
+        assert!(centroid(&mesh, ids[0]).is_about(&before[0], 1e-9));
+        assert!(centroid(&mesh, ids[2]).is_about(&before[1], 1e-9));
+        let payload = mesh.island_payload(b);
+        assert_eq!(payload.name, "b");
+        assert!(payload
+            .transform
+            .apply(Vec3::new(3.0, 0.0, 0.0))
+            .is_about(&Vec3::new(6.0, 4.0, 0.0), 1e-9));
+
+        // the payloads survive inserting another island
+        mesh.insert_regular_star(1.0, 1.0, 5);
+        assert_eq!(mesh.island_ids().len(), 4);
+        assert_eq!(mesh.find_island("c"), Some(ids[2]));
+    }
+}
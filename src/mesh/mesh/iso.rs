@@ -1,6 +1,12 @@
-use super::MeshType;
-use crate::math::IndexType;
-use std::collections::HashMap;
+use super::{soup::WeldGrid, MeshBasics, MeshType, MeshType3D};
+use crate::{
+    math::{IndexType, Scalar, Vector},
+    mesh::{EdgeBasics, FaceBasics, HalfEdge, VertexBasics},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+};
 
 /// The difference between two meshes when comparing them for equivalence.
 #[derive(Clone, Debug, PartialEq)]
@@ -74,6 +80,452 @@ impl<V1: IndexType, V2: IndexType> IndexIsomorphism<V1, V2> {
     }
 }
 
+/// The changes between two meshes, see `MeshIsomorphism::diff`.
+///
+/// Ids of removed elements refer to the old mesh, ids of added elements to the new one.
+/// Moved elements are given as pairs of the old and the new id. Edges are represented
+/// by the half-edge with the smaller id of each pair of twins.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct MeshDiff<T: MeshType> {
+    /// Vertices of the new mesh without a counterpart in the old mesh
+    pub added_vertices: Vec<T::V>,
+
+    /// Vertices of the old mesh without a counterpart in the new mesh
+    pub removed_vertices: Vec<T::V>,
+
+    /// Vertices with a different position, but the same neighbors
+    pub moved_vertices: Vec<(T::V, T::V)>,
+
+    /// Edges of the new mesh without a counterpart in the old mesh
+    pub added_edges: Vec<T::E>,
+
+    /// Edges of the old mesh without a counterpart in the new mesh
+    pub removed_edges: Vec<T::E>,
+
+    /// Edges that still connect the same vertices, but at least one of them moved
+    pub moved_edges: Vec<(T::E, T::E)>,
+
+    /// Faces of the new mesh without a counterpart in the old mesh
+    pub added_faces: Vec<T::F>,
+
+    /// Faces of the old mesh without a counterpart in the new mesh
+    pub removed_faces: Vec<T::F>,
+
+    /// Faces with the same vertex cycle, but at least one of the vertices moved
+    pub moved_faces: Vec<(T::F, T::F)>,
+}
+
+impl<T: MeshType> MeshDiff<T> {
+    /// Whether the meshes are the same up to the ids of their elements.
+    pub fn is_empty(&self) -> bool {
+        self.num_changes() == 0
+    }
+
+    /// Returns the number of added, removed, and moved elements.
+    pub fn num_changes(&self) -> usize {
+        self.added_vertices.len()
+            + self.removed_vertices.len()
+            + self.moved_vertices.len()
+            + self.added_edges.len()
+            + self.removed_edges.len()
+            + self.moved_edges.len()
+            + self.added_faces.len()
+            + self.removed_faces.len()
+            + self.moved_faces.len()
+    }
+}
+
+impl<T: MeshType> Display for MeshDiff<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn line<I: std::fmt::Debug>(
+            f: &mut Formatter<'_>,
+            name: &str,
+            added: &[I],
+            removed: &[I],
+            moved: usize,
+        ) -> std::fmt::Result {
+            writeln!(
+                f,
+                "{}: {} added, {} removed, {} moved",
+                name,
+                added.len(),
+                removed.len(),
+                moved
+            )?;
+            if !added.is_empty() {
+                writeln!(f, "  + {:?}", added)?;
+            }
+            if !removed.is_empty() {
+                writeln!(f, "  - {:?}", removed)?;
+            }
+            Ok(())
+        }
+        line(
+            f,
+            "vertices",
+            &self.added_vertices,
+            &self.removed_vertices,
+            self.moved_vertices.len(),
+        )?;
+        line(
+            f,
+            "edges",
+            &self.added_edges,
+            &self.removed_edges,
+            self.moved_edges.len(),
+        )?;
+        line(
+            f,
+            "faces",
+            &self.added_faces,
+            &self.removed_faces,
+            self.moved_faces.len(),
+        )
+    }
+}
+
+/// A representation of a mesh that doesn't depend on the ids of its elements or the
+/// order in which they were created, see `MeshIsomorphism::canonical_form`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct CanonicalMesh {
+    /// The sorted vertex positions in multiples of the tolerance
+    pub positions: Vec<[i64; 3]>,
+
+    /// The sorted undirected edges as ordered pairs of indices into `positions`
+    pub edges: Vec<(usize, usize)>,
+
+    /// The sorted faces as cycles of indices into `positions`, starting at the smallest index
+    pub faces: Vec<Vec<usize>>,
+}
+
+impl CanonicalMesh {
+    /// Returns a 64-bit FNV-1a hash of the canonical form. Unlike `std::hash::Hash`,
+    /// this is stable across platforms and compiler versions, so it can be stored,
+    /// e.g., to deduplicate assets.
+    pub fn fingerprint(&self) -> u64 {
+        let mut h = Fnv::default();
+        h.write(self.positions.len() as u64);
+        for p in &self.positions {
+            p.iter().for_each(|x| h.write(*x as u64));
+        }
+        h.write(self.edges.len() as u64);
+        for (a, b) in &self.edges {
+            h.write(*a as u64);
+            h.write(*b as u64);
+        }
+        h.write(self.faces.len() as u64);
+        for f in &self.faces {
+            h.write(f.len() as u64);
+            f.iter().for_each(|v| h.write(*v as u64));
+        }
+        h.finish()
+    }
+}
+
+/// The 64-bit FNV-1a hash.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Labels the vertices by the Weisfeiler-Lehman refinement of the initial `labels`, i.e.,
+/// repeatedly combines the label of each vertex with the sorted labels of its neighbors
+/// until the number of distinct labels stops growing.
+fn refine_labels<T: MeshType>(mesh: &T::Mesh, mut labels: HashMap<T::V, u64>) -> HashMap<T::V, u64>
+where
+    T::Mesh: MeshBasics<T>,
+{
+    let distinct = |labels: &HashMap<T::V, u64>| labels.values().collect::<HashSet<_>>().len();
+    let mut classes = distinct(&labels);
+    for _ in 0..mesh.num_vertices() {
+        let next = mesh
+            .vertices()
+            .map(|v| {
+                let mut neighbors = v.neighbor_ids(mesh).map(|w| labels[&w]).collect::<Vec<_>>();
+                neighbors.sort();
+                let mut h = Fnv::default();
+                h.write(labels[&v.id()]);
+                h.write(neighbors.len() as u64);
+                neighbors.iter().for_each(|l| h.write(*l));
+                (v.id(), h.finish())
+            })
+            .collect::<HashMap<_, _>>();
+        let next_classes = distinct(&next);
+        labels = next;
+        if next_classes <= classes {
+            break;
+        }
+        classes = next_classes;
+    }
+    labels
+}
+
+/// Methods to compare meshes that don't rely on the ids of their elements.
+pub trait MeshIsomorphism<T: MeshType3D<Mesh = Self>>: MeshBasics<T>
+where
+    T::Edge: HalfEdge<T>,
+{
+    /// Returns the added, removed, and moved vertices, edges, and faces between `self`
+    /// (the old mesh) and `other` (the new mesh), matching vertices within `sqrt(EPS)`.
+    /// See `diff_eps`.
+    fn diff(&self, other: &Self) -> MeshDiff<T> {
+        self.diff_eps(other, T::S::EPS.sqrt())
+    }
+
+    /// Like `diff`, but matches vertices closer than `eps`.
+    ///
+    /// Vertices are first matched by their positions. Remaining vertices whose neighbors
+    /// are all matched are then matched to a vertex with the same neighbors in the other
+    /// mesh and reported as moved. Edges and faces are matched by their matched vertices,
+    /// so flipping the orientation of a face removes it and adds a new one.
+    /// Useful for golden-file regression tests of procedural generators, since, unlike
+    /// the equivalence checks, it doesn't stop at the first difference.
+    fn diff_eps(&self, other: &Self, eps: T::S) -> MeshDiff<T> {
+        let new_ids = other.vertex_ids().collect::<Vec<_>>();
+        let new_pos = new_ids
+            .iter()
+            .map(|v| other.vertex(*v).pos())
+            .collect::<Vec<T::Vec>>();
+        let mut grid = WeldGrid::<3, T::S>::new(eps);
+        for (i, p) in new_pos.iter().enumerate() {
+            grid.insert(p, i);
+        }
+
+        // match the vertices at the same position
+        let mut map: HashMap<T::V, T::V> = HashMap::new();
+        let mut matched: HashSet<T::V> = HashSet::new();
+        for v in self.vertices() {
+            if let Some(i) = grid.find_by(&v.pos(), &new_pos, |i| !matched.contains(&new_ids[i])) {
+                map.insert(v.id(), new_ids[i]);
+                matched.insert(new_ids[i]);
+            }
+        }
+
+        // match the remaining vertices by their neighbors
+        let mut diff = MeshDiff::<T>::default();
+        loop {
+            let mut progress = false;
+            let unmatched = self
+                .vertices()
+                .filter(|v| !map.contains_key(&v.id()))
+                .collect::<Vec<_>>();
+            for v in unmatched {
+                let Some(neighbors) = v
+                    .neighbor_ids(self)
+                    .map(|w| map.get(&w).copied())
+                    .collect::<Option<HashSet<_>>>()
+                else {
+                    continue;
+                };
+                let Some(first) = neighbors.iter().next() else {
+                    continue;
+                };
+                let candidate = other.vertex(*first).neighbor_ids(other).find(|w| {
+                    !matched.contains(w)
+                        && other.vertex(*w).neighbor_ids(other).collect::<HashSet<_>>() == neighbors
+                });
+                if let Some(w) = candidate {
+                    map.insert(v.id(), w);
+                    matched.insert(w);
+                    diff.moved_vertices.push((v.id(), w));
+                    progress = true;
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+        let moved = diff
+            .moved_vertices
+            .iter()
+            .map(|(v, _)| *v)
+            .collect::<HashSet<_>>();
+        diff.removed_vertices = self.vertex_ids().filter(|v| !map.contains_key(v)).collect();
+        diff.added_vertices = other
+            .vertex_ids()
+            .filter(|v| !matched.contains(v))
+            .collect();
+
+        // edges
+        let mut matched_edges = HashSet::new();
+        for e in self.edges().filter(|e| e.id() < e.twin_id()) {
+            let (a, b) = (e.origin_id(), e.target_id(self));
+            let counterpart = map
+                .get(&a)
+                .zip(map.get(&b))
+                .and_then(|(ma, mb)| other.shared_edge(*ma, *mb))
+                .map(|o| o.id().min(o.twin_id()));
+            match counterpart {
+                Some(o) => {
+                    matched_edges.insert(o);
+                    if moved.contains(&a) || moved.contains(&b) {
+                        diff.moved_edges.push((e.id(), o));
+                    }
+                }
+                None => diff.removed_edges.push(e.id()),
+            }
+        }
+        diff.added_edges = other
+            .edges()
+            .filter(|e| e.id() < e.twin_id() && !matched_edges.contains(&e.id()))
+            .map(|e| e.id())
+            .collect();
+
+        // faces, compared by their cycles starting at the smallest vertex id
+        let canonical = |mut cycle: Vec<T::V>| {
+            if let Some(i) = (0..cycle.len()).min_by_key(|i| cycle[*i]) {
+                cycle.rotate_left(i);
+            }
+            cycle
+        };
+        let mut new_faces: HashMap<Vec<T::V>, T::F> = other
+            .faces()
+            .map(|f| (canonical(f.vertex_ids(other).collect()), f.id()))
+            .collect();
+        for f in self.faces() {
+            let cycle = f
+                .vertex_ids(self)
+                .map(|v| map.get(&v).copied())
+                .collect::<Option<Vec<_>>>();
+            match cycle.and_then(|c| new_faces.remove(&canonical(c))) {
+                Some(o) => {
+                    if f.vertex_ids(self).any(|v| moved.contains(&v)) {
+                        diff.moved_faces.push((f.id(), o));
+                    }
+                }
+                None => diff.removed_faces.push(f.id()),
+            }
+        }
+        diff.added_faces = new_faces.into_values().collect();
+
+        diff.added_vertices.sort();
+        diff.removed_vertices.sort();
+        diff.moved_vertices.sort();
+        diff.added_edges.sort();
+        diff.removed_edges.sort();
+        diff.moved_edges.sort();
+        diff.added_faces.sort();
+        diff.removed_faces.sort();
+        diff.moved_faces.sort();
+        diff
+    }
+
+    /// Returns a form of the mesh that is independent of the ids of its elements, so
+    /// meshes built in different orders can be compared. Positions are rounded to
+    /// multiples of `eps` and payloads other than the position are ignored.
+    ///
+    /// Vertices are ordered by their rounded position. Vertices at the same rounded
+    /// position are ordered by their connectivity, so the form is only ambiguous if
+    /// coincident vertices are also topologically indistinguishable.
+    /// Note that positions close to the boundary of a rounding cell might round
+    /// differently in almost identical meshes.
+    fn canonical_form(&self, eps: T::S) -> CanonicalMesh {
+        let quantize = |x: T::S| (x / eps).to_f64().round() as i64;
+        let keys = self
+            .vertices()
+            .map(|v| {
+                let p = v.pos();
+                (v.id(), [quantize(p.x()), quantize(p.y()), quantize(p.z())])
+            })
+            .collect::<HashMap<_, _>>();
+        let labels = refine_labels::<T>(
+            self,
+            keys.iter()
+                .map(|(v, k)| {
+                    let mut h = Fnv::default();
+                    k.iter().for_each(|x| h.write(*x as u64));
+                    (*v, h.finish())
+                })
+                .collect(),
+        );
+        let mut order = self.vertex_ids().collect::<Vec<_>>();
+        order.sort_by_key(|v| (keys[v], labels[v]));
+        let index = order
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (*v, i))
+            .collect::<HashMap<_, _>>();
+
+        let mut edges = self
+            .edges()
+            .filter(|e| e.id() < e.twin_id())
+            .map(|e| {
+                let (a, b) = (index[&e.origin_id()], index[&e.target_id(self)]);
+                (a.min(b), a.max(b))
+            })
+            .collect::<Vec<_>>();
+        edges.sort();
+        let mut faces = self
+            .faces()
+            .map(|f| {
+                let mut cycle = f.vertex_ids(self).map(|v| index[&v]).collect::<Vec<_>>();
+                if let Some(i) = (0..cycle.len()).min_by_key(|i| cycle[*i]) {
+                    cycle.rotate_left(i);
+                }
+                cycle
+            })
+            .collect::<Vec<_>>();
+        faces.sort();
+
+        CanonicalMesh {
+            positions: order.iter().map(|v| keys[v]).collect(),
+            edges,
+            faces,
+        }
+    }
+
+    /// Returns a hash of the `canonical_form` that is equal for meshes that only differ
+    /// in the ids of their elements. See `CanonicalMesh::fingerprint`.
+    fn canonical_hash(&self, eps: T::S) -> u64 {
+        self.canonical_form(eps).fingerprint()
+    }
+
+    /// Returns a hash of the connectivity that ignores the positions and ids, e.g., to
+    /// check whether a generator still produces the same topology after changing its
+    /// parameters. Equal meshes have equal hashes, but, as for any graph hash, different
+    /// meshes might collide.
+    fn topology_hash(&self) -> u64 {
+        let labels = refine_labels::<T>(self, self.vertex_ids().map(|v| (v, 0)).collect());
+        let mut vertices = labels.values().copied().collect::<Vec<_>>();
+        vertices.sort();
+        let mut faces = self
+            .faces()
+            .map(|f| {
+                let mut ls = f.vertex_ids(self).map(|v| labels[&v]).collect::<Vec<_>>();
+                ls.sort();
+                ls
+            })
+            .collect::<Vec<_>>();
+        faces.sort();
+
+        let mut h = Fnv::default();
+        h.write(vertices.len() as u64);
+        vertices.iter().for_each(|l| h.write(*l));
+        h.write(faces.len() as u64);
+        for f in &faces {
+            h.write(f.len() as u64);
+            f.iter().for_each(|l| h.write(*l));
+        }
+        h.finish()
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "nalgebra")]
 mod tests {
@@ -102,6 +554,91 @@ mod tests {
         mesh
     }
 
+    #[test]
+    fn test_diff() {
+        let cube = Mesh3d64::cube(1.0);
+        assert!(cube.diff(&cube).is_empty());
+        assert!(cube
+            .diff(&cuboid_from_vertices(Vec3::new(1.0, 1.0, 1.0)))
+            .is_empty());
+
+        // moving a vertex keeps its edges and faces
+        let mut moved = cube.clone();
+        let v = moved.vertex_ids().next().unwrap();
+        let p = moved.vertex(v).pos();
+        moved.vertex_mut(v).payload_mut().set_pos(p * 1.5);
+        let diff = cube.diff(&moved);
+        assert_eq!(diff.moved_vertices, vec![(v, v)]);
+        assert_eq!(diff.moved_edges.len(), 3);
+        assert_eq!(diff.moved_faces.len(), 3);
+        assert!(diff.added_vertices.is_empty() && diff.removed_vertices.is_empty());
+        assert_eq!(diff.num_changes(), 7);
+
+        // extruding a face adds the ring around it
+        let mut extruded = cube.clone();
+        let f = extruded.face_ids().next().unwrap();
+        extruded.extrude_face(f, NdAffine::from_translation(Vec3::new(0.0, 0.0, 1.0)));
+        let diff = cube.diff(&extruded);
+        assert_eq!(diff.added_vertices.len(), 4);
+        assert_eq!(diff.added_edges.len(), 8);
+        assert_eq!(diff.added_faces.len(), 5);
+        assert_eq!(diff.removed_faces, vec![f]);
+        assert!(diff.removed_vertices.is_empty() && diff.moved_vertices.is_empty());
+        assert_eq!(
+            diff.to_string().lines().next(),
+            Some("vertices: 4 added, 0 removed, 0 moved")
+        );
+
+        // the other way around
+        let diff = extruded.diff(&cube);
+        assert_eq!(diff.removed_vertices.len(), 4);
+        assert_eq!(diff.removed_faces.len(), 5);
+        assert_eq!(diff.added_faces.len(), 1);
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let cube = Mesh3d64::cube(1.0);
+        let by_vertices = cuboid_from_vertices(Vec3::new(1.0, 1.0, 1.0));
+        assert_eq!(cube.canonical_form(1e-6), by_vertices.canonical_form(1e-6));
+        assert_eq!(cube.canonical_hash(1e-6), by_vertices.canonical_hash(1e-6));
+        assert_eq!(cube.topology_hash(), by_vertices.topology_hash());
+        let form = cube.canonical_form(1e-6);
+        assert_eq!(form.positions.len(), 8);
+        assert_eq!(form.edges.len(), 12);
+        assert_eq!(form.faces.len(), 6);
+
+        // the same faces in a shuffled order
+        let sphere = Mesh3d64::icosphere(1.0, 2);
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        let vs = sphere.vertex_ids().collect::<Vec<_>>();
+        let index = |v| vs.len() - 1 - vs.iter().position(|w| *w == v).unwrap();
+        for v in vs.iter().rev() {
+            builder.add_vertex(*sphere.vertex(*v).payload());
+        }
+        for (i, (_, cycle)) in sphere.face_cycles().iter().enumerate().rev() {
+            let mut cycle = cycle.iter().map(|v| index(*v)).collect::<Vec<_>>();
+            cycle.rotate_left(i % 3);
+            builder.add_face(cycle);
+        }
+        let shuffled = builder.build();
+        assert!(sphere.is_trivially_isomorphic(&shuffled).ne());
+        assert_eq!(sphere.canonical_hash(1e-6), shuffled.canonical_hash(1e-6));
+
+        // moving a vertex changes the geometry, but not the topology
+        let mut moved = cube.clone();
+        let v = moved.vertex_ids().next().unwrap();
+        let p = moved.vertex(v).pos();
+        moved.vertex_mut(v).payload_mut().set_pos(p * 1.5);
+        assert_ne!(cube.canonical_hash(1e-6), moved.canonical_hash(1e-6));
+        assert_eq!(cube.topology_hash(), moved.topology_hash());
+        assert_ne!(cube.topology_hash(), sphere.topology_hash());
+        let mut extruded = cube.clone();
+        let f = extruded.face_ids().next().unwrap();
+        extruded.extrude_face(f, NdAffine::from_translation(Vec3::new(0.0, 0.0, 1.0)));
+        assert_ne!(cube.topology_hash(), extruded.topology_hash());
+    }
+
     #[test]
     fn cube_equivalence() {
         let cube = Mesh3d64::cube(1.0);
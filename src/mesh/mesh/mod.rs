@@ -2,30 +2,46 @@
 mod basics;
 mod builder;
 mod check;
+mod compact;
+mod dump;
 mod halfedge;
+mod islands;
 mod iso;
 mod mesh_type;
 mod normals;
 mod path_builder;
 mod payload;
 mod position;
+mod report;
+mod selection;
+mod soup;
 mod topology;
+mod transaction;
 mod transform;
 mod triangulate;
+mod validate;
 
 pub use basics::*;
 pub use builder::*;
 pub use check::*;
+pub use compact::*;
+pub use dump::*;
 pub use halfedge::*;
+pub use islands::*;
 pub use iso::*;
 pub use mesh_type::*;
 pub use normals::*;
 pub use path_builder::*;
 pub use payload::*;
 pub use position::*;
+pub use report::*;
+pub use selection::*;
+pub use soup::*;
 pub use topology::*;
+pub use transaction::*;
 pub use transform::*;
 pub use triangulate::*;
+pub use validate::*;
 
 #[cfg(feature = "netsci")]
 mod netsci;
@@ -33,6 +49,12 @@ mod netsci;
 #[cfg(feature = "netsci")]
 pub use netsci::*;
 
+#[cfg(feature = "compression")]
+mod compress;
+
+#[cfg(feature = "compression")]
+pub use compress::*;
+
 #[cfg(feature = "fonts")]
 mod fonts;
 
@@ -1,5 +1,5 @@
 use crate::{
-    math::{HasPosition, IndexType, TransformTrait, Transformable},
+    math::{HasPosition, IndexType, Scalar, TransformTrait, Transformable, Vector},
     mesh::{
         CurvedEdge, CurvedEdgeType, DefaultEdgePayload, EdgeBasics, HalfEdge, MeshBasics,
         MeshBuilder, MeshHalfEdgeBuilder, MeshType, VertexBasics,
@@ -458,4 +458,208 @@ where
             .set_curve_type(CurvedEdgeType::CubicBezier(ct1, ct2));
         self
     }
+
+    /// Draws a quadratic bezier curve from the current vertex to the given position.
+    /// Unlike `quad`, this reuses the start vertex if `end` is at its position, closing the path.
+    pub fn quad_bezier_to<const D: usize>(&mut self, control: T::Vec, end: T::Vec) -> &mut Self
+    where
+        T::Edge: CurvedEdge<D, T>,
+        T::EP: DefaultEdgePayload,
+        T: EuclideanMeshType<D, Trans = Transform> + MeshTypeHalfEdge,
+        Transform: TransformTrait<T::S, D, Vec = T::Vec>,
+    {
+        let v = self.add_vertex_autoclose(end);
+        self.quad_to(control, v)
+    }
+
+    /// Draws a cubic bezier curve from the current vertex to the given position.
+    /// Unlike `cubic`, this reuses the start vertex if `end` is at its position, closing the path.
+    pub fn cubic_bezier_to<const D: usize>(
+        &mut self,
+        control1: T::Vec,
+        control2: T::Vec,
+        end: T::Vec,
+    ) -> &mut Self
+    where
+        T::Edge: CurvedEdge<D, T>,
+        T::EP: DefaultEdgePayload,
+        T: EuclideanMeshType<D, Trans = Transform> + MeshTypeHalfEdge,
+        Transform: TransformTrait<T::S, D, Vec = T::Vec>,
+    {
+        let v = self.add_vertex_autoclose(end);
+        self.cubic_to(control1, control2, v)
+    }
+
+    /// Draws an elliptical arc from the current vertex to the given position like the `A`
+    /// command of SVG paths. The ellipse has the given `radii` and is rotated by `x_rotation`
+    /// radians. Of the four possible arcs, `large_arc` selects the one spanning more than 180
+    /// degrees and `sweep` the one drawn in the direction of increasing angles, i.e.,
+    /// counter-clockwise in a y-up coordinate system. Radii that are too small to reach `end`
+    /// are scaled up uniformly.
+    ///
+    /// The arc is approximated by one cubic bezier curve per quarter turn. Reuses the start
+    /// vertex if `end` is at its position, closing the path.
+    pub fn arc_to(
+        &mut self,
+        radii: T::Vec,
+        x_rotation: T::S,
+        large_arc: bool,
+        sweep: bool,
+        end: T::Vec,
+    ) -> &mut Self
+    where
+        T::Edge: CurvedEdge<2, T>,
+        T::EP: DefaultEdgePayload,
+        T: EuclideanMeshType<2, Trans = Transform> + MeshTypeHalfEdge,
+        Transform: TransformTrait<T::S, 2, Vec = T::Vec>,
+    {
+        let current = self.current_vertex();
+        let start = self.mesh().vertex(current).pos();
+        let (mut rx, mut ry) = (radii.x().abs(), radii.y().abs());
+        if start.is_about(&end, T::S::EPS) {
+            // SVG omits arcs without extent
+            return self;
+        }
+        if rx <= T::S::EPS || ry <= T::S::EPS {
+            let v = self.add_vertex_autoclose(end);
+            return self.line_to(v);
+        }
+
+        // convert from endpoint to center parameterization (SVG spec, appendix B.2.4)
+        let (sin, cos) = (x_rotation.sin(), x_rotation.cos());
+        let rotate = |x: T::S, y: T::S| T::Vec::from_xy(cos * x - sin * y, sin * x + cos * y);
+        let half = (start - end) * T::S::HALF;
+        let x1 = cos * half.x() + sin * half.y();
+        let y1 = -sin * half.x() + cos * half.y();
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > T::S::ONE {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coef = (num / den).max(T::S::ZERO).sqrt();
+        if large_arc == sweep {
+            coef = -coef;
+        }
+        let (cx, cy) = (coef * rx * y1 / ry, -coef * ry * x1 / rx);
+        let center = rotate(cx, cy) + (start + end) * T::S::HALF;
+        let theta = ((y1 - cy) / ry).atan2((x1 - cx) / rx);
+        let mut delta = ((-y1 - cy) / ry).atan2((-x1 - cx) / rx) - theta;
+        if sweep && delta < T::S::ZERO {
+            delta += T::S::TWO * T::S::PI;
+        } else if !sweep && delta > T::S::ZERO {
+            delta -= T::S::TWO * T::S::PI;
+        }
+
+        let quarter = T::S::HALF * T::S::PI * (T::S::ONE + T::S::EPS.sqrt());
+        let mut n = 1;
+        while delta.abs() > quarter * T::S::from_usize(n) {
+            n += 1;
+        }
+        let step = delta / T::S::from_usize(n);
+        let alpha = T::S::from(4.0 / 3.0) * (step / T::S::from(4.0)).tan();
+        let point = |t: T::S| center + rotate(rx * t.cos(), ry * t.sin());
+        let tangent = |t: T::S| rotate(-rx * t.sin(), ry * t.cos());
+        for i in 0..n {
+            let a = theta + step * T::S::from_usize(i);
+            let b = a + step;
+            let to = if i + 1 == n { end } else { point(b) };
+            self.cubic_bezier_to(
+                point(a) + tangent(a) * alpha,
+                point(b) - tangent(b) * alpha,
+                to,
+            );
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_arc_to() {
+        // an ellipse made of two arcs
+        let mut mesh = Mesh2d64Curved::new();
+        let mut pb = PathBuilder::<MeshType2d64PNUCurved, _>::new(&mut mesh);
+        pb.move_to_new(Vec2::new(2.0, 0.0))
+            .arc_to(Vec2::new(2.0, 1.0), 0.0, false, true, Vec2::new(-2.0, 0.0))
+            .arc_to(Vec2::new(2.0, 1.0), 0.0, false, true, Vec2::new(2.0, 0.0));
+        assert!(pb.is_closed());
+        pb.close(Default::default());
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_vertices(), 4);
+        assert_eq!(mesh.num_faces(), 1);
+        let flat = mesh.flatten_curved_edges(1e-4);
+        assert!(flat.num_vertices() > 4);
+        for v in flat.vertices() {
+            let p = v.pos();
+            let r = (p.x / 2.0) * (p.x / 2.0) + p.y * p.y;
+            assert!((r - 1.0).abs() < 1e-3);
+        }
+        // the first arc is counter-clockwise, i.e., passes through the top
+        assert!(flat
+            .vertices()
+            .any(|v| v.pos().is_about(&Vec2::new(0.0, 1.0), 1e-9)));
+
+        // a quarter circle and the three quarters on the other side
+        for (large_arc, sweep, segments) in [(false, true, 1), (true, false, 3)] {
+            let mut mesh = Mesh2d64Curved::new();
+            PathBuilder::<MeshType2d64PNUCurved, _>::new(&mut mesh)
+                .move_to_new(Vec2::new(1.0, 0.0))
+                .arc_to(
+                    Vec2::new(1.0, 1.0),
+                    0.3,
+                    large_arc,
+                    sweep,
+                    Vec2::new(0.0, 1.0),
+                );
+            assert_eq!(mesh.num_vertices(), segments + 1);
+            mesh.flatten_curved_edges(1e-4);
+            for v in mesh.vertices() {
+                let p = v.pos();
+                assert!((p.norm() - 1.0).abs() < 1e-3);
+                if p.x.abs() > 1e-9 && p.y.abs() > 1e-9 {
+                    assert_eq!(p.x > 0.0 && p.y > 0.0, !large_arc);
+                }
+            }
+        }
+
+        // radii that are too small are scaled up to a half circle
+        let mut mesh = Mesh2d64Curved::new();
+        PathBuilder::<MeshType2d64PNUCurved, _>::new(&mut mesh)
+            .move_to_new(Vec2::new(1.0, 0.0))
+            .arc_to(Vec2::new(0.1, 0.1), 0.0, false, false, Vec2::new(-1.0, 0.0));
+        mesh.flatten_curved_edges(1e-4);
+        assert!(mesh
+            .vertices()
+            .any(|v| v.pos().is_about(&Vec2::new(0.0, -1.0), 1e-9)));
+    }
+
+    #[test]
+    fn test_bezier_to() {
+        let mut mesh = Mesh2d64Curved::new();
+        let mut pb = PathBuilder::<MeshType2d64PNUCurved, _>::new(&mut mesh);
+        pb.move_to_new(Vec2::new(0.0, 0.0))
+            .quad_bezier_to(Vec2::new(1.0, -1.0), Vec2::new(2.0, 0.0))
+            .line(Vec2::new(2.0, 1.0))
+            .cubic_bezier_to(
+                Vec2::new(1.0, 2.0),
+                Vec2::new(0.0, 1.0),
+                Vec2::new(0.0, 0.0),
+            );
+        assert!(pb.is_closed());
+        pb.close(Default::default());
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_vertices(), 3);
+        assert_eq!(
+            mesh.edges()
+                .filter(|e| e.curve_type() != CurvedEdgeType::Linear)
+                .count(),
+            2
+        );
+    }
 }
@@ -1,5 +1,8 @@
 use super::{basics::MeshBasics, EuclideanMeshType};
-use crate::{math::VectorIteratorExt, mesh::VertexBasics};
+use crate::{
+    math::{Scalar, Vector, Vector3D, VectorIteratorExt},
+    mesh::VertexBasics,
+};
 
 /// Methods for transforming meshes.
 pub trait MeshPosition<const D: usize, T: EuclideanMeshType<D, Mesh = Self>>:
@@ -9,4 +12,28 @@ pub trait MeshPosition<const D: usize, T: EuclideanMeshType<D, Mesh = Self>>:
     fn centroid(&self) -> T::Vec {
         self.vertices().map(|v| v.pos()).stable_mean()
     }
+
+    /// Returns the smallest and largest coordinates of the vertices, i.e., the corners
+    /// of the axis-aligned bounding box, or `None` if the mesh has no vertices.
+    fn bounding_box(&self) -> Option<(T::Vec, T::Vec)>
+    where
+        T::Vec: Vector3D<S = T::S>,
+    {
+        let mut min = [T::S::INFINITY; 3];
+        let mut max = [T::S::NEG_INFINITY; 3];
+        for v in self.vertices() {
+            let p = v.pos().to_array();
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+        if min[0] > max[0] {
+            return None;
+        }
+        Some((
+            T::Vec::from_xyz(min[0], min[1], min[2]),
+            T::Vec::from_xyz(max[0], max[1], max[2]),
+        ))
+    }
 }
@@ -0,0 +1,264 @@
+use super::{MeshDump, MeshType3D, Triangulateable};
+use crate::{
+    math::{Scalar, Vector, Vector3D},
+    mesh::{EdgeBasics, FaceBasics, HalfEdge, MeshBasics, Triangulation, VertexBasics},
+    tesselate::{TesselationMeta, TriangulationAlgorithm},
+};
+use std::fmt::{Display, Formatter};
+
+/// Returns the signed volume enclosed by the faces using a fan triangulation of each face.
+/// Unlike `MeshReport::volume`, this doesn't require the mesh to be closed or its faces to
+/// be planar, e.g., for meshes with T-junctions or while smoothing.
+pub(crate) fn signed_volume<T: MeshType3D>(mesh: &T::Mesh) -> T::S {
+    T::S::stable_sum(mesh.faces().map(|f| {
+        let vs = f.vertices(mesh).map(|v| v.pos()).collect::<Vec<T::Vec>>();
+        T::S::stable_sum((1..vs.len() - 1).map(|i| vs[0].dot(&vs[i].cross(&vs[i + 1]))))
+    })) / T::S::from_usize(6)
+}
+
+/// The number of bins of `MeshReport::quality_histogram`.
+pub const QUALITY_BINS: usize = 10;
+
+/// A summary of the statistics of a mesh, e.g., to log them in an asset pipeline or to
+/// fail the build when a generator produces bad geometry.
+/// Enable the `serde` feature to serialize the report.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeshReport<S: Scalar> {
+    /// The number of vertices
+    pub num_vertices: usize,
+
+    /// The number of edges, counting each pair of half-edges once
+    pub num_edges: usize,
+
+    /// The number of faces
+    pub num_faces: usize,
+
+    /// The number of triangles after triangulating the faces
+    pub num_triangles: usize,
+
+    /// The total area of the faces
+    pub area: S,
+
+    /// The enclosed volume if the mesh is closed, i.e., has no boundary loops
+    pub volume: Option<S>,
+
+    /// The number of boundary loops, i.e., holes and open rims
+    pub boundary_loops: usize,
+
+    /// The number of connected components
+    pub islands: usize,
+
+    /// The number of edges with zero length
+    pub degenerate_edges: usize,
+
+    /// The number of faces with zero area
+    pub degenerate_faces: usize,
+
+    /// The number of triangles in each of the equally wide quality bins from 0 to 1.
+    /// The quality of a triangle is `4 sqrt(3) area / (a^2 + b^2 + c^2)`, which is 1 for
+    /// equilateral triangles and 0 for degenerate ones.
+    pub quality_histogram: [usize; QUALITY_BINS],
+
+    /// The smallest quality of all triangles, or 1 if there are none
+    pub min_quality: S,
+
+    /// The average quality of all triangles, or 1 if there are none
+    pub mean_quality: S,
+}
+
+impl<S: Scalar> MeshReport<S> {
+    /// Returns the number of sliver triangles, i.e., triangles in the lowest quality bin.
+    pub fn sliver_triangles(&self) -> usize {
+        self.quality_histogram[0]
+    }
+
+    /// Returns the fraction of sliver triangles among all triangles.
+    pub fn sliver_ratio(&self) -> f64 {
+        if self.num_triangles == 0 {
+            return 0.0;
+        }
+        self.sliver_triangles() as f64 / self.num_triangles as f64
+    }
+
+    /// Whether the mesh is closed and has no degenerate elements.
+    pub fn is_clean(&self) -> bool {
+        self.boundary_loops == 0 && self.degenerate_edges == 0 && self.degenerate_faces == 0
+    }
+}
+
+impl<S: Scalar> Display for MeshReport<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} vertices, {} edges, {} faces, {} triangles",
+            self.num_vertices, self.num_edges, self.num_faces, self.num_triangles
+        )?;
+        write!(f, "area: {}, volume: ", self.area)?;
+        match self.volume {
+            Some(v) => writeln!(f, "{}", v)?,
+            None => writeln!(f, "open")?,
+        }
+        writeln!(
+            f,
+            "boundary loops: {}, islands: {}",
+            self.boundary_loops, self.islands
+        )?;
+        writeln!(
+            f,
+            "degenerate edges: {}, degenerate faces: {}",
+            self.degenerate_edges, self.degenerate_faces
+        )?;
+        writeln!(
+            f,
+            "quality: min {}, mean {}, slivers {:.2}%",
+            self.min_quality,
+            self.mean_quality,
+            self.sliver_ratio() * 100.0
+        )?;
+        for (i, count) in self.quality_histogram.iter().enumerate() {
+            writeln!(
+                f,
+                "  [{:.1}, {:.1}): {}",
+                i as f64 / QUALITY_BINS as f64,
+                (i + 1) as f64 / QUALITY_BINS as f64,
+                count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Methods to summarize the geometry and topology of a mesh.
+pub trait MeshStatistics<T: MeshType3D<Mesh = Self>>: MeshDump<T> + Triangulateable<T>
+where
+    T::Edge: HalfEdge<T>,
+{
+    /// Returns a summary of the mesh using the default triangulation algorithm
+    /// for the triangle statistics. See `report_with`.
+    fn report(&self) -> MeshReport<T::S> {
+        self.report_with(TriangulationAlgorithm::Auto)
+    }
+
+    /// Returns a summary of the mesh with element counts, area, volume, boundary loops,
+    /// islands, degenerate elements, and a histogram of the quality of the triangles
+    /// the faces are split into by the given `algorithm`.
+    fn report_with(&self, algorithm: TriangulationAlgorithm) -> MeshReport<T::S> {
+        let mut indices = Vec::new();
+        let mut tri = Triangulation::new(&mut indices);
+        self.triangulate_into(&mut tri, algorithm, &mut TesselationMeta::default());
+        let ranges = tri.face_ranges::<T::F>().collect::<Vec<_>>();
+
+        let pos = |v: T::V| -> T::Vec { self.vertex(v).pos() };
+        let triangle = |i: usize| [0, 1, 2].map(|k| pos(indices[3 * i + k]));
+        let triangle_area = |[a, b, c]: [T::Vec; 3]| (b - a).cross(&(c - a)).length() * T::S::HALF;
+
+        let mut histogram = [0; QUALITY_BINS];
+        let mut min_quality = T::S::ONE;
+        let mut quality_sum = T::S::ZERO;
+        let mut area = T::S::ZERO;
+        let mut volume = T::S::ZERO;
+        let normalization = T::S::from(4.0) * T::S::from(3.0).sqrt();
+        let num_triangles = indices.len() / 3;
+        for i in 0..num_triangles {
+            let [a, b, c] = triangle(i);
+            let tri_area = triangle_area([a, b, c]);
+            area += tri_area;
+            volume += a.dot(&b.cross(&c)) / T::S::from(6.0);
+
+            let lengths =
+                (b - a).length_squared() + (c - b).length_squared() + (a - c).length_squared();
+            let quality = if lengths <= T::S::EPS {
+                T::S::ZERO
+            } else {
+                (normalization * tri_area / lengths).clamp(T::S::ZERO, T::S::ONE)
+            };
+            let bin = (quality * T::S::from_usize(QUALITY_BINS)).to_f64() as usize;
+            histogram[bin.min(QUALITY_BINS - 1)] += 1;
+            min_quality = min_quality.min(quality);
+            quality_sum += quality;
+        }
+
+        let degenerate_faces = ranges
+            .iter()
+            .filter(|(_, range)| {
+                (range.start / 3..range.end / 3)
+                    .map(|i| triangle_area(triangle(i)))
+                    .fold(T::S::ZERO, |a, b| a + b)
+                    <= T::S::EPS
+            })
+            .count();
+        let degenerate_edges = self
+            .edges()
+            .filter(|e| e.id() <= e.twin_id())
+            .filter(|e| pos(e.origin_id()).distance(&pos(e.target_id(self))) <= T::S::EPS)
+            .count();
+        let boundary_loops = self.boundary_loops().len();
+
+        MeshReport {
+            num_vertices: self.num_vertices(),
+            num_edges: self.edges().filter(|e| e.id() <= e.twin_id()).count(),
+            num_faces: self.num_faces(),
+            num_triangles,
+            area,
+            volume: (boundary_loops == 0).then_some(volume),
+            boundary_loops,
+            islands: self.islands().len(),
+            degenerate_edges,
+            degenerate_faces,
+            quality_histogram: histogram,
+            min_quality,
+            mean_quality: if num_triangles == 0 {
+                T::S::ONE
+            } else {
+                quality_sum / T::S::from_usize(num_triangles)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_report() {
+        let cube = Mesh3d64::cube(2.0);
+        let report = cube.report();
+        assert_eq!(report.num_vertices, 8);
+        assert_eq!(report.num_edges, 12);
+        assert_eq!(report.num_faces, 6);
+        assert_eq!(report.num_triangles, 12);
+        assert!(report.area.is_about(24.0, 1e-9));
+        assert!(report.volume.unwrap().is_about(8.0, 1e-9));
+        assert_eq!(report.islands, 1);
+        assert!(report.is_clean());
+        // the right isosceles triangles of the cube have a quality of sqrt(3)/2
+        assert_eq!(report.quality_histogram[8], 12);
+        assert_eq!(report.sliver_ratio(), 0.0);
+        assert!(report.to_string().contains("12 triangles"));
+
+        // an open fan of slivers next to a second island
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        for i in 0..64 {
+            let a = i as f64 / 64.0 * std::f64::consts::TAU;
+            builder.add_vertex(VertexPayloadPNU::from_pos(Vec3::new(a.cos(), a.sin(), 0.0)));
+        }
+        builder.add_face(0..64);
+        for p in [[3.0, 0.0], [4.0, 0.0], [3.0, 1.0]] {
+            builder.add_vertex(VertexPayloadPNU::from_pos(Vec3::new(p[0], p[1], 0.0)));
+        }
+        builder.add_face([64, 65, 66]);
+        let fan: Mesh3d64 = builder.build();
+        let report = fan.report_with(TriangulationAlgorithm::Fan);
+        assert_eq!(report.islands, 2);
+        assert_eq!(report.boundary_loops, 2);
+        assert_eq!(report.volume, None);
+        assert!(!report.is_clean());
+        assert_eq!(report.num_triangles, 62 + 1);
+        assert!(report.sliver_ratio() > 0.1);
+        assert!(report.min_quality < 0.1);
+        assert_eq!(report.quality_histogram.iter().sum::<usize>(), 63);
+    }
+}
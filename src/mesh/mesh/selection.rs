@@ -0,0 +1,276 @@
+use super::{MeshBasics, MeshType};
+use crate::{
+    math::{Scalar, Vector, Vector3DIteratorExt},
+    mesh::{EdgeBasics, FaceBasics, HalfEdge, MeshType3D, MeshTypeHalfEdge, VertexBasics},
+    util::Selection,
+};
+
+/// Methods to select parts of a mesh, e.g., to apply operations only to them.
+///
+/// Vertices are adjacent if they share an edge and faces are adjacent if they share an
+/// edge. Selections of edges contain half-edges, so both half-edges of an edge can be
+/// selected independently.
+pub trait MeshSelection<T: MeshType<Mesh = Self>>: MeshBasics<T> {
+    /// Returns the vertices for which `predicate` is true.
+    fn select_vertices(&self, predicate: impl Fn(&T::Vertex) -> bool) -> Selection<T::V> {
+        self.vertices()
+            .filter(|v| predicate(v))
+            .map(|v| v.id())
+            .collect()
+    }
+
+    /// Returns the (half-)edges for which `predicate` is true.
+    fn select_edges(&self, predicate: impl Fn(&T::Edge) -> bool) -> Selection<T::E> {
+        self.edges()
+            .filter(|e| predicate(e))
+            .map(|e| e.id())
+            .collect()
+    }
+
+    /// Returns the faces for which `predicate` is true.
+    fn select_faces(&self, predicate: impl Fn(&T::Face) -> bool) -> Selection<T::F> {
+        self.faces()
+            .filter(|f| predicate(f))
+            .map(|f| f.id())
+            .collect()
+    }
+
+    /// Returns the vertices of the mesh that are not selected.
+    fn invert_vertices(&self, selection: &Selection<T::V>) -> Selection<T::V> {
+        self.select_vertices(|v| !selection.contains(v.id()))
+    }
+
+    /// Returns the faces of the mesh that are not selected.
+    fn invert_faces(&self, selection: &Selection<T::F>) -> Selection<T::F> {
+        self.select_faces(|f| !selection.contains(f.id()))
+    }
+
+    /// Returns the selection extended by all vertices adjacent to a selected vertex.
+    fn grow_vertices(&self, selection: &Selection<T::V>) -> Selection<T::V> {
+        let mut grown = selection.clone();
+        for v in selection.iter() {
+            grown.extend(self.vertex(v).neighbor_ids(self));
+        }
+        grown
+    }
+
+    /// Returns the selected vertices whose neighbors are all selected, i.e., the
+    /// selection without its boundary.
+    fn shrink_vertices(&self, selection: &Selection<T::V>) -> Selection<T::V> {
+        selection
+            .iter()
+            .filter(|v| {
+                self.vertex(*v)
+                    .neighbor_ids(self)
+                    .all(|w| selection.contains(w))
+            })
+            .collect()
+    }
+
+    /// Returns the selected vertices with at least one unselected neighbor.
+    fn vertex_selection_boundary(&self, selection: &Selection<T::V>) -> Selection<T::V> {
+        selection.difference(&self.shrink_vertices(selection))
+    }
+
+    /// Returns the selection extended by all faces sharing an edge with a selected face.
+    fn grow_faces(&self, selection: &Selection<T::F>) -> Selection<T::F>
+    where
+        T: MeshTypeHalfEdge,
+    {
+        let mut grown = selection.clone();
+        for f in selection.iter() {
+            grown.extend(
+                self.face(f)
+                    .edges(self)
+                    .map(|e| e.twin(self))
+                    .filter(|t| !t.is_boundary_self())
+                    .map(|t| t.face_id()),
+            );
+        }
+        grown
+    }
+
+    /// Returns the selected faces whose neighbors are all selected. The boundary of the
+    /// mesh doesn't count as an unselected neighbor.
+    fn shrink_faces(&self, selection: &Selection<T::F>) -> Selection<T::F>
+    where
+        T: MeshTypeHalfEdge,
+    {
+        selection
+            .iter()
+            .filter(|f| {
+                self.face(*f).edges(self).all(|e| {
+                    let twin = e.twin(self);
+                    twin.is_boundary_self() || selection.contains(twin.face_id())
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the half-edges of the selected faces whose twins don't belong to a
+    /// selected face. See `MeshTopology::region_boundary` to get them as loops.
+    fn face_selection_boundary(&self, selection: &Selection<T::F>) -> Selection<T::E>
+    where
+        T: MeshTypeHalfEdge,
+    {
+        selection
+            .iter()
+            .flat_map(|f| self.face(f).edges(self).collect::<Vec<_>>())
+            .filter(|e| {
+                let twin = e.twin(self);
+                twin.is_boundary_self() || !selection.contains(twin.face_id())
+            })
+            .map(|e| e.id())
+            .collect()
+    }
+
+    /// Returns the faces connected to `seed` across edges whose dihedral angle, i.e., the
+    /// angle between the normals of the two faces, is at most `max_angle` (in radians).
+    /// This selects smooth or flat regions, e.g., the side of a cylinder without the caps.
+    fn select_linked_flat(&self, seed: T::F, max_angle: T::S) -> Selection<T::F>
+    where
+        T: MeshTypeHalfEdge + MeshType3D,
+    {
+        let min_cos = max_angle.min(T::S::PI).cos();
+        let normal = |f: T::F| {
+            let n = self.face(f).vertices(self).map(|v| v.pos()).normal();
+            let len = n.length();
+            if len > T::S::EPS {
+                n / len
+            } else {
+                n
+            }
+        };
+        let mut selection = Selection::new();
+        selection.insert(seed);
+        let mut stack = vec![(seed, normal(seed))];
+        while let Some((f, n)) = stack.pop() {
+            for e in self.face(f).edges(self) {
+                let twin = e.twin(self);
+                if twin.is_boundary_self() || selection.contains(twin.face_id()) {
+                    continue;
+                }
+                let m = normal(twin.face_id());
+                if n.dot(&m) >= min_cos {
+                    selection.insert(twin.face_id());
+                    stack.push((twin.face_id(), m));
+                }
+            }
+        }
+        selection
+    }
+
+    /// Returns the vertices of the selected faces.
+    fn vertices_of_faces(&self, selection: &Selection<T::F>) -> Selection<T::V> {
+        selection
+            .iter()
+            .flat_map(|f| self.face(f).vertex_ids(self).collect::<Vec<_>>())
+            .collect()
+    }
+
+    /// Returns the half-edges of the selected faces.
+    fn edges_of_faces(&self, selection: &Selection<T::F>) -> Selection<T::E> {
+        selection
+            .iter()
+            .flat_map(|f| self.face(f).edge_ids(self).collect::<Vec<_>>())
+            .collect()
+    }
+
+    /// Returns the faces whose vertices are all selected.
+    fn faces_of_vertices(&self, selection: &Selection<T::V>) -> Selection<T::F> {
+        self.select_faces(|f| f.vertex_ids(self).all(|v| selection.contains(v)))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    /// A 4x4 grid of unit squares centered at the origin.
+    fn grid() -> Mesh3d64 {
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        for y in 0..5 {
+            for x in 0..5 {
+                builder.add_vertex(VertexPayloadPNU::from_pos(Vec3::new(
+                    x as f64 - 2.0,
+                    y as f64 - 2.0,
+                    0.0,
+                )));
+            }
+        }
+        for y in 0..4 {
+            for x in 0..4 {
+                let i = y * 5 + x;
+                builder.add_face([i, i + 1, i + 6, i + 5]);
+            }
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_mesh_selection() {
+        let mesh = grid();
+        let center = mesh.select_vertices(|v| v.pos().norm() < 1e-9);
+        assert_eq!(center.len(), 1);
+
+        // growing and shrinking by adjacency
+        let ring = mesh.grow_vertices(&center);
+        assert_eq!(ring.len(), 5);
+        let grown = mesh.grow_vertices(&ring);
+        assert_eq!(grown.len(), 13);
+        assert_eq!(mesh.shrink_vertices(&grown), ring);
+        assert_eq!(mesh.vertex_selection_boundary(&grown), &grown - &ring);
+        assert!(mesh.shrink_vertices(&center).is_empty());
+        assert_eq!(mesh.invert_vertices(&center).len(), 24);
+
+        // the four faces around the center
+        let inner = mesh.select_faces(|f| f.centroid(&mesh).norm() < 1.0);
+        assert_eq!(inner.len(), 4);
+        assert_eq!(mesh.vertices_of_faces(&inner).len(), 9);
+        assert_eq!(
+            mesh.faces_of_vertices(&mesh.vertices_of_faces(&inner)),
+            inner
+        );
+        assert_eq!(mesh.edges_of_faces(&inner).len(), 16);
+        let boundary = mesh.face_selection_boundary(&inner);
+        assert_eq!(boundary.len(), 8);
+        let region = mesh.region_boundary(&inner.to_vec());
+        assert_eq!(region.len(), 1);
+        assert_eq!(
+            region[0].edges.iter().copied().collect::<Selection<_>>(),
+            boundary
+        );
+
+        let more = mesh.grow_faces(&inner);
+        assert_eq!(more.len(), 12);
+        assert_eq!(mesh.shrink_faces(&more), inner);
+        assert_eq!(mesh.invert_faces(&more).len(), 4);
+
+        // the boundary of the mesh doesn't shrink the selection
+        let all = mesh.select_faces(|_| true);
+        assert_eq!(mesh.shrink_faces(&all), all);
+        assert_eq!(mesh.face_selection_boundary(&all).len(), 16);
+        assert_eq!(mesh.invert_faces(&inner), &all - &inner);
+        let edges = mesh.select_edges(|e| e.is_boundary_self());
+        assert_eq!(edges.len(), 16);
+    }
+
+    #[test]
+    fn test_select_linked_flat() {
+        let mesh = grid();
+        let all = mesh.select_faces(|_| true);
+        assert_eq!(mesh.select_linked_flat(all.to_vec()[5], 0.01), all);
+
+        // the side of a cylinder is smooth, but the caps are at a right angle
+        let n = 12;
+        let cylinder = Mesh3d64::regular_frustum(1.0, 1.0, 2.0, n, false);
+        let side = cylinder.select_faces(|f| f.num_vertices(&cylinder) == 4);
+        assert_eq!(side.len(), n);
+        let seed = side.to_vec()[0];
+        let step = std::f64::consts::TAU / n as f64;
+        assert_eq!(cylinder.select_linked_flat(seed, step * 1.01), side);
+        assert_eq!(cylinder.select_linked_flat(seed, step * 0.99).len(), 1);
+        assert_eq!(cylinder.select_linked_flat(seed, 1.6).len(), n + 2);
+    }
+}
@@ -0,0 +1,286 @@
+use super::{EuclideanMeshType, MeshBasics, MeshBuilder};
+use crate::{
+    math::{HasPosition, Scalar, Vector},
+    mesh::{DefaultEdgePayload, DefaultFacePayload},
+};
+use std::collections::HashMap;
+
+/// A spatial hash of points with cells of size `epsilon`.
+/// Points within `epsilon` of each other are always in the same or in neighboring cells.
+#[derive(Clone, Debug)]
+pub(crate) struct WeldGrid<const D: usize, S: Scalar> {
+    epsilon: S,
+    cells: HashMap<[i64; D], Vec<usize>>,
+}
+
+impl<const D: usize, S: Scalar> WeldGrid<D, S> {
+    pub(crate) fn new(epsilon: S) -> Self {
+        assert!(epsilon > S::ZERO, "the weld epsilon must be positive");
+        Self {
+            epsilon,
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cell<Vec: Vector<S, D>>(&self, p: &Vec) -> [i64; D] {
+        std::array::from_fn(|i| {
+            let x = match i {
+                0 => p.x(),
+                1 => p.y(),
+                2 => p.z(),
+                3 => p.w(),
+                _ => panic!("welding is only supported up to 4 dimensions"),
+            };
+            (x / self.epsilon).to_f64().floor() as i64
+        })
+    }
+
+    /// Returns the index of a point within `epsilon` of `p` if there is one.
+    pub(crate) fn find<Vec: Vector<S, D>>(&self, p: &Vec, points: &[Vec]) -> Option<usize> {
+        self.find_by(p, points, |_| true)
+    }
+
+    /// Like `find`, but only returns points whose index is accepted by `filter`.
+    pub(crate) fn find_by<Vec: Vector<S, D>>(
+        &self,
+        p: &Vec,
+        points: &[Vec],
+        filter: impl Fn(usize) -> bool,
+    ) -> Option<usize> {
+        let c = self.cell(p);
+        let eps2 = self.epsilon * self.epsilon;
+        // iterate the 3^D neighboring cells
+        for k in 0..3usize.pow(D as u32) {
+            let mut key = c;
+            let mut rest = k;
+            for coord in key.iter_mut() {
+                *coord += (rest % 3) as i64 - 1;
+                rest /= 3;
+            }
+            if let Some(candidates) = self.cells.get(&key) {
+                if let Some(&i) = candidates
+                    .iter()
+                    .find(|&&i| filter(i) && points[i].distance_squared(p) <= eps2)
+                {
+                    return Some(i);
+                }
+            }
+        }
+        None
+    }
+
+    pub(crate) fn insert<Vec: Vector<S, D>>(&mut self, p: &Vec, index: usize) {
+        let c = self.cell(p);
+        self.cells.entry(c).or_default().push(index);
+    }
+}
+
+/// Collects vertices and faces given as cycles of vertex indices and builds a mesh from them.
+/// This is useful for generators that emit "polygon soups", e.g., marching cubes or lofts.
+///
+/// Use `with_weld` to merge vertices on the fly that are closer than `epsilon`
+/// instead of welding them after the mesh was built.
+#[derive(Clone, Debug)]
+pub struct SoupBuilder<const D: usize, T: EuclideanMeshType<D>> {
+    vertices: Vec<T::VP>,
+    positions: Vec<T::Vec>,
+    faces: Vec<Vec<usize>>,
+    tags: Vec<Option<&'static str>>,
+    weld: Option<WeldGrid<D, T::S>>,
+}
+
+impl<const D: usize, T: EuclideanMeshType<D>> Default for SoupBuilder<D, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const D: usize, T: EuclideanMeshType<D>> SoupBuilder<D, T> {
+    /// Creates a new, empty builder that doesn't merge any vertices.
+    pub fn new() -> Self {
+        Self {
+            vertices: Vec::new(),
+            positions: Vec::new(),
+            faces: Vec::new(),
+            tags: Vec::new(),
+            weld: None,
+        }
+    }
+
+    /// Enables welding: vertices closer than `epsilon` to an already added vertex are merged into it.
+    /// Must be called before adding any vertices.
+    pub fn with_weld(mut self, epsilon: T::S) -> Self {
+        assert!(
+            self.vertices.is_empty(),
+            "welding must be enabled before adding vertices"
+        );
+        self.weld = Some(WeldGrid::new(epsilon));
+        self
+    }
+
+    /// Returns the number of (unique) vertices.
+    pub fn num_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns the payload of the vertex with the given index.
+    pub fn vertex(&self, i: usize) -> &T::VP {
+        &self.vertices[i]
+    }
+
+    /// Returns the number of faces.
+    pub fn num_faces(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// Returns the cycle of vertex indices of the face with the given index.
+    pub fn face(&self, i: usize) -> &[usize] {
+        &self.faces[i]
+    }
+
+    /// Adds a vertex and returns its index.
+    /// When welding is enabled, this might return the index of an existing vertex instead.
+    pub fn add_vertex(&mut self, vp: T::VP) -> usize {
+        let p = *vp.pos();
+        if let Some(grid) = &self.weld {
+            if let Some(i) = grid.find(&p, &self.positions) {
+                return i;
+            }
+        }
+        let i = self.vertices.len();
+        if let Some(grid) = &mut self.weld {
+            grid.insert(&p, i);
+        }
+        self.vertices.push(vp);
+        self.positions.push(p);
+        i
+    }
+
+    /// Adds a face given by the (ccw) cycle of vertex indices.
+    /// Consecutive duplicates (e.g., caused by welding) are removed and faces
+    /// with less than 3 remaining vertices are dropped.
+    /// Returns whether the face was added.
+    pub fn add_face(&mut self, cycle: impl IntoIterator<Item = usize>) -> bool {
+        self.push_face(cycle, None)
+    }
+
+    /// Like `add_face`, but records the provenance `tag` in the payload of the face.
+    pub fn add_tagged_face(
+        &mut self,
+        cycle: impl IntoIterator<Item = usize>,
+        tag: &'static str,
+    ) -> bool {
+        self.push_face(cycle, Some(tag))
+    }
+
+    fn push_face(
+        &mut self,
+        cycle: impl IntoIterator<Item = usize>,
+        tag: Option<&'static str>,
+    ) -> bool {
+        let mut vs: Vec<usize> = Vec::new();
+        for v in cycle {
+            assert!(v < self.vertices.len(), "vertex index {} out of range", v);
+            if vs.last() != Some(&v) {
+                vs.push(v);
+            }
+        }
+        while vs.len() > 1 && vs.first() == vs.last() {
+            vs.pop();
+        }
+        if vs.len() < 3 {
+            return false;
+        }
+        self.faces.push(vs);
+        self.tags.push(tag);
+        true
+    }
+
+    /// Adds the vertices and connects them with a face.
+    pub fn add_polygon(&mut self, vp: impl IntoIterator<Item = T::VP>) -> bool {
+        let cycle: Vec<usize> = vp.into_iter().map(|vp| self.add_vertex(vp)).collect();
+        self.add_face(cycle)
+    }
+
+    /// Inserts the collected vertices and faces into the given mesh.
+    /// Returns the ids of the inserted faces.
+    pub fn insert_into(self, mesh: &mut T::Mesh) -> Vec<T::F>
+    where
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        let faces = mesh.insert_indexed_faces(self.vertices, self.faces);
+        for (f, tag) in faces.iter().zip(self.tags) {
+            if let Some(tag) = tag {
+                mesh.tag_faces([*f], tag);
+            }
+        }
+        faces
+    }
+
+    /// Builds a new mesh from the collected vertices and faces.
+    pub fn build(self) -> T::Mesh
+    where
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        let mut mesh = T::Mesh::default();
+        self.insert_into(&mut mesh);
+        mesh
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn cube_soup(builder: &mut SoupBuilder<3, MeshType3d64PNU>) {
+        // each face emits its own copy of the corners
+        let p = |x: f64, y: f64, z: f64| VertexPayloadPNU::from_pos(Vec3::new(x, y, z));
+        let quads = [
+            [(0., 0., 0.), (0., 1., 0.), (1., 1., 0.), (1., 0., 0.)],
+            [(0., 0., 1.), (1., 0., 1.), (1., 1., 1.), (0., 1., 1.)],
+            [(0., 0., 0.), (1., 0., 0.), (1., 0., 1.), (0., 0., 1.)],
+            [(0., 1., 0.), (0., 1., 1.), (1., 1., 1.), (1., 1., 0.)],
+            [(0., 0., 0.), (0., 0., 1.), (0., 1., 1.), (0., 1., 0.)],
+            [(1., 0., 0.), (1., 1., 0.), (1., 1., 1.), (1., 0., 1.)],
+        ];
+        for q in quads {
+            // jitter the positions a bit so welding has to be tolerant
+            builder.add_polygon(q.map(|(x, y, z)| p(x + 1e-9, y - 1e-9, z)));
+        }
+    }
+
+    #[test]
+    fn test_soup_weld_cube() {
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new().with_weld(1e-6);
+        cube_soup(&mut builder);
+        assert_eq!(builder.num_vertices(), 8);
+        let mesh = builder.build();
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_vertices(), 8);
+        assert_eq!(mesh.num_faces(), 6);
+        assert_eq!(mesh.num_edges(), 24);
+        assert!(!mesh.is_open());
+    }
+
+    #[test]
+    fn test_soup_no_weld() {
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        cube_soup(&mut builder);
+        assert_eq!(builder.num_vertices(), 24);
+        let mesh = builder.build();
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_faces(), 6);
+        assert_eq!(mesh.islands().len(), 6);
+    }
+
+    #[test]
+    fn test_soup_degenerate_face() {
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new().with_weld(0.1);
+        let p = |x: f64| VertexPayloadPNU::from_pos(Vec3::new(x, 0.0, 0.0));
+        assert!(!builder.add_polygon([p(0.0), p(0.01), p(1.0)]));
+        assert_eq!(builder.num_faces(), 0);
+    }
+}
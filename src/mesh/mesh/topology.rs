@@ -1,8 +1,61 @@
-use crate::mesh::VertexBasics;
+use crate::{
+    math::{
+        HasPosition, Polygon, Scalar, TransformTrait, Vector, Vector3DIteratorExt,
+        VectorIteratorExt,
+    },
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, EuclideanMeshType, FaceBasics,
+        HalfEdge, MeshType3D, MeshTypeHalfEdge, SoupBuilder, VertexBasics,
+    },
+};
 
 use super::{MeshBasics, MeshType};
 use std::collections::{HashMap, HashSet, VecDeque};
 
+/// A closed loop of half-edges at the boundary of a set of faces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundaryLoop<T: MeshType> {
+    /// The vertices of the loop. `vertices[i]` is the origin of `edges[i]`.
+    pub vertices: Vec<T::V>,
+
+    /// The half-edges of the loop. They belong to the faces inside of the region,
+    /// so the loop runs in the same direction as the faces.
+    pub edges: Vec<T::E>,
+}
+
+impl<T: MeshTypeHalfEdge + MeshType3D> BoundaryLoop<T> {
+    /// Returns the loop rotated to the XY plane or `None` if a vertex is further than
+    /// `eps` from the plane through the centroid of the loop. The plane is perpendicular
+    /// to the summed normals of the adjacent faces inside the region, so outer loops are
+    /// counter-clockwise and the loops of holes are clockwise.
+    pub fn polygon(&self, mesh: &T::Mesh, eps: T::S) -> Option<T::Poly> {
+        let normal = self
+            .edges
+            .iter()
+            .filter_map(|e| mesh.edge(*e).face(mesh))
+            .map(|f| f.vertices(mesh).map(|v| v.pos()).normal())
+            .stable_sum();
+        if normal.length_squared() <= T::S::EPS {
+            return None;
+        }
+        let normal = normal.normalize();
+        let ps: Vec<T::Vec> = self
+            .vertices
+            .iter()
+            .map(|v| mesh.vertex(*v).pos())
+            .collect();
+        let center: T::Vec = ps.iter().copied().stable_mean();
+        if ps.iter().any(|p| (*p - center).dot(&normal).abs() > eps) {
+            return None;
+        }
+        let z_axis = T::Vec::from_xyz(T::S::ZERO, T::S::ZERO, T::S::ONE);
+        let rotation = <T::Trans as TransformTrait<T::S, 3>>::from_rotation_arc(normal, z_axis);
+        Some(<T::Poly as Polygon<T::Vec2>>::from_iter(
+            ps.iter().map(|p| rotation.apply(*p).vec2()),
+        ))
+    }
+}
+
 /// Methods concerned with mesh topology.
 pub trait MeshTopology<T: MeshType<Mesh = Self>>: MeshBasics<T> {
     /// Returns the shortest path (least number of edges) between two vertices
@@ -46,4 +99,392 @@ pub trait MeshTopology<T: MeshType<Mesh = Self>>: MeshBasics<T> {
 
         None
     }
+
+    /// Constructs the dual mesh: The centroid of each face becomes a vertex and each vertex
+    /// becomes a face connecting the centroids of the adjacent faces, e.g., the dual of a cube
+    /// is an octahedron and the dual of a geodesic icosahedron is a Goldberg polyhedron.
+    ///
+    /// On open meshes, the dual face of a boundary vertex is closed by the midpoints of the
+    /// two adjacent boundary edges and the boundary vertex itself, so the dual covers the same
+    /// surface and has the same boundary as the original mesh.
+    ///
+    /// Vertices without faces are ignored. Assumes a manifold mesh.
+    fn dual<const D: usize>(&self) -> Self
+    where
+        T: MeshTypeHalfEdge + EuclideanMeshType<D>,
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        let mut builder = SoupBuilder::<D, T>::new();
+        let centroids: HashMap<T::F, usize> = self
+            .faces()
+            .map(|f| {
+                let c: T::Vec = f.vertices(self).map(|v| v.pos()).stable_mean();
+                (f.id(), builder.add_vertex(T::VP::from_pos(c)))
+            })
+            .collect();
+        let mut midpoints: HashMap<T::E, usize> = HashMap::new();
+        let mut midpoint = |builder: &mut SoupBuilder<D, T>, e: &T::Edge| {
+            *midpoints.entry(e.id()).or_insert_with(|| {
+                let (a, b): (T::Vec, T::Vec) = (e.origin(self).pos(), e.target(self).pos());
+                builder.add_vertex(T::VP::from_pos((a + b) * T::S::HALF))
+            })
+        };
+
+        for v in self.vertices() {
+            // start behind the boundary if there is one such that the faces are contiguous
+            let incoming = v.edges_in(self).find(|e| e.is_boundary_self());
+            let Some(start) = incoming
+                .as_ref()
+                .map_or_else(|| v.edge(self), |e| Some(e.twin(self)))
+            else {
+                continue;
+            };
+            if start.is_boundary_self() {
+                continue;
+            }
+
+            // rotate counter-clockwise around the vertex
+            let mut cycle = Vec::new();
+            let mut e = start.clone();
+            loop {
+                if e.is_boundary_self() {
+                    cycle.push(midpoint(&mut builder, &e));
+                    cycle.push(builder.add_vertex(v.payload().clone()));
+                    break;
+                }
+                cycle.push(centroids[&e.face_id()]);
+                e = e.prev(self).twin(self);
+                if e.id() == start.id() {
+                    break;
+                }
+            }
+            if let Some(incoming) = incoming {
+                cycle.insert(0, midpoint(&mut builder, &incoming));
+            }
+            builder.add_face(cycle);
+        }
+
+        builder.build()
+    }
+
+    /// Returns the boundary of the region formed by the given `faces` as closed loops.
+    ///
+    /// Each loop consists of the half-edges of the selected faces whose twins are not
+    /// part of a selected face. The loops are ordered like the faces, i.e., outer
+    /// boundaries run in the direction of the faces and the boundaries of holes in the
+    /// opposite direction. Where the region touches itself at a single vertex, the loop
+    /// continues with the first boundary edge counter-clockwise around the vertex.
+    fn region_boundary(&self, faces: &[T::F]) -> Vec<BoundaryLoop<T>>
+    where
+        T: MeshTypeHalfEdge,
+    {
+        let selected: HashSet<T::F> = faces.iter().copied().collect();
+        let is_boundary = |e: &T::Edge| {
+            let twin = e.twin(self);
+            twin.is_boundary_self() || !selected.contains(&twin.face_id())
+        };
+
+        let mut visited = HashSet::new();
+        let mut loops = Vec::new();
+        for f in faces {
+            for start in self.face(*f).edges(self) {
+                if visited.contains(&start.id()) || !is_boundary(&start) {
+                    continue;
+                }
+                let mut boundary = BoundaryLoop {
+                    vertices: Vec::new(),
+                    edges: Vec::new(),
+                };
+                let mut e = start.clone();
+                loop {
+                    visited.insert(e.id());
+                    boundary.vertices.push(e.origin_id());
+                    boundary.edges.push(e.id());
+
+                    // rotate around the target until leaving the region
+                    let mut next = e.next(self);
+                    while !is_boundary(&next) {
+                        next = next.twin(self).next(self);
+                    }
+                    e = next;
+                    if e.id() == start.id() {
+                        break;
+                    }
+                }
+                loops.push(boundary);
+            }
+        }
+        loops
+    }
+
+    /// Returns the connectivity of the mesh as an undirected petgraph graph, e.g., to
+    /// run matchings, flows, or minimum spanning trees on the mesh.
+    ///
+    /// Each vertex becomes a node weighted with its id and payload. Each pair of
+    /// half-edges becomes one edge weighted with the id and payload of the half-edge
+    /// with the smaller id. Nodes are inserted in the order of `vertices`.
+    #[cfg(feature = "petgraph")]
+    fn to_petgraph<'a>(&'a self) -> petgraph::graph::UnGraph<(T::V, &'a T::VP), (T::E, &'a T::EP)>
+    where
+        T: MeshTypeHalfEdge + 'a,
+    {
+        let mut graph =
+            petgraph::graph::UnGraph::with_capacity(self.num_vertices(), self.num_edges() / 2);
+        let nodes: HashMap<T::V, petgraph::graph::NodeIndex> = self
+            .vertices()
+            .map(|v| (v.id(), graph.add_node((v.id(), v.payload()))))
+            .collect();
+        for e in self.edges() {
+            if e.id() < e.twin_id() {
+                graph.add_edge(
+                    nodes[&e.origin_id()],
+                    nodes[&e.target_id(self)],
+                    (e.id(), e.payload()),
+                );
+            }
+        }
+        graph
+    }
+
+    /// Constructs a mesh from a planar straight-line embedding of a graph, i.e., the
+    /// node weights are the vertex payloads and the edges only cross at shared nodes
+    /// when projected to the xy-plane.
+    ///
+    /// The faces are the bounded regions of the embedding and are oriented
+    /// counter-clockwise. Edges that don't separate two regions, e.g., bridges or
+    /// dangling edges, are dropped and nodes without faces become isolated vertices.
+    /// The edge weights are ignored.
+    #[cfg(feature = "petgraph")]
+    fn from_petgraph<const D: usize, E>(graph: &petgraph::graph::UnGraph<T::VP, E>) -> Self
+    where
+        T: MeshTypeHalfEdge + EuclideanMeshType<D>,
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        use petgraph::visit::EdgeRef;
+
+        let pos = |n: petgraph::graph::NodeIndex| -> T::Vec { *graph[n].pos() };
+        let angle = |from: petgraph::graph::NodeIndex, to: petgraph::graph::NodeIndex| {
+            let d = pos(to) - pos(from);
+            d.y().atan2(d.x())
+        };
+
+        // the neighbors of each node sorted counter-clockwise
+        let neighbors: Vec<Vec<petgraph::graph::NodeIndex>> = graph
+            .node_indices()
+            .map(|n| {
+                let mut ns = graph
+                    .edges(n)
+                    .map(|e| {
+                        if e.source() == n {
+                            e.target()
+                        } else {
+                            e.source()
+                        }
+                    })
+                    .filter(|m| *m != n)
+                    .collect::<Vec<_>>();
+                ns.sort_by(|a, b| angle(n, *a).partial_cmp(&angle(n, *b)).unwrap());
+                ns.dedup();
+                ns
+            })
+            .collect();
+
+        // trace the regions to the left of each directed edge
+        let mut builder = SoupBuilder::<D, T>::new();
+        let indices = graph
+            .node_indices()
+            .map(|n| builder.add_vertex(graph[n].clone()))
+            .collect::<Vec<_>>();
+        let mut visited = HashSet::new();
+        for u in graph.node_indices() {
+            for &v in &neighbors[u.index()] {
+                if visited.contains(&(u, v)) {
+                    continue;
+                }
+                let mut cycle = Vec::new();
+                let (mut a, mut b) = (u, v);
+                while visited.insert((a, b)) {
+                    cycle.push(a);
+                    // the next neighbor clockwise from `a` keeps the region on the left
+                    let ns = &neighbors[b.index()];
+                    let i = ns.iter().position(|n| *n == a).unwrap();
+                    let c = ns[(i + ns.len() - 1) % ns.len()];
+                    (a, b) = (b, c);
+                }
+                let area = T::S::stable_sum(cycle.iter().zip(cycle.iter().cycle().skip(1)).map(
+                    |(p, q)| {
+                        let (p, q) = (pos(*p), pos(*q));
+                        p.x() * q.y() - q.x() * p.y()
+                    },
+                ));
+                let simple = cycle.iter().collect::<HashSet<_>>().len() == cycle.len();
+                if cycle.len() >= 3 && simple && area > T::S::ZERO {
+                    builder.add_face(cycle.iter().map(|n| indices[n.index()]));
+                }
+            }
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_region_boundary() {
+        let cube = Mesh3d64::cube(1.0);
+        let all = cube.face_ids().collect::<Vec<_>>();
+        assert!(cube.region_boundary(&all).is_empty());
+        let loops = cube.region_boundary(&all[..1]);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(
+            loops[0].edges,
+            cube.face(all[0]).edge_ids(&cube).collect::<Vec<_>>()
+        );
+        let polygon = loops[0].polygon(&cube, 1e-9).unwrap();
+        assert!(polygon.signed_area().is_about(1.0, 1e-9));
+        // the complement has the same loop in the opposite direction
+        let loops = cube.region_boundary(&all[1..]);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].vertices.len(), 4);
+        for e in &loops[0].edges {
+            assert_eq!(cube.edge(*e).twin(&cube).face_id(), all[0]);
+        }
+        // the region isn't planar
+        assert!(loops[0].polygon(&cube, 1e-9).is_none());
+
+        // a 3x3 grid without the center cell
+        let grid = Mesh3d64::heightmap(3.0, 3.0, 3, |_, _| 0.0);
+        let ring = grid
+            .faces()
+            .filter(|f| {
+                let c = f.centroid(&grid);
+                c.x.abs() > 0.5 || c.z.abs() > 0.5
+            })
+            .map(|f| f.id())
+            .collect::<Vec<_>>();
+        let mut loops = grid.region_boundary(&ring);
+        assert_eq!(loops.len(), 2);
+        loops.sort_by_key(|l| l.vertices.len());
+        assert_eq!(loops[0].vertices.len(), 4);
+        assert_eq!(loops[1].vertices.len(), 12);
+        let hole = loops[0].polygon(&grid, 1e-9).unwrap();
+        let outer = loops[1].polygon(&grid, 1e-9).unwrap();
+        assert!(hole.signed_area().is_about(-1.0, 1e-9));
+        assert!(outer.signed_area().is_about(9.0, 1e-9));
+    }
+
+    #[test]
+    fn test_dual_closed() {
+        let cube = Mesh3d64::cube(1.0);
+        let octahedron = cube.dual();
+        assert!(octahedron.check().is_ok());
+        assert!(!octahedron.is_open());
+        assert_eq!(octahedron.num_vertices(), 6);
+        assert_eq!(octahedron.num_faces(), 8);
+        for f in octahedron.faces() {
+            assert_eq!(f.num_vertices(&octahedron), 3);
+            // faces point outwards
+            assert!(f.normal(&octahedron).dot(&f.centroid(&octahedron)) > 0.0);
+        }
+
+        let cube2 = octahedron.dual();
+        assert!(cube2.check().is_ok());
+        assert_eq!(cube2.num_vertices(), 8);
+        assert_eq!(cube2.num_faces(), 6);
+    }
+
+    #[test]
+    fn test_dual_open() {
+        // a 2x2 grid of unit squares
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        for y in 0..3 {
+            for x in 0..3 {
+                builder.add_vertex(VertexPayloadPNU::from_pos(Vec3::new(
+                    x as f64, y as f64, 0.0,
+                )));
+            }
+        }
+        for y in 0..2 {
+            for x in 0..2 {
+                let i = y * 3 + x;
+                builder.add_face([i, i + 1, i + 4, i + 3]);
+            }
+        }
+        let grid: Mesh3d64 = builder.build();
+
+        let dual = grid.dual();
+        assert!(dual.check().is_ok());
+        // 4 centroids, 8 boundary edge midpoints, and 8 boundary vertices
+        assert_eq!(dual.num_vertices(), 4 + 8 + 8);
+        assert_eq!(dual.num_faces(), 9);
+        // the dual covers the same area with the same orientation
+        let area: f64 = dual.faces().map(|f| f.normal(&dual).z).sum();
+        assert!(area.is_about(4.0, 1e-9));
+    }
+
+    #[test]
+    #[cfg(feature = "petgraph")]
+    fn test_petgraph() {
+        let cube = Mesh3d64::cube(1.0);
+        let graph = cube.to_petgraph();
+        assert_eq!(graph.node_count(), 8);
+        assert_eq!(graph.edge_count(), 12);
+        assert_eq!(petgraph::algo::connected_components(&graph), 1);
+        let lengths = graph.map(
+            |_, _| (),
+            |_, (e, _)| {
+                cube.edge(*e)
+                    .origin(&cube)
+                    .pos()
+                    .distance(&cube.edge(*e).target(&cube).pos())
+            },
+        );
+        let mst = petgraph::algo::min_spanning_tree(&lengths)
+            .filter(|e| matches!(e, petgraph::data::Element::Edge { .. }))
+            .count();
+        assert_eq!(mst, 7);
+        for e in graph.edge_indices() {
+            let (a, b) = graph.edge_endpoints(e).unwrap();
+            let (id, _) = graph[e];
+            assert_eq!(cube.edge(id).origin_id(), graph[a].0);
+            assert_eq!(cube.edge(id).target_id(&cube), graph[b].0);
+            assert_eq!(*graph[a].1.pos(), cube.vertex(graph[a].0).pos());
+        }
+
+        // a 3x3 grid with a dangling edge and an isolated node
+        let mut grid = petgraph::graph::UnGraph::<VertexPayloadPNU<f64, 3>, ()>::new_undirected();
+        let n = (0..9)
+            .map(|i| {
+                let p = Vec3::new((i % 3) as f64, (i / 3) as f64, 0.0);
+                grid.add_node(VertexPayloadPNU::from_pos(p))
+            })
+            .collect::<Vec<_>>();
+        for i in 0..9 {
+            if i % 3 < 2 {
+                grid.add_edge(n[i], n[i + 1], ());
+            }
+            if i < 6 {
+                grid.add_edge(n[i], n[i + 3], ());
+            }
+        }
+        let tail = grid.add_node(VertexPayloadPNU::from_pos(Vec3::new(3.0, 0.0, 0.0)));
+        grid.add_edge(n[2], tail, ());
+        grid.add_node(VertexPayloadPNU::from_pos(Vec3::new(5.0, 5.0, 0.0)));
+
+        let mesh = Mesh3d64::from_petgraph(&grid);
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_faces(), 4);
+        assert_eq!(mesh.num_vertices(), 11);
+        for f in mesh.faces() {
+            assert_eq!(f.num_vertices(&mesh), 4);
+            assert!(f.normal(&mesh).normalize().z.is_about(1.0, 1e-9));
+        }
+        // the round trip keeps the interior edges
+        assert_eq!(mesh.to_petgraph().edge_count(), 12);
+    }
 }
@@ -0,0 +1,145 @@
+use super::{MeshChecker, MeshType};
+use std::{
+    fmt::Display,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+};
+
+/// The reason why a transaction was rolled back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionError<E> {
+    /// The transaction returned an error.
+    Aborted(E),
+
+    /// The transaction succeeded, but left a mesh that was valid before in an inconsistent
+    /// state. Contains the message of `MeshChecker::check`.
+    Invalid(String),
+}
+
+impl<E: Display> Display for TransactionError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionError::Aborted(e) => write!(f, "transaction aborted: {}", e),
+            TransactionError::Invalid(msg) => {
+                write!(f, "transaction left an invalid mesh: {}", msg)
+            }
+        }
+    }
+}
+
+impl<E: Display + std::fmt::Debug> std::error::Error for TransactionError<E> {}
+
+/// Atomic modifications of meshes.
+pub trait MeshTransaction<T: MeshType<Mesh = Self>>: MeshChecker<T> {
+    /// Applies the builder operations in `f` to the mesh atomically.
+    ///
+    /// If `f` returns an error or breaks the mesh, i.e., the mesh passed `check` before but
+    /// doesn't afterwards, the mesh is rolled back to the state before the transaction, so
+    /// complex generators can't leave a half-built mesh behind. Meshes that were already
+    /// invalid before are only rolled back on errors. If `f` panics, the mesh is rolled
+    /// back before the panic continues.
+    ///
+    /// The operations are applied to the mesh directly and the rollback restores a copy
+    /// taken at the start. Hence, every transaction takes O(n) time and memory in the size
+    /// of the whole mesh, even if `f` only touches a few elements, plus another O(n) for
+    /// `check`. Prefer few transactions with many operations over many small ones.
+    fn transaction<R, E>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<R, E>,
+    ) -> Result<R, TransactionError<E>> {
+        let backup = self.clone();
+        let result = match catch_unwind(AssertUnwindSafe(|| f(self))) {
+            Ok(result) => result,
+            Err(panic) => {
+                *self = backup;
+                resume_unwind(panic);
+            }
+        };
+        match result {
+            Ok(r) => match self.check() {
+                // the transaction didn't break the mesh if it was invalid before
+                Err(msg) if backup.check().is_ok() => {
+                    *self = backup;
+                    Err(TransactionError::Invalid(msg))
+                }
+                _ => Ok(r),
+            },
+            Err(e) => {
+                *self = backup;
+                Err(TransactionError::Aborted(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn positions(mesh: &Mesh3d64) -> Vec<Vec3<f64>> {
+        mesh.vertices().map(|v| v.pos()).collect()
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        let before = positions(&mesh);
+
+        // successful transactions are kept
+        let r = mesh.transaction(|tx| -> Result<usize, ()> {
+            let f = tx.face_ids().next().unwrap();
+            tx.extrude_face(f, NdAffine::from_translation(Vec3::new(0.0, 0.0, 1.0)));
+            Ok(tx.num_faces())
+        });
+        assert_eq!(r, Ok(10));
+        assert_eq!(mesh.num_faces(), 10);
+        let extruded = positions(&mesh);
+
+        // failing transactions are rolled back
+        let r = mesh.transaction(|tx| {
+            tx.translate(&Vec3::new(1.0, 0.0, 0.0));
+            tx.insert_regular_star(1.0, 1.0, 5);
+            Err::<(), _>("out of material")
+        });
+        assert_eq!(r, Err(TransactionError::Aborted("out of material")));
+        assert_eq!(
+            r.unwrap_err().to_string(),
+            "transaction aborted: out of material"
+        );
+        assert_eq!(positions(&mesh), extruded);
+        assert!(mesh.check().is_ok());
+
+        // so are transactions that corrupt the mesh
+        let r = mesh.transaction(|tx| {
+            let e = tx.edge_ids().next().unwrap();
+            tx.edge_mut(e).set_twin(e);
+            Ok::<(), ()>(())
+        });
+        assert!(matches!(r, Err(TransactionError::Invalid(_))));
+        assert_eq!(positions(&mesh), extruded);
+        assert!(mesh.check().is_ok());
+
+        // and transactions that panic
+        let mut cube = Mesh3d64::cube(1.0);
+        let caught = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            cube.transaction(|tx| {
+                tx.translate(&Vec3::new(0.0, 5.0, 0.0));
+                tx.vertex(1000);
+                Ok::<(), ()>(())
+            })
+        }));
+        assert!(caught.is_err());
+        assert_eq!(positions(&cube), before);
+
+        // meshes that were invalid before aren't blamed on the transaction
+        let e = cube.edge_ids().next().unwrap();
+        cube.edge_mut(e).set_twin(e);
+        assert!(cube.check().is_err());
+        let r = cube.transaction(|tx| {
+            tx.translate(&Vec3::new(0.0, 5.0, 0.0));
+            Ok::<(), ()>(())
+        });
+        assert_eq!(r, Ok(()));
+        assert_ne!(positions(&cube), before);
+    }
+}
@@ -1,9 +1,49 @@
 use super::{basics::MeshBasics, EuclideanMeshType};
 use crate::{
-    math::Transformable,
+    math::{HasPosition, Scalar, TransformTrait, Transformable, Vector, Vector3D},
     mesh::{EdgeBasics, FaceBasics, VertexBasics},
+    util::Selection,
 };
 
+/// Returns the rotation by `angle` radians in the plane spanned by the orthonormal vectors
+/// `p` and `r` that turns `p` towards `r`.
+pub(crate) fn plane_rotation<const D: usize, T: EuclideanMeshType<D>>(
+    p: T::Vec,
+    r: T::Vec,
+    angle: T::S,
+) -> T::Trans {
+    // normalize to (-pi, pi] and rotate in two steps, since the rotation arc
+    // is ambiguous for half a turn and imprecise for tiny angles
+    let angle = angle.sin().atan2(angle.cos());
+    if angle.abs() <= T::S::EPS {
+        return T::Trans::identity();
+    }
+    let at = |a: T::S| p * a.cos() + r * a.sin();
+    let mid = at(angle * T::S::HALF);
+    T::Trans::from_rotation_arc(p, mid).chain(&T::Trans::from_rotation_arc(mid, at(angle)))
+}
+
+/// The point of a mesh that `TransformableMesh::set_pivot` moves to the origin.
+/// The y-axis is considered to point up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PivotMode {
+    /// The center of the bounding box.
+    #[default]
+    Center,
+
+    /// The center of the bottom of the bounding box, e.g., to place the mesh on the floor.
+    BottomCenter,
+
+    /// The center of the top of the bounding box, e.g., to hang the mesh from the ceiling.
+    TopCenter,
+
+    /// The corner of the bounding box with the smallest coordinates.
+    Min,
+
+    /// The mean of the vertex positions, see `MeshPosition::centroid`.
+    Centroid,
+}
+
 /// Methods for transforming meshes.
 pub trait TransformableMesh<const D: usize, T: EuclideanMeshType<D, Mesh = Self>>:
     MeshBasics<T>
@@ -28,6 +68,15 @@ where
         self
     }
 
+    /// Transforms only the selected vertices, e.g., to move a part of the mesh.
+    /// The payloads of edges, faces, and the mesh are kept.
+    fn transform_selected(&mut self, t: &T::Trans, selection: &Selection<T::V>) -> &mut Self {
+        for v in selection.iter() {
+            self.vertex_mut(v).payload_mut().transform(t);
+        }
+        self
+    }
+
     /// Returns a transformed clone of the mesh
     fn transformed(&self, t: &T::Trans) -> Self {
         let mut mesh = self.clone();
@@ -100,4 +149,232 @@ where
         mesh.scale(scale);
         mesh
     }
+
+    /// Applies a separate transformation to each vertex payload, e.g., to deform the mesh
+    /// non-linearly while keeping the normals of the vertices roughly aligned.
+    fn transform_vertices(&mut self, f: impl Fn(&T::VP) -> T::Trans) -> &mut Self {
+        for v in self.vertices_mut() {
+            let t = f(v.payload());
+            v.payload_mut().transform(&t);
+        }
+        self
+    }
+
+    /// Twists the mesh around the `axis` through the origin. Each vertex is rotated
+    /// counter-clockwise around the axis by `angle_per_unit` times its height along the axis.
+    fn twist(&mut self, axis: T::Vec, angle_per_unit: T::S) -> &mut Self
+    where
+        T::Vec: Vector3D<S = T::S>,
+    {
+        let a = axis.normalize();
+        let seed = if a.x().abs() < T::S::HALF {
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)
+        } else {
+            T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO)
+        };
+        let u = (seed - a * seed.dot(&a)).normalize();
+        let w = a.cross(&u);
+        self.transform_vertices(|vp| {
+            plane_rotation::<D, T>(u, w, vp.pos().dot(&a) * angle_per_unit)
+        })
+    }
+
+    /// Tapers the mesh along the `axis` through the origin. The distance of each vertex
+    /// from the axis is scaled by `1 + factor_per_unit * h` where `h` is its height along the axis.
+    fn taper(&mut self, axis: T::Vec, factor_per_unit: T::S) -> &mut Self {
+        let a = axis.normalize();
+        self.transform_vertices(|vp| {
+            let h = vp.pos().dot(&a);
+            let s = T::S::ONE + factor_per_unit * h;
+            T::Trans::from_scale(T::Vec::splat(s))
+                .chain(&T::Trans::from_translation(a * ((T::S::ONE - s) * h)))
+        })
+    }
+
+    /// Bends the mesh along the `axis` through the origin towards `direction`, which must not
+    /// be parallel to the axis. The axis becomes a circular arc that turns by `angle_per_unit`
+    /// radians per unit length, i.e., with radius `1 / angle_per_unit`. Lengths along the axis
+    /// are preserved and the cross-sections stay perpendicular to the arc.
+    fn bend(&mut self, axis: T::Vec, direction: T::Vec, angle_per_unit: T::S) -> &mut Self {
+        if angle_per_unit.abs() <= T::S::EPS {
+            return self;
+        }
+        let a = axis.normalize();
+        let d = (direction - a * direction.dot(&a)).normalize();
+        let center = d / angle_per_unit;
+        self.transform_vertices(|vp| {
+            let h = vp.pos().dot(&a);
+            T::Trans::from_translation(-(a * h) - center)
+                .chain(&plane_rotation::<D, T>(a, d, h * angle_per_unit))
+                .chain(&T::Trans::from_translation(center))
+        })
+    }
+
+    /// Translates the mesh such that the point given by `mode` lies at the origin.
+    fn set_pivot(&mut self, mode: PivotMode) -> &mut Self
+    where
+        T::Vec: Vector3D<S = T::S>,
+    {
+        let Some((min, max)) = self.bounding_box() else {
+            return self;
+        };
+        let center = (min + max) * T::S::HALF;
+        let pivot = match mode {
+            PivotMode::Center => center,
+            PivotMode::BottomCenter => T::Vec::from_xyz(center.x(), min.y(), center.z()),
+            PivotMode::TopCenter => T::Vec::from_xyz(center.x(), max.y(), center.z()),
+            PivotMode::Min => min,
+            PivotMode::Centroid => self.centroid(),
+        };
+        self.translate(&-pivot)
+    }
+
+    /// Translates the mesh such that the center of its bounding box lies at the origin.
+    fn center_to_origin(&mut self) -> &mut Self
+    where
+        T::Vec: Vector3D<S = T::S>,
+    {
+        self.set_pivot(PivotMode::Center)
+    }
+
+    /// Centers the mesh at the origin and scales it to fit into the box from `-0.5` to `0.5`.
+    /// If `keep_aspect` is set, the mesh is scaled uniformly such that its longest side has
+    /// length 1. Otherwise, each side is scaled to length 1 separately, except for flat
+    /// sides which can't be stretched.
+    fn normalize_to_unit_box(&mut self, keep_aspect: bool) -> &mut Self
+    where
+        T::Vec: Vector3D<S = T::S>,
+    {
+        let Some((min, max)) = self.bounding_box() else {
+            return self;
+        };
+        let size = (max - min).to_array();
+        let longest = size[0].max(size[1]).max(size[2]);
+        if longest <= T::S::EPS {
+            return self.center_to_origin();
+        }
+        let [x, y, z] = size.map(|s| {
+            if keep_aspect {
+                T::S::ONE / longest
+            } else if s <= T::S::EPS {
+                T::S::ONE
+            } else {
+                T::S::ONE / s
+            }
+        });
+        self.center_to_origin().scale(&T::Vec::from_xyz(x, y, z))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_twist_taper() {
+        let mut bar = Mesh3d64::cuboid(Vec3::new(1.0, 2.0, 1.0));
+        bar.translate(&Vec3::new(0.0, 1.0, 0.0));
+        let twisted = bar
+            .clone()
+            .twist(Vec3::new(0.0, 1.0, 0.0), PI / 4.0)
+            .clone();
+        for (v, w) in bar.vertices().zip(twisted.vertices()) {
+            let (p, q) = (v.pos(), w.pos());
+            // the height and distance to the axis are preserved
+            assert!(q.y.is_about(p.y, 1e-9));
+            assert!(q.xz().norm().is_about(p.xz().norm(), 1e-9));
+            let angle = q.z.atan2(q.x) - p.z.atan2(p.x);
+            let expected = -p.y * PI / 4.0;
+            assert!((angle - expected).sin().abs() < 1e-9);
+        }
+
+        let tapered = bar.clone().taper(Vec3::new(0.0, 1.0, 0.0), -0.5).clone();
+        for (v, w) in bar.vertices().zip(tapered.vertices()) {
+            let (p, q) = (v.pos(), w.pos());
+            assert!(q.y.is_about(p.y, 1e-9));
+            assert!(q.xz().is_about(&(p.xz() * (1.0 - 0.5 * p.y)), 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_transform_selected() {
+        let cube = Mesh3d64::cube(1.0);
+        let top = cube.select_vertices(|v| v.pos().y > 0.0);
+        assert_eq!(top.len(), 4);
+        let mut moved = cube.clone();
+        moved.transform_selected(&NdAffine::from_translation(Vec3::new(0.0, 1.0, 0.0)), &top);
+        for v in cube.vertices() {
+            let shift = if top.contains(v.id()) { 1.0 } else { 0.0 };
+            assert_eq!(
+                moved.vertex(v.id()).pos(),
+                v.pos() + Vec3::new(0.0, shift, 0.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_pivot() {
+        let mut mesh = Mesh3d64::cuboid(Vec3::new(2.0, 4.0, 1.0));
+        mesh.translate(&Vec3::new(5.0, 3.0, -1.0));
+        let (min, max) = mesh.bounding_box().unwrap();
+        assert!(min.is_about(&Vec3::new(4.0, 1.0, -1.5), 1e-12));
+        assert!(max.is_about(&Vec3::new(6.0, 5.0, -0.5), 1e-12));
+        assert!(Mesh3d64::default().bounding_box().is_none());
+
+        let bounds = |mesh: &Mesh3d64| mesh.bounding_box().unwrap();
+        let (min, max) = bounds(mesh.clone().center_to_origin());
+        assert!(min.is_about(&Vec3::new(-1.0, -2.0, -0.5), 1e-12));
+        assert!(max.is_about(&-min, 1e-12));
+        let (min, max) = bounds(mesh.clone().set_pivot(PivotMode::BottomCenter));
+        assert!(min.is_about(&Vec3::new(-1.0, 0.0, -0.5), 1e-12));
+        assert!(max.is_about(&Vec3::new(1.0, 4.0, 0.5), 1e-12));
+        let (_, max) = bounds(mesh.clone().set_pivot(PivotMode::TopCenter));
+        assert!(max.y.is_about(0.0, 1e-12));
+        let (min, _) = bounds(mesh.clone().set_pivot(PivotMode::Min));
+        assert!(min.is_about(&Vec3::zeros(), 1e-12));
+        assert!(mesh
+            .clone()
+            .set_pivot(PivotMode::Centroid)
+            .centroid()
+            .is_about(&Vec3::zeros(), 1e-12));
+
+        let (min, max) = bounds(mesh.clone().normalize_to_unit_box(true));
+        assert!(min.is_about(&Vec3::new(-0.25, -0.5, -0.125), 1e-12));
+        assert!(max.is_about(&-min, 1e-12));
+        let (min, max) = bounds(mesh.clone().normalize_to_unit_box(false));
+        assert!(min.is_about(&Vec3::splat(-0.5), 1e-12));
+        assert!(max.is_about(&Vec3::splat(0.5), 1e-12));
+    }
+
+    #[test]
+    fn test_bend() {
+        // a thin strip along x bent into a quarter circle of radius 2 towards y
+        let mut strip = Mesh3d64::cuboid(Vec3::new(PI, 0.1, 0.1));
+        strip.translate(&Vec3::new(PI / 2.0, 0.0, 0.0));
+        let bent = strip
+            .clone()
+            .bend(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 0.5)
+            .clone();
+        let center = Vec3::new(0.0, 2.0, 0.0);
+        for (v, w) in strip.vertices().zip(bent.vertices()) {
+            let (p, q) = (v.pos(), w.pos());
+            assert!(q.z.is_about(p.z, 1e-9));
+            // the distance to the center of the bend depends on the offset towards it
+            assert!((q - center).xy().norm().is_about(2.0 - p.y, 1e-9));
+        }
+        // the end of the strip points along y
+        assert!(bent.vertices().all(|v| v.pos().x <= 2.0 + 0.05 + 1e-9));
+
+        // no curvature means no change
+        let straight = strip
+            .clone()
+            .bend(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 0.0)
+            .clone();
+        assert!(straight
+            .vertices()
+            .zip(strip.vertices())
+            .all(|(v, w)| v.pos().is_about(&w.pos(), 1e-12)));
+    }
 }
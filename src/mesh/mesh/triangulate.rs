@@ -1,12 +1,92 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
 
 use super::{basics::MeshBasics, MeshType, MeshType3D};
 use crate::{
-    math::{HasNormal, IndexType, Vector},
-    mesh::{Face3d, FaceBasics, Triangulation, VertexBasics},
-    tesselate::{triangulate_face, TesselationMeta, TriangulationAlgorithm},
+    math::{HasNormal, HasPosition, IndexType, Vector},
+    mesh::{
+        build_meshlets, CornerPayload, EdgeBasics, Face3d, FaceBasics, Meshlet, Triangulation,
+        VertexBasics,
+    },
+    tesselate::{
+        delaunay_refinement, find_holes, triangulate_budgeted, triangulate_face,
+        triangulate_face_with_holes, validate_face, DelaunayRefinement, TesselationError,
+        TesselationMeta, TesselationOptions, TriangulationAlgorithm,
+    },
 };
 
+/// Duplicates the vertices of each face of the triangulation `raw` with the face ranges
+/// `ranges` and sets their normals to the normal of the face.
+pub(crate) fn flat_normal_vertices<T: MeshType3D>(
+    mesh: &T::Mesh,
+    raw: &[T::V],
+    ranges: Vec<(T::F, Range<usize>)>,
+) -> (Vec<T::V>, Vec<T::VP>)
+where
+    T::VP: HasNormal<3, T::Vec, S = T::S>,
+{
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    for (f, range) in ranges {
+        let f = mesh.face(f);
+        let face_normal = Face3d::normal(f, mesh).normalize();
+        let mut id_map: HashMap<T::V, T::V> = HashMap::new();
+        // generate a new list of vertices (full duplication)
+        f.vertices(mesh).for_each(|v| {
+            let mut p = v.payload().clone();
+            id_map.insert(v.id(), IndexType::new(vertices.len()));
+            p.set_normal(face_normal);
+            vertices.push(p);
+        });
+        indices.extend(raw[range].iter().map(|v| id_map[v]));
+    }
+
+    (indices, vertices)
+}
+
+/// Applies the attributes of the face corners to the vertices of the triangulation `raw`
+/// with the face ranges `ranges` and only duplicates vertices where their corners differ.
+pub(crate) fn corner_vertices<T: MeshType3D>(
+    mesh: &T::Mesh,
+    raw: &[T::V],
+    ranges: Vec<(T::F, Range<usize>)>,
+) -> (Vec<T::V>, Vec<T::VP>)
+where
+    T::EP: CornerPayload<T::VP>,
+{
+    let mut vertices: Vec<T::VP> = Vec::new();
+    let mut indices = Vec::new();
+    // the exported copies of each vertex
+    let mut copies: HashMap<T::V, Vec<T::V>> = HashMap::new();
+
+    for (f, range) in ranges {
+        let f = mesh.face(f);
+        let mut id_map = HashMap::new();
+        for e in f.edges(mesh) {
+            let v = e.origin(mesh);
+            let mut p = v.payload().clone();
+            e.payload().apply_corner(&mut p);
+            let copies = copies.entry(v.id()).or_default();
+            let i = match copies.iter().find(|i| vertices[i.index()] == p) {
+                Some(i) => *i,
+                None => {
+                    let i = IndexType::new(vertices.len());
+                    vertices.push(p);
+                    copies.push(i);
+                    i
+                }
+            };
+            id_map.insert(v.id(), i);
+        }
+        indices.extend(raw[range].iter().map(|v| id_map[v]));
+    }
+
+    (indices, vertices)
+}
+
 /// Methods for transforming meshes.
 pub trait Triangulateable<T: MeshType<Mesh = Self>>: MeshBasics<T> {
     /// convert the mesh to triangles and get all indices to do so.
@@ -20,13 +100,160 @@ pub trait Triangulateable<T: MeshType<Mesh = Self>>: MeshBasics<T> {
         T: MeshType3D,
     {
         let mut indices = Vec::new();
+        self.triangulate_into(&mut Triangulation::new(&mut indices), algorithm, meta);
+        let vs = self.dense_vertices(&mut indices);
+        (indices, vs)
+    }
+
+    /// Like `triangulate`, but schedules and post-processes the triangulation as requested
+    /// by `options`, e.g., to limit the time spent on high-quality triangulations or to
+    /// reorder the triangles for the vertex cache of the GPU.
+    fn triangulate_with_options(
+        &self,
+        algorithm: TriangulationAlgorithm,
+        options: &TesselationOptions,
+        meta: &mut TesselationMeta<T::V>,
+    ) -> (Vec<T::V>, Vec<T::VP>)
+    where
+        T: MeshType3D,
+    {
+        let mut indices = Vec::new();
+        self.triangulate_into_with_options(
+            &mut Triangulation::new(&mut indices),
+            algorithm,
+            options,
+            meta,
+        );
+        let vs = self.dense_vertices(&mut indices);
+        options.post_process(&mut indices, vs.len());
+        (indices, vs)
+    }
+
+    /// Like `triangulate`, but validates all faces first using `validate_face` and returns
+    /// an error with the id of the first invalid face instead of panicking, e.g., when
+    /// triangulating user-provided outlines.
+    fn try_triangulate(
+        &self,
+        algorithm: TriangulationAlgorithm,
+        meta: &mut TesselationMeta<T::V>,
+    ) -> Result<(Vec<T::V>, Vec<T::VP>), TesselationError<T::F>>
+    where
+        T: MeshType3D,
+    {
+        if matches!(
+            algorithm,
+            TriangulationAlgorithm::EdgeFlip | TriangulationAlgorithm::Heuristic
+        ) {
+            return Err(TesselationError::UnsupportedAlgorithm(algorithm));
+        }
         for f in self.faces() {
-            let mut tri = Triangulation::new(&mut indices);
-            triangulate_face::<T>(f, self, &mut tri, algorithm, meta)
+            validate_face::<T>(f, self)?;
+        }
+        Ok(self.triangulate(algorithm, meta))
+    }
+
+    /// Appends the triangles of all faces to the given triangulation using the ids of the
+    /// vertices in the mesh. The triangles of each face are recorded in
+    /// `Triangulation::face_ranges`, so they can be mapped back to their faces.
+    fn triangulate_into(
+        &self,
+        tri: &mut Triangulation<T::V>,
+        algorithm: TriangulationAlgorithm,
+        meta: &mut TesselationMeta<T::V>,
+    ) where
+        T: MeshType3D,
+    {
+        for f in self.faces() {
+            tri.begin_face(f.id());
+            triangulate_face::<T>(f, self, tri, algorithm, meta);
 
             // TODO debug_assert!(tri.verify_full());
         }
+    }
 
+    /// Like `triangulate_into`, but triangulates the mesh with `triangulate_budgeted`
+    /// if the algorithm is `TriangulationAlgorithm::Auto` and `options` has a time budget.
+    /// Doesn't apply the post-processing of `options`, since it needs the final vertices.
+    fn triangulate_into_with_options(
+        &self,
+        tri: &mut Triangulation<T::V>,
+        algorithm: TriangulationAlgorithm,
+        options: &TesselationOptions,
+        meta: &mut TesselationMeta<T::V>,
+    ) where
+        T: MeshType3D,
+    {
+        match (algorithm, options.time_budget) {
+            (TriangulationAlgorithm::Auto, Some(budget)) => {
+                triangulate_budgeted::<T>(self, tri, budget, meta);
+            }
+            _ => self.triangulate_into(tri, algorithm, meta),
+        }
+    }
+
+    /// Triangulates the mesh using the ids of the vertices in the mesh and returns the
+    /// index buffer together with the range of the indices of each face.
+    fn triangulate_face_ranges(
+        &self,
+        algorithm: TriangulationAlgorithm,
+        meta: &mut TesselationMeta<T::V>,
+    ) -> (Vec<T::V>, Vec<(T::F, Range<usize>)>)
+    where
+        T: MeshType3D,
+    {
+        let mut indices = Vec::new();
+        let mut tri = Triangulation::new(&mut indices);
+        self.triangulate_into(&mut tri, algorithm, meta);
+        let ranges = tri.face_ranges().collect();
+        (indices, ranges)
+    }
+
+    /// Triangulates the mesh like `triangulate` and partitions the triangles into meshlets
+    /// with at most `max_vertices` vertices and `max_triangles` triangles each using
+    /// `build_meshlets`, e.g., for GPU-driven rendering pipelines.
+    fn triangulate_meshlets(
+        &self,
+        algorithm: TriangulationAlgorithm,
+        meta: &mut TesselationMeta<T::V>,
+        max_vertices: usize,
+        max_triangles: usize,
+    ) -> (Vec<Meshlet<T::V, T::Vec>>, Vec<T::VP>)
+    where
+        T: MeshType3D,
+    {
+        let (indices, vs) = self.triangulate(algorithm, meta);
+        let positions: Vec<T::Vec> = vs.iter().map(|v| *v.pos()).collect();
+        let meshlets = build_meshlets(&indices, &positions, max_vertices, max_triangles);
+        (meshlets, vs)
+    }
+
+    /// Like `triangulate`, but treats faces lying inside oppositely oriented faces in the
+    /// same plane as holes, e.g., the counters of font glyphs like "o" or "P". The holes
+    /// are found with `find_holes` and are cut out of their enclosing faces instead of
+    /// being triangulated themselves.
+    fn triangulate_with_holes(
+        &self,
+        algorithm: TriangulationAlgorithm,
+        meta: &mut TesselationMeta<T::V>,
+    ) -> (Vec<T::V>, Vec<T::VP>)
+    where
+        T: MeshType3D,
+    {
+        let holes = find_holes::<T>(self);
+        let is_hole: HashSet<T::F> = holes.values().flatten().copied().collect();
+        let mut indices = Vec::new();
+        let mut tri = Triangulation::new(&mut indices);
+        for f in self.faces() {
+            if is_hole.contains(&f.id()) {
+                continue;
+            }
+            let face_holes = holes
+                .get(&f.id())
+                .map(|hs| hs.iter().map(|h| self.face(*h)).collect::<Vec<_>>())
+                .unwrap_or_default();
+            tri.begin_face(f.id());
+            triangulate_face_with_holes::<T>(f, &face_holes, self, &mut tri, algorithm, meta);
+        }
         let vs = self.dense_vertices(&mut indices);
         (indices, vs)
     }
@@ -42,24 +269,192 @@ pub trait Triangulateable<T: MeshType<Mesh = Self>>: MeshBasics<T> {
         T: MeshType3D,
         T::VP: HasNormal<3, T::Vec, S = T::S>,
     {
-        let mut vertices = Vec::new();
+        let (raw, ranges) = self.triangulate_face_ranges(algorithm, meta);
+        flat_normal_vertices::<T>(self, &raw, ranges)
+    }
+
+    /// Triangulates the mesh and applies the attributes stored at the face corners
+    /// to the exported vertices. A vertex is only duplicated where its corners differ,
+    /// e.g., at uv seams or hard edges, so smooth regions keep sharing their vertices.
+    /// This doesn't duplicate the halfedge mesh but only the exported vertex buffer.
+    fn triangulate_with_corners(
+        &self,
+        algorithm: TriangulationAlgorithm,
+        meta: &mut TesselationMeta<T::V>,
+    ) -> (Vec<T::V>, Vec<T::VP>)
+    where
+        T: MeshType3D,
+        T::EP: CornerPayload<T::VP>,
+    {
+        let (raw, ranges) = self.triangulate_face_ranges(algorithm, meta);
+        corner_vertices::<T>(self, &raw, ranges)
+    }
+
+    /// Triangulates the mesh with refined delaunay triangulations, i.e., inserts Steiner
+    /// points into the faces until the triangles satisfy `refinement`. This gives
+    /// well-shaped triangles for large flat faces, e.g., for shading or physics.
+    /// The Steiner points are only added to the exported vertex buffer and their payloads
+    /// are interpolated from the vertices of their face.
+    fn triangulate_refined(&self, refinement: &DelaunayRefinement<T::S>) -> (Vec<T::V>, Vec<T::VP>)
+    where
+        T: MeshType3D,
+    {
+        let mut vertices: Vec<T::VP> = Vec::new();
         let mut indices = Vec::new();
+        let mut id_map: HashMap<T::V, T::V> = HashMap::new();
 
         for f in self.faces() {
-            let mut tri = Triangulation::new(&mut indices);
-            let face_normal = Face3d::normal(f, self).normalize();
-            let mut id_map = HashMap::new();
-            // generate a new list of vertices (full duplication)
-            f.vertices(self).for_each(|v| {
-                let mut p = v.payload().clone();
-                id_map.insert(v.id(), IndexType::new(vertices.len()));
-                p.set_normal(face_normal);
-                vertices.push(p)
-            });
-            triangulate_face::<T>(f, self, &mut tri, algorithm, meta);
-            tri.map_indices(&id_map);
+            let mut local = f
+                .vertices(self)
+                .map(|v| {
+                    *id_map.entry(v.id()).or_insert_with(|| {
+                        vertices.push(v.payload().clone());
+                        IndexType::new(vertices.len() - 1)
+                    })
+                })
+                .collect::<Vec<_>>();
+            let (triangles, steiner) = delaunay_refinement::<T>(f, self, refinement);
+            for vp in steiner {
+                local.push(IndexType::new(vertices.len()));
+                vertices.push(vp);
+            }
+            indices.extend(triangles.into_iter().map(|i| local[i]));
         }
 
         (indices, vertices)
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_face_ranges() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        mesh.insert_regular_star(0.5, 1.0, 8);
+        let mut indices = vec![0, 1, 2];
+        let mut tri = Triangulation::new(&mut indices);
+        mesh.triangulate_into(
+            &mut tri,
+            TriangulationAlgorithm::Auto,
+            &mut TesselationMeta::default(),
+        );
+        let ranges = tri.face_ranges::<usize>().collect::<Vec<_>>();
+        assert_eq!(ranges.len(), 7);
+        assert_eq!(ranges[0].1.start, 3);
+        for (f, range) in ranges {
+            let face = mesh.face(f);
+            assert_eq!(range.len(), (face.num_vertices(&mesh) - 2) * 3);
+            // the triangles only use the vertices of their face
+            let vs = face.vertex_ids(&mesh).collect::<Vec<_>>();
+            assert!(indices[range].iter().all(|v| vs.contains(v)));
+        }
+    }
+
+    #[test]
+    fn test_try_triangulate() {
+        let mut meta = TesselationMeta::default();
+        let mesh = Mesh3d64::cube(1.0);
+        let (is, _) = mesh
+            .try_triangulate(TriangulationAlgorithm::Delaunay, &mut meta)
+            .unwrap();
+        assert_eq!(is.len(), 36);
+        assert_eq!(
+            mesh.try_triangulate(TriangulationAlgorithm::EdgeFlip, &mut meta),
+            Err(TesselationError::UnsupportedAlgorithm(
+                TriangulationAlgorithm::EdgeFlip
+            ))
+        );
+
+        let mut polygon = |ps: &[(f64, f64, f64)]| {
+            let mut mesh = Mesh3d64::default();
+            mesh.insert_polygon(
+                ps.iter()
+                    .map(|(x, y, z)| VertexPayloadPNU::from_pos(Vec3::new(*x, *y, *z))),
+            );
+            let f = mesh.faces().next().unwrap().id();
+            (
+                mesh.try_triangulate(TriangulationAlgorithm::Auto, &mut meta),
+                f,
+            )
+        };
+        let (r, f) = polygon(&[
+            (0.0, 0.0, 0.0),
+            (1.0, 0.0, 0.0),
+            (1.0, 1.0, 0.0),
+            (0.0, 1.0, 0.0),
+        ]);
+        assert!(r.is_ok() && f == 0);
+        let (r, f) = polygon(&[(0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (2.0, 0.0, 0.0)]);
+        assert_eq!(r, Err(TesselationError::Degenerate { face: f }));
+        let (r, f) = polygon(&[
+            (0.0, 0.0, 0.0),
+            (1.0, 0.0, 0.0),
+            (1.0, 1.0, 0.5),
+            (0.0, 1.0, 0.0),
+        ]);
+        assert_eq!(r, Err(TesselationError::NotPlanar { face: f }));
+        let (r, f) = polygon(&[
+            (0.0, 0.0, 0.0),
+            (2.0, 2.0, 0.0),
+            (2.0, 0.0, 0.0),
+            (0.0, 1.0, 0.0),
+        ]);
+        assert_eq!(r, Err(TesselationError::SelfIntersecting { face: f }));
+        let (r, f) = polygon(&[
+            (0.0, 0.0, 0.0),
+            (1.0, 0.0, 0.0),
+            (1.0, 1.0, 0.0),
+            (0.0, 0.0, 0.0),
+            (0.0, 1.0, 0.0),
+        ]);
+        assert_eq!(r, Err(TesselationError::DuplicateVertices { face: f }));
+        assert_eq!(r.unwrap_err().face(), Some(f));
+    }
+
+    #[test]
+    #[cfg(feature = "fonts")]
+    fn test_triangulate_glyph_holes() {
+        for text in ["o", "g", "B", "A", "e", "8"] {
+            let mut mesh2d = Mesh2d64Curved::new();
+            Font::new(include_bytes!("../../../assets/Cochineal-Roman.otf"), 1.0)
+                .layout_text::<2, MeshType2d64PNUCurved>(text, &mut mesh2d);
+            let mesh = mesh2d.to_nd(0.01);
+            let holes = find_holes::<MeshType3d64PNU>(&mesh);
+            assert!(!holes.is_empty(), "{} has no holes", text);
+
+            // the area of the triangles is the area of the outlines minus their holes
+            let area = |f: &HalfEdgeFaceImpl<MeshType3d64PNU>| -> f64 {
+                let ps = f.vertices(&mesh).map(|v| v.pos()).collect::<Vec<_>>();
+                ps.into_iter().normal().length()
+            };
+            let expected = mesh.faces().map(&area).sum::<f64>()
+                - 2.0
+                    * holes
+                        .values()
+                        .flatten()
+                        .map(|h| area(mesh.face(*h)))
+                        .sum::<f64>();
+            let (is, vs) = mesh.triangulate_with_holes(
+                TriangulationAlgorithm::Auto,
+                &mut TesselationMeta::default(),
+            );
+            let is_hole = |f: &HalfEdgeFaceImpl<MeshType3d64PNU>| {
+                holes.values().flatten().any(|h| *h == f.id())
+            };
+            let outline = mesh.faces().find(|f| !is_hole(f)).unwrap();
+            let normal = outline.normal(&mesh).normalize();
+            let triangles = is
+                .chunks(3)
+                .map(|t| {
+                    let [a, b, c] = [t[0], t[1], t[2]].map(|i| *vs[i].pos());
+                    (b - a).cross(&(c - a)).dot(&normal) * 0.5
+                })
+                .collect::<Vec<_>>();
+            assert!(triangles.iter().all(|a| *a > 0.0));
+            assert!(triangles.iter().sum::<f64>().is_about(expected, 1e-9));
+        }
+    }
+}
@@ -0,0 +1,601 @@
+use super::{MeshDump, MeshType3D};
+use crate::{
+    math::{IndexType, Scalar, Vector, Vector3D},
+    mesh::{EdgeBasics, FaceBasics, HalfEdge, VertexBasics},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+};
+
+/// How serious a violated invariant is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The connectivity is consistent, but probably not what the builder intended,
+    /// so some operations might give unexpected results.
+    Warning,
+
+    /// The connectivity is broken. Most operations will panic or produce garbage.
+    Error,
+}
+
+/// The invariants checked by `MeshValidation::validate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Invariant {
+    /// A half-edge, vertex, or face references an element that doesn't exist.
+    MissingElement,
+
+    /// The twin of a half-edge doesn't exist or has another twin.
+    DanglingTwin,
+
+    /// The `prev` of the `next` or the `next` of the `prev` of a half-edge isn't the half-edge itself.
+    PrevNext,
+
+    /// Following `next` from a half-edge doesn't lead back to it.
+    OpenCycle,
+
+    /// The origin of the `next` of a half-edge isn't the origin of its twin.
+    TargetMismatch,
+
+    /// A half-edge and its `next` belong to different faces,
+    /// or a face references a half-edge of another face.
+    FaceMismatch,
+
+    /// The outgoing half-edge of a vertex starts at another vertex.
+    VertexEdge,
+
+    /// A face with less than three edges.
+    DegenerateFace,
+
+    /// Both half-edges of an edge are boundary edges, i.e., the edge isn't part of any face.
+    EdgeWithoutFace,
+
+    /// A vertex without any edges.
+    IsolatedVertex,
+
+    /// The vertices of a face don't lie in a common plane.
+    NonPlanarFace,
+
+    /// The faces of a closed island are oriented inwards, i.e., enclose a negative volume.
+    InconsistentIsland,
+}
+
+impl Invariant {
+    /// Returns how serious a violation of the invariant is.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Invariant::EdgeWithoutFace
+            | Invariant::IsolatedVertex
+            | Invariant::NonPlanarFace
+            | Invariant::InconsistentIsland => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl Display for Invariant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Invariant::MissingElement => "missing element",
+            Invariant::DanglingTwin => "dangling twin",
+            Invariant::PrevNext => "inconsistent prev/next",
+            Invariant::OpenCycle => "open cycle",
+            Invariant::TargetMismatch => "target mismatch",
+            Invariant::FaceMismatch => "face mismatch",
+            Invariant::VertexEdge => "vertex edge mismatch",
+            Invariant::DegenerateFace => "degenerate face",
+            Invariant::EdgeWithoutFace => "edge without face",
+            Invariant::IsolatedVertex => "isolated vertex",
+            Invariant::NonPlanarFace => "non-planar face",
+            Invariant::InconsistentIsland => "inconsistent island",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A violated invariant together with the offending elements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation<V: IndexType, E: IndexType, F: IndexType> {
+    /// The violated invariant
+    pub invariant: Invariant,
+
+    /// The severity of the violation, see `Invariant::severity`
+    pub severity: Severity,
+
+    /// The offending vertices
+    pub vertices: Vec<V>,
+
+    /// The offending half-edges
+    pub edges: Vec<E>,
+
+    /// The offending faces
+    pub faces: Vec<F>,
+
+    /// A human-readable description of the violation
+    pub message: String,
+}
+
+impl<V: IndexType, E: IndexType, F: IndexType> Display for Violation<V, E, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}: {}", severity, self.invariant, self.message)
+    }
+}
+
+/// A list of all violated invariants of a mesh, see `MeshValidation::validate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport<V: IndexType, E: IndexType, F: IndexType> {
+    /// The violations in the order they were found
+    pub violations: Vec<Violation<V, E, F>>,
+}
+
+impl<V: IndexType, E: IndexType, F: IndexType> Default for ValidationReport<V, E, F> {
+    fn default() -> Self {
+        Self {
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl<V: IndexType, E: IndexType, F: IndexType> ValidationReport<V, E, F> {
+    fn push(
+        &mut self,
+        invariant: Invariant,
+        vertices: Vec<V>,
+        edges: Vec<E>,
+        faces: Vec<F>,
+        message: String,
+    ) {
+        self.violations.push(Violation {
+            invariant,
+            severity: invariant.severity(),
+            vertices,
+            edges,
+            faces,
+            message,
+        });
+    }
+
+    /// Whether there are no errors. There might still be warnings.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Whether there are neither errors nor warnings.
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Iterates the violations with severity `Error`.
+    pub fn errors(&self) -> impl Iterator<Item = &Violation<V, E, F>> {
+        self.violations
+            .iter()
+            .filter(|v| v.severity == Severity::Error)
+    }
+
+    /// Iterates the violations with severity `Warning`.
+    pub fn warnings(&self) -> impl Iterator<Item = &Violation<V, E, F>> {
+        self.violations
+            .iter()
+            .filter(|v| v.severity == Severity::Warning)
+    }
+
+    /// Iterates the violations of the given invariant.
+    pub fn of(&self, invariant: Invariant) -> impl Iterator<Item = &Violation<V, E, F>> {
+        self.violations
+            .iter()
+            .filter(move |v| v.invariant == invariant)
+    }
+}
+
+impl<V: IndexType, E: IndexType, F: IndexType> Display for ValidationReport<V, E, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} errors, {} warnings",
+            self.errors().count(),
+            self.warnings().count()
+        )?;
+        for v in &self.violations {
+            writeln!(f, "  {}", v)?;
+        }
+        Ok(())
+    }
+}
+
+/// Methods to list all violated invariants of a mesh. Unlike `MeshChecker::check`, which
+/// stops at the first problem, this collects every violation with the offending ids,
+/// which helps to debug meshes produced by custom builder code.
+pub trait MeshValidation<T: MeshType3D<Mesh = Self>>: MeshDump<T>
+where
+    T::Edge: HalfEdge<T>,
+{
+    /// Validates the mesh with a planarity tolerance of `sqrt(EPS)`. See `validate_with`.
+    fn validate(&self) -> ValidationReport<T::V, T::E, T::F> {
+        self.validate_with(T::S::EPS.sqrt())
+    }
+
+    /// Validates the connectivity of the mesh and reports every violated invariant.
+    ///
+    /// The invariants that need to walk the faces, i.e., degenerate faces, non-planar
+    /// faces (farther than `eps` from their plane), and inconsistent islands, are only
+    /// checked if the connectivity has no errors, since the walks might not terminate otherwise.
+    fn validate_with(&self, eps: T::S) -> ValidationReport<T::V, T::E, T::F> {
+        let mut report = ValidationReport::default();
+        let vertex_ids: HashSet<T::V> = self.vertex_ids().collect();
+        let edge_ids: HashSet<T::E> = self.edge_ids().collect();
+        let face_ids: HashSet<T::F> = self.face_ids().collect();
+
+        for e in self.edges() {
+            let id = e.id();
+            for other in [e.next_id(), e.prev_id()] {
+                if !edge_ids.contains(&other) {
+                    report.push(
+                        Invariant::MissingElement,
+                        vec![],
+                        vec![id, other],
+                        vec![],
+                        format!("HalfEdge {} references missing half-edge {}", id, other),
+                    );
+                }
+            }
+            if !vertex_ids.contains(&e.origin_id()) {
+                report.push(
+                    Invariant::MissingElement,
+                    vec![e.origin_id()],
+                    vec![id],
+                    vec![],
+                    format!("HalfEdge {} has missing origin {}", id, e.origin_id()),
+                );
+            }
+            if !e.is_boundary_self() && !face_ids.contains(&e.face_id()) {
+                report.push(
+                    Invariant::MissingElement,
+                    vec![],
+                    vec![id],
+                    vec![e.face_id()],
+                    format!("HalfEdge {} has missing face {}", id, e.face_id()),
+                );
+            }
+
+            if !edge_ids.contains(&e.twin_id()) {
+                report.push(
+                    Invariant::DanglingTwin,
+                    vec![],
+                    vec![id],
+                    vec![],
+                    format!("HalfEdge {} has missing twin {}", id, e.twin_id()),
+                );
+            } else {
+                let twin = self.edge(e.twin_id());
+                if twin.twin_id() != id {
+                    report.push(
+                        Invariant::DanglingTwin,
+                        vec![],
+                        vec![id, twin.id()],
+                        vec![],
+                        format!(
+                            "HalfEdge {} has a twin {} with twin {}",
+                            id,
+                            twin.id(),
+                            twin.twin_id()
+                        ),
+                    );
+                }
+                if id < twin.id() && e.is_boundary_self() && twin.is_boundary_self() {
+                    report.push(
+                        Invariant::EdgeWithoutFace,
+                        vec![e.origin_id(), twin.origin_id()],
+                        vec![id, twin.id()],
+                        vec![],
+                        format!("HalfEdge {} and its twin {} have no face", id, twin.id()),
+                    );
+                }
+                if edge_ids.contains(&e.next_id()) {
+                    let next = self.edge(e.next_id());
+                    if next.origin_id() != twin.origin_id() {
+                        report.push(
+                            Invariant::TargetMismatch,
+                            vec![next.origin_id(), twin.origin_id()],
+                            vec![id, next.id(), twin.id()],
+                            vec![],
+                            format!(
+                                "HalfEdge {} has next origin {} but twin origin {}",
+                                id,
+                                next.origin_id(),
+                                twin.origin_id()
+                            ),
+                        );
+                    }
+                }
+            }
+
+            if !edge_ids.contains(&e.next_id()) || !edge_ids.contains(&e.prev_id()) {
+                continue;
+            }
+            let next = self.edge(e.next_id());
+            let prev = self.edge(e.prev_id());
+            if next.prev_id() != id || prev.next_id() != id {
+                report.push(
+                    Invariant::PrevNext,
+                    vec![],
+                    vec![id, next.id(), prev.id()],
+                    vec![],
+                    format!(
+                        "HalfEdge {} has prev(next) {} and next(prev) {}",
+                        id,
+                        next.prev_id(),
+                        prev.next_id()
+                    ),
+                );
+            }
+            if next.face_id() != e.face_id() {
+                report.push(
+                    Invariant::FaceMismatch,
+                    vec![],
+                    vec![id, next.id()],
+                    [e.face_id(), next.face_id()]
+                        .into_iter()
+                        .filter(|f| *f != IndexType::max())
+                        .collect(),
+                    format!(
+                        "HalfEdge {} has face {} but next has face {}",
+                        id,
+                        e.face_id(),
+                        next.face_id()
+                    ),
+                );
+            }
+
+            // walk the cycle, but not further than the number of half-edges
+            let mut current = e.next_id();
+            let mut steps = 0;
+            while current != id && steps <= edge_ids.len() && edge_ids.contains(&current) {
+                current = self.edge(current).next_id();
+                steps += 1;
+            }
+            if current != id && edge_ids.contains(&current) {
+                report.push(
+                    Invariant::OpenCycle,
+                    vec![],
+                    vec![id],
+                    vec![],
+                    format!("Following next from HalfEdge {} never returns to it", id),
+                );
+            }
+        }
+
+        for v in self.vertices() {
+            let e = v.edge_id(self);
+            if e == IndexType::max() {
+                report.push(
+                    Invariant::IsolatedVertex,
+                    vec![v.id()],
+                    vec![],
+                    vec![],
+                    format!("Vertex {} has no edges", v.id()),
+                );
+            } else if !edge_ids.contains(&e) {
+                report.push(
+                    Invariant::MissingElement,
+                    vec![v.id()],
+                    vec![e],
+                    vec![],
+                    format!("Vertex {} has missing edge {}", v.id(), e),
+                );
+            } else if self.edge(e).origin_id() != v.id() {
+                report.push(
+                    Invariant::VertexEdge,
+                    vec![v.id(), self.edge(e).origin_id()],
+                    vec![e],
+                    vec![],
+                    format!(
+                        "Vertex {} has edge {} with origin {}",
+                        v.id(),
+                        e,
+                        self.edge(e).origin_id()
+                    ),
+                );
+            }
+        }
+
+        for f in self.faces() {
+            let e = f.edge_id();
+            if !edge_ids.contains(&e) {
+                report.push(
+                    Invariant::MissingElement,
+                    vec![],
+                    vec![e],
+                    vec![f.id()],
+                    format!("Face {} has missing edge {}", f.id(), e),
+                );
+            } else if self.edge(e).face_id() != f.id() {
+                report.push(
+                    Invariant::FaceMismatch,
+                    vec![],
+                    vec![e],
+                    vec![f.id()],
+                    format!(
+                        "Face {} has edge {} with face {}",
+                        f.id(),
+                        e,
+                        self.edge(e).face_id()
+                    ),
+                );
+            }
+        }
+
+        if !report.is_valid() {
+            return report;
+        }
+
+        for f in self.faces() {
+            let vs = f
+                .vertices(self)
+                .map(|v| (v.id(), v.pos()))
+                .collect::<Vec<_>>();
+            if vs.len() < 3 {
+                report.push(
+                    Invariant::DegenerateFace,
+                    vs.iter().map(|(v, _)| *v).collect(),
+                    vec![],
+                    vec![f.id()],
+                    format!("Face {} has only {} edges", f.id(), vs.len()),
+                );
+                continue;
+            }
+            if vs.len() == 3 {
+                continue;
+            }
+
+            // Newell's method is robust against collinear vertices
+            let normal = T::Vec::stable_sum((0..vs.len()).map(|i| {
+                let (a, b) = (vs[i].1, vs[(i + 1) % vs.len()].1);
+                T::Vec::from_xyz(
+                    (a.y() - b.y()) * (a.z() + b.z()),
+                    (a.z() - b.z()) * (a.x() + b.x()),
+                    (a.x() - b.x()) * (a.y() + b.y()),
+                )
+            }));
+            if normal.length() <= T::S::EPS {
+                continue;
+            }
+            let normal = normal.normalize();
+            let center =
+                T::Vec::stable_sum(vs.iter().map(|(_, p)| *p)) / T::S::from_usize(vs.len());
+            let off = vs
+                .iter()
+                .filter(|(_, p)| (*p - center).dot(&normal).abs() > eps)
+                .map(|(v, _)| *v)
+                .collect::<Vec<_>>();
+            if !off.is_empty() {
+                report.push(
+                    Invariant::NonPlanarFace,
+                    off,
+                    vec![],
+                    vec![f.id()],
+                    format!("Face {} is not planar", f.id()),
+                );
+            }
+        }
+
+        let islands = self.islands();
+        let island_of: HashMap<T::V, usize> = islands
+            .iter()
+            .enumerate()
+            .flat_map(|(i, vs)| vs.iter().map(move |v| (*v, i)))
+            .collect();
+        let mut volumes = vec![Some(T::S::ZERO); islands.len()];
+        let mut faces = vec![Vec::new(); islands.len()];
+        for e in self.edges().filter(|e| e.is_boundary_self()) {
+            volumes[island_of[&e.origin_id()]] = None;
+        }
+        for f in self.faces() {
+            let vs = f.vertices(self).map(|v| v.pos()).collect::<Vec<T::Vec>>();
+            let i = island_of[&f.edge(self).origin_id()];
+            faces[i].push(f.id());
+            if let Some(volume) = volumes[i].as_mut() {
+                *volume += T::S::stable_sum(
+                    (1..vs.len() - 1).map(|k| vs[0].dot(&vs[k].cross(&vs[k + 1]))),
+                ) / T::S::from_usize(6);
+            }
+        }
+        for (i, volume) in volumes.iter().enumerate() {
+            if volume.is_some_and(|v| v < -eps) {
+                report.push(
+                    Invariant::InconsistentIsland,
+                    islands[i].clone(),
+                    vec![],
+                    faces[i].clone(),
+                    format!(
+                        "Island with {} faces encloses a negative volume",
+                        faces[i].len()
+                    ),
+                );
+            }
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_validate_clean() {
+        let mesh = Mesh3d64::cube(1.0);
+        let report = mesh.validate();
+        assert!(report.is_clean());
+        assert_eq!(report.to_string(), "0 errors, 0 warnings\n");
+    }
+
+    #[test]
+    fn test_validate_warnings() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        let v = mesh.vertex_ids().next().unwrap();
+        // pull a corner outwards, bending its three faces
+        let p = mesh.vertex(v).pos();
+        mesh.vertex_mut(v).payload_mut().set_pos(p * 1.4);
+        let lonely = mesh.add_vertex(VertexPayloadPNU::from_pos(Vec3::new(3.0, 0.0, 0.0)));
+        mesh.add_isolated_edge_default(
+            VertexPayloadPNU::from_pos(Vec3::new(4.0, 0.0, 0.0)),
+            VertexPayloadPNU::from_pos(Vec3::new(5.0, 0.0, 0.0)),
+        );
+        let report = mesh.validate();
+        assert!(report.is_valid());
+        assert!(!report.is_clean());
+        assert_eq!(report.of(Invariant::NonPlanarFace).count(), 3);
+        assert!(report
+            .of(Invariant::NonPlanarFace)
+            .all(|w| w.vertices.contains(&v) && w.severity == Severity::Warning));
+        let isolated = report.of(Invariant::IsolatedVertex).collect::<Vec<_>>();
+        assert_eq!(isolated.len(), 1);
+        assert_eq!(isolated[0].vertices, vec![lonely]);
+        assert_eq!(report.of(Invariant::EdgeWithoutFace).count(), 1);
+        assert_eq!(report.of(Invariant::InconsistentIsland).count(), 0);
+
+        // turning the cube inside out is a consistent, but probably unintended mesh
+        let cube = Mesh3d64::cube(1.0);
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        for v in cube.vertices() {
+            builder.add_vertex(*v.payload());
+        }
+        for (_, cycle) in cube.face_cycles() {
+            builder.add_face(cycle.iter().rev().map(|v| v.index()));
+        }
+        let inside_out = builder.build();
+        assert!(inside_out.check().is_ok());
+        let report = inside_out.validate();
+        let islands = report.of(Invariant::InconsistentIsland).collect::<Vec<_>>();
+        assert_eq!(islands.len(), 1);
+        assert_eq!(islands[0].faces.len(), 6);
+    }
+
+    #[test]
+    fn test_validate_errors() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        let edges = mesh.edge_ids().collect::<Vec<_>>();
+        let (a, b) = (edges[0], edges[6]);
+        mesh.edge_mut(a).set_twin(a);
+        mesh.edge_mut(b).set_next(b);
+        assert!(mesh.check().is_err());
+
+        let report = mesh.validate();
+        assert!(!report.is_valid());
+        assert!(report
+            .of(Invariant::DanglingTwin)
+            .any(|e| e.edges.contains(&a)));
+        assert!(report.of(Invariant::PrevNext).any(|e| e.edges.contains(&b)));
+        assert!(report.errors().all(|e| e.severity == Severity::Error));
+        assert!(report.errors().count() >= 3);
+        assert!(report
+            .to_string()
+            .contains("error: dangling twin: HalfEdge"));
+    }
+}
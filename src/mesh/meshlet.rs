@@ -0,0 +1,244 @@
+use crate::math::{IndexType, Scalar, Vector3D};
+use std::collections::HashMap;
+
+/// A small cluster of triangles for GPU-driven rendering, e.g., with mesh shaders or
+/// Bevy's meshlet renderer. Meshlets are culled as a whole using their bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meshlet<V: IndexType, Vec3: Vector3D> {
+    /// The indices of the vertices of the meshlet in the vertex buffer
+    pub vertices: Vec<V>,
+
+    /// The triangles of the meshlet as indices into `vertices`
+    pub triangles: Vec<[u8; 3]>,
+
+    /// The center of the bounding sphere
+    pub center: Vec3,
+
+    /// The radius of the bounding sphere
+    pub radius: Vec3::S,
+
+    /// The apex of the normal cone used for backface culling
+    pub cone_apex: Vec3,
+
+    /// The axis of the normal cone used for backface culling
+    pub cone_axis: Vec3,
+
+    /// The cosine of the angle between the view direction and the axis beyond which
+    /// all triangles of the meshlet are back-facing. A cutoff of one disables culling.
+    pub cone_cutoff: Vec3::S,
+}
+
+impl<V: IndexType, Vec3: Vector3D> Meshlet<V, Vec3> {
+    /// Iterates the triangles of the meshlet using indices into the vertex buffer.
+    pub fn global_triangles(&self) -> impl Iterator<Item = [V; 3]> + '_ {
+        self.triangles
+            .iter()
+            .map(|t| t.map(|i| self.vertices[i as usize]))
+    }
+
+    /// Returns whether all triangles of the meshlet are back-facing when seen
+    /// from the given camera position. This is conservative, i.e., it may return
+    /// `false` even though all triangles are back-facing.
+    pub fn is_backfacing(&self, camera: Vec3) -> bool {
+        if self.cone_cutoff >= Vec3::S::ONE {
+            return false;
+        }
+        let view = self.cone_apex - camera;
+        view.dot(&self.cone_axis) >= self.cone_cutoff * view.length()
+    }
+}
+
+/// Partitions the counter-clockwise triangle list `indices` into meshlets with at most
+/// `max_vertices` vertices and `max_triangles` triangles each and computes their bounding
+/// spheres and normal cones for culling. The positions of the vertices are given by
+/// `positions`. For Bevy, use 255 vertices and 128 triangles; mesh shaders usually
+/// prefer 64 vertices and 124 triangles.
+///
+/// The meshlets are grown greedily across shared vertices, preferring triangles that
+/// add the fewest new vertices, so the meshlets are compact and reuse their vertices.
+pub fn build_meshlets<V: IndexType, Vec3: Vector3D>(
+    indices: &[V],
+    positions: &[Vec3],
+    max_vertices: usize,
+    max_triangles: usize,
+) -> Vec<Meshlet<V, Vec3>> {
+    assert!(indices.len() % 3 == 0, "not a triangle list");
+    assert!(
+        (3..=256).contains(&max_vertices),
+        "a meshlet needs between 3 and 256 vertices"
+    );
+    assert!(max_triangles >= 1);
+    let num_triangles = indices.len() / 3;
+    let triangle = |t: usize| [0, 1, 2].map(|k| indices[3 * t + k]);
+
+    // the triangles of each vertex
+    let mut adjacency: HashMap<V, Vec<usize>> = HashMap::new();
+    for (k, i) in indices.iter().enumerate() {
+        adjacency.entry(*i).or_default().push(k / 3);
+    }
+
+    let mut assigned = vec![false; num_triangles];
+    let mut meshlets = Vec::new();
+    let mut cursor = 0;
+    while cursor < num_triangles {
+        if assigned[cursor] {
+            cursor += 1;
+            continue;
+        }
+        let mut local: HashMap<V, u8> = HashMap::new();
+        let mut vertices: Vec<V> = Vec::new();
+        let mut triangles: Vec<[u8; 3]> = Vec::new();
+        let mut candidates: Vec<usize> = vec![cursor];
+        while triangles.len() < max_triangles {
+            // the candidate that adds the fewest new vertices
+            candidates.retain(|&t| !assigned[t]);
+            let Some((new, t)) = candidates
+                .iter()
+                .map(|&t| {
+                    let new = triangle(t)
+                        .iter()
+                        .filter(|v| !local.contains_key(v))
+                        .count();
+                    (new, t)
+                })
+                .min()
+            else {
+                break;
+            };
+            if vertices.len() + new > max_vertices {
+                break;
+            }
+            assigned[t] = true;
+            triangles.push(triangle(t).map(|v| {
+                *local.entry(v).or_insert_with(|| {
+                    vertices.push(v);
+                    candidates.extend(adjacency[&v].iter().filter(|&&u| !assigned[u]));
+                    (vertices.len() - 1) as u8
+                })
+            }));
+        }
+        meshlets.push(meshlet_bounds(vertices, triangles, positions));
+    }
+    meshlets
+}
+
+/// Computes the bounding sphere and the normal cone of the meshlet
+/// (similar to `meshopt_computeMeshletBounds` of meshoptimizer).
+fn meshlet_bounds<V: IndexType, Vec3: Vector3D>(
+    vertices: Vec<V>,
+    triangles: Vec<[u8; 3]>,
+    positions: &[Vec3],
+) -> Meshlet<V, Vec3> {
+    let pos = |i: u8| positions[vertices[i as usize].index()];
+    let center = vertices.iter().map(|v| positions[v.index()]).fold(
+        Vec3::from_xyz(Vec3::S::ZERO, Vec3::S::ZERO, Vec3::S::ZERO),
+        |a, b| a + b,
+    ) / Vec3::S::from_usize(vertices.len());
+    let radius = vertices
+        .iter()
+        .map(|v| positions[v.index()].distance(&center))
+        .fold(Vec3::S::ZERO, |a, b| a.max(b));
+
+    let normals: Vec<(Vec3, Vec3)> = triangles
+        .iter()
+        .filter_map(|t| {
+            let (a, b, c) = (pos(t[0]), pos(t[1]), pos(t[2]));
+            let n = (b - a).cross(&(c - a));
+            let len = n.length();
+            (len > Vec3::S::EPS).then(|| (a, n / len))
+        })
+        .collect();
+    let zero = Vec3::from_xyz(Vec3::S::ZERO, Vec3::S::ZERO, Vec3::S::ZERO);
+    let axis = normals.iter().fold(zero, |acc, (_, n)| acc + *n);
+    let min_dot = if axis.length() <= Vec3::S::EPS {
+        Vec3::S::ZERO
+    } else {
+        normals
+            .iter()
+            .map(|(_, n)| n.dot(&axis.normalize()))
+            .fold(Vec3::S::ONE, |a, b| a.min(b))
+    };
+    let (cone_apex, cone_axis, cone_cutoff) = if min_dot <= Vec3::S::from(0.1) {
+        // the cone is too wide to ever cull the meshlet
+        (center, zero, Vec3::S::ONE)
+    } else {
+        // move the apex back along the axis until all triangle planes are in front of it
+        let axis = axis.normalize();
+        let offset = normals
+            .iter()
+            .map(|(p, n)| (center - *p).dot(n) / n.dot(&axis))
+            .fold(Vec3::S::ZERO, |a, b| a.max(b));
+        (
+            center - axis * offset,
+            axis,
+            (Vec3::S::ONE - min_dot * min_dot).sqrt(),
+        )
+    };
+
+    Meshlet {
+        vertices,
+        triangles,
+        center,
+        radius,
+        cone_apex,
+        cone_axis,
+        cone_cutoff,
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_meshlets() {
+        let mesh = Mesh3d64::uv_sphere(1.0, 32, 32);
+        let (meshlets, vs) = mesh.triangulate_meshlets(
+            TriangulationAlgorithm::Fan,
+            &mut Default::default(),
+            64,
+            124,
+        );
+        let (indices, _) = mesh.triangulate(TriangulationAlgorithm::Fan, &mut Default::default());
+        let positions: Vec<Vec3<f64>> = vs.iter().map(|v| *v.pos()).collect();
+
+        // every triangle is in exactly one meshlet and the limits are respected
+        let mut seen = HashSet::new();
+        for m in &meshlets {
+            assert!(m.vertices.len() <= 64);
+            assert!(m.triangles.len() <= 124);
+            for t in m.global_triangles() {
+                let i = (0..3).min_by_key(|&i| t[i]).unwrap();
+                assert!(seen.insert([t[i], t[(i + 1) % 3], t[(i + 2) % 3]]));
+            }
+            for v in &m.vertices {
+                assert!(positions[*v].distance(&m.center) <= m.radius + 1e-9);
+            }
+        }
+        assert_eq!(seen.len(), indices.len() / 3);
+        // the meshlets are compact, i.e., they reuse most of their vertices
+        assert!(meshlets.len() * 124 < 2 * seen.len());
+
+        // culling is conservative
+        let mut culled = 0;
+        for camera in [
+            Vec3::new(0.0, 0.0, 3.0),
+            Vec3::new(2.0, -3.0, 1.0),
+            Vec3::new(0.0, 10.0, 0.0),
+        ] {
+            for m in &meshlets {
+                if m.is_backfacing(camera) {
+                    culled += 1;
+                    for [a, b, c] in m.global_triangles() {
+                        let (a, b, c) = (positions[a], positions[b], positions[c]);
+                        let n = (b - a).cross(&(c - a));
+                        assert!(n.dot(&(camera - a)) <= 1e-9);
+                    }
+                }
+            }
+        }
+        assert!(culled > 0);
+    }
+}
@@ -3,11 +3,17 @@
 mod edge;
 mod face;
 mod mesh;
+mod meshlet;
+mod strip;
 mod triangulation;
 mod vertex;
+mod vertex_cache;
 
 pub use edge::*;
 pub use face::*;
 pub use mesh::*;
+pub use meshlet::*;
+pub use strip::*;
 pub use triangulation::*;
 pub use vertex::*;
+pub use vertex_cache::*;
@@ -0,0 +1,158 @@
+use crate::math::IndexType;
+use std::collections::HashMap;
+
+/// Converts a counter-clockwise triangle list into a single triangle strip.
+///
+/// The triangles are greedily walked across shared edges, starting each strip next to the
+/// previous one at a triangle with the fewest unvisited neighbors. Separate strips are stitched together using degenerate
+/// triangles, so the result can be rendered in one draw call without primitive restart.
+/// The winding of every non-degenerate triangle in the strip is preserved, i.e., the `i`-th
+/// triangle of the strip is `(s[i], s[i+1], s[i+2])` if `i` is even and
+/// `(s[i+1], s[i], s[i+2])` otherwise.
+///
+/// For regular meshes, e.g., grids, this reduces the number of indices to a bit more than
+/// one per triangle instead of three.
+pub fn stripify<V: IndexType>(indices: &[V]) -> Vec<V> {
+    assert!(indices.len() % 3 == 0, "not a triangle list");
+    let n = indices.len() / 3;
+    let tri = |t: usize| [indices[3 * t], indices[3 * t + 1], indices[3 * t + 2]];
+
+    // directed edge -> triangle containing it
+    let mut edges: HashMap<(V, V), usize> = HashMap::with_capacity(indices.len());
+    for t in 0..n {
+        let [a, b, c] = tri(t);
+        edges.insert((a, b), t);
+        edges.insert((b, c), t);
+        edges.insert((c, a), t);
+    }
+    let neighbor =
+        |visited: &Vec<bool>, a: V, b: V| edges.get(&(a, b)).copied().filter(|&t| !visited[t]);
+    let free_neighbors = |visited: &Vec<bool>, t: usize| {
+        let [a, b, c] = tri(t);
+        [(b, a), (c, b), (a, c)]
+            .iter()
+            .filter(|(u, v)| neighbor(visited, *u, *v).is_some())
+            .count()
+    };
+
+    // Walks the strip starting with the given triangle. Only marks the visited
+    // triangles if `commit` is set.
+    let walk = |visited: &mut Vec<bool>, start: [V; 3], t: usize, commit: bool| {
+        let mut strip = start.to_vec();
+        let mut local = vec![t];
+        visited[t] = true;
+        loop {
+            let k = strip.len() - 2;
+            let (p, q) = (strip[k], strip[k + 1]);
+            // the next triangle contains the edge in the direction of its winding
+            let (u, v) = if k % 2 == 0 { (p, q) } else { (q, p) };
+            let Some(next) = neighbor(visited, u, v) else {
+                break;
+            };
+            let x = tri(next).into_iter().find(|&x| x != u && x != v).unwrap();
+            visited[next] = true;
+            local.push(next);
+            strip.push(x);
+        }
+        if !commit {
+            for t in local {
+                visited[t] = false;
+            }
+        }
+        strip
+    };
+
+    let mut visited = vec![false; n];
+    let mut result: Vec<V> = Vec::with_capacity(n + 2);
+    let mut cursor = 0;
+    let mut candidates: Vec<usize> = Vec::new();
+    loop {
+        // prefer to continue next to the previous strip at a triangle with few free neighbors
+        let start = candidates
+            .iter()
+            .copied()
+            .filter(|&t| !visited[t])
+            .min_by_key(|&t| free_neighbors(&visited, t))
+            .or_else(|| {
+                while cursor < n && visited[cursor] {
+                    cursor += 1;
+                }
+                (cursor < n).then_some(cursor)
+            });
+        let Some(start) = start else {
+            break;
+        };
+        let [a, b, c] = tri(start);
+        let rotation = [[a, b, c], [b, c, a], [c, a, b]]
+            .into_iter()
+            .max_by_key(|r| walk(&mut visited, *r, start, false).len())
+            .unwrap();
+        let strip = walk(&mut visited, rotation, start, true);
+        candidates = strip
+            .windows(2)
+            .filter_map(|w| neighbor(&visited, w[0], w[1]).or(neighbor(&visited, w[1], w[0])))
+            .collect();
+
+        if let Some(&last) = result.last() {
+            // stitch with degenerate triangles such that the next strip starts at an even index
+            result.push(last);
+            if result.len() % 2 == 0 {
+                result.push(last);
+            }
+            result.push(strip[0]);
+        }
+        result.extend(strip);
+    }
+    result
+}
+
+/// Converts a triangle strip back into a counter-clockwise triangle list
+/// and drops degenerate triangles, e.g., the ones stitching strips together.
+pub fn unstripify<V: IndexType>(strip: &[V]) -> Vec<V> {
+    let mut indices = Vec::with_capacity(3 * strip.len());
+    for i in 0..strip.len().saturating_sub(2) {
+        let (a, b, c) = if i % 2 == 0 {
+            (strip[i], strip[i + 1], strip[i + 2])
+        } else {
+            (strip[i + 1], strip[i], strip[i + 2])
+        };
+        if a != b && b != c && c != a {
+            indices.extend([a, b, c]);
+        }
+    }
+    indices
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::collections::HashSet;
+
+    /// Returns the triangles of a triangle list, rotated such that the smallest index comes first.
+    fn triangles(indices: &[usize]) -> HashSet<[usize; 3]> {
+        indices
+            .chunks(3)
+            .map(|t| {
+                let i = (0..3).min_by_key(|&i| t[i]).unwrap();
+                [t[i], t[(i + 1) % 3], t[(i + 2) % 3]]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stripify() {
+        // a grid is stripified into a few long strips
+        let mesh = Mesh3d64::heightmap(1.0, 1.0, 16, |_, _| 0.0);
+        let (indices, _) = mesh.triangulate(TriangulationAlgorithm::Fan, &mut Default::default());
+        let strip = stripify(&indices);
+        assert_eq!(triangles(&unstripify(&strip)), triangles(&indices));
+        assert!(strip.len() < indices.len() / 2);
+
+        // disconnected and odd-length strips are stitched without changing the winding
+        let indices = [0, 1, 2, 3, 4, 5, 5, 4, 6, 7, 8, 9];
+        let strip = stripify(&indices);
+        assert_eq!(triangles(&unstripify(&strip)), triangles(&indices));
+        assert_eq!(stripify::<usize>(&[]), Vec::<usize>::new());
+    }
+}
@@ -1,5 +1,9 @@
+use super::stripify;
 use crate::math::{IndexType, LineSegment2D, Polygon, Scalar, ScalarIteratorExt, Vector2D};
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
 
 /// A vertex with its index in the global structure
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,12 +33,19 @@ impl<V: IndexType, Vec2: Vector2D> IndexedVertex2D<V, Vec2> {
 /// Will borrow the index buffer and append new triangles to it.
 /// Most methods will only look at the indices that are added after the borrow startet.
 /// It's fine to add triangles to the index buffer directly while it is borrowed.
+///
+/// When triangulating several faces into the same buffer, call `begin_face` before each
+/// face to record which triangles belong to it. Afterwards, the methods only look at the
+/// triangles of the current face.
 pub struct Triangulation<'a, V: IndexType> {
     /// The index buffer
     indices: &'a mut Vec<V>,
 
-    /// The position of the index where _this_ `Triangulation` begins
+    /// The position of the index where _this_ `Triangulation` or the current face begins
     start: usize,
+
+    /// The recorded faces and the positions of the index where their triangles begin
+    faces: Vec<(usize, usize)>,
 }
 
 impl<V: IndexType> std::fmt::Debug for Triangulation<'_, V> {
@@ -59,9 +70,36 @@ impl<'a, V: IndexType> Triangulation<'a, V> {
         Triangulation {
             start: indices.len(),
             indices,
+            faces: Vec::new(),
         }
     }
 
+    /// Starts the triangles of the given face. All triangles inserted until the next call
+    /// belong to that face.
+    pub fn begin_face<F: IndexType>(&mut self, face: F) {
+        self.start = self.indices.len();
+        self.faces.push((face.index(), self.start));
+    }
+
+    /// Iterates the recorded faces with the ranges of their indices in the index buffer,
+    /// e.g., to map triangles back to their faces for picking or to update the triangles
+    /// of single faces on the GPU.
+    pub fn face_ranges<F: IndexType>(&self) -> impl Iterator<Item = (F, Range<usize>)> + '_ {
+        self.faces.iter().enumerate().map(|(i, (face, start))| {
+            let end = self
+                .faces
+                .get(i + 1)
+                .map_or(self.indices.len(), |(_, next)| *next);
+            (F::new(*face), *start..end)
+        })
+    }
+
+    /// Converts the triangles of the triangulation into a triangle strip.
+    /// See `stripify` for details.
+    pub fn to_strip(&self) -> Vec<V> {
+        stripify(&self.indices[self.start..])
+    }
+
     /// Insert a triangle into the triangulation using global indices
     pub fn insert_triangle(&mut self, a: V, b: V, c: V) {
         self.indices.extend([a, b, c]);
@@ -179,7 +217,7 @@ impl<'a, V: IndexType> Triangulation<'a, V> {
 
     /// Check for non-degenerate triangles (no zero-area triangles)
     pub fn verify_non_degenerate_triangle<Vec2: Vector2D>(&self, vec_hm: &HashMap<V, Vec2>) {
-        for i in self.start..self.len() {
+        for i in 0..self.len() {
             let area = self.get_triangle_area(i, vec_hm);
             /*assert!(
                 area.abs() > Vec2::S::ZERO,
@@ -228,8 +266,8 @@ impl<'a, V: IndexType> Triangulation<'a, V> {
             );
         }*/
 
-        for i in (self.start..num_vertices).step_by(3) {
-            for j in (self.start..num_vertices).step_by(3) {
+        for i in (0..num_vertices).step_by(3) {
+            for j in (0..num_vertices).step_by(3) {
                 if i == j {
                     continue;
                 }
@@ -292,7 +330,7 @@ impl<'a, V: IndexType> Triangulation<'a, V> {
     /// Calculate the total edge weight of the triangulation
     pub fn total_edge_weight<Vec2: Vector2D>(&self, vec_hm: &HashMap<V, Vec2>) -> Vec2::S {
         let mut total = Vec2::S::ZERO;
-        for i in self.start..self.len() {
+        for i in 0..self.len() {
             let (i1, i2, i3) = self.get_triangle(i);
             let v0 = vec_hm[&i1];
             let v1 = vec_hm[&i2];
@@ -0,0 +1,195 @@
+use crate::math::IndexType;
+use std::collections::VecDeque;
+
+/// The size of the simulated cache used by `optimize_vertex_cache`.
+const CACHE_SIZE: usize = 32;
+
+/// Returns the score of a vertex for Forsyth's algorithm given its position in the
+/// cache and the number of triangles using it that haven't been emitted yet.
+fn vertex_score(cache_position: Option<usize>, remaining: usize) -> f32 {
+    if remaining == 0 {
+        return -1.0;
+    }
+    let cache = match cache_position {
+        None => 0.0,
+        // the vertices of the last triangle get a fixed score to avoid emitting
+        // triangles that are almost identical to it
+        Some(p) if p < 3 => 0.75,
+        Some(p) => (1.0 - (p - 3) as f32 / (CACHE_SIZE - 3) as f32).powf(1.5),
+    };
+    // prefer vertices with few remaining triangles to get rid of them
+    cache + 2.0 * (remaining as f32).powf(-0.5)
+}
+
+/// Reorders the triangles of the triangle list `indices` to improve the hit rate of the
+/// post-transform vertex cache of the GPU using Tom Forsyth's
+/// "Linear-Speed Vertex Cache Optimisation". The winding of the triangles is preserved.
+///
+/// All indices must be smaller than `num_vertices`. The algorithm doesn't depend on the
+/// exact cache size of the GPU and runs in linear time, so it's cheap enough to be applied
+/// after every triangulation of large meshes.
+pub fn optimize_vertex_cache<V: IndexType>(indices: &mut [V], num_vertices: usize) {
+    assert!(indices.len() % 3 == 0, "not a triangle list");
+    let num_triangles = indices.len() / 3;
+    if num_triangles <= 1 {
+        return;
+    }
+
+    // the triangles of each vertex in compressed sparse row format
+    let mut offsets = vec![0; num_vertices + 1];
+    for i in indices.iter() {
+        offsets[i.index() + 1] += 1;
+    }
+    for v in 0..num_vertices {
+        offsets[v + 1] += offsets[v];
+    }
+    let mut remaining: Vec<usize> = (0..num_vertices)
+        .map(|v| offsets[v + 1] - offsets[v])
+        .collect();
+    let mut adjacency = vec![0; indices.len()];
+    let mut fill = offsets.clone();
+    for (k, i) in indices.iter().enumerate() {
+        adjacency[fill[i.index()]] = k / 3;
+        fill[i.index()] += 1;
+    }
+
+    let mut vertex_scores: Vec<f32> = (0..num_vertices)
+        .map(|v| vertex_score(None, remaining[v]))
+        .collect();
+    let triangle = |t: usize| [0, 1, 2].map(|k| indices[3 * t + k].index());
+    let mut emitted = vec![false; num_triangles];
+
+    let mut order = Vec::with_capacity(num_triangles);
+    let mut cache: VecDeque<usize> = VecDeque::with_capacity(CACHE_SIZE + 3);
+    let mut best: Option<usize> = None;
+    let mut cursor = 0;
+    while order.len() < num_triangles {
+        let t = best.unwrap_or_else(|| {
+            // nothing in the cache is connected to unemitted triangles; start somewhere else
+            while emitted[cursor] {
+                cursor += 1;
+            }
+            cursor
+        });
+        emitted[t] = true;
+        order.push(t);
+
+        // move the vertices of the triangle to the front of the cache
+        for v in triangle(t).into_iter().rev() {
+            remaining[v] -= 1;
+            if let Some(p) = cache.iter().position(|&u| u == v) {
+                cache.remove(p);
+            }
+            cache.push_front(v);
+        }
+        let evicted: Vec<usize> = if cache.len() > CACHE_SIZE {
+            cache.drain(CACHE_SIZE..).collect()
+        } else {
+            Vec::new()
+        };
+        for v in evicted {
+            vertex_scores[v] = vertex_score(None, remaining[v]);
+        }
+        for (p, &v) in cache.iter().enumerate() {
+            vertex_scores[v] = vertex_score(Some(p), remaining[v]);
+        }
+
+        // rescore the triangles touching the cache and pick the best one
+        best = None;
+        let mut best_score = f32::NEG_INFINITY;
+        for &v in cache.iter() {
+            for &u in &adjacency[offsets[v]..offsets[v + 1]] {
+                if emitted[u] {
+                    continue;
+                }
+                let score = triangle(u).iter().map(|&w| vertex_scores[w]).sum();
+                if score > best_score {
+                    best_score = score;
+                    best = Some(u);
+                }
+            }
+        }
+    }
+
+    let reordered: Vec<V> = order
+        .into_iter()
+        .flat_map(|t| [0, 1, 2].map(|k| indices[3 * t + k]))
+        .collect();
+    indices.copy_from_slice(&reordered);
+}
+
+/// Returns the average number of vertices that have to be transformed per triangle
+/// when rendering the triangle list `indices` with a FIFO vertex cache of the given size.
+/// This is called the average cache miss ratio and lies between about 0.5 for
+/// well-ordered regular meshes and 3 when nothing is reused.
+pub fn average_cache_miss_ratio<V: IndexType>(indices: &[V], cache_size: usize) -> f64 {
+    if indices.is_empty() {
+        return 0.0;
+    }
+    let mut cache: VecDeque<V> = VecDeque::with_capacity(cache_size + 1);
+    let mut misses = 0;
+    for &i in indices {
+        if !cache.contains(&i) {
+            misses += 1;
+            cache.push_back(i);
+            if cache.len() > cache_size {
+                cache.pop_front();
+            }
+        }
+    }
+    misses as f64 / (indices.len() / 3) as f64
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_optimize_vertex_cache() {
+        let mesh = Mesh3d64::heightmap(1.0, 1.0, 48, |_, _| 0.0);
+        let (indices, vs) = mesh.triangulate(TriangulationAlgorithm::Fan, &mut Default::default());
+
+        // shuffle the triangles to simulate a bad order
+        let n = indices.len() / 3;
+        let shuffled: Vec<usize> = (0..n)
+            .flat_map(|i| {
+                let t = (i * 7919) % n;
+                [indices[3 * t], indices[3 * t + 1], indices[3 * t + 2]]
+            })
+            .collect();
+        let before = average_cache_miss_ratio(&shuffled, 32);
+
+        let mut optimized = shuffled.clone();
+        optimize_vertex_cache(&mut optimized, vs.len());
+        let after = average_cache_miss_ratio(&optimized, 32);
+        assert!(before > 2.0);
+        assert!(after < 0.8, "ACMR {} after optimization", after);
+
+        // the triangles and their winding are preserved
+        let triangles = |is: &[usize]| {
+            is.chunks(3)
+                .map(|t| {
+                    let i = (0..3).min_by_key(|&i| t[i]).unwrap();
+                    [t[i], t[(i + 1) % 3], t[(i + 2) % 3]]
+                })
+                .collect::<HashSet<_>>()
+        };
+        assert_eq!(optimized.len(), shuffled.len());
+        assert_eq!(triangles(&optimized), triangles(&shuffled));
+
+        // the optimization can be requested when triangulating
+        let options = TesselationOptions {
+            optimize_vertex_cache: true,
+            ..Default::default()
+        };
+        let (is, _) = mesh.triangulate_with_options(
+            TriangulationAlgorithm::Fan,
+            &options,
+            &mut Default::default(),
+        );
+        assert!(average_cache_miss_ratio(&is, 32) < 0.8);
+        assert_eq!(triangles(&is), triangles(&indices));
+    }
+}
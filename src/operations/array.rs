@@ -0,0 +1,196 @@
+use super::MeshSubmesh;
+use crate::{
+    math::{Scalar, TransformTrait, Transformable, Vector, Vector3D},
+    mesh::{
+        plane_rotation, DefaultEdgePayload, DefaultFacePayload, FaceBasics, FacePayload,
+        MeshBasics, MeshType3D, MeshTypeHalfEdge, SoupBuilder, VertexBasics,
+    },
+};
+use std::collections::HashMap;
+
+/// Returns a copy of the mesh with the orientation of all faces reversed.
+/// Vertex payloads and face tags are kept, other payloads are reset.
+fn reversed<T: MeshType3D>(mesh: &T::Mesh) -> T::Mesh
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    let mut builder = SoupBuilder::<3, T>::new();
+    let indices: HashMap<T::V, usize> = mesh
+        .vertices()
+        .map(|v| (v.id(), builder.add_vertex(v.payload().clone())))
+        .collect();
+    for f in mesh.faces() {
+        let cycle = f
+            .vertex_ids(mesh)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .map(|v| indices[&v]);
+        if let Some(tag) = f.payload().tag() {
+            builder.add_tagged_face(cycle, tag);
+        } else {
+            builder.add_face(cycle);
+        }
+    }
+    builder.build()
+}
+
+/// Methods to repeat a mesh or a part of it, e.g., for fences, stairs, or circular arrangements.
+pub trait MeshArray<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshSubmesh<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+    /// Inserts copies of the given faces such that there are `count` instances including
+    /// the original ones. The `i`-th copy is transformed by `transform` applied `i` times.
+    /// Copies under a reflection are flipped so they keep the orientation of the original.
+    /// These only keep the vertex payloads and the tags of the faces.
+    ///
+    /// If `weld` is `Some(eps)`, the boundary loops of each copy are stitched to matching
+    /// boundary loops of the mesh. See `MeshSubmesh::insert_submesh`.
+    ///
+    /// Returns the new faces of each copy.
+    fn array_faces(
+        &mut self,
+        faces: &[T::F],
+        transform: &T::Trans,
+        count: usize,
+        weld: Option<T::S>,
+    ) -> Vec<Vec<T::F>> {
+        let template = self.extract_submesh(faces);
+        let (x, y, z) = (
+            transform.apply_vec(T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)),
+            transform.apply_vec(T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO)),
+            transform.apply_vec(T::Vec::from_xyz(T::S::ZERO, T::S::ZERO, T::S::ONE)),
+        );
+        let reflection = x.dot(&y.cross(&z)) < T::S::ZERO;
+
+        let flipped = if reflection {
+            reversed::<T>(&template)
+        } else {
+            template.clone()
+        };
+
+        let mut frame = T::Trans::identity();
+        (1..count)
+            .map(|i| {
+                frame = frame.chain(transform);
+                let mut copy = if i % 2 == 1 {
+                    flipped.clone()
+                } else {
+                    template.clone()
+                };
+                for v in copy.vertices_mut() {
+                    v.payload_mut().transform(&frame);
+                }
+                self.insert_submesh(&copy, weld)
+            })
+            .collect()
+    }
+
+    /// Like `array_faces`, but repeats the whole mesh.
+    fn array(&mut self, transform: &T::Trans, count: usize, weld: Option<T::S>) -> Vec<Vec<T::F>> {
+        let faces = self.face_ids().collect::<Vec<_>>();
+        self.array_faces(&faces, transform, count, weld)
+    }
+
+    /// Repeats the whole mesh `count` times evenly around the `axis` through the origin,
+    /// rotating counter-clockwise. See `array_faces`.
+    fn radial_array(&mut self, axis: T::Vec, count: usize, weld: Option<T::S>) -> Vec<Vec<T::F>> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let a = axis.normalize();
+        let seed = if a.x().abs() < T::S::HALF {
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)
+        } else {
+            T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO)
+        };
+        let u = (seed - a * seed.dot(&a)).normalize();
+        let rotation = plane_rotation::<3, T>(
+            u,
+            a.cross(&u),
+            T::S::TWO * T::S::PI / T::S::from_usize(count),
+        );
+        self.array(&rotation, count, weld)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_array() {
+        // a fence of separate posts
+        let mut fence = Mesh3d64::cuboid(Vec3::new(0.2, 1.0, 0.2));
+        let copies = fence.array(
+            &NdAffine::from_translation(Vec3::new(1.0, 0.0, 0.0)),
+            5,
+            None,
+        );
+        assert_eq!(copies.len(), 4);
+        assert!(fence.check().is_ok());
+        assert_eq!(fence.num_faces(), 30);
+        assert_eq!(fence.islands().len(), 5);
+        assert!(fence.vertices().any(|v| v.pos().x.is_about(4.1, 1e-9)));
+
+        // stacking the sides of a cube gives a closed tube
+        let cube = Mesh3d64::cube(1.0);
+        let sides = cube
+            .face_ids()
+            .filter(|f| cube.face(*f).normal(&cube).y.abs() < 0.5)
+            .collect::<Vec<_>>();
+        let mut tube = cube.extract_submesh(&sides);
+        let up = NdAffine::from_translation(Vec3::new(0.0, 1.0, 0.0));
+        tube.array(&up, 3, Some(1e-6));
+        assert!(tube.check().is_ok());
+        assert_eq!(tube.num_faces(), 12);
+        assert_eq!(tube.num_vertices(), 16);
+        assert_eq!(tube.islands().len(), 1);
+
+        // repeating a selection keeps the rest of the mesh
+        let mut mesh = cube.clone();
+        let top = cube
+            .face_ids()
+            .find(|f| cube.face(*f).normal(&cube).y > 0.5)
+            .unwrap();
+        mesh.array_faces(&[top], &up, 3, None);
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_faces(), 8);
+        assert_eq!(mesh.islands().len(), 3);
+
+        // reflected copies keep their orientation
+        let mut mirrored = Mesh3d64::cube(1.0);
+        let reflect = NdAffine::from_scale(Vec3::new(-1.0, 1.0, 1.0))
+            .chain(&NdAffine::from_translation(Vec3::new(2.0, 0.0, 0.0)));
+        mirrored.array(&reflect, 2, None);
+        assert!(mirrored.check().is_ok());
+        assert!(mirrored.report().volume.unwrap().is_about(2.0, 1e-9));
+    }
+
+    #[test]
+    fn test_radial_array() {
+        // a sixth of a torus
+        let profile: Vec<Vec2<f64>> = (0..=8)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / 8.0;
+                Vec2::new(2.0 + 0.5 * a.cos(), 0.5 * a.sin())
+            })
+            .collect();
+        let mut torus = Mesh3d64::default();
+        torus.revolve(&profile, Vec3::new(0.0, 1.0, 0.0), PI / 3.0, 2);
+        assert!(torus.is_open());
+        let n = torus.num_faces();
+        torus.radial_array(Vec3::new(0.0, 1.0, 0.0), 6, Some(1e-6));
+        assert!(torus.check().is_ok());
+        assert!(!torus.is_open());
+        assert_eq!(torus.num_faces(), 6 * n);
+        assert_eq!(torus.num_vertices(), 6 * 2 * 8);
+    }
+}
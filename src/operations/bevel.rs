@@ -0,0 +1,614 @@
+use crate::{
+    math::{HasPosition, Scalar, Transformable, Vector, Vector3D},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, FaceBasics, FacePayload, HalfEdge,
+        HalfEdgeMesh, MeshBasics, MeshType3D, MeshTypeHalfEdge, SoupBuilder, VertexBasics,
+    },
+    util::Selection,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Rounds or chamfers edges and vertices of meshes.
+pub trait MeshBevel<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    HalfEdgeMesh<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Returns a copy of the mesh where the selected edges are replaced by bevels.
+    /// An edge is beveled if any of its half-edges is selected.
+    ///
+    /// The faces next to a beveled edge are cut back so their new border runs at distance
+    /// `width` from the edge. The gap is bridged by a strip of `segments` faces following a
+    /// circular profile, so a single segment gives a flat chamfer. Where several beveled edges
+    /// meet, the hole between the strips is closed by a corner patch, which is a fan of
+    /// triangles for rounded bevels. Bevels continuing straight through a vertex share their
+    /// profile instead, and at the ends of a bevel, the faces around the end vertex absorb it.
+    ///
+    /// The payloads of new vertices are interpolated from the vertices they are derived from.
+    /// The new faces are tagged with `"bevel"`. The width is limited to half of the length
+    /// of the edges the faces are cut back along. Vertices on the boundary don't get a patch.
+    /// Panics if a selected edge is a boundary edge.
+    fn bevel_edges(&self, selection: &Selection<T::E>, width: T::S, segments: usize) -> Self {
+        assert!(width > T::S::ZERO, "the width must be positive");
+        assert!(segments >= 1, "a bevel needs at least 1 segment");
+
+        // each edge is identified by its smaller half-edge
+        let key = |e: &T::Edge| e.id().min(e.twin_id());
+        let mut beveled = HashSet::new();
+        for e in selection.iter() {
+            let edge = self.edge(e);
+            assert!(
+                !edge.is_boundary_self() && !edge.twin(self).is_boundary_self(),
+                "boundary edges can't be beveled"
+            );
+            beveled.insert(key(edge));
+        }
+        let is_beveled = |e: &T::Edge| beveled.contains(&key(e));
+        let pos = |v: T::V| self.vertex(v).pos();
+        let payload = |v: T::V| self.vertex(v).payload();
+        let mut builder = SoupBuilder::<3, T>::new();
+
+        // Face corners next to one beveled edge slide along the other edge of the corner.
+        // Corners between two beveled edges move into the face.
+        let mut slides: HashMap<(T::E, T::V), Vec<T::S>> = HashMap::new();
+        let mut inner = HashMap::new();
+        for f in self.faces() {
+            for e in f.edges(self) {
+                let prev = e.prev(self);
+                let (v, a, b) = (e.origin_id(), prev.origin_id(), e.target_id(self));
+                let (da, db) = (pos(a) - pos(v), pos(b) - pos(v));
+                let sin = da.normalize().cross(&db.normalize()).length();
+                let slide = width / sin.max(T::S::EPS);
+                match (is_beveled(&prev), is_beveled(&e)) {
+                    (true, true) => {
+                        let ta = (slide / da.length()).min(T::S::HALF);
+                        let tb = (slide / db.length()).min(T::S::HALF);
+                        let side = payload(a).lerped(payload(b), tb / (ta + tb));
+                        let mut vp = payload(v).lerped(&side, ta + tb);
+                        vp.set_pos(pos(v) + da * ta + db * tb);
+                        inner.insert(e.id(), builder.add_vertex(vp));
+                    }
+                    (true, false) => slides.entry((key(&e), v)).or_default().push(slide),
+                    (false, true) => slides.entry((key(&prev), v)).or_default().push(slide),
+                    (false, false) => {}
+                }
+            }
+        }
+        let mut ends = HashMap::new();
+        for e in self.edges() {
+            let (a, b) = (e.origin_id(), e.target_id(self));
+            if e.id() != key(e) {
+                continue;
+            }
+            for (v, w) in [(a, b), (b, a)] {
+                let Some(distances) = slides.get(&(e.id(), v)) else {
+                    continue;
+                };
+                let slide = distances.iter().fold(T::S::ZERO, |s, d| s + *d)
+                    / T::S::from_usize(distances.len());
+                let t = (slide / pos(v).distance(&pos(w))).min(T::S::HALF);
+                let mut vp = payload(v).lerped(payload(w), t);
+                vp.set_pos(pos(v) + (pos(w) - pos(v)) * t);
+                ends.insert((e.id(), v), builder.add_vertex(vp));
+            }
+        }
+
+        // the profiles of the strips at both ends of each beveled edge, starting on the side
+        // of the face of the edge's key
+        let mut profiles: HashMap<(T::E, T::V), Vec<usize>> = HashMap::new();
+        // bevels continuing through a vertex share the profile there and need no patch
+        let mut through = HashSet::new();
+        {
+            let mut shared: HashMap<(T::V, usize, usize), Vec<usize>> = HashMap::new();
+            let origin_side = |x: &T::Edge| {
+                let prev = x.prev(self);
+                if is_beveled(&prev) {
+                    inner[&x.id()]
+                } else {
+                    ends[&(key(&prev), x.origin_id())]
+                }
+            };
+            let target_side = |x: &T::Edge| {
+                let next = x.next(self);
+                if is_beveled(&next) {
+                    inner[&next.id()]
+                } else {
+                    ends[&(key(&next), x.target_id(self))]
+                }
+            };
+            for e in self.edges() {
+                if e.id() != key(e) || !is_beveled(e) {
+                    continue;
+                }
+                let twin = e.twin(self);
+                let (v, u) = (e.origin_id(), e.target_id(self));
+                for (v, u, a, b) in [
+                    (v, u, origin_side(e), target_side(&twin)),
+                    (u, v, target_side(e), origin_side(&twin)),
+                ] {
+                    if let Some(row) = shared.get(&(v, a.min(b), a.max(b))) {
+                        let mut row = row.clone();
+                        if row[0] != a {
+                            row.reverse();
+                        }
+                        profiles.insert((e.id(), v), row);
+                        through.insert(v);
+                        continue;
+                    }
+
+                    // a rational quadratic Bézier curve is a circular arc if the control
+                    // point has the same distance to both ends
+                    let dir = (pos(u) - pos(v)).normalize();
+                    let (pa, pb) = (*builder.vertex(a).pos(), *builder.vertex(b).pos());
+                    let c = pos(v) + dir * ((pa + pb) * T::S::HALF - pos(v)).dot(&dir);
+                    let (ca, cb) = (pa - c, pb - c);
+                    let weight = if ca.length() > T::S::EPS && cb.length() > T::S::EPS {
+                        let cos = ca.normalize().dot(&cb.normalize());
+                        ((T::S::ONE - cos) * T::S::HALF).max(T::S::ZERO).sqrt()
+                    } else {
+                        T::S::ONE
+                    };
+                    let mut row = vec![a];
+                    for j in 1..segments {
+                        let t = T::S::from_usize(j) / T::S::from_usize(segments);
+                        let (wa, wc, wb) = (
+                            (T::S::ONE - t) * (T::S::ONE - t),
+                            T::S::TWO * t * (T::S::ONE - t) * weight,
+                            t * t,
+                        );
+                        let mut vp = builder.vertex(a).lerped(builder.vertex(b), t);
+                        vp.set_pos((pa * wa + c * wc + pb * wb) / (wa + wc + wb));
+                        row.push(builder.add_vertex(vp));
+                    }
+                    row.push(b);
+                    shared.insert((v, a.min(b), a.max(b)), row.clone());
+                    profiles.insert((e.id(), v), row);
+                }
+            }
+        }
+
+        // At the end of a bevel, the faces between the two edges next to it take over the
+        // profile and the edges in between attach to the nearest point of the profile.
+        let mut absorbed: HashMap<T::E, Vec<usize>> = HashMap::new();
+        for v in self.vertices() {
+            let mut out = v.edges_out(self).filter(|e| is_beveled(e));
+            let (Some(h), None) = (out.next(), out.next()) else {
+                continue;
+            };
+            if v.is_boundary(self) {
+                continue;
+            }
+            let mut fan = Vec::new();
+            let mut e = h.prev(self).twin(self);
+            while e.id() != h.id() {
+                fan.push(e.id());
+                e = e.prev(self).twin(self);
+            }
+            let mut row = profiles[&(key(&h), v.id())].clone();
+            if key(&h) != h.id() {
+                row.reverse();
+            }
+            let k = fan.len();
+            let index = |i: usize| (i * segments + (k - 1) / 2) / (k - 1);
+            for i in 0..k - 1 {
+                if i > 0 {
+                    ends.insert((key(self.edge(fan[i])), v.id()), row[index(i)]);
+                }
+                absorbed.insert(
+                    fan[i],
+                    row[index(i)..=index(i + 1)].iter().rev().copied().collect(),
+                );
+            }
+        }
+
+        // cut back the faces
+        let mut original = HashMap::new();
+        let mut corners: HashMap<T::E, Vec<usize>> = HashMap::new();
+        let mut polygons = Vec::new();
+        for f in self.faces() {
+            let mut polygon = Vec::new();
+            for e in f.edges(self) {
+                let v = e.origin_id();
+                let mut end = |x: &T::Edge| match ends.get(&(key(x), v)) {
+                    Some(i) => *i,
+                    None => *original
+                        .entry(v)
+                        .or_insert_with(|| builder.add_vertex(payload(v).clone())),
+                };
+                let prev = e.prev(self);
+                let corner = if let Some(corner) = absorbed.get(&e.id()) {
+                    corner.clone()
+                } else {
+                    match (is_beveled(&prev), is_beveled(&e)) {
+                        (true, true) => vec![inner[&e.id()]],
+                        (true, false) => vec![end(&e)],
+                        (false, true) => vec![end(&prev)],
+                        (false, false) => vec![end(&prev), end(&e)],
+                    }
+                };
+                polygon.extend(corner.iter().copied());
+                corners.insert(e.id(), corner);
+            }
+            polygons.push((polygon, f.payload().tag()));
+        }
+
+        // the strips along the beveled edges
+        for e in self.edges() {
+            if e.id() != key(e) || !is_beveled(e) {
+                continue;
+            }
+            let start = &profiles[&(e.id(), e.origin_id())];
+            let end = &profiles[&(e.id(), e.target_id(self))];
+            for j in 0..segments {
+                polygons.push((
+                    vec![end[j], start[j], start[j + 1], end[j + 1]],
+                    Some("bevel"),
+                ));
+            }
+        }
+
+        // the patches where multiple beveled edges meet
+        for v in self.vertices() {
+            if v.is_boundary(self)
+                || through.contains(&v.id())
+                || v.edges_out(self).filter(|e| is_beveled(e)).count() < 2
+            {
+                continue;
+            }
+            let first = v.edges_out(self).next().unwrap();
+            let mut patch: Vec<usize> = Vec::new();
+            let mut e = first.clone();
+            loop {
+                patch.extend(corners[&e.id()].iter().rev());
+                let prev = e.prev(self);
+                if is_beveled(&prev) {
+                    let row = &profiles[&(key(&prev), v.id())][1..segments];
+                    if key(&prev) == prev.id() {
+                        patch.extend(row.iter());
+                    } else {
+                        patch.extend(row.iter().rev());
+                    }
+                }
+                e = prev.twin(self);
+                if e.id() == first.id() {
+                    break;
+                }
+            }
+            patch.dedup();
+            while patch.len() > 1 && patch.first() == patch.last() {
+                patch.pop();
+            }
+            if patch.len() < 3 {
+                continue;
+            }
+            if segments == 1 || patch.len() == 3 {
+                polygons.push((patch, Some("bevel")));
+                continue;
+            }
+            let mut vp = builder.vertex(patch[0]).clone();
+            let mut center = *vp.pos();
+            for (i, p) in patch.iter().enumerate().skip(1) {
+                let other = builder.vertex(*p);
+                vp = vp.lerped(other, T::S::ONE / T::S::from_usize(i + 1));
+                center += *other.pos();
+            }
+            vp.set_pos(center / T::S::from_usize(patch.len()));
+            let center = builder.add_vertex(vp);
+            for i in 0..patch.len() {
+                let next = patch[(i + 1) % patch.len()];
+                polygons.push((vec![patch[i], next, center], Some("bevel")));
+            }
+        }
+
+        for (polygon, tag) in polygons {
+            if let Some(tag) = tag {
+                builder.add_tagged_face(polygon, tag);
+            } else {
+                builder.add_face(polygon);
+            }
+        }
+        builder.build()
+    }
+
+    /// Returns a copy of the mesh where each selected vertex is cut off at `distance` along
+    /// its edges and replaced by a face through the cut points, tagged with `"chamfer"`.
+    ///
+    /// The distance is limited to half of the length of each edge. Where both ends of an
+    /// edge are cut at its midpoint, the two cut points are merged, e.g., chamfering all
+    /// vertices of a cube by half of its side gives a cuboctahedron.
+    /// The payloads of the cut points are interpolated along the edges.
+    /// Vertices on the boundary are kept as they are.
+    fn chamfer_vertices(&self, selection: &Selection<T::V>, distance: T::S) -> Self {
+        assert!(distance > T::S::ZERO, "the distance must be positive");
+        cut_vertices::<T>(self, selection, |length| distance / length)
+    }
+
+    /// Returns a copy of the mesh where all vertices are cut off at `fraction` of the length
+    /// of each edge, e.g., 1/3 turns a regular octahedron into a truncated octahedron and 1/2
+    /// gives the rectification. Together with `dual`, this turns the Platonic solids into
+    /// the truncated polyhedra. See `chamfer_vertices`.
+    fn truncate(&self, fraction: T::S) -> Self {
+        assert!(
+            fraction > T::S::ZERO && fraction <= T::S::HALF,
+            "the fraction must be in (0, 1/2]"
+        );
+        let all = self.vertices().map(|v| v.id()).collect();
+        cut_vertices::<T>(self, &all, |_| fraction)
+    }
+}
+
+/// Cuts off the selected vertices at the fraction of each adjacent edge given by `t`
+/// for the length of the edge.
+fn cut_vertices<T: MeshTypeHalfEdge + MeshType3D>(
+    mesh: &T::Mesh,
+    selection: &Selection<T::V>,
+    t: impl Fn(T::S) -> T::S,
+) -> T::Mesh
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    let cut = |v: T::V| selection.contains(v) && !mesh.vertex(v).is_boundary(mesh);
+    let mut builder = SoupBuilder::<3, T>::new();
+    let mut original = HashMap::new();
+    // the cut points keyed by the edge and the vertex they belong to, or no vertex if
+    // they meet at the midpoint
+    let mut points: HashMap<(T::E, Option<T::V>), usize> = HashMap::new();
+    let mut point = |builder: &mut SoupBuilder<3, T>, e: &T::Edge| {
+        let (v, w) = (e.origin(mesh), e.target(mesh));
+        let t = t(v.pos().distance(&w.pos())).min(T::S::HALF);
+        let at_midpoint = t >= T::S::HALF - T::S::EPS.sqrt() && cut(w.id());
+        let key = (e.id().min(e.twin_id()), (!at_midpoint).then_some(v.id()));
+        *points.entry(key).or_insert_with(|| {
+            let mut vp = v.payload().lerped(w.payload(), t);
+            vp.set_pos(v.pos() + (w.pos() - v.pos()) * t);
+            builder.add_vertex(vp)
+        })
+    };
+
+    let mut polygons = Vec::new();
+    for f in mesh.faces() {
+        let mut polygon = Vec::new();
+        for e in f.edges(mesh) {
+            let v = e.origin_id();
+            if cut(v) {
+                polygon.push(point(&mut builder, &e.prev(mesh).twin(mesh)));
+                polygon.push(point(&mut builder, &e));
+            } else {
+                polygon.push(
+                    *original
+                        .entry(v)
+                        .or_insert_with(|| builder.add_vertex(e.origin(mesh).payload().clone())),
+                );
+            }
+        }
+        polygon.dedup();
+        while polygon.len() > 1 && polygon.first() == polygon.last() {
+            polygon.pop();
+        }
+        polygons.push((polygon, f.payload().tag()));
+    }
+    for v in selection.iter() {
+        if !cut(v) {
+            continue;
+        }
+        // walk clockwise around the vertex, i.e., counter-clockwise around the new face
+        let first = mesh.vertex(v).edges_out(mesh).next().unwrap();
+        let mut polygon = Vec::new();
+        let mut e = first.clone();
+        loop {
+            polygon.push(point(&mut builder, &e));
+            e = e.prev(mesh).twin(mesh);
+            if e.id() == first.id() {
+                break;
+            }
+        }
+        polygons.push((polygon, Some("chamfer")));
+    }
+
+    for (polygon, tag) in polygons {
+        if polygon.len() < 3 {
+            continue;
+        }
+        if let Some(tag) = tag {
+            builder.add_tagged_face(polygon, tag);
+        } else {
+            builder.add_face(polygon);
+        }
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_bevel_cube() {
+        let cube = Mesh3d64::cube(2.0);
+        let all = cube.select_edges(|_| true);
+
+        // a chamfer of all edges
+        let chamfered = cube.bevel_edges(&all, 0.2, 1);
+        assert!(chamfered.check().is_ok());
+        assert!(!chamfered.is_open());
+        assert_eq!(chamfered.num_faces(), 6 + 12 + 8);
+        assert_eq!(chamfered.num_vertices(), 24);
+        // the same solid as an intersection of half-spaces
+        let mut planes = Vec::new();
+        for x in [-1.0, 1.0] {
+            planes.push((Vec3::new(x, 0.0, 0.0), 1.0));
+            planes.push((Vec3::new(0.0, x, 0.0), 1.0));
+            planes.push((Vec3::new(0.0, 0.0, x), 1.0));
+            for y in [-1.0, 1.0] {
+                planes.push((Vec3::new(x, y, 0.0), 1.8));
+                planes.push((Vec3::new(x, 0.0, y), 1.8));
+                planes.push((Vec3::new(0.0, x, y), 1.8));
+                for z in [-1.0, 1.0] {
+                    planes.push((Vec3::new(x, y, z), 2.6));
+                }
+            }
+        }
+        let expected = Mesh3d64::convex_polyhedron(&planes)
+            .report()
+            .volume
+            .unwrap();
+        assert!((chamfered.report().volume.unwrap() - expected).abs() < 1e-9);
+
+        // rounded edges lie between the chamfer and the cube
+        let rounded = cube.bevel_edges(&all, 0.2, 4);
+        assert!(rounded.check().is_ok());
+        assert!(!rounded.is_open());
+        assert_eq!(rounded.num_faces(), 6 + 12 * 4 + 8 * 12);
+        let volume = rounded.report().volume.unwrap();
+        assert!(volume > expected && volume < 8.0);
+        for v in rounded.vertices() {
+            assert!(v.pos().abs().max() <= 1.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_bevel_single_edge() {
+        let cube = Mesh3d64Tagged::cube(2.0);
+        let e = cube.edges().next().unwrap().id();
+        let selection = [e].into_iter().collect();
+
+        let chamfered = cube.bevel_edges(&selection, 0.2, 1);
+        assert!(chamfered.check().is_ok());
+        assert!(!chamfered.is_open());
+        assert_eq!(chamfered.num_faces(), 7);
+        assert_eq!(chamfered.num_vertices(), 10);
+        assert!((chamfered.report().volume.unwrap() - (8.0 - 0.02 * 2.0)).abs() < 1e-9);
+        assert_eq!(chamfered.select_by_tag("bevel").len(), 1);
+
+        // the end faces take over the profile
+        let rounded = cube.bevel_edges(&selection, 0.2, 3);
+        assert!(rounded.check().is_ok());
+        assert!(!rounded.is_open());
+        assert_eq!(rounded.num_faces(), 6 + 3);
+        assert_eq!(rounded.num_vertices(), 8 - 2 + 2 * 4);
+        assert_eq!(
+            rounded
+                .faces()
+                .filter(|f| f.num_vertices(&rounded) == 7)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_chamfer_vertices() {
+        let cube = Mesh3d64Tagged::cube(2.0);
+        let all = cube.select_vertices(|_| true);
+
+        // a truncated cube
+        let chamfered = cube.chamfer_vertices(&all, 0.5);
+        assert!(chamfered.check().is_ok());
+        assert!(!chamfered.is_open());
+        assert_eq!(chamfered.num_faces(), 6 + 8);
+        assert_eq!(chamfered.num_vertices(), 24);
+        assert_eq!(chamfered.select_by_tag("chamfer").len(), 8);
+        let corner = 0.5f64.powi(3) / 6.0;
+        assert!((chamfered.report().volume.unwrap() - (8.0 - 8.0 * corner)).abs() < 1e-9);
+
+        // cutting at the midpoints merges the cut points
+        let rectified = cube.chamfer_vertices(&all, 5.0);
+        assert!(rectified.check().is_ok());
+        assert!(!rectified.is_open());
+        assert_eq!(rectified.num_faces(), 14);
+        assert_eq!(rectified.num_vertices(), 12);
+        assert!((rectified.report().volume.unwrap() - 20.0 / 3.0).abs() < 1e-9);
+
+        // a single vertex
+        let v = cube.vertices().next().unwrap().id();
+        let one = cube.chamfer_vertices(&[v].into_iter().collect(), 0.5);
+        assert!(one.check().is_ok());
+        assert!(!one.is_open());
+        assert_eq!(one.num_faces(), 7);
+        assert_eq!(one.num_vertices(), 10);
+        assert!((one.report().volume.unwrap() - (8.0 - corner)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_truncate() {
+        // the truncated octahedron from the dual of a cube
+        let octahedron = Mesh3d64::cube(2.0).dual::<3>();
+        let truncated = octahedron.truncate(1.0 / 3.0);
+        assert!(truncated.check().is_ok());
+        assert!(!truncated.is_open());
+        assert_eq!(truncated.num_faces(), 14);
+        assert_eq!(truncated.num_vertices(), 24);
+        assert_eq!(
+            truncated
+                .faces()
+                .filter(|f| f.num_vertices(&truncated) == 6)
+                .count(),
+            8
+        );
+        // all edges have the same length
+        let length = |e: &HalfEdgeImpl<MeshType3d64PNU>| {
+            e.origin(&truncated)
+                .pos()
+                .distance(&e.target(&truncated).pos())
+        };
+        let first = length(truncated.edges().next().unwrap());
+        assert!(truncated.edges().all(|e| (length(e) - first).abs() < 1e-9));
+
+        // the icosidodecahedron as the rectified icosahedron
+        let rectified = Mesh3d64::regular_icosahedron(1.0).truncate(0.5);
+        assert!(rectified.check().is_ok());
+        assert!(!rectified.is_open());
+        assert_eq!(rectified.num_faces(), 32);
+        assert_eq!(rectified.num_vertices(), 30);
+    }
+
+    #[test]
+    fn test_bevel_open_mesh() {
+        // a 4x4 grid with an interior edge through the center
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        for y in 0..5 {
+            for x in 0..5 {
+                let (x, y) = (x as f64 - 2.0, y as f64 - 2.0);
+                let mut vp = VertexPayloadPNU::from_pos(Vec3::new(x, y, 0.0));
+                vp.set_uv(Vec2::new(x, y));
+                builder.add_vertex(vp);
+            }
+        }
+        for y in 0..4 {
+            for x in 0..4 {
+                let i = y * 5 + x;
+                builder.add_face([i, i + 1, i + 6, i + 5]);
+            }
+        }
+        let grid: Mesh3d64 = builder.build();
+        // the signed area in the xy-plane
+        let area = |mesh: &Mesh3d64| {
+            mesh.faces()
+                .map(|f| {
+                    let vs: Vec<Vec3<f64>> = f.vertices(mesh).map(|v| v.pos()).collect();
+                    (0..vs.len())
+                        .map(|i| vs[i].xy().perp(&vs[(i + 1) % vs.len()].xy()) / 2.0)
+                        .sum::<f64>()
+                })
+                .sum::<f64>()
+        };
+
+        let edges = grid.select_edges(|e| {
+            let (a, b) = (e.origin(&grid).pos(), e.target(&grid).pos());
+            a.y == 0.0 && b.y == 0.0 && a.x.min(b.x) >= -1.0 && a.x.max(b.x) <= 1.0
+        });
+        for segments in [1, 2] {
+            let beveled = grid.bevel_edges(&edges, 0.25, segments);
+            assert!(beveled.check().is_ok());
+            assert_eq!(beveled.num_faces(), 16 + 2 * segments);
+            assert!((area(&beveled) - 16.0).abs() < 1e-9);
+            // the uvs are interpolated along with the positions
+            for v in beveled.vertices() {
+                assert_eq!(v.pos().z, 0.0);
+                assert!((v.payload().uv() - v.pos().xy()).norm() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,621 @@
+use crate::{
+    math::{HasNormal, HasPosition, HasUV, OrderedFloats, Scalar, Vector, Vector3D},
+    mesh::{
+        CornerPayload, DefaultEdgePayload, DefaultFacePayload, EdgeBasics, FaceBasics, HalfEdge,
+        HalfEdgeMesh, MeshType3D, MeshTypeHalfEdge, SoupBuilder, Triangulateable, VertexBasics,
+    },
+    tesselate::{TesselationMeta, TriangulationAlgorithm},
+};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+/// Parameters for `MeshDecimate::decimate`.
+///
+/// Constrained edges add planes through the edge and perpendicular to the adjacent faces to
+/// the error quadrics of their vertices, weighted by the penalty weight times the squared
+/// length of the edge. So they can still be simplified along themselves, but moving them
+/// sideways is expensive. A weight of zero disables the constraint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecimateOptions<S: Scalar> {
+    /// The number of triangles to stop at.
+    pub target_triangles: usize,
+
+    /// Collapses with a larger quadric error are not performed, even if the target
+    /// isn't reached yet.
+    pub max_error: S,
+
+    /// The penalty weight of edges on the boundary of the mesh.
+    pub boundary_weight: S,
+
+    /// The penalty weight of uv seams, i.e., edges where the uv coordinates of the face
+    /// corners on both sides differ.
+    pub seam_weight: S,
+
+    /// The penalty weight of material boundaries, i.e., edges between faces with different
+    /// payloads, e.g., different tags.
+    pub material_weight: S,
+
+    /// The penalty weight of sharp edges, i.e., hard edges where the normals of the face
+    /// corners on both sides differ, and edges with a dihedral angle above `sharp_angle`.
+    pub sharp_weight: S,
+
+    /// The dihedral angle (in radians) above which edges count as sharp.
+    pub sharp_angle: S,
+
+    /// Collapses that rotate the normal of a triangle by more than this angle (in radians)
+    /// are rejected, which avoids fold-overs and shading popping.
+    pub max_normal_change: S,
+}
+
+impl<S: Scalar> Default for DecimateOptions<S> {
+    fn default() -> Self {
+        Self {
+            target_triangles: 0,
+            max_error: S::INFINITY,
+            boundary_weight: S::from_usize(100),
+            seam_weight: S::from_usize(100),
+            material_weight: S::from_usize(100),
+            sharp_weight: S::TEN,
+            sharp_angle: S::PI / S::from_usize(3),
+            max_normal_change: S::PI / S::TWO,
+        }
+    }
+}
+
+/// A symmetric 4x4 matrix measuring the sum of squared distances to a set of planes.
+#[derive(Debug, Clone, Copy)]
+struct Quadric<S: Scalar>([S; 10]);
+
+impl<S: Scalar> Quadric<S> {
+    fn zero() -> Self {
+        Self([S::ZERO; 10])
+    }
+
+    /// The quadric of the plane `n·p + d = 0` with unit normal `n`, scaled by `weight`.
+    fn plane<V: Vector3D<S = S>>(n: V, d: S, weight: S) -> Self {
+        let (a, b, c) = (n.x(), n.y(), n.z());
+        Self(
+            [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ]
+            .map(|x| x * weight),
+        )
+    }
+
+    fn add(&mut self, other: &Self) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a += b;
+        }
+    }
+
+    fn error<V: Vector3D<S = S>>(&self, p: V) -> S {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, dd] = self.0;
+        let (x, y, z) = (p.x(), p.y(), p.z());
+        let two = S::TWO;
+        (aa * x * x
+            + bb * y * y
+            + cc * z * z
+            + dd
+            + two * (ab * x * y + ac * x * z + bc * y * z + ad * x + bd * y + cd * z))
+            .max(S::ZERO)
+    }
+}
+
+/// A triangle of the mesh being decimated with the payloads of its corners and face.
+struct Triangle<EP, FP> {
+    vertices: [usize; 3],
+    corners: [EP; 3],
+    face: FP,
+    alive: bool,
+}
+
+impl<EP, FP> Triangle<EP, FP> {
+    fn corner(&self, v: usize) -> Option<usize> {
+        self.vertices.iter().position(|w| *w == v)
+    }
+}
+
+/// Returns the vertices sharing a triangle with `v`.
+fn neighbors<EP, FP>(
+    incident: &[Vec<usize>],
+    triangles: &[Triangle<EP, FP>],
+    v: usize,
+) -> HashSet<usize> {
+    incident[v]
+        .iter()
+        .flat_map(|t| triangles[*t].vertices)
+        .filter(|w| *w != v)
+        .collect()
+}
+
+/// Methods to reduce the number of triangles of meshes, e.g., to generate levels of detail.
+pub trait MeshDecimate<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    HalfEdgeMesh<T> + Triangulateable<T>
+where
+    T::EP: DefaultEdgePayload + CornerPayload<T::VP>,
+    T::FP: DefaultFacePayload,
+    T::VP: HasNormal<3, T::Vec, S = T::S> + HasUV<T::Vec2, S = T::S>,
+{
+    /// Returns a triangulated copy of the mesh with at most `options.target_triangles`
+    /// triangles if that is possible within `options.max_error`.
+    ///
+    /// Edges are collapsed in the order of their quadric error (Garland and Heckbert).
+    /// Vertices are only moved onto their neighbors (half-edge collapses), so the remaining
+    /// vertices keep their positions and payloads and textures don't swim between levels of
+    /// detail. Boundaries, uv seams, material boundaries, and sharp edges are preserved
+    /// according to the penalty weights in `options`. The payloads of the face corners and
+    /// faces are carried along, so hard edges and seams stored in the corners survive.
+    /// Collapses that would change the topology or flip triangles are never performed.
+    fn decimate(&self, options: &DecimateOptions<T::S>) -> Self {
+        let (indices, ranges) = self.triangulate_face_ranges(
+            TriangulationAlgorithm::Auto,
+            &mut TesselationMeta::default(),
+        );
+        let mut ids = HashMap::new();
+        let mut payloads: Vec<T::VP> = Vec::new();
+        let mut index = |v: T::V| {
+            *ids.entry(v).or_insert_with(|| {
+                payloads.push(self.vertex(v).payload().clone());
+                payloads.len() - 1
+            })
+        };
+        let mut triangles: Vec<Triangle<T::EP, T::FP>> = Vec::new();
+        for (f, range) in ranges {
+            let face = self.face(f);
+            let corners: HashMap<T::V, T::EP> = face
+                .edges(self)
+                .map(|e| (e.origin_id(), e.payload().clone()))
+                .collect();
+            for tri in indices[range].chunks_exact(3) {
+                triangles.push(Triangle {
+                    vertices: [index(tri[0]), index(tri[1]), index(tri[2])],
+                    corners: [0, 1, 2].map(|i| corners[&tri[i]].clone()),
+                    face: *face.payload(),
+                    alive: true,
+                });
+            }
+        }
+        let pos: Vec<T::Vec> = payloads.iter().map(|vp| *vp.pos()).collect();
+        let normal = |vs: [usize; 3]| (pos[vs[1]] - pos[vs[0]]).cross(&(pos[vs[2]] - pos[vs[0]]));
+        let n = payloads.len();
+        let mut incident = vec![Vec::new(); n];
+        for (t, tri) in triangles.iter().enumerate() {
+            for v in tri.vertices {
+                incident[v].push(t);
+            }
+        }
+
+        // the error quadrics of the planes of the triangles weighted by their area
+        let mut quadrics = vec![Quadric::zero(); n];
+        for tri in &triangles {
+            let normal = normal(tri.vertices);
+            let area = normal.length() * T::S::HALF;
+            if area <= T::S::EPS {
+                continue;
+            }
+            let normal = normal.normalize();
+            let q = Quadric::plane(normal, -normal.dot(&pos[tri.vertices[0]]), area);
+            for v in tri.vertices {
+                quadrics[v].add(&q);
+            }
+        }
+
+        // the constrained edges and the edges the corner payloads don't continue across
+        let key = |a: usize, b: usize| (a.min(b), a.max(b));
+        let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (t, tri) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                let (a, b) = (tri.vertices[i], tri.vertices[(i + 1) % 3]);
+                edges.entry(key(a, b)).or_default().push(t);
+            }
+        }
+        let corner_payload = |t: usize, v: usize| {
+            let tri = &triangles[t];
+            let mut vp = payloads[v].clone();
+            tri.corners[tri.corner(v).unwrap()].apply_corner(&mut vp);
+            vp
+        };
+        let min_cos = options.sharp_angle.cos();
+        let mut constrained = HashSet::new();
+        let mut wedges = HashSet::new();
+        for (&(a, b), ts) in &edges {
+            let weight = if let [s, t] = ts[..] {
+                let (sa, ta) = (corner_payload(s, a), corner_payload(t, a));
+                let (sb, tb) = (corner_payload(s, b), corner_payload(t, b));
+                let seam = sa.uv() != ta.uv() || sb.uv() != tb.uv();
+                let hard = sa.normal() != ta.normal() || sb.normal() != tb.normal();
+                if seam || hard {
+                    wedges.insert((a, b));
+                }
+                let (ns, nt) = (
+                    normal(triangles[s].vertices).normalize(),
+                    normal(triangles[t].vertices).normalize(),
+                );
+                let sharp = hard || ns.dot(&nt) < min_cos;
+                let material = triangles[s].face != triangles[t].face;
+                [
+                    (seam, options.seam_weight),
+                    (sharp, options.sharp_weight),
+                    (material, options.material_weight),
+                ]
+                .iter()
+                .filter(|(c, _)| *c)
+                .fold(T::S::ZERO, |w, (_, x)| w.max(*x))
+            } else {
+                // boundary and non-manifold edges
+                options.boundary_weight
+            };
+            if ts.len() != 2 {
+                constrained.insert((a, b));
+            }
+            if weight <= T::S::ZERO {
+                continue;
+            }
+            let d = pos[b] - pos[a];
+            for t in ts {
+                let n = d.cross(&normal(triangles[*t].vertices));
+                if n.length_squared() <= T::S::EPS {
+                    continue;
+                }
+                let n = n.normalize();
+                let q = Quadric::plane(n, -n.dot(&pos[a]), weight * d.length_squared());
+                quadrics[a].add(&q);
+                quadrics[b].add(&q);
+            }
+        }
+        let boundary: Vec<bool> = (0..n)
+            .map(|v| {
+                incident[v].iter().any(|t| {
+                    let tri = &triangles[*t];
+                    tri.vertices
+                        .iter()
+                        .any(|w| *w != v && constrained.contains(&key(v, *w)))
+                })
+            })
+            .collect();
+
+        // collapse the cheapest half-edges first; entries are outdated once one of their
+        // vertices changed
+        let mut version = vec![0usize; n];
+        let mut heap = BinaryHeap::new();
+        let push = |heap: &mut BinaryHeap<_>,
+                    quadrics: &[Quadric<T::S>],
+                    version: &[usize],
+                    a: usize,
+                    b: usize| {
+            let mut q = quadrics[a];
+            q.add(&quadrics[b]);
+            heap.push(Reverse((
+                OrderedFloats::new(q.error(pos[b])),
+                a,
+                b,
+                version[a],
+                version[b],
+            )));
+        };
+        for &(a, b) in edges.keys() {
+            push(&mut heap, &quadrics, &version, a, b);
+            push(&mut heap, &quadrics, &version, b, a);
+        }
+        let max_cos = options.max_normal_change.cos();
+        let mut alive = triangles.len();
+        let mut removed = vec![false; n];
+        while alive > options.target_triangles {
+            let Some(Reverse((error, a, b, va, vb))) = heap.pop() else {
+                break;
+            };
+            if removed[a] || removed[b] || va != version[a] || vb != version[b] {
+                continue;
+            }
+            if error.value() > options.max_error {
+                break;
+            }
+            let shared: Vec<usize> = incident[a]
+                .iter()
+                .copied()
+                .filter(|t| triangles[*t].corner(b).is_some())
+                .collect();
+            if shared.is_empty() {
+                continue;
+            }
+
+            // the link condition: the only common neighbors are opposite to the collapsed edge
+            let common = neighbors(&incident, &triangles, a)
+                .intersection(&neighbors(&incident, &triangles, b))
+                .count();
+            let is_boundary_edge = constrained.contains(&key(a, b));
+            if common != shared.len() || shared.len() > 2 || (boundary[a] && !is_boundary_edge) {
+                continue;
+            }
+
+            // don't flip or degenerate the remaining triangles
+            let moved: Vec<usize> = incident[a]
+                .iter()
+                .copied()
+                .filter(|t| !shared.contains(t))
+                .collect();
+            let flips = moved.iter().any(|t| {
+                let old = normal(triangles[*t].vertices);
+                let new = normal(triangles[*t].vertices.map(|v| if v == a { b } else { v }));
+                let (lo, ln) = (old.length(), new.length());
+                ln <= T::S::EPS || old.dot(&new) < max_cos * lo * ln
+            });
+            if flips {
+                continue;
+            }
+
+            // The corners at `a` take the payload of the corner at `b` in the collapsed
+            // triangle on their side of the seams around `a`.
+            let mut corners = Vec::new();
+            for t in &moved {
+                let mut found = None;
+                'search: for forward in [true, false] {
+                    let (mut current, mut from) = (*t, usize::MAX);
+                    for _ in 0..incident[a].len() {
+                        let tri = &triangles[current];
+                        let i = tri.corner(a).unwrap();
+                        let o = if forward {
+                            tri.vertices[(i + 1) % 3]
+                        } else {
+                            tri.vertices[(i + 2) % 3]
+                        };
+                        if wedges.contains(&key(a, o)) {
+                            break;
+                        }
+                        let Some(next) = edges[&key(a, o)]
+                            .iter()
+                            .copied()
+                            .find(|s| *s != current && *s != from && triangles[*s].alive)
+                        else {
+                            break;
+                        };
+                        if shared.contains(&next) {
+                            found = Some(next);
+                            break 'search;
+                        }
+                        (from, current) = (current, next);
+                    }
+                }
+                let source = &triangles[found.unwrap_or(shared[0])];
+                corners.push(source.corners[source.corner(b).unwrap()].clone());
+            }
+
+            // collapse
+            let around = neighbors(&incident, &triangles, a);
+            for t in &shared {
+                triangles[*t].alive = false;
+                alive -= 1;
+                for v in triangles[*t].vertices {
+                    incident[v].retain(|s| s != t);
+                }
+            }
+            for (t, corner) in moved.iter().zip(corners) {
+                let i = triangles[*t].corner(a).unwrap();
+                triangles[*t].vertices[i] = b;
+                triangles[*t].corners[i] = corner;
+                incident[b].push(*t);
+            }
+            for o in around {
+                if o == b {
+                    continue;
+                }
+                let (from, to) = (key(a, o), key(b, o));
+                let mut ts = edges.remove(&from).unwrap_or_default();
+                ts.extend(edges.remove(&to).unwrap_or_default());
+                ts.retain(|t| triangles[*t].alive);
+                ts.dedup();
+                edges.insert(to, ts);
+                if wedges.remove(&from) {
+                    wedges.insert(to);
+                }
+                if constrained.remove(&from) {
+                    constrained.insert(to);
+                }
+            }
+            edges.remove(&key(a, b));
+            wedges.remove(&key(a, b));
+            constrained.remove(&key(a, b));
+            incident[a].clear();
+            removed[a] = true;
+            let q = quadrics[a];
+            quadrics[b].add(&q);
+            version[b] += 1;
+            for c in neighbors(&incident, &triangles, b) {
+                push(&mut heap, &quadrics, &version, b, c);
+                push(&mut heap, &quadrics, &version, c, b);
+            }
+        }
+
+        let mut builder = SoupBuilder::<3, T>::new();
+        let mut map = HashMap::new();
+        let mut kept = Vec::new();
+        for tri in triangles.iter().filter(|tri| tri.alive) {
+            let cycle = tri.vertices.map(|v| {
+                *map.entry(v)
+                    .or_insert_with(|| builder.add_vertex(payloads[v].clone()))
+            });
+            assert!(builder.add_face(cycle));
+            kept.push(tri);
+        }
+        let mut mesh = Self::default();
+        let faces = builder.insert_into(&mut mesh);
+        for (f, tri) in faces.into_iter().zip(kept) {
+            *mesh.face_mut(f).payload_mut() = tri.face;
+            let es = mesh.face(f).edge_ids(&mesh).collect::<Vec<_>>();
+            for e in es {
+                let p = mesh.edge(e).origin(&mesh).pos();
+                let i = (0..3)
+                    .min_by_key(|i| OrderedFloats::new(pos[tri.vertices[*i]].distance_squared(&p)))
+                    .unwrap();
+                *mesh.edge_mut(e).payload_mut() = tri.corners[i].clone();
+            }
+        }
+        mesh
+    }
+
+    /// Returns a decimated copy of the mesh for each number of triangles in `targets`,
+    /// e.g., to generate a chain of levels of detail. Each level is decimated from the
+    /// original mesh using the other parameters from `options`.
+    fn decimate_lods(&self, targets: &[usize], options: &DecimateOptions<T::S>) -> Vec<Self> {
+        targets
+            .iter()
+            .map(|target| {
+                self.decimate(&DecimateOptions {
+                    target_triangles: *target,
+                    ..*options
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn triangles<T: MeshType3D>(mesh: &T::Mesh) -> usize {
+        mesh.faces().map(|f| f.num_vertices(mesh) - 2).sum()
+    }
+
+    /// The vertices and quads of an 8x8 grid in the xy-plane centered at the origin
+    /// lifted by `z(x)`.
+    fn grid(z: impl Fn(f64) -> f64) -> (Vec<Vec3<f64>>, Vec<[usize; 4]>) {
+        let mut vertices = Vec::new();
+        for y in 0..9 {
+            for x in 0..9 {
+                let x = x as f64 / 2.0 - 2.0;
+                vertices.push(Vec3::new(x, y as f64 / 2.0 - 2.0, z(x)));
+            }
+        }
+        let mut quads = Vec::new();
+        for y in 0..8 {
+            for x in 0..8 {
+                let i = y * 9 + x;
+                quads.push([i, i + 1, i + 10, i + 9]);
+            }
+        }
+        (vertices, quads)
+    }
+
+    #[test]
+    fn test_decimate_sphere() {
+        let sphere = Mesh3d64::uv_sphere(1.0, 16, 32);
+        let options = DecimateOptions {
+            target_triangles: 200,
+            ..Default::default()
+        };
+        let lods = sphere.decimate_lods(&[600, 200], &options);
+        let volume = sphere.report().volume.unwrap();
+        for (lod, target) in lods.iter().zip([600, 200]) {
+            assert!(lod.check().is_ok());
+            assert!(!lod.is_open());
+            assert_eq!(triangles::<MeshType3d64PNU>(lod), target);
+            // the vertices aren't moved
+            for v in lod.vertices() {
+                assert!((v.pos().norm() - 1.0).abs() < 1e-9);
+            }
+            assert!((lod.report().volume.unwrap() - volume).abs() < 0.1 * volume);
+        }
+
+        // the error bound stops early
+        let coarse = sphere.decimate(&DecimateOptions {
+            max_error: 1e-6,
+            ..options
+        });
+        assert!(triangles::<MeshType3d64PNU>(&coarse) > 600);
+    }
+
+    #[test]
+    fn test_decimate_constraints() {
+        // two materials meeting at x = 0 on a flat grid or on a ridge
+        let build = |z: fn(f64) -> f64| {
+            let (vertices, quads) = grid(z);
+            let mut builder = SoupBuilder::<3, MeshType3d64PNUTagged>::new();
+            for p in &vertices {
+                builder.add_vertex(VertexPayloadPNU::from_pos(*p));
+            }
+            for q in &quads {
+                let x = q.iter().map(|i| vertices[*i].x).sum::<f64>();
+                builder.add_tagged_face(*q, if x < 0.0 { "left" } else { "right" });
+            }
+            builder.build()
+        };
+        let separated = |mesh: &Mesh3d64Tagged| {
+            mesh.faces().all(|f| {
+                let xs = f.vertices(mesh).map(|v| v.pos().x).collect::<Vec<_>>();
+                let left = xs.iter().all(|x| *x <= 1e-9);
+                let right = xs.iter().all(|x| *x >= -1e-9);
+                (left || right) && f.payload().tag() == Some(if left { "left" } else { "right" })
+            })
+        };
+        let options = DecimateOptions {
+            target_triangles: 4,
+            ..Default::default()
+        };
+        for (mesh, material) in [(build(|_| 0.0), 100.0), (build(|x| x.abs() * 0.5), 0.0)] {
+            let lod = mesh.decimate(&DecimateOptions {
+                material_weight: material,
+                sharp_angle: 0.1,
+                ..options
+            });
+            assert!(lod.check().is_ok());
+            assert_eq!(triangles::<MeshType3d64PNUTagged>(&lod), 4);
+            assert!(separated(&lod));
+            // the outline is preserved
+            assert!((lod.report().area - mesh.report().area).abs() < 1e-9);
+        }
+
+        // without constraints, the flat grid is simplified across the materials
+        let lod = build(|_| 0.0).decimate(&DecimateOptions {
+            target_triangles: 2,
+            material_weight: 0.0,
+            ..options
+        });
+        assert_eq!(triangles::<MeshType3d64PNUTagged>(&lod), 2);
+        assert!(!separated(&lod));
+    }
+
+    #[test]
+    fn test_decimate_seams() {
+        // a flat grid with a uv seam along x = 0 stored in the face corners
+        let (vertices, quads) = grid(|_| 0.0);
+        let uv = |p: Vec3<f64>, left: bool| Vec2::new(p.x + if left { 0.0 } else { 10.0 }, p.y);
+        let mut mesh = Mesh3d64Corners::default();
+        let faces = mesh.insert_indexed_faces(
+            vertices.iter().map(|p| VertexPayloadPNU::from_pos(*p)),
+            quads.iter().map(|q| q.to_vec()),
+        );
+        for f in faces {
+            let left = mesh.face(f).centroid(&mesh).x < 0.0;
+            for e in mesh.face(f).edge_ids(&mesh).collect::<Vec<_>>() {
+                let p = mesh.edge(e).origin(&mesh).pos();
+                mesh.edge_mut(e).payload_mut().set_uv(Some(uv(p, left)));
+            }
+        }
+
+        let lod = mesh.decimate(&DecimateOptions {
+            target_triangles: 4,
+            ..Default::default()
+        });
+        assert!(lod.check().is_ok());
+        assert_eq!(triangles::<MeshType3d64PNUCorners>(&lod), 4);
+        for f in lod.faces() {
+            let left = f.centroid(&lod).x < 0.0;
+            for e in f.edges(&lod) {
+                assert_eq!(e.payload().uv(), Some(uv(e.origin(&lod).pos(), left)));
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector3DIteratorExt},
+    mesh::{FaceBasics, MeshBasics, MeshType3D, VertexBasics},
+};
+use std::collections::HashMap;
+
+/// Methods to displace the vertices of a mesh.
+pub trait MeshDisplace<T: MeshType3D<Mesh = Self>>: MeshBasics<T> {
+    /// Moves each vertex along its normal by `offset(pos)`.
+    ///
+    /// The vertex normals are the area-weighted averages of the normals of the adjacent faces,
+    /// so they don't depend on the normals stored in the vertex payloads.
+    /// All vertices are evaluated before any of them is moved.
+    ///
+    /// Combined with a noise sampler, e.g., `Fbm::sample` when the `noise` feature
+    /// is enabled, this turns an `icosphere` into an asteroid or planet.
+    fn displace_along_normals(&mut self, offset: impl Fn(T::Vec) -> T::S) -> &mut Self {
+        let mut normals: HashMap<T::V, T::Vec> = HashMap::new();
+        for f in self.faces() {
+            let vs = f.vertices(self).map(|v| v.pos()).collect::<Vec<T::Vec>>();
+            // Newell's normal is scaled by the area of the face
+            let n = vs.iter().copied().normal();
+            for v in f.vertex_ids(self) {
+                let e = normals.entry(v).or_insert(T::Vec::zero());
+                *e += n;
+            }
+        }
+
+        let moved = self
+            .vertices()
+            .filter_map(|v| {
+                let n = normals.get(&v.id())?;
+                if n.length_squared() <= T::S::EPS {
+                    return None;
+                }
+                let p: T::Vec = v.pos();
+                Some((v.id(), p + n.normalize() * offset(p)))
+            })
+            .collect::<Vec<_>>();
+        for (v, p) in moved {
+            self.vertex_mut(v).payload_mut().set_pos(p);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_displace_along_normals() {
+        let base = Mesh3d64::icosphere(1.0, 4);
+        let mut sphere = base.clone();
+        sphere.displace_along_normals(|_| 0.5);
+        for v in sphere.vertices() {
+            let old = base.vertex(v.id()).pos();
+            assert!(v.pos().distance(&old).is_about(0.5, 1e-9));
+            // the vertex normals of a sphere point approximately outwards
+            assert!(v.pos().norm().is_about(1.5, 1e-2));
+        }
+
+        // a bumpy sphere
+        let mut sphere = Mesh3d64::icosphere(1.0, 4);
+        sphere.displace_along_normals(|p| 0.1 * (5.0 * p.x).sin());
+        assert!(sphere.check().is_ok());
+        for v in sphere.vertices() {
+            let r = v.pos().norm();
+            assert!(r > 0.89 && r < 1.11);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "noise")]
+    fn test_displace_noise() {
+        let fbm = Fbm::<f64> {
+            frequency: 2.0,
+            ..Default::default()
+        };
+        let mut asteroid = Mesh3d64::icosphere(1.0, 4);
+        asteroid.displace_along_normals(|p| 0.2 * fbm.sample(p));
+        assert!(asteroid.check().is_ok());
+        let radii = asteroid
+            .vertices()
+            .map(|v| v.pos().norm())
+            .collect::<Vec<_>>();
+        assert!(radii.iter().all(|r| *r >= 0.8 - 1e-9 && *r <= 1.2 + 1e-9));
+        assert!(radii.iter().any(|r| (r - 1.0).abs() > 0.01));
+    }
+}
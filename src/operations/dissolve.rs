@@ -0,0 +1,297 @@
+use crate::{
+    math::{IndexType, Scalar, Vector, Vector3DIteratorExt},
+    mesh::{
+        EdgeBasics, FaceBasics, HalfEdge, MeshBasics, MeshBuilder, MeshHalfEdgeBuilder, MeshType3D,
+        MeshTypeHalfEdge, VertexBasics,
+    },
+};
+use std::collections::HashSet;
+
+/// Returns the Newell normal of the face without asserting that it is planar.
+fn newell_normal<T: MeshType3D>(mesh: &T::Mesh, f: T::F) -> T::Vec {
+    mesh.face(f).vertices(mesh).map(|v| v.pos()).normal()
+}
+
+/// Returns whether `v` has two neighbors and joining its edges won't turn any of
+/// the adjacent faces into a face with less than three vertices.
+fn can_join_edges<T: MeshTypeHalfEdge + MeshType3D>(mesh: &T::Mesh, v: T::V) -> bool {
+    let vertex = mesh.vertex(v);
+    vertex.degree(mesh) == 2
+        && vertex
+            .edges_out(mesh)
+            .all(|e| e.is_boundary_self() || mesh.face(e.face_id()).num_vertices(mesh) > 3)
+}
+
+/// Joins the edges at `v` and returns the ids of the halfedges that were removed.
+fn join_edges_tracked<T: MeshTypeHalfEdge + MeshType3D>(mesh: &mut T::Mesh, v: T::V) -> Vec<T::E> {
+    let before = mesh
+        .vertex(v)
+        .edges_out(mesh)
+        .flat_map(|e| [e.id(), e.twin_id()])
+        .collect::<Vec<_>>();
+    let a = mesh.join_edges(v);
+    let a_twin = mesh.edge(a).twin_id();
+    before
+        .into_iter()
+        .filter(|e| *e != a && *e != a_twin)
+        .collect()
+}
+
+/// Methods to remove vertices and edges while merging the surrounding faces into larger
+/// n-gons, e.g., to undo subdivisions or clean up after boolean operations.
+pub trait MeshDissolve<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshHalfEdgeBuilder<T> + MeshBuilder<T>
+{
+    /// Removes the vertex `v`.
+    ///
+    /// If `v` has exactly two neighbors, its edges are joined, i.e., this is the inverse of
+    /// subdividing an edge, and the face of one of the joined halfedges is returned.
+    /// Otherwise, `v` must be an interior vertex and all faces around it are merged into a
+    /// single face, which is returned. The payload of one of the faces is kept.
+    fn dissolve_vertex(&mut self, v: T::V) -> T::F {
+        if self.vertex(v).degree(self) == 2 {
+            let a = self.join_edges(v);
+            return self.edge(a).face_id();
+        }
+        assert!(
+            self.vertex(v)
+                .edges_out(self)
+                .all(|e| !e.is_boundary_self() && !e.twin(self).is_boundary_self()),
+            "Vertex {} is at the boundary",
+            v
+        );
+        while self.vertex(v).degree(self) > 2 {
+            let e = self
+                .vertex(v)
+                .edges_out(self)
+                .find(|e| e.target(self).degree(self) > 2)
+                .unwrap_or_else(|| panic!("Cannot dissolve vertex {}", v))
+                .id();
+            self.remove_edge(e);
+        }
+        let e = self.join_edges(v);
+        self.remove_edge(e)
+    }
+
+    /// Removes the edge `e` and merges the faces on both sides. Endpoints that are left
+    /// with only two neighbors are removed as well by joining their edges, unless that
+    /// would leave a face with less than three vertices.
+    ///
+    /// Returns the merged face.
+    fn dissolve_edge(&mut self, e: T::E) -> T::F {
+        let edge = self.edge(e);
+        assert!(
+            !edge.is_boundary_self() && !edge.twin(self).is_boundary_self(),
+            "Edge {} is at the boundary",
+            e
+        );
+        let (u, w) = (edge.origin_id(), edge.target_id(self));
+        let f = self.remove_edge(e);
+        for v in [u, w] {
+            if can_join_edges::<T>(self, v) {
+                self.join_edges(v);
+            }
+        }
+        f
+    }
+
+    /// Limited dissolve: Merges adjacent faces of the selection whose normals differ by
+    /// at most `max_angle` (in radians) by dissolving the edges between them. Afterwards,
+    /// vertices of the merged faces with two neighbors whose edges deviate at most
+    /// `max_angle` from a straight line are removed.
+    ///
+    /// Returns the remaining faces of the selection in the order of `faces`. The merged
+    /// faces keep the id and payload of one of the original faces.
+    fn dissolve_faces(&mut self, faces: &[T::F], max_angle: T::S) -> Vec<T::F> {
+        let min_cos = max_angle.cos();
+        let mut selected: HashSet<T::F> = faces.iter().copied().collect();
+        let mut deleted: HashSet<T::E> = HashSet::new();
+
+        let flat = |mesh: &Self, f1: T::F, f2: T::F| {
+            let (n1, n2) = (newell_normal::<T>(mesh, f1), newell_normal::<T>(mesh, f2));
+            n1.dot(&n2) >= min_cos * n1.length() * n2.length()
+        };
+
+        loop {
+            let candidates = faces
+                .iter()
+                .filter(|f| selected.contains(f))
+                .flat_map(|f| {
+                    self.face(*f)
+                        .edges(self)
+                        .map(|e| e.id())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let mut changed = false;
+            for e in candidates {
+                if deleted.contains(&e) {
+                    continue;
+                }
+                let edge = self.edge(e).clone();
+                let twin = edge.twin(self);
+                let (f1, f2) = (edge.face_id(), twin.face_id());
+                if twin.is_boundary_self()
+                    || f1 == f2
+                    || !selected.contains(&f2)
+                    || !flat(self, f1, f2)
+                {
+                    continue;
+                }
+                let (u, w) = (edge.origin_id(), twin.origin_id());
+                if self.vertex(u).degree(self) > 2 && self.vertex(w).degree(self) > 2 {
+                    self.remove_edge(e);
+                    selected.remove(&f2);
+                    deleted.extend([e, twin.id()]);
+                } else {
+                    // the edge is part of a chain between the two faces
+                    let v = if self.vertex(u).degree(self) == 2 {
+                        u
+                    } else {
+                        w
+                    };
+                    deleted.extend(join_edges_tracked::<T>(self, v));
+                }
+                changed = true;
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        // remove vertices in the middle of straight edges
+        let vertices = faces
+            .iter()
+            .filter(|f| selected.contains(f))
+            .flat_map(|f| self.face(*f).vertex_ids(self).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut removed = HashSet::new();
+        for v in vertices {
+            if removed.contains(&v) || !can_join_edges::<T>(self, v) {
+                continue;
+            }
+            let vertex = self.vertex(v);
+            let neighbors = vertex.neighbor_ids(self).collect::<Vec<_>>();
+            let (u, w) = (neighbors[0], neighbors[1]);
+            let only_selected = vertex.edges_out(self).all(|e| {
+                let f = e.face_id();
+                f == IndexType::max() || selected.contains(&f)
+            });
+            let p = vertex.pos();
+            let (a, b) = (p - self.vertex(u).pos(), self.vertex(w).pos() - p);
+            let straight = a.dot(&b) >= min_cos * a.length() * b.length();
+            if only_selected && straight {
+                self.join_edges(v);
+                removed.insert(v);
+            }
+        }
+
+        faces
+            .iter()
+            .filter(|f| selected.contains(f))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_dissolve_vertex() {
+        // poking a face and dissolving the new vertex restores the face
+        let mut mesh = Mesh3d64::regular_prism(1.0, 1.0, 6);
+        let f = mesh
+            .faces()
+            .find(|f| f.num_vertices(&mesh) == 6)
+            .unwrap()
+            .id();
+        let (c, _) = mesh.poke_face(f);
+        assert_eq!(mesh.num_faces(), 6 + 2 + 5);
+        let g = mesh.dissolve_vertex(c);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.num_vertices(), 12);
+        assert_eq!(mesh.num_faces(), 8);
+        assert_eq!(mesh.face(g).num_vertices(&mesh), 6);
+
+        // subdividing an edge and dissolving the new vertex restores the edge
+        let mut cube = Mesh3d64::cube(1.0);
+        let e = cube.edge_ids().next().unwrap();
+        let mid = cube.edge(e).centroid(&cube);
+        cube.insert_vertices_into_edge(
+            e,
+            std::iter::once((
+                Default::default(),
+                Default::default(),
+                VertexPayloadPNU::from_pos(mid),
+            )),
+        );
+        assert_eq!(cube.num_vertices(), 9);
+        let v = cube.vertices().find(|v| v.degree(&cube) == 2).unwrap().id();
+        cube.dissolve_vertex(v);
+        assert!(cube.check().is_ok());
+        assert_eq!(cube.num_vertices(), 8);
+        assert_eq!(cube.num_edges(), Mesh3d64::cube(1.0).num_edges());
+        assert!(cube.faces().all(|f| f.num_vertices(&cube) == 4));
+    }
+
+    #[test]
+    fn test_dissolve_edge() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        let e = mesh.edge_ids().next().unwrap();
+        let f = mesh.dissolve_edge(e);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.num_faces(), 5);
+        // both endpoints are left with two neighbors and are removed, turning the
+        // two adjacent quads into triangles
+        assert_eq!(mesh.num_vertices(), 6);
+        assert_eq!(mesh.face(f).num_vertices(&mesh), 4);
+        assert_eq!(
+            mesh.faces().filter(|f| f.num_vertices(&mesh) == 3).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_dissolve_faces() {
+        // a flat 3x3 grid of triangles merges into a single quad
+        let mut grid = Mesh3d64::heightmap(3.0, 3.0, 3, |_, _| 0.0);
+        let faces = grid.face_ids().collect::<Vec<_>>();
+        let res = grid.dissolve_faces(&faces, 1e-6);
+        assert!(grid.check().is_ok());
+        assert_eq!(res.len(), 1);
+        assert_eq!(grid.num_faces(), 1);
+        assert_eq!(grid.face(res[0]).num_vertices(&grid), 4);
+        assert_eq!(grid.num_vertices(), 4);
+
+        // a subdivided cube keeps its six sides
+        let mut cube = Mesh3d64::cube(1.0);
+        cube.triangulate_in_place(TriangulationAlgorithm::Fan);
+        let faces = cube.face_ids().collect::<Vec<_>>();
+        assert_eq!(faces.len(), 12);
+        let res = cube.dissolve_faces(&faces, 0.1);
+        assert!(cube.check().is_ok());
+        assert!(!cube.is_open());
+        assert_eq!(res.len(), 6);
+        assert!(cube.faces().all(|f| f.num_vertices(&cube) == 4));
+
+        // only the selected faces, i.e., the top and the bottom, are merged
+        let mut cube = Mesh3d64::cube(1.0);
+        cube.triangulate_in_place(TriangulationAlgorithm::Fan);
+        let faces = cube
+            .faces()
+            .filter(|f| f.normal(&cube).y.abs() > 0.1)
+            .map(|f| f.id())
+            .collect::<Vec<_>>();
+        assert_eq!(faces.len(), 4);
+        let res = cube.dissolve_faces(&faces, 0.1);
+        assert!(cube.check().is_ok());
+        assert_eq!(res.len(), 2);
+        assert_eq!(cube.num_faces(), 10);
+        assert!(res.iter().all(|f| cube.face(*f).num_vertices(&cube) == 4));
+    }
+}
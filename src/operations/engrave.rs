@@ -0,0 +1,293 @@
+use crate::{
+    math::{HasPosition, IndexType, Scalar, Vector, Vector3D, VectorIteratorExt},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, Face3d, FaceBasics, FacePayload, Font, MeshType3D,
+        SoupBuilder, Triangulateable, VertexBasics,
+    },
+    primitives::Make2dShape,
+    tesselate::{TesselationMeta, TriangulationAlgorithm},
+};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// Whether `p` lies inside the closed polygon.
+fn contains_point<S: Scalar>(polygon: &[[S; 2]], p: [S; 2]) -> bool {
+    let mut inside = false;
+    for (u, v) in polygon.iter().circular_tuple_windows() {
+        if (u[1] > p[1]) != (v[1] > p[1])
+            && p[0] < u[0] + (p[1] - u[1]) / (v[1] - u[1]) * (v[0] - u[0])
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Whether the closed segments `a`-`b` and `c`-`d` intersect or touch.
+fn segments_intersect<S: Scalar>(a: [S; 2], b: [S; 2], c: [S; 2], d: [S; 2]) -> bool {
+    let orient = |p: [S; 2], q: [S; 2], r: [S; 2]| {
+        let x = (q[0] - p[0]) * (r[1] - p[1]) - (q[1] - p[1]) * (r[0] - p[0]);
+        if x > S::ZERO {
+            1
+        } else if x < S::ZERO {
+            -1
+        } else {
+            0
+        }
+    };
+    let within = |p: [S; 2], q: [S; 2], r: [S; 2]| {
+        r[0] >= p[0].min(q[0])
+            && r[0] <= p[0].max(q[0])
+            && r[1] >= p[1].min(q[1])
+            && r[1] <= p[1].max(q[1])
+    };
+    let (o1, o2, o3, o4) = (
+        orient(a, b, c),
+        orient(a, b, d),
+        orient(c, d, a),
+        orient(c, d, b),
+    );
+    (o1 * o2 < 0 && o3 * o4 < 0)
+        || (o1 == 0 && within(a, b, c))
+        || (o2 == 0 && within(a, b, d))
+        || (o3 == 0 && within(c, d, a))
+        || (o4 == 0 && within(c, d, b))
+}
+
+/// Whether the outlines of two glyphs intersect or touch.
+fn glyphs_overlap<S: Scalar>(a: &[Vec<[S; 2]>], b: &[Vec<[S; 2]>]) -> bool {
+    a.iter().any(|ca| {
+        b.iter().any(|cb| {
+            ca.iter().circular_tuple_windows().any(|(p, q)| {
+                cb.iter()
+                    .circular_tuple_windows()
+                    .any(|(r, s)| segments_intersect(*p, *q, *r, *s))
+            })
+        })
+    })
+}
+
+/// Methods to engrave or emboss text on meshes, e.g., for signs, coins, or plaques.
+pub trait MeshEngrave<T: MeshType3D<Mesh = Self>>: Make2dShape<T> + Triangulateable<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Cuts the outlines of the `text` into the planar `face` and moves them inwards
+    /// by `depth`. A negative depth embosses the text instead, i.e., raises it.
+    ///
+    /// The text is laid out with the font scaled to `size` and centered on the face with
+    /// the baseline running along the first edge of the face. Glyphs whose outlines touch
+    /// are moved apart since the outlines aren't merged. The face is replaced by a
+    /// triangulation of its parts outside the glyphs, the glyphs become a triangulated
+    /// floor with walls connecting it to the face. Panics if the text doesn't fit on the face.
+    ///
+    /// The mesh is rebuilt, so the ids of all elements change and only the vertex payloads
+    /// and the tags of the faces are kept. Returns the faces of the walls and the floor.
+    fn engrave_text(
+        &mut self,
+        font: &Font,
+        text: &str,
+        face: T::F,
+        depth: T::S,
+        size: T::S,
+    ) -> Vec<T::F> {
+        let f = self.face(face);
+        let ps: Vec<T::Vec> = f.vertices(self).map(|v| v.pos()).collect();
+        let n = Face3d::normal(f, self).normalize();
+        let d = ps[1] - ps[0];
+        let u = (d - n * d.dot(&n)).normalize();
+        let w = n.cross(&u);
+        let c: T::Vec = ps.iter().copied().stable_mean();
+        let boundary = ps
+            .iter()
+            .map(|p| [(*p - c).dot(&u), (*p - c).dot(&w)])
+            .collect::<Vec<_>>();
+
+        // scale the outlines and move glyphs apart that overlap, e.g., at their serifs
+        let k = size / T::S::from_f64(font.scale() as f64);
+        let step = size * T::S::from_f64(0.01);
+        let mut glyphs: Vec<Vec<Vec<[T::S; 2]>>> = Vec::new();
+        let mut shift = T::S::ZERO;
+        for glyph in font.text_outlines(text) {
+            let scaled = |shift: T::S| {
+                glyph
+                    .iter()
+                    .map(|contour| {
+                        contour
+                            .iter()
+                            .map(|p| {
+                                let [x, y] = p.map(|x| T::S::from_f64(x as f64) * k);
+                                [x + shift, y]
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            };
+            while glyphs.iter().any(|g| glyphs_overlap(g, &scaled(shift))) {
+                shift += step;
+            }
+            glyphs.push(scaled(shift));
+        }
+        let mut outlines = glyphs.into_iter().flatten().collect::<Vec<_>>();
+
+        // center the text on the face
+        let (mut min, mut max) = ([T::S::INFINITY; 2], [T::S::NEG_INFINITY; 2]);
+        for p in outlines.iter().flatten() {
+            for i in 0..2 {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            }
+        }
+        let center = [0, 1].map(|i| (min[i] + max[i]) * T::S::HALF);
+        for p in outlines.iter_mut().flatten() {
+            *p = [p[0] - center[0], p[1] - center[1]];
+        }
+        assert!(
+            outlines
+                .iter()
+                .flatten()
+                .all(|p| contains_point(&boundary, *p)),
+            "The text doesn't fit on the face"
+        );
+        let at = |p: &[T::S; 2]| c + u * p[0] + w * p[1];
+        let offset = -n * depth;
+
+        // the rest of the face with the glyphs as holes and the floor of the glyphs;
+        // `insert_polygon` reverses the order, so the fills of the floor are reversed
+        let mut top = Self::default();
+        top.insert_polygon(ps.iter().rev().map(|p| T::VP::from_pos(*p)));
+        let mut floor = Self::default();
+        for contour in &outlines {
+            top.insert_polygon(contour.iter().map(|p| T::VP::from_pos(at(p))));
+            floor.insert_polygon(
+                contour
+                    .iter()
+                    .rev()
+                    .map(|p| T::VP::from_pos(at(p) + offset)),
+            );
+        }
+
+        let mut builder = SoupBuilder::<3, T>::new().with_weld(size * T::S::from_f64(1e-6));
+        let indices: HashMap<T::V, usize> = self
+            .vertices()
+            .map(|v| (v.id(), builder.add_vertex(v.payload().clone())))
+            .collect();
+        for g in self.faces().filter(|g| g.id() != face) {
+            let cycle = g.vertex_ids(self).map(|v| indices[&v]);
+            if let Some(tag) = g.payload().tag() {
+                builder.add_tagged_face(cycle, tag);
+            } else {
+                builder.add_face(cycle);
+            }
+        }
+
+        let triangulate = |mesh: &Self, builder: &mut SoupBuilder<3, T>| {
+            let (is, vs) = mesh.triangulate_with_holes(
+                TriangulationAlgorithm::Auto,
+                &mut TesselationMeta::default(),
+            );
+            let vs = vs
+                .into_iter()
+                .map(|vp| builder.add_vertex(vp))
+                .collect::<Vec<_>>();
+            for t in is.chunks(3) {
+                builder.add_face(t.iter().map(|i| vs[i.index()]));
+            }
+        };
+        triangulate(&top, &mut builder);
+        let start = builder.num_faces();
+        triangulate(&floor, &mut builder);
+        for contour in &outlines {
+            for (p, q) in contour.iter().circular_tuple_windows() {
+                let (a, b) = (at(p), at(q));
+                let wall = [a, b, b + offset, a + offset].map(T::VP::from_pos);
+                builder.add_polygon(wall);
+            }
+        }
+
+        *self = Self::default();
+        builder.insert_into(self).split_off(start)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn font() -> Font<'static> {
+        Font::new(include_bytes!("../../assets/Cochineal-Roman.otf"), 1.0)
+    }
+
+    /// Returns the cube with side length `4` and the id of its top face.
+    fn plaque() -> (Mesh3d64, usize) {
+        let cube = Mesh3d64::cube(4.0);
+        let top = cube
+            .face_ids()
+            .find(|f| cube.face(*f).normal(&cube).y > 0.5)
+            .unwrap();
+        (cube, top)
+    }
+
+    /// Returns the area enclosed by the outlines of the text at the given size.
+    fn text_area(text: &str, size: f64) -> f64 {
+        font()
+            .text_outlines(text)
+            .iter()
+            .flatten()
+            .map(|c| {
+                (0..c.len())
+                    .map(|i| {
+                        let (u, v) = (c[i], c[(i + 1) % c.len()]);
+                        (u[0] * v[1] - v[0] * u[1]) as f64
+                    })
+                    .sum::<f64>()
+                    * 0.5
+            })
+            .sum::<f64>()
+            * size
+            * size
+    }
+
+    #[test]
+    fn test_engrave_text() {
+        // the serifs of "ll" touch, so the glyphs are moved apart
+        let area = text_area("Hello", 1.0);
+        assert!(area > 0.1);
+
+        let (mut mesh, top) = plaque();
+        let faces = mesh.engrave_text(&font(), "Hello", top, 0.2, 1.0);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert!(!faces.is_empty());
+        assert!(mesh
+            .report()
+            .volume
+            .unwrap()
+            .is_about(64.0 - 0.2 * area, 1e-6));
+        // the floor lies below the face
+        assert!(faces
+            .iter()
+            .flat_map(|f| mesh.face(*f).vertices(&mesh))
+            .all(|v| v.pos().y > 1.79 && v.pos().y < 2.01));
+
+        let area = text_area("Bo", 1.5);
+        let (mut mesh, top) = plaque();
+        mesh.engrave_text(&font(), "Bo", top, -0.2, 1.5);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert!(mesh
+            .report()
+            .volume
+            .unwrap()
+            .is_about(64.0 + 0.2 * area, 1e-6));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit")]
+    fn test_engrave_text_too_large() {
+        let (mut mesh, top) = plaque();
+        mesh.engrave_text(&font(), "Hello", top, 0.2, 10.0);
+    }
+}
@@ -1,13 +1,60 @@
 use crate::{
-    math::{Scalar, Transformable},
+    math::{Scalar, TransformTrait, Transformable, Vector, Vector3D},
     mesh::{
         DefaultEdgePayload, DefaultFacePayload, EdgeBasics, EuclideanMeshType, FaceBasics,
-        HalfEdge, MeshTypeHalfEdge, VertexBasics,
+        HalfEdge, MeshBasics, MeshType3D, MeshTypeHalfEdge, VertexBasics,
     },
     operations::MeshLoft,
 };
 use itertools::Itertools;
 
+/// How the cross-section is oriented along the path of a sweep.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FrameMode {
+    /// The cross-section is only translated and keeps its initial orientation.
+    Fixed,
+
+    /// The cross-section is rotated with the path using rotation-minimizing frames
+    /// (computed with the double reflection method), i.e., parallel transport without twisting.
+    #[default]
+    RotationMinimizing,
+}
+
+/// Returns the rotation from the unit vector `a` to the unit vector `b`. Nearly identical
+/// vectors are not rotated since the rotation arc is numerically unstable for them.
+fn rotation_arc<T: MeshType3D>(a: T::Vec, b: T::Vec) -> T::Trans {
+    if a.distance_squared(&b) <= T::S::EPS {
+        T::Trans::identity()
+    } else {
+        T::Trans::from_rotation_arc(a, b)
+    }
+}
+
+/// Returns the transformation moving the frame `(p, t, r)` given by position, tangent,
+/// and a unit vector perpendicular to the tangent to the frame `(q, u, s)`
+/// while scaling by `scale` around `p`.
+fn frame_transform<T: MeshType3D>(
+    (p, t, r): (T::Vec, T::Vec, T::Vec),
+    (q, u, s): (T::Vec, T::Vec, T::Vec),
+    scale: T::S,
+) -> T::Trans {
+    let bend = rotation_arc::<T>(t, u);
+    let x = bend.apply_vec(r);
+    // rotate around the tangent in two steps to avoid rotating by exactly half a turn
+    let mid = x + s;
+    let mid = if mid.length_squared() > T::S::EPS {
+        mid.normalize()
+    } else {
+        u.cross(&x)
+    };
+    T::Trans::from_translation(-p)
+        .chain(&T::Trans::from_scale(T::Vec::splat(scale)))
+        .chain(&bend)
+        .chain(&rotation_arc::<T>(x, mid))
+        .chain(&rotation_arc::<T>(mid, s))
+        .chain(&T::Trans::from_translation(q))
+}
+
 // TODO: Adjust this to not be halfedge-specific
 
 /// Extrude operations for meshes.
@@ -38,19 +85,48 @@ where
     ///
     /// Uses one row of quad faces.
     fn extrude<const D: usize>(&mut self, e: T::E, transform: T::Trans) -> T::E
+    where
+        T::VP: Transformable<D, Trans = T::Trans, S = T::S>,
+        T: EuclideanMeshType<D, Mesh = Self>,
+    {
+        self.extrude_with(e, transform, 1, |_, vp| vp)
+    }
+
+    /// Extrudes the given edge `steps` times, applying the transformation once per step.
+    /// Returns an edge on the boundary of the extrusion.
+    ///
+    /// The payloads of each new ring of vertices are given by `payload(step, vp)` where
+    /// `step` starts at 1 for the first ring and `vp` is the payload of the corresponding
+    /// vertex of the previous ring after applying the transformation. This allows varying
+    /// colors, uvs, or custom attributes along the extrusion, e.g., a gradient along a tower.
+    ///
+    /// Uses one row of quad faces per step.
+    fn extrude_with<const D: usize>(
+        &mut self,
+        e: T::E,
+        transform: T::Trans,
+        steps: usize,
+        mut payload: impl FnMut(usize, T::VP) -> T::VP,
+    ) -> T::E
     where
         T::VP: Transformable<D, Trans = T::Trans, S = T::S>,
         T: EuclideanMeshType<D, Mesh = Self>,
     {
         assert!(self.edge(e).is_boundary_self());
-        // TODO: avoid collecting
-        let vps: Vec<_> = self
-            .edges_back_from(self.edge(e).next_id())
-            .map(|v| v.origin(self).payload().transformed(&transform))
-            .collect();
-        let start = self.loft_polygon_back(e, 2, 2, vps);
-        self.close_hole(start, Default::default(), false);
-        start
+        assert!(steps >= 1, "need at least one step");
+        let boundary = self.edges_from(e).map(|e| e.id()).collect_vec();
+        let mut current = e;
+        for step in 1..=steps {
+            // TODO: avoid collecting
+            let vps: Vec<_> = self
+                .edges_back_from(self.edge(current).next_id())
+                .map(|v| payload(step, v.origin(self).payload().transformed(&transform)))
+                .collect();
+            current = self.loft_polygon_back(current, 2, 2, vps);
+        }
+        let cap = self.close_hole(current, Default::default(), false);
+        tag_extrusion::<T>(self, boundary, cap);
+        current
     }
 
     /// Remove the given face and extrude the boundary using the given transformation.
@@ -90,8 +166,10 @@ where
             .edges_from(self.edge(e).next_id())
             .map(|v| v.origin(self).payload().transformed(&transform))
             .collect();
+        let boundary = self.edges_from(e).map(|e| e.id()).collect_vec();
         let start = self.loft_tri_closed(e, vps);
-        self.close_hole(start, Default::default(), false);
+        let cap = self.close_hole(start, Default::default(), false);
+        tag_extrusion::<T>(self, boundary, cap);
         start
     }
 
@@ -116,11 +194,161 @@ where
             .map(|(a, b)| a.lerped(&b, T::S::HALF))
             .collect();
         vps.rotate_right(1);
+        let boundary = self.edges_from(e).map(|e| e.id()).collect_vec();
         let start = self.loft_tri_closed(e, vps);
-        self.close_hole(start, Default::default(), false);
+        let cap = self.close_hole(start, Default::default(), false);
+        tag_extrusion::<T>(self, boundary, cap);
         start
     }
 
+    /// Sweeps the boundary starting at `e` along the `path` and closes the end with a cap.
+    /// Returns an edge on the boundary of the cap.
+    ///
+    /// The cross-section starts at the first point of the path and should be perpendicular
+    /// to its first segment, facing backwards like the bottom of a prism. It is transported along the
+    /// path and oriented according to `frame`. Uses one row of quad faces per segment.
+    fn sweep(&mut self, e: T::E, path: &[T::Vec], frame: FrameMode) -> T::E
+    where
+        T: MeshType3D<Mesh = Self>,
+        T::VP: Transformable<3, Trans = T::Trans, S = T::S>,
+    {
+        self.sweep_ex(e, path, frame, |_| T::S::ONE, |_| T::S::ZERO)
+    }
+
+    /// Like `sweep`, but the cross-section is scaled by `scale(t)` and rotated by `twist(t)`
+    /// radians around the path where `t` is the relative arc length along the path.
+    /// The initial cross-section is assumed to have scale `scale(0)` and twist `twist(0)`.
+    fn sweep_ex(
+        &mut self,
+        e: T::E,
+        path: &[T::Vec],
+        frame: FrameMode,
+        scale: impl Fn(T::S) -> T::S,
+        twist: impl Fn(T::S) -> T::S,
+    ) -> T::E
+    where
+        T: MeshType3D<Mesh = Self>,
+        T::VP: Transformable<3, Trans = T::Trans, S = T::S>,
+    {
+        self.sweep_with(e, path, frame, scale, twist, |_, vp| vp)
+    }
+
+    /// Like `sweep_ex`, but the payloads of the cross-section at the `i`-th point of the
+    /// path are given by `payload(i, vp)` where `vp` is the payload of the corresponding
+    /// vertex of the previous cross-section after transporting it along the path.
+    /// The initial cross-section is the 0-th one and isn't changed.
+    fn sweep_with(
+        &mut self,
+        e: T::E,
+        path: &[T::Vec],
+        frame: FrameMode,
+        scale: impl Fn(T::S) -> T::S,
+        twist: impl Fn(T::S) -> T::S,
+        mut payload: impl FnMut(usize, T::VP) -> T::VP,
+    ) -> T::E
+    where
+        T: MeshType3D<Mesh = Self>,
+        T::VP: Transformable<3, Trans = T::Trans, S = T::S>,
+    {
+        assert!(self.edge(e).is_boundary_self());
+        let mut ps: Vec<T::Vec> = Vec::with_capacity(path.len());
+        for p in path {
+            if ps
+                .last()
+                .map_or(true, |q: &T::Vec| q.distance_squared(p) > T::S::EPS)
+            {
+                ps.push(*p);
+            }
+        }
+        assert!(
+            ps.len() >= 2,
+            "the path must have at least 2 distinct points"
+        );
+        let n = ps.len();
+
+        let mut ts = vec![T::S::ZERO];
+        for w in ps.windows(2) {
+            ts.push(*ts.last().unwrap() + w[0].distance(&w[1]));
+        }
+        let total = ts[n - 1];
+        ts.iter_mut().for_each(|t| *t /= total);
+
+        let dirs = ps
+            .windows(2)
+            .map(|w| (w[1] - w[0]).normalize())
+            .collect::<Vec<_>>();
+        let tangents = (0..n)
+            .map(|i| match i {
+                0 => dirs[0],
+                _ if i == n - 1 => dirs[n - 2],
+                _ => {
+                    let d = dirs[i - 1] + dirs[i];
+                    if d.length_squared() > T::S::EPS {
+                        d.normalize()
+                    } else {
+                        dirs[i]
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // initial frame perpendicular to the first tangent
+        let t0 = tangents[0];
+        let axis = if t0.x().abs() < T::S::HALF {
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)
+        } else {
+            T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO)
+        };
+        let mut frames = vec![(t0, t0.cross(&axis).normalize())];
+        for i in 1..n {
+            let (t, r) = frames[i - 1];
+            frames.push(match frame {
+                FrameMode::Fixed => (t, r),
+                FrameMode::RotationMinimizing => {
+                    // double reflection, see Wang et al. 2008
+                    let v1 = ps[i] - ps[i - 1];
+                    let c1 = v1.dot(&v1);
+                    let rl = r - v1 * (T::S::TWO * v1.dot(&r) / c1);
+                    let tl = t - v1 * (T::S::TWO * v1.dot(&t) / c1);
+                    let v2 = tangents[i] - tl;
+                    let c2 = v2.dot(&v2);
+                    let r = if c2 > T::S::EPS {
+                        rl - v2 * (T::S::TWO * v2.dot(&rl) / c2)
+                    } else {
+                        rl
+                    };
+                    let t = tangents[i];
+                    (t, (r - t * r.dot(&t)).normalize())
+                }
+            });
+        }
+        let stations = (0..n)
+            .map(|i| {
+                let (t, r) = frames[i];
+                let angle = twist(ts[i]);
+                (ps[i], t, r * angle.cos() + t.cross(&r) * angle.sin())
+            })
+            .collect::<Vec<_>>();
+
+        let boundary = self.edges_from(e).map(|e| e.id()).collect_vec();
+        let mut current = e;
+        for i in 1..n {
+            let transform = frame_transform::<T>(
+                stations[i - 1],
+                stations[i],
+                scale(ts[i]) / scale(ts[i - 1]),
+            );
+            let vps: Vec<_> = self
+                .edges_back_from(self.edge(current).next_id())
+                .map(|v| payload(i, v.origin(self).payload().transformed(&transform)))
+                .collect();
+            current = self.loft_polygon_back(current, 2, 2, vps);
+        }
+        let cap = self.close_hole(current, Default::default(), false);
+        tag_extrusion::<T>(self, boundary, cap);
+        current
+    }
+
     /// Assumes `start` is on the boundary of the edge.
     /// Will insert a vertex `apex` with the given vp and fill the hole along the boundary with triangles connected to the apex vertex.
     /// Returns the id of the apex vertex.
@@ -142,3 +370,139 @@ where
         v
     }
 }
+
+/// Tags the `cap` of an extrusion with "cap" and the faces between the
+/// extruded `boundary` and the cap with "side-wall".
+fn tag_extrusion<T: MeshTypeHalfEdge>(mesh: &mut T::Mesh, boundary: Vec<T::E>, cap: T::F) {
+    let walls = boundary
+        .into_iter()
+        .chain(mesh.face(cap).edges(mesh).map(|e| e.twin_id()))
+        .filter_map(|e| mesh.edge(e).face(mesh).map(|f| f.id()))
+        .filter(|f| *f != cap)
+        .collect_vec();
+    mesh.tag_faces(walls, "side-wall");
+    mesh.tag_faces([cap], "cap");
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    /// Returns a regular octagon, one of its boundary edges, and the direction it faces away from.
+    fn profile() -> (Mesh3d64, usize, Vec3<f64>) {
+        let mesh = Mesh3d64::regular_polygon(1.0, 8);
+        let e = mesh.edges().find(|e| e.is_boundary_self()).unwrap().id();
+        let d = -mesh.faces().next().unwrap().normal(&mesh).normalize();
+        (mesh, e, d)
+    }
+
+    fn cap(mesh: &Mesh3d64, e: usize) -> Vec<Vec3<f64>> {
+        mesh.edge(e)
+            .face(mesh)
+            .unwrap()
+            .vertices(mesh)
+            .map(|v| v.pos())
+            .collect()
+    }
+
+    #[test]
+    fn test_sweep_bend() {
+        // a straight segment along d followed by a quarter circle ending along a
+        let (_, _, d) = profile();
+        let a = d.cross(&Vec3::new(1.0, 0.0, 0.0)).normalize();
+        let path = (0..=8)
+            .map(|i| {
+                let phi = PI * 0.5 * i as f64 / 8.0;
+                d + a * 3.0 * (1.0 - phi.cos()) + d * 3.0 * phi.sin()
+            })
+            .collect::<Vec<_>>();
+        let path = [vec![Vec3::zeros()], path].concat();
+        let end = *path.last().unwrap();
+        // the tangent at the end is the direction of the last segment
+        let tangent = (end - path[8]).normalize();
+
+        let (mut mesh, e, _) = profile();
+        let top = mesh.sweep(e, &path, FrameMode::RotationMinimizing);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.num_faces(), 2 + 9 * 8);
+        for p in cap(&mesh, top) {
+            assert!(p.distance(&end).is_about(1.0, 1e-6));
+            assert!((p - end).dot(&tangent).is_about(0.0, 1e-6));
+        }
+
+        // with fixed frames, the cap keeps the orientation of the profile
+        let (mut mesh, e, _) = profile();
+        let top = mesh.sweep_ex(e, &path, FrameMode::Fixed, |t| 1.0 + t, |_| 0.0);
+        assert!(mesh.check().is_ok());
+        for p in cap(&mesh, top) {
+            assert!(p.distance(&end).is_about(2.0, 1e-6));
+            assert!((p - end).dot(&d).is_about(0.0, 1e-6));
+        }
+    }
+
+    #[test]
+    fn test_sweep_twist() {
+        let (mut mesh, e, d) = profile();
+        let original = mesh
+            .vertices()
+            .map(|v| v.pos() + d * 2.0)
+            .collect::<Vec<_>>();
+        let top = mesh.sweep_ex(
+            e,
+            &[Vec3::zeros(), d, d * 2.0],
+            FrameMode::RotationMinimizing,
+            |_| 1.0,
+            |t| t * PI / 8.0,
+        );
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_faces(), 2 + 2 * 8);
+        // each vertex of the cap is rotated by a sixteenth of a turn
+        let chord = 2.0 * (PI / 16.0).sin();
+        for p in cap(&mesh, top) {
+            let nearest = original
+                .iter()
+                .map(|q| q.distance(&p))
+                .fold(f64::INFINITY, f64::min);
+            assert!(nearest.is_about(chord, 1e-6));
+        }
+    }
+
+    #[test]
+    fn test_extrude_with() {
+        // a tower with a gradient in the v coordinate
+        let (mut mesh, e, d) = profile();
+        let steps = 4;
+        let transform = NdAffine::from_translation(d * 0.5);
+        let top = mesh.extrude_with(e, transform, steps, |step, mut vp| {
+            vp.set_uv(Vec2::new(0.0, step as f64 / steps as f64));
+            vp
+        });
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.num_faces(), 2 + steps * 8);
+        for v in mesh.vertices() {
+            let height = (v.pos() - mesh.vertex(0).pos()).dot(&d);
+            assert!(v.payload().uv().y.is_about(height / 2.0, 1e-9));
+        }
+        assert_eq!(cap(&mesh, top).len(), 8);
+
+        // the payload of the sweep is given per point of the path
+        let (mut mesh, e, d) = profile();
+        let mut calls = Vec::new();
+        mesh.sweep_with(
+            e,
+            &[Vec3::zeros(), d, d * 2.0],
+            FrameMode::Fixed,
+            |_| 1.0,
+            |_| 0.0,
+            |i, vp| {
+                calls.push(i);
+                vp
+            },
+        );
+        assert_eq!(calls, [vec![1; 8], vec![2; 8]].concat());
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector},
+    mesh::{
+        CurvedEdge, CurvedEdgeType, DefaultEdgePayload, EdgeBasics, EuclideanMeshType, FaceBasics,
+        HalfEdge, HalfEdgeSemiBuilder, MeshBasics, MeshTypeHalfEdge, VertexBasics,
+    },
+};
+
+/// The arc replacing a corner of a polygon.
+struct Fillet<V: Vector<S, D>, S: Scalar, const D: usize> {
+    /// The tangent point on the incoming edge.
+    start: V,
+    /// The tangent point on the outgoing edge.
+    end: V,
+    /// The position of the sharp corner.
+    corner: V,
+    /// The center of the arc.
+    center: V,
+    /// The angle spanned by the arc.
+    angle: S,
+}
+
+impl<V: Vector<S, D>, S: Scalar, const D: usize> Fillet<V, S, D> {
+    /// Returns the point at the parameter `t` in `[0, 1]` on the arc.
+    fn point(&self, t: S) -> V {
+        let (a, b) = (self.start - self.center, self.end - self.center);
+        self.center
+            + (a * ((S::ONE - t) * self.angle).sin() + b * (t * self.angle).sin())
+                / self.angle.sin()
+    }
+
+    /// Returns the cubic bezier approximating the arc between the parameters `t0` and `t1`.
+    fn cubic(&self, t0: S, t1: S) -> (V, V) {
+        let (p0, p1) = (self.point(t0), self.point(t1));
+        let radius = (self.start - self.center).length();
+        let k = S::FOUR / S::THREE * ((t1 - t0) * self.angle / S::FOUR).tan() * radius;
+        // the tangents are perpendicular to the radii and point along the arc
+        let tangent = |p: V, q: V| {
+            let r = (p - self.center).normalize();
+            let d = q - p;
+            (d - r * d.dot(&r)).normalize()
+        };
+        (
+            p0 + tangent(p0, self.corner) * k,
+            p1 + tangent(p1, self.corner) * k,
+        )
+    }
+}
+
+/// Computes the fillets of the corners of the face with the given radius. The distance
+/// of the tangent points from a corner is limited to half the lengths of its edges.
+/// Returns the outgoing edge of each rounded corner, its fillet, and whether the end of
+/// the arc coincides with the start of the arc at the next corner.
+fn fillets<const D: usize, T: EuclideanMeshType<D>>(
+    mesh: &T::Mesh,
+    face: T::F,
+    radius: T::S,
+) -> Vec<(T::E, Fillet<T::Vec, T::S, D>, bool)> {
+    let edges = mesh
+        .face(face)
+        .edges(mesh)
+        .map(|e| e.id())
+        .collect::<Vec<_>>();
+    let ps = edges
+        .iter()
+        .map(|e| mesh.edge(*e).origin(mesh).pos())
+        .collect::<Vec<T::Vec>>();
+    let n = ps.len();
+    let eps = T::S::EPS.sqrt();
+
+    // the distance of the tangent points from each corner
+    let corners = (0..n)
+        .map(|i| {
+            let (prev, v, next) = (ps[(i + n - 1) % n], ps[i], ps[(i + 1) % n]);
+            let (la, lb) = ((prev - v).length(), (next - v).length());
+            let (a, b) = ((prev - v) / la, (next - v) / lb);
+            // the half angle between the edges
+            let cos = a.dot(&b).max(-T::S::ONE).min(T::S::ONE);
+            let half = cos.acos() * T::S::HALF;
+            if half.tan() <= eps || T::S::PI * T::S::HALF - half <= eps {
+                return None;
+            }
+            let t = (radius / half.tan())
+                .min(la * T::S::HALF)
+                .min(lb * T::S::HALF);
+            Some((a, b, t, half))
+        })
+        .collect::<Vec<_>>();
+
+    (0..n)
+        .filter_map(|i| {
+            let (a, b, t, half) = corners[i]?;
+            let v = ps[i];
+            let bisector = (a + b).normalize();
+            let r = t * half.tan();
+            let fillet = Fillet {
+                start: v + a * t,
+                end: v + b * t,
+                corner: v,
+                center: v + bisector * (r / half.sin()),
+                angle: T::S::PI - half * T::S::TWO,
+            };
+            let next = corners[(i + 1) % n].map_or(T::S::ZERO, |c| c.2);
+            let touching = t + next >= (ps[(i + 1) % n] - v).length() - eps;
+            Some((edges[i], fillet, touching))
+        })
+        .collect()
+}
+
+/// Methods to round the corners of polygons, e.g., for softer extruded shapes.
+pub trait MeshFillet<const D: usize, T: EuclideanMeshType<D, Mesh = Self> + MeshTypeHalfEdge>:
+    MeshBasics<T> + HalfEdgeSemiBuilder<T>
+where
+    T::EP: DefaultEdgePayload,
+{
+    /// Replaces the corners of the `face` with circular arcs of the given `radius` made of
+    /// `segments` straight edges each. The face must be planar.
+    ///
+    /// The arcs are tangent to the edges of the face. If an edge is too short for the arcs at
+    /// both of its ends, the arcs are shrunk to meet at its midpoint. The corner vertices are
+    /// moved to the start of their arcs, so faces sharing them are distorted as well.
+    /// Straight corners are kept. Returns the edges of the face along each arc.
+    fn round_corners(&mut self, face: T::F, radius: T::S, segments: usize) -> Vec<Vec<T::E>> {
+        assert!(segments >= 1);
+        fillets::<D, T>(self, face, radius)
+            .into_iter()
+            .map(|(e, fillet, touching)| insert_arc::<D, T>(self, e, &fillet, segments, touching))
+            .collect()
+    }
+
+    /// Like `round_corners`, but the arcs are made of curved edges, i.e., cubic bezier
+    /// curves spanning at most 90° each. Returns the edges of the face along each arc.
+    fn round_corners_curved(&mut self, face: T::F, radius: T::S) -> Vec<Vec<T::E>>
+    where
+        T::Edge: CurvedEdge<D, T>,
+    {
+        let quarter = T::S::PI * T::S::HALF;
+        fillets::<D, T>(self, face, radius)
+            .into_iter()
+            .map(|(e, fillet, touching)| {
+                let n = ((fillet.angle / quarter).to_f64().ceil() as usize).max(1);
+                let arc = insert_arc::<D, T>(self, e, &fillet, n, touching);
+                for (i, e) in arc.iter().enumerate() {
+                    let (c1, c2) = fillet.cubic(
+                        T::S::from_usize(i) / T::S::from_usize(n),
+                        T::S::from_usize(i + 1) / T::S::from_usize(n),
+                    );
+                    self.edge_mut(*e)
+                        .set_curve_type(CurvedEdgeType::CubicBezier(c1, c2));
+                }
+                arc
+            })
+            .collect()
+    }
+}
+
+/// Moves the origin of the edge `e` to the start of the fillet and splits the edge at
+/// `n` points along the arc. The last point is skipped if it coincides with the start of
+/// the next arc. Returns the edges along the arc.
+fn insert_arc<const D: usize, T: EuclideanMeshType<D> + MeshTypeHalfEdge>(
+    mesh: &mut T::Mesh,
+    e: T::E,
+    fillet: &Fillet<T::Vec, T::S, D>,
+    n: usize,
+    touching: bool,
+) -> Vec<T::E>
+where
+    T::EP: DefaultEdgePayload,
+    T::Mesh: HalfEdgeSemiBuilder<T>,
+{
+    let v = mesh.edge(e).origin_id();
+    mesh.vertex_mut(v).payload_mut().set_pos(fillet.start);
+    let mut arc = vec![e];
+    let mut current = e;
+    let last = if touching { n - 1 } else { n };
+    for i in 1..=last {
+        let p = fillet.point(T::S::from_usize(i) / T::S::from_usize(n));
+        let twin = mesh.edge(current).twin_id();
+        let next = mesh.subdivide_unsafe(current, T::VP::from_pos(p), Default::default());
+        mesh.subdivide_unsafe_try_fixup(twin, Default::default())
+            .expect("the twin must be subdivided");
+        if i < n {
+            arc.push(next);
+        }
+        current = next;
+    }
+    arc
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    /// Returns the area enclosed by the face.
+    fn area(mesh: &Mesh2d64Curved, f: usize) -> f64 {
+        let ps = mesh
+            .face(f)
+            .vertices(mesh)
+            .map(|v| v.pos())
+            .collect::<Vec<_>>();
+        (0..ps.len())
+            .map(|i| ps[i].perp_dot(&ps[(i + 1) % ps.len()]) * 0.5)
+            .sum::<f64>()
+            .abs()
+    }
+
+    fn square() -> (Mesh2d64Curved, usize) {
+        let mut mesh = Mesh2d64Curved::new();
+        let e = mesh.insert_polygon(
+            [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]
+                .iter()
+                .map(|(x, y)| VertexPayloadPNU::from_pos(Vec2::new(*x, *y))),
+        );
+        let f = mesh.edge(mesh.edge(e).twin_id()).face_id();
+        (mesh, f)
+    }
+
+    #[test]
+    fn test_round_corners() {
+        let (mut mesh, f) = square();
+        let arcs = mesh.round_corners(f, 0.5, 8);
+        assert!(mesh.check().is_ok());
+        assert_eq!(arcs.len(), 4);
+        assert!(arcs.iter().all(|arc| arc.len() == 8));
+        assert_eq!(mesh.num_vertices(), 4 * 9);
+        // the arcs stay on the circles around the centers of the corners
+        let centers =
+            [(0.5, 0.5), (1.5, 0.5), (1.5, 1.5), (0.5, 1.5)].map(|(x, y)| Vec2::new(x, y));
+        for arc in &arcs {
+            let ps = arc
+                .iter()
+                .map(|e| mesh.edge(*e).origin(&mesh).pos())
+                .collect::<Vec<_>>();
+            assert!(centers
+                .iter()
+                .any(|c| ps.iter().all(|p| (p - c).norm().is_about(0.5, 1e-9))));
+        }
+        // a polygon approximation of the rounded square
+        let expected = 4.0 - (1.0 - PI / 4.0);
+        assert!(area(&mesh, f) < expected && area(&mesh, f) > expected - 0.01);
+
+        // a radius too large for the edges turns the square into a polygonal circle
+        let (mut mesh, f) = square();
+        mesh.round_corners(f, 5.0, 4);
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_vertices(), 4 * 4);
+        let center = Vec2::new(1.0, 1.0);
+        assert!(mesh
+            .vertices()
+            .all(|v| (v.pos() - center).norm().is_about(1.0, 1e-9)));
+
+        // straight corners are kept and the tangent points respect short edges
+        let mut mesh = Mesh2d64Curved::new();
+        let e = mesh.insert_polygon(
+            [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 0.2), (0.0, 0.2)]
+                .iter()
+                .map(|(x, y)| VertexPayloadPNU::from_pos(Vec2::new(*x, *y))),
+        );
+        let f = mesh.edge(mesh.edge(e).twin_id()).face_id();
+        let arcs = mesh.round_corners(f, 1.0, 2);
+        assert!(mesh.check().is_ok());
+        assert_eq!(arcs.len(), 4);
+        assert!(mesh
+            .vertices()
+            .any(|v| v.pos().is_about(&Vec2::new(1.0, 0.0), 1e-12)));
+    }
+
+    #[test]
+    fn test_round_corners_curved() {
+        let (mut mesh, f) = square();
+        let arcs = mesh.round_corners_curved(f, 0.5);
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_vertices(), 8);
+        assert!(arcs.iter().all(|arc| arc.len() == 1));
+
+        // the flattened curves are close to the circles
+        let flat = mesh.to_nd::<3>(0.001);
+        assert!(flat.check().is_ok());
+        let center = Vec3::new(0.5, 0.5, 0.0);
+        let p = flat
+            .vertices()
+            .map(|v| v.pos())
+            .filter(|p| p.x < 0.5 && p.y < 0.5)
+            .collect::<Vec<_>>();
+        assert!(p.len() > 2);
+        assert!(p.iter().all(|p| ((p - center).norm() - 0.5).abs() < 1e-3));
+    }
+}
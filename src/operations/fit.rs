@@ -0,0 +1,70 @@
+use crate::{
+    math::{self, FittedCylinder, FittedPlane, FittedSphere},
+    mesh::{MeshBasics, MeshType3D, VertexBasics},
+};
+
+/// Methods to fit analytical shapes to parts of a mesh, e.g., to recognize the primitives
+/// of imported geometry or to snap generated vertices to an ideal shape.
+pub trait MeshFit<T: MeshType3D<Mesh = Self>>: MeshBasics<T> {
+    /// Returns the positions of the selected vertices.
+    fn selected_positions(&self, selection: &[T::V]) -> Vec<T::Vec> {
+        selection.iter().map(|v| self.vertex(*v).pos()).collect()
+    }
+
+    /// Fits a plane to the selected vertices.
+    /// Returns `None` if less than 3 vertices are selected.
+    fn fit_plane(&self, selection: &[T::V]) -> Option<FittedPlane<T::Vec>> {
+        math::fit_plane(&self.selected_positions(selection))
+    }
+
+    /// Fits a sphere to the selected vertices.
+    /// Returns `None` if less than 4 vertices are selected or they are coplanar.
+    fn fit_sphere(&self, selection: &[T::V]) -> Option<FittedSphere<T::Vec>> {
+        math::fit_sphere(&self.selected_positions(selection))
+    }
+
+    /// Fits an infinite cylinder to the selected vertices.
+    /// Returns `None` if less than 5 vertices are selected.
+    fn fit_cylinder(&self, selection: &[T::V]) -> Option<FittedCylinder<T::Vec>> {
+        math::fit_cylinder(&self.selected_positions(selection))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_fit_mesh() {
+        let mut sphere = Mesh3d64::uv_sphere(2.0, 16, 16);
+        sphere.translate(&Vec3::new(1.0, 2.0, 3.0));
+        let all = sphere.vertex_ids().collect::<Vec<_>>();
+        let fit = sphere.fit_sphere(&all).unwrap();
+        assert!(fit.center.is_about(&Vec3::new(1.0, 2.0, 3.0), 1e-9));
+        assert!(fit.radius.is_about(2.0, 1e-9));
+        assert!(fit.rms < 1e-9);
+
+        // the vertices of a face of the cube lie in a plane
+        let cube = Mesh3d64::cube(1.0);
+        let face = cube.faces().next().unwrap();
+        let selection = face.vertex_ids(&cube).collect::<Vec<_>>();
+        let plane = cube.fit_plane(&selection).unwrap();
+        assert!(plane.rms < 1e-12);
+        assert!(plane
+            .normal
+            .dot(&face.normal(&cube))
+            .abs()
+            .is_about(1.0, 1e-12));
+        // but all of them don't
+        let all = cube.vertex_ids().collect::<Vec<_>>();
+        assert!(cube.fit_plane(&all).unwrap().rms.is_about(0.5, 1e-12));
+
+        let cylinder = Mesh3d64::regular_prism(0.5, 2.0, 12);
+        let all = cylinder.vertex_ids().collect::<Vec<_>>();
+        let fit = cylinder.fit_cylinder(&all).unwrap();
+        assert!(fit.axis.y.abs().is_about(1.0, 1e-9));
+        assert!(fit.radius.is_about(0.5, 1e-6));
+        assert!(fit.rms < 1e-6);
+    }
+}
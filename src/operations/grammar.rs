@@ -0,0 +1,560 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector3D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshType3D, SoupBuilder},
+    operations::hash01,
+};
+use std::collections::HashMap;
+
+/// An oriented box the rules of a shape grammar operate on.
+///
+/// The box spans `size.x()`, `size.y()`, and `size.z()` along the orthonormal axes
+/// `x`, `y`, and `z` starting at `origin`. A scope with zero depth is flat, i.e., a
+/// rectangle facing along `z`, such as a façade.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scope<V: Vector3D> {
+    /// The corner the box grows from.
+    pub origin: V,
+
+    /// The first axis, e.g., the horizontal direction of a façade.
+    pub x: V,
+
+    /// The second axis, e.g., the vertical direction of a façade.
+    pub y: V,
+
+    /// The third axis, i.e., the normal of a flat scope.
+    pub z: V,
+
+    /// The extents along the axes.
+    pub size: V,
+}
+
+impl<V: Vector3D> Scope<V> {
+    /// Creates an axis-aligned box with the given corner and size.
+    pub fn new(origin: V, size: V) -> Self {
+        Self {
+            origin,
+            x: V::from_xyz(V::S::ONE, V::S::ZERO, V::S::ZERO),
+            y: V::from_xyz(V::S::ZERO, V::S::ONE, V::S::ZERO),
+            z: V::from_xyz(V::S::ZERO, V::S::ZERO, V::S::ONE),
+            size,
+        }
+    }
+
+    /// Returns whether the scope has no depth.
+    pub fn is_flat(&self) -> bool {
+        self.size.z() <= V::S::EPS
+    }
+
+    /// Returns the axis and the extent along it.
+    fn axis(&self, axis: GrammarAxis) -> (V, V::S) {
+        match axis {
+            GrammarAxis::X => (self.x, self.size.x()),
+            GrammarAxis::Y => (self.y, self.size.y()),
+            GrammarAxis::Z => (self.z, self.size.z()),
+        }
+    }
+
+    /// Returns the part of the scope between `offset` and `offset + length` along the axis.
+    fn part(&self, axis: GrammarAxis, offset: V::S, length: V::S) -> Self {
+        let (dir, _) = self.axis(axis);
+        let mut size = self.size;
+        match axis {
+            GrammarAxis::X => size = V::from_xyz(length, size.y(), size.z()),
+            GrammarAxis::Y => size = V::from_xyz(size.x(), length, size.z()),
+            GrammarAxis::Z => size = V::from_xyz(size.x(), size.y(), length),
+        }
+        Self {
+            origin: self.origin + dir * offset,
+            size,
+            ..*self
+        }
+    }
+
+    /// Returns the flat scope of the given side of the box. The `x`-axis of a side is
+    /// horizontal, its `y`-axis points along the `z`-axis of the box, and its `z`-axis
+    /// points outwards. The top and the bottom keep the `x`-axis of the box.
+    fn side(&self, component: Component) -> Self {
+        let (o, s) = (self.origin, self.size);
+        let (x, y, z) = (self.x, self.y, self.z);
+        let (origin, ax, ay, w, h) = match component {
+            Component::Front => (o, x, z, s.x(), s.z()),
+            Component::Right => (o + x * s.x(), y, z, s.y(), s.z()),
+            Component::Back => (o + x * s.x() + y * s.y(), -x, z, s.x(), s.z()),
+            Component::Left => (o + y * s.y(), -y, z, s.y(), s.z()),
+            Component::Top => (o + z * s.z(), x, y, s.x(), s.y()),
+            Component::Bottom => (o + y * s.y(), x, -y, s.x(), s.y()),
+            Component::Sides => unreachable!(),
+        };
+        Self {
+            origin,
+            x: ax,
+            y: ay,
+            z: ax.cross(&ay),
+            size: V::from_xyz(w, h, V::S::ZERO),
+        }
+    }
+
+    /// Returns the corners of the box. The first four are the bottom rectangle
+    /// in counter-clockwise order when looking along `-z`, the last four the top.
+    pub fn corners(&self) -> [V; 8] {
+        let (dx, dy, dz) = (
+            self.x * self.size.x(),
+            self.y * self.size.y(),
+            self.z * self.size.z(),
+        );
+        let o = self.origin;
+        [
+            o,
+            o + dx,
+            o + dx + dy,
+            o + dy,
+            o + dz,
+            o + dx + dz,
+            o + dx + dy + dz,
+            o + dy + dz,
+        ]
+    }
+}
+
+/// An axis of a scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrammarAxis {
+    /// The `x`-axis of the scope.
+    X,
+    /// The `y`-axis of the scope.
+    Y,
+    /// The `z`-axis of the scope.
+    Z,
+}
+
+/// The size of a part of a split.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GrammarSize<S: Scalar> {
+    /// A fixed length.
+    Absolute(S),
+
+    /// A fraction of the length of the split scope.
+    Relative(S),
+
+    /// A share of the length left over by the absolute and relative parts.
+    /// The remainder is distributed proportionally to the weights.
+    Floating(S),
+}
+
+/// A side of a box scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Component {
+    /// The side at the start of the `y`-axis.
+    Front,
+    /// The side at the end of the `y`-axis.
+    Back,
+    /// The side at the start of the `x`-axis.
+    Left,
+    /// The side at the end of the `x`-axis.
+    Right,
+    /// The side at the end of the `z`-axis.
+    Top,
+    /// The side at the start of the `z`-axis.
+    Bottom,
+    /// The front, right, back, and left side, in this order.
+    Sides,
+}
+
+/// A rule replacing a shape with new shapes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Production<S: Scalar> {
+    /// Splits the scope along the axis into consecutive parts with the given sizes and symbols.
+    /// If the absolute and relative parts don't fit, they are scaled down to fit.
+    Split(GrammarAxis, Vec<(GrammarSize<S>, String)>),
+
+    /// Splits the scope along the axis into as many equally sized parts as fit
+    /// the given size best, but at least one.
+    Repeat(GrammarAxis, S, String),
+
+    /// Replaces a box scope with flat scopes on its sides. See `Component`.
+    /// A flat scope is passed through as its `Front` and `Sides` component.
+    Components(Vec<(Component, String)>),
+
+    /// Gives the scope the depth along its `z`-axis, e.g., to turn a footprint into a mass.
+    /// A negative depth extrudes backwards, e.g., to recess a window.
+    Extrude(S, String),
+
+    /// Renames the shape, e.g., to pick a symbol at random.
+    Replace(String),
+
+    /// Removes the shape.
+    Nil,
+}
+
+/// A stochastic, context-free shape grammar in the spirit of CGA shape.
+///
+/// Each shape is a symbol with a `Scope`. Starting with the axiom, every shape whose
+/// symbol has rules is replaced by the shapes produced by one of them, chosen at random
+/// proportionally to the weights. Shapes without rules are terminal and are emitted as
+/// geometry, i.e., as a box or, if flat, as a rectangle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeGrammar<S: Scalar> {
+    /// The symbol of the initial shape.
+    pub axiom: String,
+
+    /// The weighted alternatives of each symbol.
+    pub rules: HashMap<String, Vec<(S, Production<S>)>>,
+
+    /// Shapes nested deeper than this are treated as terminal to stop infinite recursions.
+    pub max_depth: usize,
+}
+
+impl<S: Scalar> ShapeGrammar<S> {
+    /// Creates a new grammar with the given axiom and no rules.
+    pub fn new(axiom: &str) -> Self {
+        Self {
+            axiom: axiom.to_string(),
+            rules: HashMap::new(),
+            max_depth: 64,
+        }
+    }
+
+    /// Adds a rule that always replaces `symbol` with the result of `production`.
+    pub fn with_rule(self, symbol: &str, production: Production<S>) -> Self {
+        self.with_weighted_rule(symbol, S::ONE, production)
+    }
+
+    /// Adds an alternative for `symbol` that is chosen with a probability
+    /// proportional to `weight`.
+    pub fn with_weighted_rule(
+        mut self,
+        symbol: &str,
+        weight: S,
+        production: Production<S>,
+    ) -> Self {
+        self.rules
+            .entry(symbol.to_string())
+            .or_default()
+            .push((weight, production));
+        self
+    }
+
+    /// Sets the maximum depth of the derivation.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Picks one of the alternatives using the `i`-th random number of the `seed`.
+    fn choose(&self, alternatives: &[(S, Production<S>)], i: usize, seed: u32) -> usize {
+        let total = alternatives.iter().fold(S::ZERO, |acc, (w, _)| acc + *w);
+        let mut r = S::from_f64(hash01(i, seed)) * total;
+        for (k, (w, _)) in alternatives.iter().enumerate() {
+            if r < *w {
+                return k;
+            }
+            r -= *w;
+        }
+        alternatives.len() - 1
+    }
+
+    /// Returns the lengths of the parts of a split of the given length.
+    fn split_lengths(parts: &[(GrammarSize<S>, String)], length: S) -> Vec<S> {
+        let mut fixed = S::ZERO;
+        let mut floating = S::ZERO;
+        for (size, _) in parts {
+            match size {
+                GrammarSize::Absolute(a) => fixed += *a,
+                GrammarSize::Relative(r) => fixed += *r * length,
+                GrammarSize::Floating(w) => floating += *w,
+            }
+        }
+        let scale = if fixed > length {
+            length / fixed
+        } else {
+            S::ONE
+        };
+        let rest = (length - fixed * scale).max(S::ZERO);
+        parts
+            .iter()
+            .map(|(size, _)| match size {
+                GrammarSize::Absolute(a) => *a * scale,
+                GrammarSize::Relative(r) => *r * length * scale,
+                GrammarSize::Floating(w) if floating > S::ZERO => rest * *w / floating,
+                GrammarSize::Floating(_) => S::ZERO,
+            })
+            .collect()
+    }
+
+    /// Applies the rules to the axiom with the given scope until only terminal
+    /// shapes are left and returns them in the order of the derivation.
+    /// The same `seed` always gives the same result.
+    pub fn derive<V: Vector3D<S = S>>(
+        &self,
+        scope: Scope<V>,
+        seed: u32,
+    ) -> Vec<(String, Scope<V>)> {
+        let mut res = Vec::new();
+        // depth-first, so the leaves appear in the order of the rules
+        let mut stack = vec![(self.axiom.clone(), scope, 0)];
+        let mut choices = 0;
+        while let Some((symbol, scope, depth)) = stack.pop() {
+            let Some(alternatives) = self.rules.get(&symbol) else {
+                res.push((symbol, scope));
+                continue;
+            };
+            if depth >= self.max_depth || alternatives.is_empty() {
+                res.push((symbol, scope));
+                continue;
+            }
+            let k = if alternatives.len() == 1 {
+                0
+            } else {
+                choices += 1;
+                self.choose(alternatives, choices, seed)
+            };
+
+            let mut children = Vec::new();
+            match &alternatives[k].1 {
+                Production::Split(axis, parts) => {
+                    let (_, length) = scope.axis(*axis);
+                    let mut offset = S::ZERO;
+                    for ((_, name), l) in parts.iter().zip(Self::split_lengths(parts, length)) {
+                        if l > S::ZERO {
+                            children.push((name.clone(), scope.part(*axis, offset, l)));
+                        }
+                        offset += l;
+                    }
+                }
+                Production::Repeat(axis, size, name) => {
+                    let (_, length) = scope.axis(*axis);
+                    let n = (length / *size).to_f64().round().max(1.0) as usize;
+                    let l = length / S::from_usize(n);
+                    for i in 0..n {
+                        children.push((name.clone(), scope.part(*axis, l * S::from_usize(i), l)));
+                    }
+                }
+                Production::Components(components) => {
+                    for (component, name) in components {
+                        let sides: &[Component] = match component {
+                            Component::Sides => &[
+                                Component::Front,
+                                Component::Right,
+                                Component::Back,
+                                Component::Left,
+                            ],
+                            c => std::slice::from_ref(c),
+                        };
+                        for side in sides {
+                            if scope.is_flat() {
+                                if *side == Component::Front {
+                                    children.push((name.clone(), scope));
+                                }
+                            } else {
+                                children.push((name.clone(), scope.side(*side)));
+                            }
+                        }
+                    }
+                }
+                Production::Extrude(depth, name) => {
+                    let offset = depth.min(S::ZERO);
+                    children.push((
+                        name.clone(),
+                        scope.part(GrammarAxis::Z, offset, depth.abs()),
+                    ));
+                }
+                Production::Replace(name) => children.push((name.clone(), scope)),
+                Production::Nil => {}
+            }
+            stack.extend(
+                children
+                    .into_iter()
+                    .rev()
+                    .map(|(name, scope)| (name, scope, depth + 1)),
+            );
+        }
+        res
+    }
+}
+
+/// Methods to create meshes from shape grammars, e.g., procedural buildings.
+pub trait MeshShapeGrammar<T: MeshType3D<Mesh = Self>>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Derives the `grammar` from the `scope` and creates a mesh from the terminal shapes.
+    /// See `MeshShapeGrammar::insert_shape_grammar`.
+    fn shape_grammar(grammar: &ShapeGrammar<T::S>, scope: Scope<T::Vec>, seed: u32) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_shape_grammar(grammar, scope, seed);
+        mesh
+    }
+
+    /// Like `shape_grammar`, but inserts the terminal shapes into the existing mesh.
+    /// Each box is a separate closed cuboid and each flat shape a separate rectangle
+    /// facing along its `z`-axis. Shapes with zero area are skipped.
+    ///
+    /// Returns the ids of the inserted faces grouped by the symbols of the terminal shapes.
+    fn insert_shape_grammar(
+        &mut self,
+        grammar: &ShapeGrammar<T::S>,
+        scope: Scope<T::Vec>,
+        seed: u32,
+    ) -> HashMap<String, Vec<T::F>> {
+        let mut builder = SoupBuilder::<3, T>::new();
+        let mut symbols = Vec::new();
+        for (symbol, scope) in grammar.derive(scope, seed) {
+            let s = scope.size;
+            if s.x() <= T::S::EPS || s.y() <= T::S::EPS {
+                continue;
+            }
+            let c = scope
+                .corners()
+                .map(|p| builder.add_vertex(T::VP::from_pos(p)));
+            let faces = if scope.is_flat() {
+                vec![[c[0], c[1], c[2], c[3]]]
+            } else {
+                vec![
+                    [c[0], c[3], c[2], c[1]],
+                    [c[4], c[5], c[6], c[7]],
+                    [c[0], c[1], c[5], c[4]],
+                    [c[1], c[2], c[6], c[5]],
+                    [c[2], c[3], c[7], c[6]],
+                    [c[3], c[0], c[4], c[7]],
+                ]
+            };
+            for face in faces {
+                builder.add_face(face);
+                symbols.push(symbol.clone());
+            }
+        }
+
+        let mut res: HashMap<String, Vec<T::F>> = HashMap::new();
+        for (f, symbol) in builder.insert_into(self).into_iter().zip(symbols) {
+            res.entry(symbol).or_default().push(f);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn s(name: &str) -> String {
+        name.to_string()
+    }
+
+    /// A building with a ground floor, repeated upper floors, and a roof.
+    fn building() -> ShapeGrammar<f64> {
+        use GrammarSize::*;
+        ShapeGrammar::new("Lot")
+            .with_rule("Lot", Production::Extrude(10.0, s("Mass")))
+            .with_rule(
+                "Mass",
+                Production::Components(vec![
+                    (Component::Sides, s("Facade")),
+                    (Component::Top, s("Roof")),
+                ]),
+            )
+            .with_rule(
+                "Facade",
+                Production::Split(
+                    GrammarAxis::Y,
+                    vec![(Absolute(4.0), s("Ground")), (Floating(1.0), s("Floors"))],
+                ),
+            )
+            .with_rule(
+                "Floors",
+                Production::Repeat(GrammarAxis::Y, 3.0, s("Floor")),
+            )
+            .with_rule("Floor", Production::Repeat(GrammarAxis::X, 2.0, s("Tile")))
+            .with_rule(
+                "Tile",
+                Production::Split(
+                    GrammarAxis::X,
+                    vec![
+                        (Relative(0.25), s("Wall")),
+                        (Floating(1.0), s("Window")),
+                        (Relative(0.25), s("Wall")),
+                    ],
+                ),
+            )
+    }
+
+    #[test]
+    fn test_grammar_derive() {
+        let lot = Scope::new(Vec3::zeros(), Vec3::new(8.0, 6.0, 0.0));
+        let leaves = building().derive(lot, 0);
+        let count = |name: &str| leaves.iter().filter(|(s, _)| s == name).count();
+        assert_eq!(count("Roof"), 1);
+        assert_eq!(count("Ground"), 4);
+        // 2 floors of 4 resp. 3 tiles on each of the long resp. short sides
+        assert_eq!(count("Window"), 2 * (4 + 3 + 4 + 3));
+        assert_eq!(count("Wall"), 2 * count("Window"));
+
+        // the sides face outwards and the tiles fill the façades
+        for (symbol, scope) in &leaves {
+            assert!(scope.is_flat());
+            let center = scope.origin + (scope.x * scope.size.x + scope.y * scope.size.y) * 0.5;
+            assert!((center - Vec3::new(4.0, 3.0, 5.0)).dot(&scope.z) > 0.0);
+            if symbol == "Window" {
+                assert!(scope.size.y.is_about(3.0, 1e-12));
+                assert!(scope.size.x.is_about(1.0, 1e-12));
+            }
+        }
+        let ground = &leaves.iter().find(|(s, _)| s == "Ground").unwrap().1;
+        assert!(ground.origin.is_about(&Vec3::zeros(), 1e-12));
+        assert!(ground.size.is_about(&Vec3::new(8.0, 4.0, 0.0), 1e-12));
+    }
+
+    #[test]
+    fn test_grammar_stochastic() {
+        let grammar = ShapeGrammar::new("Facade")
+            .with_rule("Facade", Production::Repeat(GrammarAxis::X, 1.0, s("Tile")))
+            .with_weighted_rule("Tile", 3.0, Production::Replace(s("Window")))
+            .with_weighted_rule("Tile", 1.0, Production::Nil);
+        let scope = Scope::new(Vec3::zeros(), Vec3::new(200.0, 1.0, 0.0));
+        let a = grammar.derive(scope, 1);
+        assert_eq!(a, grammar.derive(scope, 1));
+        assert_ne!(a, grammar.derive(scope, 2));
+        assert!(a.iter().all(|(s, _)| s == "Window"));
+        assert!(a.len() > 120 && a.len() < 180);
+
+        // recursive rules stop at the maximum depth
+        let grammar = ShapeGrammar::new("A")
+            .with_rule(
+                "A",
+                Production::Split(
+                    GrammarAxis::X,
+                    vec![
+                        (GrammarSize::Relative(0.5), s("B")),
+                        (GrammarSize::Floating(1.0), s("A")),
+                    ],
+                ),
+            )
+            .with_max_depth(5);
+        assert_eq!(grammar.derive(scope, 0).len(), 6);
+    }
+
+    #[test]
+    fn test_grammar_mesh() {
+        let lot = Scope::new(Vec3::zeros(), Vec3::new(8.0, 6.0, 0.0));
+        let grammar = building()
+            .with_rule("Window", Production::Extrude(-0.2, s("Recess")))
+            .with_rule("Wall", Production::Nil);
+        let mut mesh = Mesh3d64::default();
+        let faces = mesh.insert_shape_grammar(&grammar, Scope::new(Vec3::zeros(), lot.size), 3);
+        assert!(mesh.check().is_ok());
+        assert_eq!(faces["Roof"].len(), 1);
+        assert_eq!(faces["Ground"].len(), 4);
+        assert_eq!(faces["Recess"].len(), 6 * 28);
+        assert_eq!(mesh.num_faces(), 1 + 4 + 6 * 28);
+
+        // a single box is a closed cuboid
+        let cuboid = Mesh3d64::shape_grammar(
+            &ShapeGrammar::new("Box"),
+            Scope::new(Vec3::zeros(), Vec3::new(1.0, 2.0, 3.0)),
+            0,
+        );
+        assert!(!cuboid.is_open());
+        assert!(cuboid.report().volume.unwrap().is_about(6.0, 1e-9));
+    }
+}
@@ -0,0 +1,193 @@
+use super::{MeshSmoothing, MeshSplitFaces, SmoothingOptions};
+use crate::{
+    math::{HasPosition, Scalar, Transformable, VectorIteratorExt},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, FaceBasics, MeshBasics, MeshDump,
+        MeshHalfEdgeBuilder, MeshType3D, MeshTypeHalfEdge, VertexBasics,
+    },
+    tesselate::TriangulationAlgorithm,
+    util::Selection,
+};
+
+/// How `MeshFillHoles::fill_holes` closes a boundary loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HoleFilling {
+    /// A single face using the vertices of the loop. Can be non-planar.
+    Polygon,
+
+    /// Triangles between the vertices of the loop. No vertices are inserted.
+    #[default]
+    Triangulated,
+
+    /// Concentric rings of quads around a fan of triangles. The new vertices are relaxed
+    /// to the average of their neighbors while the loop stays fixed, which approximates
+    /// a minimal surface spanned by the loop.
+    Smooth,
+}
+
+/// Methods to close the holes of meshes, e.g., to make imported or clipped meshes watertight.
+pub trait MeshFillHoles<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshSplitFaces<T> + MeshSmoothing<T> + MeshDump<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+    /// Fills all boundary loops with at most `max_edges` edges as described by `mode`.
+    /// Larger loops, e.g., the outline of an intentionally open mesh, are kept.
+    ///
+    /// Returns the new faces.
+    fn fill_holes(&mut self, max_edges: usize, mode: HoleFilling) -> Vec<T::F> {
+        self.boundary_loops()
+            .into_iter()
+            .filter(|chain| chain.len() <= max_edges)
+            .flat_map(|chain| self.fill_hole(chain[0], mode))
+            .collect()
+    }
+
+    /// Fills the boundary loop of the boundary halfedge `e`, i.e., a halfedge without a
+    /// face, as described by `mode`. Returns the new faces.
+    fn fill_hole(&mut self, e: T::E, mode: HoleFilling) -> Vec<T::F> {
+        let f = self.close_hole_default(e);
+        match mode {
+            HoleFilling::Polygon => vec![f],
+            HoleFilling::Triangulated => {
+                self.triangulate_face_in_place(f, TriangulationAlgorithm::Auto)
+            }
+            HoleFilling::Smooth => {
+                let boundary = self
+                    .face(f)
+                    .vertices(self)
+                    .map(|v| v.payload().clone())
+                    .collect::<Vec<_>>();
+                let n = boundary.len();
+                let center: T::Vec = boundary.iter().map(|vp| *vp.pos()).stable_mean();
+                let rings = n / 4;
+                let mut faces = Vec::new();
+                let mut inner = f;
+                let mut added = Selection::new();
+                for k in 1..=rings {
+                    let t = T::S::from_usize(k) / T::S::from_usize(rings + 1);
+                    let center = T::VP::from_pos(center);
+                    let ring = boundary
+                        .iter()
+                        .map(|vp| vp.lerped(&center, t))
+                        .collect::<Vec<_>>();
+                    let (f, vs) = inset_ring::<T>(self, inner, ring, &mut faces);
+                    inner = f;
+                    added.extend(vs);
+                }
+                let (c, fan) = self.poke_face(inner);
+                added.insert(c);
+                faces.extend(fan);
+
+                let options = SmoothingOptions {
+                    iterations: 8 * (rings + 1) * (rings + 1),
+                    fix_boundary: false,
+                    ..Default::default()
+                };
+                self.smooth_selected(&options, &added);
+                faces
+            }
+        }
+    }
+}
+
+/// Splits a ring of quads off the boundary of the face `f` with the new vertices `ring`,
+/// one for each vertex of the face in the same order. The quads are appended to `faces`.
+/// Returns the remaining inner face and the new vertices.
+fn inset_ring<T: MeshTypeHalfEdge + MeshType3D>(
+    mesh: &mut T::Mesh,
+    f: T::F,
+    ring: Vec<T::VP>,
+    faces: &mut Vec<T::F>,
+) -> (T::F, Vec<T::V>)
+where
+    T::EP: DefaultEdgePayload,
+    T::Mesh: MeshSplitFaces<T>,
+{
+    let vs = mesh.face(f).vertex_ids(mesh).collect::<Vec<_>>();
+    let n = vs.len();
+    let mut ring = ring.into_iter();
+
+    // the first quad is created together with the first two vertices of the ring
+    let (mut rest, quad) = mesh.split_face_default(
+        f,
+        vs[0],
+        vs[1],
+        [ring.next().unwrap(), ring.next().unwrap()],
+    );
+    faces.push(quad);
+    // the quad runs from `vs[0]` to `vs[1]` and back along the ring
+    let ids = mesh.face(quad).vertex_ids(mesh).collect::<Vec<_>>();
+    let j = ids.iter().position(|v| *v == vs[1]).unwrap();
+    let mut qs = vec![ids[(j + 2) % 4], ids[(j + 1) % 4]];
+
+    // cut off one quad after the other
+    for (i, vp) in ring.enumerate() {
+        let (left, right) = mesh.split_face_default(rest, qs[i + 1], vs[i + 2], [vp]);
+        faces.push(right);
+        rest = left;
+        let q = mesh
+            .face(right)
+            .vertex_ids(mesh)
+            .find(|v| !vs.contains(v) && !qs.contains(v))
+            .unwrap();
+        qs.push(q);
+    }
+    let (inner, quad) = mesh.split_face_default(rest, qs[n - 1], qs[0], []);
+    faces.push(quad);
+    (inner, qs)
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    /// Returns a cube with the face at the given edge removed. If `join` is set, the face
+    /// is joined with its neighbor first to get a non-planar hole with six edges.
+    fn open_cube(join: bool) -> Mesh3d64 {
+        let mut mesh = Mesh3d64::cube(2.0);
+        let e = mesh.edge_ids().min().unwrap();
+        let f = if join {
+            mesh.remove_edge(e)
+        } else {
+            mesh.edge(e).face_id()
+        };
+        mesh.remove_face(f);
+        mesh
+    }
+
+    #[test]
+    fn test_fill_holes() {
+        for (mode, new_faces) in [
+            (HoleFilling::Polygon, 1),
+            (HoleFilling::Triangulated, 2),
+            (HoleFilling::Smooth, 8),
+        ] {
+            let mut mesh = open_cube(false);
+            let faces = mesh.fill_holes(4, mode);
+            assert_eq!(faces.len(), new_faces);
+            assert!(mesh.check().is_ok());
+            assert!(mesh.boundary_loops().is_empty());
+            assert!(mesh.report().volume.unwrap().is_about(8.0, 1e-9));
+        }
+
+        // loops with too many edges are kept
+        let mut mesh = open_cube(true);
+        assert_eq!(mesh.boundary_loops()[0].len(), 6);
+        assert!(mesh.fill_holes(5, HoleFilling::Triangulated).is_empty());
+        assert_eq!(mesh.boundary_loops().len(), 1);
+
+        // a non-planar loop gets a patch between its vertices
+        let faces = mesh.fill_holes(6, HoleFilling::Smooth);
+        assert_eq!(faces.len(), 6 + 6);
+        assert!(mesh.check().is_ok());
+        assert!(mesh.boundary_loops().is_empty());
+        assert_eq!(mesh.num_vertices(), 8 + 6 + 1);
+        assert!(mesh
+            .vertices()
+            .all(|v| v.pos().iter().all(|x| x.abs() <= 1.0 + 1e-9)));
+    }
+}
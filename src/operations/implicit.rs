@@ -0,0 +1,622 @@
+use super::MeshInternalFaces;
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector3D, VectorIteratorExt},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, FaceBasics, MeshTrait, MeshType3D, SoupBuilder,
+        VertexBasics,
+    },
+};
+use std::collections::HashMap;
+
+/// Returns the closest point to `p` on the triangle `a`, `b`, `c`
+/// (see Christer Ericson, Real-Time Collision Detection, 5.1.5).
+pub(super) fn closest_point_on_triangle<V: Vector3D>(p: V, a: V, b: V, c: V) -> V {
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= V::S::ZERO && d2 <= V::S::ZERO {
+        return a;
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= V::S::ZERO && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= V::S::ZERO && d1 >= V::S::ZERO && d3 <= V::S::ZERO {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= V::S::ZERO && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= V::S::ZERO && d2 >= V::S::ZERO && d6 <= V::S::ZERO {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= V::S::ZERO && (d4 - d3) >= V::S::ZERO && (d5 - d6) >= V::S::ZERO {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = V::S::ONE / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+/// The corners of the six faces of a unit cube in counter-clockwise order
+/// when seen from outside. Each corner is given as its offset `(dx, dy, dz)`.
+const CUBE_FACES: [[[usize; 3]; 4]; 6] = [
+    [[0, 0, 0], [0, 0, 1], [0, 1, 1], [0, 1, 0]],
+    [[1, 0, 0], [1, 1, 0], [1, 1, 1], [1, 0, 1]],
+    [[0, 0, 0], [1, 0, 0], [1, 0, 1], [0, 0, 1]],
+    [[0, 1, 0], [0, 1, 1], [1, 1, 1], [1, 1, 0]],
+    [[0, 0, 0], [0, 1, 0], [1, 1, 0], [1, 0, 0]],
+    [[0, 0, 1], [1, 0, 1], [1, 1, 1], [0, 1, 1]],
+];
+
+/// Samples a scalar field on a regular grid and extracts its zero level set.
+struct IsoGrid<T: MeshType3D> {
+    origin: T::Vec,
+    cell: T::S,
+    dims: [usize; 3],
+    values: Vec<T::S>,
+}
+
+impl<T: MeshType3D> IsoGrid<T> {
+    /// Samples `field` at the corners of `dims` cubic cells of size `cell` starting at `origin`.
+    fn sample(
+        origin: T::Vec,
+        cell: T::S,
+        dims: [usize; 3],
+        field: impl Fn(T::Vec) -> T::S,
+    ) -> Self {
+        let mut values = Vec::with_capacity((dims[0] + 1) * (dims[1] + 1) * (dims[2] + 1));
+        for z in 0..=dims[2] {
+            for y in 0..=dims[1] {
+                for x in 0..=dims[0] {
+                    values.push(field(Self::corner(origin, cell, [x, y, z])));
+                }
+            }
+        }
+        Self {
+            origin,
+            cell,
+            dims,
+            values,
+        }
+    }
+
+    /// Samples `field` on cubic cells inside the box from `min` to `max` such that
+    /// the longest side of the box is split into `resolution` cells.
+    fn in_box(min: T::Vec, max: T::Vec, resolution: usize, field: impl Fn(T::Vec) -> T::S) -> Self {
+        assert!(resolution >= 1);
+        let size = max - min;
+        let extent = [size.x(), size.y(), size.z()];
+        assert!(
+            extent.iter().all(|e| *e > T::S::ZERO),
+            "the box must not be empty"
+        );
+        let cell = extent.iter().fold(T::S::ZERO, |a, b| a.max(*b)) / T::S::from_usize(resolution);
+        let dims = extent.map(|e| ((e / cell).to_f64().ceil() as usize).max(1));
+        Self::sample(min, cell, dims, field)
+    }
+
+    fn corner(origin: T::Vec, cell: T::S, c: [usize; 3]) -> T::Vec {
+        origin
+            + T::Vec::from_xyz(
+                T::S::from_usize(c[0]),
+                T::S::from_usize(c[1]),
+                T::S::from_usize(c[2]),
+            ) * cell
+    }
+
+    fn value(&self, c: [usize; 3]) -> T::S {
+        self.values[c[0] + (self.dims[0] + 1) * (c[1] + (self.dims[1] + 1) * c[2])]
+    }
+
+    /// Extracts the surface where the field is zero using marching cubes.
+    /// The faces point towards positive values and vertices on shared cell edges are welded.
+    ///
+    /// Instead of a lookup table, the surface is traced along the faces of each cell.
+    /// Ambiguous faces are resolved with the asymptotic decider, which only depends on
+    /// the values of the face, so neighboring cells agree and the surface is watertight.
+    fn marching_cubes(&self) -> T::Mesh
+    where
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        let mut builder = SoupBuilder::<3, T>::new();
+        let mut crossings: HashMap<([usize; 3], usize), usize> = HashMap::new();
+        // the crossing of the grid edge from `a` to its neighbor `b`
+        let mut crossing = |builder: &mut SoupBuilder<3, T>, a: [usize; 3], b: [usize; 3]| {
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            let axis = (0..3).find(|&i| lo[i] != hi[i]).unwrap();
+            *crossings.entry((lo, axis)).or_insert_with(|| {
+                let (vl, vh) = (self.value(lo), self.value(hi));
+                let t = vl / (vl - vh);
+                let pl = Self::corner(self.origin, self.cell, lo);
+                let ph = Self::corner(self.origin, self.cell, hi);
+                builder.add_vertex(T::VP::from_pos(pl + (ph - pl) * t))
+            })
+        };
+
+        for z in 0..self.dims[2] {
+            for y in 0..self.dims[1] {
+                for x in 0..self.dims[0] {
+                    // directed segments of the surface on the faces of this cell
+                    let mut next: HashMap<usize, usize> = HashMap::new();
+                    for face in CUBE_FACES {
+                        let cs = face.map(|o| [x + o[0], y + o[1], z + o[2]]);
+                        let vs = cs.map(|c| self.value(c));
+                        let positive = vs.map(|v| v >= T::S::ZERO);
+                        let edge = |k: usize| (cs[k], cs[(k + 1) % 4]);
+                        let starts = (0..4)
+                            .filter(|&k| positive[k] && !positive[(k + 1) % 4])
+                            .collect::<Vec<_>>();
+                        let ends = (0..4)
+                            .filter(|&k| !positive[k] && positive[(k + 1) % 4])
+                            .collect::<Vec<_>>();
+                        let pairs = if starts.len() == 2 {
+                            // asymptotic decider: are the positive corners connected?
+                            let saddle =
+                                (vs[0] * vs[2] - vs[1] * vs[3]) / (vs[0] + vs[2] - vs[1] - vs[3]);
+                            let step = if saddle >= T::S::ZERO { 1 } else { 3 };
+                            starts.iter().map(|&k| (k, (k + step) % 4)).collect()
+                        } else {
+                            starts.into_iter().zip(ends).collect::<Vec<_>>()
+                        };
+                        for (s, e) in pairs {
+                            let (a, b) = edge(s);
+                            let from = crossing(&mut builder, a, b);
+                            let (a, b) = edge(e);
+                            let to = crossing(&mut builder, a, b);
+                            next.insert(from, to);
+                        }
+                    }
+
+                    // chain the segments to loops and triangulate them
+                    while let Some(&start) = next.keys().min() {
+                        let mut cycle = vec![start];
+                        let mut current = next.remove(&start).unwrap();
+                        while current != start {
+                            cycle.push(current);
+                            current = next.remove(&current).unwrap();
+                        }
+                        for i in 1..cycle.len() - 1 {
+                            builder.add_face([cycle[0], cycle[i], cycle[i + 1]]);
+                        }
+                    }
+                }
+            }
+        }
+
+        builder.build()
+    }
+    /// Places the vertex of the cell at `c` by solving the quadratic error function of the
+    /// tangent planes at the crossings. Returns `None` if the surface doesn't cross the cell.
+    fn dual_vertex(&self, c: [usize; 3], sdf: &impl Fn(T::Vec) -> T::S) -> Option<T::Vec> {
+        let h = self.cell * T::S::from(0.001);
+        let gradient = |p: T::Vec| {
+            let d = |o: T::Vec| sdf(p + o) - sdf(p - o);
+            let (x, y, z) = (
+                T::Vec::from_xyz(h, T::S::ZERO, T::S::ZERO),
+                T::Vec::from_xyz(T::S::ZERO, h, T::S::ZERO),
+                T::Vec::from_xyz(T::S::ZERO, T::S::ZERO, h),
+            );
+            T::Vec::from_xyz(d(x), d(y), d(z)).normalize()
+        };
+
+        let mut planes = Vec::new();
+        for axis in 0..3 {
+            for i in 0..4 {
+                let mut lo = c;
+                lo[(axis + 1) % 3] += i & 1;
+                lo[(axis + 2) % 3] += i >> 1;
+                let mut hi = lo;
+                hi[axis] += 1;
+                let (vl, vh) = (self.value(lo), self.value(hi));
+                if (vl >= T::S::ZERO) == (vh >= T::S::ZERO) {
+                    continue;
+                }
+                let pl = Self::corner(self.origin, self.cell, lo);
+                let ph = Self::corner(self.origin, self.cell, hi);
+                let p = pl + (ph - pl) * (vl / (vl - vh));
+                planes.push((p, gradient(p)));
+            }
+        }
+        if planes.is_empty() {
+            return None;
+        }
+
+        // Minimize sum (n * (x - p))^2 + lambda * |x - center|^2. The regularization keeps the
+        // system solvable when the normals are (nearly) parallel. Moving the center to the
+        // solution a few times removes its bias along the constrained directions.
+        let mass: T::Vec = planes.iter().map(|(p, _)| *p).stable_mean();
+        let lambda = T::S::from(0.01);
+        let mut m = [[T::S::ZERO; 3]; 3];
+        for (_, n) in &planes {
+            let n = [n.x(), n.y(), n.z()];
+            for i in 0..3 {
+                for j in 0..3 {
+                    m[i][j] += n[i] * n[j];
+                }
+            }
+        }
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] += lambda;
+        }
+        let det = |m: &[[T::S; 3]; 3]| {
+            T::S::det3(
+                m[0][0], m[0][1], m[0][2], m[1][0], m[1][1], m[1][2], m[2][0], m[2][1], m[2][2],
+            )
+        };
+        let d = det(&m);
+        let mut x = mass;
+        for _ in 0..4 {
+            let mut r = [T::S::ZERO; 3];
+            for (p, n) in &planes {
+                let dist = (*p - x).dot(n);
+                r[0] += n.x() * dist;
+                r[1] += n.y() * dist;
+                r[2] += n.z() * dist;
+            }
+            // Cramer's rule
+            let y = [0, 1, 2].map(|k| {
+                let mut mk = m;
+                for i in 0..3 {
+                    mk[i][k] = r[i];
+                }
+                det(&mk) / d
+            });
+            x += T::Vec::from_xyz(y[0], y[1], y[2]);
+        }
+
+        // fall back to the mass point if the minimum is outside of the cell
+        let lo = Self::corner(self.origin, self.cell, c);
+        let tol = self.cell * T::S::from(0.01);
+        let inside = [x.x() - lo.x(), x.y() - lo.y(), x.z() - lo.z()]
+            .iter()
+            .all(|v| *v >= -tol && *v <= self.cell + tol);
+        Some(if inside { x } else { mass })
+    }
+
+    /// Extracts the surface where the field is zero using dual contouring.
+    /// The faces point towards positive values.
+    fn dual_contouring(&self, sdf: &impl Fn(T::Vec) -> T::S) -> T::Mesh
+    where
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        let mut builder = SoupBuilder::<3, T>::new();
+        let mut vertices: HashMap<[usize; 3], usize> = HashMap::new();
+        let mut vertex = |builder: &mut SoupBuilder<3, T>, c: [usize; 3]| {
+            *vertices.entry(c).or_insert_with(|| {
+                let p = self.dual_vertex(c, sdf).unwrap();
+                builder.add_vertex(T::VP::from_pos(p))
+            })
+        };
+
+        for z in 0..=self.dims[2] {
+            for y in 0..=self.dims[1] {
+                for x in 0..=self.dims[0] {
+                    let lo = [x, y, z];
+                    for axis in 0..3 {
+                        let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+                        // only edges with four adjacent cells
+                        if lo[axis] >= self.dims[axis]
+                            || !(1..self.dims[b]).contains(&lo[b])
+                            || !(1..self.dims[c]).contains(&lo[c])
+                        {
+                            continue;
+                        }
+                        let mut hi = lo;
+                        hi[axis] += 1;
+                        let inside = self.value(lo) < T::S::ZERO;
+                        if inside == (self.value(hi) < T::S::ZERO) {
+                            continue;
+                        }
+                        // the cells around the edge in counter-clockwise order around `axis`
+                        let mut quad = [(1, 1), (0, 1), (0, 0), (1, 0)].map(|(db, dc)| {
+                            let mut cell = lo;
+                            cell[b] -= db;
+                            cell[c] -= dc;
+                            vertex(&mut builder, cell)
+                        });
+                        if !inside {
+                            quad.reverse();
+                        }
+                        builder.add_face(quad);
+                    }
+                }
+            }
+        }
+
+        builder.build()
+    }
+}
+
+/// Methods to create meshes from implicit surfaces.
+pub trait MeshImplicit<T: MeshType3D<Mesh = Self>>: MeshTrait<T = T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Extracts the surface where the signed distance function `sdf` is zero using marching
+    /// cubes. The function should be negative inside and positive outside of the solid.
+    ///
+    /// The box from `min` to `max` is divided into cubic cells such that its longest side
+    /// is split into `resolution` cells. The result is a triangle mesh with welded vertices
+    /// and faces pointing outwards. Where the solid reaches the boundary of the box,
+    /// the surface is cut off and the mesh is open.
+    fn marching_cubes(
+        sdf: impl Fn(T::Vec) -> T::S,
+        min: T::Vec,
+        max: T::Vec,
+        resolution: usize,
+    ) -> Self {
+        IsoGrid::<T>::in_box(min, max, resolution, &sdf).marching_cubes()
+    }
+
+    /// Extracts the surface where the signed distance function `sdf` is zero using
+    /// dual contouring. Takes the same parameters as `marching_cubes`.
+    ///
+    /// Each cell crossed by the surface gets one vertex and each crossed grid edge gets a
+    /// quad connecting the four adjacent cells. The vertices are placed by minimizing the
+    /// distance to the tangent planes at the crossings, which uses the gradient of `sdf`
+    /// and reproduces sharp edges and corners of the solid. The quads are usually not planar.
+    ///
+    /// Cells where the surface has multiple components can make the result non-manifold,
+    /// so the resolution should be high enough to separate thin features.
+    fn dual_contouring(
+        sdf: impl Fn(T::Vec) -> T::S,
+        min: T::Vec,
+        max: T::Vec,
+        resolution: usize,
+    ) -> Self {
+        IsoGrid::<T>::in_box(min, max, resolution, &sdf).dual_contouring(&sdf)
+    }
+}
+
+/// Methods to work with signed distance fields of meshes.
+pub trait MeshSdf<T: MeshType3D<Mesh = Self>>: MeshInternalFaces<T> {
+    /// Returns the signed distance from `p` to the surface of the mesh. The distance is
+    /// negative inside the solid, i.e., where the winding number is larger than one half.
+    /// Faces are fan-triangulated, so they should be convex.
+    fn signed_distance(&self, p: T::Vec) -> T::S {
+        let mut distance = T::S::INFINITY;
+        for f in self.faces() {
+            let vs = f.vertices(self).map(|v| v.pos()).collect::<Vec<T::Vec>>();
+            for i in 1..vs.len() - 1 {
+                let q = closest_point_on_triangle(p, vs[0], vs[i], vs[i + 1]);
+                distance = distance.min(q.distance(&p));
+            }
+        }
+        if self.winding_number(p) > T::S::HALF {
+            -distance
+        } else {
+            distance
+        }
+    }
+
+    /// Blends the shapes of the closed meshes `a` and `b` by linearly interpolating
+    /// their signed distance fields with the factor `t` in `[0, 1]` and extracting the
+    /// zero level set with marching cubes. This works with meshes of arbitrary topology,
+    /// e.g., to generate in-betweens for animations or variations of shapes.
+    ///
+    /// The longest side of the common bounding box is divided into `resolution` cells.
+    fn blend_sdf(a: &Self, b: &Self, t: T::S, resolution: usize) -> Self
+    where
+        Self: MeshImplicit<T>,
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        assert!(resolution >= 1);
+        let (min, max) = padded_bounds::<T>(
+            a.vertices().chain(b.vertices()).map(|v| v.pos()),
+            T::S::ZERO,
+            resolution,
+        );
+        Self::marching_cubes(
+            |p| a.signed_distance(p) * (T::S::ONE - t) + b.signed_distance(p) * t,
+            min,
+            max,
+            resolution + 4,
+        )
+    }
+
+    /// Offsets the surface of the closed mesh by `distance` along its signed distance field,
+    /// i.e., grows the solid for positive and shrinks it for negative distances, and extracts
+    /// the new surface with marching cubes. Unlike moving the vertices along their normals,
+    /// this rounds off convex edges and corners and never self-intersects, so it also works
+    /// for distances larger than the features of the mesh.
+    ///
+    /// The longest side of the bounding box of the result is divided into about
+    /// `resolution` cells. Evaluating the distance field visits every face for each sample,
+    /// so this is slow for large meshes.
+    fn offset_sdf(&self, distance: T::S, resolution: usize) -> Self
+    where
+        Self: MeshImplicit<T>,
+        T::EP: DefaultEdgePayload,
+        T::FP: DefaultFacePayload,
+    {
+        assert!(resolution >= 1);
+        let (min, max) = padded_bounds::<T>(
+            self.vertices().map(|v| v.pos()),
+            distance.max(T::S::ZERO),
+            resolution,
+        );
+        Self::marching_cubes(
+            |p| self.signed_distance(p) - distance,
+            min,
+            max,
+            resolution + 4,
+        )
+    }
+}
+
+/// Returns the bounding box of the points grown by `margin` and padded by two cells
+/// of a grid that splits its longest side into `resolution` cells, such that the
+/// surfaces extracted from it are closed.
+pub(super) fn padded_bounds<T: MeshType3D>(
+    points: impl Iterator<Item = T::Vec>,
+    margin: T::S,
+    resolution: usize,
+) -> (T::Vec, T::Vec) {
+    let mut min = [T::S::INFINITY; 3];
+    let mut max = [T::S::NEG_INFINITY; 3];
+    for p in points {
+        for (i, c) in [p.x(), p.y(), p.z()].into_iter().enumerate() {
+            min[i] = min[i].min(c - margin);
+            max[i] = max[i].max(c + margin);
+        }
+    }
+    let size = (0..3)
+        .map(|i| max[i] - min[i])
+        .fold(T::S::ZERO, |a, b| a.max(b));
+    let padding = T::Vec::splat(size / T::S::from_usize(resolution) * T::S::TWO);
+    (
+        T::Vec::from_xyz(min[0], min[1], min[2]) - padding,
+        T::Vec::from_xyz(max[0], max[1], max[2]) + padding,
+    )
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_signed_distance() {
+        let cube = Mesh3d64::cube(2.0);
+        assert!(cube
+            .signed_distance(Vec3::new(0.0, 0.0, 0.0))
+            .is_about(-1.0, 1e-9));
+        assert!(cube
+            .signed_distance(Vec3::new(0.5, 0.2, 0.0))
+            .is_about(-0.5, 1e-9));
+        assert!(cube
+            .signed_distance(Vec3::new(3.0, 0.0, 0.0))
+            .is_about(2.0, 1e-9));
+        let corner = cube.signed_distance(Vec3::new(2.0, 2.0, 2.0));
+        assert!(corner.is_about(3.0f64.sqrt(), 1e-9));
+    }
+
+    #[test]
+    fn test_blend_sdf() {
+        let cube = Mesh3d64::cube(2.0);
+        let sphere = Mesh3d64::icosphere(1.0, 2);
+
+        for t in [0.0, 0.5, 1.0] {
+            let blend = Mesh3d64::blend_sdf(&cube, &sphere, t, 8);
+            assert!(blend.check().is_ok());
+            assert!(!blend.is_open());
+            for v in blend.vertices() {
+                let d =
+                    cube.signed_distance(v.pos()) * (1.0 - t) + sphere.signed_distance(v.pos()) * t;
+                assert!(d.abs() < 0.1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_offset_sdf() {
+        let cube = Mesh3d64::cube(2.0);
+
+        // growing rounds off the edges and corners
+        let grown = cube.offset_sdf(0.5, 12);
+        assert!(grown.check().is_ok());
+        assert!(!grown.is_open());
+        for v in grown.vertices() {
+            assert!((cube.signed_distance(v.pos()) - 0.5).abs() < 0.05);
+        }
+        assert!(grown
+            .vertices()
+            .all(|v| v.pos().norm() < 3.0f64.sqrt() + 0.5 + 1e-9));
+
+        // shrinking keeps the corners sharp
+        let shrunk = cube.offset_sdf(-0.25, 12);
+        assert!(shrunk.check().is_ok());
+        assert!(!shrunk.is_open());
+        for v in shrunk.vertices() {
+            assert!(v.pos().abs().max() < 0.75 + 0.05);
+            assert!((cube.signed_distance(v.pos()) + 0.25).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_marching_cubes() {
+        let min = Vec3::new(-1.5, -1.5, -1.5);
+        let max = Vec3::new(1.5, 1.5, 1.5);
+        let sphere = Mesh3d64::marching_cubes(|p| p.norm() - 1.0, min, max, 12);
+        assert!(sphere.check().is_ok());
+        assert!(!sphere.is_open());
+        let euler = sphere.num_vertices() + sphere.num_faces() - sphere.num_edges() / 2;
+        assert_eq!(euler, 2);
+        for v in sphere.vertices() {
+            assert!((v.pos().norm() - 1.0).abs() < 0.02);
+        }
+        for f in sphere.faces() {
+            assert!(f.normal(&sphere).dot(&f.centroid(&sphere)) > 0.0);
+        }
+
+        // a torus has genus one
+        let torus = Mesh3d64::marching_cubes(
+            |p| (Vec2::new(Vec2::new(p.x, p.z).norm() - 1.0, p.y)).norm() - 0.4,
+            Vec3::new(-1.5, -0.5, -1.5),
+            Vec3::new(1.5, 0.5, 1.5),
+            24,
+        );
+        assert!(torus.check().is_ok());
+        assert!(!torus.is_open());
+        assert_eq!(
+            torus.num_vertices() + torus.num_faces(),
+            torus.num_edges() / 2
+        );
+
+        // the box cuts off the sphere
+        let cut = Mesh3d64::marching_cubes(|p| p.norm() - 1.0, min, Vec3::new(1.5, 1.5, 0.0), 12);
+        assert!(cut.check().is_ok());
+        assert!(cut.is_open());
+    }
+
+    #[test]
+    fn test_dual_contouring() {
+        let min = Vec3::new(-1.5, -1.5, -1.5);
+        let max = Vec3::new(1.5, 1.5, 1.5);
+        let sphere = Mesh3d64::dual_contouring(|p| p.norm() - 1.0, min, max, 12);
+        assert!(sphere.check().is_ok());
+        assert!(!sphere.is_open());
+        let euler = sphere.num_vertices() + sphere.num_faces() - sphere.num_edges() / 2;
+        assert_eq!(euler, 2);
+        for v in sphere.vertices() {
+            assert!((v.pos().norm() - 1.0).abs() < 0.05);
+        }
+        for f in sphere.faces() {
+            assert_eq!(f.vertices(&sphere).count(), 4);
+            let vs: Vec<Vec3<f64>> = f.vertices(&sphere).map(|v| v.pos()).collect();
+            let n = vs.iter().copied().normal();
+            assert!(n.dot(&f.centroid(&sphere)) > 0.0);
+        }
+
+        // the corners and edges of a cube are preserved
+        let cube = Mesh3d64::dual_contouring(
+            |p| p.x.abs().max(p.y.abs()).max(p.z.abs()) - 0.55,
+            min,
+            max,
+            10,
+        );
+        assert!(cube.check().is_ok());
+        assert!(!cube.is_open());
+        for v in cube.vertices() {
+            let p = v.pos();
+            assert!(p.x.abs().max(p.y.abs()).max(p.z.abs()).is_about(0.55, 1e-3));
+        }
+        for x in [-0.55, 0.55] {
+            for y in [-0.55, 0.55] {
+                for z in [-0.55, 0.55] {
+                    let corner = Vec3::new(x, y, z);
+                    assert!(cube.vertices().any(|v| v.pos().distance(&corner) < 1e-3));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    math::{Scalar, Vector, Vector3D, VectorIteratorExt},
+    mesh::{Face3d, FaceBasics, MeshBuilder, MeshType3D, VertexBasics},
+};
+
+/// Returns the signed solid angle of the triangle `a`, `b`, `c` as seen from the origin
+/// using the formula of Van Oosterom and Strackee.
+fn solid_angle<V: Vector3D>(a: V, b: V, c: V) -> V::S {
+    let (la, lb, lc) = (a.length(), b.length(), c.length());
+    let det = a.dot(&b.cross(&c));
+    let div = la * lb * lc + a.dot(&b) * lc + a.dot(&c) * lb + b.dot(&c) * la;
+    V::S::TWO * det.atan2(div)
+}
+
+/// Methods to detect and remove faces that are hidden inside the solid, e.g.,
+/// after appending overlapping primitives without computing their boolean union.
+pub trait MeshInternalFaces<T: MeshType3D<Mesh = Self>>: MeshBuilder<T> {
+    /// Returns the generalized winding number of the mesh at `p`, i.e., the number of times
+    /// the (outward oriented) surface wraps around `p`. It is about 1 inside of closed solids,
+    /// 0 outside, and adds up where solids overlap.
+    fn winding_number(&self, p: T::Vec) -> T::S {
+        let total = T::S::stable_sum(self.faces().flat_map(|f| {
+            let vs = f
+                .vertices(self)
+                .map(|v| v.pos() - p)
+                .collect::<Vec<T::Vec>>();
+            // the signed solid angles of a fan add up to the one of the planar polygon
+            (1..vs.len() - 1)
+                .map(|i| solid_angle(vs[0], vs[i], vs[i + 1]))
+                .collect::<Vec<_>>()
+        }));
+        total / (T::S::FOUR * T::S::PI)
+    }
+
+    /// Returns all faces that are fully enclosed by the solid, i.e., faces where the
+    /// winding number directly in front of the centroid and each corner is at least one.
+    ///
+    /// `eps` is the distance in front of the face at which the winding number is sampled.
+    fn internal_faces(&self, eps: T::S) -> Vec<T::F> {
+        let limit = T::S::HALF;
+        self.faces()
+            .filter(|f| {
+                let normal = Face3d::normal(*f, self).normalize();
+                let centroid: T::Vec = f.vertices(self).map(|v| v.pos()).stable_mean();
+                let inside = |p: T::Vec| self.winding_number(p + normal * eps) > limit;
+                inside(centroid)
+                    && f.vertices(self).all(|v| {
+                        // pull the corners slightly towards the center to avoid the edges
+                        let pos: T::Vec = v.pos();
+                        inside(pos + (centroid - pos) * T::S::from(0.01))
+                    })
+            })
+            .map(|f| f.id())
+            .collect()
+    }
+
+    /// Removes all faces that are fully enclosed by the solid (see `internal_faces`)
+    /// and returns the number of removed faces.
+    ///
+    /// This reduces overdraw for kit-bashed meshes, but will leave the mesh open
+    /// where the removed faces were attached to the visible surface.
+    /// The edges and vertices of the removed faces are kept, i.e., fully enclosed
+    /// islands remain as loose boundary edges without faces.
+    fn remove_internal_faces(&mut self, eps: T::S) -> usize {
+        let faces = self.internal_faces(eps);
+        for f in &faces {
+            self.remove_face(*f);
+        }
+        faces.len()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    /// Merges the given meshes without welding or boolean operations.
+    fn merge(meshes: &[Mesh3d64]) -> Mesh3d64 {
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        for mesh in meshes {
+            for f in mesh.faces() {
+                builder.add_polygon(f.vertices(mesh).map(|v| *v.payload()));
+            }
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_winding_number() {
+        let cube = Mesh3d64::cube(1.0);
+        assert!(cube
+            .winding_number(Vec3::new(0.1, 0.2, 0.0))
+            .is_about(1.0, 1e-9));
+        assert!(cube
+            .winding_number(Vec3::new(2.0, 0.0, 0.0))
+            .is_about(0.0, 1e-9));
+    }
+
+    #[test]
+    fn test_remove_internal_faces() {
+        // a small cube fully inside a large one
+        let mut mesh = merge(&[Mesh3d64::cube(2.0), Mesh3d64::cube(0.5)]);
+        assert_eq!(mesh.remove_internal_faces(1e-6), 6);
+        assert_eq!(mesh.num_faces(), 6);
+        assert!(mesh
+            .faces()
+            .all(|f| f.vertices(&mesh).all(|v| v.pos().x.abs() > 0.9)));
+        assert!(mesh
+            .faces()
+            .all(|f| f.vertices(&mesh).all(|v| v.pos().x.abs() > 0.9)));
+
+        // only the face of the cube sticking into the box is hidden
+        let mut mesh = merge(&[
+            Mesh3d64::cube(1.0),
+            Mesh3d64::cuboid(Vec3::new(1.0, 2.0, 2.0)).translated(&Vec3::new(0.75, 0.0, 0.0)),
+        ]);
+        let internal = mesh.internal_faces(1e-6);
+        assert_eq!(internal.len(), 1);
+        let f = mesh.face(internal[0]);
+        assert!(f.centroid(&mesh).is_about(&Vec3::new(0.5, 0.0, 0.0), 1e-9));
+        assert_eq!(mesh.remove_internal_faces(1e-6), 1);
+        assert_eq!(mesh.num_faces(), 11);
+        assert!(mesh.is_open());
+    }
+}
@@ -0,0 +1,457 @@
+use super::MeshStreamlines;
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector3D, Vector3DIteratorExt},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, FaceBasics, HalfEdge, MeshType3D, MeshTypeHalfEdge,
+        VertexBasics,
+    },
+    primitives::{Make2dShape, MakeTube, TubeJoint},
+};
+use std::collections::VecDeque;
+
+/// The parameters of the ivy growth, see `MeshIvy::grow_ivy`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IvyOptions<S: Scalar> {
+    /// The length of each growth step along the surface.
+    pub step: S,
+
+    /// The length of the branches starting at the roots.
+    pub max_len: S,
+
+    /// The factor applied to the length in each branching generation.
+    pub length_factor: S,
+
+    /// How strongly the branches grow towards the `up` direction.
+    pub climb: S,
+
+    /// How strongly the branches follow the noise field, i.e., how much they meander.
+    pub wander: S,
+
+    /// The frequency of the noise field.
+    pub frequency: S,
+
+    /// How strongly side branches grow away from their parent.
+    pub spread: S,
+
+    /// The distance between side branches along their parent.
+    pub branch_spacing: S,
+
+    /// The number of branching generations. Roots are generation 0.
+    pub max_depth: usize,
+
+    /// The maximum number of branches in total.
+    pub max_branches: usize,
+
+    /// The largest gap between surfaces a branch can cross when it reaches the
+    /// boundary of the mesh. The gap is found by casting rays forwards and against `up`.
+    pub jump: S,
+
+    /// The radius of the branches starting at the roots.
+    pub radius: S,
+
+    /// The factor applied to the radius in each branching generation.
+    pub radius_factor: S,
+
+    /// The number of segments around each branch.
+    pub segments: usize,
+
+    /// The average distance between leaves along the branches. Zero disables leaves.
+    pub leaf_spacing: S,
+
+    /// The length of the leaves.
+    pub leaf_size: S,
+
+    /// The seed of the noise field and the leaf placement.
+    pub seed: u32,
+}
+
+impl<S: Scalar> Default for IvyOptions<S> {
+    fn default() -> Self {
+        Self {
+            step: S::from(0.05),
+            max_len: S::TWO,
+            length_factor: S::from(0.6),
+            climb: S::ONE,
+            wander: S::from(0.7),
+            frequency: S::TWO,
+            spread: S::from(0.8),
+            branch_spacing: S::from(0.4),
+            max_depth: 2,
+            max_branches: 64,
+            jump: S::from(0.2),
+            radius: S::from(0.02),
+            radius_factor: S::from(0.7),
+            segments: 5,
+            leaf_spacing: S::from(0.1),
+            leaf_size: S::from(0.08),
+            seed: 0,
+        }
+    }
+}
+
+/// A branch of an ivy grown by `MeshIvy::grow_ivy`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IvyBranch<V: Vector3D> {
+    /// The points of the branch on the surface of the host mesh.
+    pub points: Vec<V>,
+
+    /// The normal of the host surface at each point. Where a point lies on an edge of
+    /// the host, the normals of both faces are averaged.
+    pub normals: Vec<V>,
+
+    /// The branching generation, i.e., 0 for branches starting at a root.
+    pub depth: usize,
+}
+
+impl<V: Vector3D> IvyBranch<V> {
+    /// Returns the length of the branch.
+    pub fn length(&self) -> V::S {
+        self.points
+            .windows(2)
+            .map(|w| w[0].distance(&w[1]))
+            .fold(V::S::ZERO, |a, b| a + b)
+    }
+}
+
+/// Returns a pseudo-random number in `[0, 1)`.
+pub(crate) fn hash01(i: usize, seed: u32) -> f64 {
+    let mut h = (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (seed as u64) << 32;
+    h ^= h >> 30;
+    h = h.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h ^= h >> 27;
+    h = h.wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^= h >> 31;
+    (h >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// A smooth pseudo-random function in `[-1, 1]` built from a few sine waves
+/// with random directions and phases.
+fn wobble<V: Vector3D>(p: V, seed: u32) -> V::S {
+    let mut sum = 0.0;
+    for octave in 0..3 {
+        let r = |k: usize| hash01(octave * 4 + k, seed) * 2.0 - 1.0;
+        let (x, y, z) = (p.x().to_f64(), p.y().to_f64(), p.z().to_f64());
+        let frequency = (1 << octave) as f64;
+        let phase = r(3) * std::f64::consts::PI;
+        sum += (frequency * (x * r(0) + y * r(1) + z * r(2)) + phase).sin() / frequency;
+    }
+    V::S::from((sum / 1.75) as f32)
+}
+
+/// The maximum number of gaps a single branch can cross.
+const MAX_JUMPS: usize = 8;
+
+/// Methods to grow ivy or vines over the surface of a host mesh.
+pub trait MeshIvy<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshStreamlines<T> + MakeTube<T> + Make2dShape<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Returns the normal of the surface at the point closest to `p`.
+    fn surface_normal(&self, p: T::Vec) -> Option<T::Vec> {
+        let (f, _) = self.closest_face_point(p)?;
+        let ps = self
+            .face(f)
+            .vertices(self)
+            .map(|v| v.pos())
+            .collect::<Vec<_>>();
+        Some(ps.into_iter().normal().normalize())
+    }
+
+    /// Grows an ivy over the surface of the mesh, starting with one branch at each of the `roots`.
+    ///
+    /// Each branch crawls over the surface in geodesic steps like `trace_streamlines`,
+    /// following a field that climbs towards `up` and meanders along a noise field.
+    /// When a branch stops early, e.g., at the boundary of the mesh, it tries to cross
+    /// the gap to another surface by casting rays forwards and against `up`.
+    /// Side branches sprout alternately to the left and to the right of their parent.
+    ///
+    /// Returns the branches in the order they were grown, i.e., parents before their children.
+    fn grow_ivy(
+        &self,
+        roots: &[T::Vec],
+        up: T::Vec,
+        options: &IvyOptions<T::S>,
+    ) -> Vec<IvyBranch<T::Vec>> {
+        assert!(options.step > T::S::ZERO, "the step must be positive");
+        let up = up.normalize();
+        let zero = T::Vec::from_xyz(T::S::ZERO, T::S::ZERO, T::S::ZERO);
+        let mut queue = roots.iter().map(|r| (*r, zero, 0)).collect::<VecDeque<_>>();
+        let mut branches = Vec::new();
+        let mut seed = options.seed;
+        while let Some((start, side, depth)) = queue.pop_front() {
+            if branches.len() >= options.max_branches {
+                break;
+            }
+            let s = seed;
+            seed = seed.wrapping_add(3);
+            let field = |p: T::Vec| {
+                let q = p * options.frequency;
+                let noise = T::Vec::from_xyz(
+                    wobble(q, s),
+                    wobble(q, s.wrapping_add(1)),
+                    wobble(q, s.wrapping_add(2)),
+                );
+                up * options.climb + side * options.spread + noise * options.wander
+            };
+            let len = options.max_len
+                * T::S::from(options.length_factor.to_f64().powi(depth as i32) as f32);
+            let points = self.grow_branch(start, field, up, len, options);
+            if points.len() < 2 {
+                continue;
+            }
+            // the midpoints of the segments lie inside the faces while the points can lie on edges
+            let segment_normals = points
+                .windows(2)
+                .map(|w| {
+                    self.surface_normal((w[0] + w[1]) * T::S::HALF)
+                        .unwrap_or(up)
+                })
+                .collect::<Vec<_>>();
+            let normals = (0..points.len())
+                .map(|i| {
+                    let before = segment_normals[i.saturating_sub(1)];
+                    let after = segment_normals[i.min(segment_normals.len() - 1)];
+                    (before + after).normalize()
+                })
+                .collect::<Vec<_>>();
+
+            if depth < options.max_depth && options.branch_spacing > T::S::ZERO {
+                let mut dist = T::S::ZERO;
+                let mut next = options.branch_spacing;
+                let mut left = true;
+                for i in 1..points.len() {
+                    let d = points[i] - points[i - 1];
+                    dist += d.length();
+                    while dist >= next {
+                        let side = normals[i].cross(&d).normalize();
+                        let side = if left { side } else { -side };
+                        queue.push_back((points[i], side, depth + 1));
+                        left = !left;
+                        next += options.branch_spacing;
+                    }
+                }
+            }
+
+            branches.push(IvyBranch {
+                points,
+                normals,
+                depth,
+            });
+        }
+        branches
+    }
+
+    /// Traces a single branch of length `len` and crosses gaps, see `grow_ivy`.
+    fn grow_branch(
+        &self,
+        start: T::Vec,
+        field: impl Fn(T::Vec) -> T::Vec,
+        up: T::Vec,
+        len: T::S,
+        options: &IvyOptions<T::S>,
+    ) -> Vec<T::Vec> {
+        let mut points: Vec<T::Vec> = Vec::new();
+        let mut remaining = len;
+        let mut start = start;
+        for _ in 0..=MAX_JUMPS {
+            let line = self
+                .trace_streamlines(&field, &[start], options.step, remaining)
+                .pop()
+                .unwrap_or_default();
+            for p in line {
+                if let Some(last) = points.last() {
+                    remaining -= last.distance(&p);
+                    if last.distance_squared(&p) <= T::S::EPS {
+                        continue;
+                    }
+                }
+                points.push(p);
+            }
+            if remaining <= options.step || options.jump <= T::S::ZERO || points.len() < 2 {
+                break;
+            }
+
+            // the branch stopped early: try to reach another surface
+            let end = points[points.len() - 1];
+            let dir = (end - points[points.len() - 2]).normalize();
+            let hit = [dir, -up].iter().find_map(|d| {
+                let (_, hit, t) = self.raycast(end, *d)?;
+                (t <= options.jump && t > options.step * T::S::HALF).then_some(hit)
+            });
+            let Some(hit) = hit else {
+                break;
+            };
+            remaining -= end.distance(&hit);
+            points.push(hit);
+            start = hit;
+        }
+        points
+    }
+
+    /// Returns a new mesh with the ivy grown over this mesh, see `grow_ivy` and `insert_ivy`.
+    fn ivy(&self, roots: &[T::Vec], up: T::Vec, options: &IvyOptions<T::S>) -> Self {
+        let branches = self.grow_ivy(roots, up, options);
+        let mut mesh = Self::default();
+        mesh.insert_ivy(&branches, options);
+        mesh
+    }
+
+    /// Sweeps tubes along the `branches` and scatters leaves along them.
+    /// The tubes lie on top of the host surface and get thinner with each generation.
+    /// The leaves are flat, diamond-shaped faces facing away from the host surface that
+    /// point alternately to the left and to the right with some random jitter.
+    ///
+    /// Returns the ids of the faces of the branches and of the leaves.
+    fn insert_ivy(
+        &mut self,
+        branches: &[IvyBranch<T::Vec>],
+        options: &IvyOptions<T::S>,
+    ) -> (Vec<T::F>, Vec<T::F>) {
+        let mut vine = Vec::new();
+        let mut leaves = Vec::new();
+        let mut leaf_index = 0;
+        for branch in branches {
+            let radius = options.radius
+                * T::S::from(options.radius_factor.to_f64().powi(branch.depth as i32) as f32);
+            let path = branch
+                .points
+                .iter()
+                .zip(branch.normals.iter())
+                .map(|(p, n)| *p + *n * radius)
+                .collect::<Vec<_>>();
+            vine.extend(self.insert_tube_from_path(
+                &path,
+                radius,
+                options.segments,
+                TubeJoint::Miter,
+                true,
+            ));
+
+            if options.leaf_spacing <= T::S::ZERO {
+                continue;
+            }
+            let mut dist = T::S::ZERO;
+            let mut next = options.leaf_spacing * T::S::HALF;
+            for i in 1..branch.points.len() {
+                let d = branch.points[i] - branch.points[i - 1];
+                let l = d.length();
+                while dist + l >= next {
+                    let jitter = T::S::from(hash01(leaf_index, options.seed) as f32);
+                    let n = (branch.normals[i - 1] + branch.normals[i]).normalize();
+                    let t = d / l;
+                    let side = n.cross(&t);
+                    let angle = T::S::PI * (T::S::from(0.15) + jitter * T::S::from(0.2));
+                    let angle = if leaf_index % 2 == 0 { angle } else { -angle };
+                    let dir = t * angle.cos() + side * angle.sin();
+                    let across = n.cross(&dir) * options.leaf_size * T::S::from(0.25);
+                    let along = dir * options.leaf_size;
+                    let base =
+                        branch.points[i - 1] + d * ((next - dist) / l) + n * radius * T::S::TWO;
+                    let e = self.insert_polygon(
+                        [
+                            base,
+                            base + along * T::S::HALF + across,
+                            base + along,
+                            base + along * T::S::HALF - across,
+                        ]
+                        .map(T::VP::from_pos),
+                    );
+                    leaves.push(self.edge(e).twin(self).face_id());
+                    leaf_index += 1;
+                    next += options.leaf_spacing
+                        * (T::S::HALF + T::S::from(hash01(leaf_index, options.seed + 1) as f32));
+                }
+                dist += l;
+            }
+        }
+        (vine, leaves)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_ivy_cube() {
+        let cube = Mesh3d64::cube(1.0);
+        let options = IvyOptions::<f64> {
+            max_len: 1.5,
+            ..Default::default()
+        };
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let branches = cube.grow_ivy(&[Vec3::new(0.0, -0.5, -0.5)], up, &options);
+        assert!(branches.len() > 1);
+        assert!(branches.len() <= options.max_branches);
+        assert_eq!(branches[0].depth, 0);
+        assert!(branches.iter().any(|b| b.depth == 2));
+        assert!(branches.iter().all(|b| b.depth <= 2));
+        let root = &branches[0];
+        assert!(root.length() <= 1.5 + 1e-9);
+        // the root climbs the wall
+        assert!(root.points.iter().any(|p| p.y > 0.4));
+        for branch in &branches {
+            for p in &branch.points {
+                let (_, q) = cube.closest_face_point(*p).unwrap();
+                assert!(p.distance(&q) < 1e-9);
+            }
+        }
+
+        let mut mesh = Mesh3d64::default();
+        let (vine, leaves) = mesh.insert_ivy(&branches, &options);
+        assert!(mesh.check().is_ok());
+        assert!(!vine.is_empty());
+        assert!(!leaves.is_empty());
+        assert_eq!(mesh.num_faces(), vine.len() + leaves.len());
+        // leaves grow from the vine and face away from the (convex) host
+        for f in leaves {
+            let n = mesh.face(f).normal(&mesh).normalize();
+            for v in mesh.face(f).vertices(&mesh) {
+                let (_, q) = cube.closest_face_point(v.pos()).unwrap();
+                assert!(q.distance(&v.pos()) <= 2.0 * options.radius + options.leaf_size);
+                assert!(n.dot(&v.pos()) > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ivy_jump() {
+        // a plane and a box behind a small gap
+        let mut host = Mesh3d64::heightmap(2.0, 2.0, 4, |_, _| 0.0);
+        let mut block = Mesh3d64::cube(1.0);
+        block.translate(&Vec3::new(1.6, 0.0, 0.0));
+        host.insert_submesh(&block, None);
+        let options = IvyOptions::<f64> {
+            max_len: 2.0,
+            wander: 0.0,
+            max_depth: 0,
+            leaf_spacing: 0.0,
+            ..Default::default()
+        };
+        let up = Vec3::new(1.0, 0.2, 0.0);
+        let seed = [Vec3::new(0.1, 0.0, 0.1)];
+        let branches = host.grow_ivy(&seed, up, &options);
+        assert_eq!(branches.len(), 1);
+        let branch = &branches[0];
+        assert!(branch.length().is_about(2.0, 1e-6));
+        // the branch crosses the gap, climbs the box and continues on its top
+        assert!(branch
+            .points
+            .iter()
+            .any(|p| p.x.is_about(1.1, 1e-9) && p.y.is_about(0.0, 1e-9)));
+        let last = branch.points.last().unwrap();
+        assert!(last.y.is_about(0.5, 1e-9) && last.x > 1.1);
+
+        // without jumping, the branch stops at the boundary
+        let options = IvyOptions {
+            jump: 0.0,
+            ..options
+        };
+        let branches = host.grow_ivy(&seed, up, &options);
+        assert!(branches[0].points.last().unwrap().x.is_about(1.0, 1e-9));
+        assert_eq!(host.ivy(&seed, up, &options).islands().len(), 1);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    math::{HasPosition, Polygon, Scalar, Transformable, Vector, Vector2D, Vector3D},
+    mesh::{
+        DefaultEdgePayload, EdgeBasics, Face3d, FaceBasics, HalfEdge, HalfEdgeSemiBuilder,
+        MeshHalfEdgeBuilder, MeshType3D, MeshTypeHalfEdge, VertexBasics,
+    },
+};
+use std::collections::{HashMap, HashSet};
+
+/// Methods to cut new edges into meshes, e.g., to prepare partial extrusions or material splits.
+pub trait MeshKnife<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshHalfEdgeBuilder<T> + HalfEdgeSemiBuilder<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+    /// Projects the `polyline` along `direction` onto the faces facing against it, i.e.,
+    /// those visible when looking along the direction, and cuts it into them. The faces
+    /// are split along the projected polyline and no geometry is removed. Since all facing
+    /// faces are cut, faces hidden behind others are cut as well. Repeat the first point
+    /// at the end to cut a closed loop.
+    ///
+    /// Only the position of the points perpendicular to the direction matters. The edges
+    /// crossed by the polyline are split and the new vertices get payloads interpolated along
+    /// the edges. Points of the polyline inside a face become new vertices on its plane.
+    /// Parts of the polyline that don't cross a face from one edge to another, e.g., its
+    /// ends, aren't cut. The faces should be planar and convex.
+    ///
+    /// Returns one halfedge of each new edge.
+    fn knife(&mut self, polyline: &[T::Vec], direction: T::Vec) -> Vec<T::E> {
+        if polyline.len() < 2 {
+            return Vec::new();
+        }
+        let d = direction.normalize();
+        let seed = if d.x().abs() < T::S::HALF {
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)
+        } else {
+            T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO)
+        };
+        let u = (seed - d * seed.dot(&d)).normalize();
+        let w = d.cross(&u);
+        let project = |p: T::Vec| T::Vec2::from_xy(p.dot(&u), p.dot(&w));
+        let line = polyline.iter().map(|p| project(*p)).collect::<Vec<_>>();
+        let eps = T::S::EPS.sqrt();
+
+        // the position along the polyline of a point on it, i.e., the index of the
+        // segment plus the parameter on the segment
+        let locate = |p: T::Vec2| {
+            line.windows(2).enumerate().find_map(|(i, s)| {
+                let ab = s[1] - s[0];
+                let l = ab.length_squared();
+                if l <= eps * eps {
+                    return None;
+                }
+                let t = ((p - s[0]).dot(&ab) / l).max(T::S::ZERO).min(T::S::ONE);
+                ((s[0] + ab * t - p).length() <= eps).then(|| T::S::from_usize(i) + t)
+            })
+        };
+
+        let faces = self
+            .faces()
+            .filter(|f| Face3d::normal(*f, self).dot(&d) < T::S::ZERO)
+            .map(|f| f.id())
+            .collect::<Vec<_>>();
+        let mut cuts: HashMap<T::V, T::S> = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut edges = Vec::new();
+        for f in &faces {
+            for e in self.face(*f).edges(self) {
+                if let Some(t) = locate(project(e.origin(self).pos())) {
+                    cuts.insert(e.origin_id(), t);
+                }
+                if visited.insert(e.id()) && visited.insert(e.twin_id()) {
+                    edges.push(e.id());
+                }
+            }
+        }
+
+        // split the edges where they cross the polyline
+        for e in edges {
+            let a = self.edge(e).origin(self).payload().clone();
+            let b = self.edge(e).target(self).payload().clone();
+            let (pa, pb) = (project(*a.pos()), project(*b.pos()));
+            let r = pb - pa;
+            let mut hits = line
+                .windows(2)
+                .enumerate()
+                .filter_map(|(i, s)| {
+                    let q = s[1] - s[0];
+                    let denom = r.perp_dot(&q);
+                    if denom.abs() <= eps {
+                        return None;
+                    }
+                    let t = (s[0] - pa).perp_dot(&q) / denom;
+                    let k = (s[0] - pa).perp_dot(&r) / denom;
+                    (t > eps && t < T::S::ONE - eps && k >= T::S::ZERO && k <= T::S::ONE)
+                        .then(|| (t, T::S::from_usize(i) + k))
+                })
+                .collect::<Vec<_>>();
+            hits.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+            hits.dedup_by(|x, y| (x.0 - y.0).abs() <= eps);
+            let mut current = e;
+            for (t, position) in hits {
+                let twin = self.edge(current).twin_id();
+                let next = self.subdivide_unsafe(current, a.lerped(&b, t), Default::default());
+                self.subdivide_unsafe_try_fixup(twin, Default::default())
+                    .expect("the twin must be subdivided");
+                cuts.insert(self.edge(next).origin_id(), position);
+                current = next;
+            }
+        }
+
+        // connect consecutive cuts on the boundary of each face
+        let mut res = Vec::new();
+        for f in faces {
+            let normal = Face3d::normal(self.face(f), self).normalize();
+            let origin = self.face(f).vertices(self).next().unwrap().pos();
+            let mut points = self
+                .face(f)
+                .vertex_ids(self)
+                .filter_map(|v| cuts.get(&v).map(|t| (*t, v)))
+                .collect::<Vec<_>>();
+            points.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+            let mut pieces = vec![f];
+            for pair in points.windows(2) {
+                let ((t0, v0), (t1, v1)) = (pair[0], pair[1]);
+                let inner = (0..polyline.len())
+                    .filter(|k| {
+                        let k = T::S::from_usize(*k);
+                        k > t0 + eps && k < t1 - eps
+                    })
+                    .collect::<Vec<_>>();
+                let path = std::iter::once(self.vertex(v0).pos())
+                    .chain(inner.iter().map(|k| polyline[*k]))
+                    .chain(std::iter::once(self.vertex(v1).pos()))
+                    .map(project)
+                    .collect::<Vec<_>>();
+                let inside = |f: T::F| {
+                    let poly =
+                        T::Poly::from_iter(self.face(f).vertices(self).map(|v| project(v.pos())));
+                    path.windows(2)
+                        .all(|s| poly.contains(&((s[0] + s[1]) * T::S::HALF)))
+                };
+                let Some(i) = pieces.iter().position(|piece| {
+                    let vs = self.face(*piece).vertex_ids(self).collect::<Vec<_>>();
+                    let n = vs.len();
+                    let i = vs.iter().position(|v| *v == v0);
+                    let j = vs.iter().position(|v| *v == v1);
+                    matches!((i, j), (Some(i), Some(j))
+                        if !inner.is_empty() || ((i + 1) % n != j && (j + 1) % n != i))
+                        && inside(*piece)
+                }) else {
+                    continue;
+                };
+
+                // the points inside the face are moved along the direction onto its plane
+                let chain = inner.iter().map(|k| {
+                    let p = polyline[*k];
+                    T::VP::from_pos(p + d * ((origin - p).dot(&normal) / d.dot(&normal)))
+                });
+                let before = self.face(pieces[i]).edge_ids(self).collect::<HashSet<_>>();
+                let (left, right) = self.split_face_default(pieces[i], v0, v1, chain);
+                res.extend(
+                    self.face(left)
+                        .edge_ids(self)
+                        .filter(|e| !before.contains(e)),
+                );
+                pieces.swap_remove(i);
+                pieces.push(left);
+                pieces.push(right);
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn above(x: f64, z: f64) -> Vec3<f64> {
+        Vec3::new(x, 5.0, z)
+    }
+
+    #[test]
+    fn test_knife() {
+        let down = Vec3::new(0.0, -1.0, 0.0);
+
+        // a straight cut across the top splits it and the edges it crosses
+        let mut cube = Mesh3d64::cube(2.0);
+        let edges = cube.knife(&[above(-2.0, 0.2), above(2.0, 0.2)], down);
+        assert!(cube.check().is_ok());
+        assert_eq!(edges.len(), 1);
+        assert_eq!(cube.num_faces(), 7);
+        assert_eq!(cube.num_vertices(), 10);
+        let e = cube.edge(edges[0]);
+        assert!(e.origin(&cube).pos().z.is_about(0.2, 1e-12));
+        assert!(e.target(&cube).pos().z.is_about(0.2, 1e-12));
+        assert!(cube.report().volume.unwrap().is_about(8.0, 1e-12));
+
+        // a bend inside the face becomes a new vertex on the face
+        let mut cube = Mesh3d64::cube(2.0);
+        let edges = cube.knife(
+            &[above(-2.0, -0.5), above(0.0, 0.5), above(2.0, -0.5)],
+            down,
+        );
+        assert!(cube.check().is_ok());
+        assert_eq!(edges.len(), 2);
+        assert_eq!(cube.num_faces(), 7);
+        assert_eq!(cube.num_vertices(), 11);
+        assert!(cube
+            .vertices()
+            .any(|v| v.pos().is_about(&Vec3::new(0.0, 1.0, 0.5), 1e-12)));
+
+        // a cut through existing vertices only inserts the diagonal
+        let mut cube = Mesh3d64::cube(2.0);
+        let edges = cube.knife(&[above(-2.0, -2.0), above(2.0, 2.0)], down);
+        assert!(cube.check().is_ok());
+        assert_eq!(edges.len(), 1);
+        assert_eq!(cube.num_faces(), 7);
+        assert_eq!(cube.num_vertices(), 8);
+
+        // a closed loop inside the face doesn't reach its boundary
+        let mut cube = Mesh3d64::cube(2.0);
+        let square = [
+            (-0.5, -0.5),
+            (0.5, -0.5),
+            (0.5, 0.5),
+            (-0.5, 0.5),
+            (-0.5, -0.5),
+        ];
+        let edges = cube.knife(&square.map(|(x, z)| above(x, z)), down);
+        assert!(edges.is_empty());
+        assert_eq!(cube.num_faces(), 6);
+
+        // cutting from the side crosses two faces
+        let mut cube = Mesh3d64::cube(2.0);
+        let edges = cube.knife(
+            &[Vec3::new(-5.0, 0.0, 5.0), Vec3::new(5.0, 0.0, -5.0)],
+            Vec3::new(-1.0, 0.0, -1.0),
+        );
+        assert!(cube.check().is_ok());
+        assert_eq!(edges.len(), 2);
+        assert_eq!(cube.num_faces(), 8);
+    }
+}
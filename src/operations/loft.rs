@@ -1,4 +1,11 @@
-use crate::mesh::{DefaultEdgePayload, DefaultFacePayload, HalfEdge, MeshTypeHalfEdge};
+use crate::{
+    math::{HasPosition, NurbsCurve, Scalar, Vector, Vector3D},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, HalfEdge, MeshType3D, MeshTypeHalfEdge,
+        SoupBuilder, VertexBasics,
+    },
+    primitives::fit_budget,
+};
 
 // TODO: Adjust this to not be halfedge-specific
 
@@ -312,6 +319,408 @@ where
             }
         }
     }
+
+    /// Inserts the surface of revolution of the `profile` around the `axis` through the origin
+    /// as a new connected component and returns the ids of the inserted faces.
+    ///
+    /// The profile lies in a half-plane bounded by the axis: `x` is the distance from the axis
+    /// and `y` the height along it. The profile is rotated counter-clockwise around the
+    /// axis by `angle` radians using `segments` steps. The faces point outwards if the
+    /// profile goes up, e.g., from the bottom to the top of a vase.
+    ///
+    /// Points on the axis aren't duplicated, so the faces touching them become triangles.
+    /// If the first and last point of the profile are the same, the profile is closed.
+    /// If the angle is a full turn, the seam is closed, too. Hence, a profile that starts
+    /// and ends on the axis or a closed profile revolved by a full turn gives a closed mesh.
+    /// Otherwise, the ends are left open.
+    fn revolve(
+        &mut self,
+        profile: &[T::Vec2],
+        axis: T::Vec,
+        angle: T::S,
+        segments: usize,
+    ) -> Vec<T::F>
+    where
+        T: MeshType3D<Mesh = Self>,
+    {
+        self.revolve_with(profile, axis, angle, segments, |_, vp| vp)
+    }
+
+    /// Like `revolve`, but the payload of each vertex in the `j`-th column, i.e., rotated by
+    /// `angle * j / segments`, is given by `payload(j, vp)` where `vp` is the default payload
+    /// at that position. Vertices on the axis are shared by all columns and belong to column 0.
+    fn revolve_with(
+        &mut self,
+        profile: &[T::Vec2],
+        axis: T::Vec,
+        angle: T::S,
+        segments: usize,
+        mut payload: impl FnMut(usize, T::VP) -> T::VP,
+    ) -> Vec<T::F>
+    where
+        T: MeshType3D<Mesh = Self>,
+    {
+        assert!(segments >= 1, "need at least one segment");
+        assert!(angle > T::S::ZERO, "the angle must be positive");
+        let mut profile = profile.to_vec();
+        let closed_profile =
+            profile.len() > 2 && profile[0].distance_squared(profile.last().unwrap()) <= T::S::EPS;
+        if closed_profile {
+            profile.pop();
+        }
+        assert!(profile.len() >= 2, "the profile needs at least 2 points");
+        assert!(
+            profile.iter().all(|p| p.x() >= -T::S::EPS),
+            "the profile must not cross the axis"
+        );
+
+        let full = angle >= T::S::TWO * T::S::PI - T::S::EPS.sqrt();
+        let columns = if full { segments } else { segments + 1 };
+        let up = axis.normalize();
+        let seed = if up.x().abs() < T::S::HALF {
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)
+        } else {
+            T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO)
+        };
+        let u = (seed - up * seed.dot(&up)).normalize();
+        let v = up.cross(&u);
+
+        // one row of vertices per profile point; points on the axis have a single vertex
+        let mut builder = SoupBuilder::<3, T>::new();
+        let rows = profile
+            .iter()
+            .map(|p| {
+                if p.x() <= T::S::EPS {
+                    vec![builder.add_vertex(payload(0, T::VP::from_pos(up * p.y()))); columns]
+                } else {
+                    (0..columns)
+                        .map(|j| {
+                            let phi = angle * T::S::from_usize(j) / T::S::from_usize(segments);
+                            let radial = u * phi.cos() + v * phi.sin();
+                            builder.add_vertex(payload(
+                                j,
+                                T::VP::from_pos(up * p.y() + radial * p.x()),
+                            ))
+                        })
+                        .collect::<Vec<_>>()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let n = profile.len();
+        let spans = if closed_profile { n } else { n - 1 };
+        for i in 0..spans {
+            let (a, b) = (&rows[i], &rows[(i + 1) % n]);
+            if a[0] == b[0] && a[1 % columns] == b[1 % columns] {
+                // both points are on the axis
+                continue;
+            }
+            for j in 0..segments {
+                let k = (j + 1) % columns;
+                builder.add_face([a[j], a[k], b[k], b[j]]);
+            }
+        }
+        builder.insert_into(self)
+    }
+
+    /// Like `revolve`, but with the largest number of segments such that the inserted faces
+    /// have at most `max_triangles` triangles. Uses 3 segments for a full turn and 1 otherwise
+    /// if the budget is smaller.
+    fn revolve_with_budget(
+        &mut self,
+        profile: &[T::Vec2],
+        axis: T::Vec,
+        angle: T::S,
+        max_triangles: usize,
+    ) -> Vec<T::F>
+    where
+        T: MeshType3D<Mesh = Self>,
+    {
+        // a quad per span and segment that degenerates to a triangle if it touches the axis
+        let closed_profile =
+            profile.len() > 2 && profile[0].distance_squared(profile.last().unwrap()) <= T::S::EPS;
+        let points = if closed_profile {
+            &profile[..profile.len() - 1]
+        } else {
+            profile
+        };
+        let spans = if closed_profile {
+            points.len()
+        } else {
+            points.len().saturating_sub(1)
+        };
+        let per_segment: usize = (0..spans)
+            .map(|i| {
+                let on_axis = |p: &T::Vec2| p.x() <= T::S::EPS;
+                match (
+                    on_axis(&points[i]),
+                    on_axis(&points[(i + 1) % points.len()]),
+                ) {
+                    (true, true) => 0,
+                    (false, false) => 2,
+                    _ => 1,
+                }
+            })
+            .sum();
+        let full = angle >= T::S::TWO * T::S::PI - T::S::EPS.sqrt();
+        let segments = fit_budget(max_triangles, if full { 3 } else { 1 }, |n| {
+            n * per_segment.max(1)
+        });
+        self.revolve(profile, axis, angle, segments)
+    }
+
+    /// Like `revolve`, but the profile is a curve that is flattened with the tolerance `tol`.
+    fn revolve_curve(
+        &mut self,
+        profile: &NurbsCurve<T::S, 2, T::Vec2>,
+        tol: T::S,
+        axis: T::Vec,
+        angle: T::S,
+        segments: usize,
+    ) -> Vec<T::F>
+    where
+        T: MeshType3D<Mesh = Self>,
+    {
+        self.revolve(&profile.flatten(tol), axis, angle, segments)
+    }
+
+    /// Connects the boundary loops of the boundary edges `a` and `b` with a band of faces
+    /// tagged `"bridge"` and returns their ids, e.g., to join two separately generated
+    /// parts into one solid. The loops must be different but can belong to the same
+    /// connected component.
+    ///
+    /// The vertices of the loops are paired such that the sum of the squared distances is
+    /// minimal. If both loops have the same number of vertices, the band consists of quads.
+    /// Otherwise, the surplus vertices are distributed evenly by inserting triangles.
+    fn bridge(&mut self, a: T::E, b: T::E) -> Vec<T::F>
+    where
+        T: MeshType3D<Mesh = Self>,
+    {
+        assert!(
+            self.edge(a).is_boundary_self() && self.edge(b).is_boundary_self(),
+            "Both edges must be on the boundary"
+        );
+        let la: Vec<T::E> = self.edges_from(a).map(|e| e.id()).collect();
+        let lb: Vec<T::E> = self.edges_from(b).map(|e| e.id()).collect();
+        assert!(
+            !la.contains(&b),
+            "The edges must be on different boundary loops"
+        );
+        let (na, nb) = (la.len(), lb.len());
+        let pa: Vec<T::Vec> = la
+            .iter()
+            .map(|e| self.edge(*e).origin(self).pos())
+            .collect();
+        let pb: Vec<T::Vec> = lb
+            .iter()
+            .map(|e| self.edge(*e).origin(self).pos())
+            .collect();
+
+        // The loops run in opposite directions, so the `j`-th vertex of the second loop
+        // when walking along with the first loop is `pb[(s + nb - j) % nb]`.
+        let shift = (0..nb)
+            .map(|s| {
+                let cost = (0..na)
+                    .map(|i| {
+                        let j = (2 * i * nb + na) / (2 * na);
+                        pa[i].distance_squared(&pb[(s + nb - j % nb) % nb])
+                    })
+                    .fold(T::S::ZERO, |acc, d| acc + d);
+                (s, cost)
+            })
+            .min_by(|x, y| x.1.partial_cmp(&y.1).unwrap())
+            .unwrap()
+            .0;
+        // the boundary edge from the `j+1`-th to the `j`-th vertex of the second loop
+        let gb = |j: usize| lb[(shift + 2 * nb - j - 1) % nb];
+
+        self.insert_edge_between(
+            self.edge(la[0]).origin_id(),
+            Default::default(),
+            self.edge(lb[shift]).origin_id(),
+            Default::default(),
+        );
+        // the boundary edge from the current vertex of the second loop to the current
+        // vertex of the first loop
+        let mut connector = self.edge(la[0]).prev_id();
+
+        let mut faces = Vec::with_capacity(na + nb);
+        let (mut i, mut j) = (0, 0);
+        while i < na || j < nb {
+            // advance along the loop that is further behind or both if they are level
+            let (ai, bj) = ((i + 1) * nb, (j + 1) * na);
+            let (step_a, step_b) = if i == na {
+                (false, true)
+            } else if j == nb {
+                (true, false)
+            } else {
+                (ai <= bj, bj <= ai)
+            };
+            let (ni, nj) = (i + step_a as usize, j + step_b as usize);
+            if ni == na && nj == nb {
+                faces.push(self.close_hole(connector, Default::default(), false));
+                break;
+            }
+            let inside = if step_a { la[i] } else { connector };
+            let outside = if step_b {
+                self.edge(gb(j)).prev_id()
+            } else {
+                self.edge(connector).prev_id()
+            };
+            let (f, _, e) = self.close_face_default(inside, outside, false);
+            faces.push(f);
+            connector = e;
+            (i, j) = (ni, nj);
+        }
+
+        self.tag_faces(faces.iter().copied(), "bridge");
+        faces
+    }
 }
 
 // TODO: tests!
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, mesh::signed_volume, prelude::*};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_revolve() {
+        // a cylinder with both ends on the axis
+        let profile = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ];
+        let mut mesh = Mesh3d64::default();
+        let faces = mesh.revolve(&profile, Vec3::new(0.0, 1.0, 0.0), 2.0 * PI, 64);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(faces.len(), 3 * 64);
+        assert_eq!(mesh.num_vertices(), 2 + 2 * 64);
+        let expected = PI * 2.0;
+        assert!((mesh.report().volume.unwrap() - expected).abs() < 0.01 * expected);
+
+        // half a turn leaves the seam open
+        let mut half = Mesh3d64::default();
+        half.revolve(&profile, Vec3::new(0.0, 0.0, 1.0), PI, 8);
+        assert!(half.check().is_ok());
+        assert!(half.is_open());
+        assert_eq!(half.num_vertices(), 2 + 2 * 9);
+        assert!(half
+            .vertices()
+            .all(|v| v.pos().z >= 0.0 && v.pos().z <= 2.0 && v.pos().y >= -1e-12));
+
+        // a closed profile gives a torus
+        let circle = (0..16)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / 16.0;
+                Vec2::new(2.0 + 0.5 * a.cos(), 0.5 * a.sin())
+            })
+            .chain(std::iter::once(Vec2::new(2.5, 0.0)))
+            .collect::<Vec<_>>();
+        let mut torus = Mesh3d64::default();
+        torus.revolve(&circle, Vec3::new(0.0, 1.0, 0.0), 2.0 * PI, 32);
+        assert!(torus.check().is_ok());
+        assert!(!torus.is_open());
+        assert_eq!(torus.num_vertices(), 16 * 32);
+
+        // the columns can carry their own payload, e.g., a texture coordinate around the axis
+        let mut vase = Mesh3d64::default();
+        vase.revolve_with(&profile, Vec3::new(0.0, 1.0, 0.0), PI, 4, |j, mut vp| {
+            vp.set_uv(Vec2::new(j as f64 / 4.0, 0.0));
+            vp
+        });
+        assert!(vase.check().is_ok());
+        assert!(vase.vertices().all(|v| {
+            let p = v.pos();
+            p.x * p.x + p.z * p.z < 1e-12
+                || p.z.atan2(p.x).abs().is_about(v.payload().uv().x * PI, 1e-9)
+        }));
+        assert!(torus.report().volume.unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_revolve_curve() {
+        // a quarter circle from the bottom of the axis up to the rim is a half sphere
+        let curve = NurbsCurve::new(
+            2,
+            vec![
+                Vec2::new(0.0, -1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(1.0, 0.0),
+            ],
+            vec![1.0, 0.5f64.sqrt(), 1.0],
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        );
+        let mut bowl = Mesh3d64::default();
+        bowl.revolve_curve(&curve, 1e-4, Vec3::new(0.0, 1.0, 0.0), 2.0 * PI, 32);
+        assert!(bowl.check().is_ok());
+        assert!(bowl.is_open());
+        assert!(bowl.num_vertices() > 32 * 4);
+        assert!(bowl.vertices().all(|v| v.pos().norm().is_about(1.0, 1e-9)));
+    }
+
+    /// Two horizontal polygons with `n` vertices at the bottom facing down and
+    /// `m` vertices at the top facing up. The top polygon starts `shift` vertices later.
+    fn two_polygons(n: usize, m: usize, shift: usize) -> Mesh3d64 {
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        for (k, z, sign, s) in [(n, 0.0, -1.0, 0), (m, 1.0, 1.0, shift)] {
+            let first = builder.num_vertices();
+            for i in 0..k {
+                let a = sign * 2.0 * PI * (i + s) as f64 / k as f64;
+                builder.add_vertex(VertexPayloadPNU::from_pos(Vec3::new(a.cos(), a.sin(), z)));
+            }
+            builder.add_face((first..first + k).collect::<Vec<_>>());
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_bridge() {
+        // bridging the rims of an open trough, i.e., loops of the same component, gives a torus
+        let profile = [
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.5, -1.0),
+            Vec2::new(2.0, 0.0),
+        ];
+        let mut tube = Mesh3d64::default();
+        tube.revolve(&profile, Vec3::new(0.0, 1.0, 0.0), 2.0 * PI, 6);
+        let rim = |r: f64| {
+            tube.edges()
+                .find(|e| e.is_boundary_self() && e.origin(&tube).pos().norm().is_about(r, 1e-9))
+                .unwrap()
+                .id()
+        };
+        let ends = [rim(1.0), rim(2.0)];
+        let faces = tube.bridge(ends[0], ends[1]);
+        assert_eq!(faces.len(), 6);
+        assert!(faces.iter().all(|f| tube.face(*f).num_vertices(&tube) == 4));
+        assert!(tube.check().is_ok());
+        assert!(!tube.is_open());
+        assert_eq!(tube.report().islands, 1);
+
+        // bridging the boundaries of two separate faces gives a closed solid
+        for (n, m) in [(8, 8), (8, 5), (3, 7)] {
+            let mut mesh = two_polygons(n, m, 3);
+            let bottom = mesh.faces().next().unwrap().edge(&mesh).twin_id();
+            let top = mesh.faces().last().unwrap().edge(&mesh).twin_id();
+            let faces = mesh.bridge(bottom, top);
+            assert!(mesh.check().is_ok());
+            assert!(!mesh.is_open());
+            // the faces between loops of different sizes aren't planar
+            let volume = signed_volume::<MeshType3d64PNU>(&mesh);
+            assert!(volume > 0.0);
+            // a quad whenever both loops are level, i.e., gcd(n, m) times
+            let gcd = (1..=n.min(m)).filter(|d| n % d == 0 && m % d == 0).max();
+            assert_eq!(faces.len(), n + m - gcd.unwrap());
+            if n == m {
+                // the vertices are paired such that the quads are vertical
+                assert!(volume.is_about(regular_polygon_area(1.0, n), 1e-9));
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{
+    math::{Scalar, Vector3D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshType3D},
+    primitives::{MakeTube, TubeJoint},
+};
+use std::collections::HashMap;
+
+/// A deterministic, context-free Lindenmayer system.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LSystem {
+    /// The initial string.
+    pub axiom: String,
+
+    /// The production rules. Symbols without a rule are kept.
+    pub rules: HashMap<char, String>,
+}
+
+impl LSystem {
+    /// Creates a new L-system with the given axiom and no rules.
+    pub fn new(axiom: &str) -> Self {
+        Self {
+            axiom: axiom.to_string(),
+            rules: HashMap::new(),
+        }
+    }
+
+    /// Adds a rule that replaces `symbol` with `replacement`.
+    pub fn with_rule(mut self, symbol: char, replacement: &str) -> Self {
+        self.rules.insert(symbol, replacement.to_string());
+        self
+    }
+
+    /// Applies the rules `iterations` times to the axiom.
+    pub fn expand(&self, iterations: usize) -> String {
+        let mut s = self.axiom.clone();
+        for _ in 0..iterations {
+            s = s
+                .chars()
+                .map(|c| self.rules.get(&c).cloned().unwrap_or_else(|| c.to_string()))
+                .collect();
+        }
+        s
+    }
+}
+
+/// A polyline drawn by the turtle with a constant radius.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurtleStroke<V: Vector3D> {
+    /// The points of the polyline.
+    pub points: Vec<V>,
+
+    /// The radius of the branch.
+    pub radius: V::S,
+}
+
+/// The parameters of the turtle interpreting an L-system.
+///
+/// The turtle starts at the origin heading along the y-axis and understands these symbols:
+/// - `F`, `G`: Move forward and draw a branch.
+/// - `f`: Move forward without drawing.
+/// - `+`, `-`: Turn left resp. right.
+/// - `&`, `^`: Pitch down resp. up.
+/// - `\`, `/`: Roll left resp. right.
+/// - `|`: Turn around.
+/// - `[`, `]`: Push resp. pop the state of the turtle to start a branch.
+/// - `!`: Multiply the radius by `radius_factor`.
+///
+/// All other symbols are ignored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurtleOptions<S: Scalar> {
+    /// The distance of each step.
+    pub step: S,
+
+    /// The angle of each rotation in radians.
+    pub angle: S,
+
+    /// The initial radius of the branches.
+    pub radius: S,
+
+    /// The factor applied to the radius by `!`.
+    pub radius_factor: S,
+
+    /// The number of segments around each branch.
+    pub segments: usize,
+
+    /// How the branches bend.
+    pub joint: TubeJoint,
+}
+
+impl<S: Scalar> Default for TurtleOptions<S> {
+    fn default() -> Self {
+        Self {
+            step: S::ONE,
+            angle: S::PI / S::from_usize(8),
+            radius: S::from(0.1),
+            radius_factor: S::from(0.7),
+            segments: 6,
+            joint: TubeJoint::Miter,
+        }
+    }
+}
+
+/// Rotates `v` around the unit vector `axis` by `angle`.
+fn rotate<V: Vector3D>(v: V, axis: V, angle: V::S) -> V {
+    let (sin, cos) = (angle.sin(), angle.cos());
+    v * cos + axis.cross(&v) * sin + axis * (axis.dot(&v) * (V::S::ONE - cos))
+}
+
+#[derive(Clone)]
+struct TurtleState<V: Vector3D> {
+    pos: V,
+    heading: V,
+    left: V,
+    up: V,
+    radius: V::S,
+}
+
+impl<S: Scalar> TurtleOptions<S> {
+    /// Interprets the `commands` and returns the drawn branches.
+    ///
+    /// Consecutive steps are joined into a single stroke. Strokes are split where the radius
+    /// changes or the turtle turns by more than 90 degrees. Branches start a new stroke
+    /// at the position of the turtle, so they overlap with their parent.
+    pub fn interpret<V: Vector3D<S = S>>(&self, commands: &str) -> Vec<TurtleStroke<V>> {
+        let mut state = TurtleState {
+            pos: V::from_xyz(S::ZERO, S::ZERO, S::ZERO),
+            heading: V::from_xyz(S::ZERO, S::ONE, S::ZERO),
+            left: V::from_xyz(-S::ONE, S::ZERO, S::ZERO),
+            up: V::from_xyz(S::ZERO, S::ZERO, S::ONE),
+            radius: self.radius,
+        };
+        let mut path: Vec<V> = Vec::new();
+        let mut stack: Vec<(TurtleState<V>, Vec<V>)> = Vec::new();
+        let mut strokes = Vec::new();
+        let flush = |path: &mut Vec<V>, radius: S, strokes: &mut Vec<TurtleStroke<V>>| {
+            if path.len() >= 2 {
+                strokes.push(TurtleStroke {
+                    points: std::mem::take(path),
+                    radius,
+                });
+            }
+            path.clear();
+        };
+
+        for c in commands.chars() {
+            match c {
+                'F' | 'G' => {
+                    let next = state.pos + state.heading * self.step;
+                    if let [.., a, b] = path[..] {
+                        if (b - a).normalize().dot(&state.heading) < -S::EPS.sqrt() {
+                            flush(&mut path, state.radius, &mut strokes);
+                        }
+                    }
+                    if path.is_empty() {
+                        path.push(state.pos);
+                    }
+                    path.push(next);
+                    state.pos = next;
+                }
+                'f' => {
+                    flush(&mut path, state.radius, &mut strokes);
+                    state.pos += state.heading * self.step;
+                }
+                '+' | '-' | '|' => {
+                    let angle = match c {
+                        '+' => self.angle,
+                        '-' => -self.angle,
+                        _ => S::PI,
+                    };
+                    state.heading = rotate(state.heading, state.up, angle);
+                    state.left = rotate(state.left, state.up, angle);
+                }
+                '&' | '^' => {
+                    let angle = if c == '&' { self.angle } else { -self.angle };
+                    state.heading = rotate(state.heading, state.left, angle);
+                    state.up = rotate(state.up, state.left, angle);
+                }
+                '\\' | '/' => {
+                    let angle = if c == '\\' { self.angle } else { -self.angle };
+                    state.left = rotate(state.left, state.heading, angle);
+                    state.up = rotate(state.up, state.heading, angle);
+                }
+                '[' => {
+                    stack.push((state.clone(), std::mem::take(&mut path)));
+                }
+                ']' => {
+                    flush(&mut path, state.radius, &mut strokes);
+                    if let Some((s, p)) = stack.pop() {
+                        state = s;
+                        path = p;
+                    }
+                }
+                '!' => {
+                    flush(&mut path, state.radius, &mut strokes);
+                    state.radius *= self.radius_factor;
+                }
+                _ => {}
+            }
+        }
+        flush(&mut path, state.radius, &mut strokes);
+        // close unbalanced branches
+        while let Some((s, mut p)) = stack.pop() {
+            flush(&mut p, s.radius, &mut strokes);
+        }
+        strokes
+    }
+}
+
+/// Methods to create meshes from L-systems.
+pub trait MeshLSystem<T: MeshType3D<Mesh = Self>>: MakeTube<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Interprets the `commands` with a turtle and sweeps a closed tube along each stroke.
+    /// The tubes of different strokes are separate connected components.
+    /// See `TurtleOptions` for the supported symbols.
+    fn lsystem(commands: &str, options: &TurtleOptions<T::S>) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_lsystem(commands, options);
+        mesh
+    }
+
+    /// Like `lsystem`, but inserts the tubes into the existing mesh.
+    /// Returns the ids of the inserted faces.
+    fn insert_lsystem(&mut self, commands: &str, options: &TurtleOptions<T::S>) -> Vec<T::F> {
+        options
+            .interpret::<T::Vec>(commands)
+            .iter()
+            .flat_map(|stroke| {
+                self.insert_tube_from_path(
+                    &stroke.points,
+                    stroke.radius,
+                    options.segments,
+                    options.joint,
+                    true,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_lsystem_expand() {
+        let algae = LSystem::new("A").with_rule('A', "AB").with_rule('B', "A");
+        assert_eq!(algae.expand(0), "A");
+        assert_eq!(algae.expand(4), "ABAABABA");
+    }
+
+    #[test]
+    fn test_turtle() {
+        let options = TurtleOptions::<f64> {
+            angle: std::f64::consts::FRAC_PI_2,
+            ..Default::default()
+        };
+        // a square, drawn as a single stroke
+        let strokes = options.interpret::<Vec3<f64>>("F+F+F+F");
+        assert_eq!(strokes.len(), 1);
+        assert_eq!(strokes[0].points.len(), 5);
+        assert!(strokes[0].points[4].is_about(&Vec3::zeros(), 1e-12));
+        // the first turn is to the left
+        assert!(strokes[0].points[2].is_about(&Vec3::new(-1.0, 1.0, 0.0), 1e-12));
+
+        // a branch with a thinner radius and a gap
+        let strokes = options.interpret::<Vec3<f64>>("FF[!&F]fF");
+        assert_eq!(strokes.len(), 3);
+        assert!(strokes[0].radius.is_about(0.07, 1e-6));
+        assert!(strokes[0].points[1].is_about(&Vec3::new(0.0, 2.0, -1.0), 1e-12));
+        assert_eq!(strokes[1].points.len(), 3);
+        assert!(strokes[1].radius.is_about(0.1, 1e-6));
+        assert!(strokes[2].points[0].is_about(&Vec3::new(0.0, 3.0, 0.0), 1e-12));
+    }
+
+    #[test]
+    fn test_lsystem_mesh() {
+        let plant = LSystem::new("X")
+            .with_rule('X', "F[+X][-X]FX")
+            .with_rule('F', "FF")
+            .expand(3);
+        let options = TurtleOptions::<f64>::default();
+        let strokes = options.interpret::<Vec3<f64>>(&plant);
+        let mesh = Mesh3d64::lsystem(&plant, &options);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.islands().len(), strokes.len());
+    }
+}
@@ -0,0 +1,196 @@
+use super::{padded_bounds, MeshImplicit};
+use crate::{
+    math::{Scalar, Vector3D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshType3D},
+};
+
+/// An element of the skeleton of an organic blob.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetaElement<Vec: Vector3D> {
+    /// A sphere with the given center and radius.
+    Sphere {
+        /// The center of the sphere
+        center: Vec,
+        /// The radius of the sphere
+        radius: Vec::S,
+    },
+
+    /// A tapered capsule around the segment from `a` to `b` whose radius
+    /// changes linearly from `radius_a` to `radius_b`, e.g., a limb or a branch.
+    Capsule {
+        /// The first end of the segment
+        a: Vec,
+        /// The second end of the segment
+        b: Vec,
+        /// The radius at `a`
+        radius_a: Vec::S,
+        /// The radius at `b`
+        radius_b: Vec::S,
+    },
+}
+
+impl<Vec: Vector3D> MetaElement<Vec> {
+    /// Creates a sphere.
+    pub fn sphere(center: Vec, radius: Vec::S) -> Self {
+        Self::Sphere { center, radius }
+    }
+
+    /// Creates a capsule with the same radius at both ends.
+    pub fn capsule(a: Vec, b: Vec, radius: Vec::S) -> Self {
+        Self::tapered_capsule(a, b, radius, radius)
+    }
+
+    /// Creates a capsule whose radius changes linearly from `radius_a` to `radius_b`.
+    pub fn tapered_capsule(a: Vec, b: Vec, radius_a: Vec::S, radius_b: Vec::S) -> Self {
+        Self::Capsule {
+            a,
+            b,
+            radius_a,
+            radius_b,
+        }
+    }
+
+    /// Returns the (approximate) signed distance from `p` to the element.
+    /// The distance is exact for spheres and capsules with constant radius.
+    pub fn distance(&self, p: Vec) -> Vec::S {
+        match *self {
+            Self::Sphere { center, radius } => p.distance(&center) - radius,
+            Self::Capsule {
+                a,
+                b,
+                radius_a,
+                radius_b,
+            } => {
+                let ab = b - a;
+                let len2 = ab.length_squared();
+                let t = if len2 <= Vec::S::EPS {
+                    Vec::S::ZERO
+                } else {
+                    ((p - a).dot(&ab) / len2).clamp(Vec::S::ZERO, Vec::S::ONE)
+                };
+                p.distance(&(a + ab * t)) - (radius_a + (radius_b - radius_a) * t)
+            }
+        }
+    }
+
+    /// Returns the ends of the element and their radii.
+    fn ends(&self) -> [(Vec, Vec::S); 2] {
+        match *self {
+            Self::Sphere { center, radius } => [(center, radius); 2],
+            Self::Capsule {
+                a,
+                b,
+                radius_a,
+                radius_b,
+            } => [(a, radius_a), (b, radius_b)],
+        }
+    }
+}
+
+/// Smooth minimum of `a` and `b` that blends them within the distance `k`
+/// (see Inigo Quilez, "smooth minimum", quadratic polynomial).
+fn smooth_min<S: Scalar>(a: S, b: S, k: S) -> S {
+    if k <= S::ZERO {
+        return a.min(b);
+    }
+    let h = (k - (a - b).abs()).max(S::ZERO) / k;
+    a.min(b) - h * h * k * S::from(0.25)
+}
+
+/// Methods to mesh organic blobs given by a skeleton of spheres and capsules.
+pub trait MeshMetaballs<T: MeshType3D<Mesh = Self>>: MeshImplicit<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Returns the signed distance field of the smooth union of the `elements`,
+    /// where the elements are blended with fillets of size about `blend`.
+    fn metaball_field(elements: &[MetaElement<T::Vec>], blend: T::S, p: T::Vec) -> T::S {
+        elements
+            .iter()
+            .map(|e| e.distance(p))
+            .reduce(|a, b| smooth_min(a, b, blend))
+            .unwrap_or(T::S::INFINITY)
+    }
+
+    /// Meshes the smooth union of the skeleton `elements` with marching cubes, e.g.,
+    /// to generate quick organic shapes like animals or plants from spheres and capsules.
+    ///
+    /// Away from the joints, the surface keeps the radii of the elements; where they meet,
+    /// they are blended smoothly within the distance `blend`. Overlapping or touching
+    /// elements give a single closed manifold mesh with faces pointing outwards.
+    ///
+    /// The longest side of the bounding box is divided into about `resolution` cells,
+    /// so thin elements need a high resolution to be resolved.
+    fn metaballs(elements: &[MetaElement<T::Vec>], blend: T::S, resolution: usize) -> Self {
+        assert!(!elements.is_empty(), "need at least one element");
+        assert!(resolution >= 1);
+        let margin = elements
+            .iter()
+            .flat_map(|e| e.ends())
+            .fold(T::S::ZERO, |m, (_, r)| m.max(r))
+            + blend.max(T::S::ZERO);
+        let (min, max) = padded_bounds::<T>(
+            elements.iter().flat_map(|e| e.ends()).map(|(p, _)| p),
+            margin,
+            resolution,
+        );
+        Self::marching_cubes(
+            |p| Self::metaball_field(elements, blend, p),
+            min,
+            max,
+            resolution + 4,
+        )
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_metaballs() {
+        // a single sphere
+        let ball = Mesh3d64::metaballs(&[MetaElement::sphere(Vec3::zeros(), 1.0)], 0.0, 12);
+        assert!(ball.check().is_ok());
+        assert!(!ball.is_open());
+        for v in ball.vertices() {
+            assert!((v.pos().norm() - 1.0).abs() < 0.05);
+        }
+
+        // a simple animal: a body, a head, and four tapered legs
+        let body = MetaElement::capsule(Vec3::new(-1.0, 1.0, 0.0), Vec3::new(1.0, 1.0, 0.0), 0.5);
+        let head = MetaElement::sphere(Vec3::new(1.6, 1.6, 0.0), 0.4);
+        let legs = [(-0.8, -0.3), (-0.8, 0.3), (0.8, -0.3), (0.8, 0.3)].map(|(x, z)| {
+            MetaElement::tapered_capsule(Vec3::new(x, 1.0, z), Vec3::new(x, 0.0, z), 0.2, 0.1)
+        });
+        let elements = [vec![body, head], legs.to_vec()].concat();
+        let animal = Mesh3d64::metaballs(&elements, 0.2, 32);
+        assert!(animal.check().is_ok());
+        assert!(!animal.is_open());
+        let euler = animal.num_vertices() + animal.num_faces() - animal.num_edges() / 2;
+        assert_eq!(euler, 2);
+        for v in animal.vertices() {
+            assert!(Mesh3d64::metaball_field(&elements, 0.2, v.pos()).abs() < 0.05);
+        }
+        for f in animal.faces() {
+            // the faces point along the gradient of the field
+            let p = f.centroid(&animal);
+            let field = |o: Vec3<f64>| Mesh3d64::metaball_field(&elements, 0.2, p + o);
+            let h = 1e-4;
+            let gradient = Vec3::new(
+                field(Vec3::new(h, 0.0, 0.0)) - field(Vec3::new(-h, 0.0, 0.0)),
+                field(Vec3::new(0.0, h, 0.0)) - field(Vec3::new(0.0, -h, 0.0)),
+                field(Vec3::new(0.0, 0.0, h)) - field(Vec3::new(0.0, 0.0, -h)),
+            );
+            assert!(f.normal(&animal).dot(&gradient) > 0.0);
+        }
+
+        // the blend grows the joints
+        let a = MetaElement::sphere(Vec3::new(-0.6, 0.0, 0.0), 0.5);
+        let b = MetaElement::sphere(Vec3::new(0.6, 0.0, 0.0), 0.5);
+        assert!(Mesh3d64::metaball_field(&[a, b], 0.0, Vec3::zeros()) > 0.0);
+        assert!(Mesh3d64::metaball_field(&[a, b], 0.5, Vec3::zeros()) < 0.0);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    math::{HasPosition, Scalar, TransformTrait, Transformable, Vector},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, FaceBasics, FacePayload, HalfEdgeMesh, MeshType3D,
+        MeshTypeHalfEdge, SoupBuilder, VertexBasics,
+    },
+};
+use std::collections::HashMap;
+
+/// Returns the reflection across the plane through `point` with the given `normal`.
+pub fn plane_reflection<T: MeshType3D>(point: T::Vec, normal: T::Vec) -> T::Trans {
+    // rotate the normal onto the x-axis, flip the x-coordinate, and rotate back
+    let x = T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO);
+    let n = normal.normalize();
+    let axis = if n.dot(&x) < T::S::ZERO { -x } else { x };
+    T::Trans::from_translation(-point)
+        .chain(&T::Trans::from_rotation_arc(n, axis))
+        .chain(&T::Trans::from_scale(T::Vec::from_xyz(
+            -T::S::ONE,
+            T::S::ONE,
+            T::S::ONE,
+        )))
+        .chain(&T::Trans::from_rotation_arc(axis, n))
+        .chain(&T::Trans::from_translation(point))
+}
+
+/// Methods to mirror meshes, e.g., to generate only one half of a symmetric model.
+pub trait MeshMirror<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    HalfEdgeMesh<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+    /// Returns the mesh together with its mirror image across the plane through `point`
+    /// with the given `normal`. The faces of the copy are flipped so both halves are
+    /// oriented the same way.
+    ///
+    /// Vertices closer than `merge_tolerance` to the plane are moved onto it and shared
+    /// by both halves, so a half that is open along the plane gives a closed mesh.
+    /// Faces lying in the plane are removed since they would end up inside. The mesh
+    /// should lie on one side of the plane. The vertex payloads of the copy are reflected,
+    /// and the tags of the faces are kept. Other edge and face payloads are reset.
+    fn mirror(&self, point: T::Vec, normal: T::Vec, merge_tolerance: T::S) -> Self {
+        let n = normal.normalize();
+        let reflection = plane_reflection::<T>(point, n);
+        let mut builder = SoupBuilder::<3, T>::new();
+        // the indices of the original and the mirrored copy of each vertex
+        let mut indices = HashMap::new();
+        let mut on_plane = HashMap::new();
+        for v in self.vertices() {
+            let d = (v.pos() - point).dot(&n);
+            let mut vp = v.payload().clone();
+            let flat = d.abs() <= merge_tolerance;
+            if flat {
+                vp.set_pos(v.pos() - n * d);
+            }
+            let i = builder.add_vertex(vp.clone());
+            let j = if flat {
+                i
+            } else {
+                builder.add_vertex(vp.transformed(&reflection))
+            };
+            indices.insert(v.id(), (i, j));
+            on_plane.insert(v.id(), flat);
+        }
+
+        for f in self.faces() {
+            let vs: Vec<T::V> = f.vertex_ids(self).collect();
+            if vs.iter().all(|v| on_plane[v]) {
+                continue;
+            }
+            let original = vs.iter().map(|v| indices[v].0);
+            let mirrored = vs.iter().rev().map(|v| indices[v].1);
+            if let Some(tag) = f.payload().tag() {
+                builder.add_tagged_face(original, tag);
+                builder.add_tagged_face(mirrored, tag);
+            } else {
+                builder.add_face(original);
+                builder.add_face(mirrored);
+            }
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_mirror() {
+        // a unit cube touching the plane x = 0 becomes a 2x1x1 box
+        let mut half = Mesh3d64::cube(1.0);
+        half.translate(&Vec3::new(0.5, 0.0, 0.0));
+        // a tiny error is snapped onto the plane
+        let v = half
+            .vertices()
+            .find(|v| v.pos().x.abs() < 1e-9)
+            .unwrap()
+            .id();
+        let p = half.vertex(v).pos();
+        half.vertex_mut(v)
+            .payload_mut()
+            .set_pos(p + Vec3::new(1e-6, 0.0, 0.0));
+        let mesh = half.mirror(Vec3::zeros(), Vec3::new(-2.0, 0.0, 0.0), 1e-4);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.num_vertices(), 12);
+        assert_eq!(mesh.num_faces(), 10);
+        assert!(mesh.report().volume.unwrap().is_about(2.0, 1e-9));
+        assert!(mesh
+            .vertices()
+            .all(|v| v.pos().x.abs().is_about(1.0, 1e-9) || v.pos().x.abs() < 1e-12));
+
+        // a bowl that is open along the plane becomes a closed sphere
+        let profile: Vec<Vec2<f64>> = (0..=8)
+            .map(|i| {
+                let a = -PI / 2.0 + PI / 2.0 * i as f64 / 8.0;
+                Vec2::new(a.cos(), a.sin())
+            })
+            .collect();
+        let mut bowl = Mesh3d64::default();
+        bowl.revolve(&profile, Vec3::new(0.0, 1.0, 0.0), 2.0 * PI, 16);
+        assert!(bowl.is_open());
+        let sphere = bowl.mirror(Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0), 1e-9);
+        assert!(sphere.check().is_ok());
+        assert!(!sphere.is_open());
+        assert_eq!(sphere.num_faces(), 2 * bowl.num_faces());
+        let volume = sphere.report().volume.unwrap();
+        assert!(volume > 0.9 * 4.0 / 3.0 * PI && volume < 4.0 / 3.0 * PI);
+
+        // the halves stay apart if the plane is away from the rim
+        let apart = bowl.mirror(Vec3::new(0.0, 0.5, 0.0), Vec3::new(0.0, 1.0, 0.0), 1e-9);
+        assert!(apart.check().is_ok());
+        assert_eq!(apart.report().islands, 2);
+        assert_eq!(apart.num_vertices(), 2 * bowl.num_vertices());
+    }
+}
@@ -1,9 +1,77 @@
 //! This module contains the builder functions for the mesh representation.
 
+mod array;
+mod bevel;
+mod decimate;
+mod displace;
+mod dissolve;
+#[cfg(feature = "fonts")]
+mod engrave;
 mod extrude;
+mod fillet;
+mod fit;
+mod grammar;
+mod holes;
+mod implicit;
+mod internal;
+mod ivy;
+mod knife;
 mod loft;
+mod lsystem;
+mod metaballs;
+mod mirror;
+mod nesting;
+mod offset;
+mod paint;
+mod pipeline;
+mod planarize;
+mod project;
+mod replicate;
+mod seams;
+mod silhouette;
+mod slice;
+mod smooth;
+mod split;
+mod streamlines;
 mod subdivision;
+mod submesh;
+mod thicken;
+mod triangulate;
 
+pub use array::*;
+pub use bevel::*;
+pub use decimate::*;
+pub use displace::*;
+pub use dissolve::*;
+#[cfg(feature = "fonts")]
+pub use engrave::*;
 pub use extrude::*;
+pub use fillet::*;
+pub use fit::*;
+pub use grammar::*;
+pub use holes::*;
+pub use implicit::*;
+pub use internal::*;
+pub use ivy::*;
+pub use knife::*;
 pub use loft::*;
+pub use lsystem::*;
+pub use metaballs::*;
+pub use mirror::*;
+pub use nesting::*;
+pub use offset::*;
+pub use paint::*;
+pub use pipeline::*;
+pub use planarize::*;
+pub use project::*;
+pub use replicate::*;
+pub use seams::*;
+pub use silhouette::*;
+pub use slice::*;
+pub use smooth::*;
+pub use split::*;
+pub use streamlines::*;
 pub use subdivision::*;
+pub use submesh::*;
+pub use thicken::*;
+pub use triangulate::*;
@@ -0,0 +1,549 @@
+use crate::{
+    math::{Scalar, TransformTrait, Transformable, Vector},
+    mesh::{plane_rotation, EuclideanMeshType, FaceBasics, HalfEdge, MeshIslands, VertexBasics},
+};
+use std::collections::HashSet;
+
+/// The parameters of the nesting, see `MeshNesting::nest`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NestingOptions<S: Scalar> {
+    /// The width of a sheet.
+    pub width: S,
+
+    /// The height of a sheet.
+    pub height: S,
+
+    /// The minimal distance between two parts, e.g., the kerf of a laser cutter.
+    pub spacing: S,
+
+    /// The minimal distance between the parts and the edges of the sheet.
+    pub margin: S,
+
+    /// The number of rotation steps in a full turn. 1 disables rotations,
+    /// 4 allows quarter turns.
+    pub rotations: usize,
+
+    /// The size of the grid cells approximating the parts. Parts are placed on this grid
+    /// and never closer than `spacing`, so smaller cells waste less material but are slower.
+    pub resolution: S,
+}
+
+impl<S: Scalar> NestingOptions<S> {
+    /// Creates options for sheets of the given size without spacing or margin, allowing
+    /// quarter turns, and with a resolution of 1/128 of the larger side of the sheet.
+    pub fn new(width: S, height: S) -> Self {
+        Self {
+            width,
+            height,
+            spacing: S::ZERO,
+            margin: S::ZERO,
+            rotations: 4,
+            resolution: width.max(height) / S::from_usize(128),
+        }
+    }
+}
+
+/// Where an island is placed by `MeshNesting::nest`.
+#[derive(Debug, Clone)]
+pub struct IslandPlacement<V, S, Trans> {
+    /// The id of the island, see `MeshIslands`.
+    pub island: V,
+
+    /// The index of the sheet the island is placed on.
+    pub sheet: usize,
+
+    /// The angle the island is rotated by (in radians).
+    pub angle: S,
+
+    /// The transformation from the original position of the island to its position on the
+    /// sheet, whose lower left corner is at the origin.
+    pub transform: Trans,
+}
+
+/// The result of `MeshNesting::nest`.
+#[derive(Debug, Clone)]
+pub struct NestingLayout<V, S, Trans> {
+    /// The width of the sheets.
+    pub width: S,
+
+    /// The height of the sheets.
+    pub height: S,
+
+    /// The number of sheets needed for the placed islands.
+    pub sheets: usize,
+
+    /// The placed islands ordered by decreasing area.
+    pub placements: Vec<IslandPlacement<V, S, Trans>>,
+
+    /// The islands that don't fit on a sheet, even on their own.
+    pub unplaced: Vec<V>,
+
+    /// The total area of the placed islands divided by the total area of the sheets.
+    pub utilization: S,
+}
+
+impl<V, S: Scalar, Trans> NestingLayout<V, S, Trans> {
+    /// Returns the placements on the given sheet.
+    pub fn sheet(&self, sheet: usize) -> impl Iterator<Item = &IslandPlacement<V, S, Trans>> {
+        self.placements.iter().filter(move |p| p.sheet == sheet)
+    }
+}
+
+/// The faces of an island as polygons.
+struct Island {
+    polygons: Vec<Vec<[f64; 2]>>,
+    area: f64,
+}
+
+impl Island {
+    /// Returns whether the island intersects the open square from `lo` to `hi`.
+    fn intersects(&self, lo: [f64; 2], hi: [f64; 2], rotated: &[Vec<[f64; 2]>]) -> bool {
+        let center = [(lo[0] + hi[0]) / 2.0, (lo[1] + hi[1]) / 2.0];
+        rotated.iter().any(|polygon| {
+            let mut inside = false;
+            for i in 0..polygon.len() {
+                let a = polygon[i];
+                let b = polygon[(i + 1) % polygon.len()];
+                if (a[1] > center[1]) != (b[1] > center[1])
+                    && center[0] < a[0] + (center[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0])
+                {
+                    inside = !inside;
+                }
+                // clip the edge against the open square
+                let (mut t0, mut t1) = (0.0f64, 1.0f64);
+                for k in 0..2 {
+                    let d = b[k] - a[k];
+                    for (p, q) in [(-d, a[k] - lo[k]), (d, hi[k] - a[k])] {
+                        if p == 0.0 {
+                            if q <= 0.0 {
+                                t1 = -1.0;
+                            }
+                        } else if p < 0.0 {
+                            t0 = t0.max(q / p);
+                        } else {
+                            t1 = t1.min(q / p);
+                        }
+                    }
+                }
+                if t0 < t1 {
+                    return true;
+                }
+            }
+            inside
+        })
+    }
+
+    /// Rasterizes the island rotated by `angle`. Returns the lower left corner of the
+    /// bounding box of the rotated island, the cells overlapping the island, and the cells
+    /// closer than `spacing` to them. The cells are relative to the corner.
+    fn rasterize(
+        &self,
+        angle: f64,
+        res: f64,
+        spacing: f64,
+    ) -> ([f64; 2], Vec<(isize, isize)>, Vec<(isize, isize)>) {
+        let (s, c) = angle.sin_cos();
+        let rotated: Vec<Vec<[f64; 2]>> = self
+            .polygons
+            .iter()
+            .map(|p| {
+                p.iter()
+                    .map(|q| [q[0] * c - q[1] * s, q[0] * s + q[1] * c])
+                    .collect()
+            })
+            .collect();
+        let mut min = [f64::INFINITY; 2];
+        let mut max = [f64::NEG_INFINITY; 2];
+        for q in rotated.iter().flatten() {
+            for k in 0..2 {
+                min[k] = min[k].min(q[k]);
+                max[k] = max[k].max(q[k]);
+            }
+        }
+        let n = [
+            ((max[0] - min[0]) / res).ceil() as isize,
+            ((max[1] - min[1]) / res).ceil() as isize,
+        ];
+        let mut core = Vec::new();
+        for j in -1..=n[1] {
+            for i in -1..=n[0] {
+                let lo = [min[0] + i as f64 * res, min[1] + j as f64 * res];
+                if self.intersects(lo, [lo[0] + res, lo[1] + res], &rotated) {
+                    core.push((i, j));
+                }
+            }
+        }
+
+        // the cells whose gap to a cell of the island is smaller than the spacing
+        let r = (spacing / res).ceil() as isize + 1;
+        let mut halo = HashSet::new();
+        for (i, j) in &core {
+            for dj in -r..=r {
+                for di in -r..=r {
+                    let gap = |d: isize| (d.abs() - 1).max(0) as f64;
+                    if (di == 0 && dj == 0)
+                        || (gap(di).powi(2) + gap(dj).powi(2)).sqrt() * res < spacing
+                    {
+                        halo.insert((i + di, j + dj));
+                    }
+                }
+            }
+        }
+        (min, core, halo.into_iter().collect())
+    }
+}
+
+/// A sheet as a grid of cells that are blocked by the halos of the placed islands.
+struct Sheet {
+    size: [isize; 2],
+    blocked: Vec<bool>,
+}
+
+impl Sheet {
+    fn new(size: [isize; 2]) -> Self {
+        Self {
+            size,
+            blocked: vec![false; (size[0] * size[1]).max(0) as usize],
+        }
+    }
+
+    fn index(&self, x: isize, y: isize) -> Option<usize> {
+        (x >= 0 && y >= 0 && x < self.size[0] && y < self.size[1])
+            .then(|| (y * self.size[0] + x) as usize)
+    }
+
+    fn fits(&self, x: isize, y: isize, cells: &[(isize, isize)]) -> bool {
+        cells.iter().all(|(i, j)| {
+            self.index(x + i, y + j)
+                .map(|k| !self.blocked[k])
+                .unwrap_or(false)
+        })
+    }
+
+    /// Returns the lowest, then leftmost position where the cells fit.
+    fn find(&self, cells: &[(isize, isize)]) -> Option<(isize, isize)> {
+        let (lo, hi) = cells.iter().fold(
+            ((isize::MAX, isize::MAX), (isize::MIN, isize::MIN)),
+            |(lo, hi), (i, j)| ((lo.0.min(*i), lo.1.min(*j)), (hi.0.max(*i), hi.1.max(*j))),
+        );
+        for y in -lo.1..self.size[1] - hi.1 {
+            for x in -lo.0..self.size[0] - hi.0 {
+                if self.fits(x, y, cells) {
+                    return Some((x, y));
+                }
+            }
+        }
+        None
+    }
+
+    fn block(&mut self, x: isize, y: isize, cells: &[(isize, isize)]) {
+        for (i, j) in cells {
+            if let Some(k) = self.index(x + i, y + j) {
+                self.blocked[k] = true;
+            }
+        }
+    }
+}
+
+/// Methods to arrange the islands of a 2D mesh on rectangular sheets, e.g., to
+/// lay out the parts of a papercraft model or of a laser-cut design.
+pub trait MeshNesting<T: EuclideanMeshType<2, Mesh = Self>>: MeshIslands<2, T>
+where
+    T::Edge: HalfEdge<T>,
+{
+    /// Places the islands of the mesh on as few sheets as possible without overlaps.
+    ///
+    /// The islands are approximated by grid cells of the size `options.resolution` and
+    /// placed greedily ordered by decreasing area. Each island is put on the first sheet
+    /// where it fits, as low and then as far left as possible, trying all rotation steps.
+    /// Since the faces are filled cell by cell, small islands can be placed in the holes of
+    /// larger ones. Curved edges are approximated by straight lines, so flatten them first
+    /// if they bulge out a lot. Use `apply_nesting` to move the islands to their places.
+    fn nest(&self, options: &NestingOptions<T::S>) -> NestingLayout<T::V, T::S, T::Trans> {
+        let res = options.resolution.to_f64();
+        assert!(res > 0.0, "The resolution must be positive");
+        let margin = options.margin.to_f64();
+        let spacing = options.spacing.to_f64();
+        let size = [
+            ((options.width.to_f64() - 2.0 * margin) / res).floor() as isize,
+            ((options.height.to_f64() - 2.0 * margin) / res).floor() as isize,
+        ];
+
+        let mut islands: Vec<(T::V, Island)> = self
+            .island_ids()
+            .into_iter()
+            .map(|island| {
+                let mut faces = HashSet::new();
+                for v in self.island_vertices(island) {
+                    for e in self.vertex(v).edges_out(self) {
+                        if !e.is_boundary_self() {
+                            faces.insert(e.face_id());
+                        }
+                    }
+                }
+                let polygons: Vec<Vec<[f64; 2]>> = faces
+                    .into_iter()
+                    .map(|f| {
+                        self.face(f)
+                            .vertices(self)
+                            .map(|v| [v.pos().x().to_f64(), v.pos().y().to_f64()])
+                            .collect()
+                    })
+                    .collect();
+                let area = polygons
+                    .iter()
+                    .map(|p| {
+                        (0..p.len())
+                            .map(|i| {
+                                let (a, b) = (p[i], p[(i + 1) % p.len()]);
+                                a[0] * b[1] - a[1] * b[0]
+                            })
+                            .sum::<f64>()
+                            .abs()
+                            / 2.0
+                    })
+                    .sum();
+                (island, Island { polygons, area })
+            })
+            .collect();
+        islands.sort_by(|a, b| b.1.area.total_cmp(&a.1.area).then(a.0.cmp(&b.0)));
+
+        let steps = options.rotations.max(1);
+        let mut sheets: Vec<Sheet> = Vec::new();
+        let mut placements = Vec::new();
+        let mut unplaced = Vec::new();
+        let mut placed_area = 0.0;
+        for (id, island) in islands {
+            let rasters: Vec<_> = (0..steps)
+                .map(|k| {
+                    let angle = std::f64::consts::TAU * k as f64 / steps as f64;
+                    (angle, island.rasterize(angle, res, spacing))
+                })
+                .collect();
+            // the best position on the first sheet where the island fits
+            let place = |sheet: &Sheet| {
+                rasters
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(k, (_, (_, core_cells, _)))| {
+                        let (x, y) = sheet.find(core_cells)?;
+                        let top = core_cells.iter().map(|c| c.1).max().unwrap_or(0) + y;
+                        Some(((top, x), k, x, y))
+                    })
+                    .min_by_key(|c| c.0)
+            };
+            let mut found = sheets
+                .iter()
+                .enumerate()
+                .find_map(|(i, sheet)| place(sheet).map(|c| (i, c)));
+            if found.is_none() {
+                let sheet = Sheet::new(size);
+                found = place(&sheet).map(|c| (sheets.len(), c));
+                if found.is_some() {
+                    sheets.push(sheet);
+                }
+            }
+            let Some((i, (_, k, x, y))) = found else {
+                unplaced.push(id);
+                continue;
+            };
+
+            let (angle, (min, _, halo_cells)) = &rasters[k];
+            sheets[i].block(x, y, halo_cells);
+            placed_area += island.area;
+            let angle = T::S::from_f64(*angle);
+            let rotation = plane_rotation::<2, T>(
+                T::Vec::from_xy(T::S::ONE, T::S::ZERO),
+                T::Vec::from_xy(T::S::ZERO, T::S::ONE),
+                angle,
+            );
+            let offset = T::Vec::from_xy(
+                T::S::from_f64(margin + x as f64 * res - min[0]),
+                T::S::from_f64(margin + y as f64 * res - min[1]),
+            );
+            placements.push(IslandPlacement {
+                island: id,
+                sheet: i,
+                angle,
+                transform: rotation.chain(&T::Trans::from_translation(offset)),
+            });
+        }
+
+        let sheet_area = (options.width * options.height).to_f64() * sheets.len() as f64;
+        NestingLayout {
+            width: options.width,
+            height: options.height,
+            sheets: sheets.len(),
+            placements,
+            unplaced,
+            utilization: T::S::from_f64(if sheets.is_empty() {
+                0.0
+            } else {
+                placed_area / sheet_area
+            }),
+        }
+    }
+
+    /// Moves the islands to their places in the `layout`. The `k`-th sheet is
+    /// shifted by `k` times the width of a sheet plus `gap` along the x-axis.
+    /// Unplaced islands are left where they are.
+    fn apply_nesting(
+        &mut self,
+        layout: &NestingLayout<T::V, T::S, T::Trans>,
+        gap: T::S,
+    ) -> &mut Self
+    where
+        T::VP: Transformable<2, Rot = T::Rot, Vec = T::Vec, Trans = T::Trans, S = T::S>,
+        T::EP: Transformable<2, Rot = T::Rot, Vec = T::Vec, Trans = T::Trans, S = T::S>,
+        T::FP: Transformable<2, Rot = T::Rot, Vec = T::Vec, Trans = T::Trans, S = T::S>,
+    {
+        for p in &layout.placements {
+            let shift =
+                T::Vec::from_xy((layout.width + gap) * T::S::from_usize(p.sheet), T::S::ZERO);
+            let t = p.transform.chain(&T::Trans::from_translation(shift));
+            self.transform_island(p.island, &t);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    /// Returns a mesh with the given axis-aligned rectangles as islands.
+    fn rectangles(sizes: &[(f64, f64)]) -> Mesh2d64 {
+        let mut mesh = Mesh2d64::default();
+        for (i, (w, h)) in sizes.iter().enumerate() {
+            let (x, y) = (10.0 * i as f64, 5.0);
+            mesh.insert_polygon(
+                [(x, y), (x + w, y), (x + w, y + h), (x, y + h)]
+                    .iter()
+                    .map(|(x, y)| VertexPayloadPNU::from_pos(Vec2::new(*x, *y))),
+            );
+        }
+        mesh
+    }
+
+    /// Returns the bounding box of the island.
+    fn bounds(mesh: &Mesh2d64, island: usize) -> (Vec2<f64>, Vec2<f64>) {
+        let ps: Vec<Vec2<f64>> = mesh
+            .island_vertices(island)
+            .iter()
+            .map(|v| mesh.vertex(*v).pos())
+            .collect();
+        let min = ps.iter().fold(ps[0], |a, b| a.inf(b));
+        let max = ps.iter().fold(ps[0], |a, b| a.sup(b));
+        (min, max)
+    }
+
+    #[test]
+    fn test_nesting() {
+        // eight 2x1 tiles fill a 4x4 sheet if they may be rotated
+        let mut mesh = rectangles(&[(2.0, 1.0); 8]);
+        let mut options = NestingOptions::new(4.0, 4.0);
+        options.resolution = 0.25;
+        let layout = mesh.nest(&options);
+        assert_eq!(layout.sheets, 1);
+        assert!(layout.unplaced.is_empty());
+        assert!(layout.utilization.is_about(1.0, 1e-9));
+
+        mesh.apply_nesting(&layout, 1.0);
+        assert!(mesh.check().is_ok());
+        let boxes: Vec<_> = mesh
+            .island_ids()
+            .iter()
+            .map(|i| bounds(&mesh, *i))
+            .collect();
+        for (i, (min, max)) in boxes.iter().enumerate() {
+            assert!(min.x >= -1e-9 && min.y >= -1e-9);
+            assert!(max.x <= 4.0 + 1e-9 && max.y <= 4.0 + 1e-9);
+            // the tiles don't overlap
+            for (min2, max2) in &boxes[..i] {
+                let overlap = (max.x.min(max2.x) - min.x.max(min2.x))
+                    .min(max.y.min(max2.y) - min.y.max(min2.y));
+                assert!(overlap <= 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nesting_sheets() {
+        // without rotations and with spacing, the tiles need more sheets
+        let mesh = rectangles(&[(2.0, 1.0); 8]);
+        let mut options = NestingOptions::new(4.0, 4.0);
+        options.resolution = 0.25;
+        options.rotations = 1;
+        options.spacing = 0.5;
+        options.margin = 0.25;
+        let layout = mesh.nest(&options);
+        assert!(layout.unplaced.is_empty());
+        assert!(layout.sheets > 1);
+        assert!(layout.utilization < 1.0);
+        assert_eq!(
+            (0..layout.sheets)
+                .map(|s| layout.sheet(s).count())
+                .sum::<usize>(),
+            8
+        );
+        assert!(layout.placements.iter().all(|p| p.angle == 0.0));
+
+        let mut nested = mesh.clone();
+        nested.apply_nesting(&layout, 1.0);
+        for p in &layout.placements {
+            let (min, max) = bounds(&nested, p.island);
+            let x0 = 5.0 * p.sheet as f64;
+            assert!(min.x >= x0 + 0.25 - 1e-9 && min.y >= 0.25 - 1e-9);
+            assert!(max.x <= x0 + 3.75 + 1e-9 && max.y <= 3.75 + 1e-9);
+        }
+        // the spacing between the tiles on the first sheet is respected
+        let first: Vec<_> = layout.sheet(0).map(|p| bounds(&nested, p.island)).collect();
+        for (i, (min, max)) in first.iter().enumerate() {
+            for (min2, max2) in &first[..i] {
+                let gap = (min.x - max2.x)
+                    .max(min2.x - max.x)
+                    .max(min.y - max2.y)
+                    .max(min2.y - max.y);
+                assert!(gap >= 0.5 - 1e-9);
+            }
+        }
+
+        // an island larger than the sheet can't be placed
+        let layout = rectangles(&[(5.0, 1.0), (1.0, 1.0)]).nest(&options);
+        assert_eq!(layout.unplaced, vec![0]);
+        assert_eq!(layout.sheets, 1);
+    }
+
+    #[test]
+    #[cfg(feature = "svg")]
+    fn test_nesting_svg() {
+        use crate::extensions::svg::BackendSVG;
+
+        let mut mesh = Mesh2d64Curved::default();
+        for i in 0..5 {
+            let x = 10.0 * i as f64;
+            mesh.insert_polygon(
+                [(x, 0.0), (x + 3.0, 0.0), (x + 3.0, 2.0), (x, 2.0)]
+                    .iter()
+                    .map(|(x, y)| VertexPayloadPNU::from_pos(Vec2::new(*x, *y))),
+            );
+        }
+        let mut options = NestingOptions::new(6.0, 4.0);
+        options.resolution = 0.5;
+        let layout = mesh.nest(&options);
+        assert_eq!(layout.sheets, 2);
+        let sheets = mesh.export_nesting_svg(&layout);
+        assert_eq!(sheets.len(), 2);
+        assert!(sheets[0].contains("viewBox='0 0 6 4'"));
+        assert_eq!(sheets[0].matches("<path").count(), 4);
+        assert_eq!(sheets[1].matches("<path").count(), 1);
+
+        // the exported sheet can be imported again
+        let sheet: Mesh2d64Curved = BackendSVG::<MeshType2d64PNUCurved>::from_svg(&sheets[0]);
+        assert_eq!(sheet.island_ids().len(), 4);
+        assert!(
+            BackendSVG::<MeshType2d64PNUCurved>::export_svg(&mesh).contains("viewBox='0 0 43 2'")
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    math::{offset_polygon, HasPosition, OffsetJoin, Vector},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, EuclideanMeshType, FaceBasics, MeshTrait,
+        SoupBuilder, VertexBasics,
+    },
+};
+
+/// Methods to grow or shrink the faces of 2d meshes, e.g., for outlined text, borders
+/// around shapes, or insets before extruding them.
+pub trait MeshOffset<T: EuclideanMeshType<2, Mesh = Self>>: MeshTrait<T = T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Returns a new mesh where each face is replaced by its outline moved outwards by
+    /// `distance`, or inwards for negative distances. See `offset_polygon` for details.
+    /// Faces that vanish when shrinking are dropped. Curved edges are treated as straight,
+    /// so they should be flattened first.
+    fn offset_faces(&self, distance: T::S, join: OffsetJoin<T::S>) -> Self {
+        let mut builder = SoupBuilder::<2, T>::new();
+        for f in self.faces() {
+            let ps = f
+                .vertices(self)
+                .map(|v| v.pos().vec2::<T::Vec2>())
+                .collect::<Vec<_>>();
+            builder.add_polygon(
+                offset_polygon(&ps, distance, join)
+                    .iter()
+                    .map(|p| T::VP::from_pos(T::Vec::from_xy(p.x(), p.y()))),
+            );
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_offset_faces() {
+        let mut star = Mesh2d64Curved::regular_star(1.0, 2.0, 5);
+        star.insert_regular_star(0.1, 0.1, 8);
+        let area = |mesh: &Mesh2d64Curved| {
+            mesh.faces()
+                .map(|f| {
+                    let ps = f.vertices(mesh).map(|v| v.pos()).collect::<Vec<_>>();
+                    (0..ps.len())
+                        .map(|i| ps[i].perp_dot(&ps[(i + 1) % ps.len()]) * 0.5)
+                        .sum::<f64>()
+                })
+                .sum::<f64>()
+        };
+
+        let grown = star.offset_faces(0.05, OffsetJoin::Round(32));
+        assert!(grown.check().is_ok());
+        assert_eq!(grown.num_faces(), 2);
+        assert!(area(&grown) > area(&star));
+
+        // the small polygon vanishes
+        let shrunk = star.offset_faces(-0.2, OffsetJoin::default());
+        assert!(shrunk.check().is_ok());
+        assert_eq!(shrunk.num_faces(), 1);
+        assert!(area(&shrunk) < area(&star));
+    }
+}
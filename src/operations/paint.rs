@@ -0,0 +1,278 @@
+use super::MeshStreamlines;
+use crate::{
+    math::{OrderedFloats, Scalar, Vector},
+    mesh::{FaceBasics, MeshType3D, MeshTypeHalfEdge, VertexBasics},
+    util::{Attributes, Element},
+};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+/// The profile of a brush, i.e., how the strength of the brush decreases
+/// from its center to its radius.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Falloff {
+    /// Full strength within the whole radius.
+    Constant,
+
+    /// Decreases linearly to zero.
+    Linear,
+
+    /// Decreases with a smoothstep, i.e., without kinks at the center and the radius.
+    Smooth,
+
+    /// A spherical dome that stays strong for longer and drops steeply at the radius.
+    Sphere,
+
+    /// Decreases quadratically, concentrating the strength at the center.
+    Sharp,
+}
+
+impl Falloff {
+    /// Returns the strength of the brush at the distance `t` from the center,
+    /// given as a fraction of the radius. The strength is zero beyond the radius.
+    pub fn weight(&self, t: f64) -> f64 {
+        if !(0.0..=1.0).contains(&t) {
+            return if t < 0.0 { 1.0 } else { 0.0 };
+        }
+        match self {
+            Falloff::Constant => 1.0,
+            Falloff::Linear => 1.0 - t,
+            Falloff::Smooth => 1.0 - t * t * (3.0 - 2.0 * t),
+            Falloff::Sphere => (1.0 - t * t).sqrt(),
+            Falloff::Sharp => (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// How the distance between the center of a brush and the vertices is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BrushDistance {
+    /// The straight-line distance. Fast, but bleeds through thin walls and across gaps.
+    #[default]
+    Euclidean,
+
+    /// The shortest path along the edges of the mesh starting at the closest point on
+    /// the surface, so the brush only reaches what is connected to the painted surface.
+    Geodesic,
+}
+
+/// Methods to author per-vertex attributes, e.g., weights, colors, or selection masks,
+/// with brushes.
+pub trait MeshPaint<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshStreamlines<T>
+{
+    /// Returns the attribute channels the brushes write to.
+    fn attributes_mut(&mut self) -> &mut Attributes;
+
+    /// Returns the distances of all vertices within `radius` of `center`.
+    fn brush_distances(
+        &self,
+        center: T::Vec,
+        radius: T::S,
+        distance: BrushDistance,
+    ) -> HashMap<T::V, T::S> {
+        match distance {
+            BrushDistance::Euclidean => self
+                .vertices()
+                .filter_map(|v| {
+                    let d = v.pos().distance(&center);
+                    (d <= radius).then(|| (v.id(), d))
+                })
+                .collect(),
+            BrushDistance::Geodesic => {
+                let Some((f, q)) = self.closest_face_point(center) else {
+                    return HashMap::new();
+                };
+                // Dijkstra along the edges starting at the corners of the closest face
+                let offset = q.distance(&center);
+                let mut dist: HashMap<T::V, T::S> = HashMap::new();
+                let mut queue = BinaryHeap::new();
+                for v in self.face(f).vertices(self) {
+                    queue.push(Reverse((
+                        OrderedFloats::new(offset + v.pos().distance(&q)),
+                        v.id(),
+                    )));
+                }
+                while let Some(Reverse((d, v))) = queue.pop() {
+                    let d = d.value();
+                    if d > radius || dist.contains_key(&v) {
+                        continue;
+                    }
+                    dist.insert(v, d);
+                    let p = self.vertex(v).pos();
+                    for w in self.vertex(v).neighbor_ids(self) {
+                        if !dist.contains_key(&w) {
+                            let dw = d + self.vertex(w).pos().distance(&p);
+                            queue.push(Reverse((OrderedFloats::new(dw), w)));
+                        }
+                    }
+                }
+                dist
+            }
+        }
+    }
+
+    /// Blends the vertex attribute channel named `attribute` towards `value` within
+    /// `radius` of `brush_center`, using the Euclidean distance. The weight of `value`
+    /// is given by the `falloff`, see `AttributeChannel::blend`.
+    ///
+    /// Returns the number of vertices within the radius or `None` if there is no
+    /// vertex channel named `attribute` with values of type `A`.
+    fn paint<A: Clone + 'static>(
+        &mut self,
+        attribute: &str,
+        brush_center: T::Vec,
+        radius: T::S,
+        falloff: Falloff,
+        value: A,
+    ) -> Option<usize> {
+        self.paint_ex(
+            attribute,
+            brush_center,
+            radius,
+            falloff,
+            BrushDistance::Euclidean,
+            value,
+        )
+    }
+
+    /// Like `paint`, but measures the distance along the surface, so the brush doesn't
+    /// bleed into nearby parts of the mesh that aren't connected to the painted surface.
+    fn paint_geodesic<A: Clone + 'static>(
+        &mut self,
+        attribute: &str,
+        brush_center: T::Vec,
+        radius: T::S,
+        falloff: Falloff,
+        value: A,
+    ) -> Option<usize> {
+        self.paint_ex(
+            attribute,
+            brush_center,
+            radius,
+            falloff,
+            BrushDistance::Geodesic,
+            value,
+        )
+    }
+
+    /// Like `paint`, but with a configurable way to measure the distance.
+    fn paint_ex<A: Clone + 'static>(
+        &mut self,
+        attribute: &str,
+        brush_center: T::Vec,
+        radius: T::S,
+        falloff: Falloff,
+        distance: BrushDistance,
+        value: A,
+    ) -> Option<usize> {
+        let channel = self.attributes_mut().get_mut::<A>(attribute)?;
+        if channel.element() != Element::Vertex {
+            return None;
+        }
+        let distances = self.brush_distances(brush_center, radius, distance);
+        let channel = self.attributes_mut().get_mut::<A>(attribute)?;
+        for (v, d) in distances.iter() {
+            let t = if radius > T::S::ZERO {
+                (*d / radius).to_f64()
+            } else {
+                0.0
+            };
+            channel.blend(*v, &value, falloff.weight(t));
+        }
+        Some(distances.len())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_paint() {
+        let mut mesh = Mesh3d64::heightmap(2.0, 2.0, 8, |_, _| 0.0);
+        mesh.attributes_mut()
+            .add::<f32>("weight", Element::Vertex)
+            .with_interpolation(|a, b, t| a + (b - a) * t as f32);
+        mesh.attributes_mut().add::<bool>("mask", Element::Vertex);
+        mesh.attributes_mut().add::<f32>("face", Element::Face);
+
+        let center = Vec3::new(0.0, 0.0, 0.0);
+        let n = mesh
+            .paint("weight", center, 0.8, Falloff::Linear, 1.0f32)
+            .unwrap();
+        assert!(n > 1 && n < mesh.num_vertices());
+        let weight = mesh.attributes().get::<f32>("weight").unwrap();
+        for v in mesh.vertices() {
+            let d = v.pos().distance(&center);
+            let expected = (1.0 - d / 0.8).max(0.0) as f32;
+            assert!((weight.get(v.id()) - expected).abs() < 1e-6);
+        }
+
+        // painting the same value again moves further towards it
+        let before = mesh
+            .vertex_ids()
+            .map(|v| *weight.get(v))
+            .collect::<Vec<_>>();
+        mesh.paint("weight", center, 0.8, Falloff::Linear, 1.0f32);
+        let weight = mesh.attributes().get::<f32>("weight").unwrap();
+        for (v, b) in mesh.vertex_ids().zip(before) {
+            assert!(*weight.get(v) >= b && *weight.get(v) <= 1.0);
+        }
+
+        // channels without interpolation are set where the brush is strong enough
+        let within = mesh
+            .vertices()
+            .filter(|v| v.pos().distance(&center) <= 1.0)
+            .count();
+        assert_eq!(
+            mesh.paint("mask", center, 1.0, Falloff::Constant, true),
+            Some(within)
+        );
+        let mask = mesh.attributes().get::<bool>("mask").unwrap();
+        for v in mesh.vertices() {
+            assert_eq!(*mask.get(v.id()), v.pos().distance(&center) <= 1.0);
+        }
+
+        // wrong types, elements, or names aren't painted
+        assert_eq!(
+            mesh.paint("weight", center, 1.0, Falloff::Linear, 1.0f64),
+            None
+        );
+        assert_eq!(
+            mesh.paint("face", center, 1.0, Falloff::Linear, 1.0f32),
+            None
+        );
+        assert_eq!(
+            mesh.paint("missing", center, 1.0, Falloff::Linear, 1.0f32),
+            None
+        );
+    }
+
+    #[test]
+    fn test_paint_geodesic() {
+        // a tall, thin wall separates the two halves of the plane
+        let mut mesh =
+            Mesh3d64::heightmap(2.0, 2.0, 20, |x, _| if x.abs() < 0.05 { 1.0 } else { 0.0 });
+        mesh.attributes_mut().add::<bool>("mask", Element::Vertex);
+        let center = Vec3::new(-0.2, 0.0, 0.0);
+        let euclidean = mesh.brush_distances(center, 0.5, BrushDistance::Euclidean);
+        let geodesic = mesh.brush_distances(center, 0.5, BrushDistance::Geodesic);
+        // the shortest path is never shorter than the straight line
+        for (v, d) in geodesic.iter() {
+            assert!(*d >= euclidean[v] - 1e-9);
+        }
+        // only the euclidean brush reaches the other side of the wall
+        assert!(euclidean.keys().any(|v| mesh.vertex(*v).pos().x > 0.1));
+        assert!(geodesic.keys().all(|v| mesh.vertex(*v).pos().x < 0.1));
+
+        mesh.paint_geodesic("mask", center, 0.5, Falloff::Constant, true);
+        let mask = mesh.attributes().get::<bool>("mask").unwrap();
+        assert!(mesh
+            .vertices()
+            .all(|v| *mask.get(v.id()) == geodesic.contains_key(&v.id())));
+    }
+}
@@ -0,0 +1,463 @@
+use super::{MeshDisplace, MeshExtrude, MeshSmoothing, MeshSubdivision, SmoothingOptions};
+use crate::{
+    math::{Scalar, TransformTrait, Transformable, Vector, Vector3DIteratorExt, VectorIteratorExt},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, FaceBasics, HalfEdge, HalfEdgeSemiBuilder,
+        LinearVertexInterpolator, MeshBasics, MeshType3D, MeshTypeHalfEdge, VertexBasics,
+    },
+};
+use std::{fmt::Display, str::FromStr};
+
+/// A serializable description of a single mesh operation.
+///
+/// Operations on faces act on the current selection of the pipeline, which
+/// initially contains all faces of the mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op<S: Scalar> {
+    /// Selects all faces of the mesh.
+    SelectAll,
+
+    /// Extrudes each selected face along its normal by the given distance.
+    /// Selects the caps of the extrusions.
+    Extrude(S),
+
+    /// Insets each selected face by extruding it with a scaling of the given factor
+    /// around its centroid. Selects the inner faces.
+    Inset(S),
+
+    /// Applies the given number of iterations of loop subdivision.
+    /// Only works for triangle meshes. Selects all faces.
+    Subdivide(usize),
+
+    /// Moves each vertex along its normal by the given distance.
+    Displace(S),
+
+    /// Moves each vertex along its normal by fractal noise with the given
+    /// `amplitude`, `frequency`, and `seed`.
+    #[cfg(feature = "noise")]
+    Noise {
+        /// The maximal displacement.
+        amplitude: S,
+
+        /// The frequency of the first octave.
+        frequency: S,
+
+        /// The seed of the noise.
+        seed: u32,
+    },
+
+    /// Applies uniform laplacian smoothing with the given number of `iterations` and `lambda`.
+    Smooth {
+        /// Number of smoothing iterations.
+        iterations: usize,
+
+        /// How far each vertex moves towards the mean of its neighbors in each iteration.
+        lambda: S,
+    },
+
+    /// Translates the mesh by the given vector.
+    Translate([S; 3]),
+
+    /// Scales the mesh by the given factors along the axes.
+    Scale([S; 3]),
+}
+
+impl<S: Scalar> Display for Op<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Op::SelectAll => write!(f, "select_all"),
+            Op::Extrude(d) => write!(f, "extrude {}", d),
+            Op::Inset(s) => write!(f, "inset {}", s),
+            Op::Subdivide(n) => write!(f, "subdivide {}", n),
+            Op::Displace(d) => write!(f, "displace {}", d),
+            #[cfg(feature = "noise")]
+            Op::Noise {
+                amplitude,
+                frequency,
+                seed,
+            } => write!(f, "noise {} {} {}", amplitude, frequency, seed),
+            Op::Smooth { iterations, lambda } => write!(f, "smooth {} {}", iterations, lambda),
+            Op::Translate([x, y, z]) => write!(f, "translate {} {} {}", x, y, z),
+            Op::Scale([x, y, z]) => write!(f, "scale {} {} {}", x, y, z),
+        }
+    }
+}
+
+impl<S: Scalar> FromStr for Op<S> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let name = words.next().ok_or("missing operation")?;
+        let args = words.collect::<Vec<_>>();
+        let expect = |n: usize| {
+            if args.len() == n {
+                Ok(())
+            } else {
+                Err(format!(
+                    "'{}' expects {} arguments but got {}",
+                    name,
+                    n,
+                    args.len()
+                ))
+            }
+        };
+        let scalar = |i: usize| {
+            S::from_str_radix(args[i], 10).map_err(|_| format!("invalid number '{}'", args[i]))
+        };
+        let int = |i: usize| {
+            args[i]
+                .parse::<usize>()
+                .map_err(|_| format!("invalid integer '{}'", args[i]))
+        };
+        let op = match name {
+            "select_all" => {
+                expect(0)?;
+                Op::SelectAll
+            }
+            "extrude" => {
+                expect(1)?;
+                Op::Extrude(scalar(0)?)
+            }
+            "inset" => {
+                expect(1)?;
+                Op::Inset(scalar(0)?)
+            }
+            "subdivide" => {
+                expect(1)?;
+                Op::Subdivide(int(0)?)
+            }
+            "displace" => {
+                expect(1)?;
+                Op::Displace(scalar(0)?)
+            }
+            #[cfg(feature = "noise")]
+            "noise" => {
+                expect(3)?;
+                Op::Noise {
+                    amplitude: scalar(0)?,
+                    frequency: scalar(1)?,
+                    seed: args[2]
+                        .parse::<u32>()
+                        .map_err(|_| format!("invalid seed '{}'", args[2]))?,
+                }
+            }
+            "smooth" => {
+                expect(2)?;
+                Op::Smooth {
+                    iterations: int(0)?,
+                    lambda: scalar(1)?,
+                }
+            }
+            "translate" => {
+                expect(3)?;
+                Op::Translate([scalar(0)?, scalar(1)?, scalar(2)?])
+            }
+            "scale" => {
+                expect(3)?;
+                Op::Scale([scalar(0)?, scalar(1)?, scalar(2)?])
+            }
+            _ => return Err(format!("unknown operation '{}'", name)),
+        };
+        Ok(op)
+    }
+}
+
+/// An error while parsing an `OpPipeline`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePipelineError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl Display for ParsePipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParsePipelineError {}
+
+/// A list of operations that can be applied to any compatible mesh.
+///
+/// Pipelines are plain values, so they can be stored in configuration files to describe
+/// variants of an asset. The text format has one operation per line, e.g., `inset 0.8`
+/// or `translate 0 1 0`. Empty lines and lines starting with `#` are ignored.
+/// Use `to_string` to save and `parse` to load a pipeline.
+///
+/// To avoid re-running all operations after changing a parameter, use a `PipelineCache`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OpPipeline<S: Scalar> {
+    /// The operations in the order they are applied.
+    pub ops: Vec<Op<S>>,
+}
+
+impl<S: Scalar> OpPipeline<S> {
+    /// Creates an empty pipeline.
+    pub fn new() -> Self {
+        Self { ops: Vec::new() }
+    }
+
+    /// Appends the operation to the pipeline.
+    pub fn then(mut self, op: Op<S>) -> Self {
+        self.ops.push(op);
+        self
+    }
+
+    /// Returns the number of operations.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Returns whether the pipeline contains no operations.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+impl<S: Scalar> Display for OpPipeline<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for op in &self.ops {
+            writeln!(f, "{}", op)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Scalar> FromStr for OpPipeline<S> {
+    type Err = ParsePipelineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ops = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            ops.push(line.parse().map_err(|message| ParsePipelineError {
+                line: i + 1,
+                message,
+            })?);
+        }
+        Ok(Self { ops })
+    }
+}
+
+/// Methods to apply an `OpPipeline` to a mesh.
+pub trait MeshPipeline<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshExtrude<T> + MeshDisplace<T> + MeshSmoothing<T> + MeshSubdivision<T> + HalfEdgeSemiBuilder<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Applies a single operation to the faces in `selection` and updates the selection.
+    fn apply_op(&mut self, op: &Op<T::S>, selection: &mut Vec<T::F>) -> &mut Self {
+        match op {
+            Op::SelectAll => {
+                *selection = self.face_ids().collect();
+            }
+            Op::Extrude(distance) => {
+                for f in selection.iter_mut() {
+                    let n = self.face(*f).vertices(self).map(|v| v.pos()).normal();
+                    let t = T::Trans::from_translation(n.normalize() * *distance);
+                    let e = self.extrude_face(*f, t);
+                    *f = self.edge(e).face_id();
+                }
+            }
+            Op::Inset(factor) => {
+                for f in selection.iter_mut() {
+                    let c: T::Vec = self.face(*f).vertices(self).map(|v| v.pos()).stable_mean();
+                    let t = T::Trans::from_translation(-c)
+                        .chain(&T::Trans::from_scale(T::Vec::splat(*factor)))
+                        .chain(&T::Trans::from_translation(c));
+                    let e = self.extrude_face(*f, t);
+                    *f = self.edge(e).face_id();
+                }
+            }
+            Op::Subdivide(iterations) => {
+                for _ in 0..*iterations {
+                    self.loop_subdivision(&LinearVertexInterpolator::<3> {});
+                }
+                *selection = self.face_ids().collect();
+            }
+            Op::Displace(distance) => {
+                self.displace_along_normals(|_| *distance);
+            }
+            #[cfg(feature = "noise")]
+            Op::Noise {
+                amplitude,
+                frequency,
+                seed,
+            } => {
+                let fbm = crate::math::Fbm {
+                    frequency: *frequency,
+                    seed: *seed,
+                    ..Default::default()
+                };
+                self.displace_along_normals(|p| fbm.sample(p) * *amplitude);
+            }
+            Op::Smooth { iterations, lambda } => {
+                self.smooth(&SmoothingOptions {
+                    iterations: *iterations,
+                    lambda: *lambda,
+                    ..Default::default()
+                });
+            }
+            Op::Translate([x, y, z]) => {
+                let t = T::Trans::from_translation(T::Vec::from_xyz(*x, *y, *z));
+                for v in self.vertices_mut() {
+                    v.payload_mut().transform(&t);
+                }
+            }
+            Op::Scale([x, y, z]) => {
+                let t = T::Trans::from_scale(T::Vec::from_xyz(*x, *y, *z));
+                for v in self.vertices_mut() {
+                    v.payload_mut().transform(&t);
+                }
+            }
+        }
+        self
+    }
+
+    /// Applies all operations of the pipeline, starting with all faces selected.
+    fn apply_pipeline(&mut self, pipeline: &OpPipeline<T::S>) -> &mut Self {
+        let mut selection = self.face_ids().collect();
+        for op in &pipeline.ops {
+            self.apply_op(op, &mut selection);
+        }
+        self
+    }
+}
+
+/// Stores the intermediate results of a pipeline applied to a base mesh, so only the
+/// operations after the first changed one have to be re-evaluated when the pipeline changes.
+#[derive(Debug, Clone)]
+pub struct PipelineCache<T: MeshTypeHalfEdge + MeshType3D> {
+    base: T::Mesh,
+    ops: Vec<Op<T::S>>,
+    stages: Vec<(T::Mesh, Vec<T::F>)>,
+}
+
+impl<T: MeshTypeHalfEdge<Mesh: MeshPipeline<T>> + MeshType3D> PipelineCache<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Creates a cache for pipelines applied to `base`.
+    pub fn new(base: T::Mesh) -> Self {
+        Self {
+            base,
+            ops: Vec::new(),
+            stages: Vec::new(),
+        }
+    }
+
+    /// Brings the cache up to date with `pipeline`.
+    /// Returns the number of operations that had to be evaluated.
+    pub fn update(&mut self, pipeline: &OpPipeline<T::S>) -> usize {
+        let reused = self
+            .ops
+            .iter()
+            .zip(pipeline.ops.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        self.ops.truncate(reused);
+        self.stages.truncate(reused);
+        for op in &pipeline.ops[reused..] {
+            let (mut mesh, mut selection) = match self.stages.last() {
+                Some(stage) => stage.clone(),
+                None => (self.base.clone(), self.base.face_ids().collect()),
+            };
+            mesh.apply_op(op, &mut selection);
+            self.ops.push(op.clone());
+            self.stages.push((mesh, selection));
+        }
+        pipeline.len() - reused
+    }
+
+    /// Returns the result of the last pipeline passed to `update`.
+    pub fn mesh(&self) -> &T::Mesh {
+        self.stages.last().map_or(&self.base, |(mesh, _)| mesh)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_pipeline_format() {
+        let pipeline = OpPipeline::<f64>::new()
+            .then(Op::Inset(0.8))
+            .then(Op::Extrude(-0.1))
+            .then(Op::SelectAll)
+            .then(Op::Smooth {
+                iterations: 2,
+                lambda: 0.5,
+            })
+            .then(Op::Translate([0.0, 1.5, -2.0]));
+        let text = pipeline.to_string();
+        assert_eq!(text.parse::<OpPipeline<f64>>(), Ok(pipeline));
+
+        let parsed: OpPipeline<f64> = "# comment\n\nextrude 1\n  scale 1 2 3 \n".parse().unwrap();
+        assert_eq!(
+            parsed.ops,
+            vec![Op::Extrude(1.0), Op::Scale([1.0, 2.0, 3.0])]
+        );
+
+        let err = "extrude 1\nextrude\n"
+            .parse::<OpPipeline<f64>>()
+            .unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!("twist 1".parse::<OpPipeline<f64>>().is_err());
+        assert!("inset x".parse::<OpPipeline<f64>>().is_err());
+    }
+
+    #[test]
+    fn test_pipeline_apply() {
+        let pipeline: OpPipeline<f64> = "inset 0.5\nextrude 0.25\n".parse().unwrap();
+        let mut mesh = Mesh3d64::cube(1.0);
+        mesh.apply_pipeline(&pipeline);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        // each side is replaced by 4 border faces, 4 walls, and the cap
+        assert_eq!(mesh.num_faces(), 6 * 9);
+        let top = mesh
+            .vertices()
+            .map(|v| v.pos().y)
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert!(top.is_about(0.75, 1e-9));
+
+        let mut moved = Mesh3d64::cube(1.0);
+        moved.apply_pipeline(&"translate 1 0 0\nscale 2 2 2".parse().unwrap());
+        for v in moved.vertices() {
+            assert!(v.pos().x.is_about(1.0, 1e-9) || v.pos().x.is_about(3.0, 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_pipeline_cache() {
+        let mut cache = PipelineCache::<MeshTypeNd64PNU<3>>::new(Mesh3d64::cube(1.0));
+        let mut pipeline: OpPipeline<f64> = "inset 0.5\nextrude 0.25\n".parse().unwrap();
+        assert_eq!(cache.update(&pipeline), 2);
+        assert_eq!(cache.mesh().num_faces(), 6 * 9);
+        assert_eq!(cache.update(&pipeline), 0);
+
+        // only the last step is re-evaluated
+        pipeline.ops[1] = Op::Extrude(0.5);
+        assert_eq!(cache.update(&pipeline), 1);
+        let mut expected = Mesh3d64::cube(1.0);
+        expected.apply_pipeline(&pipeline);
+        assert_eq!(cache.mesh().num_vertices(), expected.num_vertices());
+        for (a, b) in cache.mesh().vertices().zip(expected.vertices()) {
+            assert!(a.pos().is_about(&b.pos(), 1e-12));
+        }
+
+        assert_eq!(cache.update(&OpPipeline::new()), 0);
+        assert_eq!(cache.mesh().num_faces(), 6);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    math::{Scalar, Vector, Vector3D, Vector3DIteratorExt, VectorIteratorExt},
+    mesh::{
+        DefaultEdgePayload, FaceBasics, MeshHalfEdgeBuilder, MeshType3D, MeshTypeHalfEdge,
+        VertexBasics,
+    },
+};
+
+/// Returns the largest distance of the points from the plane through their centroid
+/// perpendicular to their Newell normal, i.e., how far the polygon is from being planar.
+fn plane_deviation<S: Scalar, V: Vector3D<S = S>>(ps: &[V]) -> S {
+    let n = ps.iter().copied().normal();
+    if n.length_squared() <= S::EPS {
+        return S::ZERO;
+    }
+    let n = n.normalize();
+    let c: V = ps.iter().copied().stable_mean();
+    ps.iter()
+        .map(|p| (*p - c).dot(&n).abs())
+        .fold(S::ZERO, |a, b| a.max(b))
+}
+
+/// Methods to split non-planar faces into planar pieces.
+pub trait MeshPlanarize<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshHalfEdgeBuilder<T>
+where
+    T::EP: DefaultEdgePayload,
+{
+    /// Returns how far the face is from being planar, i.e., the largest distance of its
+    /// vertices from the best-fitting plane through its centroid.
+    fn face_plane_deviation(&self, f: T::F) -> T::S {
+        let ps = self
+            .face(f)
+            .vertices(self)
+            .map(|v| v.pos())
+            .collect::<Vec<T::Vec>>();
+        plane_deviation(&ps)
+    }
+
+    /// Returns the faces whose vertices deviate more than `eps` from a plane.
+    fn non_planar_faces(&self, eps: T::S) -> Vec<T::F> {
+        self.face_ids()
+            .filter(|f| self.face_plane_deviation(*f) > eps)
+            .collect()
+    }
+
+    /// Recursively splits the face along diagonals until each piece deviates at most `eps`
+    /// from a plane. Each split uses the diagonal that minimizes the larger deviation of the
+    /// two pieces, so the pieces fit the surface as well as possible and remain large.
+    /// Diagonals that would flip the orientation of a piece are skipped.
+    ///
+    /// Returns the resulting faces. Triangles are always planar, so this terminates.
+    fn split_non_planar_face(&mut self, f: T::F, eps: T::S) -> Vec<T::F> {
+        let vs = self.face(f).vertex_ids(self).collect::<Vec<_>>();
+        let ps = self
+            .face(f)
+            .vertices(self)
+            .map(|v| v.pos())
+            .collect::<Vec<T::Vec>>();
+        let n = vs.len();
+        if n <= 3 || plane_deviation(&ps) <= eps {
+            return vec![f];
+        }
+        let normal = ps.iter().copied().normal();
+
+        let mut best: Option<(T::S, usize, usize)> = None;
+        for i in 0..n {
+            for j in i + 2..n {
+                if i == 0 && j == n - 1 {
+                    continue;
+                }
+                let a = ps[i..=j].to_vec();
+                let b = ps[j..]
+                    .iter()
+                    .chain(ps[..=i].iter())
+                    .copied()
+                    .collect::<Vec<_>>();
+                let oriented = [&a, &b]
+                    .iter()
+                    .all(|piece| piece.iter().copied().normal().dot(&normal) > T::S::ZERO);
+                if !oriented {
+                    continue;
+                }
+                let deviation = plane_deviation(&a).max(plane_deviation(&b));
+                if best.map_or(true, |(d, _, _)| deviation < d) {
+                    best = Some((deviation, i, j));
+                }
+            }
+        }
+        let Some((_, i, j)) = best else {
+            return vec![f];
+        };
+
+        let (left, right) = self.split_face_default(f, vs[i], vs[j], []);
+        let mut faces = self.split_non_planar_face(left, eps);
+        faces.extend(self.split_non_planar_face(right, eps));
+        faces
+    }
+
+    /// Splits all faces deviating more than `eps` from a plane into planar pieces
+    /// using `split_non_planar_face`. Returns the number of inserted faces.
+    fn planarize(&mut self, eps: T::S) -> usize {
+        let before = self.num_faces();
+        for f in self.non_planar_faces(eps) {
+            self.split_non_planar_face(f, eps);
+        }
+        self.num_faces() - before
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_planarize() {
+        let mut cube = Mesh3d64::cube(1.0);
+        assert!(cube.non_planar_faces(1e-9).is_empty());
+        assert_eq!(cube.planarize(1e-9), 0);
+
+        // a box whose top is bent along the diagonal between the first and third vertex
+        let mut mesh = Mesh3d64::cube(1.0);
+        let top = mesh.faces().find(|f| f.normal(&mesh).y > 0.5).unwrap().id();
+        let v = mesh.face(top).vertex_ids(&mesh).nth(1).unwrap();
+        let p = mesh.vertex(v).pos();
+        mesh.vertex_mut(v)
+            .payload_mut()
+            .set_pos(p + Vec3::new(0.0, 0.3, 0.0));
+        // the side walls are still planar since the vertex moved vertically
+        assert_eq!(mesh.non_planar_faces(1e-9), vec![top]);
+        assert_eq!(mesh.planarize(1e-9), 1);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert!(mesh.non_planar_faces(1e-9).is_empty());
+        // either diagonal is optimal, so the moved vertex is in 3 or 4 faces
+        assert!((3..=4).contains(&mesh.vertex(v).faces(&mesh).count()));
+    }
+
+    #[test]
+    fn test_planarize_ngon() {
+        // a regular octagon with every other vertex lifted
+        let mut mesh = Mesh3d64::regular_prism(1.0, 1.0, 8);
+        let top = mesh
+            .faces()
+            .find(|f| f.num_vertices(&mesh) == 8 && f.normal(&mesh).y > 0.0)
+            .unwrap()
+            .id();
+        let vs = mesh.face(top).vertex_ids(&mesh).collect::<Vec<_>>();
+        for v in vs.iter().step_by(2) {
+            let p = mesh.vertex(*v).pos();
+            mesh.vertex_mut(*v)
+                .payload_mut()
+                .set_pos(p + Vec3::new(0.0, 0.2, 0.0));
+        }
+        let faces = mesh.split_non_planar_face(top, 1e-6);
+        assert!(faces.len() > 1 && faces.len() < 8);
+        assert!(mesh.check().is_ok());
+        for f in faces {
+            assert!(mesh.face_plane_deviation(f) <= 1e-6);
+        }
+    }
+}
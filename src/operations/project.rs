@@ -0,0 +1,126 @@
+use crate::{
+    math::{HasPosition, Scalar, TransformTrait, Vector},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, EuclideanMeshType, FaceBasics, MeshBasics,
+        MeshType3D, SoupBuilder, VertexBasics,
+    },
+};
+use std::collections::HashMap;
+
+/// Methods to turn 3d meshes into 2d drawings, e.g., for exporting them as vector graphics.
+pub trait MeshProject<T: MeshType3D<Mesh = Self>>: MeshBasics<T> {
+    /// Projects the mesh orthographically into a 2d mesh as seen through `view`.
+    ///
+    /// `view` transforms the mesh into view space where the camera looks along the negative
+    /// z-axis, i.e., `x` and `y` of the transformed positions become the 2d positions.
+    /// Faces pointing away from the camera and faces seen edge-on are removed, so the
+    /// remaining faces are counter-clockwise. They are sorted from back to front by the
+    /// mean depth of their vertices, so drawing them in order of their ids paints the
+    /// faces in front over the ones behind them. Vertices shared by the remaining faces
+    /// stay shared, so the edges of the outline can be traced in the result.
+    fn project_and_cleanup<T2: EuclideanMeshType<2, S = T::S>>(&self, view: &T::Trans) -> T2::Mesh
+    where
+        T2::EP: DefaultEdgePayload,
+        T2::FP: DefaultFacePayload,
+    {
+        let mut faces = Vec::new();
+        for f in self.faces() {
+            let vs = f
+                .vertices(self)
+                .map(|v| (v.id(), view.apply(v.pos())))
+                .collect::<Vec<_>>();
+            let n = vs.len();
+            let area = T::S::stable_sum((0..n).map(|i| {
+                let (p, q) = (vs[i].1, vs[(i + 1) % n].1);
+                p.x() * q.y() - p.y() * q.x()
+            })) * T::S::HALF;
+            let size = vs
+                .iter()
+                .map(|(_, p)| p.x().abs().max(p.y().abs()))
+                .fold(T::S::ONE, |a, b| a.max(b));
+            if area <= T::S::EPS.sqrt() * size * size {
+                continue;
+            }
+            let depth = T::S::stable_mean(vs.iter().map(|(_, p)| p.z()));
+            faces.push((depth, vs));
+        }
+        faces.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut builder = SoupBuilder::<2, T2>::new();
+        let mut indices: HashMap<T::V, usize> = HashMap::new();
+        for (_, vs) in faces {
+            let cycle = vs
+                .iter()
+                .map(|(v, p)| {
+                    *indices.entry(*v).or_insert_with(|| {
+                        builder.add_vertex(T2::VP::from_pos(T2::Vec::from_xy(p.x(), p.y())))
+                    })
+                })
+                .collect::<Vec<_>>();
+            builder.add_face(cycle);
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn area(mesh: &Mesh2d64) -> f64 {
+        mesh.faces()
+            .map(|f| {
+                let ps = f.vertices(mesh).map(|v| v.pos()).collect::<Vec<_>>();
+                (0..ps.len())
+                    .map(|i| ps[i].perp_dot(&ps[(i + 1) % ps.len()]) * 0.5)
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_project_cube() {
+        let cube = Mesh3d64::cube(1.0);
+
+        // looking at a face, the sides are seen edge-on
+        let front: Mesh2d64 = cube.project_and_cleanup::<MeshType2d64PNU>(&NdAffine::identity());
+        assert!(front.check().is_ok());
+        assert_eq!(front.num_faces(), 1);
+        assert!(area(&front).is_about(1.0, 1e-12));
+
+        // looking at a corner shows three faces sharing their vertices
+        let view = NdAffine::from_rotation_arc(
+            Vec3::new(1.0, 1.0, 1.0).normalize(),
+            Vec3::new(0.0, 0.0, 1.0),
+        );
+        let corner: Mesh2d64 = cube.project_and_cleanup::<MeshType2d64PNU>(&view);
+        assert!(corner.check().is_ok());
+        assert_eq!(corner.num_faces(), 3);
+        assert_eq!(corner.num_vertices(), 7);
+        assert!(area(&corner).is_about(3.0f64.sqrt(), 1e-9));
+    }
+
+    #[test]
+    fn test_project_order() {
+        // two squares behind each other are painted from back to front
+        let mut mesh = Mesh3d64::default();
+        for (r, z) in [(1.0, 1.0), (2.0, -1.0)] {
+            let mut square = Mesh3d64::regular_polygon(r, 4);
+            square.translate(&Vec3::new(0.0, 0.0, z));
+            mesh.insert_submesh(&square, None);
+        }
+        // the square in front was inserted first
+        assert!(mesh.faces().next().unwrap().centroid(&mesh).z > 0.0);
+
+        let flat: Mesh2d64 = mesh.project_and_cleanup::<MeshType2d64PNU>(&NdAffine::identity());
+        assert_eq!(flat.num_faces(), 2);
+        assert_eq!(flat.num_vertices(), 8);
+        let radii = flat
+            .faces()
+            .map(|f| f.vertices(&flat).next().unwrap().pos().norm())
+            .collect::<Vec<_>>();
+        assert!(radii[0].is_about(2.0, 1e-6));
+        assert!(radii[1].is_about(1.0, 1e-6));
+    }
+}
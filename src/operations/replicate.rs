@@ -0,0 +1,133 @@
+use super::MeshSubmesh;
+use crate::{
+    math::{
+        Scalar, TransformTrait, Transformable, Vector, Vector3D, Vector3DIteratorExt,
+        VectorIteratorExt,
+    },
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, FaceBasics, MeshType3D, MeshTypeHalfEdge,
+        VertexBasics,
+    },
+};
+
+/// Methods to place copies of a template mesh on the faces of a host mesh,
+/// e.g., spikes on a sphere or shingles on a roof.
+///
+/// The template is given in a local frame where the y-axis points along the face
+/// normal, the x-axis along the first edge of the face, and the origin is the centroid.
+pub trait MeshReplicate<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshSubmesh<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+    /// Returns the transformation from the local frame of the face to world space.
+    /// If `scale_by_area` is true, the frame is scaled by the square root of the area
+    /// of the face, so a template with unit footprint covers about the whole face.
+    fn face_frame(&self, f: T::F, scale_by_area: bool) -> T::Trans {
+        let face = self.face(f);
+        let ps = face.vertices(self).map(|v| v.pos()).collect::<Vec<_>>();
+        let c: T::Vec = ps.iter().copied().stable_mean();
+        // the length of the Newell normal is the area of the face
+        let newell = ps.iter().copied().normal();
+        let area = newell.length();
+        let n = newell.normalize();
+        let d = ps[1] - ps[0];
+        let t = (d - n * d.dot(&n)).normalize();
+
+        let up =
+            T::Trans::from_rotation_arc(T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO), n);
+        let x = up.apply_vec(T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO));
+        // twist around the normal in two steps to avoid rotating by exactly half a turn
+        let mid = x + t;
+        let mid = if mid.length_squared() > T::S::EPS {
+            mid.normalize()
+        } else {
+            n.cross(&x)
+        };
+        let scale = if scale_by_area {
+            area.sqrt()
+        } else {
+            T::S::ONE
+        };
+        T::Trans::from_scale(T::Vec::splat(scale))
+            .chain(&up)
+            .chain(&T::Trans::from_rotation_arc(x, mid))
+            .chain(&T::Trans::from_rotation_arc(mid, t))
+            .chain(&T::Trans::from_translation(c))
+    }
+
+    /// Returns the frames of the given faces, e.g., to render the template instanced.
+    /// See `face_frame`.
+    fn face_instances(&self, faces: &[T::F], scale_by_area: bool) -> Vec<T::Trans> {
+        faces
+            .iter()
+            .map(|f| self.face_frame(*f, scale_by_area))
+            .collect()
+    }
+
+    /// Inserts a copy of `template` on each of the given faces, transformed by `face_frame`.
+    /// The copies aren't connected to the host mesh. Returns the new faces of each copy.
+    fn replicate_on_faces(
+        &mut self,
+        template: &Self,
+        faces: &[T::F],
+        scale_by_area: bool,
+    ) -> Vec<Vec<T::F>> {
+        self.face_instances(faces, scale_by_area)
+            .iter()
+            .map(|frame| {
+                let mut copy = template.clone();
+                for v in copy.vertices_mut() {
+                    v.payload_mut().transform(frame);
+                }
+                self.insert_submesh(&copy, None)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_face_frame() {
+        let cube = Mesh3d64::cube(2.0);
+        for f in cube.faces() {
+            let frame = cube.face_frame(f.id(), true);
+            let n = f.normal(&cube).normalize();
+            assert!(frame
+                .apply(Vec3::zeros())
+                .is_about(&f.centroid(&cube), 1e-9));
+            assert!(frame
+                .apply_vec(Vec3::new(0.0, 1.0, 0.0))
+                .is_about(&(n * 2.0), 1e-9));
+            let x = frame.apply_vec(Vec3::new(1.0, 0.0, 0.0));
+            assert!(x.norm().is_about(2.0, 1e-9));
+            assert!(x.dot(&n).is_about(0.0, 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_replicate_on_faces() {
+        let mut cube = Mesh3d64::cube(1.0);
+        let spike = Mesh3d64::regular_pyramid(0.3, 1.0, 4);
+        let faces = cube.face_ids().collect::<Vec<_>>();
+        let copies = cube.replicate_on_faces(&spike, &faces, false);
+        assert!(cube.check().is_ok());
+        assert_eq!(copies.len(), 6);
+        assert_eq!(cube.num_faces(), 6 + 6 * spike.num_faces());
+        assert_eq!(cube.num_vertices(), 8 + 6 * spike.num_vertices());
+        // the tips point outwards
+        for axis in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut tip = Vec3::zeros();
+                tip[axis] = 1.5 * sign;
+                assert!(cube.vertices().any(|v| v.pos().is_about(&tip, 1e-9)));
+            }
+        }
+    }
+}
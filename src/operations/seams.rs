@@ -0,0 +1,184 @@
+use crate::{
+    math::{Scalar, Vector, Vector3DIteratorExt},
+    mesh::{
+        EdgeBasics, FaceBasics, HalfEdge, MeshBasics, MeshType3D, MeshTypeHalfEdge, VertexBasics,
+    },
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Methods to find seams for cutting meshes into charts, e.g., for UV unwrapping.
+pub trait MeshSeams<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshBasics<T>
+{
+    /// Returns a set of seam edges (one halfedge per edge) that cuts the mesh into charts
+    /// homeomorphic to disks, so they can be flattened without manual edge picking.
+    ///
+    /// The faces are grouped into charts by growing a spanning tree of the dual graph from a
+    /// seed face as long as the normals deviate at most `max_stretch` (an angle in radians)
+    /// from the normal of the seed. Edges between charts are always seams. Within a chart,
+    /// all edges not crossed by the spanning tree are seams at first. Branches of these that
+    /// end in the interior of the chart are then pruned, so only the cuts needed to open up
+    /// closed charts and to connect holes to the border remain.
+    /// A closed chart without holes keeps a single seam edge.
+    fn suggest_seams(&self, max_stretch: T::S) -> Vec<T::E> {
+        let min_cos = max_stretch.min(T::S::PI).cos();
+        let normals: HashMap<T::F, T::Vec> = self
+            .faces()
+            .map(|f| {
+                let n = f.vertices(self).map(|v| v.pos()).normal();
+                let len = n.length();
+                (f.id(), if len > T::S::EPS { n / len } else { n })
+            })
+            .collect();
+
+        // grow the charts along a spanning tree of the dual graph
+        let mut chart: HashMap<T::F, usize> = HashMap::new();
+        let mut tree: HashSet<T::E> = HashSet::new();
+        for seed in self.face_ids() {
+            if chart.contains_key(&seed) {
+                continue;
+            }
+            let id = chart.len();
+            chart.insert(seed, id);
+            let mut queue = VecDeque::from([seed]);
+            while let Some(f) = queue.pop_front() {
+                for e in self.face(f).edges(self) {
+                    let twin = e.twin(self);
+                    if twin.is_boundary_self() {
+                        continue;
+                    }
+                    let g = twin.face_id();
+                    if chart.contains_key(&g) || normals[&g].dot(&normals[&seed]) < min_cos {
+                        continue;
+                    }
+                    chart.insert(g, id);
+                    tree.insert(e.id().min(twin.id()));
+                    queue.push_back(g);
+                }
+            }
+        }
+
+        // all interior edges not crossed by the tree are seam candidates
+        let mut forced: HashSet<T::E> = HashSet::new();
+        let mut seams: HashSet<T::E> = HashSet::new();
+        let mut incident: HashMap<T::V, Vec<T::E>> = HashMap::new();
+        let mut anchored: HashSet<T::V> = HashSet::new();
+        for e in self.edges() {
+            if e.is_boundary_self() {
+                anchored.insert(e.origin_id());
+                anchored.insert(e.target_id(self));
+                continue;
+            }
+            let twin = e.twin(self);
+            if e.id() > twin.id() || twin.is_boundary_self() || tree.contains(&e.id()) {
+                continue;
+            }
+            if chart[&e.face_id()] != chart[&twin.face_id()] {
+                forced.insert(e.id());
+            }
+            seams.insert(e.id());
+            incident.entry(e.origin_id()).or_default().push(e.id());
+            incident.entry(e.target_id(self)).or_default().push(e.id());
+        }
+
+        // prune branches that end in the interior
+        let mut degree: HashMap<T::V, usize> =
+            incident.iter().map(|(v, es)| (*v, es.len())).collect();
+        let dangling = |v: &T::V, degree: &HashMap<T::V, usize>| {
+            degree.get(v) == Some(&1) && !anchored.contains(v)
+        };
+        let mut stack = degree
+            .keys()
+            .filter(|v| dangling(v, &degree))
+            .copied()
+            .collect::<Vec<_>>();
+        while let Some(v) = stack.pop() {
+            if !dangling(&v, &degree) {
+                continue;
+            }
+            let e = *incident[&v].iter().find(|e| seams.contains(e)).unwrap();
+            if forced.contains(&e) {
+                continue;
+            }
+            let edge = self.edge(e);
+            let w = if edge.origin_id() == v {
+                edge.target_id(self)
+            } else {
+                edge.origin_id()
+            };
+            if dangling(&w, &degree) {
+                // the last edge of a closed chart
+                continue;
+            }
+            seams.remove(&e);
+            *degree.get_mut(&v).unwrap() -= 1;
+            *degree.get_mut(&w).unwrap() -= 1;
+            stack.push(w);
+        }
+
+        self.edge_ids().filter(|e| seams.contains(e)).collect()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::collections::HashMap;
+
+    /// Returns the number of seams at each vertex.
+    fn seam_degrees(mesh: &Mesh3d64, seams: &[usize]) -> HashMap<usize, usize> {
+        let mut degree = HashMap::new();
+        for e in seams {
+            let edge = mesh.edge(*e);
+            *degree.entry(edge.origin_id()).or_insert(0) += 1;
+            *degree.entry(edge.target_id(mesh)).or_insert(0) += 1;
+        }
+        degree
+    }
+
+    #[test]
+    fn test_seams_closed() {
+        // every face of the cube is its own chart
+        let cube = Mesh3d64::cube(1.0);
+        let seams = cube.suggest_seams(std::f64::consts::PI / 4.0);
+        assert_eq!(seams.len(), 12);
+
+        // a single chart without holes only needs one cut
+        let seams = cube.suggest_seams(std::f64::consts::PI);
+        assert_eq!(seams.len(), 1);
+        let sphere = Mesh3d64::uv_sphere(1.0, 8, 8);
+        assert_eq!(sphere.suggest_seams(std::f64::consts::PI).len(), 1);
+    }
+
+    #[test]
+    fn test_seams_open() {
+        // an open tube is cut along a single path from one end to the other
+        let path = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(3.0, 0.0, 0.0),
+        ];
+        let tube = Mesh3d64::tube_from_path(&path, 0.5, 8, false);
+        let seams = tube.suggest_seams(std::f64::consts::PI);
+        assert!(seams.len() >= 3);
+        let degree = seam_degrees(&tube, &seams);
+        let boundary = tube
+            .edges()
+            .filter(|e| e.is_boundary_self())
+            .map(|e| e.origin_id())
+            .collect::<Vec<_>>();
+        for (v, d) in degree {
+            if boundary.contains(&v) {
+                assert_eq!(d, 1);
+            } else {
+                assert_eq!(d, 2);
+            }
+        }
+
+        // with a small angle, each column of faces is a separate chart
+        let seams = tube.suggest_seams(0.1);
+        assert_eq!(seams.len(), 8 * 3);
+    }
+}
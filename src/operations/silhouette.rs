@@ -0,0 +1,261 @@
+use crate::{
+    math::{Polygon, Scalar, Vector, Vector2D, Vector3D},
+    mesh::{
+        EdgeBasics, Face3d, FaceBasics, HalfEdge, MeshBasics, MeshType3D, MeshTypeHalfEdge,
+        VertexBasics,
+    },
+};
+use std::collections::HashMap;
+
+/// Returns the parameters along `p`-`q` where it meets `r`-`s`, i.e., the intersection
+/// or, if the segments are collinear, the endpoints of each segment lying on the other one.
+fn meetings<V: Vector2D>(p: V, q: V, r: V, s: V, tol: V::S) -> Vec<(V::S, V::S)> {
+    let (a, b) = (q - p, s - r);
+    let denom = a.perp_dot(&b);
+    if denom.abs() > tol * a.length() * b.length() {
+        let t = (r - p).perp_dot(&b) / denom;
+        let k = (r - p).perp_dot(&a) / denom;
+        let range = V::S::ZERO..=V::S::ONE;
+        return if range.contains(&t) && range.contains(&k) {
+            vec![(t, k)]
+        } else {
+            Vec::new()
+        };
+    }
+    // parallel segments only meet if they lie on the same line
+    if (r - p).perp_dot(&a).abs() > tol * a.length() {
+        return Vec::new();
+    }
+    let on = |x: V, from: V, dir: V| (x - from).dot(&dir) / dir.length_squared();
+    vec![
+        (on(r, p, a), V::S::ZERO),
+        (on(s, p, a), V::S::ONE),
+        (V::S::ZERO, on(p, r, b)),
+        (V::S::ONE, on(q, r, b)),
+    ]
+}
+
+/// Methods to compute the outline of meshes as seen from a direction, e.g., for impostors.
+pub trait MeshSilhouette<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshBasics<T>
+{
+    /// Returns the outline of the mesh when looking along `view_dir`, i.e., the boundary of
+    /// the union of all projected faces, as closed polygons. Outlines are counter-clockwise
+    /// and holes clockwise as seen by the viewer, and collinear points are removed.
+    ///
+    /// The points are given in the plane perpendicular to the view direction with the same
+    /// frame as `MeshSlice::slice_contours` along the axis `-view_dir`. E.g., looking down
+    /// the negative y-axis gives `(x, -z)`.
+    ///
+    /// The outline consists of the projections of the boundary edges and of the edges
+    /// where the surface turns away from the viewer. All pairs of these are intersected,
+    /// so this is quadratic in their number.
+    fn silhouette_polygon(&self, view_dir: T::Vec) -> Vec<Vec<T::Vec2>> {
+        let a = -view_dir.normalize();
+        let seed = if a.x().abs() < T::S::HALF {
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)
+        } else {
+            T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO)
+        };
+        let u = (seed - a * seed.dot(&a)).normalize();
+        let w = a.cross(&u);
+        let project = |p: T::Vec| T::Vec2::from_xy(p.dot(&u), p.dot(&w));
+
+        // whether each face is facing the viewer, facing away, or seen edge-on
+        let facing = self
+            .faces()
+            .map(|f| {
+                let n = Face3d::normal(f, self);
+                let c = n.dot(&a);
+                let eps = T::S::EPS.sqrt() * n.length();
+                let class = if c > eps {
+                    1
+                } else if c < -eps {
+                    -1
+                } else {
+                    0
+                };
+                (f.id(), class)
+            })
+            .collect::<HashMap<_, _>>();
+        let polygons = self
+            .faces()
+            .filter(|f| facing[&f.id()] != 0)
+            .map(|f| {
+                let ps = f
+                    .vertices(self)
+                    .map(|v| project(v.pos()))
+                    .collect::<Vec<_>>();
+                let (mut min, mut max) = (ps[0], ps[0]);
+                for p in &ps {
+                    min = T::Vec2::from_xy(min.x().min(p.x()), min.y().min(p.y()));
+                    max = T::Vec2::from_xy(max.x().max(p.x()), max.y().max(p.y()));
+                }
+                (T::Poly::from_points(&ps), min, max)
+            })
+            .collect::<Vec<_>>();
+        let scale = polygons
+            .iter()
+            .map(|(_, min, max)| (*max - *min).length())
+            .fold(T::S::ZERO, |s, x| s.max(x));
+        if scale <= T::S::ZERO {
+            return Vec::new();
+        }
+        let tol = scale * T::S::EPS.sqrt();
+        let inside = |p: T::Vec2| {
+            polygons.iter().any(|(poly, min, max)| {
+                p.x() >= min.x()
+                    && p.y() >= min.y()
+                    && p.x() <= max.x()
+                    && p.y() <= max.y()
+                    && poly.contains(&p)
+            })
+        };
+
+        // the edges where the facing changes, including boundary edges
+        let class = |e: &T::Edge| e.face(self).map_or(2, |f| facing[&f.id()]);
+        let segments = self
+            .edges()
+            .filter(|e| e.id() < e.twin_id() && class(e) != class(&e.twin(self)))
+            .map(|e| (project(e.origin(self).pos()), project(e.target(self).pos())))
+            .filter(|(p, q)| (*q - *p).length() > tol)
+            .collect::<Vec<_>>();
+
+        // split the segments where they meet and keep the pieces with the union on one side
+        let mut params = vec![vec![T::S::ZERO, T::S::ONE]; segments.len()];
+        for i in 0..segments.len() {
+            for j in i + 1..segments.len() {
+                let ((p, q), (r, s)) = (segments[i], segments[j]);
+                for (t, k) in meetings(p, q, r, s, tol) {
+                    params[i].push(t);
+                    params[j].push(k);
+                }
+            }
+        }
+        let mut pieces: Vec<(T::Vec2, T::Vec2)> = Vec::new();
+        for (i, (p, q)) in segments.iter().enumerate() {
+            let ts = &mut params[i];
+            ts.retain(|t| *t >= T::S::ZERO && *t <= T::S::ONE);
+            ts.sort_by(|x, y| x.partial_cmp(y).unwrap());
+            for pair in ts.windows(2) {
+                let (a, b) = (*p + (*q - *p) * pair[0], *p + (*q - *p) * pair[1]);
+                if (b - a).length() <= tol {
+                    continue;
+                }
+                let m = (a + b) * T::S::HALF;
+                let d = (b - a).normalize();
+                let left = T::Vec2::from_xy(-d.y(), d.x()) * tol;
+                let piece = match (inside(m + left), inside(m - left)) {
+                    (true, false) => (a, b),
+                    (false, true) => (b, a),
+                    _ => continue,
+                };
+                // collinear edges can give the same piece twice
+                if !pieces
+                    .iter()
+                    .any(|(x, y)| (*x - piece.0).length() <= tol && (*y - piece.1).length() <= tol)
+                {
+                    pieces.push(piece);
+                }
+            }
+        }
+
+        // chain the pieces into loops and remove collinear points
+        let mut used = vec![false; pieces.len()];
+        let mut res = Vec::new();
+        for i in 0..pieces.len() {
+            if used[i] {
+                continue;
+            }
+            used[i] = true;
+            let mut contour = vec![pieces[i].0];
+            let mut end = pieces[i].1;
+            let closed = loop {
+                if (end - contour[0]).length() <= tol {
+                    break true;
+                }
+                let Some(j) =
+                    (0..pieces.len()).find(|j| !used[*j] && (pieces[*j].0 - end).length() <= tol)
+                else {
+                    break false;
+                };
+                used[j] = true;
+                contour.push(pieces[j].0);
+                end = pieces[j].1;
+            };
+            if !closed {
+                continue;
+            }
+            let mut k = 0;
+            while contour.len() > 3 && k < contour.len() {
+                let n = contour.len();
+                let (prev, p, next) = (contour[(k + n - 1) % n], contour[k], contour[(k + 1) % n]);
+                let d = next - prev;
+                if (p - prev).perp_dot(&d).abs() <= tol * d.length() {
+                    contour.remove(k);
+                    k = k.saturating_sub(1);
+                } else {
+                    k += 1;
+                }
+            }
+            res.push(contour);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    fn signed_area(contour: &[Vec2<f64>]) -> f64 {
+        (0..contour.len())
+            .map(|i| contour[i].perp_dot(&contour[(i + 1) % contour.len()]) * 0.5)
+            .sum()
+    }
+
+    #[test]
+    fn test_silhouette_polygon() {
+        // a cube seen from above is a square
+        let cube = Mesh3d64::cube(2.0);
+        let outline = cube.silhouette_polygon(Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(outline.len(), 1);
+        assert_eq!(outline[0].len(), 4);
+        assert!(signed_area(&outline[0]).is_about(4.0, 1e-9));
+
+        // and along its diagonal a regular hexagon
+        let outline = cube.silhouette_polygon(Vec3::new(1.0, 1.0, 1.0));
+        assert_eq!(outline.len(), 1);
+        assert_eq!(outline[0].len(), 6);
+        assert!(signed_area(&outline[0]).is_about(4.0 * 3f64.sqrt(), 1e-9));
+
+        // overlapping boxes give the outline of their union
+        let mut boxes = Mesh3d64::cube(2.0);
+        let mut other = Mesh3d64::cube(2.0);
+        other.translate(&Vec3::new(1.0, 0.0, -3.0));
+        boxes.insert_submesh(&other, None);
+        let outline = boxes.silhouette_polygon(Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(outline.len(), 1);
+        assert_eq!(outline[0].len(), 4);
+        assert!(signed_area(&outline[0]).is_about(6.0, 1e-9));
+
+        // a torus seen from above has a hole
+        let profile: Vec<Vec2<f64>> = (0..=8)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / 8.0;
+                Vec2::new(2.0 + 0.5 * a.cos(), 0.5 * a.sin())
+            })
+            .collect();
+        let mut torus = Mesh3d64::default();
+        torus.revolve(&profile, Vec3::new(0.0, 1.0, 0.0), 2.0 * PI, 16);
+        let outline = torus.silhouette_polygon(Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(outline.len(), 2);
+        let mut areas = outline.iter().map(|c| signed_area(c)).collect::<Vec<_>>();
+        areas.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let ring = |r: f64| 0.5 * 16.0 * r * r * (2.0 * PI / 16.0).sin();
+        assert!(areas[0].is_about(-ring(1.5), 1e-9));
+        assert!(areas[1].is_about(ring(2.5), 1e-9));
+    }
+}
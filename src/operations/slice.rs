@@ -0,0 +1,213 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector3D},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, EuclideanMeshType, Face3d, FaceBasics, MeshBasics,
+        MeshType3D, VertexBasics,
+    },
+    primitives::Make2dShape,
+};
+use std::collections::HashMap;
+
+/// Methods to slice meshes into planar contours, e.g., for 3D printing or topographic lines.
+pub trait MeshSlice<T: MeshType3D<Mesh = Self>>: MeshBasics<T> {
+    /// Returns the closed contours of the cross-section of the mesh with the plane
+    /// perpendicular to `axis` at the given `height` along it. The contours keep the material
+    /// on their left, i.e., outlines are counter-clockwise and holes clockwise when looking
+    /// against the axis.
+    ///
+    /// The points are given in the plane with the orthonormal frame `(u, w)` where `u` is the
+    /// x-axis or, if the axis is close to it, the y-axis made perpendicular to the axis, and
+    /// `w = axis x u`. E.g., slicing along the z-axis gives `(x, y)` and along the y-axis
+    /// `(x, -z)`.
+    ///
+    /// Vertices on the plane count as above it, so a mesh resting on the plane doesn't
+    /// intersect it, while a mesh whose top lies in the plane does. Open meshes may give
+    /// open contours, which are skipped. The faces should be convex.
+    fn slice_contours(&self, axis: T::Vec, height: T::S) -> Vec<Vec<T::Vec2>> {
+        let a = axis.normalize();
+        let seed = if a.x().abs() < T::S::HALF {
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)
+        } else {
+            T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO)
+        };
+        let u = (seed - a * seed.dot(&a)).normalize();
+        let w = a.cross(&u);
+        let eps = T::S::EPS.sqrt();
+        let distance = self
+            .vertices()
+            .map(|v| (v.id(), v.pos().dot(&a) - height))
+            .collect::<HashMap<_, _>>();
+
+        // the segments of the faces crossing the plane, keyed by the edges they cross
+        let mut next = HashMap::new();
+        let mut points = HashMap::new();
+        let mut starts = Vec::new();
+        for f in self.faces() {
+            let vs = f.vertex_ids(self).collect::<Vec<_>>();
+            let ps = f.vertices(self).map(|v| v.pos()).collect::<Vec<_>>();
+            let n = vs.len();
+            // the material lies opposite to the normal, i.e., to the left of `a x normal`
+            let along = a.cross(&Face3d::normal(f, self));
+            let mut crossings = (0..n)
+                .filter_map(|i| {
+                    let j = (i + 1) % n;
+                    let (du, dw) = (distance[&vs[i]], distance[&vs[j]]);
+                    if (du >= T::S::ZERO) == (dw >= T::S::ZERO) {
+                        return None;
+                    }
+                    let p = ps[i] + (ps[j] - ps[i]) * (du / (du - dw));
+                    let key = (vs[i].min(vs[j]), vs[i].max(vs[j]));
+                    points.insert(key, T::Vec2::from_xy(p.dot(&u), p.dot(&w)));
+                    Some((p.dot(&along), key))
+                })
+                .collect::<Vec<_>>();
+            crossings.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+            for pair in crossings.chunks_exact(2) {
+                next.insert(pair[0].1, pair[1].1);
+                starts.push(pair[0].1);
+            }
+        }
+
+        // chain the segments into closed loops
+        let mut res = Vec::new();
+        for start in starts {
+            let mut contour: Vec<T::Vec2> = Vec::new();
+            let mut current = start;
+            let closed = loop {
+                let Some(c) = next.remove(&current) else {
+                    break false;
+                };
+                let p = points[&current];
+                if contour.last().map_or(true, |q| (*q - p).length() > eps) {
+                    contour.push(p);
+                }
+                if c == start {
+                    break true;
+                }
+                current = c;
+            };
+            if contour.len() > 1 && (contour[0] - contour[contour.len() - 1]).length() <= eps {
+                contour.pop();
+            }
+            if closed && contour.len() >= 3 {
+                res.push(contour);
+            }
+        }
+        res
+    }
+
+    /// Intersects the mesh with parallel planes perpendicular to `axis` at all multiples
+    /// of `spacing` within the extent of the mesh. Returns the height of each plane along
+    /// the axis together with a 2D mesh with one face for each closed contour, where holes
+    /// are clockwise faces inside counter-clockwise outlines like in the outlines of a font.
+    /// See `slice_contours` for the frame of the 2D coordinates.
+    ///
+    /// The contours are made of straight edges, so curved 2D mesh types can be used, too.
+    fn slice<T2: EuclideanMeshType<2, S = T::S>>(
+        &self,
+        axis: T::Vec,
+        spacing: T::S,
+    ) -> Vec<(T::S, T2::Mesh)>
+    where
+        T2::Mesh: Make2dShape<T2>,
+        T2::EP: DefaultEdgePayload,
+        T2::FP: DefaultFacePayload,
+    {
+        assert!(spacing > T::S::ZERO, "The spacing must be positive");
+        let a = axis.normalize();
+        let (min, max) = self
+            .vertices()
+            .fold((T::S::INFINITY, T::S::NEG_INFINITY), |(min, max), v| {
+                (min.min(v.pos().dot(&a)), max.max(v.pos().dot(&a)))
+            });
+        if min > max {
+            return Vec::new();
+        }
+        let first = (min / spacing).to_f64().ceil() as i64;
+        let last = (max / spacing).to_f64().floor() as i64;
+        (first..=last)
+            .map(|k| {
+                let height = spacing * T::S::from_f64(k as f64);
+                let mut layer = T2::Mesh::default();
+                for contour in self.slice_contours(a, height) {
+                    // `insert_polygon` reverses the order
+                    layer.insert_polygon(
+                        contour
+                            .iter()
+                            .rev()
+                            .map(|p| T2::VP::from_pos(T2::Vec::from_xy(p.x(), p.y()))),
+                    );
+                }
+                (height, layer)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    /// Returns the signed area enclosed by the faces.
+    fn area(mesh: &Mesh2d64Curved) -> f64 {
+        mesh.faces()
+            .map(|f| {
+                let ps = f.vertices(mesh).map(|v| v.pos()).collect::<Vec<_>>();
+                (0..ps.len())
+                    .map(|i| ps[i].perp_dot(&ps[(i + 1) % ps.len()]) * 0.5)
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_slice() {
+        // a cube gives squares in all layers except the bottom one, where it rests on the plane
+        let cube = Mesh3d64::cube(2.0);
+        let layers = cube.slice::<MeshType2d64PNUCurved>(Vec3::new(0.0, 0.0, 1.0), 0.5);
+        assert_eq!(
+            layers.iter().map(|(h, _)| *h).collect::<Vec<_>>(),
+            [-1.0, -0.5, 0.0, 0.5, 1.0]
+        );
+        for (h, layer) in &layers {
+            assert!(layer.check().is_ok());
+            let expected = if *h > -1.0 { 4.0 } else { 0.0 };
+            assert!(area(layer).is_about(expected, 1e-9));
+        }
+        let contours = cube.slice_contours(Vec3::new(0.0, 0.0, 1.0), 0.25);
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0]
+            .iter()
+            .all(|p| p.x.abs().is_about(1.0, 1e-9) && p.y.abs().is_about(1.0, 1e-9)));
+
+        // a torus lying flat gives an annulus with the hole as a clockwise contour
+        let profile: Vec<Vec2<f64>> = (0..=16)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / 16.0;
+                Vec2::new(2.0 + 0.5 * a.cos(), 0.5 * a.sin())
+            })
+            .collect();
+        let mut torus = Mesh3d64::default();
+        torus.revolve(&profile, Vec3::new(0.0, 1.0, 0.0), 2.0 * PI, 32);
+        let contours = torus.slice_contours(Vec3::new(0.0, 1.0, 0.0), 0.1);
+        assert_eq!(contours.len(), 2);
+        let signed = |c: &Vec<Vec2<f64>>| {
+            (0..c.len())
+                .map(|i| c[i].perp_dot(&c[(i + 1) % c.len()]) * 0.5)
+                .sum::<f64>()
+        };
+        let mut areas = contours.iter().map(signed).collect::<Vec<_>>();
+        areas.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(areas[0] < 0.0 && areas[1] > -areas[0]);
+
+        let layers = torus.slice::<MeshType2d64PNUCurved>(Vec3::new(0.0, 1.0, 0.0), 0.2);
+        assert_eq!(layers.len(), 5);
+        for (_, layer) in &layers {
+            assert!(layer.check().is_ok());
+            assert_eq!(layer.num_faces(), 2);
+            assert!(area(layer) > 0.0);
+        }
+    }
+}
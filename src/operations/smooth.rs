@@ -0,0 +1,256 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector3DIteratorExt, VectorIteratorExt},
+    mesh::{signed_volume, FaceBasics, MeshBasics, MeshType3D, VertexBasics},
+    util::Selection,
+};
+use std::collections::HashMap;
+
+/// How the neighborhood of a vertex is averaged when smoothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SmoothingWeights {
+    /// Move towards the mean of the adjacent vertices (umbrella operator).
+    #[default]
+    Uniform,
+
+    /// Move towards the mean of the centroids of the adjacent faces weighted by their area.
+    /// Less sensitive to irregular tessellations than `Uniform`.
+    Area,
+}
+
+/// How the shrinkage of laplacian smoothing is counteracted.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum VolumeCorrection<S: Scalar> {
+    /// Plain laplacian smoothing. Shrinks the mesh with each iteration.
+    #[default]
+    None,
+
+    /// Taubin's lambda/mu smoothing: Each iteration is followed by a second step with
+    /// the given negative factor `mu`, where `mu < -lambda`, e.g., `lambda = 0.5` and `mu = -0.53`.
+    Taubin(S),
+
+    /// Scale the mesh around its centroid after each iteration such that the enclosed volume
+    /// stays the same. Only sensible for closed meshes.
+    Rescale,
+}
+
+/// Parameters for smoothing a mesh.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothingOptions<S: Scalar> {
+    /// Number of smoothing iterations.
+    pub iterations: usize,
+
+    /// How far each vertex moves towards the average of its neighborhood in each iteration.
+    /// Should be between 0 and 1.
+    pub lambda: S,
+
+    /// How the neighborhood is averaged.
+    pub weights: SmoothingWeights,
+
+    /// How to counteract shrinkage.
+    pub volume: VolumeCorrection<S>,
+
+    /// Whether boundary vertices are kept in place.
+    pub fix_boundary: bool,
+}
+
+impl<S: Scalar> Default for SmoothingOptions<S> {
+    fn default() -> Self {
+        Self {
+            iterations: 1,
+            lambda: S::HALF,
+            weights: SmoothingWeights::Uniform,
+            volume: VolumeCorrection::None,
+            fix_boundary: true,
+        }
+    }
+}
+
+/// Methods to smooth the vertex positions of a mesh.
+pub trait MeshSmoothing<T: MeshType3D<Mesh = Self>>: MeshBasics<T> {
+    /// Smoothes the mesh according to the given `options`.
+    fn smooth(&mut self, options: &SmoothingOptions<T::S>) -> &mut Self {
+        self.smooth_weighted(options, |_| T::S::ONE)
+    }
+
+    /// Like `smooth`, but scales the step of each vertex by `weight`, which is
+    /// usually derived from some attribute of the vertex payload, e.g., a painted mask.
+    /// Weights are clamped to `[0, 1]`; vertices with weight 0 don't move.
+    fn smooth_weighted(
+        &mut self,
+        options: &SmoothingOptions<T::S>,
+        weight: impl Fn(&T::VP) -> T::S,
+    ) -> &mut Self {
+        self.smooth_vertices(options, |v| weight(v.payload()))
+    }
+
+    /// Like `smooth`, but only moves the selected vertices. The unselected vertices
+    /// still pull on their selected neighbors, so the selection blends into the rest.
+    fn smooth_selected(
+        &mut self,
+        options: &SmoothingOptions<T::S>,
+        selection: &Selection<T::V>,
+    ) -> &mut Self {
+        self.smooth_vertices(options, |v| {
+            if selection.contains(v.id()) {
+                T::S::ONE
+            } else {
+                T::S::ZERO
+            }
+        })
+    }
+
+    /// Like `smooth_weighted`, but derives the weight from the whole vertex.
+    fn smooth_vertices(
+        &mut self,
+        options: &SmoothingOptions<T::S>,
+        weight: impl Fn(&T::Vertex) -> T::S,
+    ) -> &mut Self {
+        let weights: HashMap<T::V, T::S> = self
+            .vertices()
+            .map(|v| {
+                let fixed = options.fix_boundary && v.is_boundary(self);
+                let w = if fixed {
+                    T::S::ZERO
+                } else {
+                    weight(v).clamp(T::S::ZERO, T::S::ONE)
+                };
+                (v.id(), w)
+            })
+            .collect();
+
+        for _ in 0..options.iterations {
+            let volume = signed_volume::<T>(self);
+            self.smoothing_step(options.lambda, options.weights, &weights);
+            match options.volume {
+                VolumeCorrection::None => {}
+                VolumeCorrection::Taubin(mu) => {
+                    self.smoothing_step(mu, options.weights, &weights);
+                }
+                VolumeCorrection::Rescale => {
+                    let current = signed_volume::<T>(self);
+                    if current.abs() > T::S::EPS {
+                        let scale = (volume / current).cbrt();
+                        let center: T::Vec = self.vertices().map(|v| v.pos()).stable_mean();
+                        let ids = self.vertex_ids().collect::<Vec<_>>();
+                        for v in ids {
+                            let p: T::Vec = self.vertex(v).pos();
+                            self.vertex_mut(v)
+                                .payload_mut()
+                                .set_pos(center + (p - center) * scale);
+                        }
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Moves each vertex by `factor * weights[v]` towards the average of its neighborhood.
+    /// All vertices are updated simultaneously.
+    fn smoothing_step(
+        &mut self,
+        factor: T::S,
+        mode: SmoothingWeights,
+        weights: &HashMap<T::V, T::S>,
+    ) -> &mut Self {
+        let targets = self
+            .vertices()
+            .filter(|v| weights[&v.id()] != T::S::ZERO)
+            .filter_map(|v| {
+                let p: T::Vec = v.pos();
+                let target = match mode {
+                    SmoothingWeights::Uniform => {
+                        let ns = v.vertices(self).map(|n| n.pos()).collect::<Vec<T::Vec>>();
+                        if ns.is_empty() {
+                            return None;
+                        }
+                        T::Vec::stable_mean(ns.into_iter())
+                    }
+                    SmoothingWeights::Area => {
+                        let (sum, area) =
+                            v.faces(self)
+                                .fold((T::Vec::zero(), T::S::ZERO), |(sum, area), f| {
+                                    let vs =
+                                        f.vertices(self).map(|v| v.pos()).collect::<Vec<T::Vec>>();
+                                    let a = vs.iter().copied().normal().length();
+                                    let c = T::Vec::stable_mean(vs.into_iter());
+                                    (sum + c * a, area + a)
+                                });
+                        if area <= T::S::EPS {
+                            return None;
+                        }
+                        sum / area
+                    }
+                };
+                Some((v.id(), p + (target - p) * (factor * weights[&v.id()])))
+            })
+            .collect::<Vec<_>>();
+        for (v, p) in targets {
+            self.vertex_mut(v).payload_mut().set_pos(p);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_smoothing_volume() {
+        let base = Mesh3d64::uv_sphere(1.0, 8, 8);
+        let v0 = base.report().volume.unwrap();
+        // the volume after smoothing with the given parameters
+        let smoothed = |volume: VolumeCorrection<f64>, weights: SmoothingWeights| {
+            let mut mesh = base.clone();
+            mesh.smooth(&SmoothingOptions {
+                iterations: 10,
+                lambda: 0.5,
+                weights,
+                volume,
+                fix_boundary: true,
+            });
+            mesh.report().volume.unwrap()
+        };
+
+        let plain = smoothed(VolumeCorrection::None, SmoothingWeights::Uniform);
+        let area = smoothed(VolumeCorrection::None, SmoothingWeights::Area);
+        let taubin = smoothed(VolumeCorrection::Taubin(-0.53), SmoothingWeights::Uniform);
+        let rescale = smoothed(VolumeCorrection::Rescale, SmoothingWeights::Area);
+        assert!(plain < 0.8 * v0);
+        assert!(area < 0.8 * v0);
+        assert!((taubin - v0).abs() < (plain - v0).abs() / 4.0);
+        assert!(rescale.is_about(v0, 1e-9));
+    }
+
+    #[test]
+    fn test_smoothing_weights() {
+        let base = Mesh3d64::uv_sphere(1.0, 8, 8);
+        let mut mesh = base.clone();
+        // only smooth the northern hemisphere
+        mesh.smooth_weighted(&SmoothingOptions::default(), |vp| {
+            if vp.pos().y > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        });
+        for v in mesh.vertices() {
+            let old = base.vertex(v.id()).pos();
+            if old.y > 0.0 {
+                assert!(v.pos().norm() < 1.0 - 1e-6);
+            } else {
+                assert_eq!(v.pos(), old);
+            }
+        }
+
+        // selections behave like binary weights
+        let mut selected = base.clone();
+        let north = base.select_vertices(|v| v.pos().y > 0.0);
+        selected.smooth_selected(&SmoothingOptions::default(), &north);
+        for v in selected.vertices() {
+            assert_eq!(v.pos(), mesh.vertex(v.id()).pos());
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{
+    math::{HasPosition, IndexType, Scalar, Transformable, Vector},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, Face3d, FaceBasics, FacePayload, MeshIslands,
+        MeshType3D, MeshTypeHalfEdge, SoupBuilder, Triangulateable, VertexBasics, ISLAND_ATTRIBUTE,
+    },
+    primitives::Make2dShape,
+    tesselate::{TesselationMeta, TriangulationAlgorithm},
+};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+/// A vertex of one of the halves: either an original vertex or the intersection
+/// of the plane with the edge between two vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Cut<V> {
+    Vertex(V),
+    Edge(V, V),
+}
+
+/// A face of one of the halves given by its cycle of vertices and its tag.
+type CutFace<V> = (Vec<Cut<V>>, Option<&'static str>);
+
+/// Methods to split meshes into two parts, e.g., for destruction effects or cross-sections.
+pub trait MeshSplit<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    Make2dShape<T> + Triangulateable<T> + MeshIslands<3, T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::VP: Transformable<3, Trans = T::Trans, Rot = T::Rot, Vec = T::Vec, S = T::S>,
+{
+    /// Splits the mesh by the plane through `point` with the given `normal`. Returns the
+    /// part in front of the plane, i.e., in the direction of the normal, and the part behind it.
+    ///
+    /// Faces crossing the plane are cut into two, so they should be convex. The payloads of
+    /// the new vertices are interpolated along the cut edges. The cross-sections are closed
+    /// with faces tagged "cap", so cutting a closed mesh gives two closed meshes. Holes in
+    /// the cross-sections, e.g., of a tube, are kept. Only closed loops are capped, so open
+    /// meshes stay open where they are cut. Faces lying in the plane are assigned to the
+    /// part they bound. Vertices closer to the plane than a small epsilon are moved onto it.
+    ///
+    /// The islands of both parts keep the payloads of the islands they were cut from, see
+    /// `MeshIslands`. An island cut into several pieces on one side passes its payload to
+    /// each of them. Only vertex payloads and face tags are kept, other payloads are reset.
+    fn split_by_plane(&self, point: T::Vec, normal: T::Vec) -> (Self, Self) {
+        let n = normal.normalize();
+        let eps = T::S::EPS.sqrt();
+        let mut distance = HashMap::new();
+        let mut side = HashMap::new();
+        for v in self.vertices() {
+            let d = (v.pos() - point).dot(&n);
+            distance.insert(v.id(), d);
+            side.insert(
+                v.id(),
+                if d > eps {
+                    1
+                } else if d < -eps {
+                    -1
+                } else {
+                    0
+                },
+            );
+        }
+        let on_plane = |c: &Cut<T::V>| match c {
+            Cut::Vertex(v) => side[v] == 0,
+            Cut::Edge(_, _) => true,
+        };
+        let payload = |c: &Cut<T::V>| match c {
+            Cut::Vertex(v) => {
+                let mut vp = self.vertex(*v).payload().clone();
+                if side[v] == 0 {
+                    vp.set_pos(*vp.pos() - n * distance[v]);
+                }
+                vp
+            }
+            Cut::Edge(u, w) => {
+                let (du, dw) = (distance[u], distance[w]);
+                self.vertex(*u)
+                    .payload()
+                    .lerped(self.vertex(*w).payload(), du / (du - dw))
+            }
+        };
+
+        // distribute the faces to the halves and cut those crossing the plane
+        let mut halves: [Vec<CutFace<T::V>>; 2] = [Vec::new(), Vec::new()];
+        for f in self.faces() {
+            let vs: Vec<T::V> = f.vertex_ids(self).collect();
+            let tag = f.payload().tag();
+            let front = vs.iter().any(|v| side[v] > 0);
+            let back = vs.iter().any(|v| side[v] < 0);
+            if !front && !back {
+                // the face bounds the part on the opposite side of its normal
+                let i = if Face3d::normal(f, self).dot(&n) < T::S::ZERO {
+                    0
+                } else {
+                    1
+                };
+                halves[i].push((vs.iter().map(|v| Cut::Vertex(*v)).collect(), tag));
+                continue;
+            }
+            let mut cycles = [Vec::new(), Vec::new()];
+            for (u, w) in vs.iter().circular_tuple_windows() {
+                if side[u] >= 0 {
+                    cycles[0].push(Cut::Vertex(*u));
+                }
+                if side[u] <= 0 {
+                    cycles[1].push(Cut::Vertex(*u));
+                }
+                if side[u] * side[w] < 0 {
+                    let c = Cut::Edge(*u.min(w), *u.max(w));
+                    cycles[0].push(c);
+                    cycles[1].push(c);
+                }
+            }
+            let [f0, f1] = cycles;
+            if front {
+                halves[0].push((f0, tag));
+            }
+            if back {
+                halves[1].push((f1, tag));
+            }
+        }
+
+        // islands have to be looked up before the halves are built
+        let islands = if self.attributes().contains(ISLAND_ATTRIBUTE) {
+            Some(
+                self.island_ids()
+                    .into_iter()
+                    .flat_map(|island| {
+                        self.island_vertices(island)
+                            .into_iter()
+                            .map(move |v| (v, island))
+                    })
+                    .collect::<HashMap<_, _>>(),
+            )
+        } else {
+            None
+        };
+
+        let [front, back] = halves.map(|mut faces| {
+            // the boundary edges on the plane, reversed, bound the cross-sections
+            let edges = faces
+                .iter()
+                .flat_map(|(cycle, _)| {
+                    cycle
+                        .iter()
+                        .circular_tuple_windows()
+                        .map(|(a, b)| (*a, *b))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let lookup: HashSet<_> = edges.iter().copied().collect();
+            let mut next: HashMap<Cut<T::V>, Vec<Cut<T::V>>> = HashMap::new();
+            let mut starts = Vec::new();
+            for (a, b) in &edges {
+                if on_plane(a) && on_plane(b) && !lookup.contains(&(*b, *a)) {
+                    next.entry(*b).or_default().push(*a);
+                    starts.push(*b);
+                }
+            }
+            let mut loops = Vec::new();
+            for start in starts {
+                let mut cycle = vec![start];
+                let mut current = start;
+                let closed = loop {
+                    let Some(c) = next.get_mut(&current).and_then(|cs| cs.pop()) else {
+                        break false;
+                    };
+                    if c == start {
+                        break true;
+                    }
+                    cycle.push(c);
+                    current = c;
+                };
+                if closed && cycle.len() >= 3 {
+                    loops.push(cycle);
+                }
+            }
+            // triangulate the cross-sections; `insert_polygon` reverses the order
+            if !loops.is_empty() {
+                let mut section = Self::default();
+                for cycle in &loops {
+                    section.insert_polygon(
+                        cycle
+                            .iter()
+                            .rev()
+                            .map(|c| T::VP::from_pos(*payload(c).pos())),
+                    );
+                }
+                let points = loops
+                    .iter()
+                    .flatten()
+                    .map(|c| (*c, *payload(c).pos()))
+                    .collect::<Vec<_>>();
+                let (is, vs) = section.triangulate_with_holes(
+                    TriangulationAlgorithm::Auto,
+                    &mut TesselationMeta::default(),
+                );
+                let cuts = vs
+                    .iter()
+                    .map(|vp| {
+                        points
+                            .iter()
+                            .min_by(|a, b| {
+                                let (da, db) = (
+                                    (a.1 - *vp.pos()).length_squared(),
+                                    (b.1 - *vp.pos()).length_squared(),
+                                );
+                                da.partial_cmp(&db).unwrap()
+                            })
+                            .expect("The cross-section has vertices")
+                            .0
+                    })
+                    .collect::<Vec<_>>();
+                for t in is.chunks(3) {
+                    faces.push((t.iter().map(|i| cuts[i.index()]).collect(), Some("cap")));
+                }
+            }
+
+            // the vertices of a new mesh are numbered in the order they are added
+            let mut builder = SoupBuilder::<3, T>::new();
+            let mut indices = HashMap::new();
+            let mut sources = Vec::new();
+            for (cycle, tag) in faces {
+                let mut is = Vec::with_capacity(cycle.len());
+                for c in cycle {
+                    let i = *indices.entry(c).or_insert_with(|| {
+                        sources.push(match c {
+                            Cut::Vertex(v) | Cut::Edge(v, _) => v,
+                        });
+                        builder.add_vertex(payload(&c))
+                    });
+                    is.push(i);
+                }
+                if let Some(tag) = tag {
+                    builder.add_tagged_face(is, tag);
+                } else {
+                    builder.add_face(is);
+                }
+            }
+            let mut mesh = builder.build();
+            if let Some(islands) = &islands {
+                for island in mesh.island_ids() {
+                    let source = islands[&sources[island.index()]];
+                    mesh.set_island_payload(island, self.island_payload(source));
+                }
+            }
+            mesh
+        });
+        (front, back)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_split_by_plane() {
+        let cube = Mesh3d64Tagged::cube(2.0);
+        let (top, bottom) = cube.split_by_plane(Vec3::new(0.0, 0.3, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        for (half, volume) in [(&top, 4.0 * 0.7), (&bottom, 4.0 * 1.3)] {
+            assert!(half.check().is_ok());
+            assert!(!half.is_open());
+            assert_eq!(half.islands().len(), 1);
+            assert!(!half.select_by_tag("cap").is_empty());
+            assert!(half.report().volume.unwrap().is_about(volume, 1e-9));
+        }
+        assert!(top.vertices().all(|v| v.pos().y >= 0.3 - 1e-9));
+        assert!(bottom.vertices().all(|v| v.pos().y <= 0.3 + 1e-9));
+
+        // cutting along existing faces and edges doesn't add caps
+        let (top, bottom) = cube.split_by_plane(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(top.num_faces(), 0);
+        assert_eq!(bottom.num_faces(), 6);
+        assert!(bottom.select_by_tag("cap").is_empty());
+
+        // a torus cut horizontally has an annulus as cross-section
+        let profile: Vec<Vec2<f64>> = (0..=8)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / 8.0;
+                Vec2::new(2.0 + 0.5 * a.cos(), 0.5 * a.sin())
+            })
+            .collect();
+        let mut torus = Mesh3d64::default();
+        torus.revolve(&profile, Vec3::new(0.0, 1.0, 0.0), 2.0 * PI, 16);
+        let volume = torus.report().volume.unwrap();
+        let (top, bottom) = torus.split_by_plane(Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        for half in [&top, &bottom] {
+            assert!(half.check().is_ok());
+            assert!(!half.is_open());
+            assert_eq!(half.islands().len(), 1);
+            assert!(half.report().volume.unwrap().is_about(volume / 2.0, 1e-9));
+        }
+
+        // cutting it vertically gives two bent tubes with two caps each
+        let (left, right) =
+            torus.split_by_plane(Vec3::new(0.1, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        for half in [&left, &right] {
+            assert!(half.check().is_ok());
+            assert!(!half.is_open());
+        }
+        assert!(
+            (left.report().volume.unwrap() + right.report().volume.unwrap()).is_about(volume, 1e-9)
+        );
+    }
+
+    #[test]
+    fn test_split_by_plane_islands() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        let mut other = Mesh3d64::cube(1.0);
+        other.translate(&Vec3::new(3.0, 0.0, 0.0));
+        mesh.insert_submesh(&other, None);
+        let islands = mesh.island_ids();
+        mesh.name_island(islands[0], "left")
+            .name_island(islands[1], "right");
+
+        // the plane cuts the left cube and misses the right one
+        let (front, back) =
+            mesh.split_by_plane(Vec3::new(0.2, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        assert_eq!(front.islands().len(), 1);
+        assert_eq!(back.islands().len(), 2);
+        assert_eq!(front.island_payload(front.island_ids()[0]).name, "left");
+        let mut names = back
+            .island_ids()
+            .into_iter()
+            .map(|island| back.island_payload(island).name)
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["left", "right"]);
+    }
+}
@@ -0,0 +1,238 @@
+use super::implicit::closest_point_on_triangle;
+use crate::{
+    math::{Scalar, Vector, Vector3D, Vector3DIteratorExt},
+    mesh::{
+        EdgeBasics, FaceBasics, HalfEdge, MeshBasics, MeshType3D, MeshTypeHalfEdge, VertexBasics,
+    },
+};
+
+/// Methods to trace curves along the surface of a mesh.
+pub trait MeshStreamlines<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshBasics<T>
+{
+    /// Returns the face closest to `p` and the closest point on it.
+    /// Faces are fan-triangulated, so they should be convex.
+    fn closest_face_point(&self, p: T::Vec) -> Option<(T::F, T::Vec)> {
+        let mut best: Option<(T::S, T::F, T::Vec)> = None;
+        for f in self.faces() {
+            let vs = f.vertices(self).map(|v| v.pos()).collect::<Vec<T::Vec>>();
+            for i in 1..vs.len() - 1 {
+                let q = closest_point_on_triangle(p, vs[0], vs[i], vs[i + 1]);
+                let d = q.distance_squared(&p);
+                if best.as_ref().map_or(true, |(b, _, _)| d < *b) {
+                    best = Some((d, f.id(), q));
+                }
+            }
+        }
+        best.map(|(_, f, q)| (f, q))
+    }
+
+    /// Returns the first intersection of the ray from `origin` in direction `dir` with the
+    /// faces of the mesh as the face, the point, and the distance along the ray in units
+    /// of `dir`. Faces are fan-triangulated, so they should be convex.
+    fn raycast(&self, origin: T::Vec, dir: T::Vec) -> Option<(T::F, T::Vec, T::S)> {
+        let mut best: Option<(T::F, T::Vec, T::S)> = None;
+        for f in self.faces() {
+            let vs = f.vertices(self).map(|v| v.pos()).collect::<Vec<T::Vec>>();
+            for i in 1..vs.len() - 1 {
+                // Möller-Trumbore intersection
+                let (e1, e2) = (vs[i] - vs[0], vs[i + 1] - vs[0]);
+                let h = dir.cross(&e2);
+                let det = e1.dot(&h);
+                if det.abs() <= T::S::EPS {
+                    continue;
+                }
+                let s = origin - vs[0];
+                let u = s.dot(&h) / det;
+                let q = s.cross(&e1);
+                let v = dir.dot(&q) / det;
+                if u < T::S::ZERO || v < T::S::ZERO || u + v > T::S::ONE {
+                    continue;
+                }
+                let t = e2.dot(&q) / det;
+                if t > T::S::EPS && best.as_ref().map_or(true, |(_, _, b)| t < *b) {
+                    best = Some((f.id(), origin + dir * t, t));
+                }
+            }
+        }
+        best
+    }
+
+    /// Traces the flow of the `field` along the surface starting at each of the `seeds`,
+    /// e.g., to sweep grooves or ridges along the resulting curves.
+    ///
+    /// Each seed is moved to the closest point on the surface. From there, the field is
+    /// projected onto the plane of the current face and followed in steps of length `step`.
+    /// When a step leaves the face, the curve continues on the neighboring face from the
+    /// point where it crossed the edge. A streamline ends after `max_len`, at the boundary
+    /// of the mesh, or where the projected field vanishes.
+    ///
+    /// Returns one polyline per seed consisting of the points after each step and the
+    /// points where it crosses edges.
+    fn trace_streamlines(
+        &self,
+        field: impl Fn(T::Vec) -> T::Vec,
+        seeds: &[T::Vec],
+        step: T::S,
+        max_len: T::S,
+    ) -> Vec<Vec<T::Vec>> {
+        assert!(step > T::S::ZERO, "the step must be positive");
+        let normal = |f: T::F| {
+            let ps = self
+                .face(f)
+                .vertices(self)
+                .map(|v| v.pos())
+                .collect::<Vec<_>>();
+            ps.into_iter().normal().normalize()
+        };
+        // a step can stop at every edge, so allow a few more iterations than steps
+        let max_iterations = (max_len / step).to_f64().ceil() as usize * 2 + self.num_faces();
+
+        seeds
+            .iter()
+            .map(|seed| {
+                let Some((mut f, mut p)) = self.closest_face_point(*seed) else {
+                    return Vec::new();
+                };
+                let mut line = vec![p];
+                let mut n = normal(f);
+                let mut len = T::S::ZERO;
+                // the edge the curve entered the current face through
+                let mut entered: Option<T::E> = None;
+                for _ in 0..max_iterations {
+                    let remaining = (max_len - len).min(step);
+                    if remaining <= T::S::EPS {
+                        break;
+                    }
+                    let v = field(p);
+                    let v = v - n * n.dot(&v);
+                    let speed = v.length();
+                    if speed <= T::S::EPS {
+                        break;
+                    }
+                    let q = p + v * (remaining / speed);
+
+                    // find the first edge crossed by the step
+                    let mut exit: Option<(T::S, T::E)> = None;
+                    let mut backwards = false;
+                    for e in self.face(f).edges(self) {
+                        let a = e.origin(self).pos();
+                        let b = e.target(self).pos();
+                        let inward = n.cross(&(b - a));
+                        let (sp, sq) = ((p - a).dot(&inward), (q - a).dot(&inward));
+                        if Some(e.id()) == entered {
+                            // the field turns back where the faces meet at a ridge or valley
+                            backwards = sq < sp.min(T::S::ZERO);
+                            continue;
+                        }
+                        if sq >= T::S::ZERO || sp < sq {
+                            continue;
+                        }
+                        let t = (sp / (sp - sq)).max(T::S::ZERO);
+                        let x = p + (q - p) * t;
+                        let along = (x - a).dot(&(b - a)) / (b - a).length_squared();
+                        if along < -T::S::EPS || along > T::S::ONE + T::S::EPS {
+                            continue;
+                        }
+                        if exit.map_or(true, |(s, _)| t < s) {
+                            exit = Some((t, e.id()));
+                        }
+                    }
+
+                    if backwards {
+                        break;
+                    }
+                    let Some((t, e)) = exit else {
+                        len += remaining;
+                        p = q;
+                        line.push(p);
+                        entered = None;
+                        continue;
+                    };
+                    let x = p + (q - p) * t;
+                    len += remaining * t;
+                    if line
+                        .last()
+                        .map_or(true, |l| l.distance_squared(&x) > T::S::EPS)
+                    {
+                        line.push(x);
+                    }
+                    p = x;
+                    let twin = self.edge(e).twin(self);
+                    if twin.is_boundary_self() {
+                        break;
+                    }
+                    if twin.face_id() == f {
+                        break;
+                    }
+                    f = twin.face_id();
+                    n = normal(f);
+                    entered = Some(twin.id());
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn length(line: &[Vec3<f64>]) -> f64 {
+        line.windows(2).map(|w| w[0].distance(&w[1])).sum()
+    }
+
+    #[test]
+    fn test_streamlines_plane() {
+        let plane = Mesh3d64::heightmap(4.0, 4.0, 8, |_, _| 0.0);
+        let field = |_: Vec3<f64>| Vec3::new(1.0, 0.5, 0.0);
+        let seeds = [Vec3::new(0.0, 1.0, 0.3), Vec3::new(-1.0, 0.0, -0.7)];
+
+        // the field is projected onto the plane and followed to the boundary
+        let lines = plane.trace_streamlines(field, &seeds, 0.3, 10.0);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0][0].is_about(&Vec3::new(0.0, 0.0, 0.3), 1e-12));
+        assert!(length(&lines[0]).is_about(2.0, 1e-9));
+        assert!(length(&lines[1]).is_about(3.0, 1e-9));
+        for line in &lines {
+            assert!(line.last().unwrap().x.is_about(2.0, 1e-9));
+            assert!(line.iter().all(|p| p.y.abs() < 1e-12));
+        }
+
+        // or until the maximum length
+        let lines = plane.trace_streamlines(field, &seeds, 0.3, 1.0);
+        assert!(length(&lines[0]).is_about(1.0, 1e-9));
+    }
+
+    #[test]
+    fn test_raycast() {
+        let cube = Mesh3d64::cube(1.0);
+        let (f, p, t) = cube
+            .raycast(Vec3::new(0.1, 0.2, -5.0), Vec3::new(0.0, 0.0, 2.0))
+            .unwrap();
+        assert!(p.is_about(&Vec3::new(0.1, 0.2, -0.5), 1e-12));
+        assert!(t.is_about(2.25, 1e-12));
+        assert!(cube.face(f).normal(&cube).z.is_about(-1.0, 1e-12));
+        assert!(cube
+            .raycast(Vec3::new(0.1, 0.2, -5.0), Vec3::new(0.0, 0.0, -1.0))
+            .is_none());
+    }
+
+    #[test]
+    fn test_streamlines_sphere() {
+        // circle around the y-axis
+        let sphere = Mesh3d64::uv_sphere(1.0, 32, 32);
+        let field = |p: Vec3<f64>| Vec3::new(-p.z, 0.0, p.x);
+        let lines = sphere.trace_streamlines(field, &[Vec3::new(1.0, 0.3, 0.0)], 0.05, 3.0);
+        let line = &lines[0];
+        assert!(length(line).is_about(3.0, 1e-6));
+        // the curve stays on the surface and keeps its height
+        for p in line {
+            let (f, q) = sphere.closest_face_point(*p).unwrap();
+            assert!(p.distance(&q) < 1e-9, "{:?} {:?}", f, p);
+            assert!((p.y - line[0].y).abs() < 0.05);
+        }
+    }
+}
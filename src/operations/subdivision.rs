@@ -1,10 +1,29 @@
-use crate::mesh::{
-    DefaultEdgePayload, EdgeBasics, FaceBasics, HalfEdge, HalfEdgeSemiBuilder, MeshTypeHalfEdge,
-    VertexInterpolator,
+use crate::{
+    math::{HasPosition, Scalar, Vector},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, FaceBasics, HalfEdge,
+        HalfEdgeSemiBuilder, MeshType3D, MeshTypeHalfEdge, SoupBuilder, VertexBasics,
+        VertexInterpolator,
+    },
 };
+use std::collections::HashMap;
+
+/// The classes of geodesic subdivisions, named after the pattern of the triangles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeodesicClass {
+    /// Frequency (b, 0): The edges of the triangles are parallel to the edges of the base face.
+    I,
+
+    /// Frequency (b, b): The edges of the triangles are perpendicular to the edges of the base face.
+    II,
+
+    /// Any other frequency (b, c): The triangles are skewed relative to the base face,
+    /// so the pattern is chiral.
+    III,
+}
 
 /// Describes how to subdivide a mesh.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SubdivisionDescription {
     b: usize,
     c: usize,
@@ -36,6 +55,17 @@ impl SubdivisionDescription {
     pub fn triangulation_number(&self) -> usize {
         self.b * self.b + self.b * self.c + self.c * self.c
     }
+
+    /// Returns the class of the subdivision pattern.
+    pub fn class(&self) -> GeodesicClass {
+        if self.c == 0 {
+            GeodesicClass::I
+        } else if self.b == self.c {
+            GeodesicClass::II
+        } else {
+            GeodesicClass::III
+        }
+    }
 }
 
 /// TODO
@@ -158,6 +188,133 @@ where
 
         self
     }
+
+    /// Subdivides the triangles of the mesh non-uniformly and returns a new mesh.
+    ///
+    /// Each triangle is split into four (like `loop_subdivision`) until it was split
+    /// `level(c)` times, where `c` is the centroid of the resulting triangle. This way,
+    /// the resolution can follow a point of interest, a band, or the curvature.
+    /// `midpoint` creates the vertex payload in the middle of an edge, e.g., by
+    /// projecting it onto a sphere.
+    ///
+    /// Afterwards, triangles next to finer triangles are refined until neighbors differ
+    /// by at most one level and each triangle borders at most one finer triangle.
+    /// Those are split into two transition triangles, so the mesh has no T-junctions.
+    fn adaptive_subdivision(
+        &self,
+        level: impl Fn(T::Vec) -> usize,
+        midpoint: impl Fn(&T::VP, &T::VP) -> T::VP,
+    ) -> Self
+    where
+        T: MeshType3D<Mesh = Self>,
+        T::FP: DefaultFacePayload,
+    {
+        let mut vps: Vec<T::VP> = Vec::new();
+        let mut ids = HashMap::new();
+        let mut tris: Vec<([usize; 3], usize)> = Vec::new();
+        for f in self.faces() {
+            let vs = f
+                .vertices(self)
+                .map(|v| {
+                    *ids.entry(v.id()).or_insert_with(|| {
+                        vps.push(v.payload().clone());
+                        vps.len() - 1
+                    })
+                })
+                .collect::<Vec<_>>();
+            assert!(
+                vs.len() == 3,
+                "adaptive subdivision requires a triangle mesh"
+            );
+            tris.push(([vs[0], vs[1], vs[2]], 0));
+        }
+
+        // midpoints of all edges that were split so far
+        let mut mids = HashMap::new();
+        let key = |a: usize, b: usize| (a.min(b), a.max(b));
+        type Mids = HashMap<(usize, usize), usize>;
+        let split = |vps: &mut Vec<T::VP>, mids: &mut Mids, [a, b, c]: [usize; 3], d: usize| {
+            let mut mid = |a: usize, b: usize| {
+                *mids.entry(key(a, b)).or_insert_with(|| {
+                    vps.push(midpoint(&vps[a], &vps[b]));
+                    vps.len() - 1
+                })
+            };
+            let (ab, bc, ca) = (mid(a, b), mid(b, c), mid(c, a));
+            [
+                ([a, ab, ca], d + 1),
+                ([ab, b, bc], d + 1),
+                ([ca, bc, c], d + 1),
+                ([ab, bc, ca], d + 1),
+            ]
+        };
+
+        // refine until each triangle reached its level
+        let centroid = |vps: &Vec<T::VP>, t: [usize; 3]| {
+            T::Vec::stable_sum(t.iter().map(|&i| *vps[i].pos())) / T::S::THREE
+        };
+        loop {
+            let mut next = Vec::with_capacity(tris.len());
+            for (t, d) in tris.iter() {
+                if *d < level(centroid(&vps, *t)) {
+                    next.extend(split(&mut vps, &mut mids, *t, *d));
+                } else {
+                    next.push((*t, *d));
+                }
+            }
+            if next.len() == tris.len() {
+                break;
+            }
+            tris = next;
+        }
+
+        // balance the levels of neighboring triangles
+        loop {
+            let mut next = Vec::with_capacity(tris.len());
+            for (t, d) in tris.iter() {
+                let edges = [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])];
+                let hanging = edges
+                    .iter()
+                    .filter(|(a, b)| mids.contains_key(&key(*a, *b)))
+                    .count();
+                let deep = edges.iter().any(|(a, b)| {
+                    mids.get(&key(*a, *b)).is_some_and(|m| {
+                        mids.contains_key(&key(*a, *m)) || mids.contains_key(&key(*m, *b))
+                    })
+                });
+                if hanging >= 2 || deep {
+                    next.extend(split(&mut vps, &mut mids, *t, *d));
+                } else {
+                    next.push((*t, *d));
+                }
+            }
+            if next.len() == tris.len() {
+                break;
+            }
+            tris = next;
+        }
+
+        let mut builder = SoupBuilder::<3, T>::new();
+        for vp in vps {
+            builder.add_vertex(vp);
+        }
+        for ([a, b, c], _) in tris {
+            // close the remaining T-junctions with transition triangles
+            if let Some(&m) = mids.get(&key(a, b)) {
+                builder.add_face([a, m, c]);
+                builder.add_face([m, b, c]);
+            } else if let Some(&m) = mids.get(&key(b, c)) {
+                builder.add_face([b, m, a]);
+                builder.add_face([m, c, a]);
+            } else if let Some(&m) = mids.get(&key(c, a)) {
+                builder.add_face([c, m, b]);
+                builder.add_face([m, a, b]);
+            } else {
+                builder.add_face([a, b, c]);
+            }
+        }
+        builder.build()
+    }
 }
 
 /*
@@ -182,4 +339,4 @@ mod tests {
         assert!(mesh.check().is_ok());
     }
 }
-*/
\ No newline at end of file
+*/
@@ -0,0 +1,221 @@
+use crate::{
+    math::Vector,
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, FaceBasics, HalfEdge, MeshBasics,
+        MeshBuilder, MeshHalfEdgeBuilder, MeshType3D, MeshTypeHalfEdge, VertexBasics,
+    },
+    util::Selection,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Copies the faces `faces` of `src` with all payloads into `dst`.
+/// Returns the new faces and the new ids of the copied vertices.
+fn copy_faces<T: MeshTypeHalfEdge>(
+    src: &T::Mesh,
+    faces: &[T::F],
+    dst: &mut T::Mesh,
+) -> (Vec<T::F>, HashMap<T::V, T::V>)
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    let mut index: HashMap<T::V, usize> = HashMap::new();
+    let mut vertices = Vec::new();
+    let cycles = faces
+        .iter()
+        .map(|f| {
+            // start at the origin of the representative edge so the new halfedges line up
+            src.face(*f)
+                .edges(src)
+                .map(|e| {
+                    *index.entry(e.origin_id()).or_insert_with(|| {
+                        vertices.push(e.origin_id());
+                        vertices.len() - 1
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let new_faces = dst.insert_indexed_faces(
+        vertices.iter().map(|v| src.vertex(*v).payload().clone()),
+        cycles,
+    );
+
+    let mut map = HashMap::new();
+    for (f, g) in faces.iter().zip(new_faces.iter()) {
+        *dst.face_mut(*g).payload_mut() = *src.face(*f).payload();
+        let pairs = src
+            .face(*f)
+            .edges(src)
+            .zip(dst.face(*g).edge_ids(dst).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for (e, e2) in pairs {
+            map.insert(e.origin_id(), dst.edge(e2).origin_id());
+            *dst.edge_mut(e2).payload_mut() = e.payload().clone();
+            let t2 = dst.edge(e2).twin_id();
+            if dst.edge(t2).is_boundary_self() {
+                *dst.edge_mut(t2).payload_mut() = e.twin(src).payload().clone();
+            }
+        }
+    }
+    (new_faces, map)
+}
+
+/// Methods to copy parts of meshes into other meshes, e.g., for cut/copy/paste
+/// workflows or for reusing modules in generators.
+pub trait MeshSubmesh<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshHalfEdgeBuilder<T> + MeshBuilder<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Returns a new mesh containing copies of the given faces and their vertices and edges
+    /// including their payloads. Edges and vertices without a selected face are not copied.
+    fn extract_submesh(&self, faces: &[T::F]) -> Self {
+        let mut res = Self::default();
+        copy_faces::<T>(self, faces, &mut res);
+        res
+    }
+
+    /// Returns a new mesh without the selected faces. Vertices and edges that only
+    /// belonged to selected faces are removed as well. The remaining elements keep
+    /// their payloads, but not necessarily their ids.
+    fn without_faces(&self, selection: &Selection<T::F>) -> Self {
+        let kept = self
+            .face_ids()
+            .filter(|f| !selection.contains(*f))
+            .collect::<Vec<_>>();
+        self.extract_submesh(&kept)
+    }
+
+    /// Inserts a copy of all faces of `sub` including their payloads into the mesh.
+    /// Returns the new faces in the order of `sub.face_ids()`.
+    ///
+    /// If `weld_boundary` is `Some(eps)`, each boundary loop of the inserted faces is
+    /// stitched to a boundary loop of the mesh with the same number of vertices if all
+    /// of them are within `eps` of each other. The stitched vertices keep the payloads
+    /// of the mesh.
+    fn insert_submesh(&mut self, sub: &Self, weld_boundary: Option<T::S>) -> Vec<T::F> {
+        let faces = sub.face_ids().collect::<Vec<_>>();
+        let (new_faces, map) = copy_faces::<T>(sub, &faces, self);
+        let Some(eps) = weld_boundary else {
+            return new_faces;
+        };
+
+        let inserted: HashSet<T::V> = map.values().copied().collect();
+        let mut visited = HashSet::new();
+        let (mut old_loops, mut new_loops) = (Vec::new(), Vec::new());
+        for e in self.edges() {
+            if !e.is_boundary_self() || visited.contains(&e.id()) {
+                continue;
+            }
+            let chain = self.edges_from(e.id()).map(|e| e.id()).collect::<Vec<_>>();
+            visited.extend(chain.iter().copied());
+            if inserted.contains(&e.origin_id()) {
+                new_loops.push(chain);
+            } else {
+                old_loops.push(chain);
+            }
+        }
+
+        let pos = |e: T::E| self.edge(e).origin(self).pos();
+        let mut stitches = Vec::new();
+        for lb in new_loops {
+            // walking forward on the old loop matches walking backward on the new one
+            let n = lb.len();
+            let found = old_loops.iter().enumerate().find_map(|(k, la)| {
+                if la.len() != n {
+                    return None;
+                }
+                (0..n)
+                    .find(|s| {
+                        (0..n).all(|i| {
+                            let a = self.edge(la[(s + i) % n]).next_id();
+                            pos(a).distance(&pos(lb[(n - i) % n])) <= eps
+                        })
+                    })
+                    .map(|s| (k, la[s]))
+            });
+            if let Some((k, a)) = found {
+                stitches.push((a, lb[0]));
+                old_loops.swap_remove(k);
+            }
+        }
+        for (a, b) in stitches {
+            self.stitch_boundary_loops(a, b);
+        }
+        new_faces
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_extract_submesh() {
+        let mut cube = Mesh3d64Tagged::cube(1.0);
+        let faces = cube.face_ids().collect::<Vec<_>>();
+        cube.tag_faces([faces[0]], "first");
+
+        let one = cube.extract_submesh(&faces[..1]);
+        assert!(one.check().is_ok());
+        assert_eq!(one.num_faces(), 1);
+        assert_eq!(one.num_vertices(), 4);
+        assert_eq!(one.select_by_tag("first").len(), 1);
+        assert!(one
+            .face(one.face_ids().next().unwrap())
+            .vertices(&one)
+            .map(|v| v.pos())
+            .eq(cube.face(faces[0]).vertices(&cube).map(|v| v.pos())));
+
+        let open = cube.extract_submesh(&faces[1..]);
+        assert!(open.check().is_ok());
+        assert!(open.is_open());
+        assert_eq!(open.num_faces(), 5);
+        assert_eq!(open.num_vertices(), 8);
+        assert!(open.select_by_tag("first").is_empty());
+
+        // deleting a selection keeps the rest
+        let top = cube.select_faces(|f| f.centroid(&cube).y > 0.4);
+        let rest = cube.without_faces(&top);
+        assert!(rest.check().is_ok());
+        assert_eq!(rest.num_faces(), 5);
+        assert_eq!(rest.num_vertices(), 8);
+        let both = cube.grow_faces(&top);
+        assert_eq!(cube.without_faces(&both).num_vertices(), 4);
+    }
+
+    #[test]
+    fn test_insert_submesh() {
+        let cube = Mesh3d64::cube(1.0);
+        let faces = cube.face_ids().collect::<Vec<_>>();
+        let lid = cube.extract_submesh(&faces[..1]);
+        let bottom = cube.extract_submesh(&faces[1..]);
+
+        // pasting without welding leaves a seam
+        let mut mesh = bottom.clone();
+        let new = mesh.insert_submesh(&lid, None);
+        assert_eq!(new.len(), 1);
+        assert!(mesh.check().is_ok());
+        assert!(mesh.is_open());
+        assert_eq!(mesh.num_vertices(), 12);
+
+        // welding closes the box again
+        let mut mesh = bottom.clone();
+        mesh.insert_submesh(&lid, Some(1e-6));
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.num_vertices(), 8);
+        assert_eq!(mesh.num_faces(), 6);
+
+        // a lid that doesn't fit isn't welded
+        let mut moved = lid.clone();
+        moved.translate(&Vec3::new(0.0, 0.1, 0.0));
+        let mut mesh = bottom.clone();
+        mesh.insert_submesh(&moved, Some(1e-6));
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_vertices(), 12);
+    }
+}
@@ -0,0 +1,365 @@
+use crate::{
+    math::{HasPosition, Scalar, TransformTrait, Vector, Vector3D},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, Face3d, FaceBasics, FacePayload,
+        HalfEdge, HalfEdgeMesh, MeshSelection, MeshType3D, MeshTypeHalfEdge, SoupBuilder,
+        VertexBasics,
+    },
+    util::Selection,
+};
+use std::collections::HashMap;
+
+/// Turns flat meshes into solids and extrudes parts of meshes.
+pub trait MeshThicken<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    HalfEdgeMesh<T> + MeshSelection<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Creates a watertight solid from a flat mesh by extruding it by `depth` along its normal.
+    ///
+    /// The mesh may consist of multiple faces sharing edges and may have holes.
+    /// Each face gets a copy on the top and a reversed copy on the bottom, while walls
+    /// are only created along the boundary, so shared interior edges don't produce walls.
+    /// If `bevel` is positive, the rim of the top is chamfered by moving the boundary
+    /// of the top inwards by `bevel` and inserting a ring `bevel` below the top.
+    ///
+    /// Assumes that each boundary vertex lies on exactly one boundary loop.
+    fn thicken(&self, depth: T::S, bevel: T::S) -> Self {
+        assert!(depth > T::S::ZERO, "the depth must be positive");
+        assert!(
+            bevel >= T::S::ZERO && bevel < depth,
+            "the bevel must be non-negative and smaller than the depth"
+        );
+
+        let normal = T::Vec::stable_sum(self.faces().map(|f| Face3d::normal(f, self))).normalize();
+        let up = normal * depth;
+
+        // the outgoing boundary half-edge of each boundary vertex
+        let boundary: HashMap<T::V, T::E> = self
+            .edges()
+            .filter(|e| e.is_boundary_self())
+            .map(|e| (e.origin_id(), e.id()))
+            .collect();
+
+        let mut builder = SoupBuilder::<3, T>::new();
+        let mut bottom = HashMap::new();
+        let mut top = HashMap::new();
+        let mut ring = HashMap::new();
+        for v in self.vertices() {
+            let p: T::Vec = v.pos();
+            let vp = |pos: T::Vec| {
+                let mut vp = v.payload().clone();
+                vp.set_pos(pos);
+                vp
+            };
+            bottom.insert(v.id(), builder.add_vertex(vp(p)));
+            let Some(&e) = boundary.get(&v.id()).filter(|_| bevel > T::S::ZERO) else {
+                top.insert(v.id(), builder.add_vertex(vp(p + up)));
+                continue;
+            };
+
+            // the face lies to the right of the boundary half-edges
+            let edge = self.edge(e);
+            let prev = self.edge(edge.prev_id());
+            let inward = |a: T::Vec, b: T::Vec| (b - a).cross(&normal).normalize();
+            let n0 = inward(prev.origin(self).pos(), p);
+            let n1 = inward(p, edge.target(self).pos());
+            // miter the inset such that the bevel is equally wide along both edges
+            let bisector = (n0 + n1).normalize();
+            let inset = bisector * (bevel / bisector.dot(&n0));
+            ring.insert(v.id(), builder.add_vertex(vp(p + normal * (depth - bevel))));
+            top.insert(v.id(), builder.add_vertex(vp(p + up + inset)));
+        }
+
+        for f in self.faces() {
+            let vs = f.vertex_ids(self).collect::<Vec<_>>();
+            builder.add_tagged_face(vs.iter().rev().map(|v| bottom[v]), "cap");
+            builder.add_tagged_face(vs.iter().map(|v| top[v]), "cap");
+        }
+
+        for e in self.edges().filter(|e| e.is_boundary_self()) {
+            let (a, c) = (e.origin_id(), e.target_id(self));
+            if let (Some(&ra), Some(&rc)) = (ring.get(&a), ring.get(&c)) {
+                builder.add_tagged_face([bottom[&c], bottom[&a], ra, rc], "side-wall");
+                builder.add_tagged_face([rc, ra, top[&a], top[&c]], "bevel-ring");
+            } else {
+                builder.add_tagged_face([bottom[&c], bottom[&a], top[&a], top[&c]], "side-wall");
+            }
+        }
+
+        builder.build()
+    }
+
+    /// Creates a stepped solid from a flat mesh by extruding each face along the normal
+    /// of the mesh by its own positive `height`, e.g., city blocks from a zoning map.
+    ///
+    /// Faces sharing an edge are welded where their heights are equal. Walls are only
+    /// created along the boundary and between neighboring faces with different heights,
+    /// and they are split at the heights of all other faces around their vertical edges,
+    /// so the result is a closed manifold without T-junctions. Where separate parts of
+    /// the solid only touch along a vertical edge, e.g., in a checkerboard pattern,
+    /// each part gets its own copy of that edge.
+    fn extrude_heights(&self, height: impl Fn(&T::Face) -> T::S) -> Self {
+        let heights: HashMap<T::F, T::S> = self.faces().map(|f| (f.id(), height(f))).collect();
+        assert!(
+            heights.values().all(|h| *h > T::S::ZERO),
+            "the heights must be positive"
+        );
+        let normal = T::Vec::stable_sum(self.faces().map(|f| Face3d::normal(f, self))).normalize();
+        let face_height = |e: &T::Edge| {
+            if e.is_boundary_self() {
+                T::S::ZERO
+            } else {
+                heights[&e.face_id()]
+            }
+        };
+
+        // The heights present at each vertex and, for each height, the maximal fans of
+        // adjacent faces reaching at least that height. Each fan gets its own vertex.
+        let mut levels: HashMap<T::V, Vec<T::S>> = HashMap::new();
+        let mut fans: HashMap<(T::V, usize, T::F), usize> = HashMap::new();
+        for v in self.vertices() {
+            let around = v.edges_out(self).collect::<Vec<_>>();
+            let mut ls: Vec<T::S> = around.iter().map(face_height).collect();
+            ls.push(T::S::ZERO);
+            ls.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            ls.dedup();
+            for (l, &level) in ls.iter().enumerate() {
+                let inside = |i: usize| {
+                    let e = &around[i % around.len()];
+                    !e.is_boundary_self() && face_height(e) >= level
+                };
+                // start right after a gap such that no fan wraps around
+                let start = (0..around.len()).find(|&i| !inside(i)).unwrap_or(0);
+                let mut fan = start;
+                for i in start..start + around.len() {
+                    if !inside(i) {
+                        fan = i + 1;
+                        continue;
+                    }
+                    fans.insert((v.id(), l, around[i % around.len()].face_id()), fan);
+                }
+            }
+            levels.insert(v.id(), ls);
+        }
+
+        let mut builder = SoupBuilder::<3, T>::new();
+        let mut corners = HashMap::new();
+        let mut corner = |v: T::V, l: usize, f: T::F| {
+            let key = (v, l, fans[&(v, l, f)]);
+            *corners.entry(key).or_insert_with(|| {
+                let vertex = self.vertex(v);
+                let mut vp = vertex.payload().clone();
+                vp.set_pos(vertex.pos::<T::S, 3, T::Vec>() + normal * levels[&v][l]);
+                builder.add_vertex(vp)
+            })
+        };
+        let level = |v: T::V, h: T::S| levels[&v].iter().position(|l| *l == h).unwrap();
+
+        let mut polygons = Vec::new();
+        for f in self.faces() {
+            let h = heights[&f.id()];
+            let vs = f.vertex_ids(self).collect::<Vec<_>>();
+            let bottom = vs.iter().rev().map(|&v| corner(v, 0, f.id()));
+            polygons.push((bottom.collect::<Vec<_>>(), "cap"));
+            let top = vs.iter().map(|&v| corner(v, level(v, h), f.id()));
+            polygons.push((top.collect::<Vec<_>>(), "cap"));
+        }
+
+        for e in self.edges().filter(|e| !e.is_boundary_self()) {
+            let (high, low) = (face_height(e), face_height(&e.twin(self)));
+            if high <= low {
+                continue;
+            }
+            // the wall faces the lower neighbor and steps down along `a` and up along `b`
+            let (a, b, f) = (e.origin_id(), e.target_id(self), e.face_id());
+            let (a_high, a_low) = (level(a, high), level(a, low));
+            let (b_high, b_low) = (level(b, high), level(b, low));
+            let wall = std::iter::once((b, b_high))
+                .chain((a_low..=a_high).rev().map(|l| (a, l)))
+                .chain((b_low..b_high).map(|l| (b, l)))
+                .map(|(v, l)| corner(v, l, f));
+            polygons.push((wall.collect::<Vec<_>>(), "side-wall"));
+        }
+
+        for (polygon, tag) in polygons {
+            builder.add_tagged_face(polygon, tag);
+        }
+        builder.build()
+    }
+
+    /// Extrudes the selected faces as one region, i.e., moves a copy of them by
+    /// `transform` and connects the copy to the rest of the mesh with a wall of quads
+    /// along the boundary of the selection. Faces that share an edge stay connected.
+    ///
+    /// Returns a new mesh where the faces keep their tags and the walls are tagged as
+    /// "side-wall". Like in most modeling tools, the selected faces are moved rather than
+    /// copied, so extruding all faces of an open mesh leaves the walls open at the bottom.
+    fn extrude_selection(&self, selection: &Selection<T::F>, transform: T::Trans) -> Self {
+        let mut builder = SoupBuilder::<3, T>::new();
+        let mut corners = HashMap::new();
+        let mut corner = |v: T::V, moved: bool| {
+            *corners.entry((v, moved)).or_insert_with(|| {
+                let vertex = self.vertex(v);
+                let mut vp = vertex.payload().clone();
+                if moved {
+                    vp.set_pos(transform.apply(vertex.pos()));
+                }
+                builder.add_vertex(vp)
+            })
+        };
+
+        let mut polygons = Vec::new();
+        for f in self.faces() {
+            let moved = selection.contains(f.id());
+            let cycle = f.vertex_ids(self).map(|v| corner(v, moved));
+            polygons.push((cycle.collect::<Vec<_>>(), f.payload().tag()));
+        }
+        for e in self.face_selection_boundary(selection).iter() {
+            let (a, b) = (self.edge(e).origin_id(), self.edge(e).target_id(self));
+            let wall = [
+                corner(a, false),
+                corner(b, false),
+                corner(b, true),
+                corner(a, true),
+            ];
+            polygons.push((wall.to_vec(), Some("side-wall")));
+        }
+
+        for (polygon, tag) in polygons {
+            if let Some(tag) = tag {
+                builder.add_tagged_face(polygon, tag);
+            } else {
+                builder.add_face(polygon);
+            }
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    /// A 3x3 grid of unit squares with the center square missing.
+    fn frame() -> Mesh3d64 {
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        for y in 0..4 {
+            for x in 0..4 {
+                builder.add_vertex(VertexPayloadPNU::from_pos(Vec3::new(
+                    x as f64, y as f64, 0.0,
+                )));
+            }
+        }
+        for y in 0..3 {
+            for x in 0..3 {
+                if x == 1 && y == 1 {
+                    continue;
+                }
+                let i = y * 4 + x;
+                builder.add_face([i, i + 1, i + 5, i + 4]);
+            }
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_thicken() {
+        let flat = frame();
+        assert_eq!(flat.islands().len(), 1);
+        let solid = flat.thicken(0.5, 0.0);
+        assert!(solid.check().is_ok());
+        assert!(!solid.is_open());
+        // 2 * 8 caps, 12 outer and 4 inner walls
+        assert_eq!(solid.num_faces(), 16 + 12 + 4);
+        assert!((solid.report().volume.unwrap() - 8.0 * 0.5).abs() < 1e-9);
+
+        let beveled = flat.thicken(0.5, 0.1);
+        assert!(beveled.check().is_ok());
+        assert!(!beveled.is_open());
+        assert_eq!(beveled.num_faces(), 16 + 2 * 16);
+        assert!(beveled.report().volume.unwrap() < 8.0 * 0.5);
+        assert!(beveled.report().volume.unwrap() > 8.0 * 0.4);
+    }
+
+    #[test]
+    fn test_extrude_heights() {
+        // the corners of the frame are towers, the rest is a lower wall
+        let flat = frame();
+        let city = flat.extrude_heights(|f| {
+            let c = f.centroid(&flat);
+            if (c.x - 1.5).abs() > 0.5 && (c.y - 1.5).abs() > 0.5 {
+                2.0
+            } else {
+                1.0
+            }
+        });
+        assert!(city.check().is_ok());
+        assert!(!city.is_open());
+        assert!((city.report().volume.unwrap() - (4.0 * 2.0 + 4.0 * 1.0)).abs() < 1e-9);
+        // 2 * 8 caps, 12 outer and 4 inner walls, and 8 steps between the towers and the wall
+        assert_eq!(city.num_faces(), 16 + 12 + 4 + 8);
+
+        // equal heights give the same solid as thickening
+        let slab = flat.extrude_heights(|_| 0.5);
+        assert_eq!(slab.num_faces(), flat.thicken(0.5, 0.0).num_faces());
+        assert!((slab.report().volume.unwrap() - 8.0 * 0.5).abs() < 1e-9);
+
+        // towers touching only along a vertical edge in a checkerboard pattern
+        let mut builder = SoupBuilder::<3, MeshType3d64PNU>::new();
+        for y in 0..3 {
+            for x in 0..3 {
+                builder.add_vertex(VertexPayloadPNU::from_pos(Vec3::new(
+                    x as f64, y as f64, 0.0,
+                )));
+            }
+        }
+        for i in [0, 1, 3, 4] {
+            builder.add_face([i, i + 1, i + 4, i + 3]);
+        }
+        let board: Mesh3d64 = builder.build();
+        let solid = board.extrude_heights(|f| {
+            let c = f.centroid(&board);
+            if (c.x < 1.0) == (c.y < 1.0) {
+                3.0
+            } else {
+                1.0
+            }
+        });
+        assert!(solid.check().is_ok());
+        assert!(!solid.is_open());
+        assert!((solid.report().volume.unwrap() - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_extrude_selection() {
+        let up = NdAffine::from_translation(Vec3::new(0.0, 1.0, 0.0));
+        let cube = Mesh3d64::cube(1.0);
+        let top = cube.select_faces(|f| f.centroid(&cube).y > 0.4);
+        let tower = cube.extrude_selection(&top, up);
+        assert!(tower.check().is_ok());
+        assert!(!tower.is_open());
+        assert_eq!(tower.num_faces(), 10);
+        assert_eq!(tower.num_vertices(), 12);
+        assert!((tower.report().volume.unwrap() - 2.0).abs() < 1e-9);
+
+        // adjacent faces are extruded as one region
+        let corner = cube.grow_faces(&top);
+        let side = cube.select_faces(|f| f.centroid(&cube).x > 0.4);
+        let region = &top | &side;
+        assert!(region.is_subset(&corner));
+        let extruded = cube.extrude_selection(&region, up);
+        assert!(extruded.check().is_ok());
+        assert!(!extruded.is_open());
+        assert_eq!(extruded.num_faces(), 6 + 6);
+        assert_eq!(extruded.num_vertices(), 8 + 6);
+
+        // moving all faces of an open mesh leaves the walls open at the bottom
+        let lifted = frame().extrude_selection(&frame().select_faces(|_| true), up);
+        assert!(lifted.check().is_ok());
+        assert!(lifted.is_open());
+        assert_eq!(lifted.num_faces(), 8 + 16);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    math::{HasPosition, VectorIteratorExt},
+    mesh::{
+        DefaultEdgePayload, FaceBasics, MeshBasics, MeshHalfEdgeBuilder, MeshType3D,
+        MeshTypeHalfEdge, Triangulation, VertexBasics,
+    },
+    tesselate::{triangulate_face, TesselationMeta, TriangulationAlgorithm},
+};
+use std::collections::HashMap;
+
+/// Returns the index of the face in `faces` that has both `a` and `b` on its boundary
+/// but no edge between them.
+fn face_with_diagonal<T: MeshType3D>(mesh: &T::Mesh, faces: &[T::F], a: T::V, b: T::V) -> usize {
+    faces
+        .iter()
+        .position(|f| {
+            let vs = mesh.face(*f).vertex_ids(mesh).collect::<Vec<_>>();
+            let n = vs.len();
+            let i = vs.iter().position(|v| *v == a);
+            let j = vs.iter().position(|v| *v == b);
+            matches!((i, j), (Some(i), Some(j)) if (i + 1) % n != j && (j + 1) % n != i)
+        })
+        .expect("the diagonal is not inside any of the faces")
+}
+
+/// Methods to split faces into triangles in the mesh itself.
+///
+/// Unlike `Triangulateable::triangulate`, which only generates an index buffer, these
+/// operations change the topology of the mesh and insert actual triangle faces.
+pub trait MeshSplitFaces<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshHalfEdgeBuilder<T>
+where
+    T::EP: DefaultEdgePayload,
+{
+    /// Inserts a new vertex at the centroid of the face and connects it to all vertices
+    /// of the face, i.e., replaces the face with a fan of triangles sharing the new vertex.
+    /// The payloads of the triangles are copies of the payload of the face.
+    ///
+    /// Returns the new vertex and the triangles in the order of the face's vertices.
+    fn poke_face(&mut self, f: T::F) -> (T::V, Vec<T::F>) {
+        let vs = self.face(f).vertex_ids(self).collect::<Vec<_>>();
+        let center: T::Vec = self.face(f).vertices(self).map(|v| v.pos()).stable_mean();
+
+        // the first triangle is created together with the center
+        let (mut rest, first) = self.split_face_default(f, vs[0], vs[1], [T::VP::from_pos(center)]);
+        let c = self
+            .face(first)
+            .vertex_ids(self)
+            .find(|v| !vs.contains(v))
+            .unwrap();
+
+        // cut off one triangle after the other
+        let mut faces = vec![first];
+        for v in &vs[2..] {
+            let (left, right) = self.split_face_default(rest, c, *v, []);
+            faces.push(right);
+            rest = left;
+        }
+        faces.push(rest);
+        (c, faces)
+    }
+
+    /// Replaces the face with triangles computed by the given triangulation `algorithm`.
+    /// No vertices are inserted. The payloads of the triangles are copies of the payload
+    /// of the face.
+    ///
+    /// Returns the triangles in no particular order.
+    fn triangulate_face_in_place(
+        &mut self,
+        f: T::F,
+        algorithm: TriangulationAlgorithm,
+    ) -> Vec<T::F> {
+        let mut indices = Vec::new();
+        let mut tri = Triangulation::new(&mut indices);
+        triangulate_face::<T>(
+            self.face(f),
+            self,
+            &mut tri,
+            algorithm,
+            &mut TesselationMeta::default(),
+        );
+
+        // the diagonals are the edges that are shared by two triangles
+        let mut counts: HashMap<(T::V, T::V), usize> = HashMap::new();
+        for t in indices.chunks_exact(3) {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                *counts.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        let mut diagonals = counts
+            .into_iter()
+            .filter(|(_, c)| *c == 2)
+            .map(|(d, _)| d)
+            .collect::<Vec<_>>();
+        diagonals.sort();
+
+        let mut faces = vec![f];
+        for (a, b) in diagonals {
+            let i = face_with_diagonal::<T>(self, &faces, a, b);
+            let (left, right) = self.split_face_default(faces[i], a, b, []);
+            faces[i] = left;
+            faces.push(right);
+        }
+        faces
+    }
+
+    /// Triangulates all faces of the mesh using `triangulate_face_in_place`.
+    fn triangulate_in_place(&mut self, algorithm: TriangulationAlgorithm) -> &mut Self {
+        let faces = self.face_ids().collect::<Vec<_>>();
+        for f in faces {
+            if self.face(f).num_vertices(self) > 3 {
+                self.triangulate_face_in_place(f, algorithm);
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_poke_face() {
+        let mut mesh = Mesh3d64::regular_prism(1.0, 1.0, 6);
+        let f = mesh
+            .faces()
+            .find(|f| f.num_vertices(&mesh) == 6)
+            .unwrap()
+            .id();
+        let normal = mesh.face(f).normal(&mesh).normalize();
+        let (c, faces) = mesh.poke_face(f);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(faces.len(), 6);
+        assert_eq!(mesh.vertex(c).vertices(&mesh).count(), 6);
+        for g in faces {
+            assert_eq!(mesh.face(g).num_vertices(&mesh), 3);
+            assert!(mesh.face(g).vertex_ids(&mesh).any(|v| v == c));
+            assert!(mesh
+                .face(g)
+                .normal(&mesh)
+                .normalize()
+                .is_about(&normal, 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_triangulate_face_in_place() {
+        let mut mesh = Mesh3d64::regular_prism(1.0, 1.0, 8);
+        let vertices = mesh.num_vertices();
+        for algorithm in [
+            TriangulationAlgorithm::Delaunay,
+            TriangulationAlgorithm::MinWeight,
+        ] {
+            let mut mesh = mesh.clone();
+            mesh.triangulate_in_place(algorithm);
+            assert!(mesh.check().is_ok());
+            assert!(!mesh.is_open());
+            assert_eq!(mesh.num_vertices(), vertices);
+            // 8 side walls split into 2 triangles and 2 caps split into 6 triangles
+            assert_eq!(mesh.num_faces(), 8 * 2 + 2 * 6);
+            assert!(mesh.faces().all(|f| f.num_vertices(&mesh) == 3));
+        }
+
+        let f = mesh.face_ids().next().unwrap();
+        let n = mesh.face(f).num_vertices(&mesh);
+        let faces = mesh.triangulate_face_in_place(f, TriangulationAlgorithm::Fan);
+        assert_eq!(faces.len(), n - 2);
+        assert!(mesh.check().is_ok());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector3D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshTrait, MeshType3D, SoupBuilder},
+};
+
+/// A trait for creating arrows and coordinate frame gizmos.
+pub trait MakeArrow<T: MeshType3D<Mesh = Self>>: MeshTrait<T = T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Inserts a closed arrow from `from` to `to` consisting of a
+    /// cylindrical shaft and a conical head with `n` segments each.
+    fn insert_arrow(
+        &mut self,
+        from: T::Vec,
+        to: T::Vec,
+        shaft_radius: T::S,
+        head_radius: T::S,
+        head_len: T::S,
+        n: usize,
+    ) -> Vec<T::F> {
+        assert!(n >= 3);
+        let length = from.distance(&to);
+        assert!(shaft_radius > T::S::ZERO && head_radius > shaft_radius);
+        assert!(head_len > T::S::ZERO && head_len < length);
+
+        // right-handed frame with `y` along the arrow
+        let y = (to - from) / length;
+        let axis = if y.z().abs() < T::S::HALF {
+            T::Vec::from_xyz(T::S::ZERO, T::S::ZERO, T::S::ONE)
+        } else {
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)
+        };
+        let x = y.cross(&axis).normalize();
+        let z = x.cross(&y);
+
+        let mut builder = SoupBuilder::<3, T>::new();
+        let shoulder = length - head_len;
+        for (r, h) in [
+            (shaft_radius, T::S::ZERO),
+            (shaft_radius, shoulder),
+            (head_radius, shoulder),
+        ] {
+            for i in 0..n {
+                let a = T::S::TWO * T::S::PI * T::S::from_usize(i) / T::S::from_usize(n);
+                let p = from + x * (r * a.cos()) + y * h - z * (r * a.sin());
+                builder.add_vertex(T::VP::from_pos(p));
+            }
+        }
+        let apex = builder.add_vertex(T::VP::from_pos(to));
+
+        builder.add_tagged_face((0..n).rev(), "cap");
+        // the shaft and the bottom of the head
+        for (l, tag) in [(0, "side-wall"), (1, "head")] {
+            for j in 0..n {
+                let k = (j + 1) % n;
+                builder.add_tagged_face(
+                    [l * n + j, l * n + k, (l + 1) * n + k, (l + 1) * n + j],
+                    tag,
+                );
+            }
+        }
+        for j in 0..n {
+            builder.add_tagged_face([2 * n + j, 2 * n + (j + 1) % n, apex], "head");
+        }
+        builder.insert_into(self)
+    }
+
+    /// Creates an arrow of the given `length` pointing along the y-axis starting at the origin.
+    fn arrow(length: T::S, shaft_radius: T::S, head_radius: T::S, head_len: T::S) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_arrow(
+            T::Vec::zero(),
+            T::Vec::from_xyz(T::S::ZERO, length, T::S::ZERO),
+            shaft_radius,
+            head_radius,
+            head_len,
+            16,
+        );
+        mesh
+    }
+
+    /// Creates a gizmo visualizing the coordinate frame with three unit arrows
+    /// along the x-, y-, and z-axis. The arrows are inserted in this order.
+    fn axes_gizmo() -> Self {
+        let mut mesh = Self::default();
+        let (zero, one) = (T::S::ZERO, T::S::ONE);
+        for to in [
+            T::Vec::from_xyz(one, zero, zero),
+            T::Vec::from_xyz(zero, one, zero),
+            T::Vec::from_xyz(zero, zero, one),
+        ] {
+            mesh.insert_arrow(
+                T::Vec::zero(),
+                to,
+                T::S::from(0.02),
+                T::S::from(0.06),
+                T::S::from(0.2),
+                12,
+            );
+        }
+        mesh
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_arrow() {
+        let arrow = Mesh3d64::arrow(2.0, 0.1, 0.3, 0.5);
+        assert!(arrow.check().is_ok());
+        assert!(!arrow.is_open());
+        assert_eq!(arrow.num_vertices(), 3 * 16 + 1);
+        assert_eq!(arrow.num_faces(), 1 + 2 * 16 + 16);
+        let top = arrow
+            .vertices()
+            .map(|v| v.pos().y)
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert!(top.is_about(2.0, 1e-12));
+
+        // all faces point away from the axis or along it
+        for f in arrow.faces() {
+            let c = f.centroid(&arrow);
+            let n = f.normal(&arrow);
+            assert!(n.dot(&Vec3::new(c.x, 0.0, c.z)) > -1e-9);
+        }
+        let (indices, _) = arrow.triangulate(
+            TriangulationAlgorithm::Delaunay,
+            &mut TesselationMeta::default(),
+        );
+        assert_eq!(indices.len(), 3 * (14 + 2 * 2 * 16 + 16));
+    }
+
+    #[test]
+    fn test_axes_gizmo() {
+        let gizmo = Mesh3d64::axes_gizmo();
+        assert!(gizmo.check().is_ok());
+        assert!(!gizmo.is_open());
+        let islands = gizmo.islands();
+        assert_eq!(islands.len(), 3);
+        for (island, axis) in islands.iter().zip([
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ]) {
+            let tip = island
+                .iter()
+                .map(|v| gizmo.vertex(*v).pos().dot(&axis))
+                .fold(f64::NEG_INFINITY, f64::max);
+            assert!(tip.is_about(1.0, 1e-12));
+        }
+    }
+}
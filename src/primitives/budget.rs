@@ -0,0 +1,88 @@
+//! Helpers to pick the resolution of primitives from a triangle budget, e.g., for levels of detail.
+
+/// Returns the largest `n >= min` whose `cost(n)` doesn't exceed the `budget`,
+/// assuming that the cost grows with `n` and is at least `n`.
+/// Returns `min` if even that exceeds the budget.
+pub fn fit_budget(budget: usize, min: usize, cost: impl Fn(usize) -> usize) -> usize {
+    if cost(min) > budget {
+        return min;
+    }
+    // find an upper bound by doubling, then bisect
+    let mut lo = min;
+    let mut hi = min.max(1) * 2;
+    while hi <= budget && cost(hi) <= budget {
+        lo = hi;
+        hi *= 2;
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if cost(mid) <= budget {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    fn triangles(mesh: &Mesh3d64) -> usize {
+        mesh.faces().map(|f| f.num_vertices(mesh) - 2).sum()
+    }
+
+    #[test]
+    fn test_fit_budget() {
+        assert_eq!(fit_budget(100, 1, |n| n * n), 10);
+        assert_eq!(fit_budget(99, 1, |n| n * n), 9);
+        assert_eq!(fit_budget(5, 3, |n| 2 * n), 3);
+        assert_eq!(fit_budget(1000, 0, |n| n), 1000);
+    }
+
+    #[test]
+    fn test_budget_primitives() {
+        for budget in [100, 500, 2000] {
+            let sphere = Mesh3d64::uv_sphere_with_budget(1.0, budget);
+            assert!(sphere.check().is_ok());
+            assert!(triangles(&sphere) <= budget);
+            assert!(triangles(&sphere) > budget * 3 / 4);
+
+            let ico = Mesh3d64::icosphere_with_budget(1.0, budget);
+            assert!(ico.check().is_ok());
+            assert!(triangles(&ico) <= budget);
+            assert!(triangles(&ico) > budget / 2);
+
+            let profile = [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(0.5, 1.0),
+                Vec2::new(0.5, 2.0),
+                Vec2::new(0.0, 2.0),
+            ];
+            let mut vase = Mesh3d64::default();
+            vase.revolve_with_budget(&profile, Vec3::new(0.0, 1.0, 0.0), 2.0 * PI, budget);
+            assert!(vase.check().is_ok());
+            assert!(!vase.is_open());
+            assert!(triangles(&vase) <= budget);
+            assert!(triangles(&vase) > budget - 6);
+
+            let path = [
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+            ];
+            let tube = Mesh3d64::tube_with_budget(&path, 0.1, budget, true);
+            assert!(tube.check().is_ok());
+            assert!(triangles(&tube) <= budget);
+            assert!(triangles(&tube) > budget - 6);
+        }
+
+        // the coarsest version if the budget is too small
+        assert_eq!(triangles(&Mesh3d64::icosphere_with_budget(1.0, 10)), 20);
+        assert_eq!(triangles(&Mesh3d64::uv_sphere_with_budget(1.0, 1)), 6);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{
+    math::{HasPosition, Scalar, TransformTrait, Vector, Vector3DIteratorExt, VectorIteratorExt},
+    mesh::{
+        DefaultEdgePayload, DefaultFacePayload, FaceBasics, HalfEdge, MeshType3D, MeshTypeHalfEdge,
+        SoupBuilder, VertexBasics,
+    },
+    operations::MeshExtrude,
+};
+
+/// A trait for creating composite primitives by stacking several operations.
+pub trait MakeCrate<T: MeshTypeHalfEdge<Mesh = Self> + MeshType3D<Mesh = Self>>:
+    MeshExtrude<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Inserts a cuboid with the given `size` whose edges and corners are chamfered by `bevel`.
+    /// Returns the six large faces in the order -x, +x, -y, +y, -z, +z.
+    fn insert_chamfered_cuboid(&mut self, size: T::Vec, bevel: T::S) -> Vec<T::F> {
+        let h = size * T::S::HALF;
+        let h = [h.x(), h.y(), h.z()];
+        assert!(
+            bevel > T::S::ZERO && h.iter().all(|c| bevel < *c),
+            "the bevel must be positive and smaller than half of each side"
+        );
+        let sign = |positive: bool| if positive { T::S::ONE } else { -T::S::ONE };
+
+        // every corner of the cuboid is split into one vertex on each adjacent face
+        let mut builder = SoupBuilder::<3, T>::new();
+        let index = |axis: usize, corner: [bool; 3]| {
+            axis * 8 + corner[0] as usize + 2 * corner[1] as usize + 4 * corner[2] as usize
+        };
+        for axis in 0..3 {
+            for i in 0..8 {
+                let corner = [i & 1 != 0, i & 2 != 0, i & 4 != 0];
+                let c = [0, 1, 2].map(|k| {
+                    let inset = if k == axis { T::S::ZERO } else { bevel };
+                    sign(corner[k]) * (h[k] - inset)
+                });
+                builder.add_vertex(T::VP::from_pos(T::Vec::from_xyz(c[0], c[1], c[2])));
+            }
+        }
+
+        let mut faces: Vec<Vec<usize>> = Vec::new();
+        for axis in 0..3 {
+            let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+            let corner = |s: bool, sb: bool, sc: bool| {
+                let mut corner = [false; 3];
+                corner[axis] = s;
+                corner[b] = sb;
+                corner[c] = sc;
+                corner
+            };
+            // the large faces
+            for s in [false, true] {
+                faces.push(
+                    [(false, false), (true, false), (true, true), (false, true)]
+                        .iter()
+                        .map(|&(sb, sc)| index(axis, corner(s, sb, sc)))
+                        .collect(),
+                );
+            }
+            // the chamfers along the edges parallel to `axis`
+            for (sb, sc) in [(false, false), (true, false), (true, true), (false, true)] {
+                faces.push(vec![
+                    index(b, corner(false, sb, sc)),
+                    index(b, corner(true, sb, sc)),
+                    index(c, corner(true, sb, sc)),
+                    index(c, corner(false, sb, sc)),
+                ]);
+            }
+        }
+        // the chamfers at the corners
+        for i in 0..8 {
+            let corner = [i & 1 != 0, i & 2 != 0, i & 4 != 0];
+            faces.push((0..3).map(|axis| index(axis, corner)).collect());
+        }
+
+        // the shape is convex and centered at the origin, so faces must point away from it
+        for mut face in faces {
+            let vs = face
+                .iter()
+                .map(|&v| *builder.vertex(v).pos())
+                .collect::<Vec<T::Vec>>();
+            let center = T::Vec::stable_mean(vs.iter().copied());
+            if vs.iter().copied().normal().dot(&center) < T::S::ZERO {
+                face.reverse();
+            }
+            builder.add_face(face);
+        }
+
+        let faces = builder.insert_into(self);
+        // the large faces come first in the order -x, +x, -y, +y, -z, +z
+        [0, 1, 6, 7, 12, 13].map(|i| faces[i]).to_vec()
+    }
+
+    /// Creates a crate: A chamfered cuboid with a recessed panel on each side.
+    ///
+    /// Demonstrates the operation stack: The edges of the cuboid are chamfered by `bevel`,
+    /// each side is inset by extruding it with a scaling transform, and the resulting
+    /// panel is extruded by `panel_depth` into the cuboid.
+    /// The border around each panel is 10% of the shorter side of the face.
+    fn crate_box(size: T::Vec, panel_depth: T::S, bevel: T::S) -> Self {
+        let mut mesh = Self::default();
+        let sides = mesh.insert_chamfered_cuboid(size, bevel);
+        let extent = [size.x(), size.y(), size.z()];
+        for (i, f) in sides.into_iter().enumerate() {
+            let axis = i / 2;
+            let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+            let wb = extent[b] - bevel * T::S::TWO;
+            let wc = extent[c] - bevel * T::S::TWO;
+            let border = wb.min(wc) * T::S::from(0.1);
+            assert!(
+                panel_depth < extent[axis] * T::S::HALF - bevel,
+                "the panels must not reach the opposite side"
+            );
+
+            let mut scale = [T::S::ONE; 3];
+            scale[b] = (wb - border * T::S::TWO) / wb;
+            scale[c] = (wc - border * T::S::TWO) / wc;
+            let center: T::Vec = mesh.face(f).vertices(&mesh).map(|v| v.pos()).stable_mean();
+            let inset = T::Trans::from_translation(-center)
+                .chain(&T::Trans::from_scale(T::Vec::from_xyz(
+                    scale[0], scale[1], scale[2],
+                )))
+                .chain(&T::Trans::from_translation(center));
+            let e = mesh.extrude_face(f, inset);
+            let panel = mesh.edge(e).face_id();
+
+            let mut inward = [T::S::ZERO; 3];
+            inward[axis] = if i % 2 == 0 { T::S::ONE } else { -T::S::ONE };
+            let push = T::Trans::from_translation(
+                T::Vec::from_xyz(inward[0], inward[1], inward[2]) * panel_depth,
+            );
+            mesh.extrude_face(panel, push);
+        }
+        mesh
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_chamfered_cuboid() {
+        let mut mesh = Mesh3d64::default();
+        let sides = mesh.insert_chamfered_cuboid(Vec3::new(2.0, 2.0, 2.0), 0.1);
+        assert_eq!(sides.len(), 6);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        assert_eq!(mesh.num_vertices(), 24);
+        assert_eq!(mesh.num_faces(), 6 + 12 + 8);
+        // the cube minus 12 prisms along the edges and 8 corner cubes of which
+        // only a tetrahedron with 1/6 of their volume remains
+        let expected = 8.0 - 12.0 * 1.8 * 0.005 - 8.0 * 0.001 * 5.0 / 6.0;
+        assert!(mesh.report().volume.unwrap().is_about(expected, 1e-9));
+        for (f, n) in sides.iter().zip([
+            Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ]) {
+            assert!(mesh.face(*f).normal(&mesh).normalize().is_about(&n, 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_crate_box() {
+        let mesh = Mesh3d64::crate_box(Vec3::new(2.0, 1.0, 1.0), 0.05, 0.05);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        // each side gets 4 border faces, 4 panel walls and the panel itself
+        assert_eq!(mesh.num_faces(), 12 + 8 + 6 * 9);
+        let chamfered = {
+            let mut m = Mesh3d64::default();
+            m.insert_chamfered_cuboid(Vec3::new(2.0, 1.0, 1.0), 0.05);
+            m.report().volume.unwrap()
+        };
+        assert!(mesh.report().volume.unwrap() < chamfered);
+        assert!(mesh.report().volume.unwrap() > chamfered - 6.0 * 2.0 * 0.05);
+    }
+}
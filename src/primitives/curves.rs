@@ -0,0 +1,238 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector2D},
+    mesh::{
+        CurvedEdge, CurvedEdgeType, DefaultEdgePayload, DefaultFacePayload, EdgeBasics,
+        EuclideanMeshType, HalfEdge, VertexBasics,
+    },
+};
+
+use super::Make2dShape;
+
+/// Returns the point on the axis-aligned ellipse with radii `rx` and `ry` at the given angle.
+fn ellipse_point<Vec2: Vector2D>(rx: Vec2::S, ry: Vec2::S, angle: Vec2::S) -> Vec2 {
+    Vec2::new(rx * angle.cos(), ry * angle.sin())
+}
+
+/// Generates `n + 1` points on an arc of the axis-aligned ellipse centered at the origin
+/// with radii `rx` and `ry`, starting at the angle `start` and spanning the angle `sweep`.
+/// Positive sweeps run counter-clockwise.
+pub fn ellipse_arc<Vec2: Vector2D>(
+    rx: Vec2::S,
+    ry: Vec2::S,
+    start: Vec2::S,
+    sweep: Vec2::S,
+    n: usize,
+) -> impl Iterator<Item = Vec2> {
+    assert!(n >= 1);
+    (0..=n).map(move |i| {
+        let t = Vec2::S::from_usize(i) / Vec2::S::from_usize(n);
+        ellipse_point(rx, ry, start + sweep * t)
+    })
+}
+
+/// Approximates an arc of the axis-aligned ellipse centered at the origin with cubic bezier curves.
+/// The arc is split into pieces of at most 90° each.
+///
+/// Returns the segments as `(control1, control2, end)`; the first segment starts
+/// at the point on the ellipse at the angle `start`.
+pub fn ellipse_arc_cubics<Vec2: Vector2D>(
+    rx: Vec2::S,
+    ry: Vec2::S,
+    start: Vec2::S,
+    sweep: Vec2::S,
+) -> Vec<(Vec2, Vec2, Vec2)> {
+    let quarter = Vec2::S::PI * Vec2::S::HALF;
+    let n = ((sweep.abs() / quarter).to_f64().ceil() as usize).max(1);
+    let delta = sweep / Vec2::S::from_usize(n);
+    // length of the tangents relative to the derivative of the parametrization
+    let k = Vec2::S::FOUR / Vec2::S::THREE * (delta / Vec2::S::FOUR).tan();
+    let tangent = |a: Vec2::S| Vec2::new(-rx * a.sin(), ry * a.cos());
+    (0..n)
+        .map(|i| {
+            let a0 = start + delta * Vec2::S::from_usize(i);
+            let a1 = a0 + delta;
+            let p0: Vec2 = ellipse_point(rx, ry, a0);
+            let p1: Vec2 = ellipse_point(rx, ry, a1);
+            (p0 + tangent(a0) * k, p1 - tangent(a1) * k, p1)
+        })
+        .collect()
+}
+
+/// Generates `n + 1` points on an Archimedean spiral `r = a + b * phi`
+/// winding counter-clockwise for the given number of `turns`, starting at the positive x-axis.
+pub fn archimedean_spiral<Vec2: Vector2D>(
+    a: Vec2::S,
+    b: Vec2::S,
+    turns: Vec2::S,
+    n: usize,
+) -> impl Iterator<Item = Vec2> {
+    assert!(n >= 1);
+    let total = Vec2::S::TWO * Vec2::S::PI * turns;
+    (0..=n).map(move |i| {
+        let phi = total * Vec2::S::from_usize(i) / Vec2::S::from_usize(n);
+        let r = a + b * phi;
+        Vec2::new(r * phi.cos(), r * phi.sin())
+    })
+}
+
+/// Generates `n + 1` points on a logarithmic spiral `r = a * exp(b * phi)`
+/// winding counter-clockwise for the given number of `turns`, starting at the positive x-axis.
+pub fn logarithmic_spiral<Vec2: Vector2D>(
+    a: Vec2::S,
+    b: Vec2::S,
+    turns: Vec2::S,
+    n: usize,
+) -> impl Iterator<Item = Vec2> {
+    assert!(n >= 1);
+    let total = Vec2::S::TWO * Vec2::S::PI * turns;
+    (0..=n).map(move |i| {
+        let phi = total * Vec2::S::from_usize(i) / Vec2::S::from_usize(n);
+        let r = a * (b * phi).exp();
+        Vec2::new(r * phi.cos(), r * phi.sin())
+    })
+}
+
+/// Generates the closed, counter-clockwise outline of a star with `n` rounded tips.
+/// The radius oscillates smoothly between `inner_radius` and `outer_radius`
+/// and each tip is sampled with `segments` points. The first tip points along the positive x-axis.
+pub fn rounded_star<Vec2: Vector2D>(
+    inner_radius: Vec2::S,
+    outer_radius: Vec2::S,
+    n: usize,
+    segments: usize,
+) -> impl Iterator<Item = Vec2> {
+    assert!(n >= 2 && segments >= 2);
+    let mid = (outer_radius + inner_radius) * Vec2::S::HALF;
+    let amp = (outer_radius - inner_radius) * Vec2::S::HALF;
+    let m = n * segments;
+    (0..m).map(move |i| {
+        let phi = Vec2::S::TWO * Vec2::S::PI * Vec2::S::from_usize(i) / Vec2::S::from_usize(m);
+        let r = mid + amp * (phi * Vec2::S::from_usize(n)).cos();
+        Vec2::new(r * phi.cos(), r * phi.sin())
+    })
+}
+
+/// Methods to insert 2D shapes with curved boundary edges into a mesh.
+pub trait MakeCurved2dShape<const D: usize, T: EuclideanMeshType<D, Mesh = Self>>:
+    Make2dShape<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+    T::Edge: CurvedEdge<D, T> + HalfEdge<T>,
+{
+    /// Inserts an axis-aligned ellipse centered at the origin with radii `rx` and `ry`.
+    /// The boundary consists of four cubic bezier edges.
+    /// Returns the first edge on the outside boundary.
+    fn insert_ellipse(&mut self, rx: T::S, ry: T::S) -> T::E {
+        let arcs = ellipse_arc_cubics::<T::Vec2>(rx, ry, T::S::ZERO, T::S::TWO * T::S::PI);
+        let to_vec = |p: T::Vec2| T::Vec::from_xy(p.x(), p.y());
+        let outside = self.insert_polygon(
+            (0..arcs.len())
+                .map(|i| T::VP::from_pos(to_vec(arcs[(i + arcs.len() - 1) % arcs.len()].2))),
+        );
+        let inside = self.edge(outside).twin_id();
+        let edges = self
+            .edge(inside)
+            .edges_face(self)
+            .map(|e| e.id())
+            .collect::<Vec<_>>();
+        let n = arcs.len();
+        let eps = T::S::EPS.sqrt();
+        for e in edges {
+            let edge = self.edge(e);
+            let origin: T::Vec = edge.origin(self).pos();
+            let target: T::Vec = edge.target(self).pos();
+            // find the arc connecting the two vertices and orient it along the edge
+            let curve = (0..n)
+                .find_map(|i| {
+                    let (c1, c2, end) = arcs[i];
+                    let start = to_vec(arcs[(i + n - 1) % n].2);
+                    let end = to_vec(end);
+                    if start.is_about(&origin, eps) && end.is_about(&target, eps) {
+                        Some(CurvedEdgeType::CubicBezier(to_vec(c1), to_vec(c2)))
+                    } else if start.is_about(&target, eps) && end.is_about(&origin, eps) {
+                        Some(CurvedEdgeType::CubicBezier(to_vec(c2), to_vec(c1)))
+                    } else {
+                        None
+                    }
+                })
+                .expect("the boundary must consist of the arc end points");
+            self.edge_mut(e).set_curve_type(curve);
+        }
+        outside
+    }
+
+    /// Calls `insert_ellipse` on a new mesh.
+    fn ellipse(rx: T::S, ry: T::S) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_ellipse(rx, ry);
+        mesh
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_ellipse_arc() {
+        let pts: Vec<Vec2<f64>> = ellipse_arc(2.0, 1.0, 0.0, std::f64::consts::PI, 8).collect();
+        assert_eq!(pts.len(), 9);
+        assert!(pts[0].is_about(&Vec2::new(2.0, 0.0), 1e-12));
+        assert!(pts[4].is_about(&Vec2::new(0.0, 1.0), 1e-12));
+        assert!(pts[8].is_about(&Vec2::new(-2.0, 0.0), 1e-12));
+
+        // the bezier approximation stays close to the ellipse
+        let arcs = ellipse_arc_cubics::<Vec2<f64>>(1.0, 1.0, 0.0, std::f64::consts::PI);
+        assert_eq!(arcs.len(), 2);
+        let (c1, c2, end) = arcs[0];
+        let start = Vec2::new(1.0, 0.0);
+        let mid = (start + c1 * 3.0 + c2 * 3.0 + end) / 8.0;
+        assert!((mid.length() - 1.0).abs() < 1e-3);
+        assert!(arcs[1].2.is_about(&Vec2::new(-1.0, 0.0), 1e-12));
+    }
+
+    #[test]
+    fn test_spirals() {
+        let pts: Vec<Vec2<f64>> = archimedean_spiral(1.0, 0.5, 2.0, 16).collect();
+        assert_eq!(pts.len(), 17);
+        assert!(pts[0].is_about(&Vec2::new(1.0, 0.0), 1e-12));
+        let r = 1.0 + 0.5 * 4.0 * std::f64::consts::PI;
+        assert!(pts[16].is_about(&Vec2::new(r, 0.0), 1e-9));
+
+        let pts: Vec<Vec2<f64>> = logarithmic_spiral(1.0, 0.1, 1.0, 32).collect();
+        for w in pts.windows(2) {
+            assert!(w[1].length() > w[0].length());
+        }
+        let r = (0.1 * 2.0 * std::f64::consts::PI).exp();
+        assert!((pts[32].length() - r).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rounded_star() {
+        let pts: Vec<Vec2<f64>> = rounded_star(0.5, 1.0, 5, 8).collect();
+        assert_eq!(pts.len(), 40);
+        assert!(Polygon2d::from_points(&pts).is_ccw());
+        for p in &pts {
+            assert!(p.length() >= 0.5 - 1e-12 && p.length() <= 1.0 + 1e-12);
+        }
+        let mesh = Mesh2d64::polygon(pts.iter().map(|p| VertexPayloadPNU::from_pos(*p)));
+        assert!(mesh.check().is_ok());
+    }
+
+    #[test]
+    fn test_curved_ellipse() {
+        let mut mesh = Mesh2d64Curved::ellipse(2.0, 1.0);
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_vertices(), 4);
+        assert_eq!(mesh.num_faces(), 1);
+        let flat = mesh.flatten_curved_edges(1e-3);
+        assert!(flat.num_vertices() > 4);
+        for v in flat.vertices() {
+            let p = v.pos();
+            let r = (p.x / 2.0) * (p.x / 2.0) + p.y * p.y;
+            assert!((r - 1.0).abs() < 1e-2);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector2D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshTrait, MeshType3D, SoupBuilder},
+};
+
+/// Returns the pitch radius of a gear with the given `module` and number of `teeth`.
+pub fn gear_pitch_radius<S: Scalar>(module: S, teeth: usize) -> S {
+    module * S::from_usize(teeth) * S::HALF
+}
+
+/// Generates the counter-clockwise 2d outline of an involute spur gear centered at the origin.
+///
+/// # Parameters
+///
+/// - `module`: The module of the gear, i.e., the pitch diameter divided by the number of teeth.
+/// - `teeth`: The number of teeth.
+/// - `pressure_angle`: The pressure angle in radians (usually 20°).
+/// - `flank_segments`: The number of line segments used to approximate each involute flank.
+pub fn involute_gear_profile<Vec2: Vector2D>(
+    module: Vec2::S,
+    teeth: usize,
+    pressure_angle: Vec2::S,
+    flank_segments: usize,
+) -> Vec<Vec2> {
+    assert!(teeth >= 3, "a gear must have at least 3 teeth");
+    assert!(flank_segments >= 1);
+    let zero = Vec2::S::ZERO;
+    let rp = gear_pitch_radius(module, teeth);
+    let rb = rp * pressure_angle.cos();
+    let ra = rp + module;
+    let rf = rp - module * Vec2::S::from(1.25);
+
+    // polar angle of the involute at roll angle t
+    let inv = |t: Vec2::S| t - t.atan2(Vec2::S::ONE);
+    let roll = |r: Vec2::S| ((r / rb) * (r / rb) - Vec2::S::ONE).max(zero).sqrt();
+
+    // half of the angular tooth thickness at the base circle
+    let half = Vec2::S::PI / Vec2::S::from_usize(2 * teeth) + inv(roll(rp));
+    let t_min = roll(rf.max(rb));
+    // the flanks must not cross beyond the tip
+    let mut t_max = roll(ra);
+    while inv(t_max) > half && t_max > t_min {
+        t_max *= Vec2::S::from(0.99);
+    }
+
+    let polar = |r: Vec2::S, a: Vec2::S| Vec2::new(r * a.cos(), r * a.sin());
+    let mut res = Vec::new();
+    for k in 0..teeth {
+        let center =
+            Vec2::S::TWO * Vec2::S::PI * Vec2::S::from_usize(k) / Vec2::S::from_usize(teeth);
+        if rf < rb {
+            res.push(polar(rf, center - half));
+        }
+        for i in 0..=flank_segments {
+            let t = t_min
+                + (t_max - t_min) * Vec2::S::from_usize(i) / Vec2::S::from_usize(flank_segments);
+            res.push(polar(
+                rb * (Vec2::S::ONE + t * t).sqrt(),
+                center - half + inv(t),
+            ));
+        }
+        for i in (0..=flank_segments).rev() {
+            let t = t_min
+                + (t_max - t_min) * Vec2::S::from_usize(i) / Vec2::S::from_usize(flank_segments);
+            res.push(polar(
+                rb * (Vec2::S::ONE + t * t).sqrt(),
+                center + half - inv(t),
+            ));
+        }
+        if rf < rb {
+            res.push(polar(rf, center + half));
+        }
+    }
+    res
+}
+
+/// A trait for creating gears.
+///
+/// The gears are centered at the origin with their axis along the y-axis.
+pub trait MakeGear<T: MeshType3D<Mesh = Self>>: MeshTrait<T = T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Stacks copies of the given ccw 2d profile along the y-axis, where each layer is
+    /// given as `(y, twist)`, and closes the top and bottom with a single face each.
+    fn gear_from_layers(profile: &[T::Vec2], layers: &[(T::S, T::S)]) -> Self {
+        assert!(layers.len() >= 2);
+        let n = profile.len();
+        let mut builder = SoupBuilder::<3, T>::new();
+        for &(y, twist) in layers {
+            let (s, c) = (twist.sin(), twist.cos());
+            for p in profile {
+                let (x, z) = (p.x() * c - p.y() * s, p.x() * s + p.y() * c);
+                builder.add_vertex(T::VP::from_pos(T::Vec::from_xyz(x, y, -z)));
+            }
+        }
+        builder.add_tagged_face((0..n).rev(), "cap");
+        for l in 0..layers.len() - 1 {
+            for j in 0..n {
+                let k = (j + 1) % n;
+                builder.add_tagged_face(
+                    [l * n + j, l * n + k, (l + 1) * n + k, (l + 1) * n + j],
+                    "side-wall",
+                );
+            }
+        }
+        let top = (layers.len() - 1) * n;
+        builder.add_tagged_face(top..top + n, "cap");
+        builder.build()
+    }
+
+    /// Creates an involute spur gear with the given `module`, number of `teeth`,
+    /// `pressure_angle` (in radians) and `thickness`.
+    fn spur_gear(module: T::S, teeth: usize, pressure_angle: T::S, thickness: T::S) -> Self {
+        let profile = involute_gear_profile::<T::Vec2>(module, teeth, pressure_angle, 4);
+        Self::gear_from_layers(
+            &profile,
+            &[(T::S::ZERO, T::S::ZERO), (thickness, T::S::ZERO)],
+        )
+    }
+
+    /// Creates a herringbone gear, i.e., two mirrored helical gears stacked on top of each other.
+    /// `helix_angle` is the angle (in radians) of the teeth relative to the gear axis.
+    /// Each helical half is approximated with `segments` layers.
+    fn herringbone_gear(
+        module: T::S,
+        teeth: usize,
+        pressure_angle: T::S,
+        thickness: T::S,
+        helix_angle: T::S,
+        segments: usize,
+    ) -> Self {
+        assert!(segments >= 1);
+        let profile = involute_gear_profile::<T::Vec2>(module, teeth, pressure_angle, 4);
+        let half = thickness * T::S::HALF;
+        // rotation of the profile at the middle of the gear
+        let twist = half * helix_angle.tan() / gear_pitch_radius(module, teeth);
+        let sn = T::S::from_usize(segments);
+        let layers = (0..=2 * segments)
+            .map(|i| {
+                let t = T::S::from_usize(i) / sn;
+                let y = half * t;
+                if i <= segments {
+                    (y, twist * t)
+                } else {
+                    (y, twist * (T::S::TWO - t))
+                }
+            })
+            .collect::<Vec<_>>();
+        Self::gear_from_layers(&profile, &layers)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_gear_profile() {
+        let profile = involute_gear_profile::<Vec2<f64>>(1.0, 12, 20.0f64.to_radians(), 4);
+        let polygon = Polygon2d::from_points(&profile);
+        assert!(polygon.is_ccw());
+        let rp = gear_pitch_radius(1.0, 12);
+        for p in &profile {
+            let r = p.length();
+            assert!(r <= rp + 1.0 + 1e-9);
+            assert!(r >= rp - 1.25 - 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_gears() {
+        let spur = Mesh3d64::spur_gear(1.0, 12, 20.0f64.to_radians(), 0.5);
+        assert!(spur.check().is_ok());
+        assert!(!spur.is_open());
+        assert_eq!(spur.islands().len(), 1);
+        let num_triangles: usize = spur.faces().map(|f| f.num_vertices(&spur) - 2).sum();
+        // TODO: Sweep fails on gears with an even number of teeth
+        for algo in [
+            TriangulationAlgorithm::EarClipping,
+            TriangulationAlgorithm::Delaunay,
+        ] {
+            let (indices, _) = spur.triangulate(algo, &mut TesselationMeta::default());
+            assert_eq!(indices.len(), 3 * num_triangles);
+        }
+
+        let herringbone = Mesh3d64::herringbone_gear(1.0, 17, 20.0f64.to_radians(), 2.0, 0.5, 3);
+        assert!(herringbone.check().is_ok());
+        assert!(!herringbone.is_open());
+        let per_tooth = (spur.num_faces() - 2) / 12;
+        assert_eq!(herringbone.num_faces(), 2 + 6 * 17 * per_tooth);
+    }
+}
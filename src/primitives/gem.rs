@@ -0,0 +1,442 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector3D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshTrait, MeshType3D, SoupBuilder},
+    operations::hash01,
+};
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+/// The style of the facets of a gem generated by `MakeGem::gemstone`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GemCut {
+    /// A round brilliant: A table, bezel, star, and upper girdle facets on the crown and
+    /// main and lower girdle facets on the pavilion.
+    /// `facets` is the rotational symmetry, which is 8 for the classical cut.
+    #[default]
+    Brilliant,
+
+    /// A rectangular step cut with cut corners.
+    /// `facets` is the number of steps on the crown and on the pavilion.
+    Emerald,
+
+    /// An irregular prism with pyramidal terminations like a natural crystal.
+    /// `facets` is the number of sides of the prism.
+    Crystal,
+}
+
+/// A half-space `normal · x <= offset` and the tag of the face it creates.
+type HalfSpace<V, S> = (V, S, Option<&'static str>);
+
+/// A face of the clipped region with the index of the half-space it lies in
+/// or `None` if it still belongs to the initial bounding box.
+struct Facet<V> {
+    points: Vec<V>,
+    plane: Option<usize>,
+}
+
+/// A trait for creating convex polyhedra and gems by intersecting half-spaces.
+pub trait MakeGem<T: MeshType3D<Mesh = Self>>: MeshTrait<T = T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Creates the convex polyhedron bounded by the half-spaces `normal · x <= offset`
+    /// given as `(normal, offset)` pairs. Half-spaces that don't cut the polyhedron
+    /// don't create a face. Panics if the half-spaces don't bound a finite region.
+    fn convex_polyhedron(half_spaces: &[(T::Vec, T::S)]) -> Self {
+        intersect_half_spaces::<T>(
+            &half_spaces
+                .iter()
+                .map(|(n, d)| (*n, *d, None))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Creates a faceted gem with a girdle of radius about 1 around the y-axis.
+    /// The crown points up and the pavilion points down.
+    ///
+    /// The faces are tagged with `"table"`, `"crown"`, `"girdle"`, and `"pavilion"`
+    /// (`"prism"` and `"termination"` for crystals). The `seed` varies the proportions
+    /// of the cut, e.g., the size of the table and the angles of the crown and pavilion.
+    fn gemstone(seed: u32, cut: GemCut, facets: usize) -> Self {
+        let planes = match cut {
+            GemCut::Brilliant => brilliant(seed, facets),
+            GemCut::Emerald => emerald(seed, facets),
+            GemCut::Crystal => crystal(seed, facets),
+        };
+        intersect_half_spaces::<T>(
+            &planes
+                .into_iter()
+                .map(|([x, y, z], d, tag)| {
+                    (
+                        T::Vec::from_xyz(T::S::from_f64(x), T::S::from_f64(y), T::S::from_f64(z)),
+                        T::S::from_f64(d),
+                        Some(tag),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// Builds the intersection of the half-spaces by clipping a large box with each of them.
+fn intersect_half_spaces<T: MeshType3D>(half_spaces: &[HalfSpace<T::Vec, T::S>]) -> T::Mesh
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    let planes = half_spaces
+        .iter()
+        .map(|(n, d, _)| {
+            let length = n.length();
+            assert!(
+                length > T::S::EPS,
+                "the normal of a half-space must not be zero"
+            );
+            (*n / length, *d / length)
+        })
+        .collect::<Vec<_>>();
+    let scale = planes
+        .iter()
+        .map(|(_, d)| d.abs())
+        .fold(T::S::ZERO, |a, b| a.max(b))
+        .max(T::S::EPS.sqrt());
+    let eps = T::S::EPS.sqrt() * scale;
+
+    let facets = clip_box(&planes, scale * T::S::from_f64(1e3), eps);
+    assert!(
+        facets.iter().all(|f| f.plane.is_some()),
+        "the half-spaces don't bound a finite region"
+    );
+
+    let mut builder = SoupBuilder::<3, T>::new().with_weld(eps);
+    for f in facets {
+        let cycle = f
+            .points
+            .iter()
+            .map(|p| builder.add_vertex(T::VP::from_pos(*p)))
+            .collect::<Vec<_>>();
+        match f.plane.and_then(|k| half_spaces[k].2) {
+            Some(tag) => builder.add_tagged_face(cycle, tag),
+            None => builder.add_face(cycle),
+        };
+    }
+    builder.build()
+}
+
+/// Clips the cube with the given half-size around the origin with the normalized planes
+/// and returns the faces of the remaining convex region.
+fn clip_box<V: Vector3D>(planes: &[(V, V::S)], size: V::S, eps: V::S) -> Vec<Facet<V>> {
+    let axes = [
+        V::from_xyz(V::S::ONE, V::S::ZERO, V::S::ZERO),
+        V::from_xyz(V::S::ZERO, V::S::ONE, V::S::ZERO),
+        V::from_xyz(V::S::ZERO, V::S::ZERO, V::S::ONE),
+    ];
+    let mut facets = Vec::new();
+    for a in 0..3 {
+        let (e, b, c) = (axes[a], axes[(a + 1) % 3], axes[(a + 2) % 3]);
+        for sign in [V::S::ONE, -V::S::ONE] {
+            // ccw around `e` since `b × c = e`
+            let mut points = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                .iter()
+                .map(|(i, j)| (e * sign + b * V::S::from_f64(*i) + c * V::S::from_f64(*j)) * size)
+                .collect::<Vec<_>>();
+            if sign < V::S::ZERO {
+                points.reverse();
+            }
+            facets.push(Facet {
+                points,
+                plane: None,
+            });
+        }
+    }
+
+    for (k, (n, d)) in planes.iter().enumerate() {
+        // Sutherland-Hodgman clipping of each face while collecting the points on the plane
+        let mut cap = Vec::new();
+        let mut coplanar = false;
+        facets.retain_mut(|f| {
+            let s = f.points.iter().map(|p| n.dot(p) - *d).collect::<Vec<_>>();
+            if s.iter().all(|s| s.abs() <= eps) {
+                coplanar = true;
+                return true;
+            }
+            let mut clipped = Vec::with_capacity(f.points.len() + 1);
+            for i in 0..f.points.len() {
+                let j = (i + 1) % f.points.len();
+                if s[i] <= eps {
+                    clipped.push(f.points[i]);
+                    if s[i] >= -eps {
+                        cap.push(f.points[i]);
+                    }
+                }
+                if (s[i] < -eps && s[j] > eps) || (s[i] > eps && s[j] < -eps) {
+                    let p = f.points[i] + (f.points[j] - f.points[i]) * (s[i] / (s[i] - s[j]));
+                    clipped.push(p);
+                    cap.push(p);
+                }
+            }
+            f.points = clipped;
+            f.points.len() >= 3
+        });
+        if coplanar {
+            // an existing face already covers the plane
+            continue;
+        }
+
+        // close the cut with a face whose vertices are sorted ccw around the normal
+        let mut unique: Vec<V> = Vec::new();
+        for p in cap {
+            if unique.iter().all(|q| q.distance_squared(&p) > eps * eps) {
+                unique.push(p);
+            }
+        }
+        if unique.len() < 3 {
+            continue;
+        }
+        let center = unique
+            .iter()
+            .fold(<V as Vector<V::S, 3>>::zero(), |a, b| a + *b)
+            / V::S::from_usize(unique.len());
+        let axis = if n.x().abs() < V::S::HALF {
+            axes[0]
+        } else {
+            axes[1]
+        };
+        let u = n.cross(&axis).normalize();
+        let v = n.cross(&u);
+        let mut keyed = unique
+            .into_iter()
+            .map(|p| ((p - center).dot(&v).atan2((p - center).dot(&u)), p))
+            .collect::<Vec<_>>();
+        keyed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let points = keyed.into_iter().map(|(_, p)| p).collect::<Vec<_>>();
+
+        // the plane only touches the region along an edge
+        let area = (0..points.len())
+            .map(|i| {
+                (points[i] - center)
+                    .cross(&(points[(i + 1) % points.len()] - center))
+                    .dot(n)
+            })
+            .fold(V::S::ZERO, |a, b| a + b);
+        if area <= eps * eps {
+            continue;
+        }
+        facets.push(Facet {
+            points,
+            plane: Some(k),
+        });
+    }
+    facets
+}
+
+/// The half-space below (if `up`) or above the plane at azimuth `theta` around the y-axis
+/// that is inclined by `alpha` against the horizontal and passes through the point at
+/// distance `radius` from the axis at `height`.
+fn facet(
+    theta: f64,
+    alpha: f64,
+    radius: f64,
+    height: f64,
+    up: bool,
+    tag: &'static str,
+) -> ([f64; 3], f64, &'static str) {
+    let (sin, cos) = (alpha.sin(), if up { alpha.cos() } else { -alpha.cos() });
+    (
+        [sin * theta.cos(), cos, sin * theta.sin()],
+        sin * radius + cos * height,
+        tag,
+    )
+}
+
+/// Returns a factor in `[1 - amount, 1 + amount]`.
+fn jitter(i: usize, seed: u32, amount: f64) -> f64 {
+    1.0 + amount * (2.0 * hash01(i, seed) - 1.0)
+}
+
+fn brilliant(seed: u32, n: usize) -> Vec<([f64; 3], f64, &'static str)> {
+    assert!(n >= 3, "a brilliant cut needs a symmetry of at least 3");
+    let step = TAU / n as f64;
+    // half the thickness of the girdle
+    let g = 0.015;
+    let table = 0.56 * jitter(0, seed, 0.05);
+    let crown = 34.5f64.to_radians() * jitter(1, seed, 0.08);
+    let pavilion = 40.75f64.to_radians() * jitter(2, seed, 0.03);
+    let height = g + (1.0 - table) * crown.tan();
+
+    let mut planes = vec![facet(0.0, 0.0, 0.0, height, true, "table")];
+    for i in 0..n {
+        let theta = i as f64 * step;
+        planes.push(facet(theta, crown, 1.0, g, true, "crown"));
+        planes.push(facet(
+            theta + step / 2.0,
+            crown * 0.6,
+            table,
+            height,
+            true,
+            "crown",
+        ));
+        planes.push(facet(theta, pavilion, 1.0, -g, false, "pavilion"));
+        for half in [0.25, 0.75] {
+            let phi = theta + step * half;
+            planes.push(facet(phi, crown * 1.25, 1.0, g, true, "crown"));
+            planes.push(facet(phi, pavilion * 1.04, 1.0, -g, false, "pavilion"));
+        }
+        for quarter in 0..4 {
+            let phi = theta + step * quarter as f64 / 4.0;
+            planes.push(facet(phi, FRAC_PI_2, 1.0, 0.0, true, "girdle"));
+        }
+    }
+    planes
+}
+
+fn emerald(seed: u32, steps: usize) -> Vec<([f64; 3], f64, &'static str)> {
+    assert!(steps >= 1, "an emerald cut needs at least 1 step");
+    let g = 0.015;
+    let (length, width) = (1.0, 0.7 * jitter(0, seed, 0.1));
+    let corner = 0.3 * width;
+    let table = 0.35 * width * jitter(1, seed, 0.1);
+    let crown = 0.3 * width * jitter(2, seed, 0.1);
+    let pavilion = 0.7 * width * jitter(3, seed, 0.1);
+
+    // the outline as (azimuth, distance) of the girdle facets
+    let diagonal = (length + width - corner) / 2f64.sqrt();
+    let outline = (0..8).map(|k| {
+        let distance = [length, diagonal, width, diagonal][k % 4];
+        (k as f64 * TAU / 8.0, distance)
+    });
+
+    // the profile of the steps as (inset, height), which is concave to keep the gem convex
+    let profile = |t: f64, inset: f64, height: f64| (inset * t, height * t * (2.0 - t));
+    let mut planes = vec![facet(0.0, 0.0, 0.0, g + crown, true, "table")];
+    for (theta, distance) in outline {
+        planes.push(facet(theta, FRAC_PI_2, distance, 0.0, true, "girdle"));
+        for j in 1..=steps {
+            let (t0, t1) = ((j - 1) as f64 / steps as f64, j as f64 / steps as f64);
+            for (up, inset, height, tag) in [
+                (true, table, crown, "crown"),
+                (false, width, pavilion, "pavilion"),
+            ] {
+                let (s0, h0) = profile(t0, inset, height);
+                let (s1, h1) = profile(t1, inset, height);
+                let alpha = (h1 - h0).atan2(s1 - s0);
+                let y = if up { g + h0 } else { -g - h0 };
+                planes.push(facet(theta, alpha, distance - s0, y, up, tag));
+            }
+        }
+    }
+    planes
+}
+
+fn crystal(seed: u32, sides: usize) -> Vec<([f64; 3], f64, &'static str)> {
+    assert!(sides >= 3, "a crystal needs at least 3 sides");
+    let step = TAU / sides as f64;
+    let height = 1.2 * jitter(0, seed, 0.3);
+    let mut planes = Vec::new();
+    for k in 0..sides {
+        let i = 4 * k + 1;
+        let theta = (k as f64 + 0.15 * (2.0 * hash01(i, seed) - 1.0)) * step;
+        let radius = 0.5 * jitter(i + 1, seed, 0.2);
+        let alpha = 52f64.to_radians() * jitter(i + 2, seed, 0.15);
+        planes.push(facet(theta, FRAC_PI_2, radius, 0.0, true, "prism"));
+        for (up, h) in [(true, height), (false, -height)] {
+            let h = h * jitter(i + 3, seed, 0.15);
+            planes.push(facet(theta, alpha, radius, h, up, "termination"));
+        }
+    }
+    planes
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    /// Asserts that all vertices are on one side of each face.
+    fn assert_convex(mesh: &Mesh3d64Tagged) {
+        for f in mesh.faces() {
+            let n = f.normal(mesh).normalize();
+            let p = f.centroid(mesh);
+            let sides = mesh
+                .vertices()
+                .map(|v| (v.pos() - p).dot(&n))
+                .collect::<Vec<_>>();
+            assert!(
+                sides.iter().all(|s| *s <= 1e-9) || sides.iter().all(|s| *s >= -1e-9),
+                "not convex"
+            );
+        }
+    }
+
+    #[test]
+    fn test_convex_polyhedron() {
+        let mut planes = Vec::new();
+        for axis in [Vec3::x(), Vec3::y(), Vec3::z()] {
+            planes.push((axis, 1.0));
+            planes.push((-axis, 1.0));
+        }
+        let cube = Mesh3d64::convex_polyhedron(&planes);
+        assert!(cube.check().is_ok());
+        assert!(!cube.is_open());
+        assert_eq!((cube.num_faces(), cube.num_vertices()), (6, 8));
+        assert!((cube.report().volume.unwrap() - 8.0).abs() < 1e-9);
+
+        // cut off one corner; redundant and coplanar half-spaces don't add faces
+        planes.push((Vec3::new(1.0, 1.0, 1.0), 2.5));
+        planes.push((Vec3::new(2.0, 0.0, 0.0), 2.0));
+        planes.push((Vec3::new(1.0, 1.0, 0.0), 10.0));
+        let cut = Mesh3d64::convex_polyhedron(&planes);
+        assert!(cut.check().is_ok());
+        assert!(!cut.is_open());
+        assert_eq!((cut.num_faces(), cut.num_vertices()), (7, 10));
+        let expected = 8.0 - 0.5f64.powi(3) / 6.0;
+        assert!((cut.report().volume.unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "don't bound a finite region")]
+    fn test_unbounded_polyhedron() {
+        Mesh3d64::convex_polyhedron(&[(Vec3::x(), 1.0), (Vec3::y(), 1.0)]);
+    }
+
+    #[test]
+    fn test_gemstone() {
+        let count = |mesh: &Mesh3d64Tagged, tag: &str| mesh.select_by_tag(tag).len();
+        for seed in 0..4 {
+            for (cut, facets) in [
+                (GemCut::Brilliant, 8),
+                (GemCut::Brilliant, 5),
+                (GemCut::Emerald, 3),
+                (GemCut::Emerald, 1),
+                (GemCut::Crystal, 6),
+                (GemCut::Crystal, 3),
+            ] {
+                let gem = Mesh3d64Tagged::gemstone(seed, cut, facets);
+                assert!(gem.check().is_ok(), "{:?} {}", cut, seed);
+                assert!(!gem.is_open());
+                assert!(gem.report().volume.unwrap() > 0.0);
+                assert_convex(&gem);
+                assert!(gem.faces().all(|f| f.payload().tag().is_some()));
+
+                match cut {
+                    GemCut::Brilliant => {
+                        assert_eq!(count(&gem, "table"), 1);
+                        assert_eq!(count(&gem, "crown"), 4 * facets);
+                        assert_eq!(count(&gem, "pavilion"), 3 * facets);
+                        assert_eq!(count(&gem, "girdle"), 4 * facets);
+                    }
+                    GemCut::Emerald => {
+                        assert_eq!(count(&gem, "table"), 1);
+                        assert_eq!(count(&gem, "crown"), 8 * facets);
+                        assert!(count(&gem, "pavilion") >= 4 * facets);
+                        assert_eq!(count(&gem, "girdle"), 8);
+                    }
+                    GemCut::Crystal => {
+                        assert_eq!(count(&gem, "prism"), facets);
+                        assert!(count(&gem, "termination") >= facets);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,35 @@
 //! Implementations of the various primitives that can be used to create a mesh.
 
+mod arrow;
+mod budget;
+mod crate_box;
+mod curves;
+mod gear;
+mod gem;
 mod misc;
+mod parametric;
 mod plane;
 mod polygon;
 mod prismatoid;
+mod roof;
 mod sphere;
+mod spring;
+mod tube;
+mod voxel;
 
+pub use arrow::*;
+pub use budget::*;
+pub use crate_box::*;
+pub use curves::*;
+pub use gear::*;
+pub use gem::*;
 pub use misc::*;
+pub use parametric::*;
 pub use plane::*;
 pub use polygon::*;
 pub use prismatoid::*;
+pub use roof::*;
 pub use sphere::*;
+pub use spring::*;
+pub use tube::*;
+pub use voxel::*;
@@ -0,0 +1,226 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshTrait, MeshType3D, SoupBuilder},
+};
+use std::collections::{BTreeSet, HashMap};
+
+/// Number of cells of the initial grid along each parameter axis.
+const BASE_CELLS: usize = 4;
+
+/// Maximum number of times a cell of the initial grid is split.
+const MAX_DEPTH: usize = 8;
+
+/// A cell of the parameter lattice given by its lower corner and size in lattice units.
+#[derive(Clone, Copy, Debug)]
+struct Cell {
+    i: usize,
+    j: usize,
+    size: usize,
+}
+
+/// A trait for tessellating parametric surfaces.
+pub trait MakeParametric<T: MeshType3D<Mesh = Self>>: MeshTrait<T = T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Adaptively tessellates the parametric surface `f` over `u_range` x `v_range`
+    /// such that the faces deviate roughly at most `tol` from the surface.
+    /// See `from_parametric_ex` for details.
+    fn from_parametric(
+        f: impl Fn(T::S, T::S) -> T::Vec,
+        u_range: (T::S, T::S),
+        v_range: (T::S, T::S),
+        tol: T::S,
+    ) -> Self {
+        Self::from_parametric_ex(f, u_range, v_range, tol, false, false)
+    }
+
+    /// Like `from_parametric`, but if `closed_u` (`closed_v`) is true, the surface is assumed
+    /// to wrap around in u (v), i.e., `f` takes the same values at both ends of the range,
+    /// and the two borders are stitched together.
+    ///
+    /// The parameter domain starts as a 4x4 grid whose cells are split into four as long as
+    /// the surface at the center or the midpoints of the sides of a cell deviates more than
+    /// `tol` from the bilinear interpolation of its corners. Each cell becomes one face that
+    /// also contains the vertices of smaller neighboring cells on its sides, so the mesh has
+    /// no cracks. The faces are oriented along `df/du x df/dv`.
+    /// Vertices closer than a thousandth of `tol` are merged, e.g., at the poles of a sphere.
+    fn from_parametric_ex(
+        f: impl Fn(T::S, T::S) -> T::Vec,
+        u_range: (T::S, T::S),
+        v_range: (T::S, T::S),
+        tol: T::S,
+        closed_u: bool,
+        closed_v: bool,
+    ) -> Self {
+        assert!(tol > T::S::ZERO, "the tolerance must be positive");
+        let n = BASE_CELLS << MAX_DEPTH;
+        let wrap = |i: usize, j: usize| {
+            (
+                if closed_u && i == n { 0 } else { i },
+                if closed_v && j == n { 0 } else { j },
+            )
+        };
+        let param = |k: usize, (a, b): (T::S, T::S)| {
+            a + (b - a) * T::S::from_usize(k) / T::S::from_usize(n)
+        };
+
+        let mut samples: HashMap<(usize, usize), T::Vec> = HashMap::new();
+        let mut sample = |i: usize, j: usize| {
+            *samples
+                .entry(wrap(i, j))
+                .or_insert_with(|| f(param(i, u_range), param(j, v_range)))
+        };
+
+        let mut leaves = Vec::new();
+        let mut stack = (0..BASE_CELLS * BASE_CELLS)
+            .map(|k| Cell {
+                i: (k % BASE_CELLS) << MAX_DEPTH,
+                j: (k / BASE_CELLS) << MAX_DEPTH,
+                size: 1 << MAX_DEPTH,
+            })
+            .collect::<Vec<_>>();
+        while let Some(cell) = stack.pop() {
+            let Cell { i, j, size } = cell;
+            let h = size / 2;
+            let split = size > 1 && {
+                let [p00, p10, p01, p11] = [
+                    sample(i, j),
+                    sample(i + size, j),
+                    sample(i, j + size),
+                    sample(i + size, j + size),
+                ];
+                [
+                    (i + h, j + h, (p00 + p10 + p01 + p11) * T::S::from(0.25)),
+                    (i + h, j, (p00 + p10) * T::S::HALF),
+                    (i + h, j + size, (p01 + p11) * T::S::HALF),
+                    (i, j + h, (p00 + p01) * T::S::HALF),
+                    (i + size, j + h, (p10 + p11) * T::S::HALF),
+                ]
+                .iter()
+                .any(|(a, b, p)| sample(*a, *b).distance(p) > tol)
+            };
+            if split {
+                for (a, b) in [(0, 0), (h, 0), (0, h), (h, h)] {
+                    stack.push(Cell {
+                        i: i + a,
+                        j: j + b,
+                        size: h,
+                    });
+                }
+            } else {
+                leaves.push(cell);
+            }
+        }
+
+        // the lattice points on each row and column of the lattice
+        let mut rows: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+        let mut cols: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+        for Cell { i, j, size } in &leaves {
+            for (a, b) in [
+                (*i, *j),
+                (i + size, *j),
+                (*i, j + size),
+                (i + size, j + size),
+            ] {
+                let (a, b) = wrap(a, b);
+                rows.entry(b).or_default().insert(a);
+                cols.entry(a).or_default().insert(b);
+            }
+        }
+
+        let mut builder = SoupBuilder::<3, T>::new().with_weld(tol * T::S::from(1e-3));
+        let mut indices: HashMap<(usize, usize), usize> = HashMap::new();
+        for Cell { i, j, size } in leaves {
+            let (i1, j1) = (i + size, j + size);
+            let row = |b: usize| &rows[&wrap(0, b).1];
+            let col = |a: usize| &cols[&wrap(a, 0).0];
+            let cycle = std::iter::once((i, j))
+                .chain(row(j).range(i + 1..i1).map(|a| (*a, j)))
+                .chain(std::iter::once((i1, j)))
+                .chain(col(i1).range(j + 1..j1).map(|b| (i1, *b)))
+                .chain(std::iter::once((i1, j1)))
+                .chain(row(j1).range(i + 1..i1).rev().map(|a| (*a, j1)))
+                .chain(std::iter::once((i, j1)))
+                .chain(col(i).range(j + 1..j1).rev().map(|b| (i, *b)))
+                .map(|(a, b)| {
+                    let key = wrap(a, b);
+                    *indices.entry(key).or_insert_with(|| {
+                        builder.add_vertex(T::VP::from_pos(sample(key.0, key.1)))
+                    })
+                })
+                .collect::<Vec<_>>();
+            builder.add_face(cycle);
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_parametric_plane() {
+        // a plane doesn't need any refinement
+        let mesh =
+            Mesh3d64::from_parametric(|u, v| Vec3::new(u, 0.0, v), (0.0, 1.0), (0.0, 1.0), 1e-3);
+        assert!(mesh.check().is_ok());
+        assert_eq!(mesh.num_faces(), 16);
+        assert_eq!(mesh.num_vertices(), 25);
+        // oriented along du x dv = -y
+        assert!(mesh.faces().all(|f| f.normal(&mesh).y < 0.0));
+
+        // a bump causes local refinement
+        let bump = |u: f64, v: f64| {
+            let d = (u - 0.3) * (u - 0.3) + (v - 0.3) * (v - 0.3);
+            Vec3::new(u, (-d * 50.0).exp(), v)
+        };
+        let coarse = Mesh3d64::from_parametric(bump, (0.0, 1.0), (0.0, 1.0), 1e-2);
+        let fine = Mesh3d64::from_parametric(bump, (0.0, 1.0), (0.0, 1.0), 1e-3);
+        assert!(coarse.check().is_ok() && fine.check().is_ok());
+        assert!(coarse.num_faces() > 16);
+        assert!(fine.num_faces() > coarse.num_faces());
+        // the far corner is still coarse
+        assert!(!fine.vertices().any(|v| {
+            let p = v.pos();
+            p.x > 0.75 && p.x < 1.0 && p.z > 0.75 && p.z < 1.0
+        }));
+    }
+
+    #[test]
+    fn test_parametric_closed() {
+        // a sphere is closed in u and collapses at the poles
+        let sphere = Mesh3d64::from_parametric_ex(
+            |u, v| Vec3::new(u.cos() * v.sin(), -v.cos(), -u.sin() * v.sin()),
+            (0.0, 2.0 * PI),
+            (0.0, PI),
+            1e-2,
+            true,
+            false,
+        );
+        assert!(sphere.check().is_ok());
+        assert!(!sphere.is_open());
+        assert!(sphere
+            .vertices()
+            .all(|v| v.pos().norm().is_about(1.0, 1e-9)));
+
+        // a torus is closed in both directions
+        let torus = Mesh3d64::from_parametric_ex(
+            |u, v| {
+                let r = 1.0 + 0.3 * v.cos();
+                Vec3::new(r * u.cos(), 0.3 * v.sin(), r * u.sin())
+            },
+            (0.0, 2.0 * PI),
+            (0.0, 2.0 * PI),
+            1e-2,
+            true,
+            true,
+        );
+        assert!(torus.check().is_ok());
+        assert!(!torus.is_open());
+    }
+}
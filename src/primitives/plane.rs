@@ -1,4 +1,7 @@
-use crate::mesh::{DefaultEdgePayload, DefaultFacePayload, EuclideanMeshType, MeshTrait};
+use crate::{
+    math::{HasNormal, HasPosition, Scalar, Vector, Vector3D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, EuclideanMeshType, MeshTrait, SoupBuilder},
+};
 
 /// A trait for creating plane approximations.
 pub trait MakePlane<const D: usize, T: EuclideanMeshType<D, Mesh = Self>>:
@@ -7,6 +10,96 @@ where
     T::EP: DefaultEdgePayload,
     T::FP: DefaultFacePayload,
 {
+    /// Generates a terrain in the xz-plane centered at the origin with the given `width` (x)
+    /// and `depth` (z) subdivided into `resolution` cells along each axis.
+    /// Each grid vertex is displaced along the y-axis by `sampler(x, z)`.
+    /// The cells are split into two triangles each and the vertex normals
+    /// are derived from central differences of the sampler.
+    fn heightmap(
+        width: T::S,
+        depth: T::S,
+        resolution: usize,
+        sampler: impl Fn(T::S, T::S) -> T::S,
+    ) -> Self
+    where
+        T::Vec: Vector3D<S = T::S>,
+        T::VP: HasNormal<D, T::Vec, S = T::S>,
+    {
+        Self::heightmap_ex(width, depth, resolution, T::S::ZERO, sampler)
+    }
+
+    /// Like `heightmap`, but if `skirt` is positive, vertical walls reaching `skirt` below the
+    /// boundary are added to hide the cracks between neighboring terrain tiles.
+    /// The walls are tagged as "skirt".
+    fn heightmap_ex(
+        width: T::S,
+        depth: T::S,
+        resolution: usize,
+        skirt: T::S,
+        sampler: impl Fn(T::S, T::S) -> T::S,
+    ) -> Self
+    where
+        T::Vec: Vector3D<S = T::S>,
+        T::VP: HasNormal<D, T::Vec, S = T::S>,
+    {
+        assert!(resolution >= 1);
+        let n = resolution + 1;
+        let dx = width / T::S::from_usize(resolution);
+        let dz = depth / T::S::from_usize(resolution);
+        let coord = |i: usize, j: usize| {
+            (
+                dx * T::S::from_usize(i) - width * T::S::HALF,
+                dz * T::S::from_usize(j) - depth * T::S::HALF,
+            )
+        };
+
+        let mut builder = SoupBuilder::<D, T>::new();
+        for j in 0..n {
+            for i in 0..n {
+                let (x, z) = coord(i, j);
+                // the gradient is sampled beyond the boundary to match neighboring tiles
+                let ddx = (sampler(x + dx, z) - sampler(x - dx, z)) / (dx * T::S::TWO);
+                let ddz = (sampler(x, z + dz) - sampler(x, z - dz)) / (dz * T::S::TWO);
+                let mut vp = T::VP::from_pos(T::Vec::from_xyz(x, sampler(x, z), z));
+                vp.set_normal(T::Vec::from_xyz(-ddx, T::S::ONE, -ddz).normalize());
+                builder.add_vertex(vp);
+            }
+        }
+        for j in 0..resolution {
+            for i in 0..resolution {
+                let a = j * n + i;
+                // counter-clockwise when seen from above
+                builder.add_face([a, a + n, a + n + 1]);
+                builder.add_face([a, a + n + 1, a + 1]);
+            }
+        }
+
+        if skirt > T::S::ZERO {
+            // the boundary in clockwise order when seen from above
+            let boundary = (0..resolution)
+                .chain((0..resolution).map(|j| j * n + resolution))
+                .chain((0..resolution).map(|i| resolution * n + resolution - i))
+                .chain((0..resolution).map(|j| (resolution - j) * n))
+                .collect::<Vec<_>>();
+            let down = T::Vec::from_xyz(T::S::ZERO, skirt, T::S::ZERO);
+            let lower = boundary
+                .iter()
+                .map(|&v| {
+                    let mut vp = builder.vertex(v).clone();
+                    vp.set_pos(*vp.pos() - down);
+                    builder.add_vertex(vp)
+                })
+                .collect::<Vec<_>>();
+            let m = boundary.len();
+            for k in 0..m {
+                let l = (k + 1) % m;
+                builder.add_tagged_face([boundary[k], boundary[l], lower[l], lower[k]], "skirt");
+            }
+        }
+
+        builder.build()
+    }
+
     // TODO: requires better theory of edges and half edges
     /*
     /// Generate a subdivided plane made of triangles with given `width` and `height` and
@@ -89,3 +182,43 @@ where
         mesh
     }*/
 }
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_heightmap() {
+        let terrain = Mesh3d64::heightmap(4.0, 2.0, 8, |x, _| 0.5 * x);
+        assert!(terrain.check().is_ok());
+        assert_eq!(terrain.num_vertices(), 9 * 9);
+        assert_eq!(terrain.num_faces(), 2 * 8 * 8);
+        let expected = Vec3::new(-0.5, 1.0, 0.0).normalize();
+        for v in terrain.vertices() {
+            assert!(v.pos().y.is_about(0.5 * v.pos().x, 1e-12));
+            assert!(v.payload().normal().is_about(&expected, 1e-12));
+        }
+        for f in terrain.faces() {
+            assert!(f.normal(&terrain).y > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_heightmap_skirt() {
+        let terrain = Mesh3d64Tagged::heightmap_ex(2.0, 2.0, 4, 0.5, |x, z| (x * z).sin());
+        assert!(terrain.check().is_ok());
+        assert!(terrain.is_open());
+        assert_eq!(terrain.num_vertices(), 5 * 5 + 4 * 4);
+        assert_eq!(terrain.num_faces(), 2 * 4 * 4 + 4 * 4);
+        let skirt = terrain.select_by_tag("skirt");
+        assert_eq!(skirt.len(), 4 * 4);
+        // the skirt walls point away from the center
+        for f in skirt {
+            let f = terrain.face(f);
+            assert!(f.normal(&terrain).y.abs() < 1e-9);
+            let c = f.centroid(&terrain);
+            assert!(f.normal(&terrain).dot(&Vec3::new(c.x, 0.0, c.z)) > 0.0);
+        }
+    }
+}
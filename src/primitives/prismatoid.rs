@@ -1,8 +1,8 @@
 use crate::{
     math::{HasPosition, Scalar, TransformTrait, Vector},
     mesh::{
-        DefaultEdgePayload, DefaultFacePayload, Face3d, HalfEdge, MeshType3D, MeshTypeHalfEdge,
-        VertexPayload,
+        DefaultEdgePayload, DefaultFacePayload, Face3d, FaceBasics, HalfEdge, MeshType3D,
+        MeshTypeHalfEdge, VertexPayload,
     },
     operations::{MeshExtrude, MeshLoft, MeshSubdivision},
     primitives::polygon::Make2dShape,
@@ -55,7 +55,9 @@ where
         let twin = self.edge(first).twin(self);
         let f = twin.face(self).expect("The polygon must have a face");
         let normal = Face3d::normal(f, self).normalize();
+        let base = f.id();
         let e = self.extrude(first, T::Trans::from_translation(-normal * height));
+        self.tag_faces([base], "cap");
         e
     }
 
@@ -138,8 +140,16 @@ where
         smooth: bool,
     ) -> T::E {
         let first = self.insert_polygon(base);
+        let base = self.edge(first).twin(self).face_id();
         let top_edge = self.loft_polygon(first, 2, 2, top);
-        self.close_hole(top_edge, Default::default(), false);
+        let top = self.close_hole(top_edge, Default::default(), false);
+        let walls = self
+            .face(base)
+            .edges(self)
+            .filter_map(|e| e.twin(self).face(self).map(|f| f.id()))
+            .collect::<Vec<_>>();
+        self.tag_faces(walls, "side-wall");
+        self.tag_faces([base, top], "cap");
         // TODO: smooth
         assert!(!smooth, "Smooth frustums not yet implemented");
         top_edge
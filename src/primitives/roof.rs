@@ -0,0 +1,528 @@
+use crate::{
+    math::{offset_polygon, straight_skeleton, HasPosition, OffsetJoin, Scalar, Vector, Vector2D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshTrait, MeshType3D, SoupBuilder},
+};
+use std::collections::{HashMap, HashSet};
+
+/// The shape of a roof generated by `MakeRoof::insert_styled_roof`.
+/// All pitches are given in radians.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoofStyle<S: Scalar> {
+    /// All edges of the footprint are eaves with the same pitch.
+    Hip(S),
+
+    /// Like a hip roof, but the triangular ends of the ridges, e.g., the short sides of
+    /// rectangular wings, are replaced by vertical gables.
+    Gable(S),
+
+    /// A single plane rising from the edge with the given index of the footprint.
+    Shed(S, usize),
+
+    /// A hip roof with a steep lower pitch that changes to a shallow upper pitch at the
+    /// given horizontal distance from the walls. An upper pitch of zero gives a flat top.
+    Mansard(S, S, S),
+}
+
+/// A trait for creating roofs over building footprints.
+pub trait MakeRoof<T: MeshType3D<Mesh = Self>>: MeshTrait<T = T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Creates a hip roof over the `footprint` using its straight skeleton.
+    ///
+    /// The footprint is given as closed 2d polygons with the interior on the left, i.e.,
+    /// counter-clockwise outlines and clockwise holes such as courtyards. It is placed in the
+    /// xz-plane where the 2d y-axis maps to the negative z-axis. Each point of the roof is
+    /// lifted by `height(d)` where `d` is its distance from the nearest eave, e.g.,
+    /// `|d| d * pitch.tan()` for planar slopes with the given pitch.
+    /// The roof has one face per edge of the footprint and is open at the eaves.
+    fn roof(footprint: &[Vec<T::Vec2>], height: impl Fn(T::S) -> T::S) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_roof(footprint, height);
+        mesh
+    }
+
+    /// Inserts a roof like `roof` as a new connected component of the mesh.
+    /// Returns the ids of the inserted faces.
+    fn insert_roof(
+        &mut self,
+        footprint: &[Vec<T::Vec2>],
+        height: impl Fn(T::S) -> T::S,
+    ) -> Vec<T::F> {
+        let skeleton = straight_skeleton(footprint);
+        let mut builder = SoupBuilder::<3, T>::new();
+        for (p, t) in &skeleton.nodes {
+            builder.add_vertex(T::VP::from_pos(T::Vec::from_xyz(p.x(), height(*t), -p.y())));
+        }
+        for face in skeleton.faces {
+            builder.add_tagged_face(face, "roof");
+        }
+        builder.insert_into(self)
+    }
+
+    /// Creates a hip roof with the given `pitch` whose eaves overhang the walls by
+    /// `overhang`. See `insert_styled_roof`.
+    fn hip_roof(footprint: &[Vec<T::Vec2>], pitch: T::S, overhang: T::S) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_styled_roof(footprint, RoofStyle::Hip(pitch), overhang);
+        mesh
+    }
+
+    /// Creates a gable roof with the given `pitch` whose eaves overhang the walls by
+    /// `overhang`. See `insert_styled_roof`.
+    fn gable_roof(footprint: &[Vec<T::Vec2>], pitch: T::S, overhang: T::S) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_styled_roof(footprint, RoofStyle::Gable(pitch), overhang);
+        mesh
+    }
+
+    /// Creates a shed roof with the given `pitch` rising from the edge `low_edge` of the
+    /// footprint whose eaves overhang the walls by `overhang`. See `insert_styled_roof`.
+    fn shed_roof(footprint: &[Vec<T::Vec2>], pitch: T::S, low_edge: usize, overhang: T::S) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_styled_roof(footprint, RoofStyle::Shed(pitch, low_edge), overhang);
+        mesh
+    }
+
+    /// Creates a mansard roof with a `lower` and an `upper` pitch that changes at the
+    /// horizontal distance `break_distance` from the walls and whose eaves overhang the
+    /// walls by `overhang`. See `insert_styled_roof`.
+    fn mansard_roof(
+        footprint: &[Vec<T::Vec2>],
+        lower: T::S,
+        upper: T::S,
+        break_distance: T::S,
+        overhang: T::S,
+    ) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_styled_roof(
+            footprint,
+            RoofStyle::Mansard(lower, upper, break_distance),
+            overhang,
+        );
+        mesh
+    }
+
+    /// Inserts a roof of the given `style` over the `footprint` as a new connected component
+    /// and returns the ids of the inserted faces.
+    ///
+    /// The footprint is given like for `roof`, i.e., possibly non-convex outlines with holes,
+    /// and describes the top of the walls at height zero. The eaves are moved outwards by
+    /// `overhang` and continue the slopes downwards. Except for shed roofs, the roofs are
+    /// built on the straight skeleton of the footprint, so they have a face per edge and
+    /// gables or mansard breaks split some of them. Slopes are tagged with "roof" and the
+    /// vertical ends of gable roofs with "gable". The roof is open at the eaves.
+    ///
+    /// Gables work best for footprints made of rectangular wings; where two gables would
+    /// share a ridge end, e.g., for square footprints, only the first one is created.
+    /// Shed roofs need a single outline without holes.
+    fn insert_styled_roof(
+        &mut self,
+        footprint: &[Vec<T::Vec2>],
+        style: RoofStyle<T::S>,
+        overhang: T::S,
+    ) -> Vec<T::F> {
+        assert!(overhang >= T::S::ZERO, "the overhang must not be negative");
+        let eaves = footprint
+            .iter()
+            .map(|ring| {
+                if overhang > T::S::ZERO {
+                    offset_polygon(ring, overhang, OffsetJoin::Miter(T::S::INFINITY))
+                } else {
+                    ring.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        assert!(
+            eaves.iter().zip(footprint).all(|(e, f)| e.len() == f.len()),
+            "the footprint must not have duplicate points"
+        );
+        let lift = |p: T::Vec2, h: T::S| T::VP::from_pos(T::Vec::from_xyz(p.x(), h, -p.y()));
+        let mut builder = SoupBuilder::<3, T>::new();
+
+        let (pitch, upper, break_time) = match style {
+            RoofStyle::Shed(pitch, low_edge) => {
+                assert!(footprint.len() == 1, "shed roofs need a single outline");
+                let ring = &footprint[0];
+                let a = ring[low_edge];
+                let d = (ring[(low_edge + 1) % ring.len()] - a).normalize();
+                let inwards = T::Vec2::new(-d.y(), d.x());
+                let cycle = eaves[0]
+                    .iter()
+                    .map(|p| builder.add_vertex(lift(*p, (*p - a).dot(&inwards) * pitch.tan())))
+                    .collect::<Vec<_>>();
+                builder.add_tagged_face(cycle, "roof");
+                return builder.insert_into(self);
+            }
+            RoofStyle::Hip(pitch) | RoofStyle::Gable(pitch) => (pitch, pitch, T::S::INFINITY),
+            RoofStyle::Mansard(lower, upper, distance) => (lower, upper, distance + overhang),
+        };
+
+        // the skeleton time is the horizontal distance from the eaves
+        let skeleton = straight_skeleton(&eaves);
+        let height = |t: T::S| {
+            if t <= break_time {
+                (t - overhang) * pitch.tan()
+            } else {
+                (break_time - overhang) * pitch.tan() + (t - break_time) * upper.tan()
+            }
+        };
+        let mut nodes = skeleton.nodes.clone();
+        let mut faces = skeleton.faces;
+        let mut gables = HashSet::new();
+
+        if matches!(style, RoofStyle::Gable(_)) {
+            // move the ends of the ridges onto the vertical planes above their eaves
+            let mut moved = HashSet::new();
+            for (e, face) in faces.iter().enumerate() {
+                if face.len() != 3 || moved.contains(&face[2]) {
+                    continue;
+                }
+                let (a, b) = (nodes[face[0]].0, nodes[face[1]].0);
+                let d = (b - a).normalize();
+                let apex = nodes[face[2]].0;
+                nodes[face[2]].0 = a + d * (apex - a).dot(&d);
+                moved.insert(face[2]);
+                gables.insert(e);
+            }
+        }
+
+        if break_time.is_finite() {
+            faces = split_faces(&mut nodes, &faces, break_time);
+        }
+        for (p, t) in &nodes {
+            builder.add_vertex(lift(*p, height(*t)));
+        }
+        for (e, face) in faces.into_iter().enumerate() {
+            let tag = if gables.contains(&e) { "gable" } else { "roof" };
+            builder.add_tagged_face(face, tag);
+        }
+        builder.insert_into(self)
+    }
+}
+
+/// Splits the faces of a straight skeleton where the time reaches `time`. New nodes are
+/// appended to `nodes` and shared between neighboring faces. Each face is monotone with
+/// respect to its edge, so the cut parallel to the edge pairs up along the cut line.
+fn split_faces<Vec2: Vector2D>(
+    nodes: &mut Vec<(Vec2, Vec2::S)>,
+    faces: &[Vec<usize>],
+    time: Vec2::S,
+) -> Vec<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Side {
+        Below,
+        Above,
+        // the boundary enters the region below the cut
+        Down,
+        // the boundary leaves the region below the cut
+        Up,
+    }
+
+    let eps = Vec2::S::EPS.sqrt()
+        * nodes
+            .iter()
+            .map(|(p, _)| p.x().abs().max(p.y().abs()))
+            .fold(Vec2::S::ONE, |a, b| a.max(b));
+    let mut crossings: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut result = Vec::new();
+    for face in faces {
+        let n = face.len();
+        let times = face.iter().map(|v| nodes[*v].1).collect::<Vec<_>>();
+        let above = |i: usize| times[i % n] > time + eps;
+        let on = |i: usize| (times[i % n] - time).abs() <= eps;
+
+        // insert the crossings and classify the points
+        let mut cycle: Vec<(usize, Side)> = Vec::new();
+        for i in 0..n {
+            let side = if above(i) {
+                Side::Above
+            } else if on(i) && above(i + n - 1) && above(i + 1) {
+                // only touches the cut
+                Side::Above
+            } else if on(i) && above(i + n - 1) {
+                Side::Down
+            } else if on(i) && above(i + 1) {
+                Side::Up
+            } else {
+                Side::Below
+            };
+            cycle.push((face[i], side));
+            if !on(i) && !on(i + 1) && above(i) != above(i + 1) {
+                let (u, v) = (face[i], face[(i + 1) % n]);
+                let key = (u.min(v), u.max(v));
+                let x = *crossings.entry(key).or_insert_with(|| {
+                    let ((pu, tu), (pv, tv)) = (nodes[u], nodes[v]);
+                    let s = (time - tu) / (tv - tu);
+                    nodes.push((pu + (pv - pu) * s, time));
+                    nodes.len() - 1
+                });
+                cycle.push((x, if above(i) { Side::Down } else { Side::Up }));
+            }
+        }
+
+        let cuts = cycle
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, s))| *s == Side::Down || *s == Side::Up)
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>();
+        if cuts.is_empty() {
+            result.push(face.clone());
+            continue;
+        }
+
+        // the position of the cuts along the direction of the edge
+        let dir = nodes[face[1]].0 - nodes[face[0]].0;
+        let along = |k: usize| nodes[cycle[k].0].0.dot(&dir);
+        // the next cut of the given kind along the cut line in the given direction
+        let next_cut = |k: usize, kind: Side, forward: bool| {
+            cuts.iter()
+                .copied()
+                .filter(|&c| cycle[c].1 == kind)
+                .filter(|&c| (along(c) > along(k)) == forward && c != k)
+                .min_by(|&a, &b| {
+                    let (a, b) = (along(a), along(b));
+                    let ord = a.partial_cmp(&b).unwrap();
+                    if forward {
+                        ord
+                    } else {
+                        ord.reverse()
+                    }
+                })
+                .unwrap()
+        };
+
+        // below the cut, the pieces follow the boundary from `Down` to `Up` and then the
+        // cut line backwards; above the cut from `Up` to `Down` and then forwards
+        for (start_kind, end_kind, forward) in
+            [(Side::Down, Side::Up, false), (Side::Up, Side::Down, true)]
+        {
+            let mut used = HashSet::new();
+            for &start in &cuts {
+                if cycle[start].1 != start_kind || used.contains(&start) {
+                    continue;
+                }
+                let mut piece = Vec::new();
+                let mut k = start;
+                loop {
+                    used.insert(k);
+                    // follow the boundary to the end of the chain
+                    loop {
+                        piece.push(cycle[k].0);
+                        if cycle[k].1 == end_kind {
+                            break;
+                        }
+                        k = (k + 1) % cycle.len();
+                    }
+                    k = next_cut(k, start_kind, forward);
+                    if k == start {
+                        break;
+                    }
+                }
+                result.push(piece);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_roof_rectangle() {
+        let footprint = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ];
+        let roof = Mesh3d64::roof(&[footprint], |d| d * 0.5);
+        assert!(roof.check().is_ok());
+        assert_eq!(roof.num_faces(), 4);
+        assert_eq!(roof.num_vertices(), 6);
+        // the ridge runs along the middle at half the width times the slope
+        let ridge = roof
+            .vertices()
+            .filter(|v| v.pos().y > 0.0)
+            .map(|v| v.pos())
+            .collect::<Vec<_>>();
+        assert_eq!(ridge.len(), 2);
+        for p in ridge {
+            assert!(p.y.is_about(0.5, 1e-12));
+            assert!(p.z.is_about(-1.0, 1e-12));
+        }
+        // all faces point upwards
+        assert!(roof.faces().all(|f| f.normal(&roof).y > 0.0));
+    }
+
+    #[test]
+    fn test_roof_courtyard() {
+        let outer = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(6.0, 0.0),
+            Vec2::new(6.0, 4.0),
+            Vec2::new(3.0, 4.0),
+            Vec2::new(3.0, 6.0),
+            Vec2::new(0.0, 6.0),
+        ];
+        let hole = vec![
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 1.0),
+        ];
+        let roof = Mesh3d64::roof(&[outer, hole], |d| d);
+        assert!(roof.check().is_ok());
+        assert_eq!(roof.num_faces(), 10);
+        assert!(roof.faces().all(|f| f.normal(&roof).y > 0.0));
+        // with a pitch of 45 degrees, the area is sqrt(2) times the area of the footprint
+        let area = roof
+            .faces()
+            .map(|f| {
+                let ps = f.vertices(&roof).map(|v| v.pos()).collect::<Vec<_>>();
+                ps.into_iter().normal().length()
+            })
+            .sum::<f64>();
+        assert!(area.is_about(2.0f64.sqrt() * (30.0 - 1.0), 1e-9));
+    }
+
+    /// Returns whether all vertices of each face lie in a common plane.
+    fn planar(mesh: &Mesh3d64) -> bool {
+        mesh.faces().all(|f| {
+            let ps = f.vertices(mesh).map(|v| v.pos()).collect::<Vec<_>>();
+            let n = ps.iter().copied().normal().normalize();
+            ps.iter().all(|p| (p - ps[0]).dot(&n).abs() < 1e-9)
+        })
+    }
+
+    fn rectangle(w: f64, h: f64) -> Vec<Vec2<f64>> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(w, 0.0),
+            Vec2::new(w, h),
+            Vec2::new(0.0, h),
+        ]
+    }
+
+    fn l_shape() -> Vec<Vec2<f64>> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(8.0, 0.0),
+            Vec2::new(8.0, 4.0),
+            Vec2::new(2.0, 4.0),
+            Vec2::new(2.0, 8.0),
+            Vec2::new(0.0, 8.0),
+        ]
+    }
+
+    #[test]
+    fn test_hip_and_gable_roof() {
+        let pitch = PI / 4.0;
+        let hip = Mesh3d64::hip_roof(&[rectangle(4.0, 2.0)], pitch, 0.5);
+        assert!(hip.check().is_ok());
+        assert_eq!(hip.num_faces(), 4);
+        assert!(hip.faces().all(|f| f.normal(&hip).y > 0.0));
+        for v in hip.vertices() {
+            let p = v.pos();
+            if p.y < 0.0 {
+                // the eaves overhang the walls
+                assert!(p.y.is_about(-0.5, 1e-9));
+                assert!(p.x.is_about(-0.5, 1e-9) || p.x.is_about(4.5, 1e-9));
+            } else {
+                assert!(p.y.is_about(1.0, 1e-9));
+            }
+        }
+
+        // the short sides become vertical gables and the ridge reaches the eaves
+        let gable = Mesh3d64::gable_roof(&[rectangle(4.0, 2.0)], pitch, 0.5);
+        assert!(gable.check().is_ok());
+        assert!(planar(&gable));
+        let tagged = Mesh3d64Tagged::gable_roof(&[rectangle(4.0, 2.0)], pitch, 0.5);
+        assert_eq!(tagged.select_by_tag("gable").len(), 2);
+        assert_eq!(tagged.select_by_tag("roof").len(), 2);
+        for f in gable.faces().filter(|f| f.vertices(&gable).count() == 3) {
+            assert!(f.normal(&gable).y.abs() < 1e-9);
+        }
+        let ridge = gable
+            .vertices()
+            .filter(|v| v.pos().y > 0.0)
+            .collect::<Vec<_>>();
+        assert_eq!(ridge.len(), 2);
+        assert!(ridge
+            .iter()
+            .all(|v| v.pos().x.is_about(-0.5, 1e-9) || v.pos().x.is_about(4.5, 1e-9)));
+
+        // each wing of an L-shaped footprint gets a gable
+        let l = Mesh3d64::gable_roof(&[l_shape()], pitch, 0.0);
+        assert!(l.check().is_ok());
+        assert!(planar(&l));
+        let vertical = l.faces().filter(|f| f.normal(&l).y.abs() < 1e-9).count();
+        assert_eq!(vertical, 2);
+    }
+
+    #[test]
+    fn test_shed_roof() {
+        let pitch = 0.25f64.atan();
+        let shed = Mesh3d64::shed_roof(&[rectangle(4.0, 2.0)], pitch, 0, 0.5);
+        assert!(shed.check().is_ok());
+        assert_eq!(shed.num_faces(), 1);
+        assert!(planar(&shed));
+        for v in shed.vertices() {
+            // the height grows with the distance from the low edge
+            let p = v.pos();
+            assert!(p.y.is_about(-p.z * 0.25, 1e-9));
+        }
+        let heights = shed.vertices().map(|v| v.pos().y).collect::<Vec<_>>();
+        assert!(heights.iter().any(|h| h.is_about(-0.125, 1e-9)));
+        assert!(heights.iter().any(|h| h.is_about(0.625, 1e-9)));
+    }
+
+    #[test]
+    fn test_mansard_roof() {
+        let (lower, upper) = (PI / 3.0, PI / 9.0);
+        let mansard = Mesh3d64::mansard_roof(&[rectangle(6.0, 4.0)], lower, upper, 0.5, 0.0);
+        assert!(mansard.check().is_ok());
+        assert!(planar(&mansard));
+        assert_eq!(mansard.num_faces(), 8);
+        assert!(mansard.faces().all(|f| f.normal(&mansard).y > 0.0));
+        let lip = 0.5 * lower.tan();
+        let top = lip + 1.5 * upper.tan();
+        for v in mansard.vertices() {
+            let y = v.pos().y;
+            assert!(y.is_about(0.0, 1e-9) || y.is_about(lip, 1e-9) || y.is_about(top, 1e-9));
+        }
+
+        // breaking at the ridge gives a hip roof
+        let hip = Mesh3d64::mansard_roof(&[rectangle(6.0, 4.0)], lower, upper, 2.0, 0.0);
+        assert!(hip.check().is_ok());
+        assert_eq!(hip.num_faces(), 4);
+
+        // a flat top
+        let flat = Mesh3d64::mansard_roof(&[rectangle(6.0, 4.0)], lower, 0.0, 0.5, 0.0);
+        assert!(flat.check().is_ok());
+        assert!(flat.vertices().all(|v| v.pos().y <= lip + 1e-9));
+
+        // the narrow wing lies completely below the break while the wide wing is cut
+        let l = Mesh3d64::mansard_roof(&[l_shape()], lower, upper, 1.5, 0.25);
+        assert!(l.check().is_ok());
+        assert!(planar(&l));
+        assert!(l.faces().all(|f| f.normal(&l).y > 0.0));
+        assert!(l.num_faces() > 6 && l.num_faces() < 12);
+        let area = |m: &Mesh3d64| {
+            m.faces()
+                .map(|f| {
+                    let ps = f.vertices(m).map(|v| v.pos()).collect::<Vec<_>>();
+                    ps.into_iter().normal().y
+                })
+                .sum::<f64>()
+        };
+        // the projected area is the area of the footprint grown by the overhang
+        assert!(area(&l).is_about(40.0 + 0.25 * 32.0 + 0.25 * 0.25 * 4.0, 1e-9));
+    }
+}
@@ -1,12 +1,14 @@
 use crate::{
     math::{HasPosition, IndexType, Scalar, Vector},
     mesh::{
-        DefaultEdgePayload, DefaultFacePayload, HalfEdge, HalfEdgeSemiBuilder, MeshType3D,
-        MeshTypeHalfEdge, SlerpVertexInterpolator,
+        DefaultEdgePayload, DefaultFacePayload, EdgeBasics, FaceBasics, HalfEdge,
+        HalfEdgeSemiBuilder, MeshType3D, MeshTypeHalfEdge, SlerpVertexInterpolator, SoupBuilder,
+        VertexBasics,
     },
     operations::{MeshExtrude, MeshLoft, MeshSubdivision, SubdivisionDescription},
-    primitives::{Make2dShape, MakePrismatoid},
+    primitives::{fit_budget, Make2dShape, MakePrismatoid},
 };
+use std::collections::{HashMap, HashSet};
 
 /// Convert a radius 'r' to edge length 'a' of an icosahedron.
 pub fn icosahedron_r2a<S: Scalar>(r: S) -> S {
@@ -18,6 +20,32 @@ pub fn icosahedron_a2r<S: Scalar>(a: S) -> S {
     (S::TEN + S::TWO * S::FIVE.sqrt()).sqrt() * a / S::FOUR
 }
 
+/// Parameters for geodesic spheres and domes, see `MakeSphere::geodesic_polyhedron`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeodesicOptions<S: Scalar> {
+    /// The pattern (b, c) each face of the base polyhedron is subdivided with.
+    /// Unlike `subdivision_frequency`, any class I, II, or III frequency is supported.
+    pub frequency: SubdivisionDescription,
+
+    /// If set, only the part above the given latitude (in radians, 0 is the equator and
+    /// the y-axis points to the north pole) is kept, e.g., for architectural domes.
+    /// Triangles crossing the latitude are cut, so the base ring is flat.
+    pub truncation: Option<S>,
+
+    /// Whether the base ring of a truncated sphere is closed with a single flat face.
+    pub cap: bool,
+}
+
+impl<S: Scalar> Default for GeodesicOptions<S> {
+    fn default() -> Self {
+        Self {
+            frequency: SubdivisionDescription::new(1, 0),
+            truncation: None,
+            cap: true,
+        }
+    }
+}
+
 // TODO: Reduce type requirements
 
 /// A trait for creating sphere approximations.
@@ -68,6 +96,15 @@ where
         mesh
     }
 
+    /// Create a uv sphere with a given `radius` and at most `max_triangles` triangles.
+    /// The rings and columns are chosen such that the quads are about square.
+    /// Gives the coarsest uv sphere with 6 triangles if the budget is smaller.
+    fn uv_sphere_with_budget(radius: T::S, max_triangles: usize) -> Self {
+        let n = fit_budget(max_triangles, 2, |n| 4 * n * (n - 1));
+        let m = fit_budget(max_triangles, 3, |m| 2 * m * (n - 1));
+        Self::uv_sphere(radius, n, m)
+    }
+
     /// Create a dodecahedron with a given `radius`.
     fn dodecahedron(radius: T::S) -> Self {
         // https://en.wikipedia.org/wiki/Regular_dodecahedron#/media/File:Dodecahedron_vertices.svg
@@ -221,4 +258,428 @@ where
         );
         mesh
     }
+
+    /// Create a geodesic polyhedron with a given `radius` by subdividing each triangle of
+    /// the closed `base` mesh centered at the origin with the frequency (b, c) given in the
+    /// `options` and projecting the vertices onto the sphere.
+    ///
+    /// The triangles of class II and III patterns cross the edges of the base faces, so
+    /// this builds them on the triangular lattice unfolded over the neighboring faces.
+    /// The result has $T \cdot F$ faces, where $T$ is the triangulation number of the
+    /// frequency and $F$ the number of faces of the base.
+    fn geodesic_polyhedron(base: &Self, radius: T::S, options: &GeodesicOptions<T::S>) -> Self {
+        let (b, c) = (options.frequency.b() as i64, options.frequency.c() as i64);
+        // corners of the base face in axial coordinates of the lattice
+        let corners = [(0, 0), (b, c), (-c, b + c)];
+        let det = b * b + b * c + c * c;
+        // barycentric coordinates of q multiplied by `det`
+        let bary = |(u, v): (i64, i64)| {
+            let cross = |(au, av): (i64, i64), (bu, bv): (i64, i64)| au * bv - av * bu;
+            let l1 = cross((u, v), corners[2]);
+            let l2 = cross(corners[1], (u, v));
+            [det - l1 - l2, l1, l2]
+        };
+
+        let project = |p: T::Vec| p.normalize() * radius;
+        let mut builder =
+            SoupBuilder::<3, T>::new().with_weld(radius * T::S::EPS.sqrt() * T::S::from(1e-2));
+        let mut triangles = HashSet::new();
+        for f in base.faces() {
+            let edges = f.edges(base).collect::<Vec<_>>();
+            assert!(
+                edges.len() == 3,
+                "geodesic polyhedra require a triangular base"
+            );
+            let xs = edges
+                .iter()
+                .map(|e| e.origin(base).pos())
+                .collect::<Vec<T::Vec>>();
+            // the vertex opposite of corner k in the face across the edge opposite of corner k
+            let ds = (0..3)
+                .map(|k| {
+                    let twin = edges[(k + 1) % 3].twin(base);
+                    (!twin.is_boundary_self()).then(|| twin.prev(base).origin(base).pos())
+                })
+                .collect::<Vec<Option<T::Vec>>>();
+            let det_s = T::S::from_usize(det as usize);
+            let position = |q: (i64, i64)| -> Option<T::Vec> {
+                let l = bary(q);
+                let weighted = |w: [i64; 3], ps: [T::Vec; 3]| {
+                    T::Vec::stable_sum((0..3).map(|i| ps[i] * T::S::from_f64(w[i] as f64))) / det_s
+                };
+                match (0..3).find(|k| l[*k] < 0) {
+                    None => Some(project(weighted(l, [xs[0], xs[1], xs[2]]))),
+                    Some(k) => {
+                        // unfold the neighboring face into the plane of this face
+                        let (i, j) = ((k + 1) % 3, (k + 2) % 3);
+                        let mut w = [0; 3];
+                        let mut ps = [xs[0], xs[1], xs[2]];
+                        w[i] = l[i] + l[k];
+                        w[j] = l[j] + l[k];
+                        w[k] = -l[k];
+                        ps[k] = ds[k]?;
+                        Some(project(weighted(w, ps)))
+                    }
+                }
+            };
+
+            for u in -c - 1..=b + 1 {
+                for v in -1..=b + c + 1 {
+                    for t in [
+                        [(u, v), (u + 1, v), (u, v + 1)],
+                        [(u + 1, v), (u + 1, v + 1), (u, v + 1)],
+                    ] {
+                        // keep the lattice triangles whose centroid is inside the face
+                        let ls = t.map(bary);
+                        if (0..3).any(|k| ls[0][k] + ls[1][k] + ls[2][k] < 0) {
+                            continue;
+                        }
+                        let Some(ps) = t.iter().map(|q| position(*q)).collect::<Option<Vec<_>>>()
+                        else {
+                            continue;
+                        };
+                        let ids = ps
+                            .into_iter()
+                            .map(|p| builder.add_vertex(T::VP::from_pos(p)))
+                            .collect::<Vec<_>>();
+                        let mut key = ids.clone();
+                        key.sort();
+                        // triangles on the edges are found from both faces
+                        if triangles.insert(key) {
+                            builder.add_face(ids);
+                        }
+                    }
+                }
+            }
+        }
+
+        let Some(latitude) = options.truncation else {
+            return builder.build();
+        };
+        assert!(
+            latitude.abs() < T::S::PI * T::S::HALF,
+            "the truncation must be between the poles"
+        );
+        Self::truncate_sphere(builder, radius, latitude, options.cap)
+    }
+
+    /// Cuts the triangles of the sphere in the `soup` at the given `latitude`,
+    /// keeps the northern part, and closes the base ring with a flat face if `cap` is set.
+    fn truncate_sphere(soup: SoupBuilder<3, T>, radius: T::S, latitude: T::S, cap: bool) -> Self {
+        let h = radius * latitude.sin();
+        let ring = radius * latitude.cos();
+        let to_ring = |p: T::Vec| {
+            let r = (p.x() * p.x() + p.z() * p.z()).sqrt();
+            T::Vec::from_xyz(p.x() * ring / r, h, p.z() * ring / r)
+        };
+        // snap vertices close to the base ring onto it to avoid slivers
+        let tol = radius * T::S::EPS.sqrt();
+        let mut vps = (0..soup.num_vertices())
+            .map(|i| soup.vertex(i).clone())
+            .collect::<Vec<_>>();
+        for vp in vps.iter_mut() {
+            if (vp.pos().y() - h).abs() <= tol {
+                let p = to_ring(*vp.pos());
+                vp.set_pos(p);
+            }
+        }
+        let inside = |vp: &T::VP| vp.pos().y() >= h - tol;
+
+        let mut cuts: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut builder = SoupBuilder::<3, T>::new();
+        let mut ids: HashMap<usize, usize> = HashMap::new();
+        for face in (0..soup.num_faces()).map(|i| soup.face(i)) {
+            let mut polygon = Vec::new();
+            for k in 0..face.len() {
+                let (a, b) = (face[k], face[(k + 1) % face.len()]);
+                let (pa, pb) = (*vps[a].pos(), *vps[b].pos());
+                if inside(&vps[a]) {
+                    polygon.push(
+                        *ids.entry(a)
+                            .or_insert_with(|| builder.add_vertex(vps[a].clone())),
+                    );
+                }
+                // vertices on the ring don't cut the edge
+                if (pa.y() - h).abs() > tol
+                    && (pb.y() - h).abs() > tol
+                    && inside(&vps[a]) != inside(&vps[b])
+                {
+                    polygon.push(*cuts.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                        let t = (h - pa.y()) / (pb.y() - pa.y());
+                        builder.add_vertex(T::VP::from_pos(to_ring(pa + (pb - pa) * t)))
+                    }));
+                }
+            }
+            if polygon
+                .iter()
+                .all(|v| (builder.vertex(*v).pos().y() - h).abs() <= tol)
+            {
+                // lies in the cap
+                continue;
+            }
+            for k in 1..polygon.len().saturating_sub(1) {
+                builder.add_face([polygon[0], polygon[k], polygon[k + 1]]);
+            }
+        }
+
+        let mut mesh = builder.build();
+        if cap {
+            let boundary = mesh.edges().find(|e| e.is_boundary_self()).map(|e| e.id());
+            if let Some(e) = boundary {
+                mesh.close_hole_default(e);
+            }
+        }
+        mesh
+    }
+
+    /// Create a geodesic icosahedron with a given `radius`, see `geodesic_polyhedron`.
+    fn geodesic_icosahedron_with(radius: T::S, options: &GeodesicOptions<T::S>) -> Self {
+        let base = Self::regular_icosahedron(icosahedron_r2a(radius));
+        Self::geodesic_polyhedron(&base, radius, options)
+    }
+
+    /// Create a geodesic octahedron with a given `radius`, see `geodesic_polyhedron`.
+    fn geodesic_octahedron_with(radius: T::S, options: &GeodesicOptions<T::S>) -> Self {
+        Self::geodesic_polyhedron(&Self::regular_octahedron(radius), radius, options)
+    }
+
+    /// Create a geodesic tetrahedron with a given `radius`, see `geodesic_polyhedron`.
+    fn geodesic_tetrahedron_with(radius: T::S, options: &GeodesicOptions<T::S>) -> Self {
+        // edge length of the tetrahedron with the given circumradius
+        let l = T::S::FOUR * radius / (T::S::TWO * T::S::THREE).sqrt();
+        Self::geodesic_polyhedron(&Self::regular_tetrahedron(l), radius, options)
+    }
+
+    /// Create a geodesic icosahedron with a given `radius` and at most `max_triangles` triangles.
+    /// Uses the class I or II frequency with the most triangles within the budget.
+    /// Gives the icosahedron if the budget is smaller than 20.
+    fn icosphere_with_budget(radius: T::S, max_triangles: usize) -> Self {
+        // class I has 20 b² and class II 60 b² triangles
+        let one = fit_budget(max_triangles, 1, |b| 20 * b * b);
+        let two = fit_budget(max_triangles, 1, |b| 60 * b * b);
+        let frequency = if 3 * two * two > one * one && 60 * two * two <= max_triangles {
+            SubdivisionDescription::new(two, two)
+        } else {
+            SubdivisionDescription::new(one, 0)
+        };
+        Self::geodesic_icosahedron_with(
+            radius,
+            &GeodesicOptions {
+                frequency,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Create a geodesic dome, i.e., a geodesic icosahedron with the given `radius` and
+    /// `frequency` that is cut at the given `latitude` (in radians) and closed with a flat base.
+    fn geodesic_dome(radius: T::S, frequency: SubdivisionDescription, latitude: T::S) -> Self {
+        Self::geodesic_icosahedron_with(
+            radius,
+            &GeodesicOptions {
+                frequency,
+                truncation: Some(latitude),
+                cap: true,
+            },
+        )
+    }
+
+    /// Refines the faces of the given sphere approximation centered at the origin
+    /// with `adaptive_subdivision`, projecting new vertices onto the sphere.
+    /// The `level` is evaluated at points on the sphere.
+    fn adaptive_geodesic(base: &Self, radius: T::S, level: impl Fn(T::Vec) -> usize) -> Self {
+        let project = |p: T::Vec| p.normalize() * radius;
+        base.adaptive_subdivision(
+            |p| level(project(p)),
+            |a, b| T::VP::from_pos(project(*a.pos() + *b.pos())),
+        )
+    }
+
+    /// An alias for `adaptive_geodesic_icosahedron`.
+    fn adaptive_icosphere(radius: T::S, level: impl Fn(T::Vec) -> usize) -> Self {
+        Self::adaptive_geodesic_icosahedron(radius, level)
+    }
+
+    /// Create a geodesic icosahedron with a given `radius` whose triangles are split
+    /// `level(p)` times near the point `p` on the sphere. A constant level `k` gives
+    /// the same mesh as `geodesic_icosahedron(radius, 2^k)`.
+    ///
+    /// For example, `|p| if p.distance(&poi) < 0.5 { 5 } else { 1 }` adds detail around
+    /// `poi` and `|p| if p.y().abs() < 0.2 { 4 } else { 2 }` along the equator.
+    fn adaptive_geodesic_icosahedron(radius: T::S, level: impl Fn(T::Vec) -> usize) -> Self {
+        let base = Self::regular_icosahedron(icosahedron_r2a(radius));
+        Self::adaptive_geodesic(&base, radius, level)
+    }
+
+    /// Create a geodesic tetrahedron with a given `radius` and non-uniform subdivisions,
+    /// see `adaptive_geodesic_icosahedron`.
+    fn adaptive_geodesic_tetrahedron(radius: T::S, level: impl Fn(T::Vec) -> usize) -> Self {
+        // edge length of the tetrahedron with the given circumradius
+        let l = T::S::FOUR * radius / (T::S::TWO * T::S::THREE).sqrt();
+        Self::adaptive_geodesic(&Self::regular_tetrahedron(l), radius, level)
+    }
+
+    /// Create a geodesic octahedron with a given `radius` and non-uniform subdivisions,
+    /// see `adaptive_geodesic_icosahedron`.
+    fn adaptive_geodesic_octahedron(radius: T::S, level: impl Fn(T::Vec) -> usize) -> Self {
+        Self::adaptive_geodesic(&Self::regular_octahedron(radius), radius, level)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn volume(mesh: &Mesh3d64) -> f64 {
+        mesh.report().volume.unwrap()
+    }
+
+    #[test]
+    fn test_geodesic_classes() {
+        // class I, II, and III frequencies with their vertex counts 10 T + 2
+        for (b, c, class) in [
+            (3, 0, GeodesicClass::I),
+            (4, 0, GeodesicClass::I),
+            (2, 2, GeodesicClass::II),
+            (2, 1, GeodesicClass::III),
+            (3, 1, GeodesicClass::III),
+        ] {
+            let frequency = SubdivisionDescription::new(b, c);
+            assert_eq!(frequency.class(), class);
+            let sphere = Mesh3d64::geodesic_icosahedron_with(
+                2.0,
+                &GeodesicOptions {
+                    frequency,
+                    ..Default::default()
+                },
+            );
+            let t = frequency.triangulation_number();
+            assert!(sphere.check().is_ok());
+            assert!(!sphere.is_open());
+            assert_eq!(sphere.num_faces(), 20 * t);
+            assert_eq!(sphere.num_vertices(), 10 * t + 2);
+            assert!(sphere
+                .vertices()
+                .all(|v| (v.pos().norm() - 2.0).abs() < 1e-9));
+            assert!(volume(&sphere) > 0.0);
+            // the pattern is regular, so there are only vertices of degree 5 and 6
+            assert_eq!(
+                sphere.vertices().filter(|v| v.degree(&sphere) == 5).count(),
+                12
+            );
+            assert!(sphere.vertices().all(|v| v.degree(&sphere) <= 6));
+        }
+
+        // class I matches the uniform subdivision
+        let sphere = Mesh3d64::geodesic_icosahedron_with(
+            2.0,
+            &GeodesicOptions {
+                frequency: SubdivisionDescription::new(4, 0),
+                ..Default::default()
+            },
+        );
+        let reference = Mesh3d64::geodesic_icosahedron(2.0, 4);
+        assert_eq!(sphere.num_vertices(), reference.num_vertices());
+        assert!((volume(&sphere) - volume(&reference)).abs() < 1e-2);
+
+        for sphere in [
+            Mesh3d64::geodesic_octahedron_with(
+                1.0,
+                &GeodesicOptions {
+                    frequency: SubdivisionDescription::new(2, 1),
+                    ..Default::default()
+                },
+            ),
+            Mesh3d64::geodesic_tetrahedron_with(
+                1.0,
+                &GeodesicOptions {
+                    frequency: SubdivisionDescription::new(3, 3),
+                    ..Default::default()
+                },
+            ),
+        ] {
+            assert!(sphere.check().is_ok());
+            assert!(!sphere.is_open());
+            assert!(sphere
+                .vertices()
+                .all(|v| (v.pos().norm() - 1.0).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_geodesic_dome() {
+        let frequency = SubdivisionDescription::new(4, 2);
+        let dome = Mesh3d64::geodesic_dome(2.0, frequency, 0.0);
+        assert!(dome.check().is_ok());
+        assert!(!dome.is_open());
+        assert!(dome.vertices().all(|v| v.pos().y() >= -1e-9));
+        assert!(dome.vertices().all(|v| (v.pos().norm() - 2.0).abs() < 1e-9));
+        let hemisphere = 2.0 / 3.0 * std::f64::consts::PI * 8.0;
+        assert!((volume(&dome) - hemisphere).abs() < 0.05 * hemisphere);
+
+        // the base is a single flat face
+        let base = dome
+            .faces()
+            .filter(|f| f.vertices(&dome).all(|v| v.pos().y().abs() < 1e-9))
+            .collect::<Vec<_>>();
+        assert_eq!(base.len(), 1);
+        assert!(base[0].num_vertices(&dome) > 10);
+
+        // without a cap, the base ring stays open
+        let open = Mesh3d64::geodesic_icosahedron_with(
+            2.0,
+            &GeodesicOptions {
+                frequency,
+                truncation: Some(0.5),
+                cap: false,
+            },
+        );
+        assert!(open.check().is_ok());
+        assert!(open.is_open());
+        assert!(open
+            .vertices()
+            .all(|v| v.pos().y() >= 2.0 * 0.5f64.sin() - 1e-9));
+    }
+
+    #[test]
+    fn test_adaptive_icosphere() {
+        // a constant level matches the uniform subdivision
+        let uniform = Mesh3d64::icosphere(2.0, 4);
+        let adaptive = Mesh3d64::adaptive_icosphere(2.0, |_| 2);
+        assert_eq!(adaptive.num_faces(), uniform.num_faces());
+        assert_eq!(adaptive.num_vertices(), uniform.num_vertices());
+
+        let poi = Vec3::new(0.0, 2.0, 0.0);
+        for sphere in [
+            Mesh3d64::adaptive_icosphere(2.0, |p| if p.distance(&poi) < 1.0 { 4 } else { 1 }),
+            Mesh3d64::adaptive_geodesic_tetrahedron(2.0, |p| if p.y() > 1.5 { 4 } else { 0 }),
+            Mesh3d64::adaptive_geodesic_octahedron(2.0, |p| if p.x().abs() < 0.3 { 3 } else { 1 }),
+        ] {
+            assert!(sphere.check().is_ok());
+            assert!(!sphere.is_open());
+            assert_eq!(
+                sphere.num_vertices() + sphere.num_faces(),
+                sphere.num_edges() / 2 + 2
+            );
+            assert!(sphere.faces().all(|f| f.num_vertices(&sphere) == 3));
+            assert!(sphere
+                .vertices()
+                .all(|v| (v.pos().norm() - 2.0).abs() < 1e-9));
+        }
+
+        // the resolution is graded towards the point of interest
+        let sphere =
+            Mesh3d64::adaptive_icosphere(2.0, |p| if p.distance(&poi) < 1.0 { 4 } else { 1 });
+        let near = sphere
+            .vertices()
+            .filter(|v| v.pos().distance(&poi) < 1.0)
+            .count();
+        let far = sphere
+            .vertices()
+            .filter(|v| v.pos().distance(&-poi) < 1.0)
+            .count();
+        assert!(near > 10 * far);
+        assert!(sphere.num_faces() < Mesh3d64::icosphere(2.0, 16).num_faces() / 4);
+    }
 }
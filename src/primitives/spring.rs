@@ -0,0 +1,345 @@
+use super::{circle_profile, MakeTube, TubeJoint};
+use crate::{
+    math::{Scalar, Vector, Vector2D, Vector3D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshType3D},
+};
+
+/// Returns the points of a helix around the y-axis that starts at `(radius_start, 0, 0)`
+/// and winds counter-clockwise (seen from above) upwards by `pitch` per turn, i.e., a
+/// right-handed helix. The radius changes linearly from `radius_start` to `radius_end`.
+/// Each full turn is approximated with `segments_per_turn` segments.
+pub fn helix<Vec3: Vector3D>(
+    radius_start: Vec3::S,
+    radius_end: Vec3::S,
+    pitch: Vec3::S,
+    turns: Vec3::S,
+    segments_per_turn: usize,
+) -> Vec<Vec3> {
+    assert!(
+        segments_per_turn >= 3,
+        "a helix needs at least 3 segments per turn"
+    );
+    let n = (turns.to_f64() * segments_per_turn as f64).ceil().max(1.0) as usize;
+    (0..=n)
+        .map(|i| {
+            let t = Vec3::S::from_usize(i) / Vec3::S::from_usize(n);
+            let angle = Vec3::S::TWO * Vec3::S::PI * turns * t;
+            let r = radius_start + (radius_end - radius_start) * t;
+            Vec3::new(r * angle.cos(), pitch * turns * t, -r * angle.sin())
+        })
+        .collect()
+}
+
+/// Returns the ccw 2d outline of a stadium, i.e., a rectangle with semicircular ends,
+/// centered at the origin with the given total `length` along the x-axis and `width`
+/// along the y-axis. Each semicircle is approximated with `segments` segments.
+pub fn stadium<Vec2: Vector2D>(length: Vec2::S, width: Vec2::S, segments: usize) -> Vec<Vec2> {
+    assert!(segments >= 1);
+    assert!(
+        length >= width,
+        "a stadium can't be shorter than it is wide"
+    );
+    let r = width * Vec2::S::HALF;
+    let a = (length - width) * Vec2::S::HALF;
+    let mut points = Vec::with_capacity(2 * segments + 2);
+    for (cx, start) in [(a, -Vec2::S::HALF), (-a, Vec2::S::HALF)] {
+        for i in 0..=segments {
+            let angle =
+                Vec2::S::PI * (start + Vec2::S::from_usize(i) / Vec2::S::from_usize(segments));
+            let p = Vec2::new(cx + r * angle.cos(), r * angle.sin());
+            if points
+                .last()
+                .map_or(true, |q: &Vec2| q.distance_squared(&p) > Vec2::S::EPS)
+            {
+                points.push(p);
+            }
+        }
+    }
+    if points.len() > 1 && points[0].distance_squared(points.last().unwrap()) <= Vec2::S::EPS {
+        points.pop();
+    }
+    points
+}
+
+/// A trait for creating springs, coils, and chains by sweeping a wire along a path.
+///
+/// The springs and coils wind around the y-axis starting at the xz-plane.
+/// All shapes are watertight.
+pub trait MakeSpring<T: MeshType3D<Mesh = Self>>: MakeTube<T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Creates a helical spring with the given `radius` of the helix, `pitch` (the height
+    /// per turn), and number of `turns`, made of a round wire with the radius `wire_radius`
+    /// and `wire_segments` segments. Each turn is approximated with `segments_per_turn` segments.
+    ///
+    /// The turns intersect if the pitch is smaller than the diameter of the wire.
+    fn spring(
+        radius: T::S,
+        pitch: T::S,
+        turns: T::S,
+        wire_radius: T::S,
+        segments_per_turn: usize,
+        wire_segments: usize,
+    ) -> Self {
+        Self::spring_with_profile(
+            radius,
+            pitch,
+            turns,
+            &circle_profile::<T::Vec2>(wire_radius, wire_segments),
+            segments_per_turn,
+        )
+    }
+
+    /// Like `spring`, but with a custom ccw 2d `profile` of the wire, e.g., a rectangle
+    /// for a flat-wire spring. The profile is swept with a rotation-minimizing frame,
+    /// see `MakeTube::insert_sweep`.
+    fn spring_with_profile(
+        radius: T::S,
+        pitch: T::S,
+        turns: T::S,
+        profile: &[T::Vec2],
+        segments_per_turn: usize,
+    ) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_sweep(
+            &helix::<T::Vec>(radius, radius, pitch, turns, segments_per_turn),
+            profile,
+            TubeJoint::Miter,
+            false,
+            true,
+        );
+        mesh
+    }
+
+    /// Creates a coil whose radius grows linearly from `inner_radius` to `outer_radius`
+    /// over the given number of `turns`, e.g., a conical spring or, with a `pitch` of zero,
+    /// a flat spiral. Otherwise like `spring`.
+    ///
+    /// A flat spiral intersects itself unless the radius grows by more than the diameter
+    /// of the wire per turn.
+    fn coil(
+        inner_radius: T::S,
+        outer_radius: T::S,
+        pitch: T::S,
+        turns: T::S,
+        wire_radius: T::S,
+        segments_per_turn: usize,
+        wire_segments: usize,
+    ) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_sweep(
+            &helix::<T::Vec>(inner_radius, outer_radius, pitch, turns, segments_per_turn),
+            &circle_profile::<T::Vec2>(wire_radius, wire_segments),
+            TubeJoint::Miter,
+            false,
+            true,
+        );
+        mesh
+    }
+
+    /// Inserts a chain link, i.e., a round wire with the radius `wire_radius` swept along
+    /// a stadium with the given `length` and `width` (measured at the center of the wire),
+    /// as a new connected component. The link lies in the plane spanned by `along` and
+    /// `across`, which are the directions of its length and width, around `center`.
+    /// Each semicircle is approximated with `segments` segments.
+    /// Returns the ids of the inserted faces.
+    #[allow(clippy::too_many_arguments)]
+    fn insert_chain_link(
+        &mut self,
+        center: T::Vec,
+        along: T::Vec,
+        across: T::Vec,
+        length: T::S,
+        width: T::S,
+        wire_radius: T::S,
+        segments: usize,
+        wire_segments: usize,
+    ) -> Vec<T::F> {
+        let (along, across) = (along.normalize(), across.normalize());
+        let path = stadium::<T::Vec2>(length, width, segments)
+            .iter()
+            .map(|p| center + along * p.x() + across * p.y())
+            .collect::<Vec<_>>();
+        self.insert_sweep(
+            &path,
+            &circle_profile::<T::Vec2>(wire_radius, wire_segments),
+            TubeJoint::Miter,
+            true,
+            false,
+        )
+    }
+
+    /// Creates a chain link in the xy-plane centered at the origin, see `insert_chain_link`.
+    fn chain_link(
+        length: T::S,
+        width: T::S,
+        wire_radius: T::S,
+        segments: usize,
+        wire_segments: usize,
+    ) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_chain_link(
+            T::Vec::zero(),
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO),
+            T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO),
+            length,
+            width,
+            wire_radius,
+            segments,
+            wire_segments,
+        );
+        mesh
+    }
+
+    /// Creates a chain of `links` interlocking chain links along the x-axis starting at the
+    /// origin. Every other link is rotated by 90° around the x-axis and the links are
+    /// spaced such that the wires of neighboring links touch, i.e., `length - 2 * wire_radius`
+    /// apart. The links are separate connected components. Otherwise like `chain_link`.
+    fn chain(
+        links: usize,
+        length: T::S,
+        width: T::S,
+        wire_radius: T::S,
+        segments: usize,
+        wire_segments: usize,
+    ) -> Self {
+        let spacing = length - wire_radius * T::S::TWO;
+        let x = T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO);
+        let y = T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO);
+        let z = T::Vec::from_xyz(T::S::ZERO, T::S::ZERO, T::S::ONE);
+        let mut mesh = Self::default();
+        for i in 0..links {
+            mesh.insert_chain_link(
+                x * (spacing * T::S::from_usize(i)),
+                x,
+                if i % 2 == 0 { y } else { z },
+                length,
+                width,
+                wire_radius,
+                segments,
+                wire_segments,
+            );
+        }
+        mesh
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::f64::consts::PI;
+
+    fn volume(mesh: &Mesh3d64) -> f64 {
+        mesh.report().volume.unwrap()
+    }
+
+    #[test]
+    fn test_spring() {
+        let points = helix::<Vec3<f64>>(2.0, 2.0, 0.5, 3.0, 32);
+        assert_eq!(points.len(), 3 * 32 + 1);
+        assert!(points[0].distance(&Vec3::new(2.0, 0.0, 0.0)) < 1e-12);
+        assert!(points[3 * 32].distance(&Vec3::new(2.0, 1.5, 0.0)) < 1e-12);
+        // a quarter turn winds from +x to -z
+        assert!(points[8].distance(&Vec3::new(0.0, 0.125, -2.0)) < 1e-12);
+
+        let spring = Mesh3d64::spring(2.0, 0.5, 3.0, 0.1, 64, 12);
+        assert!(spring.check().is_ok());
+        assert!(!spring.is_open());
+        assert_eq!(spring.num_faces(), 3 * 64 * 12 + 2);
+        let wire = 3.0 * ((2.0 * PI * 2.0f64).powi(2) + 0.25).sqrt();
+        let expected = PI * 0.1 * 0.1 * wire;
+        assert!((volume(&spring) - expected).abs() < 0.05 * expected);
+
+        // a flat-wire spring
+        let profile = [
+            Vec2::new(-0.05, -0.15),
+            Vec2::new(0.05, -0.15),
+            Vec2::new(0.05, 0.15),
+            Vec2::new(-0.05, 0.15),
+        ];
+        let spring = Mesh3d64::spring_with_profile(2.0, 0.5, 3.0, &profile, 64);
+        assert!(spring.check().is_ok());
+        assert!(!spring.is_open());
+        let expected = 0.1 * 0.3 * wire;
+        assert!((volume(&spring) - expected).abs() < 0.05 * expected);
+
+        // conical coil
+        let coil = Mesh3d64::coil(1.0, 2.0, 0.5, 2.5, 0.1, 32, 8);
+        assert!(coil.check().is_ok());
+        assert!(!coil.is_open());
+        assert!(volume(&coil) > 0.0);
+        let (min, max) = coil
+            .vertices()
+            .map(|v| Vec2::new(v.pos().x, v.pos().z).length())
+            .fold((f64::MAX, 0.0f64), |(a, b), r| (a.min(r), b.max(r)));
+        assert!(min > 0.85 && min < 1.0 && max > 2.0 && max < 2.15);
+    }
+
+    #[test]
+    fn test_chain() {
+        let outline = stadium::<Vec2<f64>>(4.0, 2.0, 8);
+        assert_eq!(outline.len(), 18);
+        let polygon = Polygon2d::from_points(&outline);
+        assert!(polygon.is_ccw());
+        let ring = stadium::<Vec2<f64>>(2.0, 2.0, 8);
+        assert_eq!(ring.len(), 16);
+
+        let link = Mesh3d64::chain_link(4.0, 2.0, 0.2, 16, 12);
+        assert!(link.check().is_ok());
+        assert!(!link.is_open());
+        assert_eq!(link.num_faces(), 34 * 12);
+        // the area of the 12-gon times the length of the center line
+        let area = 6.0 * (PI / 6.0).sin() * 0.2 * 0.2;
+        let expected = area * (2.0 * 2.0 + PI * 2.0);
+        assert!((volume(&link) - expected).abs() < 0.02 * expected);
+
+        let chain = Mesh3d64::chain(5, 4.0, 2.0, 0.2, 16, 12);
+        assert!(chain.check().is_ok());
+        assert!(!chain.is_open());
+        assert_eq!(chain.num_faces(), 5 * link.num_faces());
+        assert!((volume(&chain) - 5.0 * volume(&link)).abs() < 1e-9);
+        let x_max = chain.vertices().map(|v| v.pos().x).fold(f64::MIN, f64::max);
+        // the last link ends at its center line plus about the radius of the wire
+        assert!((x_max - (4.0 * 3.6 + 2.2)).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_closed_sweep() {
+        // a closed, non-planar path where the rotation-minimizing frame doesn't return to
+        // its start, so the roll has to be distributed along the path
+        let path = (0..48)
+            .map(|i| {
+                let t = 2.0 * PI * i as f64 / 48.0;
+                Vec3::new(3.0 * t.cos(), (2.0 * t).sin(), 3.0 * t.sin())
+            })
+            .collect::<Vec<_>>();
+        let profile = [
+            Vec2::new(-0.2, -0.2),
+            Vec2::new(0.2, -0.2),
+            Vec2::new(0.2, 0.2),
+            Vec2::new(-0.2, 0.2),
+        ];
+        let mut mesh = Mesh3d64::default();
+        let faces = mesh.insert_sweep(&path, &profile, TubeJoint::Miter, true, false);
+        assert_eq!(faces.len(), 48 * 4);
+        assert!(mesh.check().is_ok());
+        assert!(!mesh.is_open());
+        let length = path
+            .iter()
+            .zip(path.iter().cycle().skip(1))
+            .map(|(a, b)| a.distance(b))
+            .sum::<f64>();
+        let expected = 0.4 * 0.4 * length;
+        assert!((volume(&mesh) - expected).abs() < 0.02 * expected);
+
+        // the side walls only twist slightly, even between the last and the first ring
+        for f in mesh.faces() {
+            let vs = f.vertices(&mesh).map(|v| v.pos()).collect::<Vec<_>>();
+            let rails = [vs[1].distance(&vs[2]), vs[3].distance(&vs[0])];
+            assert!(rails[0] < 0.6 && rails[1] < 0.6);
+        }
+    }
+}
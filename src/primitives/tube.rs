@@ -0,0 +1,528 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector, Vector2D, Vector3D},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshTrait, MeshType3D, SoupBuilder},
+    primitives::fit_budget,
+    tesselate::triangulate_rings,
+};
+
+/// How the tube is shaped at the interior vertices of the path.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TubeJoint {
+    /// The segments are cut at the bisecting plane and joined with a sharp corner.
+    #[default]
+    Miter,
+
+    /// The corner is replaced by a circular bend with the given number of segments.
+    /// The bend radius is twice the tube radius or smaller if the path segments are too short.
+    Round(usize),
+}
+
+/// A cross-section of the tube: The profile around `center` perpendicular to `dir`
+/// that is projected along `dir` onto the plane through `center` with normal `plane`.
+struct Station<V> {
+    center: V,
+    dir: V,
+    plane: V,
+}
+
+/// A trait for creating tubes along paths.
+pub trait MakeTube<T: MeshType3D<Mesh = Self>>: MeshTrait<T = T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Sweeps a circle with the given `radius` and number of `segments` along the polyline
+    /// given by `points` using mitered joints.
+    /// If `cap_ends` is true, both ends are closed and the resulting mesh is watertight.
+    fn tube_from_path(points: &[T::Vec], radius: T::S, segments: usize, cap_ends: bool) -> Self {
+        Self::tube_from_path_ex(points, radius, segments, TubeJoint::Miter, cap_ends)
+    }
+
+    /// Like `tube_from_path`, but with the largest number of segments such that the tube
+    /// has at most `max_triangles` triangles. Uses 3 segments if the budget is smaller.
+    fn tube_with_budget(
+        points: &[T::Vec],
+        radius: T::S,
+        max_triangles: usize,
+        cap_ends: bool,
+    ) -> Self {
+        let mut rings: usize = 0;
+        for (i, p) in points.iter().enumerate() {
+            if i == 0 || points[i - 1].distance_squared(p) > T::S::EPS {
+                rings += 1;
+            }
+        }
+        let segments = fit_budget(max_triangles, 3, |n| {
+            2 * n * rings.max(2).saturating_sub(1) + if cap_ends { 2 * (n - 2) } else { 0 }
+        });
+        Self::tube_from_path(points, radius, segments, cap_ends)
+    }
+
+    /// Like `tube_from_path`, but with a configurable `joint` style.
+    fn tube_from_path_ex(
+        points: &[T::Vec],
+        radius: T::S,
+        segments: usize,
+        joint: TubeJoint,
+        cap_ends: bool,
+    ) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_tube_from_path(points, radius, segments, joint, cap_ends);
+        mesh
+    }
+
+    /// Inserts a tube like `tube_from_path_ex` as a new connected component of the mesh.
+    /// Returns the ids of the inserted faces.
+    fn insert_tube_from_path(
+        &mut self,
+        points: &[T::Vec],
+        radius: T::S,
+        segments: usize,
+        joint: TubeJoint,
+        cap_ends: bool,
+    ) -> Vec<T::F> {
+        assert!(segments >= 3, "a tube needs at least 3 segments");
+        self.insert_sweep(
+            points,
+            &circle_profile::<T::Vec2>(radius, segments),
+            joint,
+            false,
+            cap_ends,
+        )
+    }
+
+    /// Sweeps the ccw 2d `profile` along the polyline given by `points` and inserts the
+    /// result as a new connected component of the mesh. Returns the ids of the inserted faces.
+    ///
+    /// The profile is carried along the path with a rotation-minimizing frame, i.e., it
+    /// doesn't roll around the path more than necessary. If the path is `closed`, the last
+    /// point is connected to the first one and the remaining roll is distributed evenly
+    /// along the path, so the ends of the sweep meet without a seam. Otherwise, `cap_ends`
+    /// closes both ends with a face each.
+    fn insert_sweep(
+        &mut self,
+        points: &[T::Vec],
+        profile: &[T::Vec2],
+        joint: TubeJoint,
+        closed: bool,
+        cap_ends: bool,
+    ) -> Vec<T::F> {
+        self.insert_sweep_with_holes(points, &[profile.to_vec()], joint, closed, cap_ends)
+    }
+
+    /// Like `insert_sweep`, but the profile consists of multiple rings with the interior
+    /// on the left, i.e., counter-clockwise outlines and clockwise holes, e.g., an annulus
+    /// for pipes or a window frame. Each ring gets its own walls, so holes become inner
+    /// walls facing inwards. If the profile has holes, the caps are triangulated since
+    /// faces can't have holes. The rings must not touch each other.
+    fn insert_sweep_with_holes(
+        &mut self,
+        points: &[T::Vec],
+        profile: &[Vec<T::Vec2>],
+        joint: TubeJoint,
+        closed: bool,
+        cap_ends: bool,
+    ) -> Vec<T::F> {
+        assert!(
+            !profile.is_empty() && profile.iter().all(|ring| ring.len() >= 3),
+            "a profile needs at least 3 points per ring"
+        );
+        let mut path: Vec<T::Vec> = Vec::with_capacity(points.len());
+        for p in points {
+            if path
+                .last()
+                .map_or(true, |q: &T::Vec| q.distance_squared(p) > T::S::EPS)
+            {
+                path.push(*p);
+            }
+        }
+        if closed {
+            while path.len() > 1 && path[0].distance_squared(path.last().unwrap()) <= T::S::EPS {
+                path.pop();
+            }
+            assert!(
+                path.len() >= 3,
+                "a closed path must have at least 3 distinct points"
+            );
+            // the closing segment
+            path.push(path[0]);
+        }
+        assert!(
+            path.len() >= 2,
+            "the path must have at least 2 distinct points"
+        );
+
+        let dirs = path
+            .windows(2)
+            .map(|w| (w[1] - w[0]).normalize())
+            .collect::<Vec<_>>();
+        let lengths = path
+            .windows(2)
+            .map(|w| w[0].distance(&w[1]))
+            .collect::<Vec<_>>();
+        // the size of the profile limits the radius of the bends
+        let radius = profile
+            .iter()
+            .flatten()
+            .map(|p| p.length())
+            .fold(T::S::ZERO, |a, b| a.max(b));
+
+        let mut stations = Vec::new();
+        let corners = if closed {
+            0..dirs.len()
+        } else {
+            stations.push(Station {
+                center: path[0],
+                dir: dirs[0],
+                plane: dirs[0],
+            });
+            1..path.len() - 1
+        };
+        for i in corners {
+            let i0 = (i + dirs.len() - 1) % dirs.len();
+            let (d0, d1) = (dirs[i0], dirs[i]);
+            let cos = d0.dot(&d1).clamp(-T::S::ONE, T::S::ONE);
+            assert!(
+                cos > T::S::EPS - T::S::ONE,
+                "the path must not turn back on itself"
+            );
+            let angle = cos.acos();
+            let bend = match joint {
+                TubeJoint::Round(k) if angle > T::S::EPS.sqrt() => Some(k.max(1)),
+                _ => None,
+            };
+            let Some(k) = bend else {
+                stations.push(Station {
+                    center: path[i],
+                    dir: d0,
+                    plane: (d0 + d1).normalize(),
+                });
+                continue;
+            };
+
+            // replace the corner with a circular arc tangent to both segments
+            let half_tan = (angle * T::S::HALF).tan();
+            let available = lengths[i0].min(lengths[i]) * T::S::HALF;
+            let r = (radius * T::S::TWO).min(available / half_tan);
+            let start = path[i] - d0 * (r * half_tan);
+            let inward = (d1 - d0 * cos).normalize();
+            let center = start + inward * r;
+            for j in 0..=k {
+                let phi = angle * T::S::from_usize(j) / T::S::from_usize(k);
+                let dir = d0 * phi.cos() + inward * phi.sin();
+                stations.push(Station {
+                    center: center + (d0 * phi.sin() - inward * phi.cos()) * r,
+                    dir,
+                    plane: dir,
+                });
+            }
+        }
+        if !closed {
+            let last = *dirs.last().unwrap();
+            stations.push(Station {
+                center: *path.last().unwrap(),
+                dir: last,
+                plane: last,
+            });
+        }
+
+        // initial frame perpendicular to the first direction
+        let d = stations[0].dir;
+        let axis = if d.x().abs() < T::S::HALF {
+            T::Vec::from_xyz(T::S::ONE, T::S::ZERO, T::S::ZERO)
+        } else {
+            T::Vec::from_xyz(T::S::ZERO, T::S::ONE, T::S::ZERO)
+        };
+        let mut u = d.cross(&axis).normalize();
+        let mut prev = d;
+
+        // parallel transport of the frame, i.e., rotate it like the direction
+        let transport = |u: T::Vec, from: T::Vec, to: T::Vec| {
+            let k = from.cross(&to);
+            let sin = k.length();
+            let mut u = u;
+            if sin > T::S::EPS {
+                let k = k / sin;
+                let cos = from.dot(&to);
+                u = u * cos + k.cross(&u) * sin + k * (k.dot(&u) * (T::S::ONE - cos));
+            }
+            (u - to * u.dot(&to)).normalize()
+        };
+        let mut frames = Vec::with_capacity(stations.len());
+        for s in &stations {
+            u = transport(u, prev, s.dir);
+            prev = s.dir;
+            frames.push(u);
+        }
+
+        // distribute the roll between the last and the first frame of closed paths
+        let mut roll = vec![T::S::ZERO; stations.len()];
+        if closed {
+            let d = stations[0].dir;
+            let end = transport(u, prev, d);
+            let angle = frames[0].cross(&end).dot(&d).atan2(frames[0].dot(&end));
+            let mut arc = vec![T::S::ZERO; stations.len()];
+            for i in 1..stations.len() {
+                arc[i] = arc[i - 1] + stations[i - 1].center.distance(&stations[i].center);
+            }
+            let last = stations.len() - 1;
+            let total = arc[last] + stations[last].center.distance(&stations[0].center);
+            for (r, a) in roll.iter_mut().zip(arc) {
+                *r = -angle * a / total.max(T::S::EPS);
+            }
+        }
+
+        let mut builder = SoupBuilder::<3, T>::new();
+        for ((s, u), roll) in stations.iter().zip(frames).zip(roll) {
+            let v = s.dir.cross(&u);
+            let (u, v) = (
+                u * roll.cos() + v * roll.sin(),
+                v * roll.cos() - u * roll.sin(),
+            );
+            let denom = s.dir.dot(&s.plane);
+            for p in profile.iter().flatten() {
+                let q = u * p.x() + v * p.y();
+                let q = q - s.dir * (q.dot(&s.plane) / denom);
+                builder.add_vertex(T::VP::from_pos(s.center + q));
+            }
+        }
+        add_walls_and_caps(&mut builder, profile, stations.len(), closed, cap_ends);
+        builder.insert_into(self)
+    }
+
+    /// Extrudes the 2d `profile` in the xy-plane along the z-axis by `height` and inserts
+    /// the resulting prism as a new connected component of the mesh. Returns the ids of the
+    /// inserted faces. The profile consists of counter-clockwise outlines and clockwise
+    /// holes like for `insert_sweep_with_holes`, e.g., an I-beam or a window frame.
+    fn insert_extruded_profile(&mut self, profile: &[Vec<T::Vec2>], height: T::S) -> Vec<T::F> {
+        assert!(
+            !profile.is_empty() && profile.iter().all(|ring| ring.len() >= 3),
+            "a profile needs at least 3 points per ring"
+        );
+        let mut builder = SoupBuilder::<3, T>::new();
+        for z in [T::S::ZERO, height] {
+            for p in profile.iter().flatten() {
+                builder.add_vertex(T::VP::from_pos(T::Vec::from_xyz(p.x(), p.y(), z)));
+            }
+        }
+        add_walls_and_caps(&mut builder, profile, 2, false, true);
+        builder.insert_into(self)
+    }
+
+    /// Creates a closed prism by extruding the 2d `profile` along the z-axis by `height`.
+    /// See `insert_extruded_profile`.
+    fn extruded_profile(profile: &[Vec<T::Vec2>], height: T::S) -> Self {
+        let mut mesh = Self::default();
+        mesh.insert_extruded_profile(profile, height);
+        mesh
+    }
+}
+
+/// Connects the consecutive copies of the `profile` in the builder with walls and closes
+/// the first and the last one with caps. The builder contains one vertex per point of the
+/// profile and station.
+fn add_walls_and_caps<T: MeshType3D>(
+    builder: &mut SoupBuilder<3, T>,
+    profile: &[Vec<T::Vec2>],
+    stations: usize,
+    closed: bool,
+    cap_ends: bool,
+) where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    let size = profile.iter().map(|ring| ring.len()).sum::<usize>();
+    let walls = if closed { stations } else { stations - 1 };
+    let mut offset = 0;
+    for ring in profile {
+        let n = ring.len();
+        for l in 0..walls {
+            let (a, b) = (l * size + offset, (l + 1) % stations * size + offset);
+            for j in 0..n {
+                let k = (j + 1) % n;
+                builder.add_tagged_face([a + j, a + k, b + k, b + j], "side-wall");
+            }
+        }
+        offset += n;
+    }
+    if !cap_ends || closed {
+        return;
+    }
+    let top = (stations - 1) * size;
+    if profile.len() == 1 {
+        builder.add_tagged_face((0..size).rev(), "cap");
+        builder.add_tagged_face(top..top + size, "cap");
+        return;
+    }
+    for [a, b, c] in triangulate_rings(profile) {
+        builder.add_tagged_face([c, b, a], "cap");
+        builder.add_tagged_face([top + a, top + b, top + c], "cap");
+    }
+}
+
+/// Returns the 2d profile of a ring with the given `outer` and `inner` radius and number
+/// of `segments`, i.e., a ccw outline and a cw hole.
+pub fn annulus_profile<Vec2: Vector2D>(
+    outer: Vec2::S,
+    inner: Vec2::S,
+    segments: usize,
+) -> Vec<Vec<Vec2>> {
+    assert!(
+        inner > Vec2::S::ZERO && inner < outer,
+        "the inner radius must be between zero and the outer radius"
+    );
+    let mut hole = circle_profile(inner, segments);
+    hole.reverse();
+    vec![circle_profile(outer, segments), hole]
+}
+
+/// Returns the ccw 2d outline of a circle with the given `radius` and number of `segments`.
+pub fn circle_profile<Vec2: Vector2D>(radius: Vec2::S, segments: usize) -> Vec<Vec2> {
+    (0..segments)
+        .map(|j| {
+            let theta =
+                Vec2::S::TWO * Vec2::S::PI * Vec2::S::from_usize(j) / Vec2::S::from_usize(segments);
+            Vec2::new(theta.cos() * radius, theta.sin() * radius)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    fn path() -> Vec<Vec3<f64>> {
+        vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(2.0, 2.0, 0.0),
+            Vec3::new(2.0, 2.0, 2.0),
+            Vec3::new(4.0, 3.0, 2.0),
+        ]
+    }
+
+    #[test]
+    fn test_tube_miter() {
+        let tube = Mesh3d64::tube_from_path(&path()[..2], 0.5, 64, true);
+        assert!(tube.check().is_ok());
+        assert!(!tube.is_open());
+        let expected = std::f64::consts::PI * 0.25 * 2.0;
+        assert!((tube.report().volume.unwrap() - expected).abs() < 0.01 * expected);
+
+        let tube = Mesh3d64::tube_from_path(&path(), 0.25, 8, true);
+        assert!(tube.check().is_ok());
+        assert!(!tube.is_open());
+        assert_eq!(tube.num_vertices(), 5 * 8);
+        assert_eq!(tube.num_faces(), 4 * 8 + 2);
+        assert!(tube.report().volume.unwrap() > 0.0);
+
+        let open = Mesh3d64::tube_from_path(&path(), 0.25, 8, false);
+        assert!(open.is_open());
+        assert_eq!(open.num_faces(), 4 * 8);
+    }
+
+    #[test]
+    fn test_sweep_with_holes() {
+        // a pipe
+        let profile = annulus_profile::<Vec2<f64>>(0.5, 0.4, 32);
+        let mut pipe = Mesh3d64::default();
+        pipe.insert_sweep_with_holes(&path()[..2], &profile, TubeJoint::Miter, false, true);
+        assert!(pipe.check().is_ok());
+        assert!(!pipe.is_open());
+        assert_eq!(pipe.num_vertices(), 2 * 64);
+        assert_eq!(pipe.num_faces(), 2 * 32 + 2 * 64);
+        let area = |ring: &[Vec2<f64>]| {
+            (0..ring.len())
+                .map(|i| ring[i].perp(&ring[(i + 1) % ring.len()]) / 2.0)
+                .sum::<f64>()
+        };
+        let expected = (area(&profile[0]) + area(&profile[1])) * 2.0;
+        assert!((pipe.report().volume.unwrap() - expected).abs() < 1e-9);
+
+        // bent and closed paths have inner and outer walls
+        let mut bent = Mesh3d64::default();
+        bent.insert_sweep_with_holes(&path(), &profile, TubeJoint::Round(4), false, true);
+        assert!(bent.check().is_ok());
+        assert!(!bent.is_open());
+        let mut ring = Mesh3d64::default();
+        let square = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(2.0, 2.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+        ];
+        ring.insert_sweep_with_holes(&square, &profile, TubeJoint::Miter, true, true);
+        assert!(ring.check().is_ok());
+        assert!(!ring.is_open());
+        // the inner and the outer wall of a closed pipe aren't connected
+        assert_eq!(ring.report().islands, 2);
+        assert!(ring.report().volume.unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_extruded_profile() {
+        // a window frame with two panes
+        let rect = |x0: f64, y0: f64, x1: f64, y1: f64| {
+            vec![
+                Vec2::new(x0, y0),
+                Vec2::new(x1, y0),
+                Vec2::new(x1, y1),
+                Vec2::new(x0, y1),
+            ]
+        };
+        let mut pane1 = rect(0.1, 0.1, 0.45, 0.9);
+        let mut pane2 = rect(0.55, 0.1, 0.9, 0.9);
+        pane1.reverse();
+        pane2.reverse();
+        let frame =
+            Mesh3d64Tagged::extruded_profile(&[rect(0.0, 0.0, 1.0, 1.0), pane1, pane2], 0.1);
+        assert!(frame.check().is_ok());
+        assert!(!frame.is_open());
+        assert_eq!(frame.num_vertices(), 24);
+        assert_eq!(frame.select_by_tag("side-wall").len(), 12);
+        let volume = (1.0 - 2.0 * 0.35 * 0.8) * 0.1;
+        assert!((frame.report().volume.unwrap() - volume).abs() < 1e-9);
+
+        // an I-beam doesn't need a triangulated cap
+        let beam = Mesh3d64::extruded_profile(
+            &[vec![
+                Vec2::new(-0.5, -1.0),
+                Vec2::new(0.5, -1.0),
+                Vec2::new(0.5, -0.8),
+                Vec2::new(0.1, -0.8),
+                Vec2::new(0.1, 0.8),
+                Vec2::new(0.5, 0.8),
+                Vec2::new(0.5, 1.0),
+                Vec2::new(-0.5, 1.0),
+                Vec2::new(-0.5, 0.8),
+                Vec2::new(-0.1, 0.8),
+                Vec2::new(-0.1, -0.8),
+                Vec2::new(-0.5, -0.8),
+            ]],
+            3.0,
+        );
+        assert!(beam.check().is_ok());
+        assert!(!beam.is_open());
+        assert_eq!(beam.num_faces(), 12 + 2);
+        let volume = (2.0 * 0.2 + 0.2 * 1.6) * 3.0;
+        assert!((beam.report().volume.unwrap() - volume).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tube_round() {
+        let tube = Mesh3d64::tube_from_path_ex(&path(), 0.25, 8, TubeJoint::Round(4), true);
+        assert!(tube.check().is_ok());
+        assert!(!tube.is_open());
+        assert_eq!(tube.num_vertices(), (2 + 3 * 5) * 8);
+        assert_eq!(tube.num_faces(), 16 * 8 + 2);
+        assert!(tube.report().volume.unwrap() > 0.0);
+
+        // the faces of the bends are planar
+        let (indices, _) = tube.triangulate(
+            TriangulationAlgorithm::Delaunay,
+            &mut TesselationMeta::default(),
+        );
+        assert_eq!(indices.len(), 3 * (16 * 8 * 2 + 2 * 6));
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    math::{HasPosition, Scalar, Vector},
+    mesh::{DefaultEdgePayload, DefaultFacePayload, MeshTrait, MeshType3D, SoupBuilder},
+};
+
+/// A dense chunk of voxels. Each voxel is either empty or holds a material of type `V`.
+///
+/// Voxel `(x, y, z)` occupies the unit cube from `(x, y, z)` to `(x + 1, y + 1, z + 1)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoxelGrid<V: Copy + PartialEq> {
+    dims: [usize; 3],
+    voxels: Vec<Option<V>>,
+}
+
+impl<V: Copy + PartialEq> VoxelGrid<V> {
+    /// Creates an empty grid with the given number of voxels along each axis.
+    pub fn new(dims: [usize; 3]) -> Self {
+        Self {
+            dims,
+            voxels: vec![None; dims[0] * dims[1] * dims[2]],
+        }
+    }
+
+    /// Creates a grid by evaluating `f` for each voxel.
+    pub fn from_fn(dims: [usize; 3], f: impl Fn(usize, usize, usize) -> Option<V>) -> Self {
+        let mut grid = Self::new(dims);
+        for z in 0..dims[2] {
+            for y in 0..dims[1] {
+                for x in 0..dims[0] {
+                    grid.set([x, y, z], f(x, y, z));
+                }
+            }
+        }
+        grid
+    }
+
+    /// Returns the number of voxels along each axis.
+    pub fn dims(&self) -> [usize; 3] {
+        self.dims
+    }
+
+    fn index(&self, [x, y, z]: [usize; 3]) -> usize {
+        assert!(x < self.dims[0] && y < self.dims[1] && z < self.dims[2]);
+        x + self.dims[0] * (y + self.dims[1] * z)
+    }
+
+    /// Returns the material of the voxel or `None` if it is empty.
+    pub fn get(&self, p: [usize; 3]) -> Option<V> {
+        self.voxels[self.index(p)]
+    }
+
+    /// Sets the material of the voxel. Use `None` to clear it.
+    pub fn set(&mut self, p: [usize; 3], v: Option<V>) {
+        let i = self.index(p);
+        self.voxels[i] = v;
+    }
+
+    /// Returns the number of non-empty voxels.
+    pub fn count(&self) -> usize {
+        self.voxels.iter().filter(|v| v.is_some()).count()
+    }
+
+    /// Like `get`, but voxels outside of the grid are empty.
+    fn get_signed(&self, p: [isize; 3]) -> Option<V> {
+        if (0..3).any(|k| p[k] < 0 || p[k] as usize >= self.dims[k]) {
+            return None;
+        }
+        self.get(p.map(|c| c as usize))
+    }
+
+    /// Returns the visible faces of the voxels in the slice between layers `i - 1` and `i`
+    /// along `axis`. Faces pointing in the positive direction if `positive`.
+    /// The mask is indexed by `a + b * dims[(axis + 1) % 3]` and stores the material.
+    fn face_mask(&self, axis: usize, i: usize, positive: bool) -> Vec<Option<V>> {
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        let mut mask = Vec::with_capacity(self.dims[u] * self.dims[v]);
+        for b in 0..self.dims[v] {
+            for a in 0..self.dims[u] {
+                let mut p = [0isize; 3];
+                p[u] = a as isize;
+                p[v] = b as isize;
+                p[axis] = i as isize - 1;
+                let back = self.get_signed(p);
+                p[axis] = i as isize;
+                let front = self.get_signed(p);
+                mask.push(match (back, front, positive) {
+                    (Some(m), None, true) | (None, Some(m), false) => Some(m),
+                    _ => None,
+                });
+            }
+        }
+        mask
+    }
+}
+
+/// A trait for meshing voxel grids.
+pub trait MakeVoxels<T: MeshType3D<Mesh = Self>>: MeshTrait<T = T>
+where
+    T::EP: DefaultEdgePayload,
+    T::FP: DefaultFacePayload,
+{
+    /// Creates one quad for each visible side of each voxel, where voxels have the edge
+    /// length `size`. Sides between two non-empty voxels are skipped.
+    fn voxels<V: Copy + PartialEq>(grid: &VoxelGrid<V>, size: T::S) -> Self {
+        Self::voxels_ex(grid, size, false)
+    }
+
+    /// Like `voxels`, but merges adjacent coplanar sides of voxels with the same material
+    /// into as few rectangles as possible using greedy meshing.
+    ///
+    /// Every face has its own vertices, since the corners of the rectangles usually don't
+    /// match the corners of their neighbors (T-junctions). Hence, the mesh consists of
+    /// many open islands, but it encloses the voxels without gaps.
+    fn greedy_voxels<V: Copy + PartialEq>(grid: &VoxelGrid<V>, size: T::S) -> Self {
+        Self::voxels_ex(grid, size, true)
+    }
+
+    /// Meshes the voxel grid with or without merging the sides of voxels.
+    fn voxels_ex<V: Copy + PartialEq>(grid: &VoxelGrid<V>, size: T::S, greedy: bool) -> Self {
+        let dims = grid.dims();
+        let mut builder = SoupBuilder::<3, T>::new();
+        for axis in 0..3 {
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+            let (nu, nv) = (dims[u], dims[v]);
+            for positive in [false, true] {
+                for i in 0..=dims[axis] {
+                    let mut mask = grid.face_mask(axis, i, positive);
+                    for b in 0..nv {
+                        let mut a = 0;
+                        while a < nu {
+                            let Some(m) = mask[a + b * nu] else {
+                                a += 1;
+                                continue;
+                            };
+
+                            // grow the rectangle along u, then along v
+                            let mut w = 1;
+                            let mut h = 1;
+                            if greedy {
+                                while a + w < nu && mask[a + w + b * nu] == Some(m) {
+                                    w += 1;
+                                }
+                                while b + h < nv
+                                    && (a..a + w).all(|k| mask[k + (b + h) * nu] == Some(m))
+                                {
+                                    h += 1;
+                                }
+                            }
+                            for bb in b..b + h {
+                                for aa in a..a + w {
+                                    mask[aa + bb * nu] = None;
+                                }
+                            }
+
+                            // counter-clockwise when seen from the positive axis
+                            let mut corners = [(a, b), (a + w, b), (a + w, b + h), (a, b + h)];
+                            if !positive {
+                                corners.reverse();
+                            }
+                            builder.add_polygon(corners.map(|(ca, cb)| {
+                                let mut c = [T::S::ZERO; 3];
+                                c[axis] = T::S::from_usize(i) * size;
+                                c[u] = T::S::from_usize(ca) * size;
+                                c[v] = T::S::from_usize(cb) * size;
+                                T::VP::from_pos(T::Vec::from_xyz(c[0], c[1], c[2]))
+                            }));
+                            a += w;
+                        }
+                    }
+                }
+            }
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, mesh::signed_volume, prelude::*};
+
+    #[test]
+    fn test_greedy_voxels() {
+        let block = VoxelGrid::from_fn([4, 3, 2], |_, _, _| Some(()));
+        let greedy = Mesh3d64::greedy_voxels(&block, 0.5);
+        assert!(greedy.check().is_ok());
+        assert_eq!(greedy.num_faces(), 6);
+        assert!(signed_volume::<MeshType3d64PNU>(&greedy).is_about(24.0 / 8.0, 1e-12));
+        let naive = Mesh3d64::voxels(&block, 0.5);
+        assert_eq!(naive.num_faces(), 2 * (4 * 3 + 4 * 2 + 3 * 2));
+        assert!(signed_volume::<MeshType3d64PNU>(&naive).is_about(24.0 / 8.0, 1e-12));
+
+        // a staircase with a hole
+        let stairs = VoxelGrid::from_fn([5, 5, 3], |x, y, z| {
+            (y <= x && !(x == 2 && z == 1)).then_some(())
+        });
+        let greedy = Mesh3d64::greedy_voxels(&stairs, 1.0);
+        let naive = Mesh3d64::voxels(&stairs, 1.0);
+        assert!(greedy.check().is_ok());
+        assert!(greedy.num_faces() < naive.num_faces() / 2);
+        assert!(signed_volume::<MeshType3d64PNU>(&greedy).is_about(stairs.count() as f64, 1e-12));
+        assert!(signed_volume::<MeshType3d64PNU>(&naive).is_about(stairs.count() as f64, 1e-12));
+    }
+
+    #[test]
+    fn test_voxel_materials() {
+        let mut grid = VoxelGrid::new([2, 1, 1]);
+        grid.set([0, 0, 0], Some(1));
+        grid.set([1, 0, 0], Some(1));
+        assert_eq!(Mesh3d64::greedy_voxels(&grid, 1.0).num_faces(), 6);
+        // different materials are not merged, but the side between them is still hidden
+        grid.set([1, 0, 0], Some(2));
+        assert_eq!(Mesh3d64::greedy_voxels(&grid, 1.0).num_faces(), 10);
+        grid.set([1, 0, 0], None);
+        assert_eq!(grid.count(), 1);
+        assert_eq!(Mesh3d64::greedy_voxels(&grid, 1.0).num_faces(), 6);
+    }
+}
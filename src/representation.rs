@@ -0,0 +1,44 @@
+//! Deprecated paths of the old `representation` module.
+//!
+//! The mesh data structure moved to `mesh` (the traits) and `halfedge` (the half-edge
+//! implementation). This module only re-exports the new items under their old paths, so
+//! imports keep resolving while downstream code migrates. It doesn't adapt signatures:
+//! code calling methods whose signature changed since the move has to be updated to the
+//! new API.
+//!
+//! Only the type alias `Mesh` and imports of the module itself, e.g.,
+//! `use procedural_modelling::representation as r;`, emit deprecation warnings. Rust
+//! ignores deprecations of re-exports, and the deprecation of a module isn't reported for
+//! items used through it, so paths like `representation::MeshBuilder` or
+//! `representation::payload::VertexPayload` compile silently.
+#![allow(deprecated)]
+
+use crate::halfedge::HalfEdgeMeshImpl;
+
+pub use crate::mesh::*;
+
+/// The old path of the triangulation algorithms.
+#[deprecated(
+    since = "0.3.0",
+    note = "use `procedural_modelling::tesselate` instead"
+)]
+pub mod tesselate {
+    pub use crate::tesselate::*;
+}
+
+/// The old path of the vertex, edge, face, and mesh payloads.
+#[deprecated(since = "0.3.0", note = "use `procedural_modelling::mesh` instead")]
+pub mod payload {
+    pub use crate::mesh::{
+        DefaultEdgePayload, DefaultFacePayload, DefaultVertexPayload, EdgePayload,
+        EmptyEdgePayload, EmptyFacePayload, EmptyMeshPayload, EmptyVertexPayload, FacePayload,
+        MeshPayload, VertexPayload,
+    };
+}
+
+/// The old name of the half-edge mesh.
+#[deprecated(
+    since = "0.3.0",
+    note = "use `procedural_modelling::halfedge::HalfEdgeMeshImpl` instead"
+)]
+pub type Mesh<T> = HalfEdgeMeshImpl<T>;
@@ -0,0 +1,74 @@
+//! The stable subset of the API.
+//!
+//! The paths re-exported here follow semantic versioning: they are only removed or renamed
+//! in a new major version (or minor version while the crate is below 1.0), and only after
+//! being deprecated for at least one release. Between those versions, the re-exported
+//! traits only gain provided methods and the re-exported types only gain constructors and
+//! methods, so existing calls keep compiling. Items that are only available through
+//! `prelude` or the individual modules might still change between patch releases.
+//!
+//! The deprecated `representation` module is not part of this promise. It only keeps the
+//! old paths resolving; code using old signatures has to be migrated.
+//!
+//! Unlike `prelude`, this module lists its items explicitly, so new features don't become
+//! stable by accident. Import it with `use procedural_modelling::stable::*;` together with
+//! the backend you need, e.g., `extensions::nalgebra` or `extensions::bevy`.
+
+// The mesh data structure and its types
+pub use crate::halfedge::{
+    HalfEdgeFaceImpl, HalfEdgeImpl, HalfEdgeImplMeshType, HalfEdgeMeshImpl, HalfEdgeVertexImpl,
+};
+pub use crate::mesh::{
+    DefaultEdgePayload, DefaultFacePayload, EdgeBasics, EdgePayload, EmptyEdgePayload,
+    EmptyFacePayload, EmptyMeshPayload, EuclideanMeshType, Face3d, FaceBasics, FacePayload,
+    HalfEdge, MeshBasics, MeshBuilder, MeshPayload, MeshTrait, MeshType, MeshType3D,
+    MeshTypeHalfEdge, TransformableMesh, Triangulateable, Triangulation, VertexBasics,
+    VertexPayload, WithNormals,
+};
+
+// Math
+pub use crate::math::{
+    HasNormal, HasPosition, HasUV, IndexType, Polygon, Scalar, TransformTrait, Transformable,
+    Vector, Vector2D, Vector3D, Vector3DIteratorExt,
+};
+
+// Triangulation
+pub use crate::tesselate::{TesselationError, TesselationMeta, TriangulationAlgorithm};
+
+// Primitives
+pub use crate::primitives::{Make2dShape, MakePlane, MakePrismatoid, MakeSphere};
+
+// Operations
+pub use crate::operations::{MeshExtrude, MeshLoft, MeshSubdivision};
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use super::*;
+    use crate::extensions::nalgebra::*;
+
+    #[test]
+    fn test_stable_subset() {
+        // a typical workflow only using the stable api
+        let mut mesh = Mesh3d64::cube(1.0);
+        mesh.translate(&Vec3::new(0.0, 1.0, 0.0));
+        let (is, vs) = mesh
+            .try_triangulate(
+                TriangulationAlgorithm::Auto,
+                &mut TesselationMeta::default(),
+            )
+            .unwrap();
+        assert_eq!(is.len(), 36);
+        assert!(vs.iter().all(|v| v.pos().y >= 0.5));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_paths() {
+        // the old paths still resolve to the current types
+        let mesh: crate::representation::Mesh<MeshType3d64PNU> = Mesh3d64::cube(1.0);
+        let meta = crate::representation::tesselate::TesselationMeta::<usize>::default();
+        assert_eq!(mesh.num_faces(), 6);
+        assert!(meta.sweep.vertex_type.is_empty());
+    }
+}
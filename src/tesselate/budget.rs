@@ -0,0 +1,127 @@
+use super::{triangulate_face, TesselationMeta, TriangulationAlgorithm};
+use crate::{
+    math::{Polygon, Scalar, Vector2D},
+    mesh::{Face3d, FaceBasics, MeshBasics, MeshType3D, Triangulation},
+};
+use itertools::Itertools;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Triangulates all faces of the mesh with a fast algorithm first and then upgrades the
+/// largest faces to delaunay triangulations until the `budget` is used up. Since large
+/// faces profit the most from well-shaped triangles, they are upgraded first.
+///
+/// Convex faces start with a fan triangulation and all other faces with ear clipping.
+/// The faces are appended to `tri` in the order of the mesh. The budget is only checked
+/// between faces, so a single huge face may exceed it. Uses `web_time::Instant`, so the
+/// budget also works on wasm32.
+pub fn triangulate_budgeted<T: MeshType3D>(
+    mesh: &T::Mesh,
+    tri: &mut Triangulation<T::V>,
+    budget: Duration,
+    meta: &mut TesselationMeta<T::V>,
+) {
+    let start = Instant::now();
+
+    let mut faces = mesh
+        .faces()
+        .map(|f| {
+            let mut indices = Vec::new();
+            let fast = if is_convex(f.as_polygon(mesh).points()) {
+                TriangulationAlgorithm::Fan
+            } else {
+                TriangulationAlgorithm::EarClipping
+            };
+            triangulate_face::<T>(f, mesh, &mut Triangulation::new(&mut indices), fast, meta);
+            (f.id(), indices)
+        })
+        .collect::<Vec<_>>();
+
+    // upgrade the largest faces first
+    let mut order = faces
+        .iter()
+        .enumerate()
+        .filter(|(_, (f, _))| mesh.face(*f).num_vertices(mesh) > 3)
+        .map(|(i, (f, _))| (i, mesh.face(*f).as_polygon(mesh).area()))
+        .collect::<Vec<_>>();
+    order.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    for (i, _) in order {
+        if start.elapsed() >= budget {
+            break;
+        }
+        let f = mesh.face(faces[i].0);
+        let mut indices = Vec::new();
+        triangulate_face::<T>(
+            f,
+            mesh,
+            &mut Triangulation::new(&mut indices),
+            TriangulationAlgorithm::Delaunay,
+            meta,
+        );
+        faces[i].1 = indices;
+    }
+
+    for (f, indices) in faces {
+        tri.begin_face(f);
+        for t in indices.chunks(3) {
+            tri.insert_triangle(t[0], t[1], t[2]);
+        }
+    }
+}
+
+/// Whether all corners of the polygon turn the same way, so a fan triangulation is valid.
+/// Collinear corners are allowed.
+fn is_convex<Vec2: Vector2D>(points: &[Vec2]) -> bool {
+    let turns = points
+        .iter()
+        .circular_tuple_windows::<(_, _, _)>()
+        .map(|(a, b, c)| (*b - *a).perp_dot(&(*c - *b)))
+        .collect::<Vec<_>>();
+    turns.iter().all(|t| *t >= Vec2::S::ZERO) || turns.iter().all(|t| *t <= Vec2::S::ZERO)
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::time::Duration;
+
+    #[test]
+    fn test_triangulate_budgeted() {
+        // a large star next to many small faces
+        let mut mesh = Mesh3d64::regular_prism(1.0, 1.0, 32);
+        mesh.insert_regular_star(1.0, 3.0, 64);
+        let triangulate = |algorithm, time_budget| {
+            let options = TesselationOptions {
+                time_budget,
+                ..Default::default()
+            };
+            let mut indices = Vec::new();
+            let mut tri = Triangulation::new(&mut indices);
+            mesh.triangulate_into_with_options(
+                &mut tri,
+                algorithm,
+                &options,
+                &mut Default::default(),
+            );
+            let ranges = tri.face_ranges::<usize>().collect::<Vec<_>>();
+            ranges
+                .into_iter()
+                .map(|(f, r)| (f, indices[r].to_vec()))
+                .collect::<Vec<_>>()
+        };
+
+        // without time, nothing is upgraded
+        let fast = triangulate(TriangulationAlgorithm::Auto, Some(Duration::ZERO));
+        let ears = triangulate(TriangulationAlgorithm::EarClipping, None);
+        let delaunay = triangulate(TriangulationAlgorithm::Auto, None);
+        let star = (0..fast.len()).max_by_key(|i| fast[*i].1.len()).unwrap();
+        assert_eq!(fast[star], ears[star]);
+        assert_ne!(fast[star], delaunay[star]);
+
+        // with enough time, every face is upgraded
+        let slow = triangulate(TriangulationAlgorithm::Auto, Some(Duration::from_secs(60)));
+        assert_eq!(slow, delaunay);
+        assert_eq!(slow.len(), mesh.num_faces());
+    }
+}
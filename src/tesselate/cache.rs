@@ -0,0 +1,221 @@
+use super::{triangulate_face, TesselationMeta, TriangulationAlgorithm};
+use crate::{
+    math::{HasPosition, Scalar, Vector},
+    mesh::{FaceBasics, MeshBasics, MeshType3D, Triangulation, VertexBasics},
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+/// Caches the triangles of each face of a mesh, so repeated triangulations, e.g., when
+/// calling `bevy_set` every frame in an interactive editor, only re-triangulate the faces
+/// that actually changed.
+///
+/// A face is re-triangulated when it was marked with `mark_dirty` or when it changed since
+/// it was cached. Meshes that track their changes, see `MeshBasics::generation`, report
+/// every mutable access to the face, its edges, or its vertices, e.g., by builder
+/// operations, transformations, or edits of the payloads or the `curved` flag, so the
+/// cache doesn't have to look at the positions. For other meshes, the cache falls back to
+/// comparing a hash of the ids and positions of the vertices of each face.
+/// Cached faces that were removed from the mesh are dropped.
+///
+/// Use one cache per mesh; `clear` the cache before using it with another mesh.
+#[derive(Debug, Clone, Default)]
+pub struct TesselationCache<T: MeshType3D> {
+    algorithm: TriangulationAlgorithm,
+    faces: HashMap<T::F, (u64, Vec<T::V>)>,
+    dirty: HashSet<T::F>,
+    generation: Option<u64>,
+    retriangulated: usize,
+}
+
+impl<T: MeshType3D> TesselationCache<T> {
+    /// Creates an empty cache triangulating the faces with the given algorithm.
+    pub fn new(algorithm: TriangulationAlgorithm) -> Self {
+        Self {
+            algorithm,
+            faces: HashMap::new(),
+            dirty: HashSet::new(),
+            generation: None,
+            retriangulated: 0,
+        }
+    }
+
+    /// Returns the algorithm used to triangulate the faces.
+    pub fn algorithm(&self) -> TriangulationAlgorithm {
+        self.algorithm
+    }
+
+    /// Changes the algorithm used to triangulate the faces. Clears the cache if it differs.
+    pub fn set_algorithm(&mut self, algorithm: TriangulationAlgorithm) {
+        if algorithm != self.algorithm {
+            self.algorithm = algorithm;
+            self.clear();
+        }
+    }
+
+    /// Forces the face to be re-triangulated by the next triangulation, e.g., after
+    /// changing a payload of a mesh that doesn't track its changes.
+    pub fn mark_dirty(&mut self, face: T::F) {
+        self.dirty.insert(face);
+    }
+
+    /// Removes all cached triangulations.
+    pub fn clear(&mut self) {
+        self.faces.clear();
+        self.dirty.clear();
+        self.generation = None;
+    }
+
+    /// Returns the number of faces with a cached triangulation.
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// Returns whether no face has a cached triangulation.
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    /// Returns the number of faces that were re-triangulated by the last triangulation.
+    pub fn last_retriangulated(&self) -> usize {
+        self.retriangulated
+    }
+
+    /// Hashes the vertex ids and positions of the face. Only used for meshes that don't
+    /// track their changes.
+    fn fingerprint(face: &T::Face, mesh: &T::Mesh) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for v in face.vertices(mesh) {
+            v.id().hash(&mut hasher);
+            let p: T::Vec = *v.payload().pos();
+            for c in [p.x(), p.y(), p.z()] {
+                c.to_f64().to_bits().hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Like `Triangulateable::triangulate_into`, but reuses the cached triangles of
+    /// unchanged faces.
+    pub fn triangulate_into(
+        &mut self,
+        mesh: &T::Mesh,
+        tri: &mut Triangulation<T::V>,
+        meta: &mut TesselationMeta<T::V>,
+    ) {
+        self.retriangulated = 0;
+        let generation = mesh.generation();
+        let mut alive = HashSet::new();
+        for face in mesh.faces() {
+            let f = face.id();
+            alive.insert(f);
+            let (fingerprint, unchanged) = match (self.generation, generation) {
+                (Some(seen), Some(now)) => (0, now == seen || !mesh.face_changed_since(f, seen)),
+                (None, Some(_)) => (0, false),
+                (_, None) => {
+                    let fingerprint = Self::fingerprint(face, mesh);
+                    let unchanged = self.faces.get(&f).is_some_and(|(h, _)| *h == fingerprint);
+                    (fingerprint, unchanged)
+                }
+            };
+            let valid = unchanged && !self.dirty.contains(&f) && self.faces.contains_key(&f);
+            if !valid {
+                let mut indices = Vec::new();
+                triangulate_face::<T>(
+                    face,
+                    mesh,
+                    &mut Triangulation::new(&mut indices),
+                    self.algorithm,
+                    meta,
+                );
+                self.faces.insert(f, (fingerprint, indices));
+                self.retriangulated += 1;
+            }
+
+            tri.begin_face(f);
+            for t in self.faces[&f].1.chunks(3) {
+                tri.insert_triangle(t[0], t[1], t[2]);
+            }
+        }
+        self.faces.retain(|f, _| alive.contains(f));
+        self.dirty.clear();
+        self.generation = generation;
+    }
+
+    /// Like `Triangulateable::triangulate`, but reuses the cached triangles of
+    /// unchanged faces.
+    pub fn triangulate(
+        &mut self,
+        mesh: &T::Mesh,
+        meta: &mut TesselationMeta<T::V>,
+    ) -> (Vec<T::V>, Vec<T::VP>) {
+        let mut indices = Vec::new();
+        self.triangulate_into(mesh, &mut Triangulation::new(&mut indices), meta);
+        let vs = mesh.dense_vertices(&mut indices);
+        (indices, vs)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_tesselation_cache() {
+        let mut mesh = Mesh3d64::heightmap(1.0, 1.0, 4, |_, _| 0.0);
+        let n = mesh.num_faces();
+        let mut cache = TesselationCache::<MeshType3d64PNU>::new(TriangulationAlgorithm::Delaunay);
+        let mut meta = TesselationMeta::default();
+        let expected = mesh.triangulate(TriangulationAlgorithm::Delaunay, &mut meta);
+        assert_eq!(cache.triangulate(&mesh, &mut meta), expected);
+        assert_eq!(cache.last_retriangulated(), n);
+        assert_eq!(cache.len(), n);
+
+        // nothing changed
+        assert_eq!(cache.triangulate(&mesh, &mut meta), expected);
+        assert_eq!(cache.last_retriangulated(), 0);
+
+        // moving an inner vertex within the plane only affects its faces
+        let v = mesh
+            .vertex_ids()
+            .find(|v| !mesh.vertex(*v).is_boundary(&mesh))
+            .unwrap();
+        let affected = mesh.vertex(v).faces(&mesh).count();
+        let p = mesh.vertex(v).pos();
+        mesh.vertex_mut(v)
+            .payload_mut()
+            .set_pos(p + Vec3::new(0.01, 0.0, 0.0));
+        let (indices, _) = cache.triangulate(&mesh, &mut meta);
+        assert_eq!(cache.last_retriangulated(), affected);
+        let (fresh, _) = mesh.triangulate(TriangulationAlgorithm::Delaunay, &mut meta);
+        assert_eq!(indices, fresh);
+
+        // edits of face and edge payloads are detected, too
+        let f = mesh.face_ids().last().unwrap();
+        mesh.face_mut(f).payload_mut();
+        cache.triangulate(&mesh, &mut meta);
+        assert_eq!(cache.last_retriangulated(), 1);
+        let e = mesh
+            .edge_ids()
+            .find(|e| !mesh.edge(*e).is_boundary_self())
+            .unwrap();
+        mesh.edge_mut(e).payload_mut();
+        cache.triangulate(&mesh, &mut meta);
+        assert_eq!(cache.last_retriangulated(), 1);
+
+        // faces can be invalidated explicitly
+        let f = mesh.face_ids().next().unwrap();
+        cache.mark_dirty(f);
+        cache.triangulate(&mesh, &mut meta);
+        assert_eq!(cache.last_retriangulated(), 1);
+
+        // changing the algorithm invalidates everything
+        cache.set_algorithm(TriangulationAlgorithm::Fan);
+        assert!(cache.is_empty());
+        cache.triangulate(&mesh, &mut meta);
+        assert_eq!(cache.last_retriangulated(), n);
+    }
+}
@@ -0,0 +1,905 @@
+use crate::math::{Scalar, Vector2D};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use robust::{incircle, orient2d, Coord};
+use std::collections::{HashSet, VecDeque};
+
+/// Marks a missing neighbor.
+const NONE: usize = usize::MAX;
+
+/// The number of vertices of the super triangle. They precede the inserted vertices.
+const SUPER: usize = 3;
+
+#[inline(always)]
+fn next(i: usize) -> usize {
+    (i + 1) % 3
+}
+
+#[inline(always)]
+fn prev(i: usize) -> usize {
+    (i + 2) % 3
+}
+
+/// Converts the point to the coordinates of the exact predicates. f32 and f64 coordinates
+/// are converted without rounding.
+#[inline(always)]
+fn coord<Vec2: Vector2D>(p: Vec2) -> Coord<f64> {
+    Coord {
+        x: p.x().to_f64(),
+        y: p.y().to_f64(),
+    }
+}
+
+/// Positive iff the triangle is counter-clockwise and zero iff it is degenerate.
+/// Uses Shewchuk's adaptive predicates, so the sign is exact.
+fn orient<Vec2: Vector2D>(a: Vec2, b: Vec2, c: Vec2) -> f64 {
+    orient2d(coord(a), coord(b), coord(c))
+}
+
+/// Positive iff `d` lies inside the circumcircle of the counter-clockwise triangle `a, b, c`.
+/// Uses Shewchuk's adaptive predicates, so the sign is exact.
+fn in_circle<Vec2: Vector2D>(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> f64 {
+    incircle(coord(a), coord(b), coord(c), coord(d))
+}
+
+/// The position of the point along a Hilbert curve through the square from `min` to
+/// `max` subdivided into `2^16 x 2^16` cells.
+fn hilbert_index<Vec2: Vector2D>(p: Vec2, min: Vec2, max: Vec2) -> u64 {
+    const ORDER: u32 = 16;
+    let n = 1u64 << ORDER;
+    let size = (max.x() - min.x()).max(max.y() - min.y()).to_f64();
+    let cell = |x: Vec2::S, m: Vec2::S| {
+        let t = if size > 0.0 {
+            (x - m).to_f64() / size
+        } else {
+            0.0
+        };
+        ((t * (n - 1) as f64) as u64).min(n - 1)
+    };
+    let (mut x, mut y) = (cell(p.x(), min.x()), cell(p.y(), min.y()));
+    let mut d = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = u64::from(x & s > 0);
+        let ry = u64::from(y & s > 0);
+        d += s * s * ((3 * rx) ^ ry);
+        // rotate the quadrant
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+/// Returns a biased randomized insertion order (BRIO) of the points: The shuffled points
+/// are split into rounds doubling in size and each round is sorted along a Hilbert curve.
+/// The randomization avoids the quadratic worst case of incremental insertion on sorted
+/// inputs while the spatial order keeps the walks for locating the points short.
+/// The order is deterministic.
+fn brio<Vec2: Vector2D>(points: &[Vec2], min: Vec2, max: Vec2) -> Vec<usize> {
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.shuffle(&mut StdRng::seed_from_u64(0x5eed));
+    let mut start = 0;
+    while start < order.len() {
+        let end = (start * 2).max(1).min(order.len());
+        order[start..end].sort_by_key(|i| hilbert_index(points[*i], min, max));
+        start = end;
+    }
+    order
+}
+
+/// Where a point lies in the triangulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Location {
+    /// On the given vertex.
+    Vertex(usize),
+
+    /// On the edge of the triangle opposite to the given corner.
+    Edge(usize, usize),
+
+    /// Inside the triangle.
+    Face(usize),
+}
+
+/// A constrained delaunay triangulation of a polygon.
+///
+/// Vertices are inserted incrementally into a super triangle and the delaunay property is
+/// restored by flipping edges. Constraints are inserted by flipping the edges they cross
+/// until the segment appears, and the triangles inside the polygon are found by counting
+/// the crossed constraints.
+#[derive(Debug, Clone)]
+pub(crate) struct Cdt<Vec2: Vector2D> {
+    /// The positions of the super triangle followed by the inserted vertices.
+    points: Vec<Vec2>,
+
+    /// The corners of the triangles in counter-clockwise order.
+    triangles: Vec<[usize; 3]>,
+
+    /// The neighbor across the edge opposite to each corner.
+    neighbors: Vec<[usize; 3]>,
+
+    /// Whether the edge opposite to each corner is constrained.
+    constrained: Vec<[bool; 3]>,
+
+    /// Whether the triangle lies inside the polygon.
+    inside: Vec<bool>,
+
+    /// Some triangle incident to each vertex.
+    vertex_triangle: Vec<usize>,
+
+    /// The constrained edges.
+    segments: Vec<(usize, usize)>,
+
+    /// The triangle where the last point location ended.
+    hint: usize,
+}
+
+impl<Vec2: Vector2D> Cdt<Vec2> {
+    /// Creates an empty triangulation for points within the given bounds.
+    fn new(min: Vec2, max: Vec2) -> Self {
+        let center = (min + max) * Vec2::S::HALF;
+        let size = (max.x() - min.x()).max(max.y() - min.y()).max(Vec2::S::ONE);
+        let twenty = Vec2::S::TEN * Vec2::S::TWO;
+        let points = vec![
+            center + Vec2::new(-twenty * size, -Vec2::S::TEN * size),
+            center + Vec2::new(twenty * size, -Vec2::S::TEN * size),
+            center + Vec2::new(Vec2::S::ZERO, twenty * size),
+        ];
+        Cdt {
+            points,
+            triangles: vec![[0, 1, 2]],
+            neighbors: vec![[NONE; 3]],
+            constrained: vec![[false; 3]],
+            inside: vec![false],
+            vertex_triangle: vec![0; SUPER],
+            segments: Vec::new(),
+            hint: 0,
+        }
+    }
+
+    /// Triangulates the polygon bounded by the given rings, e.g., an outline and its holes.
+    /// The vertices of the triangulation have the same indices as the vertices of the
+    /// concatenated rings. A point is inside the polygon iff it is enclosed by an odd
+    /// number of rings, so their orientation doesn't matter.
+    ///
+    /// Vertices with the same position are merged, i.e., the triangles only use one of
+    /// their indices. The points are inserted in a biased randomized order, so the
+    /// expected running time is `O(n log n)` for typical inputs.
+    pub fn from_rings(rings: &[Vec<Vec2>]) -> Self {
+        let first = rings[0][0];
+        let (min, max) = rings
+            .iter()
+            .flatten()
+            .fold((first, first), |(min, max), p| {
+                (
+                    Vec2::new(min.x().min(p.x()), min.y().min(p.y())),
+                    Vec2::new(max.x().max(p.x()), max.y().max(p.y())),
+                )
+            });
+        let mut cdt = Self::new(min, max);
+        let points = rings.iter().flatten().copied().collect::<Vec<_>>();
+        cdt.points.extend(points.iter().copied());
+        cdt.vertex_triangle.resize(cdt.points.len(), NONE);
+
+        // the vertex each point is merged into
+        let mut merged = (SUPER..cdt.points.len()).collect::<Vec<_>>();
+        for i in brio(&points, min, max) {
+            if let Err(v) = cdt.insert_vertex(SUPER + i) {
+                merged[i] = v;
+            }
+        }
+
+        let mut offset = 0;
+        for ring in rings {
+            for i in 0..ring.len() {
+                let a = merged[offset + i];
+                let b = merged[offset + (i + 1) % ring.len()];
+                if a != b {
+                    cdt.insert_constraint(a, b);
+                }
+            }
+            offset += ring.len();
+        }
+        cdt.classify();
+        cdt
+    }
+
+    /// The number of vertices, i.e., the vertices of the polygon followed by the Steiner points.
+    pub fn num_vertices(&self) -> usize {
+        self.points.len() - SUPER
+    }
+
+    /// The position of the vertex.
+    pub fn point(&self, v: usize) -> Vec2 {
+        self.points[v + SUPER]
+    }
+
+    /// Iterates the counter-clockwise triangles inside the polygon.
+    pub fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.triangles
+            .iter()
+            .zip(&self.inside)
+            .filter(|(_, inside)| **inside)
+            .map(|(t, _)| t.map(|v| v - SUPER))
+    }
+
+    /// Returns a triangle inside the polygon containing the point if there is one.
+    pub fn containing_triangle(&self, p: Vec2) -> Option<[usize; 3]> {
+        let t = self.walk(self.hint, p);
+        let tri = self.triangles[t];
+        let candidates = std::iter::once(t).chain((0..3).filter_map(|i| {
+            (orient(self.points[tri[next(i)]], self.points[tri[prev(i)]], p) == 0.0)
+                .then_some(self.neighbors[t][i])
+        }));
+        candidates
+            .filter(|u| *u != NONE && self.inside[*u])
+            .map(|u| self.triangles[u].map(|v| v - SUPER))
+            .next()
+    }
+
+    /// Inserts the point. Returns the existing vertex as an error if there is one.
+    fn insert_point(&mut self, p: Vec2) -> Result<usize, usize> {
+        let v = self.points.len();
+        self.points.push(p);
+        self.vertex_triangle.push(NONE);
+        let res = self.insert_vertex(v);
+        if res.is_err() {
+            self.points.pop();
+            self.vertex_triangle.pop();
+        }
+        res
+    }
+
+    /// Inserts the vertex `v` whose position is already stored but which isn't part of any
+    /// triangle yet. Returns the existing vertex at the same position as an error if there
+    /// is one; `v` stays unused in that case.
+    fn insert_vertex(&mut self, v: usize) -> Result<usize, usize> {
+        match self.locate(self.points[v]) {
+            Location::Vertex(w) => return Err(w),
+            Location::Edge(t, i) => {
+                self.vertex_triangle[v] = t;
+                self.split_edge(t, i, v);
+            }
+            Location::Face(t) => {
+                self.vertex_triangle[v] = t;
+                self.split_triangle(t, v);
+            }
+        }
+        Ok(v)
+    }
+
+    /// Walks from the triangle `start` towards `p` and returns the triangle containing it.
+    fn walk(&self, start: usize, p: Vec2) -> usize {
+        let mut t = start.min(self.triangles.len() - 1);
+        let mut steps = 0;
+        'walk: loop {
+            steps += 1;
+            if steps > self.triangles.len() {
+                // numerical issues might cause cycles
+                return self.brute_force_locate(p);
+            }
+            let tri = self.triangles[t];
+            for k in 0..3 {
+                // vary the first edge to avoid cycles
+                let i = (k + steps) % 3;
+                if orient(self.points[tri[next(i)]], self.points[tri[prev(i)]], p) < 0.0 {
+                    let u = self.neighbors[t][i];
+                    assert!(u != NONE, "point outside of the super triangle");
+                    t = u;
+                    continue 'walk;
+                }
+            }
+            return t;
+        }
+    }
+
+    /// Finds the triangle containing `p` by testing all triangles.
+    fn brute_force_locate(&self, p: Vec2) -> usize {
+        let min_orient = |t: &[usize; 3]| {
+            (0..3)
+                .map(|i| orient(self.points[t[next(i)]], self.points[t[prev(i)]], p))
+                .fold(f64::INFINITY, f64::min)
+        };
+        (0..self.triangles.len())
+            .max_by(|a, b| {
+                min_orient(&self.triangles[*a]).total_cmp(&min_orient(&self.triangles[*b]))
+            })
+            .unwrap()
+    }
+
+    fn locate(&mut self, p: Vec2) -> Location {
+        let t = self.walk(self.hint, p);
+        self.hint = t;
+        let tri = self.triangles[t];
+        if let Some(v) = tri
+            .iter()
+            .find(|v| self.points[**v].x() == p.x() && self.points[**v].y() == p.y())
+        {
+            return Location::Vertex(*v);
+        }
+        for i in 0..3 {
+            if orient(self.points[tri[next(i)]], self.points[tri[prev(i)]], p) <= 0.0 {
+                return Location::Edge(t, i);
+            }
+        }
+        Location::Face(t)
+    }
+
+    fn add_triangle(
+        &mut self,
+        tri: [usize; 3],
+        neighbors: [usize; 3],
+        constrained: [bool; 3],
+        inside: bool,
+    ) -> usize {
+        self.triangles.push(tri);
+        self.neighbors.push(neighbors);
+        self.constrained.push(constrained);
+        self.inside.push(inside);
+        self.triangles.len() - 1
+    }
+
+    fn replace_neighbor(&mut self, t: usize, old: usize, new: usize) {
+        if t == NONE {
+            return;
+        }
+        for n in self.neighbors[t].iter_mut() {
+            if *n == old {
+                *n = new;
+            }
+        }
+    }
+
+    /// The corner of `u` opposite to the edge shared with `t`.
+    fn opposite(&self, u: usize, t: usize) -> usize {
+        (0..3).find(|j| self.neighbors[u][*j] == t).unwrap()
+    }
+
+    /// Splits the triangle into three triangles at the new vertex `p`.
+    fn split_triangle(&mut self, t: usize, p: usize) {
+        let [a, b, c] = self.triangles[t];
+        let [na, nb, nc] = self.neighbors[t];
+        let [ca, cb, cc] = self.constrained[t];
+        let inside = self.inside[t];
+        let t1 = self.triangles.len();
+        let t2 = t1 + 1;
+        self.triangles[t] = [a, b, p];
+        self.neighbors[t] = [t1, t2, nc];
+        self.constrained[t] = [false, false, cc];
+        self.add_triangle([b, c, p], [t2, t, na], [false, false, ca], inside);
+        self.add_triangle([c, a, p], [t, t1, nb], [false, false, cb], inside);
+        self.replace_neighbor(na, t, t1);
+        self.replace_neighbor(nb, t, t2);
+        self.vertex_triangle[a] = t;
+        self.vertex_triangle[b] = t;
+        self.vertex_triangle[c] = t1;
+        self.vertex_triangle[p] = t;
+        self.legalize(vec![(t, 2), (t1, 2), (t2, 2)]);
+    }
+
+    /// Splits the edge opposite to corner `i` of triangle `t` at the new vertex `p`.
+    fn split_edge(&mut self, t: usize, i: usize, p: usize) {
+        let tri = self.triangles[t];
+        let (a, b, c) = (tri[i], tri[next(i)], tri[prev(i)]);
+        let u = self.neighbors[t][i];
+        assert!(u != NONE, "point on the super triangle");
+        let j = self.opposite(u, t);
+        let s = self.triangles[u][j];
+        let (n_ca, n_ab) = (self.neighbors[t][next(i)], self.neighbors[t][prev(i)]);
+        let (c_ca, c_ab) = (self.constrained[t][next(i)], self.constrained[t][prev(i)]);
+        let (n_bs, n_sc) = (self.neighbors[u][next(j)], self.neighbors[u][prev(j)]);
+        let (c_bs, c_sc) = (self.constrained[u][next(j)], self.constrained[u][prev(j)]);
+        let c_bc = self.constrained[t][i];
+        let (inside_t, inside_u) = (self.inside[t], self.inside[u]);
+
+        let t1 = self.triangles.len();
+        let u1 = t1 + 1;
+        self.triangles[t] = [a, b, p];
+        self.neighbors[t] = [u1, t1, n_ab];
+        self.constrained[t] = [c_bc, false, c_ab];
+        self.triangles[u] = [s, c, p];
+        self.neighbors[u] = [t1, u1, n_sc];
+        self.constrained[u] = [c_bc, false, c_sc];
+        self.add_triangle([a, p, c], [u, n_ca, t], [c_bc, c_ca, false], inside_t);
+        self.add_triangle([s, p, b], [t, n_bs, u], [c_bc, c_bs, false], inside_u);
+        self.replace_neighbor(n_ca, t, t1);
+        self.replace_neighbor(n_bs, u, u1);
+        self.vertex_triangle[a] = t;
+        self.vertex_triangle[b] = t;
+        self.vertex_triangle[c] = t1;
+        self.vertex_triangle[s] = u;
+        self.vertex_triangle[p] = t;
+
+        if c_bc {
+            let k = self
+                .segments
+                .iter()
+                .position(|&(x, y)| (x, y) == (b, c) || (x, y) == (c, b))
+                .unwrap();
+            self.segments[k] = (b, p);
+            self.segments.push((p, c));
+        }
+
+        self.legalize(vec![(t, 2), (t1, 1), (u, 2), (u1, 1)]);
+    }
+
+    /// Flips the edge opposite to corner `i` of triangle `t`. Afterwards, `t` starts with
+    /// that corner and the neighbor ends with it.
+    fn flip(&mut self, t: usize, i: usize) -> (usize, usize) {
+        let tri = self.triangles[t];
+        let (p, q, r) = (tri[i], tri[next(i)], tri[prev(i)]);
+        let u = self.neighbors[t][i];
+        let j = self.opposite(u, t);
+        let s = self.triangles[u][j];
+        let (n_rp, n_pq) = (self.neighbors[t][next(i)], self.neighbors[t][prev(i)]);
+        let (c_rp, c_pq) = (self.constrained[t][next(i)], self.constrained[t][prev(i)]);
+        let (n_qs, n_sr) = (self.neighbors[u][next(j)], self.neighbors[u][prev(j)]);
+        let (c_qs, c_sr) = (self.constrained[u][next(j)], self.constrained[u][prev(j)]);
+
+        self.triangles[t] = [p, q, s];
+        self.neighbors[t] = [n_qs, u, n_pq];
+        self.constrained[t] = [c_qs, false, c_pq];
+        self.triangles[u] = [s, r, p];
+        self.neighbors[u] = [n_rp, t, n_sr];
+        self.constrained[u] = [c_rp, false, c_sr];
+        self.replace_neighbor(n_qs, u, t);
+        self.replace_neighbor(n_rp, t, u);
+        self.vertex_triangle[p] = t;
+        self.vertex_triangle[q] = t;
+        self.vertex_triangle[s] = t;
+        self.vertex_triangle[r] = u;
+        (t, u)
+    }
+
+    /// Whether the edge opposite to corner `i` of triangle `t` should be flipped.
+    fn is_illegal(&self, t: usize, i: usize) -> bool {
+        let u = self.neighbors[t][i];
+        if u == NONE || self.constrained[t][i] {
+            return false;
+        }
+        let s = self.triangles[u][self.opposite(u, t)];
+        let [a, b, c] = self.triangles[t].map(|v| self.points[v]);
+        in_circle(a, b, c, self.points[s]) > 0.0
+    }
+
+    /// Restores the delaunay property after inserting a vertex. The stack contains the
+    /// edges opposite to the new vertex.
+    fn legalize(&mut self, mut stack: Vec<(usize, usize)>) {
+        while let Some((t, i)) = stack.pop() {
+            if self.is_illegal(t, i) {
+                let (t, u) = self.flip(t, i);
+                stack.push((t, 0));
+                stack.push((u, 2));
+            }
+        }
+    }
+
+    /// Finds the triangle containing the directed edge from `x` to `y`. Returns the
+    /// triangle and the corner opposite to the edge.
+    fn find_edge(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        let start = self.vertex_triangle[x];
+        let mut t = start;
+        loop {
+            let k = self.triangles[t].iter().position(|v| *v == x).unwrap();
+            if self.triangles[t][next(k)] == y {
+                return Some((t, prev(k)));
+            }
+            t = self.neighbors[t][prev(k)];
+            if t == NONE || t == start {
+                return None;
+            }
+        }
+    }
+
+    fn set_constrained(&mut self, x: usize, y: usize) {
+        let (t, i) = self.find_edge(x, y).unwrap();
+        self.constrained[t][i] = true;
+        let u = self.neighbors[t][i];
+        if u != NONE {
+            let j = self.opposite(u, t);
+            self.constrained[u][j] = true;
+        }
+        self.segments.push((x, y));
+    }
+
+    /// Inserts the segment between the vertices as a constraint.
+    fn insert_constraint(&mut self, a: usize, b: usize) {
+        let mut a = a;
+        while a != b {
+            a = self.insert_constraint_part(a, b);
+        }
+    }
+
+    /// Inserts the segment from `a` towards `b` up to the first vertex on it and returns
+    /// that vertex.
+    fn insert_constraint_part(&mut self, a: usize, b: usize) -> usize {
+        if self.find_edge(a, b).is_some() {
+            self.set_constrained(a, b);
+            return b;
+        }
+        let (pa, pb) = (self.points[a], self.points[b]);
+        let side = |cdt: &Self, v: usize| orient(pa, pb, cdt.points[v]);
+
+        // find the first edge crossed by the segment
+        let start = self.vertex_triangle[a];
+        let mut t = start;
+        let mut crossing = loop {
+            let k = self.triangles[t].iter().position(|v| *v == a).unwrap();
+            let (q, r) = (self.triangles[t][next(k)], self.triangles[t][prev(k)]);
+            for v in [q, r] {
+                if side(self, v) == 0.0 && (self.points[v] - pa).dot(&(pb - pa)).is_positive() {
+                    self.set_constrained(a, v);
+                    return v;
+                }
+            }
+            if side(self, q) < 0.0 && side(self, r) > 0.0 {
+                break (t, k);
+            }
+            t = self.neighbors[t][prev(k)];
+            assert!(t != start, "constraint not found");
+        };
+
+        // collect the crossed edges as pairs of vertices right and left of the segment
+        let mut edges = VecDeque::new();
+        let end = loop {
+            let (t, i) = crossing;
+            let tri = self.triangles[t];
+            let (right, left) = (tri[next(i)], tri[prev(i)]);
+            edges.push_back((right, left));
+            let u = self.neighbors[t][i];
+            let s = self.triangles[u][self.opposite(u, t)];
+            if s == b || side(self, s) == 0.0 {
+                break s;
+            }
+            let k = self.triangles[u]
+                .iter()
+                .position(|v| *v == if side(self, s) > 0.0 { left } else { right });
+            crossing = (u, k.unwrap());
+        };
+
+        // flip the crossed edges until the segment appears
+        let mut new_edges = Vec::new();
+        while let Some((x, y)) = edges.pop_front() {
+            let (t, i) = self.find_edge(x, y).unwrap();
+            let p = self.triangles[t][i];
+            let u = self.neighbors[t][i];
+            let s = self.triangles[u][self.opposite(u, t)];
+            let convex = orient(self.points[p], self.points[x], self.points[s]) > 0.0
+                && orient(self.points[s], self.points[y], self.points[p]) > 0.0;
+            if !convex {
+                edges.push_back((x, y));
+                continue;
+            }
+            self.flip(t, i);
+            if side(self, p) * side(self, s) < 0.0 {
+                edges.push_back((p, s));
+            } else {
+                new_edges.push((p, s));
+            }
+        }
+        self.set_constrained(a, end);
+
+        // restore the delaunay property of the new edges
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for e in new_edges.iter_mut() {
+                let Some((t, i)) = self.find_edge(e.0, e.1) else {
+                    continue;
+                };
+                if self.is_illegal(t, i) {
+                    let p = self.triangles[t][i];
+                    let u = self.neighbors[t][i];
+                    let s = self.triangles[u][self.opposite(u, t)];
+                    self.flip(t, i);
+                    *e = (p, s);
+                    changed = true;
+                }
+            }
+        }
+        end
+    }
+
+    /// Marks the triangles inside the polygon, i.e., those separated from the super
+    /// triangle by an odd number of constraints.
+    fn classify(&mut self) {
+        let mut depth = vec![usize::MAX; self.triangles.len()];
+        let mut queue = VecDeque::new();
+        for (t, tri) in self.triangles.iter().enumerate() {
+            if tri.iter().any(|v| *v < SUPER) {
+                depth[t] = 0;
+                queue.push_back(t);
+            }
+        }
+        while let Some(t) = queue.pop_front() {
+            for i in 0..3 {
+                let u = self.neighbors[t][i];
+                if u == NONE {
+                    continue;
+                }
+                let c = self.constrained[t][i];
+                let d = depth[t] + c as usize;
+                if d < depth[u] {
+                    depth[u] = d;
+                    if c {
+                        queue.push_back(u);
+                    } else {
+                        queue.push_front(u);
+                    }
+                }
+            }
+        }
+        self.inside = depth.iter().map(|d| d % 2 == 1).collect();
+    }
+
+    /// Inserts Steiner points in the spirit of Ruppert's algorithm until no triangle inside
+    /// the polygon has an angle below `min_angle` or an area above `max_area`. Segments
+    /// encroached upon are split at their midpoints instead. Returns the number of
+    /// inserted points.
+    pub fn refine(
+        &mut self,
+        min_angle: Vec2::S,
+        max_area: Option<Vec2::S>,
+        max_steiner_points: usize,
+    ) -> usize {
+        let mut skipped = HashSet::new();
+        let mut added = 0;
+        while added < max_steiner_points {
+            if let Some(k) = self.encroached_segment() {
+                self.split_segment(k);
+                added += 1;
+                continue;
+            }
+            let Some(t) = self.bad_triangle(min_angle, max_area, &skipped) else {
+                break;
+            };
+            let [a, b, c] = self.triangles[t].map(|v| self.points[v]);
+            let center = circumcenter(a, b, c);
+            if let Some(k) = (0..self.segments.len()).find(|k| {
+                let (x, y) = self.segments[*k];
+                encroaches(self.points[x], self.points[y], center)
+            }) {
+                self.split_segment(k);
+                added += 1;
+                continue;
+            }
+            self.hint = t;
+            let location = self.locate(center);
+            let outside = match location {
+                Location::Vertex(_) => true,
+                Location::Edge(u, _) | Location::Face(u) => !self.inside[u],
+            };
+            if outside || self.insert_point(center).is_err() {
+                skipped.insert(self.triangles[t]);
+                continue;
+            }
+            added += 1;
+        }
+        added
+    }
+
+    /// Returns a segment with a vertex of an adjacent inner triangle in its diametral circle.
+    fn encroached_segment(&self) -> Option<usize> {
+        (0..self.segments.len()).find(|k| {
+            let (x, y) = self.segments[*k];
+            [(x, y), (y, x)].iter().any(|(x, y)| {
+                let Some((t, i)) = self.find_edge(*x, *y) else {
+                    return false;
+                };
+                let apex = self.triangles[t][i];
+                self.inside[t] && encroaches(self.points[*x], self.points[*y], self.points[apex])
+            })
+        })
+    }
+
+    /// Splits the segment at its midpoint.
+    fn split_segment(&mut self, k: usize) {
+        let (x, y) = self.segments[k];
+        let (t, i) = self.find_edge(x, y).unwrap();
+        let p = self.points.len();
+        self.points
+            .push((self.points[x] + self.points[y]) * Vec2::S::HALF);
+        self.vertex_triangle.push(t);
+        self.split_edge(t, i, p);
+    }
+
+    /// Returns an inner triangle violating the quality criteria.
+    fn bad_triangle(
+        &self,
+        min_angle: Vec2::S,
+        max_area: Option<Vec2::S>,
+        skipped: &HashSet<[usize; 3]>,
+    ) -> Option<usize> {
+        (0..self.triangles.len()).find(|t| {
+            if !self.inside[*t] || skipped.contains(&self.triangles[*t]) {
+                return false;
+            }
+            let [a, b, c] = self.triangles[*t].map(|v| self.points[v]);
+            let area = (b - a).perp_dot(&(c - a)) * Vec2::S::HALF;
+            if max_area.is_some_and(|max| area > max) {
+                return true;
+            }
+            let angle = a
+                .angle_tri(b, c)
+                .abs()
+                .min(b.angle_tri(c, a).abs())
+                .min(c.angle_tri(a, b).abs());
+            angle < min_angle
+        })
+    }
+}
+
+/// Whether `p` lies strictly inside the circle with diameter `a, b`.
+fn encroaches<Vec2: Vector2D>(a: Vec2, b: Vec2, p: Vec2) -> bool {
+    (a - p).dot(&(b - p)).is_negative()
+}
+
+/// The center of the circumcircle of the triangle.
+fn circumcenter<Vec2: Vector2D>(a: Vec2, b: Vec2, c: Vec2) -> Vec2 {
+    let (b, c) = (b - a, c - a);
+    let d = b.perp_dot(&c) * Vec2::S::TWO;
+    let (lb, lc) = (b.length_squared(), c.length_squared());
+    a + Vec2::new((c.y() * lb - b.y() * lc) / d, (b.x() * lc - c.x() * lb) / d)
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use super::*;
+    use crate::extensions::nalgebra::*;
+
+    /// Asserts that all triangles are counter-clockwise and all unconstrained edges
+    /// are locally delaunay.
+    fn assert_delaunay<Vec2: Vector2D>(cdt: &Cdt<Vec2>) {
+        for (t, tri) in cdt.triangles.iter().enumerate() {
+            let [a, b, c] = tri.map(|v| cdt.points[v]);
+            assert!(orient(a, b, c) > 0.0);
+            for i in 0..3 {
+                let u = cdt.neighbors[t][i];
+                if u != NONE && !cdt.constrained[t][i] {
+                    let s = cdt.triangles[u][cdt.opposite(u, t)];
+                    assert!(in_circle(a, b, c, cdt.points[s]) <= 0.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cdt_concave() {
+        // a comb whose teeth force the constraints to cross many delaunay edges
+        let mut polygon = vec![Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)];
+        for i in (0..5).rev() {
+            let x = i as f64 * 2.0;
+            polygon.push(Vec2::new(x + 2.0, 3.0));
+            polygon.push(Vec2::new(x + 1.0, 0.2));
+        }
+        polygon.push(Vec2::new(0.0, 3.0));
+        let cdt = Cdt::from_rings(&[polygon.clone()]);
+        assert_eq!(cdt.num_vertices(), polygon.len());
+        let triangles = cdt.triangles().collect::<Vec<_>>();
+        assert_eq!(triangles.len(), polygon.len() - 2);
+        let area = triangles
+            .iter()
+            .map(|[a, b, c]| {
+                let (a, b, c) = (polygon[*a], polygon[*b], polygon[*c]);
+                let area = (b - a).perp_dot(&(c - a)) * 0.5;
+                assert!(area > 0.0);
+                area
+            })
+            .sum::<f64>();
+        let expected = polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(a, b)| a.perp_dot(b) * 0.5)
+            .sum::<f64>();
+        assert!(area.is_about(expected, 1e-9));
+    }
+
+    #[test]
+    fn test_cdt_f32() {
+        let polygon = (0..100)
+            .map(|i| {
+                let a = i as f32 / 100.0 * std::f32::consts::TAU;
+                let r = if i % 2 == 0 { 1.0 } else { 0.6 };
+                Vec2::<f32>::new(a.cos() * r, a.sin() * r)
+            })
+            .collect::<Vec<_>>();
+        let mut cdt = Cdt::from_rings(&[polygon]);
+        assert_eq!(cdt.triangles().count(), 98);
+        let added = cdt.refine(0.3, Some(0.01), 1000);
+        assert!(added > 0);
+        assert_eq!(cdt.num_vertices(), 100 + added);
+        assert!(cdt.triangles().count() > 98);
+    }
+
+    #[test]
+    fn test_cdt_holes() {
+        let square = |r: f64| {
+            vec![
+                Vec2::new(-r, -r),
+                Vec2::new(r, -r),
+                Vec2::new(r, r),
+                Vec2::new(-r, r),
+            ]
+        };
+        // a frame around a square island; the orientation of the rings doesn't matter
+        let mut hole = square(2.0);
+        hole.reverse();
+        let cdt = Cdt::from_rings(&[square(3.0), hole, square(1.0)]);
+        let points = (0..12).map(|i| cdt.point(i)).collect::<Vec<_>>();
+        let area = cdt
+            .triangles()
+            .map(|[a, b, c]| (points[b] - points[a]).perp_dot(&(points[c] - points[a])) * 0.5)
+            .sum::<f64>();
+        assert!(area.is_about(36.0 - 16.0 + 4.0, 1e-9));
+        // n + 2h - 2 triangles for n vertices and h holes, and two for the island
+        let (n, h) = (8, 1);
+        assert_eq!(cdt.triangles().count(), n + 2 * h - 2 + 2);
+        assert_delaunay(&cdt);
+        assert!(cdt.containing_triangle(Vec2::new(0.0, 0.0)).is_some());
+        assert!(cdt.containing_triangle(Vec2::new(1.5, 0.0)).is_none());
+        assert!(cdt.containing_triangle(Vec2::new(2.5, 0.0)).is_some());
+    }
+
+    #[test]
+    fn test_cdt_exact() {
+        // the collinear and cocircular points on the boundary of a grid far from the
+        // origin, given in sorted order, are the worst case for inexact predicates
+        let k = 50;
+        let offset = 1e7;
+        let side = (0..k).map(|i| offset + i as f64 * 0.25);
+        let end = offset + k as f64 * 0.25;
+        let polygon = side
+            .clone()
+            .map(|x| Vec2::new(x, offset))
+            .chain(side.clone().map(|y| Vec2::new(end, y)))
+            .chain(side.clone().map(|x| Vec2::new(offset + end - x, end)))
+            .chain(side.map(|y| Vec2::new(offset, offset + end - y)))
+            .collect::<Vec<_>>();
+        let cdt = Cdt::from_rings(std::slice::from_ref(&polygon));
+        assert_delaunay(&cdt);
+        let triangles = cdt.triangles().collect::<Vec<_>>();
+        assert_eq!(triangles.len(), polygon.len() - 2);
+        let area = triangles
+            .iter()
+            .map(|[a, b, c]| {
+                let (a, b, c) = (polygon[*a], polygon[*b], polygon[*c]);
+                (b - a).perp_dot(&(c - a)) * 0.5
+            })
+            .sum::<f64>();
+        assert!(area.is_about((k as f64 * 0.25).powi(2), 1e-6));
+    }
+
+    #[test]
+    fn test_cdt_duplicates() {
+        // a notched square with a repeated corner
+        let polygon = [
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 2.0),
+            (1.0, 1.0),
+            (0.0, 2.0),
+        ]
+        .map(|(x, y)| Vec2::new(x, y));
+        let cdt = Cdt::from_rings(&[polygon.to_vec()]);
+        assert_delaunay(&cdt);
+        let triangles = cdt.triangles().collect::<Vec<_>>();
+        assert_eq!(triangles.len(), 3);
+        // only one of the duplicates is used
+        let used = |v: usize| triangles.iter().any(|t| t.contains(&v));
+        assert!(used(1) != used(2));
+        let area = triangles
+            .iter()
+            .map(|[a, b, c]| {
+                let (a, b, c) = (polygon[*a], polygon[*b], polygon[*c]);
+                (b - a).perp_dot(&(c - a)) * 0.5
+            })
+            .sum::<f64>();
+        assert!(area.is_about(3.0, 1e-12));
+    }
+}
@@ -0,0 +1,259 @@
+mod cdt;
+
+use super::Triangulation;
+use crate::{
+    math::{Scalar, TransformTrait, Transformable, Vector, Vector2D},
+    mesh::{Face3d, FaceBasics, MeshType3D, VertexBasics},
+};
+use cdt::Cdt;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// Parameters for refining delaunay triangulations by inserting Steiner points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DelaunayRefinement<S: Scalar> {
+    /// Triangles with a smaller angle (in radians) are split. The refinement is guaranteed
+    /// to terminate for angles up to about 20 degrees; larger limits might only stop at
+    /// `max_steiner_points`. Zero disables the angle criterion.
+    pub min_angle: S,
+
+    /// Triangles with a larger area are split.
+    pub max_area: Option<S>,
+
+    /// The maximum number of Steiner points inserted per face.
+    pub max_steiner_points: usize,
+}
+
+impl<S: Scalar> Default for DelaunayRefinement<S> {
+    fn default() -> Self {
+        DelaunayRefinement {
+            min_angle: S::PI / S::from(9.0),
+            max_area: None,
+            max_steiner_points: 1000,
+        }
+    }
+}
+
+/// Builds the constrained delaunay triangulation of the polygon. The vertices of the
+/// triangulation have the same indices as the vertices of the polygon.
+fn polygon_cdt<V, Vec2: Vector2D>(vec2s: &[(Vec2, V)]) -> Cdt<Vec2> {
+    Cdt::from_rings(&[vec2s.iter().map(|(p, _)| *p).collect_vec()])
+}
+
+/// Triangulates the 2d polygon bounded by the given rings, e.g., an outline and its holes,
+/// with a constrained delaunay triangulation. Returns counter-clockwise triangles as indices
+/// into the concatenated rings. A point is inside the polygon iff it is enclosed by an odd
+/// number of rings, so their orientation doesn't matter, but they must not touch each other.
+pub fn triangulate_rings<Vec2: Vector2D>(rings: &[Vec<Vec2>]) -> Vec<[usize; 3]> {
+    Cdt::from_rings(rings).triangles().collect()
+}
+
+/// Converts the face into a triangle list using the delaunay triangulation.
+pub fn delaunay_triangulation<T: MeshType3D>(
+    face: &T::Face,
+    mesh: &T::Mesh,
+    tri: &mut Triangulation<T::V>,
+) {
+    debug_assert!(face.may_be_curved() || face.is_planar2(mesh));
+    debug_assert!(!face.has_self_intersections(mesh));
+
+    let vec2s = face.vertices_2d(mesh).collect_vec();
+    let i2v: Vec<T::V> = vec2s.iter().map(|(_, v)| *v).collect();
+    let cdt = polygon_cdt(&vec2s);
+    for [a, b, c] in cdt.triangles() {
+        tri.insert_triangle(i2v[a], i2v[b], i2v[c]);
+    }
+
+    // TODO: make tests to perform these tests. This is too slow, even for debug builds!
+    debug_assert!({
+        let vec2s = face.vec2s(mesh);
+        let vec_hm: HashMap<T::V, T::Vec2> = vec2s.iter().map(|v| (v.index, v.vec)).collect();
+        tri.verify_indices(&vec_hm);
+        tri.verify_all_indices_used(&vec2s);
+        tri.verify_no_intersections(&vec_hm);
+        tri.verify_non_degenerate_triangle(&vec_hm);
+        true
+    });
+}
+
+/// Converts the face with the given holes into a triangle list using the delaunay
+/// triangulation. The holes are faces lying inside the face in the same plane, e.g., the
+/// counters of font glyphs. Their orientation doesn't matter, but they must not touch
+/// the boundary of the face or each other.
+pub fn delaunay_triangulation_with_holes<T: MeshType3D>(
+    face: &T::Face,
+    holes: &[&T::Face],
+    mesh: &T::Mesh,
+    tri: &mut Triangulation<T::V>,
+) {
+    debug_assert!(face.may_be_curved() || face.is_planar2(mesh));
+
+    // project the holes into the plane of the face
+    let rotation = <T::Trans as TransformTrait<T::S, 3>>::from_rotation_arc(
+        Face3d::normal(face, mesh).normalize(),
+        T::Vec::from_xyz(T::S::ZERO, T::S::ZERO, T::S::ONE),
+    );
+    let mut i2v = Vec::new();
+    let rings = std::iter::once(face)
+        .chain(holes.iter().copied())
+        .map(|f| {
+            f.vertices(mesh)
+                .map(|v| {
+                    i2v.push(v.id());
+                    rotation.apply(v.pos()).vec2::<T::Vec2>()
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+    let cdt = Cdt::from_rings(&rings);
+    for [a, b, c] in cdt.triangles() {
+        tri.insert_triangle(i2v[a], i2v[b], i2v[c]);
+    }
+}
+
+/// Triangulates the face with a constrained delaunay triangulation and refines it in the
+/// spirit of Ruppert's algorithm: Steiner points are inserted at the circumcenters of
+/// triangles violating `refinement` and boundary edges that would be encroached upon are
+/// split instead, so large faces become well-shaped triangles.
+///
+/// Returns the triangles as indices into the vertices of the face (in the order of
+/// `face.vertices(mesh)`) followed by the Steiner points, and the payloads of the Steiner
+/// points. These are interpolated from the vertices of the face, so they lie in its plane.
+pub fn delaunay_refinement<T: MeshType3D>(
+    face: &T::Face,
+    mesh: &T::Mesh,
+    refinement: &DelaunayRefinement<T::S>,
+) -> (Vec<usize>, Vec<T::VP>) {
+    debug_assert!(face.may_be_curved() || face.is_planar2(mesh));
+    debug_assert!(!face.has_self_intersections(mesh));
+
+    let vec2s = face.vertices_2d(mesh).collect_vec();
+    let payloads = face
+        .vertices(mesh)
+        .map(|v| v.payload().clone())
+        .collect_vec();
+    let n = vec2s.len();
+    let mut cdt = polygon_cdt(&vec2s);
+    let original = cdt.clone();
+    cdt.refine(
+        refinement.min_angle,
+        refinement.max_area,
+        refinement.max_steiner_points,
+    );
+
+    // interpolates the payloads of the vertices `vs` of the polygon with the given weights
+    let interpolate = |vs: &[usize], weights: &[T::S]| -> T::VP {
+        let mut vp = payloads[vs[0]].clone();
+        let mut sum = weights[0];
+        for (v, w) in vs.iter().zip(weights).skip(1) {
+            sum += *w;
+            if sum.abs() > T::S::ZERO {
+                vp.lerp(&payloads[*v], *w / sum);
+            }
+        }
+        vp
+    };
+
+    let steiner = (n..cdt.num_vertices())
+        .map(|i| {
+            let p = cdt.point(i);
+            if let Some([a, b, c]) = original.containing_triangle(p) {
+                let [pa, pb, pc] = [a, b, c].map(|v| original.point(v));
+                let area = (pb - pa).perp_dot(&(pc - pa));
+                let wa = (pb - p).perp_dot(&(pc - p)) / area;
+                let wb = (pc - p).perp_dot(&(pa - p)) / area;
+                return interpolate(&[a, b, c], &[wa, wb, T::S::ONE - wa - wb]);
+            }
+            // can only happen due to numerical errors: use the closest boundary edge
+            let project = |i: usize| {
+                let (a, b) = (original.point(i), original.point((i + 1) % n));
+                let t =
+                    ((p - a).dot(&(b - a)) / (b - a).length_squared()).clamp(T::S::ZERO, T::S::ONE);
+                (t, (a + (b - a) * t).distance_squared(&p))
+            };
+            let closest = (0..n)
+                .min_by(|i, j| project(*i).1.partial_cmp(&project(*j).1).unwrap())
+                .unwrap();
+            let t = project(closest).0;
+            interpolate(&[closest, (closest + 1) % n], &[T::S::ONE - t, t])
+        })
+        .collect_vec();
+
+    (cdt.triangles().flatten().collect(), steiner)
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::extensions::nalgebra::*;
+    use crate::prelude::*;
+
+    fn verify_triangulation<T: MeshType3D>(mesh: &T::Mesh, f: T::F) {
+        let face = mesh.face(f);
+        let vec2s = face.vec2s(mesh);
+        assert!(
+            T::Poly::from_iter(vec2s.iter().map(|v| v.vec)).is_ccw(),
+            "Polygon must be counterclockwise"
+        );
+        let mut indices = Vec::new();
+        let mut tri = Triangulation::new(&mut indices);
+        delaunay_triangulation::<T>(face, mesh, &mut tri);
+        tri.verify_full::<T::Vec2, T::Poly>(&vec2s);
+    }
+
+    #[test]
+    #[cfg(feature = "fonts")]
+    fn test_font() {
+        let mut mesh2d = Mesh2d64Curved::new();
+        Font::new(include_bytes!("../../../assets/Cochineal-Roman.otf"), 1.0)
+            .layout_text::<2, MeshType2d64PNUCurved>("F", &mut mesh2d);
+        let mesh3d = mesh2d.to_nd(0.01);
+        self::verify_triangulation::<MeshType3d64PNU>(&mesh3d, 0);
+    }
+
+    #[test]
+    fn test_refinement() {
+        // a thin, tilted rectangle
+        let mut mesh = Mesh3d64::default();
+        mesh.insert_polygon(
+            [(0.0, 0.0), (4.0, 0.0), (4.0, 0.5), (0.0, 0.5)]
+                .map(|(x, y)| VertexPayloadPNU::from_pos(Vec3::new(x, y, 0.0))),
+        );
+        mesh.rotate(&NdRotate::from_axis_angle(Vec3::x_axis(), 0.7));
+        mesh.translate(&Vec3::new(0.0, 0.0, 1.0));
+        let normal = mesh.faces().next().unwrap().normal(&mesh).normalize();
+
+        let refinement = DelaunayRefinement {
+            max_area: Some(0.05),
+            ..Default::default()
+        };
+        let (is, vs) = mesh.triangulate_refined(&refinement);
+        assert!(vs.len() > 4);
+        let mut total = 0.0;
+        for t in is.chunks(3) {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| *vs[i].pos());
+            let area = (b - a).cross(&(c - a)).dot(&normal) * 0.5;
+            assert!(area > 0.0 && area <= 0.05 + 1e-9);
+            total += area;
+            for (p, q, r) in [(a, b, c), (b, c, a), (c, a, b)] {
+                let angle = (q - p).angle(&(r - p));
+                assert!(angle.to_degrees() >= 20.0 - 1e-6);
+            }
+        }
+        assert!(total.is_about(2.0, 1e-9));
+        // the Steiner points lie in the plane of the face
+        for v in &vs {
+            assert!((v.pos() - Vec3::new(0.0, 0.0, 1.0)).dot(&normal).abs() < 1e-9);
+        }
+
+        // without refinement, only the original vertices are used
+        let none = DelaunayRefinement {
+            min_angle: 0.0,
+            max_area: None,
+            max_steiner_points: 0,
+        };
+        let (is, vs) = mesh.triangulate_refined(&none);
+        assert_eq!(vs.len(), 4);
+        assert_eq!(is.len(), 6);
+    }
+}
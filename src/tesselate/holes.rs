@@ -0,0 +1,144 @@
+use super::{delaunay_triangulation_with_holes, triangulate_face, TesselationMeta};
+use crate::{
+    math::{Scalar, Vector, Vector3D},
+    mesh::{Face3d, FaceBasics, MeshBasics, MeshType3D, Triangulation, VertexBasics},
+    tesselate::TriangulationAlgorithm,
+};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+/// Triangulates a face with the given holes, i.e., faces that lie inside the face in the
+/// same plane, using the specified algorithm. Only the delaunay triangulation supports
+/// holes, so it is used for all faces with holes.
+pub fn triangulate_face_with_holes<T: MeshType3D>(
+    face: &T::Face,
+    holes: &[&T::Face],
+    mesh: &T::Mesh,
+    tri: &mut Triangulation<T::V>,
+    algorithm: TriangulationAlgorithm,
+    meta: &mut TesselationMeta<T::V>,
+) {
+    if holes.is_empty() {
+        triangulate_face::<T>(face, mesh, tri, algorithm, meta);
+    } else {
+        delaunay_triangulation_with_holes::<T>(face, holes, mesh, tri);
+    }
+}
+
+/// Whether `p` lies inside the polygon when projected onto the plane with the given normal.
+fn contains_point<V: Vector3D>(polygon: &[V], normal: V, p: V) -> bool {
+    // drop the dominant axis of the normal
+    let (ax, ay, az) = (normal.x().abs(), normal.y().abs(), normal.z().abs());
+    let project = |v: V| {
+        if ax >= ay && ax >= az {
+            (v.y(), v.z())
+        } else if ay >= az {
+            (v.z(), v.x())
+        } else {
+            (v.x(), v.y())
+        }
+    };
+    let (px, py) = project(p);
+    let mut inside = false;
+    for (u, v) in polygon.iter().circular_tuple_windows() {
+        let ((ux, uy), (vx, vy)) = (project(*u), project(*v));
+        if (uy > py) != (vy > py) && px < ux + (py - uy) / (vy - uy) * (vx - ux) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Finds the faces that are holes of other faces, e.g., the counters of font glyphs where
+/// each contour becomes its own face.
+///
+/// A face is a hole if the smallest face enclosing it lies in the same plane, is oriented
+/// the opposite way, and is not a hole itself. Faces sharing vertices are never nested,
+/// so the two sides of a double-sided polygon aren't holes of each other.
+/// Returns the holes of each face with holes. This is quite slow O(n^2) in the number of
+/// faces.
+pub fn find_holes<T: MeshType3D>(mesh: &T::Mesh) -> HashMap<T::F, Vec<T::F>> {
+    struct Info<T: MeshType3D> {
+        id: T::F,
+        normal: T::Vec,
+        area: T::S,
+        positions: Vec<T::Vec>,
+        vertices: HashSet<T::V>,
+    }
+
+    let mut faces = mesh
+        .faces()
+        .filter(|f| f.num_vertices(mesh) >= 3)
+        .map(|f| {
+            let normal = Face3d::normal(f, mesh);
+            Info::<T> {
+                id: f.id(),
+                normal: normal.normalize(),
+                area: normal.length(),
+                positions: f.vertices(mesh).map(|v| v.pos()).collect(),
+                vertices: f.vertex_ids(mesh).collect(),
+            }
+        })
+        .filter(|f| f.area > T::S::ZERO)
+        .collect_vec();
+    faces.sort_by(|a, b| b.area.partial_cmp(&a.area).unwrap());
+
+    let eps = T::S::EPS.sqrt();
+    let mut is_hole = vec![false; faces.len()];
+    let mut holes: HashMap<T::F, Vec<T::F>> = HashMap::new();
+    for i in 0..faces.len() {
+        let f = &faces[i];
+        let p = f.positions[0];
+        // faces are sorted by area, so the last enclosing face is the smallest one
+        let Some(parent) = (0..i).rev().find(|j| {
+            let g = &faces[*j];
+            let scale = g.area.sqrt();
+            g.normal.dot(&f.normal).abs() >= T::S::ONE - eps
+                && (p - g.positions[0]).dot(&g.normal).abs() <= eps * scale
+                && f.vertices.is_disjoint(&g.vertices)
+                && contains_point(&g.positions, g.normal, p)
+        }) else {
+            continue;
+        };
+        if faces[parent].normal.dot(&f.normal).is_negative() && !is_hole[parent] {
+            is_hole[i] = true;
+            holes.entry(faces[parent].id).or_default().push(f.id);
+        }
+    }
+    holes
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_find_holes() {
+        let mut mesh = Mesh3d64::default();
+        let mut square = |r: f64, ccw: bool| {
+            let mut ps = [(-r, -r), (r, -r), (r, r), (-r, r)];
+            if !ccw {
+                ps.reverse();
+            }
+            mesh.insert_polygon(
+                ps.iter()
+                    .map(|(x, y)| VertexPayloadPNU::from_pos(Vec3::new(*x, *y, 1.0))),
+            );
+        };
+        // an outline with a hole containing an island
+        square(3.0, true);
+        square(2.0, false);
+        square(1.0, true);
+        let ids = mesh.face_ids().collect::<Vec<_>>();
+        let holes = find_holes::<MeshType3d64PNU>(&mesh);
+        assert_eq!(holes.len(), 1);
+        assert_eq!(holes[&ids[0]], vec![ids[1]]);
+
+        let (is, _) = mesh.triangulate_with_holes(
+            TriangulationAlgorithm::Auto,
+            &mut TesselationMeta::default(),
+        );
+        assert_eq!(is.len(), 3 * (8 + 2 - 2 + 2));
+    }
+}
@@ -2,14 +2,15 @@
 use std::time::Instant;
 
 use crate::{
-    math::{IndexType, Polygon, Scalar, Vector2D},
+    math::{IndexType, LineSegment2D, Polygon, Scalar, Vector2D},
     mesh::{Face3d, FaceBasics, IndexedVertex2D, MeshType3D, Triangulation},
     tesselate::try_min_weight_small,
 };
 
 /// The [min-weight triangulation problem](https://en.wikipedia.org/wiki/Minimum-weight_triangulation)
 /// is, in general, NP-hard. However, for polygons without interior points we can
-/// achieve it in O(n^3) time using dynamic programming.
+/// achieve it in O(n^3) time and O(n^2) memory using the dynamic programming algorithm
+/// of Klincsek, which is fast enough for polygons with a few hundred vertices.
 pub fn minweight_dynamic<T: MeshType3D>(
     face: &T::Face,
     mesh: &T::Mesh,
@@ -159,6 +160,11 @@ fn fill_m<V: IndexType, Vec2: Vector2D>(
             let j = i + l;
 
             let ij = m.index(i, j);
+            if !valid_diagonal[ij] {
+                // sub-polygons outside of the polygon are never used
+                m[ij] = Vec2::S::INFINITY;
+                continue;
+            }
             let mut mij = Vec2::S::INFINITY;
             let mut sij = IndexType::max();
             for k in (i + 1)..j {
@@ -187,6 +193,9 @@ fn fill_m<V: IndexType, Vec2: Vector2D>(
     }
 }
 
+/// Finds the diagonals of the polygon that lie inside of it in O(n^3) time with small
+/// constants: a diagonal must start inside the cones of the corners at both ends and
+/// must not cross any edge, which is only tested exactly if the bounding boxes overlap.
 // TODO: find_valid_diagonals should use a O(n) algorithm instead of O(n^2) (each called n times)
 // See: https://arxiv.org/pdf/1403.3905
 fn find_valid_diagonals<V: IndexType, Vec2: Vector2D, Poly: Polygon<Vec2>>(
@@ -195,10 +204,63 @@ fn find_valid_diagonals<V: IndexType, Vec2: Vector2D, Poly: Polygon<Vec2>>(
 ) -> TriangularStore<bool> {
     let mut valid_diagonal = TriangularStore::<bool>::new(n, true);
     let poly = Poly::from_iter(vs.iter().map(|v| v.vec));
+    let ps = poly.points();
+    let ccw = poly.is_ccw();
+    let left = |a: Vec2, b: Vec2, c: Vec2| {
+        let d = (b - a).perp_dot(&(c - a));
+        if ccw {
+            d > Vec2::S::ZERO
+        } else {
+            d < Vec2::S::ZERO
+        }
+    };
+    // whether the diagonal from i to j starts into the interior of the polygon at i
+    let in_cone = |i: usize, j: usize| {
+        let (prev, p, next) = (ps[(i + n - 1) % n], ps[i], ps[(i + 1) % n]);
+        if !left(p, prev, next) {
+            left(p, ps[j], prev) && left(ps[j], p, next)
+        } else {
+            left(ps[j], p, next) || left(p, ps[j], prev)
+        }
+    };
+    let bounds = |a: Vec2, b: Vec2| {
+        (
+            a.x().min(b.x()),
+            a.x().max(b.x()),
+            a.y().min(b.y()),
+            a.y().max(b.y()),
+        )
+    };
+    let edge_bounds = (0..n)
+        .map(|k| bounds(ps[k], ps[(k + 1) % n]))
+        .collect::<Vec<_>>();
+
     for i in 0..n {
         for j in (i + 2)..n {
-            let res = poly.valid_diagonal(i, j);
-            valid_diagonal[(i, j)] = res;
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            if !in_cone(i, j) || !in_cone(j, i) {
+                valid_diagonal[(i, j)] = false;
+                continue;
+            }
+            let (x0, x1, y0, y1) = bounds(ps[i], ps[j]);
+            let diagonal = LineSegment2D::new(ps[i], ps[j]);
+            valid_diagonal[(i, j)] = (0..n).all(|start| {
+                let end = (start + 1) % n;
+                // ignore edges starting or ending at i or j
+                if start == i || start == j || end == i || end == j {
+                    return true;
+                }
+                let (ex0, ex1, ey0, ey1) = edge_bounds[start];
+                let eps = Vec2::S::EPS;
+                if ex0 > x1 + eps || ex1 < x0 - eps || ey0 > y1 + eps || ey1 < y0 - eps {
+                    return true;
+                }
+                diagonal
+                    .intersect_line(&LineSegment2D::new(ps[start], ps[end]), eps, eps)
+                    .is_none()
+            });
         }
     }
 
@@ -539,3 +601,40 @@ mod tests {
     }
 }
 */
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_minweight_dynamic() {
+        // a wobbly star with many reflex corners
+        let n = 200;
+        let mesh = Mesh3d64::polygon((0..n).map(|i| {
+            let a = i as f64 / n as f64 * std::f64::consts::TAU;
+            let r = 1.0 + 0.5 * (i % 3) as f64 + 0.2 * (a * 5.0).sin();
+            VertexPayloadPNU::from_pos(Vec3::new(a.cos() * r, a.sin() * r, 0.0))
+        }));
+        let face = mesh.faces().next().unwrap();
+        let vec2s = face.vec2s(&mesh);
+        let vec_hm: HashMap<usize, Vec2<f64>> = vec2s.iter().map(|v| (v.index, v.vec)).collect();
+        let weight = |algorithm| {
+            let mut indices = Vec::new();
+            let mut tri = Triangulation::new(&mut indices);
+            triangulate_face::<MeshType3d64PNU>(
+                face,
+                &mesh,
+                &mut tri,
+                algorithm,
+                &mut Default::default(),
+            );
+            tri.verify_full::<Vec2<f64>, Polygon2d<f64>>(&vec2s);
+            tri.total_edge_weight(&vec_hm)
+        };
+
+        let optimal = weight(TriangulationAlgorithm::MinWeight);
+        assert!(optimal <= weight(TriangulationAlgorithm::Delaunay) + 1e-9);
+    }
+}
@@ -2,26 +2,35 @@
 
 // TODO: move this whole module to a separate crate!
 
+mod budget;
+mod cache;
 mod convex;
 mod delaunay;
 mod ear_clipping;
 mod fixed_n;
+mod holes;
 mod min_weight_dynamic;
 mod min_weight_greedy;
 mod sweep;
+mod validate;
 
+pub use budget::*;
+pub use cache::*;
 pub use convex::*;
 pub use delaunay::*;
 pub use ear_clipping::*;
 pub use fixed_n::*;
+pub use holes::*;
 pub use min_weight_dynamic::*;
 pub use min_weight_greedy::*;
 pub use sweep::*;
+pub use validate::*;
 
 use crate::{
     math::IndexType,
-    mesh::{FaceBasics, MeshType3D, Triangulation},
+    mesh::{optimize_vertex_cache, FaceBasics, MeshType3D, Triangulation},
 };
+use std::time::Duration;
 
 /// The algorithm to use for triangulating a face.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -42,19 +51,21 @@ pub enum TriangulationAlgorithm {
     /// The sweep-line algorithm, but with a delaunay triangulation running on each monotone sub-polygon.
     SweepDelaunay,
 
-    /// Slow, but large flat surfaces might render faster. Currently uses [Spade](https://github.com/Stoeoef/spade). TODO: allow Delaunay refinements! Runs in O(n log n) time. TODO: Isn't constrained delaunay O(n^2)?
+    /// Slow, but large flat surfaces might render faster. Uses a built-in constrained delaunay triangulation. Use `Triangulateable::triangulate_refined` to insert Steiner points for well-shaped triangles. Runs in expected O(n log n) time for typical inputs; inserting the constraints can take O(n^2) time in the worst case.
     Delaunay,
 
-    /// Same output as Delaunay, but without external dependencies and using a very slow edge-flipping algorithm. Runs in O(n^3) time.
+    /// Same output as Delaunay, but using a very slow edge-flipping algorithm. Runs in O(n^3) time.
     EdgeFlip,
 
-    /// Minimizes the overall edge length of the triangulation. Very slow, but produces the theoretically fastest rendering triangulations for large flat surfaces. Runs in O(2^n) time.
+    /// Minimizes the overall edge length of the triangulation. Slow, but produces the theoretically fastest rendering triangulations for large flat surfaces. Runs in O(n^3) time, so it is feasible for polygons with a few hundred vertices.
     MinWeight,
 
     /// Heuristic algorithm that tries to find a compromise between the speed of `Sweep` and the quality of `EdgeMin`.
     Heuristic,
 
     /// Automatically choose the "best" algorithm based on the input, i.e., with the given ratio of numerical stability and performance.
+    /// If `TesselationOptions::time_budget` is set, the whole mesh is triangulated quickly first
+    /// and the largest faces are upgraded while the budget allows, see `triangulate_budgeted`.
     #[default]
     Auto,
 }
@@ -66,6 +77,40 @@ pub struct TesselationMeta<V: IndexType> {
     pub sweep: sweep::SweepMeta<V>,
 }
 
+impl<V: IndexType> TesselationMeta<V> {
+    /// Creates meta information that records the vertex types of the sweep algorithm,
+    /// e.g., to visualize them with `show_tesselation_meta`.
+    pub fn with_sweep_debug() -> Self {
+        Self {
+            sweep: sweep::SweepMeta::with_debug(),
+        }
+    }
+}
+
+/// Options for scheduling and post-processing the triangulation of whole meshes,
+/// see `Triangulateable::triangulate_with_options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TesselationOptions {
+    /// Whether to reorder the triangles of the whole mesh with `optimize_vertex_cache`
+    /// after triangulating it, e.g., for large meshes that are rendered every frame.
+    pub optimize_vertex_cache: bool,
+
+    /// The time `TriangulationAlgorithm::Auto` may spend on upgrading faces to
+    /// high-quality triangulations after triangulating the mesh with fast algorithms,
+    /// e.g., to keep interactive editing of big meshes responsive.
+    pub time_budget: Option<Duration>,
+}
+
+impl TesselationOptions {
+    /// Applies the post-processing to the index buffer of a triangulation
+    /// with `num_vertices` vertices.
+    pub fn post_process<V: IndexType>(&self, indices: &mut [V], num_vertices: usize) {
+        if self.optimize_vertex_cache {
+            optimize_vertex_cache(indices, num_vertices);
+        }
+    }
+}
+
 /// Triangulate a face using the specified algorithm.
 pub fn triangulate_face<T: MeshType3D>(
     face: &T::Face,
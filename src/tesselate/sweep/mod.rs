@@ -19,27 +19,37 @@ use crate::{
 /// Meta information for debuggin the sweep algorithm
 #[derive(Debug, Clone, PartialEq)]
 pub struct SweepMeta<V: IndexType> {
-    #[cfg(feature = "sweep_debug")]
+    /// Whether to record the debug information. Enabled by default with the
+    /// `sweep_debug` feature. When disabled, nothing is recorded.
+    pub enabled: bool,
+
     /// The type of the vertex in the reflex chain
     pub vertex_type: Vec<(V, VertexType)>,
-
-    phantom: std::marker::PhantomData<V>,
 }
 
 impl<V: IndexType> Default for SweepMeta<V> {
     fn default() -> Self {
         SweepMeta {
-            #[cfg(feature = "sweep_debug")]
+            enabled: cfg!(feature = "sweep_debug"),
             vertex_type: Vec::new(),
-            phantom: std::marker::PhantomData,
         }
     }
 }
 
 impl<V: IndexType> SweepMeta<V> {
+    /// Creates meta information that records the debug information.
+    pub fn with_debug() -> Self {
+        SweepMeta {
+            enabled: true,
+            vertex_type: Vec::new(),
+        }
+    }
+
     /// Update the type of a vertex
-    #[cfg(feature = "sweep_debug")]
     pub fn update_type(&mut self, i: V, t: VertexType) {
+        if !self.enabled {
+            return;
+        }
         // TODO: Not efficient
         for (j, ty) in self.vertex_type.iter_mut() {
             if *j == i {
@@ -45,8 +45,7 @@ pub fn sweep_line_triangulation<MT: MonotoneTriangulator>(
         lt
     );
 
-    #[cfg(feature = "sweep_debug")]
-    {
+    if meta.enabled {
         meta.vertex_type = event_queue
             .iter()
             .map(|e| (vec2s[e.here].index, e.vertex_type))
@@ -182,7 +181,7 @@ impl<'a, 'b, MT: MonotoneTriangulator> SweepContext<'a, 'b, MT> {
     fn start_or_split(
         &mut self,
         event: &EventPoint<MT::Vec2>,
-        _meta: &mut SweepMeta<MT::V>,
+        meta: &mut SweepMeta<MT::V>,
     ) -> bool {
         /*
         let Some(next) = queue.get(event_i + 1) else {
@@ -203,8 +202,7 @@ impl<'a, 'b, MT: MonotoneTriangulator> SweepContext<'a, 'b, MT> {
             println!("Reinterpret as split");
 
             // update the meta info
-            #[cfg(feature = "sweep_debug")]
-            _meta.update_type(self.vec2s[event.here].index, VertexType::SplitLate);
+            meta.update_type(self.vec2s[event.here].index, VertexType::SplitLate);
         } else {
             #[cfg(feature = "sweep_debug_print")]
             println!("Reinterpret as start");
@@ -213,8 +211,7 @@ impl<'a, 'b, MT: MonotoneTriangulator> SweepContext<'a, 'b, MT> {
             self.start(event);
 
             // update the meta info
-            #[cfg(feature = "sweep_debug")]
-            _meta.update_type(self.vec2s[event.here].index, VertexType::StartLate);
+            meta.update_type(self.vec2s[event.here].index, VertexType::StartLate);
         }
 
         return true;
@@ -304,7 +301,6 @@ impl<'a, 'b, MT: MonotoneTriangulator> SweepContext<'a, 'b, MT> {
                 if interval.is_end() {
                     #[cfg(feature = "sweep_debug_print")]
                     println!("Reinterpret as end");
-                    #[cfg(feature = "sweep_debug")]
                     meta.update_type(self.vec2s[event.here].index, VertexType::EndLate);
                     // re-insert is faster than peeking since late vertex classification is rare
                     self.sls.insert(interval, self.vec2s);
@@ -314,7 +310,6 @@ impl<'a, 'b, MT: MonotoneTriangulator> SweepContext<'a, 'b, MT> {
                 if self.sls.peek_right(event.here).is_some() {
                     #[cfg(feature = "sweep_debug_print")]
                     println!("Reinterpret as merge");
-                    #[cfg(feature = "sweep_debug")]
                     meta.update_type(self.vec2s[event.here].index, VertexType::MergeLate);
                     self.sls.insert(interval, self.vec2s);
                     self.merge(event);
@@ -350,7 +345,6 @@ impl<'a, 'b, MT: MonotoneTriangulator> SweepContext<'a, 'b, MT> {
                 if interval.is_end() {
                     #[cfg(feature = "sweep_debug_print")]
                     println!("Reinterpret as end");
-                    #[cfg(feature = "sweep_debug")]
                     meta.update_type(self.vec2s[event.here].index, VertexType::EndLate);
                     // re-insert is faster than peeking since late vertex classification is rare
                     self.sls.insert(interval, self.vec2s);
@@ -360,7 +354,6 @@ impl<'a, 'b, MT: MonotoneTriangulator> SweepContext<'a, 'b, MT> {
                 if self.sls.peek_left(event.here).is_some() {
                     #[cfg(feature = "sweep_debug_print")]
                     println!("Reinterpret as merge");
-                    #[cfg(feature = "sweep_debug")]
                     meta.update_type(self.vec2s[event.here].index, VertexType::MergeLate);
                     self.sls.insert(interval, self.vec2s);
                     self.merge(event);
@@ -545,6 +538,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sweep_meta() {
+        let vec2s: Vec<_> = generate_zigzag::<Vec2<f64>>(100)
+            .enumerate()
+            .map(|(i, v)| IndexedVertex2D::new(v, i))
+            .collect();
+        let run = |mut meta: SweepMeta<usize>| {
+            let mut indices = Vec::new();
+            let mut tri = Triangulation::new(&mut indices);
+            sweep_line_triangulation::<LinearMonoTriangulator<usize, Vec2<f64>>>(
+                &mut tri, &vec2s, &mut meta,
+            );
+            meta
+        };
+
+        let meta = run(SweepMeta {
+            enabled: false,
+            ..Default::default()
+        });
+        assert!(meta.vertex_type.is_empty());
+
+        let meta = run(SweepMeta::with_debug());
+        assert_eq!(meta.vertex_type.len(), vec2s.len());
+        assert!(meta
+            .vertex_type
+            .iter()
+            .any(|(_, t)| *t == VertexType::Split || *t == VertexType::SplitLate));
+    }
+
     #[test]
     fn sweep_circle() {
         verify_triangulations(
@@ -0,0 +1,155 @@
+use super::TriangulationAlgorithm;
+use crate::{
+    math::{IndexType, Scalar, ScalarIteratorExt, Vector, Vector3DIteratorExt},
+    mesh::{Face3d, FaceBasics, MeshType3D, VertexBasics},
+};
+use itertools::Itertools;
+use std::fmt::Display;
+
+/// A reason why a face can't be triangulated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TesselationError<F: IndexType> {
+    /// The face has less than 3 vertices.
+    TooFewVertices {
+        /// The id of the face.
+        face: F,
+        /// The number of vertices of the face.
+        vertices: usize,
+    },
+
+    /// The face has a vertex with an infinite or NaN coordinate.
+    NonFinite {
+        /// The id of the face.
+        face: F,
+    },
+
+    /// The face has no area, e.g., all of its vertices are collinear, so it has no
+    /// well-defined winding.
+    Degenerate {
+        /// The id of the face.
+        face: F,
+    },
+
+    /// The vertices of the face don't lie in a plane.
+    NotPlanar {
+        /// The id of the face.
+        face: F,
+    },
+
+    /// The face has several vertices at the same position.
+    DuplicateVertices {
+        /// The id of the face.
+        face: F,
+    },
+
+    /// The outline of the face intersects itself.
+    SelfIntersecting {
+        /// The id of the face.
+        face: F,
+    },
+
+    /// The algorithm is not implemented yet.
+    UnsupportedAlgorithm(TriangulationAlgorithm),
+}
+
+impl<F: IndexType> TesselationError<F> {
+    /// Returns the id of the face that caused the error if there is one.
+    pub fn face(&self) -> Option<F> {
+        match self {
+            TesselationError::TooFewVertices { face, .. }
+            | TesselationError::NonFinite { face }
+            | TesselationError::Degenerate { face }
+            | TesselationError::NotPlanar { face }
+            | TesselationError::DuplicateVertices { face }
+            | TesselationError::SelfIntersecting { face } => Some(*face),
+            TesselationError::UnsupportedAlgorithm(_) => None,
+        }
+    }
+}
+
+impl<F: IndexType> Display for TesselationError<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TesselationError::TooFewVertices { face, vertices } => write!(
+                f,
+                "face {} has {} vertices, but at least 3 are required",
+                face, vertices
+            ),
+            TesselationError::NonFinite { face } => {
+                write!(f, "face {} has a vertex with a non-finite coordinate", face)
+            }
+            TesselationError::Degenerate { face } => write!(f, "face {} has no area", face),
+            TesselationError::NotPlanar { face } => write!(f, "face {} is not planar", face),
+            TesselationError::DuplicateVertices { face } => {
+                write!(f, "face {} has several vertices at the same position", face)
+            }
+            TesselationError::SelfIntersecting { face } => {
+                write!(f, "face {} intersects itself", face)
+            }
+            TesselationError::UnsupportedAlgorithm(algorithm) => {
+                write!(f, "{:?} is not implemented yet", algorithm)
+            }
+        }
+    }
+}
+
+impl<F: IndexType> std::error::Error for TesselationError<F> {}
+
+/// Checks whether the face can be triangulated by all implemented algorithms, i.e., whether
+/// it is a simple, planar polygon with at least 3 distinct vertices.
+/// This is quite slow O(n^2) because of the test for self-intersections.
+pub fn validate_face<T: MeshType3D>(
+    face: &T::Face,
+    mesh: &T::Mesh,
+) -> Result<(), TesselationError<T::F>> {
+    let id = face.id();
+    let n = face.num_vertices(mesh);
+    if n < 3 {
+        return Err(TesselationError::TooFewVertices {
+            face: id,
+            vertices: n,
+        });
+    }
+
+    let positions = face.vertices(mesh).map(|v| v.pos()).collect_vec();
+    if positions
+        .iter()
+        .any(|p| !(p.x().is_finite() && p.y().is_finite() && p.z().is_finite()))
+    {
+        return Err(TesselationError::NonFinite { face: id });
+    }
+
+    let perimeter = positions
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| a.distance(b))
+        .stable_sum();
+    if positions.iter().copied().normal().length() <= T::S::EPS * perimeter * perimeter {
+        return Err(TesselationError::Degenerate { face: id });
+    }
+
+    if !face.may_be_curved() && !face.is_planar2(mesh) {
+        return Err(TesselationError::NotPlanar { face: id });
+    }
+
+    let mut vec2s = face.vertices_2d(mesh).map(|(p, _)| p).collect_vec();
+    vec2s.sort_by(|a, b| {
+        a.x()
+            .partial_cmp(&b.x())
+            .unwrap()
+            .then(a.y().partial_cmp(&b.y()).unwrap())
+    });
+    if vec2s
+        .iter()
+        .tuple_windows()
+        .any(|(a, b)| a.x() == b.x() && a.y() == b.y())
+    {
+        return Err(TesselationError::DuplicateVertices { face: id });
+    }
+
+    if face.has_self_intersections(mesh) {
+        return Err(TesselationError::SelfIntersecting { face: id });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,471 @@
+//! A module for named, typed attribute channels on mesh elements.
+
+use super::{ColorRamp, Rgba};
+use crate::math::IndexType;
+use std::{any::Any, collections::HashMap};
+
+/// The kind of mesh element an attribute channel is attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Element {
+    /// The channel stores one value per vertex.
+    Vertex,
+
+    /// The channel stores one value per half-edge.
+    Edge,
+
+    /// The channel stores one value per face.
+    Face,
+}
+
+/// A function blending two attribute values `a` and `b` with the weight `t` of `b`.
+pub type Interpolation<A> = fn(&A, &A, f64) -> A;
+
+/// A typed attribute channel storing one value per element.
+///
+/// Elements without an explicitly set value read the default value of the channel.
+#[derive(Debug, Clone)]
+pub struct AttributeChannel<A> {
+    element: Element,
+    default: A,
+    values: HashMap<usize, A>,
+    interpolate: Option<Interpolation<A>>,
+}
+
+impl<A: Clone> AttributeChannel<A> {
+    /// Creates an empty channel for the given kind of element.
+    pub fn new(element: Element, default: A) -> Self {
+        Self {
+            element,
+            default,
+            values: HashMap::new(),
+            interpolate: None,
+        }
+    }
+
+    /// Sets the function used to blend the values of two vertices when a
+    /// builder operation inserts a new vertex between them.
+    pub fn with_interpolation(&mut self, interpolate: Interpolation<A>) -> &mut Self {
+        self.interpolate = Some(interpolate);
+        self
+    }
+
+    /// Returns the kind of element the channel is attached to.
+    pub fn element(&self) -> Element {
+        self.element
+    }
+
+    /// Returns the default value of the channel.
+    pub fn default_value(&self) -> &A {
+        &self.default
+    }
+
+    /// Returns the value of the element or the default value if it wasn't set.
+    pub fn get<I: IndexType>(&self, id: I) -> &A {
+        self.values.get(&id.index()).unwrap_or(&self.default)
+    }
+
+    /// Sets the value of the element.
+    pub fn set<I: IndexType>(&mut self, id: I, value: A) -> &mut Self {
+        self.values.insert(id.index(), value);
+        self
+    }
+
+    /// Blends the value of the element towards `value` with the weight `t` of `value`.
+    ///
+    /// Uses the interpolation of the channel. Channels without interpolation, e.g.,
+    /// selection masks, are set to `value` if `t` is at least one half.
+    pub fn blend<I: IndexType>(&mut self, id: I, value: &A, t: f64) -> &mut Self {
+        let blended = match self.interpolate {
+            Some(interpolate) => interpolate(self.get(id), value, t),
+            None if t >= 0.5 => value.clone(),
+            None => return self,
+        };
+        self.set(id, blended)
+    }
+
+    /// Resets the element to the default value and returns the previous value if it was set.
+    pub fn reset<I: IndexType>(&mut self, id: I) -> Option<A> {
+        self.values.remove(&id.index())
+    }
+
+    /// Returns the number of elements with an explicitly set value.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether no element has an explicitly set value.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// The type-erased operations the builder needs to keep the channels consistent.
+trait AnyChannel: Any {
+    fn element(&self) -> Element;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn clone_box(&self) -> Box<dyn AnyChannel>;
+    fn remove(&mut self, id: usize);
+    fn clear(&mut self);
+    fn boxed(&self, id: usize) -> Option<Box<dyn Any>>;
+    fn restore(&mut self, id: usize, value: &dyn Any);
+    fn copy(&mut self, from: usize, to: usize);
+    fn interpolate(&mut self, a: usize, b: usize, t: f64, to: usize);
+    fn remap(&mut self, map: &[Option<usize>]);
+}
+
+impl<A: Clone + 'static> AnyChannel for AttributeChannel<A> {
+    fn element(&self) -> Element {
+        self.element
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn AnyChannel> {
+        Box::new(self.clone())
+    }
+
+    fn remove(&mut self, id: usize) {
+        self.values.remove(&id);
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    fn boxed(&self, id: usize) -> Option<Box<dyn Any>> {
+        self.values
+            .get(&id)
+            .map(|v| Box::new(v.clone()) as Box<dyn Any>)
+    }
+
+    fn restore(&mut self, id: usize, value: &dyn Any) {
+        if let Some(v) = value.downcast_ref::<A>() {
+            self.values.insert(id, v.clone());
+        }
+    }
+
+    fn copy(&mut self, from: usize, to: usize) {
+        match self.values.get(&from) {
+            Some(v) => {
+                let v = v.clone();
+                self.values.insert(to, v);
+            }
+            None => {
+                self.values.remove(&to);
+            }
+        }
+    }
+
+    fn interpolate(&mut self, a: usize, b: usize, t: f64, to: usize) {
+        let Some(interpolate) = self.interpolate else {
+            self.values.remove(&to);
+            return;
+        };
+        if !self.values.contains_key(&a) && !self.values.contains_key(&b) {
+            self.values.remove(&to);
+            return;
+        }
+        let v = interpolate(self.get(a), self.get(b), t);
+        self.values.insert(to, v);
+    }
+
+    fn remap(&mut self, map: &[Option<usize>]) {
+        self.values = std::mem::take(&mut self.values)
+            .into_iter()
+            .filter_map(|(id, v)| map.get(id).copied().flatten().map(|id| (id, v)))
+            .collect();
+    }
+}
+
+/// The values of all channels of a single element, e.g., to restore them
+/// after the element was removed and re-created by a builder operation.
+pub(crate) struct AttributeValues(Vec<(String, Box<dyn Any>)>);
+
+/// A registry of named, typed attribute channels attached to the vertices,
+/// half-edges, or faces of a mesh. This allows attaching arbitrary data to mesh
+/// elements without defining a new `MeshType`.
+///
+/// The builder operations of the mesh keep the channels consistent:
+/// - Values of removed elements are dropped, so re-used ids start with the default value.
+/// - Vertices inserted into an edge are interpolated between the endpoints of the edge
+///   using the interpolation function of the channel. Without it, they read the default value.
+/// - Half-edges created by splitting a half-edge inherit its value.
+/// - Faces created by splitting a face inherit its value.
+/// - All other new elements read the default value.
+#[derive(Default)]
+pub struct Attributes {
+    channels: HashMap<String, Box<dyn AnyChannel>>,
+}
+
+impl Clone for Attributes {
+    fn clone(&self) -> Self {
+        Self {
+            channels: self
+                .channels
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone_box()))
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Attributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.channels.iter().map(|(k, v)| (k, v.element())))
+            .finish()
+    }
+}
+
+impl Attributes {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a channel with the given name and returns it. Values default to `A::default()`.
+    /// Replaces an existing channel with the same name.
+    pub fn add<A: Clone + Default + 'static>(
+        &mut self,
+        name: &str,
+        element: Element,
+    ) -> &mut AttributeChannel<A> {
+        self.add_with_default(name, element, A::default())
+    }
+
+    /// Like `add`, but with a custom default value.
+    pub fn add_with_default<A: Clone + 'static>(
+        &mut self,
+        name: &str,
+        element: Element,
+        default: A,
+    ) -> &mut AttributeChannel<A> {
+        self.channels.insert(
+            name.to_string(),
+            Box::new(AttributeChannel::new(element, default)),
+        );
+        self.get_mut(name).unwrap()
+    }
+
+    /// Returns the channel with the given name if it exists and has the type `A`.
+    pub fn get<A: 'static>(&self, name: &str) -> Option<&AttributeChannel<A>> {
+        self.channels.get(name)?.as_any().downcast_ref()
+    }
+
+    /// Returns the channel with the given name mutably if it exists and has the type `A`.
+    pub fn get_mut<A: 'static>(&mut self, name: &str) -> Option<&mut AttributeChannel<A>> {
+        self.channels.get_mut(name)?.as_any_mut().downcast_mut()
+    }
+
+    /// Returns whether a channel with the given name exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.channels.contains_key(name)
+    }
+
+    /// Removes the channel with the given name. Returns whether it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.channels.remove(name).is_some()
+    }
+
+    /// Maps the values of `ids` in the scalar channel `source` through the `ramp` into a
+    /// new channel of RGBA colors named `target`, e.g., to visualize curvature or weights
+    /// as vertex colors. Replaces an existing channel named `target`.
+    ///
+    /// `range` is mapped to the ends of the ramp. Without a `range`, the smallest and
+    /// largest value of `ids` are used. Elements without a color read the start of the ramp.
+    /// Returns `None` if there is no channel `source` with values of type `A`.
+    pub fn colorize<A: Clone + Into<f64> + 'static, I: IndexType>(
+        &mut self,
+        source: &str,
+        target: &str,
+        ids: impl IntoIterator<Item = I>,
+        ramp: &ColorRamp,
+        range: Option<(f64, f64)>,
+    ) -> Option<&mut AttributeChannel<Rgba>> {
+        let channel = self.get::<A>(source)?;
+        let element = channel.element();
+        let (ids, values): (Vec<I>, Vec<f64>) = ids
+            .into_iter()
+            .map(|id| (id, channel.get(id).clone().into()))
+            .unzip();
+        let colors = ramp.map_all(&values, range);
+        let target = self
+            .add_with_default(target, element, ramp.sample(0.0))
+            .with_interpolation(|a, b, t| std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t as f32));
+        for (id, color) in ids.into_iter().zip(colors) {
+            target.set(id, color);
+        }
+        Some(target)
+    }
+
+    /// Returns the names of all channels.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.channels.keys().map(|k| k.as_str())
+    }
+
+    /// Returns the names of all channels attached to the given kind of element.
+    pub fn names_of(&self, element: Element) -> impl Iterator<Item = &str> {
+        self.channels
+            .iter()
+            .filter(move |(_, c)| c.element() == element)
+            .map(|(k, _)| k.as_str())
+    }
+
+    /// Returns the number of channels.
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns whether there are no channels.
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    fn channels_of(&mut self, element: Element) -> impl Iterator<Item = &mut Box<dyn AnyChannel>> {
+        self.channels
+            .values_mut()
+            .filter(move |c| c.element() == element)
+    }
+
+    /// Drops the values of a removed element.
+    pub(crate) fn remove_element<I: IndexType>(&mut self, element: Element, id: I) {
+        self.channels_of(element).for_each(|c| c.remove(id.index()));
+    }
+
+    /// Drops the values of all elements but keeps the channels.
+    pub(crate) fn clear_values(&mut self) {
+        self.channels.values_mut().for_each(|c| c.clear());
+    }
+
+    /// Copies the values of the element `from` to the element `to`.
+    pub(crate) fn copy_element<I: IndexType>(&mut self, element: Element, from: I, to: I) {
+        self.channels_of(element)
+            .for_each(|c| c.copy(from.index(), to.index()));
+    }
+
+    /// Sets the values of the element `to` by interpolating between `a` and `b`.
+    pub(crate) fn interpolate_element<I: IndexType>(
+        &mut self,
+        element: Element,
+        a: I,
+        b: I,
+        t: f64,
+        to: I,
+    ) {
+        self.channels_of(element)
+            .for_each(|c| c.interpolate(a.index(), b.index(), t, to.index()));
+    }
+
+    /// Moves the values of the elements to the new indices given by a map returned
+    /// by `DeletableVector::compact_map`.
+    pub(crate) fn remap_elements<I: IndexType>(&mut self, element: Element, map: &[I]) {
+        let map = map
+            .iter()
+            .map(|i| (*i != IndexType::max()).then(|| i.index()))
+            .collect::<Vec<_>>();
+        self.channels_of(element).for_each(|c| c.remap(&map));
+    }
+
+    /// Returns the explicitly set values of the element.
+    pub(crate) fn element_values<I: IndexType>(&self, element: Element, id: I) -> AttributeValues {
+        AttributeValues(
+            self.channels
+                .iter()
+                .filter(|(_, c)| c.element() == element)
+                .filter_map(|(k, c)| c.boxed(id.index()).map(|v| (k.clone(), v)))
+                .collect(),
+        )
+    }
+
+    /// Restores the values of the element previously returned by `element_values`.
+    pub(crate) fn restore_element<I: IndexType>(&mut self, id: I, values: &AttributeValues) {
+        for (name, value) in values.0.iter() {
+            if let Some(c) = self.channels.get_mut(name) {
+                c.restore(id.index(), value.as_ref());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nalgebra")]
+mod tests {
+    use crate::{extensions::nalgebra::*, prelude::*};
+
+    #[test]
+    fn test_attributes() {
+        let mut mesh = Mesh3d64::cube(1.0);
+        mesh.attributes_mut()
+            .add::<f32>("temperature", Element::Vertex)
+            .with_interpolation(|a, b, t| a + (b - a) * t as f32);
+        mesh.attributes_mut()
+            .add_with_default::<String>("material", Element::Face, "stone".into());
+        assert!(mesh.attributes().get::<f64>("temperature").is_none());
+
+        // vertices inserted into an edge are interpolated
+        let e = mesh.edge_ids().next().unwrap();
+        let (a, b) = (mesh.edge(e).origin_id(), mesh.edge(e).target_id(&mesh));
+        let temperature = mesh.attributes_mut().get_mut::<f32>("temperature").unwrap();
+        temperature.set(a, 0.0).set(b, 12.0);
+        let vp = |t: f64| {
+            let p = mesh.vertex(a).pos().lerp(&mesh.vertex(b).pos(), t);
+            (
+                Default::default(),
+                Default::default(),
+                VertexPayloadPNU::from_pos(p),
+            )
+        };
+        let ps = [vp(0.25), vp(0.5), vp(0.75)];
+        mesh.insert_vertices_into_edge(e, ps.into_iter());
+        let temperature = mesh.attributes().get::<f32>("temperature").unwrap();
+        let mut values = mesh
+            .vertex_ids()
+            .filter(|v| *v >= 8)
+            .map(|v| *temperature.get(v))
+            .collect::<Vec<_>>();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(values, vec![3.0, 6.0, 9.0]);
+
+        // split faces inherit the attributes
+        let f = mesh.face_ids().next().unwrap();
+        mesh.attributes_mut()
+            .get_mut::<String>("material")
+            .unwrap()
+            .set(f, "wood".into());
+        let vs = mesh.face(f).vertex_ids(&mesh).collect::<Vec<_>>();
+        let (left, right) = mesh.split_face_default(f, vs[0], vs[2], []);
+        let material = mesh.attributes().get::<String>("material").unwrap();
+        assert_eq!(material.get(left), "wood");
+        assert_eq!(material.get(right), "wood");
+        assert_eq!(material.len(), 2);
+
+        // removed elements drop their values
+        let shared = mesh.shared_edge_id(vs[0], vs[2]).unwrap();
+        let kept = mesh.remove_edge(shared);
+        let material = mesh.attributes().get::<String>("material").unwrap();
+        assert_eq!(material.get(kept), "wood");
+        assert_eq!(material.len(), 1);
+
+        // clones carry their attributes and clearing keeps the channels
+        let mut copy = mesh.clone();
+        assert_eq!(copy.attributes().len(), 2);
+        copy.clear();
+        assert!(copy
+            .attributes()
+            .get::<f32>("temperature")
+            .unwrap()
+            .is_empty());
+        assert!(!mesh
+            .attributes()
+            .get::<f32>("temperature")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+//! Color ramps to visualize scalar attributes, e.g., curvature, occlusion, or painted weights.
+
+/// An RGBA color with components in `[0, 1]`.
+pub type Rgba = [f32; 4];
+
+/// A piecewise linear gradient mapping scalars in `[0, 1]` to colors.
+///
+/// The colors are interpolated component-wise in the color space they are given in.
+/// The presets are given in sRGB, so they are meant for vertex colors and exports
+/// that expect sRGB values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorRamp {
+    stops: Vec<(f64, Rgba)>,
+}
+
+impl ColorRamp {
+    /// Creates a ramp from the given `(position, color)` stops. Values before the first
+    /// or after the last stop get the color of that stop.
+    pub fn new(stops: impl IntoIterator<Item = (f64, Rgba)>) -> Self {
+        let mut stops = stops.into_iter().collect::<Vec<_>>();
+        assert!(!stops.is_empty(), "a color ramp needs at least one stop");
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops }
+    }
+
+    /// Creates a ramp with the colors evenly distributed over `[0, 1]`.
+    pub fn uniform(colors: impl IntoIterator<Item = Rgba>) -> Self {
+        let colors = colors.into_iter().collect::<Vec<_>>();
+        let n = colors.len().max(2) - 1;
+        Self::new(
+            colors
+                .into_iter()
+                .enumerate()
+                .map(|(i, c)| (i as f64 / n as f64, c)),
+        )
+    }
+
+    /// From black to white.
+    pub fn grayscale() -> Self {
+        Self::uniform([[0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]])
+    }
+
+    /// From black over red and yellow to white, e.g., for occlusion or heat.
+    pub fn heat() -> Self {
+        Self::uniform([
+            [0.0, 0.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0, 1.0],
+            [1.0, 1.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0, 1.0],
+        ])
+    }
+
+    /// An approximation of the perceptually uniform viridis ramp from purple to yellow.
+    pub fn viridis() -> Self {
+        Self::uniform([
+            [0.267, 0.005, 0.329, 1.0],
+            [0.231, 0.322, 0.545, 1.0],
+            [0.129, 0.569, 0.549, 1.0],
+            [0.369, 0.788, 0.384, 1.0],
+            [0.992, 0.906, 0.145, 1.0],
+        ])
+    }
+
+    /// A diverging ramp from blue over light gray to red, e.g., for signed curvature.
+    /// Use a range symmetric around zero to map zero to the center.
+    pub fn coolwarm() -> Self {
+        Self::uniform([
+            [0.230, 0.299, 0.754, 1.0],
+            [0.865, 0.865, 0.865, 1.0],
+            [0.706, 0.016, 0.150, 1.0],
+        ])
+    }
+
+    /// Returns the color at `t`.
+    pub fn sample(&self, t: f64) -> Rgba {
+        let first = self.stops[0];
+        if t.is_nan() || t <= first.0 {
+            return first.1;
+        }
+        for w in self.stops.windows(2) {
+            let ((t0, c0), (t1, c1)) = (w[0], w[1]);
+            if t <= t1 {
+                let s = if t1 > t0 {
+                    ((t - t0) / (t1 - t0)) as f32
+                } else {
+                    1.0
+                };
+                return std::array::from_fn(|i| c0[i] + (c1[i] - c0[i]) * s);
+            }
+        }
+        self.stops[self.stops.len() - 1].1
+    }
+
+    /// Returns the color of `value` after mapping `[min, max]` to `[0, 1]`.
+    pub fn map(&self, value: f64, min: f64, max: f64) -> Rgba {
+        if max > min {
+            self.sample((value - min) / (max - min))
+        } else {
+            self.sample(0.5)
+        }
+    }
+
+    /// Returns the colors of all `values` after mapping `range` to `[0, 1]`.
+    /// Without a `range`, the smallest and largest finite value are used.
+    pub fn map_all(&self, values: &[f64], range: Option<(f64, f64)>) -> Vec<Rgba> {
+        let (min, max) = range.unwrap_or_else(|| value_range(values));
+        values.iter().map(|v| self.map(*v, min, max)).collect()
+    }
+}
+
+/// Returns the smallest and largest finite value or `(0, 0)` if there is none.
+pub fn value_range(values: &[f64]) -> (f64, f64) {
+    values
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold(None, |acc: Option<(f64, f64)>, v| match acc {
+            None => Some((*v, *v)),
+            Some((a, b)) => Some((a.min(*v), b.max(*v))),
+        })
+        .unwrap_or((0.0, 0.0))
+}
+
+/// Formats the color as a `#rrggbb` hex string, e.g., for the fill of an svg path.
+/// The alpha channel is ignored.
+pub fn rgba_to_hex(color: Rgba) -> String {
+    let byte = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        byte(color[0]),
+        byte(color[1]),
+        byte(color[2])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Attributes, Element};
+
+    #[test]
+    fn test_color_ramp() {
+        let ramp = ColorRamp::grayscale();
+        assert_eq!(ramp.sample(0.0), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(ramp.sample(0.5), [0.5, 0.5, 0.5, 1.0]);
+        assert_eq!(ramp.sample(2.0), [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(ramp.sample(-1.0), ramp.sample(f64::NAN));
+        assert_eq!(ramp.map(15.0, 10.0, 20.0), [0.5, 0.5, 0.5, 1.0]);
+
+        let heat = ColorRamp::heat();
+        assert_eq!(heat.sample(1.0 / 3.0), [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(rgba_to_hex(heat.sample(2.0 / 3.0)), "#ffff00");
+        assert_eq!(rgba_to_hex(ColorRamp::viridis().sample(0.0)), "#440154");
+
+        // unsorted stops and automatic ranges
+        let ramp = ColorRamp::new([(1.0, [1.0, 0.0, 0.0, 1.0]), (0.0, [0.0, 0.0, 1.0, 1.0])]);
+        let colors = ramp.map_all(&[2.0, 4.0, 3.0, f64::INFINITY], None);
+        assert_eq!(colors[0], [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(colors[1], [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(colors[2], [0.5, 0.0, 0.5, 1.0]);
+        assert_eq!(colors[3], [1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_colorize_attribute() {
+        let mut attributes = Attributes::new();
+        let weights = attributes.add::<f32>("weight", Element::Vertex);
+        weights.set(0usize, 1.0).set(1usize, 3.0).set(2usize, 2.0);
+        attributes.add::<f32>("face", Element::Face);
+
+        let colors = attributes
+            .colorize::<f32, usize>("weight", "color", 0..3, &ColorRamp::grayscale(), None)
+            .unwrap();
+        assert_eq!(colors.element(), Element::Vertex);
+        assert_eq!(*colors.get(1usize), [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(*colors.get(2usize), [0.5, 0.5, 0.5, 1.0]);
+        // elements without a value read the start of the ramp
+        assert_eq!(*colors.get(3usize), [0.0, 0.0, 0.0, 1.0]);
+
+        let ramp = ColorRamp::heat();
+        let colors = attributes
+            .colorize::<f32, usize>("weight", "color", 0..3, &ramp, Some((0.0, 6.0)))
+            .unwrap();
+        assert_eq!(*colors.get(0usize), ramp.sample(1.0 / 6.0));
+        assert!(attributes.get::<Rgba>("color").is_some());
+        assert!(attributes
+            .colorize::<f64, usize>("weight", "color", 0..3, &ramp, None)
+            .is_none());
+    }
+}
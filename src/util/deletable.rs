@@ -1,6 +1,17 @@
 //! A module for soft-deletable elements.
 
 use crate::math::IndexType;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The last generation handed out by `next_generation`.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a new generation that is larger than all generations returned before.
+/// Since the counter is shared by all vectors, generations of different vectors can be
+/// compared with each other.
+fn next_generation() -> u64 {
+    GENERATION.fetch_add(1, Ordering::Relaxed) + 1
+}
 
 /// A trait for soft-deletable elements.
 pub trait Deletable<I> {
@@ -17,11 +28,29 @@ pub trait Deletable<I> {
     fn allocate() -> Self;
 }
 
+/// Maps the index using a map returned by `DeletableVector::compact_map`.
+/// `IndexType::max()` is used for missing elements and is kept.
+pub(crate) fn remap_index<I: IndexType>(map: &[I], index: I) -> I {
+    if index == IndexType::max() {
+        index
+    } else {
+        map[index.index()]
+    }
+}
+
 /// A vector that also keeps track of deleted elements to reallocate them.
+///
+/// Every element remembers the generation of its last change, i.e., the last time it was
+/// accessed mutably, allocated, or deleted, see `changed_since`. Hence, each of these
+/// accesses costs an atomic increment of a counter shared by all vectors, and each element
+/// takes 8 more bytes.
 #[derive(Debug, Clone)]
 pub struct DeletableVector<T: Deletable<I>, I: IndexType> {
     data: Vec<T>,
     deleted: Vec<I>,
+    changed: Vec<u64>,
+    all_changed: u64,
+    generation: u64,
 }
 
 impl<T: Deletable<I>, I: IndexType> DeletableVector<T, I> {
@@ -30,13 +59,49 @@ impl<T: Deletable<I>, I: IndexType> DeletableVector<T, I> {
         Self {
             data: Vec::new(),
             deleted: Vec::new(),
+            changed: Vec::new(),
+            all_changed: 0,
+            generation: 0,
+        }
+    }
+
+    /// Returns the generation of the last change of any element.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns whether the element at the given index changed after the vector had the
+    /// given generation.
+    pub fn changed_since(&self, index: I, generation: u64) -> bool {
+        self.all_changed > generation
+            || self
+                .changed
+                .get(index.index())
+                .is_some_and(|g| *g > generation)
+    }
+
+    /// Records a change of the element at the given index.
+    fn touch(&mut self, index: I) {
+        self.generation = next_generation();
+        let i = index.index();
+        if i >= self.changed.len() {
+            self.changed.resize(i + 1, 0);
         }
+        self.changed[i] = self.generation;
+    }
+
+    /// Records a change of all elements.
+    fn touch_all(&mut self) {
+        self.generation = next_generation();
+        self.all_changed = self.generation;
     }
 
     /// Deletes all elements.
     pub fn clear(&mut self) {
         self.data.clear();
         self.deleted.clear();
+        self.changed.clear();
+        self.touch_all();
     }
 
     /// Returns an iterator over the non-deleted elements.
@@ -46,6 +111,7 @@ impl<T: Deletable<I>, I: IndexType> DeletableVector<T, I> {
 
     /// Returns a mutable iterator over the non-deleted elements.
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.touch_all();
         self.data.iter_mut().filter(|f| !f.is_deleted())
     }
 
@@ -68,13 +134,13 @@ impl<T: Deletable<I>, I: IndexType> DeletableVector<T, I> {
 
     /// Returns the requested element mutably. Panics if it doesn't exist or is deleted.
     pub fn get_mut(&mut self, index: I) -> &mut T {
-        let v = &mut self.data[index.index()];
         assert!(
-            !v.is_deleted(),
+            !self.data[index.index()].is_deleted(),
             "Tried to mutably access deleted element at {}",
             index
         );
-        v
+        self.touch(index);
+        &mut self.data[index.index()]
     }
 
     /// Returns the number of non-deleted elements.
@@ -93,7 +159,7 @@ impl<T: Deletable<I>, I: IndexType> DeletableVector<T, I> {
             v.is_deleted(),
             "Tried to push an element that already has an id"
         );
-        if let Some(index) = self.deleted.pop() {
+        let index = if let Some(index) = self.deleted.pop() {
             v.set_id(index);
             self.data[index.index()] = v;
             index
@@ -102,7 +168,9 @@ impl<T: Deletable<I>, I: IndexType> DeletableVector<T, I> {
             v.set_id(index);
             self.data.push(v);
             index
-        }
+        };
+        self.touch(index);
+        index
     }
 
     /// Move the element at the given index. Assumes that the position is allocated and free, i.e., the contents are deleted.
@@ -118,25 +186,60 @@ impl<T: Deletable<I>, I: IndexType> DeletableVector<T, I> {
         );
         v.set_id(index);
         self.data[index.index()] = v;
+        self.touch(index);
     }
 
     /// Marks the element as deleted and remembers it for reallocation.
     pub fn delete_internal(&mut self, f: I) {
         self.data[f.index()].delete();
         self.deleted.push(f);
+        self.touch(f);
+    }
+
+    /// Returns the index each element will have after `compact`, or `IndexType::max()`
+    /// if it is deleted. Use this to update references between elements before compacting.
+    pub fn compact_map(&self) -> Vec<I> {
+        let mut next = 0;
+        self.data
+            .iter()
+            .map(|v| {
+                if v.is_deleted() {
+                    IndexType::max()
+                } else {
+                    next += 1;
+                    I::new(next - 1)
+                }
+            })
+            .collect()
+    }
+
+    /// Removes the deleted elements and moves the remaining ones to the front
+    /// while keeping their order. The elements get new ids as given by `compact_map`.
+    pub fn compact(&mut self) {
+        self.data.retain(|v| !v.is_deleted());
+        for (i, v) in self.data.iter_mut().enumerate() {
+            v.delete();
+            v.set_id(I::new(i));
+        }
+        self.deleted.clear();
+        self.changed.clear();
+        self.touch_all();
     }
 
     /// Returns the next free index or allocates a new one.
     /// The element is not deleted anymore, but it is not valid until it is overwritten.
     /// TODO: How can we force the user to overwrite it afterwards? Not writing to it is a memory leak.
     pub fn allocate(&mut self) -> I {
-        if let Some(index) = self.deleted.pop() {
+        let index = if let Some(index) = self.deleted.pop() {
             index
         } else {
+            let index = I::new(self.data.len());
             let t = T::allocate();
             debug_assert!(t.is_deleted());
             self.data.push(t);
-            I::new(self.data.len() - 1)
-        }
+            index
+        };
+        self.touch(index);
+        index
     }
 }
@@ -1,5 +1,11 @@
 //! utility functions
 
+mod attributes;
+mod color;
 mod deletable;
+mod selection;
 
+pub use attributes::*;
+pub use color::*;
 pub use deletable::*;
+pub use selection::*;
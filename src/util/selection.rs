@@ -0,0 +1,223 @@
+use crate::math::IndexType;
+use std::marker::PhantomData;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A set of vertex, edge, or face ids stored as a bitset over the indices of the ids,
+/// e.g., to apply operations only to parts of a mesh.
+///
+/// The memory is proportional to the largest id in the set, so selections are cheap
+/// to copy and combine, but shouldn't be used for a handful of ids of a huge mesh.
+#[derive(Clone)]
+pub struct Selection<I: IndexType> {
+    words: Vec<u64>,
+    phantom: PhantomData<I>,
+}
+
+impl<I: IndexType> Default for Selection<I> {
+    fn default() -> Self {
+        Self {
+            words: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I: IndexType> Selection<I> {
+    /// Creates an empty selection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the id. Returns whether it wasn't selected before.
+    pub fn insert(&mut self, id: I) -> bool {
+        let (w, b) = (id.index() / BITS, id.index() % BITS);
+        if w >= self.words.len() {
+            self.words.resize(w + 1, 0);
+        }
+        let before = self.words[w];
+        self.words[w] |= 1 << b;
+        before != self.words[w]
+    }
+
+    /// Removes the id. Returns whether it was selected.
+    pub fn remove(&mut self, id: I) -> bool {
+        let (w, b) = (id.index() / BITS, id.index() % BITS);
+        let Some(word) = self.words.get_mut(w) else {
+            return false;
+        };
+        let before = *word;
+        *word &= !(1 << b);
+        before != *word
+    }
+
+    /// Returns whether the id is selected.
+    pub fn contains(&self, id: I) -> bool {
+        let (w, b) = (id.index() / BITS, id.index() % BITS);
+        self.words.get(w).is_some_and(|word| word & (1 << b) != 0)
+    }
+
+    /// Returns the number of selected ids.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns whether no id is selected.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// Deselects all ids.
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// Iterates the selected ids in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = I> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let b = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(I::new(w * BITS + b))
+            })
+        })
+    }
+
+    /// Returns the selected ids in ascending order.
+    pub fn to_vec(&self) -> Vec<I> {
+        self.iter().collect()
+    }
+
+    fn zip_with(&self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let n = self.words.len().max(other.words.len());
+        let word = |s: &Self, i: usize| s.words.get(i).copied().unwrap_or(0);
+        Self {
+            words: (0..n).map(|i| op(word(self, i), word(other, i))).collect(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the ids selected in `self` or `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    /// Returns the ids selected in both `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    /// Returns the ids selected in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    /// Returns the ids selected in exactly one of `self` and `other`.
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+
+    /// Returns whether all ids selected in `self` are selected in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+}
+
+impl<I: IndexType> PartialEq for Selection<I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.symmetric_difference(other).is_empty()
+    }
+}
+
+impl<I: IndexType> Eq for Selection<I> {}
+
+impl<I: IndexType> std::fmt::Debug for Selection<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<I: IndexType> FromIterator<I> for Selection<I> {
+    fn from_iter<It: IntoIterator<Item = I>>(iter: It) -> Self {
+        let mut selection = Self::new();
+        selection.extend(iter);
+        selection
+    }
+}
+
+impl<I: IndexType> Extend<I> for Selection<I> {
+    fn extend<It: IntoIterator<Item = I>>(&mut self, iter: It) {
+        for id in iter {
+            self.insert(id);
+        }
+    }
+}
+
+impl<I: IndexType> std::ops::BitOr for &Selection<I> {
+    type Output = Selection<I>;
+
+    fn bitor(self, other: Self) -> Selection<I> {
+        self.union(other)
+    }
+}
+
+impl<I: IndexType> std::ops::BitAnd for &Selection<I> {
+    type Output = Selection<I>;
+
+    fn bitand(self, other: Self) -> Selection<I> {
+        self.intersection(other)
+    }
+}
+
+impl<I: IndexType> std::ops::Sub for &Selection<I> {
+    type Output = Selection<I>;
+
+    fn sub(self, other: Self) -> Selection<I> {
+        self.difference(other)
+    }
+}
+
+impl<I: IndexType> std::ops::BitXor for &Selection<I> {
+    type Output = Selection<I>;
+
+    fn bitxor(self, other: Self) -> Selection<I> {
+        self.symmetric_difference(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection() {
+        let mut a: Selection<usize> = [1, 5, 64, 200].into_iter().collect();
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(64) && !a.contains(63) && !a.contains(1000));
+        assert!(!a.insert(5));
+        assert!(a.insert(6));
+        assert!(a.remove(6));
+        assert!(!a.remove(6) && !a.remove(1000));
+        assert_eq!(a.to_vec(), vec![1, 5, 64, 200]);
+
+        let b: Selection<usize> = [5, 64, 65].into_iter().collect();
+        assert_eq!((&a | &b).to_vec(), vec![1, 5, 64, 65, 200]);
+        assert_eq!((&a & &b).to_vec(), vec![5, 64]);
+        assert_eq!((&a - &b).to_vec(), vec![1, 200]);
+        assert_eq!((&a ^ &b).to_vec(), vec![1, 65, 200]);
+        assert!((&a & &b).is_subset(&b));
+        assert!(!a.is_subset(&b));
+
+        // equality doesn't depend on the capacity
+        a.remove(200);
+        assert_eq!(a, [1, 5, 64].into_iter().collect());
+        assert_eq!(format!("{:?}", a), "{1, 5, 64}");
+        a.clear();
+        assert!(a.is_empty());
+        assert_eq!(a, Selection::new());
+    }
+}